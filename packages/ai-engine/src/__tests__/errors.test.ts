@@ -0,0 +1,25 @@
+/**
+ * Errors — Unit Tests
+ *
+ * Tests the structured fields reported next to engine error messages.
+ */
+import { describe, it, expect } from 'bun:test'
+import { HttpStatusError, errorDetails } from '../core/errors.js'
+
+describe('errorDetails', () => {
+  it('reports the HTTP status of failed responses', () => {
+    expect(errorDetails(new HttpStatusError(401, 'Failed to fetch models: 401 Unauthorized'))).toEqual({ status: 401 })
+    const sdkError = Object.assign(new Error('Forbidden'), { statusCode: 403 })
+    expect(errorDetails(sdkError)).toEqual({ status: 403 })
+  })
+
+  it('finds network error codes on the cause chain', () => {
+    const cause = Object.assign(new Error('connect ECONNREFUSED 127.0.0.1:1'), { code: 'ECONNREFUSED' })
+    expect(errorDetails(new TypeError('fetch failed', { cause }))).toEqual({ code: 'ECONNREFUSED' })
+  })
+
+  it('reports nothing for plain errors', () => {
+    expect(errorDetails(new Error('401 invalid api key'))).toEqual({})
+    expect(errorDetails('boom')).toEqual({})
+  })
+})
@@ -0,0 +1,81 @@
+/**
+ * Proxy — Unit Tests
+ *
+ * Tests bypass matching, per-request proxy routing, and how the resolved proxy is applied.
+ */
+import { describe, it, expect } from 'bun:test'
+import { bypassesProxy, proxiedFetch, withProxy } from '../proxy.js'
+import type { ProviderConfig } from '../types.js'
+
+function recordingFetch() {
+  const calls: Array<{ url: string; init?: RequestInit }> = []
+  const fake = (async (input: RequestInfo | URL, init?: RequestInit) => {
+    calls.push({ url: String(input), init })
+    return new Response('{}')
+  }) as typeof fetch
+  return { calls, fake }
+}
+
+describe('bypassesProxy', () => {
+  it('matches exact hosts and *.suffix patterns', () => {
+    expect(bypassesProxy('api.internal', ['api.internal'])).toBe(true)
+    expect(bypassesProxy('a.corp.example', ['*.corp.example'])).toBe(true)
+    expect(bypassesProxy('corp.example', ['*.corp.example'])).toBe(true)
+    expect(bypassesProxy('notcorp.example', ['*.corp.example'])).toBe(false)
+    expect(bypassesProxy('api.openai.com', ['api.internal'])).toBe(false)
+  })
+
+  it('always connects to loopback directly', () => {
+    expect(bypassesProxy('localhost')).toBe(true)
+    expect(bypassesProxy('127.0.0.1')).toBe(true)
+    expect(bypassesProxy('[::1]')).toBe(true)
+  })
+})
+
+describe('proxiedFetch', () => {
+  it('returns the base fetch when no proxy is set', () => {
+    const { fake } = recordingFetch()
+    expect(proxiedFetch(undefined, fake)).toBe(fake)
+    expect(proxiedFetch({ url: '  ' }, fake)).toBe(fake)
+  })
+
+  it('adds the proxy option unless the host is bypassed', async () => {
+    const { calls, fake } = recordingFetch()
+    const wrapped = proxiedFetch({ url: 'http://proxy.local:8080', bypass: ['*.internal'] }, fake)
+
+    await wrapped('https://api.example.com/v1/models', { method: 'GET' })
+    await wrapped('https://llm.internal/v1/models')
+    await wrapped('http://localhost:11434/api/tags')
+
+    expect((calls[0].init as { proxy?: string }).proxy).toBe('http://proxy.local:8080')
+    expect(calls[0].init?.method).toBe('GET')
+    expect((calls[1].init as { proxy?: string } | undefined)?.proxy).toBeUndefined()
+    expect((calls[2].init as { proxy?: string } | undefined)?.proxy).toBeUndefined()
+  })
+
+  it('rejects SOCKS proxies with a clear error', async () => {
+    const { calls, fake } = recordingFetch()
+    const wrapped = proxiedFetch({ url: 'socks5://127.0.0.1:1080' }, fake)
+    await expect(wrapped('https://api.example.com')).rejects.toThrow('SOCKS proxies are not supported')
+    expect(calls).toHaveLength(0)
+  })
+})
+
+describe('withProxy', () => {
+  const provider: ProviderConfig = {
+    id: 'p',
+    name: 'P',
+    baseURL: 'https://api.example.com/v1',
+    apiKey: 'sk-test',
+    models: [],
+    providerType: 'openai-compatible',
+  }
+
+  it('applies the request-level proxy', () => {
+    expect(withProxy(provider, { url: 'http://proxy:1' }).proxy).toEqual({ url: 'http://proxy:1' })
+  })
+
+  it('keeps the provider unchanged without a proxy', () => {
+    expect(withProxy(provider, null)).toBe(provider)
+  })
+})
@@ -17,11 +17,15 @@ describe('Tool definitions', () => {
     expect(names).toContain('read')
     expect(names).toContain('write')
     expect(names).toContain('append')
+    expect(names).toContain('edit')
+    expect(names).toContain('delete')
     expect(names).toContain('list')
     expect(names).toContain('search')
+    expect(names).toContain('stat')
     expect(names).toContain('get_chapter_info')
     expect(names).toContain('save_summary')
     expect(names).toContain('rag_search')
+    expect(names).toContain('get_story_rollup')
   })
 
   it('every tool has name, description, and parameters', () => {
@@ -69,6 +73,11 @@ describe('Tool definitions', () => {
     const infoTool = tools.find((t) => t.name === 'get_chapter_info')!
     expect(infoTool.parameters.required).toEqual([])
   })
+
+  it('get_story_rollup has no required params', () => {
+    const rollupTool = tools.find((t) => t.name === 'get_story_rollup')!
+    expect(rollupTool.parameters.required).toEqual([])
+  })
 })
 
 // ──────────────────────────────────────────────
@@ -78,15 +87,19 @@ describe('Tool definitions', () => {
 describe('getToolsForSDK (no executor)', () => {
   it('returns all tool definitions as SDK format', () => {
     const sdkTools = getToolsForSDK()
-    expect(Object.keys(sdkTools)).toHaveLength(8)
+    expect(Object.keys(sdkTools)).toHaveLength(12)
     expect(sdkTools.read).toBeDefined()
     expect(sdkTools.write).toBeDefined()
     expect(sdkTools.append).toBeDefined()
+    expect(sdkTools.edit).toBeDefined()
+    expect(sdkTools.delete).toBeDefined()
     expect(sdkTools.list).toBeDefined()
     expect(sdkTools.search).toBeDefined()
+    expect(sdkTools.stat).toBeDefined()
     expect(sdkTools.get_chapter_info).toBeDefined()
     expect(sdkTools.save_summary).toBeDefined()
     expect(sdkTools.rag_search).toBeDefined()
+    expect(sdkTools.get_story_rollup).toBeDefined()
   })
 
   it('each tool has description and parameters', () => {
@@ -0,0 +1,80 @@
+import { HttpStatusError } from './core/errors'
+import type { ProviderConfig } from './types'
+
+export const DEFAULT_AZURE_API_VERSION = '2024-06-01'
+// The data-plane deployments listing was dropped from newer api-versions.
+const DEPLOYMENTS_LIST_API_VERSION = '2023-05-15'
+
+/** Resource endpoint without a trailing slash or `/openai` suffix. */
+export function azureEndpoint(baseURL: string): string {
+  return (baseURL ?? '').trim().replace(/\/+$/, '').replace(/\/openai$/, '')
+}
+
+/**
+ * Base URL handed to the OpenAI-compatible SDK. Requests under it are rewritten by
+ * {@link azureFetch} to target the deployment for the requested model.
+ */
+export function azureSdkBaseURL(baseURL: string): string {
+  return `${azureEndpoint(baseURL)}/openai`
+}
+
+function requestURL(input: RequestInfo | URL): URL {
+  if (typeof input === 'string') return new URL(input)
+  if (input instanceof URL) return new URL(input.href)
+  return new URL(input.url)
+}
+
+/**
+ * `fetch` for Azure OpenAI: `/openai/chat/completions` becomes
+ * `/openai/deployments/<deployment>/chat/completions?api-version=...`, where the deployment
+ * comes from the provider's deployment map (falling back to the model id itself).
+ */
+export function azureFetch(
+  provider: Pick<ProviderConfig, 'apiVersion' | 'deployments'>,
+  baseFetch: typeof fetch = fetch,
+): typeof fetch {
+  const apiVersion = provider.apiVersion?.trim() || DEFAULT_AZURE_API_VERSION
+  const wrapped = async (input: RequestInfo | URL, init?: RequestInit) => {
+    const url = requestURL(input)
+    let model = ''
+    if (typeof init?.body === 'string') {
+      try {
+        model = String(JSON.parse(init.body)?.model ?? '')
+      } catch {
+        model = ''
+      }
+    }
+    const deployment = provider.deployments?.[model] || model
+    if (deployment && url.pathname.includes('/openai/') && !url.pathname.includes('/openai/deployments/')) {
+      url.pathname = url.pathname.replace('/openai/', `/openai/deployments/${encodeURIComponent(deployment)}/`)
+    }
+    url.searchParams.set('api-version', apiVersion)
+    return baseFetch(url, init)
+  }
+  return wrapped as typeof fetch
+}
+
+/** Deployment names of an Azure OpenAI resource; these are what requests address. */
+export async function fetchAzureDeployments(
+  baseURL: string,
+  apiKey: string,
+  apiVersion?: string,
+  signal?: AbortSignal,
+  fetchImpl: typeof fetch = fetch,
+): Promise<string[]> {
+  const listOnce = async (version: string) => {
+    const url = `${azureEndpoint(baseURL)}/openai/deployments?api-version=${encodeURIComponent(version)}`
+    return fetchImpl(url, { method: 'GET', headers: { 'api-key': apiKey }, signal })
+  }
+
+  let response = await listOnce(apiVersion?.trim() || DEPLOYMENTS_LIST_API_VERSION)
+  if (response.status === 404 && apiVersion?.trim() && apiVersion.trim() !== DEPLOYMENTS_LIST_API_VERSION) {
+    response = await listOnce(DEPLOYMENTS_LIST_API_VERSION)
+  }
+  if (!response.ok) {
+    throw new HttpStatusError(response.status, `Failed to fetch deployments: ${response.status} ${response.statusText}`)
+  }
+
+  const data = (await response.json()) as { data?: Array<{ id?: string }> }
+  return (data.data ?? []).map((d) => d.id ?? '').filter((id) => id.trim())
+}
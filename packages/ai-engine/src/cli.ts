@@ -8,6 +8,7 @@ import { FetchModelsPipeline } from './pipelines/fetch-models'
 import { ExtractPipeline } from './pipelines/extract'
 import { TransformPipeline } from './pipelines/transform'
 import type { PipelineRuntime } from './core/pipeline'
+import { errorDetails } from './core/errors'
 
 // --- JSONL I/O ---
 
@@ -91,7 +92,11 @@ async function main() {
     const result = await pipeline.run(input, runtime)
     writeJson(result)
   } catch (error) {
-    writeJson({ type: 'error', message: error instanceof Error ? error.message : String(error) })
+    writeJson({
+      type: 'error',
+      message: error instanceof Error ? error.message : String(error),
+      ...errorDetails(error),
+    })
     process.exit(1)
   }
 }
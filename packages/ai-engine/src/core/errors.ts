@@ -0,0 +1,30 @@
+/** A failed HTTP response; `statusCode` matches the field the AI SDK's `APICallError` uses. */
+export class HttpStatusError extends Error {
+  readonly statusCode: number
+
+  constructor(statusCode: number, message: string) {
+    super(message)
+    this.name = 'HttpStatusError'
+    this.statusCode = statusCode
+  }
+}
+
+export interface ErrorDetails {
+  /** HTTP status of the provider response, when there was one. */
+  status?: number
+  /** System error code (e.g. `ECONNREFUSED`) of a request that never got a response. */
+  code?: string
+}
+
+/** Structured fields of `error` so callers can classify it without parsing the message. */
+export function errorDetails(error: unknown): ErrorDetails {
+  const details: ErrorDetails = {}
+  for (let current = error, depth = 0; current && typeof current === 'object' && depth < 4; depth++) {
+    const record = current as { statusCode?: unknown; status?: unknown; code?: unknown; cause?: unknown }
+    const status = record.statusCode ?? record.status
+    if (details.status === undefined && typeof status === 'number') details.status = status
+    if (details.code === undefined && typeof record.code === 'string') details.code = record.code
+    current = record.cause
+  }
+  return details
+}
@@ -32,3 +32,38 @@ describe('fetchModels', () => {
   })
 })
 
+
+describe('fetchModels (azure-openai)', () => {
+  test('lists deployments with the api-key header', async () => {
+    let apiKey = ''
+    let apiVersion = ''
+
+    const server = Bun.serve({
+      port: 0,
+      fetch(req) {
+        apiKey = req.headers.get('api-key') ?? ''
+        const url = new URL(req.url)
+        apiVersion = url.searchParams.get('api-version') ?? ''
+        if (url.pathname !== '/openai/deployments') {
+          return new Response('not found', { status: 404 })
+        }
+        return Response.json({ data: [{ id: 'gpt4o-prod' }, { id: 'embed' }] })
+      },
+    })
+
+    try {
+      const models = await fetchModels(
+        `http://127.0.0.1:${server.port}/openai/`,
+        'azure-key',
+        'azure-openai',
+        undefined,
+        '2023-05-15',
+      )
+      expect(apiKey).toBe('azure-key')
+      expect(apiVersion).toBe('2023-05-15')
+      expect(models).toEqual(['embed', 'gpt4o-prod'])
+    } finally {
+      server.stop()
+    }
+  })
+})
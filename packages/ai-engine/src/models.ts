@@ -1,3 +1,8 @@
+import { fetchAzureDeployments } from './azure'
+import { HttpStatusError } from './core/errors'
+import { proxiedFetch } from './proxy'
+import type { ProxySettings } from './types'
+
 export interface ModelInfo {
   id: string
   name?: string
@@ -39,10 +44,16 @@ function uniqueSorted(items: string[]): string[] {
   return Array.from(new Set(items.filter((m) => m && m.trim()).map((m) => m.trim()))).sort()
 }
 
-async function fetchModelsOnce(baseURL: string, apiKey: string, providerType: string, signal?: AbortSignal): Promise<string[]> {
+async function fetchModelsOnce(
+  baseURL: string,
+  apiKey: string,
+  providerType: string,
+  signal: AbortSignal | undefined,
+  fetchImpl: typeof fetch,
+): Promise<string[]> {
   const url = joinURL(baseURL, '/models')
 
-  const response = await fetch(url, {
+  const response = await fetchImpl(url, {
     method: 'GET',
     headers: {
       ...authHeaders(providerType, apiKey),
@@ -52,29 +63,63 @@ async function fetchModelsOnce(baseURL: string, apiKey: string, providerType: st
   })
 
   if (!response.ok) {
-    throw new Error(`Failed to fetch models: ${response.status} ${response.statusText}`)
+    throw new HttpStatusError(response.status, `Failed to fetch models: ${response.status} ${response.statusText}`)
   }
 
   const data: ModelsResponse = await response.json()
   return data.data.map((m) => m.id)
 }
 
+/** Locally pulled Ollama models, from the native `/api/tags` endpoint. */
+async function fetchOllamaTags(
+  baseURL: string,
+  apiKey: string,
+  signal: AbortSignal | undefined,
+  fetchImpl: typeof fetch,
+): Promise<string[]> {
+  const root = baseURL.replace(/\/+$/, '').replace(/\/v1$/, '')
+  const response = await fetchImpl(joinURL(root, '/api/tags'), {
+    method: 'GET',
+    headers: apiKey ? { Authorization: `Bearer ${apiKey}` } : {},
+    signal,
+  })
+
+  if (!response.ok) {
+    throw new HttpStatusError(
+      response.status,
+      `Failed to fetch Ollama models: ${response.status} ${response.statusText}`,
+    )
+  }
+
+  const data = (await response.json()) as { models?: Array<{ name?: string; model?: string }> }
+  return (data.models ?? []).map((m) => m.name ?? m.model ?? '')
+}
+
 export async function fetchModels(
   baseURL: string,
   apiKey: string,
   providerType: string = 'openai-compatible',
   signal?: AbortSignal,
+  apiVersion?: string,
+  proxy?: ProxySettings | null,
 ): Promise<string[]> {
   const normalizedBaseURL = baseURL.trim()
+  const fetchImpl = proxiedFetch(proxy)
+  if (providerType === 'ollama') {
+    return uniqueSorted(await fetchOllamaTags(normalizedBaseURL, apiKey, signal, fetchImpl))
+  }
+  if (providerType === 'azure-openai') {
+    return uniqueSorted(await fetchAzureDeployments(normalizedBaseURL, apiKey, apiVersion, signal, fetchImpl))
+  }
   try {
-    return uniqueSorted(await fetchModelsOnce(normalizedBaseURL, apiKey, providerType, signal))
+    return uniqueSorted(await fetchModelsOnce(normalizedBaseURL, apiKey, providerType, signal, fetchImpl))
   } catch (error) {
     // Fallback: users often input a host without `/v1`, but OpenAI-compatible APIs expect `/v1/models`.
     const message = error instanceof Error ? error.message : String(error)
     const trimmed = normalizedBaseURL.replace(/\/+$/, '')
     const hasV1 = trimmed.endsWith('/v1')
     if (message.includes('404') && !hasV1) {
-      return uniqueSorted(await fetchModelsOnce(ensureV1(normalizedBaseURL), apiKey, providerType, signal))
+      return uniqueSorted(await fetchModelsOnce(ensureV1(normalizedBaseURL), apiKey, providerType, signal, fetchImpl))
     }
     throw error
   }
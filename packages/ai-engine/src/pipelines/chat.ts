@@ -1,12 +1,13 @@
 import { createEngine } from '../index'
 import type { Pipeline, PipelineRuntime } from '../core/pipeline'
-import type { ProviderConfig, ModelParameters, Message, ToolCallRequest, ToolCallResult } from '../types'
+import type { ProviderConfig, ProxySettings, ModelParameters, Message, ToolCallRequest, ToolCallResult } from '../types'
+import { withProxy } from '../proxy'
 
 export class ChatPipeline implements Pipeline {
   readonly name = 'chat'
 
   async run(input: Record<string, unknown>, runtime: PipelineRuntime): Promise<Record<string, unknown>> {
-    const provider = input.provider as ProviderConfig
+    const provider = withProxy(input.provider as ProviderConfig, input.proxy as ProxySettings | null | undefined)
     const parameters = input.parameters as ModelParameters
     const systemPrompt = input.systemPrompt as string
     const messages = input.messages as Message[]
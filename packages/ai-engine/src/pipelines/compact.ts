@@ -1,12 +1,13 @@
 import { generateCompactSummary } from '../compact'
 import type { Pipeline, PipelineRuntime } from '../core/pipeline'
-import type { ProviderConfig, ModelParameters, Message } from '../types'
+import type { ProviderConfig, ProxySettings, ModelParameters, Message } from '../types'
+import { withProxy } from '../proxy'
 
 export class CompactPipeline implements Pipeline {
   readonly name = 'compact'
 
   async run(input: Record<string, unknown>, _runtime: PipelineRuntime): Promise<Record<string, unknown>> {
-    const provider = input.provider as ProviderConfig
+    const provider = withProxy(input.provider as ProviderConfig, input.proxy as ProxySettings | null | undefined)
     const parameters = input.parameters as ModelParameters
     const messages = input.messages as Message[]
 
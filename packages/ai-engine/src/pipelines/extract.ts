@@ -1,7 +1,8 @@
 import type { Pipeline, PipelineRuntime } from '../core/pipeline'
-import type { ProviderConfig, ModelParameters } from '../types'
+import type { ProviderConfig, ProxySettings, ModelParameters } from '../types'
 import { ProviderManager } from '../provider'
 import { generateText } from 'ai'
+import { withProxy } from '../proxy'
 
 const EXTRACT_SYSTEM_PROMPT = `你是一个小说文本分析专家。分析用户提供的小说文本，提取以下结构化信息。
 
@@ -52,7 +53,7 @@ export class ExtractPipeline implements Pipeline {
   readonly name = 'extract'
 
   async run(input: Record<string, unknown>, _runtime: PipelineRuntime): Promise<Record<string, unknown>> {
-    const provider = input.provider as ProviderConfig
+    const provider = withProxy(input.provider as ProviderConfig, input.proxy as ProxySettings | null | undefined)
     const parameters = input.parameters as ModelParameters
     const text = input.text as string
 
@@ -1,5 +1,6 @@
 import { fetchModels } from '../models'
 import type { Pipeline, PipelineRuntime } from '../core/pipeline'
+import type { ProxySettings } from '../types'
 
 export class FetchModelsPipeline implements Pipeline {
   readonly name = 'fetch_models'
@@ -8,8 +9,10 @@ export class FetchModelsPipeline implements Pipeline {
     const baseURL = input.baseURL as string
     const apiKey = input.apiKey as string
     const providerType = (input.providerType as string) ?? 'openai-compatible'
+    const apiVersion = (input.apiVersion as string | undefined) ?? undefined
+    const proxy = input.proxy as ProxySettings | null | undefined
 
-    const models = await fetchModels(baseURL, apiKey, providerType)
+    const models = await fetchModels(baseURL, apiKey, providerType, undefined, apiVersion, proxy)
     return { type: 'models', models }
   }
 }
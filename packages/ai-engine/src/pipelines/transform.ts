@@ -1,7 +1,8 @@
 import type { Pipeline, PipelineRuntime } from '../core/pipeline'
-import type { ProviderConfig, ModelParameters } from '../types'
+import type { ProviderConfig, ProxySettings, ModelParameters } from '../types'
 import { ProviderManager } from '../provider'
 import { generateText } from 'ai'
+import { withProxy } from '../proxy'
 
 type TransformAction = 'polish' | 'expand' | 'condense' | 'restyle'
 
@@ -38,7 +39,7 @@ export class TransformPipeline implements Pipeline {
   readonly name = 'transform'
 
   async run(input: Record<string, unknown>, _runtime: PipelineRuntime): Promise<Record<string, unknown>> {
-    const provider = input.provider as ProviderConfig
+    const provider = withProxy(input.provider as ProviderConfig, input.proxy as ProxySettings | null | undefined)
     const parameters = input.parameters as ModelParameters
     const text = input.text as string
     const action = (input.action as TransformAction) ?? 'polish'
@@ -1,5 +1,7 @@
 import { createOpenAICompatible } from '@ai-sdk/openai-compatible'
 import type { ProviderConfig } from './types'
+import { azureFetch, azureSdkBaseURL } from './azure'
+import { proxiedFetch } from './proxy'
 
 function buildAuthHeaders(providerType: ProviderConfig['providerType'], apiKey: string) {
   const key = apiKey ?? ''
@@ -10,6 +12,11 @@ function buildAuthHeaders(providerType: ProviderConfig['providerType'], apiKey:
       return { 'x-goog-api-key': key }
     case 'anthropic':
       return { 'x-api-key': key }
+    case 'azure-openai':
+      return { 'api-key': key }
+    case 'ollama':
+      // Ollama itself ignores auth; a key only matters behind an authenticating proxy.
+      return { Authorization: `Bearer ${key}` }
     default:
       return {}
   }
@@ -17,7 +24,8 @@ function buildAuthHeaders(providerType: ProviderConfig['providerType'], apiKey:
 
 function normalizeBaseURL(provider: ProviderConfig): string {
   const trimmed = (provider.baseURL ?? '').trim().replace(/\/+$/, '')
-  if (provider.providerType !== 'openai-compatible') return trimmed
+  // Ollama serves its OpenAI-compatible API under /v1 as well.
+  if (provider.providerType !== 'openai-compatible' && provider.providerType !== 'ollama') return trimmed
   if (!trimmed || trimmed.endsWith('/v1')) return trimmed
   return `${trimmed}/v1`
 }
@@ -51,6 +59,16 @@ export class ProviderManager {
     // but some providers expect different auth headers.
     const authHeaders = buildAuthHeaders(provider.providerType, provider.apiKey)
     const mergedHeaders = { ...authHeaders, ...(provider.headers ?? {}) }
+
+    if (provider.providerType === 'azure-openai') {
+      return createOpenAICompatible({
+        baseURL: azureSdkBaseURL(provider.baseURL),
+        name: provider.name,
+        headers: mergedHeaders,
+        fetch: azureFetch(provider, proxiedFetch(provider.proxy)),
+      })
+    }
+
     const baseURL = normalizeBaseURL(provider)
 
     return createOpenAICompatible({
@@ -60,6 +78,7 @@ export class ProviderManager {
       // (some gateways treat Bearer tokens differently and may require browser verification).
       apiKey: provider.providerType === 'openai-compatible' ? provider.apiKey : undefined,
       headers: mergedHeaders,
+      fetch: proxiedFetch(provider.proxy),
     })
   }
 }
@@ -0,0 +1,53 @@
+import type { ProviderConfig, ProxySettings } from './types'
+
+function requestHost(input: RequestInfo | URL): string {
+  if (typeof input === 'string') return new URL(input).hostname
+  if (input instanceof URL) return input.hostname
+  return new URL(input.url).hostname
+}
+
+function isLoopback(host: string): boolean {
+  const normalized = host.replace(/^\[|\]$/g, '').toLowerCase()
+  return normalized === 'localhost' || normalized === '::1' || normalized.startsWith('127.')
+}
+
+/** Whether `host` connects directly: loopback, an exact bypass entry, or a `*.suffix` match. */
+export function bypassesProxy(host: string, bypass: string[] = []): boolean {
+  const target = host.toLowerCase()
+  if (isLoopback(target)) return true
+  return bypass.some((entry) => {
+    const pattern = entry.trim().toLowerCase()
+    if (!pattern) return false
+    if (pattern.startsWith('*.')) {
+      const suffix = pattern.slice(1)
+      return target.endsWith(suffix) || target === pattern.slice(2)
+    }
+    return target === pattern
+  })
+}
+
+/**
+ * `fetch` that routes requests through the configured proxy (Bun's `proxy` request option).
+ * Without a proxy URL the base fetch is returned unchanged.
+ */
+export function proxiedFetch(proxy: ProxySettings | null | undefined, baseFetch: typeof fetch = fetch): typeof fetch {
+  const url = proxy?.url?.trim()
+  if (!url) return baseFetch
+  if (/^socks5h?:/i.test(url)) {
+    const unsupported = async () => {
+      throw new Error(`SOCKS proxies are not supported by the AI engine: ${url}`)
+    }
+    return unsupported as unknown as typeof fetch
+  }
+  const wrapped = async (input: RequestInfo | URL, init?: RequestInit) => {
+    if (bypassesProxy(requestHost(input), proxy?.bypass)) return baseFetch(input, init)
+    return baseFetch(input, { ...init, proxy: url } as RequestInit)
+  }
+  return wrapped as typeof fetch
+}
+
+/** The provider with the request's resolved proxy applied; the request-level value wins. */
+export function withProxy(provider: ProviderConfig, proxy: ProxySettings | null | undefined): ProviderConfig {
+  const resolved = proxy ?? provider.proxy
+  return resolved ? { ...provider, proxy: resolved } : provider
+}
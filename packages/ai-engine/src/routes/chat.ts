@@ -17,10 +17,12 @@ import { initModel, structLog, sanitizeError } from '../core/stream-helpers.js'
 import { streamText } from 'ai'
 import { getToolsForSDK } from '../tools.js'
 import type { ConcurrencyLimiter } from '../middleware/concurrency.js'
-import type { ProviderConfig, ModelParameters, Message, ToolCallRequest, ToolCallResult } from '../types.js'
+import type { ProviderConfig, ProxySettings, ModelParameters, Message, ToolCallRequest, ToolCallResult } from '../types.js'
+import { withProxy } from '../proxy.js'
 
 interface ChatRequest {
   provider: ProviderConfig
+  proxy?: ProxySettings | null
   parameters: ModelParameters
   systemPrompt: string
   messages: Message[]
@@ -40,6 +42,7 @@ export function chatRoute(limiter?: ConcurrencyLimiter) {
     if (!body.provider || !body.parameters || !body.systemPrompt || !body.messages || !Array.isArray(body.messages)) {
       return c.json({ error: 'Missing required fields: provider, parameters, systemPrompt, messages (must be array)' }, 400)
     }
+    body.provider = withProxy(body.provider, body.proxy)
 
     // Validate toolCallbackUrl BEFORE acquiring concurrency slot.
     // Early returns after tryAcquire() would leak the slot (no finally{} to release).
@@ -6,10 +6,12 @@ import { Hono } from 'hono'
 import { generateCompactSummary } from '../compact.js'
 import { structLog, sanitizeError } from '../core/stream-helpers.js'
 import { withRetry } from '../middleware/retry.js'
-import type { ProviderConfig, ModelParameters, Message } from '../types.js'
+import type { ProviderConfig, ProxySettings, ModelParameters, Message } from '../types.js'
+import { withProxy } from '../proxy.js'
 
 interface CompactRequest {
   provider: ProviderConfig
+  proxy?: ProxySettings | null
   parameters: ModelParameters
   messages: Message[]
 }
@@ -24,6 +26,7 @@ export function compactRoute() {
     if (!body.provider || !body.parameters || !body.messages?.length) {
       return c.json({ error: 'Missing required fields: provider, parameters, messages' }, 400)
     }
+    body.provider = withProxy(body.provider, body.proxy)
 
     structLog('info', requestId, 'compact.start', {
       provider: body.provider.id,
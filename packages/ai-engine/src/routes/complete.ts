@@ -9,10 +9,12 @@
 import { Hono } from 'hono'
 import { streamTextRoute } from '../core/stream-helpers.js'
 import type { ConcurrencyLimiter } from '../middleware/concurrency.js'
-import type { ProviderConfig, ModelParameters, Message } from '../types.js'
+import type { ProviderConfig, ProxySettings, ModelParameters, Message } from '../types.js'
+import { withProxy } from '../proxy.js'
 
 interface CompleteRequest {
   provider: ProviderConfig
+  proxy?: ProxySettings | null
   parameters: ModelParameters
   systemPrompt: string
   messages: Message[]
@@ -27,6 +29,7 @@ export function completeRoute(limiter?: ConcurrencyLimiter) {
     if (!body.provider || !body.parameters || !body.systemPrompt || !body.messages) {
       return c.json({ error: 'Missing required fields: provider, parameters, systemPrompt, messages' }, 400)
     }
+    body.provider = withProxy(body.provider, body.proxy)
 
     return streamTextRoute({
       c,
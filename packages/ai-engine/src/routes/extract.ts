@@ -8,7 +8,8 @@ import { Hono } from 'hono'
 import { generateText } from 'ai'
 import { initModel, structLog, sanitizeError } from '../core/stream-helpers.js'
 import { withRetry } from '../middleware/retry.js'
-import type { ProviderConfig, ModelParameters } from '../types.js'
+import type { ProviderConfig, ProxySettings, ModelParameters } from '../types.js'
+import { withProxy } from '../proxy.js'
 
 const EXTRACT_SYSTEM_PROMPT = `你是一个小说文本分析专家。分析用户提供的小说文本，提取以下结构化信息。
 
@@ -57,6 +58,7 @@ JSON Schema:
 
 interface ExtractRequest {
   provider: ProviderConfig
+  proxy?: ProxySettings | null
   parameters: ModelParameters
   text: string
 }
@@ -71,6 +73,7 @@ export function extractRoute() {
     if (!body.provider || !body.parameters || !body.text) {
       return c.json({ error: 'Missing required fields: provider, parameters, text' }, 400)
     }
+    body.provider = withProxy(body.provider, body.proxy)
 
     structLog('info', requestId, 'extract.start', { text_length: body.text.length })
 
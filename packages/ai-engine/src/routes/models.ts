@@ -5,6 +5,7 @@
  */
 import { Hono } from 'hono'
 import { fetchModels } from '../models.js'
+import type { ProxySettings } from '../types.js'
 import { structLog, sanitizeError } from '../core/stream-helpers.js'
 
 const MODELS_FETCH_TIMEOUT_MS = 15_000 // 15 seconds
@@ -13,6 +14,8 @@ interface ModelsQuery {
   baseURL: string
   apiKey: string
   providerType?: string
+  apiVersion?: string
+  proxy?: ProxySettings | null
 }
 
 export function modelsRoute() {
@@ -22,7 +25,9 @@ export function modelsRoute() {
     const requestId = c.get('requestId') as string
     const body = await c.req.json<ModelsQuery>()
 
-    if (!body.baseURL || !body.apiKey) {
+    // Local Ollama servers run without a key.
+    const keyRequired = body.providerType !== 'ollama'
+    if (!body.baseURL || (keyRequired && !body.apiKey)) {
       return c.json({ error: 'Missing required fields: baseURL, apiKey' }, 400)
     }
 
@@ -38,7 +43,7 @@ export function modelsRoute() {
 
       let models: string[]
       try {
-        models = await fetchModels(body.baseURL, body.apiKey, body.providerType, combinedSignal)
+        models = await fetchModels(body.baseURL, body.apiKey, body.providerType, combinedSignal, body.apiVersion, body.proxy)
       } catch (err) {
         if (timeoutController.signal.aborted) {
           throw new Error(`Models fetch timed out after ${MODELS_FETCH_TIMEOUT_MS / 1000}s`)
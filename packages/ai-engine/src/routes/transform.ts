@@ -9,7 +9,8 @@
 import { Hono } from 'hono'
 import { streamTextRoute } from '../core/stream-helpers.js'
 import type { ConcurrencyLimiter } from '../middleware/concurrency.js'
-import type { ProviderConfig, ModelParameters } from '../types.js'
+import type { ProviderConfig, ProxySettings, ModelParameters } from '../types.js'
+import { withProxy } from '../proxy.js'
 
 type TransformAction = 'polish' | 'expand' | 'condense' | 'restyle'
 
@@ -41,6 +42,7 @@ const SYSTEM_PROMPTS: Record<TransformAction, string> = {
 
 interface TransformRequest {
   provider: ProviderConfig
+  proxy?: ProxySettings | null
   parameters: ModelParameters
   text: string
   action?: TransformAction
@@ -56,6 +58,7 @@ export function transformRoute(limiter?: ConcurrencyLimiter) {
     if (!body.provider || !body.parameters || !body.text) {
       return c.json({ error: 'Missing required fields: provider, parameters, text' }, 400)
     }
+    body.provider = withProxy(body.provider, body.proxy)
 
     const action = body.action ?? 'polish'
     let systemPrompt = SYSTEM_PROMPTS[action] ?? SYSTEM_PROMPTS.polish
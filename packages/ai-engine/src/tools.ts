@@ -11,9 +11,21 @@ export const tools: ToolDefinition[] = [
     parameters: {
       type: 'object',
       properties: {
-        path: { type: 'string', description: '相对于项目目录的文件路径' },
+        path: {
+          type: 'string',
+          description: '相对于项目目录的文件路径；用户配置的只读参考目录使用 ext://<目录id>/相对路径',
+        },
         offset: { type: 'number', description: '起始行号（0-based）' },
         limit: { type: 'number', description: '读取行数（默认2000）' },
+        byteOffset: {
+          type: 'number',
+          description: '按字节读取的起始位置；提供后忽略 offset/limit，适合单行极长的大文件（结果中 byte_offset 为实际起点）',
+        },
+        byteLimit: { type: 'number', description: '按字节读取的长度（默认且最多 51200）' },
+        encoding: {
+          type: 'string',
+          description: '文件编码，如 utf-8、gbk；填 auto 自动识别（BOM / UTF-8 / GBK），读取乱码或报二进制错误时使用',
+        },
       },
       required: ['path'],
     },
@@ -42,6 +54,31 @@ export const tools: ToolDefinition[] = [
       required: ['path', 'content'],
     },
   },
+  {
+    name: 'edit',
+    description: '替换文件中指定的行（行号从 1 开始，包含首尾两行），适合局部修改；content 为空时删除这些行。',
+    parameters: {
+      type: 'object',
+      properties: {
+        path: { type: 'string', description: '相对于项目目录的文件路径' },
+        startLine: { type: 'number', description: '起始行号（从 1 开始）' },
+        endLine: { type: 'number', description: '结束行号（包含该行）' },
+        content: { type: 'string', description: '替换后的内容' },
+      },
+      required: ['path', 'startLine', 'endLine', 'content'],
+    },
+  },
+  {
+    name: 'delete',
+    description: '删除项目内的文件（会先备份，不能删除目录，也不能删除目录中已登记的章节文件）。',
+    parameters: {
+      type: 'object',
+      properties: {
+        path: { type: 'string', description: '相对于项目目录的文件路径' },
+      },
+      required: ['path'],
+    },
+  },
   {
     name: 'list',
     description: '列出目录下的文件。',
@@ -49,18 +86,37 @@ export const tools: ToolDefinition[] = [
       type: 'object',
       properties: {
         path: { type: 'string', description: '相对于项目目录的目录路径' },
+        recursive: { type: 'boolean', description: '为 true 时同时列出子目录内容' },
+        maxDepth: { type: 'number', description: '递归时最多列出的层数（默认 8）' },
+        offset: { type: 'number', description: '跳过的条目数，用于翻页（结果中 has_more 表示还有更多）' },
+        limit: { type: 'number', description: '每页条目数（默认 100）' },
       },
       required: [],
     },
   },
+  {
+    name: 'stat',
+    description: '查看文件信息（是否存在、大小、修改时间，小文本文件附带行数与字数），适合读取前先确认。',
+    parameters: {
+      type: 'object',
+      properties: {
+        path: { type: 'string', description: '相对于项目目录的路径；也可用 ext://<目录id>/相对路径' },
+      },
+      required: ['path'],
+    },
+  },
   {
     name: 'search',
-    description: '在项目内搜索关键词。',
+    description: '在项目内搜索关键词，也可用正则表达式按行匹配。',
     parameters: {
       type: 'object',
       properties: {
         query: { type: 'string', description: '搜索关键词' },
-        path: { type: 'string', description: '搜索范围（目录路径）' },
+        path: { type: 'string', description: '搜索范围（目录路径）；可用 ext://<目录id>/ 搜索只读参考目录' },
+        regex: { type: 'boolean', description: '为 true 时把 query 当作正则表达式按行匹配（默认 false）' },
+        caseSensitive: { type: 'boolean', description: '为 false 时忽略大小写（默认 true）' },
+        context: { type: 'number', description: '每条结果前后各附带的上下文行数（最多 5）' },
+        maxMatches: { type: 'number', description: '最多返回的结果数（默认 50，最多 500）；结果中 totalMatches 为全部命中数' },
       },
       required: ['query'],
     },
@@ -86,6 +142,17 @@ export const tools: ToolDefinition[] = [
       required: ['chapterId', 'summary'],
     },
   },
+  {
+    name: 'get_story_rollup',
+    description: '获取全书“前情提要”（由各章摘要整合而成），并告知是否已过期。只读。',
+    parameters: {
+      type: 'object',
+      properties: {
+        upToChapterId: { type: 'string', description: '前情提要覆盖到的章节 ID（默认取覆盖最远的一份）' },
+      },
+      required: [],
+    },
+  },
   {
     name: 'rag_search',
     description: '在知识库（knowledge/）中进行语义检索，返回相关片段用于写作参考。',
@@ -154,11 +221,26 @@ export function getToolsForSDK(executeTools?: ExecuteTools) {
       parameters: jsonSchema(getToolDef('append').parameters as any),
       execute: executeTools ? makeExecute('append') : undefined,
     }),
+    edit: tool({
+      description: getToolDef('edit').description,
+      parameters: jsonSchema(getToolDef('edit').parameters as any),
+      execute: executeTools ? makeExecute('edit') : undefined,
+    }),
+    delete: tool({
+      description: getToolDef('delete').description,
+      parameters: jsonSchema(getToolDef('delete').parameters as any),
+      execute: executeTools ? makeExecute('delete') : undefined,
+    }),
     list: tool({
       description: getToolDef('list').description,
       parameters: jsonSchema(getToolDef('list').parameters as any),
       execute: executeTools ? makeExecute('list') : undefined,
     }),
+    stat: tool({
+      description: getToolDef('stat').description,
+      parameters: jsonSchema(getToolDef('stat').parameters as any),
+      execute: executeTools ? makeExecute('stat') : undefined,
+    }),
     search: tool({
       description: getToolDef('search').description,
       parameters: jsonSchema(getToolDef('search').parameters as any),
@@ -174,6 +256,11 @@ export function getToolsForSDK(executeTools?: ExecuteTools) {
       parameters: jsonSchema(getToolDef('save_summary').parameters as any),
       execute: executeTools ? makeExecute('save_summary') : undefined,
     }),
+    get_story_rollup: tool({
+      description: getToolDef('get_story_rollup').description,
+      parameters: jsonSchema(getToolDef('get_story_rollup').parameters as any),
+      execute: executeTools ? makeExecute('get_story_rollup') : undefined,
+    }),
     rag_search: tool({
       description: getToolDef('rag_search').description,
       parameters: jsonSchema(getToolDef('rag_search').parameters as any),
@@ -5,8 +5,21 @@ export interface ProviderConfig {
   baseURL: string
   apiKey: string
   models: string[]
-  providerType: 'openai-compatible' | 'google' | 'anthropic'
+  providerType: 'openai-compatible' | 'google' | 'anthropic' | 'azure-openai' | 'ollama'
   headers?: Record<string, string>
+  /** Azure OpenAI `api-version` query parameter. */
+  apiVersion?: string
+  /** Azure OpenAI: model id -> deployment name. */
+  deployments?: Record<string, string>
+  /** Proxy for this provider's requests; set per request from the resolved app setting. */
+  proxy?: ProxySettings
+}
+
+// 代理设置
+export interface ProxySettings {
+  url: string
+  /** Hosts (or `*.suffix` patterns) that connect directly. */
+  bypass?: string[]
 }
 
 // 模型参数
@@ -0,0 +1,197 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const LOG_RELATIVE_PATH: &str = ".creatorai/activity.jsonl";
+const ROTATED_RELATIVE_PATH: &str = ".creatorai/activity.1.jsonl";
+/// The live log is rotated to `activity.1.jsonl` once it grows past this size.
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Actor {
+    User,
+    Ai,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityEntry {
+    pub timestamp: u64,
+    pub actor: Actor,
+    pub action: String,
+    pub path: String,
+    pub byte_delta: i64,
+}
+
+static LOG_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+fn log_lock() -> &'static Mutex<()> {
+    LOG_LOCK.get_or_init(|| Mutex::new(()))
+}
+
+fn now_unix_seconds() -> Result<u64, String> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .map_err(|e| format!("Failed to read system time: {e}"))
+}
+
+fn rotate_if_needed(log_path: &Path, rotated_path: &Path) -> Result<(), String> {
+    let Ok(meta) = fs::metadata(log_path) else {
+        return Ok(());
+    };
+    if meta.len() < MAX_LOG_BYTES {
+        return Ok(());
+    }
+    fs::rename(log_path, rotated_path).map_err(|e| format!("Failed to rotate activity log: {e}"))
+}
+
+fn append_entry(project_root: &Path, entry: &ActivityEntry) -> Result<(), String> {
+    let _guard = log_lock()
+        .lock()
+        .map_err(|_| "Failed to lock activity log".to_string())?;
+
+    let log_path = project_root.join(LOG_RELATIVE_PATH);
+    if let Some(parent) = log_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create .creatorai: {e}"))?;
+    }
+    rotate_if_needed(&log_path, &project_root.join(ROTATED_RELATIVE_PATH))?;
+
+    let line = serde_json::to_string(entry).map_err(|e| format!("Serialize JSON failed: {e}"))?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+        .map_err(|e| format!("Failed to open activity log: {e}"))?;
+    file.write_all(format!("{line}\n").as_bytes())
+        .map_err(|e| format!("Failed to write activity log: {e}"))
+}
+
+/// Appends one entry to the project's activity log.
+///
+/// Logging is best-effort: failures are returned as a warning message instead of an error so
+/// callers never fail the operation they are recording.
+pub fn record(
+    project_root: &Path,
+    actor: Actor,
+    action: &str,
+    path: &str,
+    byte_delta: i64,
+) -> Option<String> {
+    let result = now_unix_seconds().and_then(|timestamp| {
+        let entry = ActivityEntry {
+            timestamp,
+            actor,
+            action: action.to_string(),
+            path: path.replace('\\', "/"),
+            byte_delta,
+        };
+        append_entry(project_root, &entry)
+    });
+    result.err().map(|e| format!("Activity log not updated: {e}"))
+}
+
+/// Size difference between `new_len` and whatever currently sits at `path` (0 if missing).
+pub fn byte_delta(path: &Path, new_len: usize) -> i64 {
+    let old_len = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    new_len as i64 - old_len as i64
+}
+
+/// Every parseable record of the JSONL file at `path`, oldest first; a missing file is
+/// empty. `name` describes the log in the read error.
+pub(crate) fn read_jsonl<T: DeserializeOwned>(path: &Path, name: &str) -> Result<Vec<T>, String> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {name}: {e}")),
+    };
+    // A torn trailing line from a crash should not hide the rest of the log.
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+fn read_entries(path: &Path) -> Result<Vec<ActivityEntry>, String> {
+    read_jsonl(path, "activity log")
+}
+
+/// Returns activity entries newest-first, including the most recent rotated file.
+pub fn read_log(project_root: &Path, limit: usize, offset: usize) -> Result<Vec<ActivityEntry>, String> {
+    let project_root = project_root
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path: {e}"))?;
+
+    let mut entries = read_entries(&project_root.join(ROTATED_RELATIVE_PATH))?;
+    entries.extend(read_entries(&project_root.join(LOG_RELATIVE_PATH))?);
+    entries.reverse();
+    Ok(entries.into_iter().skip(offset).take(limit).collect())
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_activity_log(
+    project_path: String,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Result<Vec<ActivityEntry>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        read_log(
+            Path::new(&project_path),
+            limit.unwrap_or(100),
+            offset.unwrap_or(0),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn temp_project(prefix: &str) -> TempDir {
+        let temp = TempDir::new(prefix);
+        fs::create_dir_all(temp.path.join(".creatorai")).expect("create temp dir");
+        temp
+    }
+
+    #[test]
+    fn entries_are_read_newest_first_across_rotation() {
+        let temp = temp_project("creatorai-v2-activity");
+        assert!(record(&temp.path, Actor::User, "chapter_save", "chapters/chapter_001.txt", 10).is_none());
+
+        // Force a rotation by padding the live log past the threshold.
+        let log_path = temp.path.join(LOG_RELATIVE_PATH);
+        let mut file = OpenOptions::new().append(true).open(&log_path).unwrap();
+        file.write_all(&vec![b' '; MAX_LOG_BYTES as usize]).unwrap();
+        file.write_all(b"\n").unwrap();
+        drop(file);
+
+        assert!(record(&temp.path, Actor::Ai, "append", "chapters/chapter_001.txt", 5).is_none());
+        assert!(temp.path.join(ROTATED_RELATIVE_PATH).exists());
+
+        let entries = read_log(&temp.path, 10, 0).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].actor, Actor::Ai);
+        assert_eq!(entries[0].byte_delta, 5);
+        assert_eq!(entries[1].action, "chapter_save");
+
+        let page = read_log(&temp.path, 1, 1).unwrap();
+        assert_eq!(page, vec![entries[1].clone()]);
+    }
+
+    #[test]
+    fn record_failure_is_reported_as_warning() {
+        let temp = temp_project("creatorai-v2-activity-fail");
+        // A directory where the log file should be makes the append fail.
+        fs::create_dir_all(temp.path.join(LOG_RELATIVE_PATH)).unwrap();
+        let warning = record(&temp.path, Actor::User, "import", "chapters/chapter_001.txt", 1);
+        assert!(warning.is_some());
+    }
+}
@@ -1,12 +1,13 @@
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::fs;
 use std::io::{BufRead, BufReader, Write};
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
 use std::sync::mpsc;
 use std::time::{Duration, Instant};
 
@@ -34,10 +35,14 @@ impl Drop for ChildGuard {
     }
 }
 
-use crate::file_ops::{append, list, read, search, write};
+use crate::activity_log::{self, Actor};
+use crate::ai_write_log;
+use crate::chapter::{self, count_words, now_unix_seconds};
+use crate::file_ops::{append, delete, edit, list, read, search, stat, write};
 use crate::project::ChapterIndex;
 use crate::session::{SessionMode, ToolCall, ToolCallStatus};
-use crate::{keyring_store, rag, security::validate_path, summary};
+use crate::config::{self, GlobalConfig, ProxySettings};
+use crate::{keyring_store, rag, redact, rollup, security::{check_not_protected, validate_path}, stats, summary, write_protection};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolCallStartEvent {
@@ -69,12 +74,18 @@ pub struct ChatRequest {
     pub mode: SessionMode,
     pub chapter_id: Option<String>,
     pub allow_write: bool,
+    /// Chat session the request belongs to, recorded on summaries the AI saves.
+    #[serde(default)]
+    pub session_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ChatResponse {
     pub content: String,
     pub tool_calls: Vec<ToolCall>,
+    /// Groups the files this turn wrote, for `undo_ai_apply`; `None` when nothing was written.
+    #[serde(default)]
+    pub apply_id: Option<String>,
 }
 
 fn chat_timeout() -> Duration {
@@ -360,6 +371,66 @@ fn spawn_ai_engine(path: &Path) -> Result<std::process::Child, String> {
             }
             format!("Failed to spawn ai-engine: {e}")
         })
+        .map_err(redact::redact_error)
+}
+
+// Engine output can echo the request, API key included, so everything taken from it is
+// redacted before it becomes an error.
+fn parse_engine_line(line: &str) -> Result<Value, String> {
+    serde_json::from_str(line).map_err(|e| {
+        format!("Failed to parse response: {e}. line={:?}", redact::redact(line))
+    })
+}
+
+fn engine_error(response: &Value) -> String {
+    redact::redact(response["message"].as_str().unwrap_or("Unknown error"))
+}
+
+/// A failed engine request, with the structured fields the engine reported alongside the
+/// message.
+#[derive(Debug, Default)]
+pub struct EngineError {
+    pub message: String,
+    /// HTTP status of the provider response, when there was one.
+    pub status: Option<u16>,
+    /// System error code of a request that never got a response (e.g. `ECONNREFUSED`).
+    pub code: Option<String>,
+    /// The request ran past its deadline and the engine was killed.
+    pub timed_out: bool,
+}
+
+impl From<String> for EngineError {
+    fn from(message: String) -> Self {
+        Self {
+            message,
+            ..Self::default()
+        }
+    }
+}
+
+impl From<&str> for EngineError {
+    fn from(message: &str) -> Self {
+        message.to_string().into()
+    }
+}
+
+impl From<EngineError> for String {
+    fn from(error: EngineError) -> Self {
+        error.message
+    }
+}
+
+fn engine_failure(response: &Value) -> EngineError {
+    EngineError {
+        message: engine_error(response),
+        status: response["status"].as_u64().and_then(|s| u16::try_from(s).ok()),
+        code: response["code"].as_str().map(str::to_string),
+        timed_out: false,
+    }
+}
+
+fn unknown_response(line: &str) -> String {
+    format!("Unknown response: {}", redact::redact(line))
 }
 
 fn format_pipe_error_from_guard(
@@ -406,11 +477,195 @@ fn format_tool_runs(runs: &[ToolCall]) -> String {
     out.trim_end().to_string()
 }
 
+/// The saved config one AI request reads its provider settings from, loaded once per
+/// request. A config that fails to load just means no saved settings apply.
+pub(crate) fn request_config() -> GlobalConfig {
+    config::load_config().unwrap_or_default()
+}
+
+/// Proxy settings forwarded to the ai-engine. A `proxy` on the provider payload wins,
+/// otherwise the saved config is consulted (provider override, then global).
+pub(crate) fn proxy_json(config: &GlobalConfig, provider: &Value) -> Value {
+    if let Some(proxy) = provider.get("proxy").filter(|v| !v.is_null()) {
+        return proxy.clone();
+    }
+    let provider_id = provider.get("id").and_then(Value::as_str);
+    config
+        .proxy_for(provider_id)
+        .and_then(|proxy| serde_json::to_value(proxy).ok())
+        .unwrap_or(Value::Null)
+}
+
+/// Fills in custom headers and Azure routing fields saved on the provider that the payload
+/// does not already carry, so completions get them even when the caller built the provider
+/// JSON without them. Runs before auth injection, which always has the last word on auth
+/// headers.
+pub(crate) fn merge_configured_provider_fields(config: &GlobalConfig, provider: &mut Value) {
+    let Some(provider_id) = provider.get("id").and_then(Value::as_str) else {
+        return;
+    };
+    let Some(saved) = config.providers.iter().find(|p| p.id == provider_id).cloned() else {
+        return;
+    };
+    let Some(obj) = provider.as_object_mut() else {
+        return;
+    };
+    if let Some(api_version) = saved.api_version {
+        obj.entry("apiVersion").or_insert(json!(api_version));
+    }
+    if !saved.deployments.is_empty() {
+        obj.entry("deployments").or_insert(json!(saved.deployments));
+    }
+    let Some(saved_headers) = saved.headers else {
+        return;
+    };
+    let headers = obj.entry("headers").or_insert(json!({}));
+    if headers.is_null() {
+        *headers = json!({});
+    }
+    if let Some(h) = headers.as_object_mut() {
+        for (name, value) in saved_headers {
+            if !h.keys().any(|k| k.eq_ignore_ascii_case(&name)) {
+                h.insert(name, json!(value));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct RequestLimits {
+    max_concurrent: Option<u32>,
+    min_interval: Option<Duration>,
+}
+
+impl RequestLimits {
+    fn for_provider(config: &GlobalConfig, provider_id: &str) -> Self {
+        let Some(provider) = config.providers.iter().find(|p| p.id == provider_id) else {
+            return Self::default();
+        };
+        Self {
+            max_concurrent: provider.max_concurrent_requests.filter(|n| *n > 0),
+            min_interval: provider
+                .min_request_interval_ms
+                .filter(|ms| *ms > 0)
+                .map(Duration::from_millis),
+        }
+    }
+
+    fn is_unlimited(&self) -> bool {
+        self.max_concurrent.is_none() && self.min_interval.is_none()
+    }
+}
+
+#[derive(Default)]
+struct LimiterState {
+    in_flight: u32,
+    last_start: Option<Instant>,
+}
+
+#[derive(Default)]
+struct ProviderLimiter {
+    state: Mutex<LimiterState>,
+    released: Condvar,
+}
+
+static PROVIDER_LIMITERS: OnceLock<Mutex<HashMap<String, Arc<ProviderLimiter>>>> = OnceLock::new();
+
+fn provider_limiter(provider_id: &str) -> Arc<ProviderLimiter> {
+    let registry = PROVIDER_LIMITERS.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut registry = registry.lock().unwrap_or_else(|e| e.into_inner());
+    registry.entry(provider_id.to_string()).or_default().clone()
+}
+
+/// A provider request slot; dropping it frees the slot for the next waiter.
+pub(crate) struct RequestSlot(Option<Arc<ProviderLimiter>>);
+
+impl Drop for RequestSlot {
+    fn drop(&mut self) {
+        if let Some(limiter) = self.0.take() {
+            let mut state = limiter.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.in_flight = state.in_flight.saturating_sub(1);
+            limiter.released.notify_all();
+        }
+    }
+}
+
+fn acquire_slot(
+    limiter: Arc<ProviderLimiter>,
+    limits: RequestLimits,
+    cancel: &AtomicBool,
+) -> Result<RequestSlot, String> {
+    // Wake up regularly so a cancel is noticed even when nothing is released.
+    const POLL: Duration = Duration::from_millis(100);
+    let mut state = limiter.state.lock().unwrap_or_else(|e| e.into_inner());
+    loop {
+        if cancel.load(Ordering::SeqCst) {
+            return Err("已停止生成".to_string());
+        }
+        let has_capacity = limits.max_concurrent.map_or(true, |max| state.in_flight < max);
+        let interval_wait = match (limits.min_interval, state.last_start) {
+            (Some(interval), Some(last)) => interval.saturating_sub(last.elapsed()),
+            _ => Duration::ZERO,
+        };
+        if has_capacity && interval_wait.is_zero() {
+            state.in_flight += 1;
+            state.last_start = Some(Instant::now());
+            drop(state);
+            return Ok(RequestSlot(Some(limiter)));
+        }
+        let wait = if has_capacity { interval_wait.min(POLL) } else { POLL };
+        state = limiter
+            .released
+            .wait_timeout(state, wait)
+            .unwrap_or_else(|e| e.into_inner())
+            .0;
+    }
+}
+
+/// Waits until the provider's `max_concurrent_requests` / `min_request_interval_ms` allow
+/// another request. Callers should start their own timeouts only after this returns.
+pub(crate) fn acquire_request_slot(
+    config: &GlobalConfig,
+    provider_id: Option<&str>,
+    cancel: &AtomicBool,
+) -> Result<RequestSlot, String> {
+    let Some(provider_id) = provider_id.filter(|id| !id.is_empty()) else {
+        return Ok(RequestSlot(None));
+    };
+    let limits = RequestLimits::for_provider(config, provider_id);
+    if limits.is_unlimited() {
+        return Ok(RequestSlot(None));
+    }
+    acquire_slot(provider_limiter(provider_id), limits, cancel)
+}
+
+fn provider_id_of(provider: &Value) -> Option<&str> {
+    provider.get("id").and_then(Value::as_str)
+}
+
 pub fn fetch_models(
+    provider_id: Option<&str>,
     provider_type: &str,
     base_url: &str,
     api_key: &str,
+    proxy: Option<&ProxySettings>,
+    api_version: Option<&str>,
 ) -> Result<Vec<String>, String> {
+    fetch_models_within(provider_id, provider_type, base_url, api_key, proxy, api_version, None)
+        .map_err(String::from)
+}
+
+/// Like [`fetch_models`], but kills the engine and fails once `timeout` elapses.
+pub fn fetch_models_within(
+    provider_id: Option<&str>,
+    provider_type: &str,
+    base_url: &str,
+    api_key: &str,
+    proxy: Option<&ProxySettings>,
+    api_version: Option<&str>,
+    timeout: Option<Duration>,
+) -> Result<Vec<String>, EngineError> {
+    let _slot = acquire_request_slot(&request_config(), provider_id, &AtomicBool::new(false))?;
     let ai_engine_path = get_ai_engine_path()?;
 
     let mut child = spawn_ai_engine(&ai_engine_path)?;
@@ -420,27 +675,51 @@ pub fn fetch_models(
 
     let mut stdin = child_ref.stdin.take().ok_or("Failed to get stdin")?;
     let stdout = child_ref.stdout.take().ok_or("Failed to get stdout")?;
-    let mut reader = BufReader::new(stdout);
 
     let request = json!({
         "type": "fetch_models",
         "providerType": provider_type,
         "baseURL": base_url,
         "apiKey": api_key,
+        "proxy": proxy,
+        "apiVersion": api_version,
     });
 
     if let Err(e) = writeln!(stdin, "{}", request.to_string()) {
-        return Err(format_pipe_error_from_guard(&mut guard, "write to stdin", &e));
+        return Err(format_pipe_error_from_guard(&mut guard, "write to stdin", &e).into());
     }
     drop(stdin);
 
-    let mut line = String::new();
-    reader
-        .read_line(&mut line)
-        .map_err(|e| format!("Failed to read from stdout: {e}"))?;
+    let (tx, rx) = mpsc::channel::<Result<String, String>>();
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(stdout);
+        let mut line = String::new();
+        let result = reader
+            .read_line(&mut line)
+            .map(|_| line)
+            .map_err(|e| format!("Failed to read from stdout: {e}"));
+        let _ = tx.send(result);
+    });
+    // On timeout the guard kills the engine, which also unblocks the reader thread.
+    let line = match timeout {
+        Some(timeout) => match rx.recv_timeout(timeout) {
+            Ok(result) => result?,
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                return Err(EngineError {
+                    timed_out: true,
+                    ..format!("Request timed out after {}s", timeout.as_secs()).into()
+                });
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                return Err("ai-engine exited unexpectedly".into());
+            }
+        },
+        None => rx
+            .recv()
+            .map_err(|_| "ai-engine exited unexpectedly".to_string())??,
+    };
 
-    let response: Value = serde_json::from_str(&line)
-        .map_err(|e| format!("Failed to parse response: {e}. line={line:?}"))?;
+    let response = parse_engine_line(&line)?;
 
     // Parse response while guard still protects against zombie.
     // Only take child after all fallible validation is done.
@@ -455,10 +734,10 @@ pub fn fetch_models(
             Ok(models)
         }
         Some("error") => {
-            Err(response["message"].as_str().unwrap_or("Unknown error").to_string())
+            Err(engine_failure(&response))
         }
         _ => {
-            Err(format!("Unknown response: {line}"))
+            Err(unknown_response(&line).into())
         }
     };
 
@@ -468,11 +747,38 @@ pub fn fetch_models(
     result
 }
 
+/// Rewrites engine errors from a local Ollama server into something actionable.
+pub(crate) fn explain_ollama_error(model: &str, message: String) -> String {
+    let lower = message.to_lowercase();
+    if lower.contains("try pulling") || (lower.contains("model") && lower.contains("not found")) {
+        return format!("模型 {model} 尚未下载（model not pulled），请先运行 `ollama pull {model}`。{message}");
+    }
+    if lower.contains("econnrefused") || lower.contains("connection refused") || lower.contains("fetch failed") {
+        return format!("无法连接 Ollama，请确认 `ollama serve` 已启动。{message}");
+    }
+    message
+}
+
+/// [`explain_ollama_error`] for the provider/parameters JSON sent to the engine; errors
+/// from other provider types pass through unchanged.
+pub(crate) fn explain_provider_error(provider: &Value, parameters: &Value, message: String) -> String {
+    let provider_type = provider
+        .get("providerType")
+        .or_else(|| provider.get("provider_type"))
+        .and_then(Value::as_str);
+    if provider_type != Some("ollama") {
+        return message;
+    }
+    let model = parameters.get("model").and_then(Value::as_str).unwrap_or_default();
+    explain_ollama_error(model, message)
+}
+
 pub fn generate_compact_summary(
     provider: Value,
     parameters: Value,
     messages: Vec<Value>,
 ) -> Result<String, String> {
+    let config = request_config();
     let ai_engine_path = get_ai_engine_path()?;
 
     let child = spawn_ai_engine(&ai_engine_path)?;
@@ -485,8 +791,9 @@ pub fn generate_compact_summary(
 
     // Runtime injection of API Key into provider config
     let mut provider_with_auth = provider.clone();
+    merge_configured_provider_fields(&config, &mut provider_with_auth);
     if let Some(provider_id) = provider_with_auth.get("id").and_then(|v| v.as_str()) {
-        if let Ok(Some(api_key)) = keyring_store::get_api_key(provider_id) {
+        if let Ok(Some(api_key)) = keyring_store::get_provider_api_key(provider_id) {
             let provider_type = provider_with_auth
                 .get("providerType")
                 .or_else(|| provider_with_auth.get("provider_type"))
@@ -520,6 +827,7 @@ pub fn generate_compact_summary(
     let request = json!({
         "type": "compact",
         "provider": provider_with_auth,
+        "proxy": proxy_json(&config, &provider_with_auth),
         "parameters": parameters,
         "messages": messages,
     });
@@ -541,8 +849,7 @@ pub fn generate_compact_summary(
     if trimmed.is_empty() {
         return Err("Empty response from ai-engine".to_string());
     }
-    let response: Value = serde_json::from_str(trimmed)
-        .map_err(|e| format!("Failed to parse response: {e}. line={trimmed:?}"))?;
+    let response = parse_engine_line(trimmed)?;
 
     // Take child out of guard — we'll wait manually
     let mut child = guard.take().unwrap();
@@ -555,11 +862,11 @@ pub fn generate_compact_summary(
         }
         Some("error") => {
             let _ = child.wait();
-            Err(response["message"].as_str().unwrap_or("Unknown error").to_string())
+            Err(engine_error(&response))
         }
         _ => {
             let _ = child.wait();
-            Err(format!("Unknown response: {line}"))
+            Err(unknown_response(&line))
         }
     }
 }
@@ -569,6 +876,7 @@ pub fn run_extract(
     parameters: Value,
     text: String,
 ) -> Result<Value, String> {
+    let config = request_config();
     let ai_engine_path = get_ai_engine_path()?;
     let child = spawn_ai_engine(&ai_engine_path)?;
     let mut guard = ChildGuard::new(child);
@@ -580,8 +888,9 @@ pub fn run_extract(
 
     // Runtime API Key injection
     let mut provider_with_auth = provider.clone();
+    merge_configured_provider_fields(&config, &mut provider_with_auth);
     if let Some(provider_id) = provider_with_auth.get("id").and_then(|v| v.as_str()) {
-        if let Ok(Some(api_key)) = keyring_store::get_api_key(provider_id) {
+        if let Ok(Some(api_key)) = keyring_store::get_provider_api_key(provider_id) {
             let provider_type = provider_with_auth
                 .get("providerType")
                 .or_else(|| provider_with_auth.get("provider_type"))
@@ -612,6 +921,7 @@ pub fn run_extract(
     let request = json!({
         "type": "extract",
         "provider": provider_with_auth,
+        "proxy": proxy_json(&config, &provider_with_auth),
         "parameters": parameters,
         "text": text,
     });
@@ -630,16 +940,15 @@ pub fn run_extract(
         return Err("Empty response from ai-engine".to_string());
     }
 
-    let response: Value = serde_json::from_str(trimmed)
-        .map_err(|e| format!("Failed to parse response: {e}. line={trimmed:?}"))?;
+    let response = parse_engine_line(trimmed)?;
 
     let mut child = guard.take().unwrap();
     let _ = child.wait();
 
     match response["type"].as_str() {
         Some("extract_result") => Ok(response),
-        Some("error") => Err(response["message"].as_str().unwrap_or("Unknown error").to_string()),
-        _ => Err(format!("Unknown response: {trimmed}")),
+        Some("error") => Err(engine_error(&response)),
+        _ => Err(unknown_response(trimmed)),
     }
 }
 
@@ -650,6 +959,7 @@ pub fn run_transform(
     action: String,
     style: Option<String>,
 ) -> Result<String, String> {
+    let config = request_config();
     let ai_engine_path = get_ai_engine_path()?;
     let child = spawn_ai_engine(&ai_engine_path)?;
     let mut guard = ChildGuard::new(child);
@@ -661,8 +971,9 @@ pub fn run_transform(
 
     // Runtime API Key injection
     let mut provider_with_auth = provider.clone();
+    merge_configured_provider_fields(&config, &mut provider_with_auth);
     if let Some(provider_id) = provider_with_auth.get("id").and_then(|v| v.as_str()) {
-        if let Ok(Some(api_key)) = keyring_store::get_api_key(provider_id) {
+        if let Ok(Some(api_key)) = keyring_store::get_provider_api_key(provider_id) {
             let provider_type = provider_with_auth
                 .get("providerType")
                 .or_else(|| provider_with_auth.get("provider_type"))
@@ -693,6 +1004,7 @@ pub fn run_transform(
     let mut request = json!({
         "type": "transform",
         "provider": provider_with_auth,
+        "proxy": proxy_json(&config, &provider_with_auth),
         "parameters": parameters,
         "text": text,
         "action": action,
@@ -715,8 +1027,7 @@ pub fn run_transform(
         return Err("Empty response from ai-engine".to_string());
     }
 
-    let response: Value = serde_json::from_str(trimmed)
-        .map_err(|e| format!("Failed to parse response: {e}. line={trimmed:?}"))?;
+    let response = parse_engine_line(trimmed)?;
 
     let mut child = guard.take().unwrap();
     let _ = child.wait();
@@ -725,8 +1036,8 @@ pub fn run_transform(
         Some("transform_result") => {
             Ok(response["content"].as_str().unwrap_or("").to_string())
         }
-        Some("error") => Err(response["message"].as_str().unwrap_or("Unknown error").to_string()),
-        _ => Err(format!("Unknown response: {trimmed}")),
+        Some("error") => Err(engine_error(&response)),
+        _ => Err(unknown_response(trimmed)),
     }
 }
 
@@ -751,10 +1062,22 @@ pub fn run_complete(
     messages: Vec<Value>,
     cancel: Option<Arc<AtomicBool>>,
 ) -> Result<String, String> {
-    let ai_engine_path = get_ai_engine_path()?;
+    run_complete_within(provider, parameters, system_prompt, messages, cancel, complete_timeout())
+        .map_err(String::from)
+}
 
+pub fn run_complete_within(
+    provider: Value,
+    parameters: Value,
+    system_prompt: String,
+    messages: Vec<Value>,
+    cancel: Option<Arc<AtomicBool>>,
+    timeout: Duration,
+) -> Result<String, EngineError> {
     let cancel_flag = cancel.unwrap_or_else(|| Arc::new(AtomicBool::new(false)));
-    let timeout = complete_timeout();
+    let config = request_config();
+    let _slot = acquire_request_slot(&config, provider_id_of(&provider), &cancel_flag)?;
+    let ai_engine_path = get_ai_engine_path()?;
 
     let child = spawn_ai_engine(&ai_engine_path)?;
     // ChildGuard protects against zombie processes on early `?` returns.
@@ -795,8 +1118,9 @@ pub fn run_complete(
 
     // Runtime injection of API Key into provider config
     let mut provider_with_auth = provider.clone();
+    merge_configured_provider_fields(&config, &mut provider_with_auth);
     if let Some(provider_id) = provider_with_auth.get("id").and_then(|v| v.as_str()) {
-        if let Ok(Some(api_key)) = keyring_store::get_api_key(provider_id) {
+        if let Ok(Some(api_key)) = keyring_store::get_provider_api_key(provider_id) {
             let provider_type = provider_with_auth
                 .get("providerType")
                 .or_else(|| provider_with_auth.get("provider_type"))
@@ -830,6 +1154,7 @@ pub fn run_complete(
     let init_request = json!({
         "type": "complete",
         "provider": provider_with_auth,
+        "proxy": proxy_json(&config, &provider_with_auth),
         "parameters": parameters,
         "systemPrompt": system_prompt,
         "messages": messages,
@@ -837,10 +1162,10 @@ pub fn run_complete(
 
     // These `?` returns are protected by ChildGuard (kills+waits child on drop)
     if let Err(e) = writeln!(stdin, "{}", init_request.to_string()) {
-        return Err(format_pipe_error_from_guard(&mut guard, "write to stdin", &e));
+        return Err(format_pipe_error_from_guard(&mut guard, "write to stdin", &e).into());
     }
     if let Err(e) = stdin.flush() {
-        return Err(format_pipe_error_from_guard(&mut guard, "flush stdin", &e));
+        return Err(format_pipe_error_from_guard(&mut guard, "flush stdin", &e).into());
     }
 
     // Take child from guard AFTER all fallible init — loop handles its own kill/wait
@@ -852,13 +1177,16 @@ pub fn run_complete(
             drop(stdin);
             let _ = child.kill();
             let _ = child.wait();
-            return Err("已停止生成".to_string());
+            return Err("已停止生成".into());
         }
         if started.elapsed() > timeout {
             drop(stdin);
             let _ = child.kill();
             let _ = child.wait();
-            return Err("补全请求超时（请重试或更换模型/Provider）".to_string());
+            return Err(EngineError {
+                timed_out: true,
+                .."补全请求超时（请重试或更换模型/Provider）".into()
+            });
         }
 
         let line = match rx.recv_timeout(Duration::from_millis(50)) {
@@ -868,7 +1196,7 @@ pub fn run_complete(
                 let status = child
                     .wait()
                     .map_err(|e| format!("Failed to wait for ai-engine: {e}"))?;
-                return Err(format!("ai-engine exited unexpectedly: {status}. {err}"));
+                return Err(format!("ai-engine exited unexpectedly: {status}. {err}").into());
             }
             Err(mpsc::RecvTimeoutError::Timeout) => continue,
             Err(mpsc::RecvTimeoutError::Disconnected) => {
@@ -876,7 +1204,7 @@ pub fn run_complete(
                 let status = child
                     .wait()
                     .map_err(|e| format!("Failed to wait for ai-engine: {e}"))?;
-                return Err(format!("ai-engine exited unexpectedly: {status}"));
+                return Err(format!("ai-engine exited unexpectedly: {status}").into());
             }
         };
 
@@ -887,7 +1215,10 @@ pub fn run_complete(
         let response: Value = match serde_json::from_str(trimmed) {
             Ok(v) => v,
             Err(e) => {
-                eprintln!("[ai-bridge] Skipping non-JSON line: {e}. line={trimmed:?}");
+                eprintln!(
+                    "[ai-bridge] Skipping non-JSON line: {e}. line={:?}",
+                    redact::redact(trimmed)
+                );
                 continue;
             }
         };
@@ -900,13 +1231,16 @@ pub fn run_complete(
                 return Ok(content);
             }
             Some("error") => {
-                let message = response["message"].as_str().unwrap_or("Unknown error");
+                let error = engine_failure(&response);
                 drop(stdin);
                 let _ = child.wait();
-                return Err(message.to_string());
+                return Err(error);
             }
             _ => {
-                eprintln!("[ai-bridge] Skipping unknown response type in complete loop: {line}");
+                eprintln!(
+                    "[ai-bridge] Skipping unknown response type in complete loop: {}",
+                    redact::redact(&line)
+                );
                 continue;
             }
         }
@@ -922,9 +1256,10 @@ pub fn run_chat_with_events(
     events: Option<ChatEventHandler>,
     cancel: Option<Arc<AtomicBool>>,
 ) -> Result<ChatResponse, String> {
-    let ai_engine_path = get_ai_engine_path()?;
-
     let cancel_flag = cancel.unwrap_or_else(|| Arc::new(AtomicBool::new(false)));
+    let config = request_config();
+    let _slot = acquire_request_slot(&config, provider_id_of(&request.provider), &cancel_flag)?;
+    let ai_engine_path = get_ai_engine_path()?;
 
     let provider_base_url = request
         .provider
@@ -936,6 +1271,13 @@ pub fn run_chat_with_events(
     // geminicli/v1 目前在多轮 tool calling 的第二次请求会要求 thought_signature（OpenAI tool_calls 不包含），
     // 因此在该端点下我们只执行工具并直接返回结果。
     let direct_return_tool_results = provider_base_url.contains("/geminicli/v1");
+    let tool_provenance = summary::SummaryProvenance {
+        source: summary::SummarySource::Tool,
+        session_id: request.session_id.clone(),
+        model: request.parameters.get("model").and_then(|v| v.as_str()).map(str::to_string),
+    };
+    let apply_id = uuid::Uuid::new_v4().to_string();
+    let mut applied = false;
 
     let child = spawn_ai_engine(&ai_engine_path)?;
     // ChildGuard protects against zombie processes on early `?` returns.
@@ -975,8 +1317,9 @@ pub fn run_chat_with_events(
 
     // Runtime injection of API Key into provider config
     let mut provider_with_auth = request.provider.clone();
+    merge_configured_provider_fields(&config, &mut provider_with_auth);
     if let Some(provider_id) = provider_with_auth.get("id").and_then(|v| v.as_str()) {
-        if let Ok(Some(api_key)) = keyring_store::get_api_key(provider_id) {
+        if let Ok(Some(api_key)) = keyring_store::get_provider_api_key(provider_id) {
             let provider_type = provider_with_auth
                 .get("providerType")
                 .or_else(|| provider_with_auth.get("provider_type"))
@@ -1011,6 +1354,7 @@ pub fn run_chat_with_events(
     let init_request = json!({
         "type": "chat",
         "provider": provider_with_auth,
+        "proxy": proxy_json(&config, &provider_with_auth),
         "parameters": request.parameters,
         "systemPrompt": request.system_prompt,
         "messages": request.messages,
@@ -1081,7 +1425,10 @@ pub fn run_chat_with_events(
         let response: Value = match serde_json::from_str(trimmed) {
             Ok(v) => v,
             Err(e) => {
-                eprintln!("[ai-bridge] Skipping non-JSON line: {e}. line={trimmed:?}");
+                eprintln!(
+                    "[ai-bridge] Skipping non-JSON line: {e}. line={:?}",
+                    redact::redact(trimmed)
+                );
                 continue;
             }
         };
@@ -1091,13 +1438,17 @@ pub fn run_chat_with_events(
                 let content = response["content"].as_str().unwrap_or("").to_string();
                 drop(stdin);
                 let _ = child.wait();
-                return Ok(ChatResponse { content, tool_calls });
+                return Ok(ChatResponse {
+                    content,
+                    tool_calls,
+                    apply_id: applied.then_some(apply_id),
+                });
             }
             Some("error") => {
-                let message = response["message"].as_str().unwrap_or("Unknown error");
+                let message = engine_error(&response);
                 drop(stdin);
                 let _ = child.wait();
-                return Err(message.to_string());
+                return Err(message);
             }
             Some("tool_call") => {
                 last_progress = Instant::now(); // Recognized response — refresh timeout
@@ -1134,19 +1485,39 @@ pub fn run_chat_with_events(
                     }
 
                     let started = Instant::now();
+                    let pending_write = ai_write_target(&name, &args)
+                        .map(|target| ai_write_log::PendingWrite::begin(Path::new(&request.project_dir), &target));
                     let result =
                         execute_tool(
                             &request.project_dir,
                             request.mode.clone(),
                             request.allow_write,
                             request.chapter_id.as_deref(),
+                            &tool_provenance,
                             &name,
                             &args,
                         );
                     let duration = started.elapsed().as_millis() as u64;
 
                     let (status, result_value, error_value) = match result {
-                        Ok(value) => (ToolCallStatus::Success, Some(value), None),
+                        Ok(value) => {
+                            // The write already happened; a logging problem is only a warning.
+                            applied |= pending_write.is_some();
+                            let warning = pending_write.and_then(|pending| {
+                                pending.finish(
+                                    Path::new(&request.project_dir),
+                                    &ai_write_log::WriteContext {
+                                        mode: &request.mode,
+                                        chapter_id: request.chapter_id.as_deref(),
+                                        session_id: request.session_id.as_deref(),
+                                        apply_id: &apply_id,
+                                    },
+                                    &name,
+                                    &args,
+                                )
+                            });
+                            (ToolCallStatus::Success, Some(value), warning)
+                        }
                         Err(err) => (ToolCallStatus::Error, None, Some(err)),
                     };
 
@@ -1169,7 +1540,7 @@ pub fn run_chat_with_events(
                     });
 
                     match (&result_value, &error_value) {
-                        (Some(value), None) => results.push(json!({ "id": id, "result": value })),
+                        (Some(value), _) => results.push(json!({ "id": id, "result": value })),
                         (_, Some(err)) => {
                             results.push(json!({ "id": id, "result": "", "error": err }))
                         }
@@ -1200,7 +1571,11 @@ pub fn run_chat_with_events(
                         drop(stdin);
                         let _ = child.kill();
                         let _ = child.wait();
-                        return Ok(ChatResponse { content, tool_calls });
+                        return Ok(ChatResponse {
+                            content,
+                            tool_calls,
+                            apply_id: applied.then_some(apply_id),
+                        });
                     }
                 } else {
                     consecutive_tool_errors = 0;
@@ -1211,7 +1586,11 @@ pub fn run_chat_with_events(
                     drop(stdin);
                     let _ = child.kill();
                     let _ = child.wait();
-                    return Ok(ChatResponse { content, tool_calls });
+                    return Ok(ChatResponse {
+                        content,
+                        tool_calls,
+                        apply_id: applied.then_some(apply_id),
+                    });
                 }
 
                 let tool_result = json!({
@@ -1231,7 +1610,10 @@ pub fn run_chat_with_events(
             _ => {
                 // Unknown response types are logged and skipped rather than killing
                 // the chat loop — the Node.js engine or bun may emit diagnostic JSON.
-                eprintln!("[ai-bridge] Skipping unknown response type in chat loop: {line}");
+                eprintln!(
+                    "[ai-bridge] Skipping unknown response type in chat loop: {}",
+                    redact::redact(&line)
+                );
                 continue;
             }
         }
@@ -1252,62 +1634,55 @@ fn as_i64(value: &Value) -> Option<i64> {
         .or_else(|| value.as_f64().and_then(|v| if v.is_finite() { Some(v as i64) } else { None }))
 }
 
-fn now_unix_seconds() -> Result<u64, String> {
-    use std::time::{SystemTime, UNIX_EPOCH};
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_secs())
-        .map_err(|e| format!("Failed to read system time: {e}"))
-}
-
-fn count_words(content: &str) -> u32 {
-    content.chars().filter(|c| !c.is_whitespace()).count() as u32
-}
-
-fn maybe_update_chapter_index(project_root: &Path, relative_path: &str) -> Result<(), String> {
-    if !relative_path.starts_with("chapters/") || !relative_path.ends_with(".txt") {
-        return Ok(());
-    }
-    let filename = relative_path
-        .rsplit('/')
-        .next()
-        .unwrap_or(relative_path);
-    let Some(chapter_id) = filename.strip_suffix(".txt") else {
-        return Ok(());
+/// Appends `content` to `relative_path`. For a chapter listed in `chapters/index.json`
+/// the chapter and its index entry (word count, updated) are written in one transaction.
+/// Returns a warning when the writing stats could not be updated afterwards.
+fn append_with_chapter_index(
+    project_root: &Path,
+    relative_path: &str,
+    content: &str,
+) -> Result<Option<String>, String> {
+    let plain_append = || {
+        append::append_file(
+            project_root,
+            append::AppendParams {
+                path: relative_path.to_string(),
+                content: content.to_string(),
+            },
+        )
+        .map(|()| None)
     };
-    if !chapter_id.starts_with("chapter_")
-        || !chapter_id["chapter_".len()..]
-            .chars()
-            .all(|c| c.is_ascii_digit())
-    {
-        return Ok(());
-    }
-
-    let index_path = validate_path(project_root, "chapters/index.json")?;
-    if !index_path.exists() {
-        return Ok(());
-    }
-    let bytes = std::fs::read(&index_path)
-        .map_err(|e| format!("Failed to read chapters/index.json: {e}"))?;
-    let mut index = serde_json::from_slice::<ChapterIndex>(&bytes)
-        .map_err(|e| format!("Failed to parse chapters/index.json: {e}"))?;
-
+    let Some(chapter_id) = write_protection::indexed_chapter_id(project_root, relative_path) else {
+        return plain_append();
+    };
+    let mut index = chapter::read_index(project_root)?;
     let Some(meta) = index.chapters.iter_mut().find(|c| c.id == chapter_id) else {
-        return Ok(());
+        return plain_append();
     };
 
     let chapter_path = validate_path(project_root, relative_path)?;
-    let content = std::fs::read_to_string(&chapter_path)
-        .map_err(|e| format!("Failed to read chapter content: {e}"))?;
+    let current = match std::fs::read_to_string(&chapter_path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("Failed to read chapter content: {e}")),
+    };
 
+    // One extra byte for the newline the append may insert first.
+    let new_len = current.len() + content.len() + 1;
+    write_protection::check_write_size(project_root, &chapter_path, new_len as u64)?;
+    let previous_words = meta.word_count;
     meta.updated = now_unix_seconds()?;
-    meta.word_count = count_words(&content);
+    meta.word_count = count_words(&current) + count_words(content);
+    let new_total = meta.word_count;
 
     let json = serde_json::to_string_pretty(&index)
         .map_err(|e| format!("Serialize JSON failed: {e}"))?;
-    std::fs::write(&index_path, format!("{json}\n"))
-        .map_err(|e| format!("Failed to write chapters/index.json: {e}"))?;
-    Ok(())
+    write_protection::transaction(project_root)
+        .append(relative_path, content)
+        .write("chapters/index.json", format!("{json}\n"))
+        .commit()?;
+    let word_delta = i64::from(new_total) - i64::from(previous_words);
+    Ok(stats::record(project_root, &chapter_id, word_delta, new_total))
 }
 
 fn normalize_chapter_id(value: &str) -> Result<String, String> {
@@ -1332,22 +1707,54 @@ fn normalize_chapter_id(value: &str) -> Result<String, String> {
     Err("Invalid chapterId".to_string())
 }
 
+fn with_activity_warning(message: &str, warning: Option<String>) -> String {
+    match warning {
+        Some(warning) => format!("{message} ({warning})"),
+        None => message.to_string(),
+    }
+}
+
+/// Project-relative file a write tool call will change, for the AI write log.
+fn ai_write_target(name: &str, args: &Value) -> Option<String> {
+    match name {
+        "write" | "append" | "delete" | "edit" => args["path"].as_str().map(str::to_string),
+        "save_summary" => {
+            let raw = args["chapterId"].as_str().or_else(|| args["chapter_id"].as_str())?;
+            normalize_chapter_id(raw)
+                .ok()
+                .map(|id| format!("summaries/{id}.json"))
+        }
+        _ => None,
+    }
+}
+
 fn execute_tool(
     project_dir: &str,
     mode: SessionMode,
     allow_write: bool,
     chapter_id: Option<&str>,
+    provenance: &summary::SummaryProvenance,
     name: &str,
     args: &Value,
 ) -> Result<String, String> {
-    if matches!(mode, SessionMode::Discussion) && matches!(name, "write" | "append" | "save_summary") {
+    if matches!(mode, SessionMode::Discussion) && matches!(name, "write" | "append" | "delete" | "edit" | "save_summary") {
         return Err("Tool not allowed in Discussion mode".to_string());
     }
-    if matches!(mode, SessionMode::Continue) && !allow_write && matches!(name, "write" | "append" | "save_summary") {
+    if matches!(mode, SessionMode::Continue)
+        && !allow_write
+        && matches!(name, "write" | "append" | "delete" | "edit" | "save_summary")
+    {
         return Err("Tool not allowed before user confirmation".to_string());
     }
 
     let project_root = Path::new(project_dir);
+    if matches!(name, "write" | "append" | "delete" | "edit" | "save_summary") {
+        crate::write_protection::ensure_writable(project_root)?;
+    }
+    if matches!(name, "write" | "append" | "delete" | "edit") {
+        let path = args["path"].as_str().ok_or("Missing path")?;
+        check_not_protected(project_root, path)?;
+    }
     match name {
         "read" => {
             let path = args["path"].as_str().ok_or("Missing path")?;
@@ -1358,6 +1765,9 @@ fn execute_tool(
                 path: path.to_string(),
                 offset,
                 limit,
+                byte_offset: args["byteOffset"].as_u64(),
+                byte_limit: as_u32(&args["byteLimit"]),
+                encoding: args["encoding"].as_str().map(str::to_string),
             };
             let result = read::read_file(project_root, params)?;
             serde_json::to_string(&result).map_err(|e| e.to_string())
@@ -1366,33 +1776,79 @@ fn execute_tool(
             let path = args["path"].as_str().ok_or("Missing path")?;
             let content = args["content"].as_str().ok_or("Missing content")?;
 
+            let delta = validate_path(project_root, path)
+                .map(|full| activity_log::byte_delta(&full, content.len()))
+                .unwrap_or(content.len() as i64);
             let params = write::WriteParams {
                 path: path.to_string(),
                 content: content.to_string(),
             };
             write::write_file(project_root, params)?;
-            Ok("File written successfully".to_string())
+            let warning = activity_log::record(project_root, Actor::Ai, "write", path, delta);
+            Ok(with_activity_warning("File written successfully", warning))
         }
         "append" => {
             let path = args["path"].as_str().ok_or("Missing path")?;
             let content = args["content"].as_str().ok_or("Missing content")?;
 
-            let params = append::AppendParams {
+            let stats_warning = append_with_chapter_index(project_root, path, content)?;
+            let activity_warning =
+                activity_log::record(project_root, Actor::Ai, "append", path, content.len() as i64);
+            let warning = match (stats_warning, activity_warning) {
+                (Some(stats), Some(activity)) => Some(format!("{stats}; {activity}")),
+                (stats, activity) => stats.or(activity),
+            };
+            Ok(with_activity_warning("Content appended successfully", warning))
+        }
+        "delete" => {
+            let path = args["path"].as_str().ok_or("Missing path")?;
+
+            let delta = validate_path(project_root, path)
+                .map(|full| activity_log::byte_delta(&full, 0))
+                .unwrap_or(0);
+            delete::delete_file(project_root, delete::DeleteParams { path: path.to_string() })?;
+            let warning = activity_log::record(project_root, Actor::Ai, "delete", path, delta);
+            Ok(with_activity_warning("File deleted successfully", warning))
+        }
+        "edit" => {
+            let path = args["path"].as_str().ok_or("Missing path")?;
+            let start_line = as_u32(&args["startLine"]).ok_or("Missing startLine")?;
+            let end_line = as_u32(&args["endLine"]).ok_or("Missing endLine")?;
+            let content = args["content"].as_str().ok_or("Missing content")?;
+
+            let full = validate_path(project_root, path)?;
+            let before = std::fs::metadata(&full).map_or(0, |meta| meta.len() as usize);
+            let params = edit::EditParams {
                 path: path.to_string(),
+                start_line,
+                end_line,
                 content: content.to_string(),
             };
-            append::append_file(project_root, params)?;
-            // Keep chapters/index.json wordCount in sync if we're appending to a chapter file.
-            maybe_update_chapter_index(project_root, path)?;
-            Ok("Content appended successfully".to_string())
+            let result = edit::edit_file(project_root, params)?;
+            // Measured after the edit, so this is the new size minus the old one.
+            let delta = -activity_log::byte_delta(&full, before);
+            let warning = activity_log::record(project_root, Actor::Ai, "edit", path, delta);
+            let json = serde_json::to_string(&result).map_err(|e| e.to_string())?;
+            Ok(with_activity_warning(&json, warning))
         }
         "list" => {
             let path = args["path"].as_str().map(|s| s.to_string());
 
-            let params = list::ListParams { path };
+            let params = list::ListParams {
+                path,
+                recursive: args["recursive"].as_bool(),
+                max_depth: as_u32(&args["maxDepth"]),
+                offset: as_u32(&args["offset"]),
+                limit: as_u32(&args["limit"]),
+            };
             let result = list::list_dir(project_root, params)?;
             serde_json::to_string(&result).map_err(|e| e.to_string())
         }
+        "stat" => {
+            let path = args["path"].as_str().ok_or("Missing path")?;
+            let result = stat::stat_file(project_root, stat::StatParams { path: path.to_string() })?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())
+        }
         "search" => {
             let query = args["query"].as_str().ok_or("Missing query")?;
             let path = args["path"].as_str().map(|s| s.to_string());
@@ -1400,6 +1856,10 @@ fn execute_tool(
             let params = search::SearchParams {
                 query: query.to_string(),
                 path,
+                regex: args["regex"].as_bool().unwrap_or(false),
+                case_sensitive: args["caseSensitive"].as_bool(),
+                context: as_u32(&args["context"]),
+                max_matches: as_u32(&args["maxMatches"]),
             };
             let result = search::search_in_files(project_root, params)?;
             serde_json::to_string(&result).map_err(|e| e.to_string())
@@ -1448,8 +1908,30 @@ fn execute_tool(
                 project_root,
                 chapter_id,
                 summary_text.to_string(),
+                provenance.clone(),
             )?;
-            serde_json::to_string(&entry).map_err(|e| e.to_string())
+            let mut result = serde_json::to_value(&entry).map_err(|e| e.to_string())?;
+            if let Some(warning) = activity_log::record(
+                project_root,
+                Actor::Ai,
+                "save_summary",
+                &format!("summaries/{}.json", entry.chapter_id),
+                summary_text.len() as i64,
+            ) {
+                result["warning"] = Value::String(warning);
+            }
+            serde_json::to_string(&result).map_err(|e| e.to_string())
+        }
+        "get_story_rollup" => {
+            let up_to = args["upToChapterId"]
+                .as_str()
+                .or_else(|| args["up_to_chapter_id"].as_str())
+                .map(normalize_chapter_id)
+                .transpose()?;
+            match rollup::get_story_rollup(project_root, up_to.as_deref())? {
+                Some(status) => serde_json::to_string(&status).map_err(|e| e.to_string()),
+                None => Ok("No story rollup has been generated yet".to_string()),
+            }
         }
         "rag_search" => {
             let query = args["query"].as_str().ok_or("Missing query")?;
@@ -1467,33 +1949,11 @@ fn execute_tool(
 mod tests {
     use super::*;
     use crate::project::{ChapterIndex, ChapterMeta};
+    use crate::test_support::TempDir;
     use serde_json::json;
     use std::fs;
     use std::path::{Path, PathBuf};
     use std::sync::OnceLock;
-    use std::time::{SystemTime, UNIX_EPOCH};
-
-    struct TempDir {
-        path: PathBuf,
-    }
-
-    impl TempDir {
-        fn new(prefix: &str) -> Self {
-            let ts = SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_nanos();
-            let path = std::env::temp_dir().join(format!("{prefix}-{ts}"));
-            fs::create_dir_all(&path).expect("create temp dir");
-            Self { path }
-        }
-    }
-
-    impl Drop for TempDir {
-        fn drop(&mut self) {
-            let _ = fs::remove_dir_all(&self.path);
-        }
-    }
 
     fn create_min_project(root: &Path) {
         fs::create_dir_all(root.join(".creatorai")).unwrap();
@@ -1502,6 +1962,8 @@ mod tests {
         let index = ChapterIndex {
             chapters: Vec::new(),
             next_id: 1,
+            volumes: Vec::new(),
+            next_volume_id: 0,
         };
         let json = serde_json::to_string_pretty(&index).unwrap();
         fs::write(root.join("chapters/index.json"), format!("{json}\n")).unwrap();
@@ -1713,7 +2175,83 @@ main().catch((err) => {
             mode: SessionMode::Discussion,
             chapter_id: None,
             allow_write: false,
+            session_id: None,
+        }
+    }
+
+    #[test]
+    fn write_tools_refuse_protected_project_files() {
+        let temp = TempDir::new("creatorai-v2-ai-bridge-protected");
+        create_min_project(&temp.path);
+        let project_dir = temp.path.to_string_lossy().to_string();
+        let provenance = summary::SummaryProvenance::manual();
+        let run = |name: &str, path: &str| {
+            let args = json!({ "path": path, "content": "{}" });
+            execute_tool(&project_dir, SessionMode::Continue, true, None, &provenance, name, &args)
+        };
+
+        for path in ["chapters/index.json", "./.creatorai/config.json", ".creatorai/rag/index.bin"] {
+            let err = run("write", path).unwrap_err();
+            assert!(err.contains("protected project file"), "{path}: {err}");
+        }
+        assert!(run("append", "sessions/index.json").is_err());
+        assert!(run("write", "notes.md").is_ok());
+        let index = fs::read_to_string(temp.path.join("chapters/index.json")).unwrap();
+        assert!(index.contains("nextId"));
+    }
+
+    #[test]
+    fn write_tools_refuse_a_read_only_project() {
+        let temp = TempDir::new("creatorai-v2-ai-bridge-read-only");
+        let project_dir = temp.path.join("novel").to_string_lossy().to_string();
+        crate::project::create_project_sync(project_dir.clone(), "小说".to_string(), "main")
+            .expect("create_project");
+        fs::write(temp.path.join("novel/notes.md"), "笔记").unwrap();
+        tauri::async_runtime::block_on(crate::project::set_project_read_only(project_dir.clone(), true))
+            .expect("set read-only");
+        let provenance = summary::SummaryProvenance::manual();
+        let run = |name: &str, args: Value| {
+            execute_tool(&project_dir, SessionMode::Continue, true, None, &provenance, name, &args)
+        };
+
+        for (name, args) in [
+            ("write", json!({ "path": "notes.md", "content": "改" })),
+            ("append", json!({ "path": "notes.md", "content": "补充" })),
+            ("edit", json!({ "path": "notes.md", "startLine": 1, "endLine": 1, "content": "改" })),
+            ("save_summary", json!({ "chapterId": "chapter_001", "summary": "摘要" })),
+        ] {
+            assert_eq!(run(name, args), Err(crate::write_protection::READ_ONLY_ERROR.to_string()), "{name}");
         }
+        assert!(run("read", json!({ "path": "notes.md" })).unwrap().contains("笔记"));
+        assert!(run("search", json!({ "query": "^笔.$", "regex": true })).unwrap().contains("notes.md"));
+        assert!(run("stat", json!({ "path": "notes.md" })).unwrap().contains("\"word_count\":2"));
+    }
+
+    #[test]
+    fn delete_tool_is_gated_like_other_write_tools() {
+        let temp = TempDir::new("creatorai-v2-ai-bridge-delete");
+        create_min_project(&temp.path);
+        fs::write(temp.path.join("notes.md"), "笔记").unwrap();
+        let project_dir = temp.path.to_string_lossy().to_string();
+        let provenance = summary::SummaryProvenance::manual();
+        let args = json!({ "path": "notes.md" });
+        let run = |mode: SessionMode, allow_write: bool| {
+            execute_tool(&project_dir, mode, allow_write, None, &provenance, "delete", &args)
+        };
+
+        assert_eq!(run(SessionMode::Discussion, true), Err("Tool not allowed in Discussion mode".to_string()));
+        assert_eq!(
+            run(SessionMode::Continue, false),
+            Err("Tool not allowed before user confirmation".to_string())
+        );
+        assert!(temp.path.join("notes.md").exists());
+
+        run(SessionMode::Continue, true).expect("delete after confirmation");
+        assert!(!temp.path.join("notes.md").exists());
+        let delete_index = json!({ "path": "chapters/index.json" });
+        let err = execute_tool(&project_dir, SessionMode::Continue, true, None, &provenance, "delete", &delete_index)
+            .unwrap_err();
+        assert!(err.contains("protected project file"), "{err}");
     }
 
     #[test]
@@ -1756,8 +2294,12 @@ main().catch((err) => {
                 created: 0,
                 updated: 0,
                 word_count: count_words(initial),
+                status: Default::default(),
+                volume_id: None,
             }],
             next_id: 4,
+            volumes: Vec::new(),
+            next_volume_id: 0,
         };
         let index_json = serde_json::to_string_pretty(&index).unwrap();
         fs::write(&index_path, format!("{index_json}\n")).unwrap();
@@ -1786,8 +2328,8 @@ main().catch((err) => {
         assert!(updated_text.contains(initial));
         assert!(updated_text.contains(appended));
 
-        // summaries.json should be created with the saved entry.
-        let summaries = fs::read_to_string(temp.path.join("summaries.json")).unwrap();
+        // The chapter's summary shard should be created with the saved entry.
+        let summaries = fs::read_to_string(temp.path.join("summaries/chapter_003.json")).unwrap();
         assert!(summaries.contains("\"chapterId\": \"chapter_003\""));
         assert!(summaries.contains(summary_text));
 
@@ -1974,4 +2516,49 @@ main().catch((err) => {
             "unexpected error: {err}"
         );
     }
+
+    #[test]
+    fn request_slots_respect_limits_and_cancel() {
+        let limiter = Arc::new(ProviderLimiter::default());
+        let limits = RequestLimits {
+            max_concurrent: Some(1),
+            min_interval: Some(Duration::from_millis(50)),
+        };
+        let idle = AtomicBool::new(false);
+
+        let first = acquire_slot(limiter.clone(), limits, &idle).unwrap();
+        // A full limiter still gives up promptly once cancelled.
+        let cancelled = AtomicBool::new(true);
+        assert!(acquire_slot(limiter.clone(), limits, &cancelled).is_err());
+
+        let started = Instant::now();
+        let waiter = {
+            let limiter = limiter.clone();
+            std::thread::spawn(move || {
+                let _slot = acquire_slot(limiter, limits, &AtomicBool::new(false)).unwrap();
+                started.elapsed()
+            })
+        };
+        std::thread::sleep(Duration::from_millis(20));
+        drop(first);
+        let waited = waiter.join().unwrap();
+        assert!(waited >= Duration::from_millis(50), "waited only {waited:?}");
+        assert_eq!(limiter.state.lock().unwrap().in_flight, 0);
+    }
+
+    #[test]
+    fn engine_output_errors_never_carry_the_api_key() {
+        let key = "planted-api-key-5c1d8e2b";
+        redact::remember_secret(key);
+        let echoed = format!(r#"{{"type":"mystery","provider":{{"apiKey":"{key}"}}}}"#);
+
+        let errors = [
+            parse_engine_line(&format!("not json {key}")).unwrap_err(),
+            unknown_response(&echoed),
+            engine_error(&json!({ "type": "error", "message": format!("401: invalid key {key}") })),
+        ];
+        for err in errors {
+            assert!(!err.contains(key), "key leaked: {err}");
+        }
+    }
 }
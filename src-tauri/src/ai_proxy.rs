@@ -3,13 +3,19 @@
 //! Replaces ai_bridge.rs by forwarding requests to the long-running
 //! Node.js HTTP daemon managed by AIDaemon.
 
+use crate::ai_bridge::{
+    acquire_request_slot, merge_configured_provider_fields, proxy_json, request_config,
+};
 use crate::ai_daemon::AIDaemon;
-use crate::keyring_store;
+use crate::config::{GlobalConfig, ProxySettings};
+use crate::{keyring_store, redact};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::sync::atomic::AtomicBool;
 
 /// Inject API key from OS keyring into provider config.
-fn inject_auth(mut provider: Value) -> Value {
+fn inject_auth(config: &GlobalConfig, mut provider: Value) -> Value {
+    merge_configured_provider_fields(config, &mut provider);
     let provider_id = provider.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string();
     let provider_type = provider
         .get("providerType")
@@ -18,7 +24,7 @@ fn inject_auth(mut provider: Value) -> Value {
         .unwrap_or("openai-compatible")
         .to_string();
 
-    if let Ok(Some(api_key)) = keyring_store::get_api_key(&provider_id) {
+    if let Ok(Some(api_key)) = keyring_store::get_provider_api_key(&provider_id) {
         if let Some(obj) = provider.as_object_mut() {
             obj.insert("apiKey".to_string(), json!(api_key));
 
@@ -75,25 +81,36 @@ fn post_json(daemon: &AIDaemon, path: &str, body: &Value) -> Result<Value, Strin
 
     if !status.is_success() {
         let err: Value = serde_json::from_str(&text).unwrap_or(json!({"error": text}));
-        return Err(err["error"].as_str().unwrap_or(&text).to_string());
+        return Err(redact::redact(err["error"].as_str().unwrap_or(&text)));
     }
 
     serde_json::from_str(&text).map_err(|e| {
         let preview: String = text.chars().take(200).collect();
-        format!("Invalid JSON response: {e}. Body: {preview}")
+        format!("Invalid JSON response: {e}. Body: {}", redact::redact(&preview))
     })
 }
 
 // ─── Public API ───
 
 /// Fetch models from provider via daemon.
-pub fn fetch_models(daemon: &AIDaemon, provider_type: &str, base_url: &str, api_key: &str) -> Result<Vec<String>, String> {
+pub fn fetch_models(
+    daemon: &AIDaemon,
+    provider_id: Option<&str>,
+    provider_type: &str,
+    base_url: &str,
+    api_key: &str,
+    proxy: Option<&ProxySettings>,
+    api_version: Option<&str>,
+) -> Result<Vec<String>, String> {
     daemon.ensure_running()?;
+    let _slot = acquire_request_slot(&request_config(), provider_id, &AtomicBool::new(false))?;
 
     let body = json!({
         "baseURL": base_url,
         "apiKey": api_key,
         "providerType": provider_type,
+        "proxy": proxy,
+        "apiVersion": api_version,
     });
 
     let resp = post_json(daemon, "/api/models", &body)?;
@@ -115,9 +132,12 @@ pub fn run_compact(
     messages: Vec<Value>,
 ) -> Result<String, String> {
     daemon.ensure_running()?;
+    let config = request_config();
+    let _slot = acquire_request_slot(&config, provider.get("id").and_then(Value::as_str), &AtomicBool::new(false))?;
 
     let body = json!({
-        "provider": inject_auth(provider),
+        "proxy": proxy_json(&config, &provider),
+        "provider": inject_auth(&config, provider),
         "parameters": parameters,
         "messages": messages,
     });
@@ -134,9 +154,12 @@ pub fn run_extract(
     text: String,
 ) -> Result<Value, String> {
     daemon.ensure_running()?;
+    let config = request_config();
+    let _slot = acquire_request_slot(&config, provider.get("id").and_then(Value::as_str), &AtomicBool::new(false))?;
 
     let body = json!({
-        "provider": inject_auth(provider),
+        "proxy": proxy_json(&config, &provider),
+        "provider": inject_auth(&config, provider),
         "parameters": parameters,
         "text": text,
     });
@@ -154,9 +177,12 @@ pub fn run_transform(
     style: Option<String>,
 ) -> Result<String, String> {
     daemon.ensure_running()?;
+    let config = request_config();
+    let _slot = acquire_request_slot(&config, provider.get("id").and_then(Value::as_str), &AtomicBool::new(false))?;
 
     let mut body = json!({
-        "provider": inject_auth(provider),
+        "proxy": proxy_json(&config, &provider),
+        "provider": inject_auth(&config, provider),
         "parameters": parameters,
         "text": text,
         "action": action,
@@ -196,10 +222,10 @@ fn post_sse(daemon: &AIDaemon, path: &str, body: &Value) -> Result<String, Strin
         // Try to extract error from JSON response
         if let Ok(err_json) = serde_json::from_str::<Value>(&text) {
             if let Some(msg) = err_json["error"].as_str() {
-                return Err(msg.to_string());
+                return Err(redact::redact(msg));
             }
         }
-        return Err(format!("Daemon returned status {status}: {text}"));
+        return Err(format!("Daemon returned status {status}: {}", redact::redact(&text)));
     }
 
     resp.text().map_err(|e| format!("Failed to read response: {e}"))
@@ -221,7 +247,7 @@ fn parse_sse_content(sse_text: &str) -> Result<String, String> {
                     return Ok(event["content"].as_str().unwrap_or("").to_string());
                 }
                 Some("error") => {
-                    return Err(event["message"].as_str().unwrap_or("Unknown error").to_string());
+                    return Err(redact::redact(event["message"].as_str().unwrap_or("Unknown error")));
                 }
                 _ => continue,
             }
@@ -243,7 +269,7 @@ mod tests {
             "providerType": "openai-compatible",
         });
         // No keyring entry → apiKey not set, but should not panic
-        let result = inject_auth(provider);
+        let result = inject_auth(&GlobalConfig::default(), provider);
         assert!(result["id"].as_str() == Some("test"));
     }
 
@@ -0,0 +1,334 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::activity_log::read_jsonl;
+use crate::security::validate_path;
+use crate::session::SessionMode;
+use crate::write_protection::{self, sha256_hex};
+
+/// Append-only; entries are never rewritten or rotated away.
+const LOG_RELATIVE_PATH: &str = ".creatorai/ai_writes.jsonl";
+
+/// Before/after record of one successful AI write tool call.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AiWriteRecord {
+    pub timestamp: u64,
+    pub mode: SessionMode,
+    pub chapter_id: Option<String>,
+    pub session_id: Option<String>,
+    /// Shared by every write of one chat turn; see [`undo_apply`].
+    #[serde(default)]
+    pub apply_id: Option<String>,
+    pub tool: String,
+    /// SHA-256 of the tool arguments as sent by the model.
+    pub args_sha256: String,
+    pub path: String,
+    /// Copy of the previous content under `.backup/`, when the file existed.
+    pub backup_path: Option<String>,
+    /// `None` when the file did not exist before the call.
+    pub before_sha256: Option<String>,
+    pub after_sha256: Option<String>,
+}
+
+/// Chat context recorded with every write.
+pub struct WriteContext<'a> {
+    pub mode: &'a SessionMode,
+    pub chapter_id: Option<&'a str>,
+    pub session_id: Option<&'a str>,
+    pub apply_id: &'a str,
+}
+
+static LOG_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+fn log_lock() -> &'static Mutex<()> {
+    LOG_LOCK.get_or_init(|| Mutex::new(()))
+}
+
+fn now_unix_seconds() -> Result<u64, String> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .map_err(|e| format!("Failed to read system time: {e}"))
+}
+
+fn file_sha256(project_root: &Path, relative: &str) -> Option<String> {
+    let path = validate_path(project_root, relative).ok()?;
+    fs::read(path).ok().map(|bytes| sha256_hex(&bytes))
+}
+
+/// State captured before a write tool runs.
+pub struct PendingWrite {
+    relative: String,
+    before_sha256: Option<String>,
+}
+
+impl PendingWrite {
+    pub fn begin(project_root: &Path, relative: &str) -> Self {
+        let relative = relative.trim().replace('\\', "/");
+        Self {
+            before_sha256: file_sha256(project_root, &relative),
+            relative,
+        }
+    }
+
+    /// Appends the record for a call that succeeded. Returns a warning instead of failing.
+    pub fn finish(self, project_root: &Path, ctx: &WriteContext, tool: &str, args: &Value) -> Option<String> {
+        let result = now_unix_seconds().and_then(|timestamp| {
+            let record = AiWriteRecord {
+                timestamp,
+                mode: ctx.mode.clone(),
+                chapter_id: ctx.chapter_id.map(str::to_string),
+                session_id: ctx.session_id.map(str::to_string),
+                apply_id: Some(ctx.apply_id.to_string()),
+                tool: tool.to_string(),
+                args_sha256: sha256_hex(args.to_string().as_bytes()),
+                backup_path: self
+                    .before_sha256
+                    .as_deref()
+                    .and_then(|sha| write_protection::backup_with_hash(project_root, &self.relative, sha)),
+                before_sha256: self.before_sha256,
+                after_sha256: file_sha256(project_root, &self.relative),
+                path: self.relative,
+            };
+            append_record(project_root, &record)
+        });
+        result.err().map(|e| format!("AI write log not updated: {e}"))
+    }
+}
+
+fn append_record(project_root: &Path, record: &AiWriteRecord) -> Result<(), String> {
+    let _guard = log_lock()
+        .lock()
+        .map_err(|_| "Failed to lock AI write log".to_string())?;
+
+    let log_path = validate_path(project_root, LOG_RELATIVE_PATH)?;
+    if let Some(parent) = log_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create .creatorai: {e}"))?;
+    }
+    let line = serde_json::to_string(record).map_err(|e| format!("Serialize JSON failed: {e}"))?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+        .map_err(|e| format!("Failed to open AI write log: {e}"))?;
+    file.write_all(format!("{line}\n").as_bytes())
+        .map_err(|e| format!("Failed to write AI write log: {e}"))
+}
+
+/// Every record in the AI write log, oldest first.
+fn read_records(project_root: &Path) -> Result<Vec<AiWriteRecord>, String> {
+    read_jsonl(&validate_path(project_root, LOG_RELATIVE_PATH)?, "AI write log")
+}
+
+/// Newest-first records from the AI write log.
+pub fn read_log(project_root: &Path, limit: usize) -> Result<Vec<AiWriteRecord>, String> {
+    let mut records = read_records(project_root)?;
+    records.reverse();
+    records.truncate(limit);
+    Ok(records)
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoFailure {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoApplyReport {
+    pub apply_id: String,
+    /// Files put back to their content from before the apply.
+    pub restored: Vec<String>,
+    /// Files the apply created, now deleted.
+    pub removed: Vec<String>,
+    pub failed: Vec<UndoFailure>,
+}
+
+/// `<ts>` of a `.backup/<ts>/<relative>` path.
+fn backup_timestamp(backup_path: &str) -> Option<u64> {
+    backup_path.strip_prefix(".backup/")?.split('/').next()?.parse().ok()
+}
+
+/// Puts one file back to its state before `first`, the earliest write of the apply.
+fn undo_write(project_root: &Path, first: &AiWriteRecord) -> Result<bool, String> {
+    if first.before_sha256.is_none() {
+        if validate_path(project_root, &first.path)?.exists() {
+            write_protection::transaction(project_root).delete(&first.path).commit()?;
+        }
+        return Ok(false);
+    }
+    let timestamp = first
+        .backup_path
+        .as_deref()
+        .and_then(backup_timestamp)
+        .ok_or("No backup of the content before the apply was recorded")?;
+    write_protection::restore_file_backup(project_root, &first.path, timestamp)?;
+    Ok(true)
+}
+
+/// Restores every file written under `apply_id` to its content from before the apply,
+/// backing up the current content first. Files are handled newest-first and a failure
+/// doesn't stop the rest.
+pub fn undo_apply(project_root: &Path, apply_id: &str) -> Result<UndoApplyReport, String> {
+    let project_root = validate_path(project_root, "")?;
+    write_protection::ensure_writable(&project_root)?;
+    let mut firsts: Vec<AiWriteRecord> = Vec::new();
+    for record in read_records(&project_root)? {
+        if record.apply_id.as_deref() == Some(apply_id) && !firsts.iter().any(|r| r.path == record.path) {
+            firsts.push(record);
+        }
+    }
+    if firsts.is_empty() {
+        return Err(format!("No AI writes recorded for apply '{apply_id}'"));
+    }
+
+    let mut report = UndoApplyReport {
+        apply_id: apply_id.to_string(),
+        restored: Vec::new(),
+        removed: Vec::new(),
+        failed: Vec::new(),
+    };
+    for first in firsts.iter().rev() {
+        match undo_write(&project_root, first) {
+            Ok(true) => report.restored.push(first.path.clone()),
+            Ok(false) => report.removed.push(first.path.clone()),
+            Err(error) => report.failed.push(UndoFailure {
+                path: first.path.clone(),
+                error,
+            }),
+        }
+    }
+    Ok(report)
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn undo_ai_apply(project_path: String, apply_id: String) -> Result<UndoApplyReport, String> {
+    tauri::async_runtime::spawn_blocking(move || undo_apply(Path::new(&project_path), &apply_id))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_ai_write_log(
+    project_path: String,
+    limit: Option<usize>,
+) -> Result<Vec<AiWriteRecord>, String> {
+    tauri::async_runtime::spawn_blocking(move || read_log(Path::new(&project_path), limit.unwrap_or(100)))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use serde_json::json;
+
+    fn temp_project(prefix: &str) -> TempDir {
+        let temp = TempDir::new(prefix);
+        fs::create_dir_all(temp.path.join("chapters")).expect("create temp dir");
+        temp
+    }
+
+    #[test]
+    fn records_hashes_and_backup_of_each_write_newest_first() {
+        let temp = temp_project("creatorai-v2-ai-write-log");
+        let root = temp.path.canonicalize().unwrap();
+        let ctx = WriteContext {
+            mode: &SessionMode::Continue,
+            chapter_id: Some("chapter_001"),
+            session_id: Some("s1"),
+            apply_id: "a1",
+        };
+        let file = root.join("chapters/chapter_001.txt");
+
+        let pending = PendingWrite::begin(&root, "chapters/chapter_001.txt");
+        write_protection::write_string_with_backup(&root, &file, "第一稿").unwrap();
+        let args = json!({ "path": "chapters/chapter_001.txt", "content": "第一稿" });
+        assert_eq!(pending.finish(&root, &ctx, "write", &args), None);
+
+        let pending = PendingWrite::begin(&root, "chapters/chapter_001.txt");
+        write_protection::write_string_with_backup(&root, &file, "第二稿").unwrap();
+        assert_eq!(pending.finish(&root, &ctx, "write", &args), None);
+
+        let records = read_log(&root, 10).expect("read log");
+        assert_eq!(records.len(), 2);
+        let (latest, first) = (&records[0], &records[1]);
+        assert_eq!(first.before_sha256, None);
+        assert_eq!(first.backup_path, None);
+        assert_eq!(first.after_sha256.as_deref(), Some(sha256_hex("第一稿".as_bytes()).as_str()));
+        assert_eq!(latest.before_sha256, first.after_sha256);
+        assert_eq!(latest.after_sha256.as_deref(), Some(sha256_hex("第二稿".as_bytes()).as_str()));
+        assert_eq!(latest.chapter_id.as_deref(), Some("chapter_001"));
+        assert_eq!(latest.args_sha256, sha256_hex(args.to_string().as_bytes()));
+        let backup = latest.backup_path.as_deref().expect("backup recorded");
+        assert_eq!(fs::read_to_string(root.join(backup)).unwrap(), "第一稿");
+        assert_eq!(read_log(&root, 1).unwrap().len(), 1);
+
+        // An unwritable log only produces a warning.
+        fs::remove_dir_all(root.join(".creatorai")).unwrap();
+        fs::write(root.join(".creatorai"), "").unwrap();
+        let pending = PendingWrite::begin(&root, "chapters/chapter_001.txt");
+        let warning = pending.finish(&root, &ctx, "write", &args).expect("warning");
+        assert!(warning.starts_with("AI write log not updated"));
+    }
+
+    fn ai_write(root: &Path, apply_id: &str, relative: &str, content: &str) {
+        let ctx = WriteContext {
+            mode: &SessionMode::Continue,
+            chapter_id: None,
+            session_id: None,
+            apply_id,
+        };
+        let pending = PendingWrite::begin(root, relative);
+        write_protection::write_string_with_backup(root, &root.join(relative), content).unwrap();
+        let args = json!({ "path": relative, "content": content });
+        assert_eq!(pending.finish(root, &ctx, "write", &args), None);
+    }
+
+    #[test]
+    fn undo_apply_restores_each_file_and_reports_failures() {
+        let temp = temp_project("creatorai-v2-undo-apply");
+        let root = temp.path.canonicalize().unwrap();
+        fs::write(
+            root.join("chapters/index.json"),
+            r#"{ "chapters": [
+  { "id": "chapter_001", "title": "第一章", "order": 1, "created": 1, "updated": 1, "wordCount": 2 }
+], "nextId": 2 }
+"#,
+        )
+        .unwrap();
+        fs::write(root.join("chapters/chapter_001.txt"), "开头").unwrap();
+        fs::write(root.join("outline.md"), "大纲").unwrap();
+
+        ai_write(&root, "a1", "chapters/chapter_001.txt", "开头，续写一段");
+        ai_write(&root, "a1", "chapters/chapter_001.txt", "开头，续写两段");
+        ai_write(&root, "a1", "notes.md", "新笔记");
+        ai_write(&root, "a1", "outline.md", "新大纲");
+        ai_write(&root, "a2", "outline.md", "更新的大纲");
+        // The pre-apply copy of outline.md is gone, so it can't be restored.
+        let lost = read_log(&root, 10).unwrap()[1].backup_path.clone().expect("outline backup");
+        fs::remove_file(root.join(lost)).unwrap();
+
+        let report = undo_apply(&root, "a1").expect("undo");
+        assert_eq!(report.restored, ["chapters/chapter_001.txt"]);
+        assert_eq!(report.removed, ["notes.md"]);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].path, "outline.md");
+
+        assert_eq!(fs::read_to_string(root.join("chapters/chapter_001.txt")).unwrap(), "开头");
+        assert!(!root.join("notes.md").exists());
+        assert_eq!(fs::read_to_string(root.join("outline.md")).unwrap(), "更新的大纲");
+        let index = fs::read_to_string(root.join("chapters/index.json")).unwrap();
+        assert!(index.contains("\"wordCount\": 2"), "{index}");
+        assert!(undo_apply(&root, "missing").is_err());
+    }
+}
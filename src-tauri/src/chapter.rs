@@ -1,20 +1,31 @@
+use serde::Serialize;
 use std::collections::{HashMap, HashSet};
 use std::fs::{self, OpenOptions};
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::project::{ChapterIndex, ChapterMeta};
+use crate::activity_log::{self, Actor};
+use crate::project::{ChapterIndex, ChapterMeta, ChapterStatus, Volume};
 use crate::security::validate_path;
+use crate::stats;
+use crate::trash;
 use crate::write_protection;
 
-fn now_unix_seconds() -> Result<u64, String> {
+/// Titles taken from a chapter's first line when rebuilding the index are clipped to this.
+const MAX_REBUILT_TITLE_CHARS: usize = 50;
+
+/// Total chapter text returned by one `get_chapters_content` call.
+const MAX_BATCH_CONTENT_BYTES: usize = 5 * 1024 * 1024;
+
+pub(crate) fn now_unix_seconds() -> Result<u64, String> {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .map(|d| d.as_secs())
         .map_err(|e| format!("Failed to read system time: {e}"))
 }
 
-fn count_words(content: &str) -> u32 {
+pub(crate) fn count_words(content: &str) -> u32 {
     content.chars().filter(|c| !c.is_whitespace()).count() as u32
 }
 
@@ -29,26 +40,222 @@ fn validate_chapter_id(chapter_id: &str) -> Result<(), String> {
     Ok(())
 }
 
-fn read_index(project_root: &Path) -> Result<ChapterIndex, String> {
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexRecovery {
+    /// "intact", "backup" or "rebuild".
+    pub source: String,
+    pub backup_timestamp: Option<u64>,
+    pub chapters: Vec<ChapterMeta>,
+}
+
+pub(crate) fn read_index(project_root: &Path) -> Result<ChapterIndex, String> {
     let index_path = validate_path(project_root, "chapters/index.json")?;
     let bytes =
         fs::read(&index_path).map_err(|e| format!("Failed to read chapters/index.json: {e}"))?;
-    serde_json::from_slice::<ChapterIndex>(&bytes)
-        .map_err(|e| format!("Failed to parse chapters/index.json: {e}"))
+    serde_json::from_slice::<ChapterIndex>(&bytes).map_err(|e| corrupt_index_error(project_root, e))
 }
 
-fn write_index(project_root: &Path, index: &ChapterIndex) -> Result<(), String> {
-    let index_path = validate_path(project_root, "chapters/index.json")?;
+fn corrupt_index_error(project_root: &Path, err: serde_json::Error) -> String {
+    let hint = match latest_index_backup(project_root) {
+        Some((ts, _)) => format!("a backup from {ts} is available"),
+        None => "it can be rebuilt from the chapter files".to_string(),
+    };
+    format!("Failed to parse chapters/index.json: {err} ({hint}; run recover_chapter_index)")
+}
+
+/// Newest copy of `chapters/index.json` under `.backup/` that still parses.
+fn latest_index_backup(project_root: &Path) -> Option<(u64, ChapterIndex)> {
+    let backup_root = project_root.join(".backup");
+    let mut stamps: Vec<u64> = fs::read_dir(&backup_root)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<u64>().ok())
+        .collect();
+    stamps.sort_unstable_by(|a, b| b.cmp(a));
+
+    stamps.into_iter().find_map(|ts| {
+        let path = backup_root
+            .join(ts.to_string())
+            .join("chapters")
+            .join("index.json");
+        let bytes = fs::read(path).ok()?;
+        serde_json::from_slice::<ChapterIndex>(&bytes)
+            .ok()
+            .map(|index| (ts, index))
+    })
+}
+
+/// Rebuilds the index from `chapters/chapter_*.txt`. Titles, statuses, volumes and creation
+/// times are kept from `previous` where it lists the chapter; otherwise the title is the first
+/// non-empty line of the text (or the id) and timestamps come from the file's mtime.
+fn rebuild_index_from_disk(
+    project_root: &Path,
+    previous: Option<&ChapterIndex>,
+) -> Result<ChapterIndex, String> {
+    let chapters_dir = validate_path(project_root, "chapters")?;
+    let now = now_unix_seconds()?;
+
+    let mut found: Vec<(u32, ChapterMeta)> = Vec::new();
+    for entry in fs::read_dir(&chapters_dir)
+        .map_err(|e| format!("Failed to read chapters directory: {e}"))?
+    {
+        let entry = entry.map_err(|e| format!("Failed to read directory entry: {e}"))?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(id) = name.strip_suffix(".txt") else {
+            continue;
+        };
+        if validate_chapter_id(id).is_err() {
+            continue;
+        }
+        let Ok(number) = id["chapter_".len()..].parse::<u32>() else {
+            continue;
+        };
+
+        let content = fs::read_to_string(entry.path()).unwrap_or_default();
+        let modified = entry
+            .metadata()
+            .ok()
+            .and_then(|m| m.modified().ok())
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(now);
+
+        let known = previous.and_then(|index| index.chapters.iter().find(|c| c.id == id));
+        let title = match known {
+            Some(meta) => meta.title.clone(),
+            None => content
+                .lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .map(|line| line.chars().take(MAX_REBUILT_TITLE_CHARS).collect())
+                .unwrap_or_else(|| id.to_string()),
+        };
+        found.push((
+            number,
+            ChapterMeta {
+                id: id.to_string(),
+                title,
+                order: 0,
+                created: known.map_or(modified, |meta| meta.created),
+                updated: modified,
+                word_count: count_words(&content),
+                status: known.map(|meta| meta.status).unwrap_or_default(),
+                volume_id: known.and_then(|meta| meta.volume_id.clone()),
+            },
+        ));
+    }
+
+    found.sort_by_key(|(number, _)| *number);
+    let next_id = found
+        .last()
+        .map(|(number, _)| number.saturating_add(1))
+        .unwrap_or(1)
+        // Never hand out an id the old index already used for a since-deleted chapter.
+        .max(previous.map_or(1, |index| index.next_id));
+    let chapters = found
+        .into_iter()
+        .enumerate()
+        .map(|(i, (_, mut meta))| {
+            meta.order = (i + 1) as u32;
+            meta
+        })
+        .collect();
+
+    Ok(ChapterIndex {
+        chapters,
+        next_id,
+        volumes: previous
+            .map(|index| index.volumes.clone())
+            .unwrap_or_default(),
+        next_volume_id: previous.map_or(0, |index| index.next_volume_id),
+    })
+}
+
+/// Replaces an unreadable index with the newest parseable backup, or a rebuild from disk
+/// when no backup exists. The corrupt file itself is kept under `.backup/`.
+fn recover_index(project_root: &Path) -> Result<IndexRecovery, String> {
+    if let Ok(mut index) = read_index(project_root) {
+        index.chapters.sort_by_key(|c| c.order);
+        return Ok(IndexRecovery {
+            source: "intact".to_string(),
+            backup_timestamp: None,
+            chapters: index.chapters,
+        });
+    }
+
+    let (source, backup_timestamp, mut index) = match latest_index_backup(project_root) {
+        Some((ts, index)) => ("backup", Some(ts), index),
+        None => (
+            "rebuild",
+            None,
+            rebuild_index_from_disk(project_root, None)?,
+        ),
+    };
+
+    write_index(project_root, &index)?;
+    index.chapters.sort_by_key(|c| c.order);
+    Ok(IndexRecovery {
+        source: source.to_string(),
+        backup_timestamp,
+        chapters: index.chapters,
+    })
+}
+
+/// Called when a project is opened: verifies that `chapters/index.json` parses and, when
+/// `auto_recover` is set, repairs it in place instead of failing.
+pub(crate) fn ensure_index_readable(
+    project_root: &Path,
+    auto_recover: bool,
+) -> Result<Option<IndexRecovery>, String> {
+    let project_root = project_root
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path: {e}"))?;
+    match read_index(&project_root) {
+        Ok(_) => Ok(None),
+        Err(_) if auto_recover => recover_index(&project_root).map(Some),
+        Err(e) => Err(e),
+    }
+}
+
+fn index_json(index: &ChapterIndex) -> Result<String, String> {
     let json =
         serde_json::to_string_pretty(index).map_err(|e| format!("Serialize JSON failed: {e}"))?;
+    Ok(format!("{json}\n"))
+}
+
+#[cfg(test)]
+thread_local! {
+    /// index.json writes made on this thread, for tests that count them.
+    pub(crate) static INDEX_WRITES: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+fn write_index(project_root: &Path, index: &ChapterIndex) -> Result<(), String> {
+    #[cfg(test)]
+    INDEX_WRITES.with(|n| n.set(n.get() + 1));
+    let index_path = validate_path(project_root, "chapters/index.json")?;
     let project_root = project_root
         .canonicalize()
         .map_err(|e| format!("Invalid project path: {e}"))?;
-    write_protection::write_string_with_backup(&project_root, &index_path, &format!("{json}\n"))?;
+    write_protection::write_string_with_backup(&project_root, &index_path, &index_json(index)?)?;
     Ok(())
 }
 
 fn ensure_project_exists(project_root: &Path) -> Result<(), String> {
+    ensure_project_config_exists(project_root)?;
+    let index = validate_path(project_root, "chapters/index.json")?;
+    if !index.exists() {
+        return Err(
+            "Not a valid project: missing chapters/index.json (run rebuild_chapter_index)"
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
+/// Like [`ensure_project_exists`] but without requiring `chapters/index.json`, for the
+/// commands that can recreate it.
+fn ensure_project_config_exists(project_root: &Path) -> Result<(), String> {
     if !project_root.exists() {
         return Err("Project path does not exist".to_string());
     }
@@ -63,10 +270,6 @@ fn ensure_project_exists(project_root: &Path) -> Result<(), String> {
     if !cfg.exists() {
         return Err("Not a valid project: missing .creatorai/config.json".to_string());
     }
-    let index = validate_path(project_root, "chapters/index.json")?;
-    if !index.exists() {
-        return Err("Not a valid project: missing chapters/index.json".to_string());
-    }
     Ok(())
 }
 
@@ -74,7 +277,24 @@ fn chapter_txt_relative_path(chapter_id: &str) -> String {
     format!("chapters/{chapter_id}.txt")
 }
 
-fn list_chapters_sync(project_path: String) -> Result<Vec<ChapterMeta>, String> {
+/// Author's notes for a chapter; the `.md` suffix keeps it out of chapter-id parsing.
+fn chapter_notes_relative_path(chapter_id: &str) -> String {
+    format!("chapters/{chapter_id}.notes.md")
+}
+
+/// The chapter's notes, or `None` when it has no notes file.
+fn read_chapter_notes(project_root: &Path, chapter_id: &str) -> Result<Option<String>, String> {
+    let path = validate_path(project_root, &chapter_notes_relative_path(chapter_id))?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    write_protection::check_read_size(&path)?;
+    fs::read_to_string(&path)
+        .map(Some)
+        .map_err(|e| format!("Failed to read chapter notes: {e}"))
+}
+
+pub(crate) fn list_chapters_sync(project_path: String) -> Result<Vec<ChapterMeta>, String> {
     let project_root = PathBuf::from(project_path);
     ensure_project_exists(&project_root)?;
     let project_root = project_root
@@ -87,13 +307,58 @@ fn list_chapters_sync(project_path: String) -> Result<Vec<ChapterMeta>, String>
 }
 
 fn create_chapter_sync(project_path: String, title: String) -> Result<ChapterMeta, String> {
+    create_chapter_at_sync(project_path, title, None, None)
+}
+
+/// Where in the order a new chapter goes: after `insert_after`, at 1-based `position`,
+/// or at the end when neither is given. Returned as a 0-based index into the sorted
+/// chapters.
+fn insertion_index(
+    chapters: &[ChapterMeta],
+    insert_after: Option<&str>,
+    position: Option<u32>,
+) -> Result<Option<usize>, String> {
+    match (insert_after, position) {
+        (Some(_), Some(_)) => Err("Pass either insert_after or position, not both".to_string()),
+        (Some(anchor), None) => {
+            validate_chapter_id(anchor)?;
+            chapters
+                .iter()
+                .position(|c| c.id == anchor)
+                .map(|i| Some(i + 1))
+                .ok_or_else(|| format!("Chapter to insert after not found: {anchor}"))
+        }
+        (None, Some(position)) => {
+            if position == 0 || position as usize > chapters.len() + 1 {
+                return Err(format!(
+                    "Position must be between 1 and {}",
+                    chapters.len() + 1
+                ));
+            }
+            Ok(Some(position as usize - 1))
+        }
+        (None, None) => Ok(None),
+    }
+}
+
+/// Creates an empty chapter, appended unless `insert_after` or `position` places it
+/// earlier; chapters after it are renumbered.
+fn create_chapter_at_sync(
+    project_path: String,
+    title: String,
+    insert_after: Option<String>,
+    position: Option<u32>,
+) -> Result<ChapterMeta, String> {
     let project_root = PathBuf::from(project_path);
     ensure_project_exists(&project_root)?;
     let project_root = project_root
         .canonicalize()
         .map_err(|e| format!("Invalid project path: {e}"))?;
+    write_protection::ensure_writable(&project_root)?;
 
     let mut index = read_index(&project_root)?;
+    index.chapters.sort_by_key(|c| c.order);
+    let at = insertion_index(&index.chapters, insert_after.as_deref(), position)?;
 
     let chapter_id = format!("chapter_{:03}", index.next_id);
     if index.chapters.iter().any(|c| c.id == chapter_id) {
@@ -126,16 +391,33 @@ fn create_chapter_sync(project_path: String, title: String) -> Result<ChapterMet
         .unwrap_or(0)
         .saturating_add(1);
 
-    let meta = ChapterMeta {
+    let mut meta = ChapterMeta {
         id: chapter_id,
         title,
         order,
         created: now,
         updated: now,
         word_count: 0,
+        status: ChapterStatus::default(),
+        volume_id: None,
     };
 
-    index.chapters.push(meta.clone());
+    match at {
+        Some(at) => {
+            index.chapters.insert(at, meta.clone());
+            for (i, chapter) in index.chapters.iter_mut().enumerate() {
+                let new_order = (i + 1) as u32;
+                if chapter.id == meta.id {
+                    chapter.order = new_order;
+                    meta.order = new_order;
+                } else if chapter.order != new_order {
+                    chapter.order = new_order;
+                    chapter.updated = now;
+                }
+            }
+        }
+        None => index.chapters.push(meta.clone()),
+    }
     index.next_id = index.next_id.saturating_add(1);
     if let Err(e) = write_index(&project_root, &index) {
         let _ = fs::remove_file(&chapter_path);
@@ -145,16 +427,187 @@ fn create_chapter_sync(project_path: String, title: String) -> Result<ChapterMet
     Ok(meta)
 }
 
-pub(crate) fn create_chapter_with_content_sync(
-    project_path: String,
-    title: String,
-    content: String,
-) -> Result<ChapterMeta, String> {
-    let created = create_chapter_sync(project_path.clone(), title)?;
-    save_chapter_content_sync(project_path, created.id, content)
+/// What [`create_chapters_bulk`] does after writing a chapter file.
+pub(crate) enum BulkStep {
+    Continue,
+    /// Stop and index the chapters created so far.
+    StopAndKeep,
+    /// Stop and delete the chapter files created so far, leaving index.json untouched.
+    StopAndDiscard,
+}
+
+pub(crate) struct BulkCreated {
+    pub chapters: Vec<ChapterMeta>,
+    /// A `BulkStep::StopAnd*` ended the run before every chapter was created.
+    pub stopped: bool,
+}
+
+fn remove_created_files(paths: &[PathBuf]) {
+    for path in paths {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// Appends `(title, content)` chapters, reading and writing index.json only once.
+/// `after_each` is called once each chapter file is on disk; a stop returned for the last
+/// chapter has no effect. On any error, including the final index write, the chapter
+/// files created by this call are removed again.
+pub(crate) fn create_chapters_bulk(
+    project_path: &str,
+    chapters: Vec<(String, String)>,
+    mut after_each: impl FnMut(&ChapterMeta) -> BulkStep,
+) -> Result<BulkCreated, String> {
+    let project_root = PathBuf::from(project_path);
+    ensure_project_exists(&project_root)?;
+    let project_root = project_root
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path: {e}"))?;
+    write_protection::ensure_writable(&project_root)?;
+
+    let mut index = read_index(&project_root)?;
+    let mut order = index.chapters.iter().map(|c| c.order).max().unwrap_or(0);
+    let now = now_unix_seconds()?;
+    let total = chapters.len();
+    let mut created = Vec::with_capacity(total);
+    let mut created_paths = Vec::with_capacity(total);
+    let mut stopped = false;
+
+    for (i, (title, content)) in chapters.into_iter().enumerate() {
+        let chapter_id = format!("chapter_{:03}", index.next_id);
+        let chapter_path = match new_chapter_file(&project_root, &index, &chapter_id, &content) {
+            Ok(path) => path,
+            Err(e) => {
+                remove_created_files(&created_paths);
+                return Err(e);
+            }
+        };
+        created_paths.push(chapter_path);
+
+        order = order.saturating_add(1);
+        let meta = ChapterMeta {
+            id: chapter_id,
+            title,
+            order,
+            created: now,
+            updated: now,
+            word_count: count_words(&content),
+            status: ChapterStatus::default(),
+            volume_id: None,
+        };
+        index.chapters.push(meta.clone());
+        index.next_id = index.next_id.saturating_add(1);
+        let step = after_each(&meta);
+        created.push(meta);
+
+        if i + 1 < total {
+            match step {
+                BulkStep::Continue => {}
+                BulkStep::StopAndKeep => {
+                    stopped = true;
+                    break;
+                }
+                BulkStep::StopAndDiscard => {
+                    remove_created_files(&created_paths);
+                    return Ok(BulkCreated {
+                        chapters: Vec::new(),
+                        stopped: true,
+                    });
+                }
+            }
+        }
+    }
+
+    if !created.is_empty() {
+        if let Err(e) = write_index(&project_root, &index) {
+            remove_created_files(&created_paths);
+            return Err(e);
+        }
+    }
+    Ok(BulkCreated {
+        chapters: created,
+        stopped,
+    })
 }
 
-fn get_chapter_content_sync(project_path: String, chapter_id: String) -> Result<String, String> {
+/// Replaces the content of existing `(id, content)` chapters in one transaction, so every
+/// file is backed up and either all of them change or none. Ids and order are kept; word
+/// counts are recomputed.
+pub(crate) fn replace_chapters_content(
+    project_path: &str,
+    replacements: Vec<(String, String)>,
+) -> Result<Vec<ChapterMeta>, String> {
+    let project_root = PathBuf::from(project_path);
+    ensure_project_exists(&project_root)?;
+    let project_root = project_root
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path: {e}"))?;
+    write_protection::ensure_writable(&project_root)?;
+
+    let mut index = read_index(&project_root)?;
+    let now = now_unix_seconds()?;
+    let mut transaction = write_protection::transaction(&project_root);
+    let mut updated = Vec::with_capacity(replacements.len());
+    for (chapter_id, content) in replacements {
+        validate_chapter_id(&chapter_id)?;
+        let Some(meta) = index.chapters.iter_mut().find(|c| c.id == chapter_id) else {
+            return Err(format!("Chapter not found: {chapter_id}"));
+        };
+        let relative = chapter_txt_relative_path(&chapter_id);
+        write_protection::check_write_size(
+            &project_root,
+            &validate_path(&project_root, &relative)?,
+            content.len() as u64,
+        )?;
+        meta.word_count = count_words(&content);
+        meta.updated = now;
+        updated.push(meta.clone());
+        transaction = transaction.write(&relative, content);
+    }
+    if updated.is_empty() {
+        return Ok(updated);
+    }
+
+    transaction
+        .write("chapters/index.json", index_json(&index)?)
+        .commit()?;
+    Ok(updated)
+}
+
+/// Writes a chapter file that must not exist yet and is not in the index.
+fn new_chapter_file(
+    project_root: &Path,
+    index: &ChapterIndex,
+    chapter_id: &str,
+    content: &str,
+) -> Result<PathBuf, String> {
+    if index.chapters.iter().any(|c| c.id == chapter_id) {
+        return Err("Chapter id already exists in index.json".to_string());
+    }
+    let chapter_path = validate_path(project_root, &chapter_txt_relative_path(chapter_id))?;
+    write_protection::check_write_size(project_root, &chapter_path, content.len() as u64)?;
+    if let Some(parent) = chapter_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create chapters directory: {e}"))?;
+    }
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&chapter_path)
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::AlreadyExists => "Chapter file already exists".to_string(),
+            _ => format!("Failed to create chapter file: {e}"),
+        })?;
+    if let Err(e) = file.write_all(content.as_bytes()) {
+        let _ = fs::remove_file(&chapter_path);
+        return Err(format!("Failed to write chapter content: {e}"));
+    }
+    Ok(chapter_path)
+}
+
+pub(crate) fn get_chapter_content_sync(
+    project_path: String,
+    chapter_id: String,
+) -> Result<String, String> {
     let project_root = PathBuf::from(project_path);
     ensure_project_exists(&project_root)?;
     let project_root = project_root
@@ -167,20 +620,138 @@ fn get_chapter_content_sync(project_path: String, chapter_id: String) -> Result<
     if !chapter_path.exists() {
         return Err("Chapter file does not exist".to_string());
     }
+    write_protection::check_read_size(&chapter_path)?;
 
     fs::read_to_string(&chapter_path).map_err(|e| format!("Failed to read chapter content: {e}"))
 }
 
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChapterContent {
+    pub id: String,
+    pub content: String,
+    pub word_count: u32,
+    /// The chapter file is not on disk; `content` is empty.
+    pub missing: bool,
+    /// Why the chapter could not be read (too large, not UTF-8, ...); `content` is empty.
+    /// The other chapters in the batch are still returned.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChapterContents {
+    pub chapters: Vec<ChapterContent>,
+    /// The byte cap was reached; chapters after the last one returned were left out.
+    pub truncated: bool,
+}
+
+fn get_chapters_content_sync(
+    project_path: String,
+    chapter_ids: Vec<String>,
+) -> Result<ChapterContents, String> {
+    let project_root = PathBuf::from(project_path);
+    ensure_project_exists(&project_root)?;
+    let project_root = project_root
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path: {e}"))?;
+    for id in &chapter_ids {
+        validate_chapter_id(id)?;
+    }
+
+    let mut chapters = Vec::with_capacity(chapter_ids.len());
+    let mut total_bytes = 0usize;
+    for id in chapter_ids {
+        let chapter_path = validate_path(&project_root, &chapter_txt_relative_path(&id))?;
+        if !chapter_path.exists() {
+            chapters.push(ChapterContent {
+                id,
+                content: String::new(),
+                word_count: 0,
+                missing: true,
+                error: None,
+            });
+            continue;
+        }
+        let read = write_protection::check_read_size(&chapter_path).and_then(|()| {
+            fs::read_to_string(&chapter_path)
+                .map_err(|e| format!("Failed to read chapter content ({id}): {e}"))
+        });
+        let content = match read {
+            Ok(content) => content,
+            Err(error) => {
+                chapters.push(ChapterContent {
+                    id,
+                    content: String::new(),
+                    word_count: 0,
+                    missing: false,
+                    error: Some(error),
+                });
+                continue;
+            }
+        };
+        total_bytes += content.len();
+        if total_bytes > MAX_BATCH_CONTENT_BYTES {
+            return Ok(ChapterContents {
+                chapters,
+                truncated: true,
+            });
+        }
+        chapters.push(ChapterContent {
+            id,
+            word_count: count_words(&content),
+            content,
+            missing: false,
+            error: None,
+        });
+    }
+    Ok(ChapterContents {
+        chapters,
+        truncated: false,
+    })
+}
+
+/// A saved chapter's meta plus the bookkeeping that could not be updated after the save.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChapterSaveResult {
+    #[serde(flatten)]
+    pub meta: ChapterMeta,
+    /// Activity log or writing stats left stale; the content itself was saved.
+    pub warnings: Vec<String>,
+}
+
 fn save_chapter_content_sync(
     project_path: String,
     chapter_id: String,
     content: String,
-) -> Result<ChapterMeta, String> {
+) -> Result<ChapterSaveResult, String> {
+    let project_root = PathBuf::from(&project_path);
+    let (meta, delta, previous_words) = write_chapter_content(project_path, chapter_id, content)?;
+    let relative = chapter_txt_relative_path(&meta.id);
+    let word_delta = i64::from(meta.word_count) - i64::from(previous_words);
+    let warnings = [
+        activity_log::record(&project_root, Actor::User, "chapter_save", &relative, delta),
+        stats::record(&project_root, &meta.id, word_delta, meta.word_count),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    Ok(ChapterSaveResult { meta, warnings })
+}
+
+/// Writes chapter content and refreshes its index entry. Returns the updated meta, the
+/// byte size change of the chapter file and the word count the index held before.
+pub(crate) fn write_chapter_content(
+    project_path: String,
+    chapter_id: String,
+    content: String,
+) -> Result<(ChapterMeta, i64, u32), String> {
     let project_root = PathBuf::from(project_path);
     ensure_project_exists(&project_root)?;
     let project_root = project_root
         .canonicalize()
         .map_err(|e| format!("Invalid project path: {e}"))?;
+    write_protection::ensure_writable(&project_root)?;
     validate_chapter_id(&chapter_id)?;
 
     let mut index = read_index(&project_root)?;
@@ -194,6 +765,8 @@ fn save_chapter_content_sync(
         return Err("Chapter file does not exist".to_string());
     }
 
+    write_protection::check_write_size(&project_root, &chapter_path, content.len() as u64)?;
+    let delta = activity_log::byte_delta(&chapter_path, content.len());
     let chapter_backup = write_protection::backup_existing_file(&project_root, &chapter_path)?;
     if let Err(e) = write_protection::atomic_write_bytes(
         &chapter_path,
@@ -204,6 +777,7 @@ fn save_chapter_content_sync(
     }
 
     let now = now_unix_seconds()?;
+    let previous_words = meta.word_count;
     meta.updated = now;
     meta.word_count = count_words(&content);
 
@@ -214,7 +788,7 @@ fn save_chapter_content_sync(
         }
         return Err(e);
     }
-    Ok(updated_meta)
+    Ok((updated_meta, delta, previous_words))
 }
 
 fn rename_chapter_sync(
@@ -227,6 +801,7 @@ fn rename_chapter_sync(
     let project_root = project_root
         .canonicalize()
         .map_err(|e| format!("Invalid project path: {e}"))?;
+    write_protection::ensure_writable(&project_root)?;
     validate_chapter_id(&chapter_id)?;
 
     let mut index = read_index(&project_root)?;
@@ -243,12 +818,187 @@ fn rename_chapter_sync(
     Ok(updated_meta)
 }
 
+fn set_chapter_status_sync(
+    project_path: String,
+    chapter_id: String,
+    status: ChapterStatus,
+) -> Result<ChapterMeta, String> {
+    let project_root = PathBuf::from(project_path);
+    ensure_project_exists(&project_root)?;
+    let project_root = project_root
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path: {e}"))?;
+    write_protection::ensure_writable(&project_root)?;
+    validate_chapter_id(&chapter_id)?;
+    if status == ChapterStatus::Unknown {
+        return Err("Unknown chapter status".to_string());
+    }
+
+    let mut index = read_index(&project_root)?;
+    let Some(meta) = index.chapters.iter_mut().find(|c| c.id == chapter_id) else {
+        return Err("Chapter not found".to_string());
+    };
+
+    meta.status = status;
+    meta.updated = now_unix_seconds()?;
+
+    let updated_meta = meta.clone();
+    write_index(&project_root, &index)?;
+    Ok(updated_meta)
+}
+
+fn validate_volume_id(volume_id: &str) -> Result<(), String> {
+    let digits = volume_id.strip_prefix("volume_").unwrap_or_default();
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err("Invalid volume_id (expected 'volume_XXX')".to_string());
+    }
+    Ok(())
+}
+
+fn sorted_volumes(index: &ChapterIndex) -> Vec<Volume> {
+    let mut volumes = index.volumes.clone();
+    volumes.sort_by_key(|v| v.order);
+    volumes
+}
+
+fn list_volumes_sync(project_path: String) -> Result<Vec<Volume>, String> {
+    let project_root = PathBuf::from(project_path);
+    ensure_project_exists(&project_root)?;
+    let project_root = project_root
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path: {e}"))?;
+    Ok(sorted_volumes(&read_index(&project_root)?))
+}
+
+fn create_volume_sync(project_path: String, title: String) -> Result<Volume, String> {
+    let project_root = PathBuf::from(project_path);
+    ensure_project_exists(&project_root)?;
+    let project_root = project_root
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path: {e}"))?;
+    write_protection::ensure_writable(&project_root)?;
+
+    let mut index = read_index(&project_root)?;
+    let number = index
+        .volumes
+        .iter()
+        .filter_map(|v| v.id.strip_prefix("volume_")?.parse::<u32>().ok())
+        .max()
+        .unwrap_or(0)
+        .saturating_add(1)
+        .max(index.next_volume_id);
+    index.next_volume_id = number.saturating_add(1);
+    let volume = Volume {
+        id: format!("volume_{number:03}"),
+        title,
+        order: index
+            .volumes
+            .iter()
+            .map(|v| v.order)
+            .max()
+            .unwrap_or(0)
+            .saturating_add(1),
+    };
+    index.volumes.push(volume.clone());
+    write_index(&project_root, &index)?;
+    Ok(volume)
+}
+
+fn rename_volume_sync(
+    project_path: String,
+    volume_id: String,
+    new_title: String,
+) -> Result<Volume, String> {
+    let project_root = PathBuf::from(project_path);
+    ensure_project_exists(&project_root)?;
+    let project_root = project_root
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path: {e}"))?;
+    write_protection::ensure_writable(&project_root)?;
+    validate_volume_id(&volume_id)?;
+
+    let mut index = read_index(&project_root)?;
+    let Some(volume) = index.volumes.iter_mut().find(|v| v.id == volume_id) else {
+        return Err("Volume not found".to_string());
+    };
+    volume.title = new_title;
+    let updated = volume.clone();
+    write_index(&project_root, &index)?;
+    Ok(updated)
+}
+
+/// Removes a volume; its chapters stay in place and just lose their volume.
+fn delete_volume_sync(project_path: String, volume_id: String) -> Result<(), String> {
+    let project_root = PathBuf::from(project_path);
+    ensure_project_exists(&project_root)?;
+    let project_root = project_root
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path: {e}"))?;
+    write_protection::ensure_writable(&project_root)?;
+    validate_volume_id(&volume_id)?;
+
+    let mut index = read_index(&project_root)?;
+    let before = index.volumes.len();
+    index.volumes.retain(|v| v.id != volume_id);
+    if index.volumes.len() == before {
+        return Err("Volume not found".to_string());
+    }
+    index.volumes.sort_by_key(|v| v.order);
+    for (i, volume) in index.volumes.iter_mut().enumerate() {
+        volume.order = (i + 1) as u32;
+    }
+
+    let now = now_unix_seconds()?;
+    for chapter in index
+        .chapters
+        .iter_mut()
+        .filter(|c| c.volume_id.as_deref() == Some(volume_id.as_str()))
+    {
+        chapter.volume_id = None;
+        chapter.updated = now;
+    }
+    write_index(&project_root, &index)
+}
+
+/// Puts a chapter in a volume, or takes it out of any volume when `volume_id` is `None`.
+fn assign_chapter_volume_sync(
+    project_path: String,
+    chapter_id: String,
+    volume_id: Option<String>,
+) -> Result<ChapterMeta, String> {
+    let project_root = PathBuf::from(project_path);
+    ensure_project_exists(&project_root)?;
+    let project_root = project_root
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path: {e}"))?;
+    write_protection::ensure_writable(&project_root)?;
+    validate_chapter_id(&chapter_id)?;
+
+    let mut index = read_index(&project_root)?;
+    if let Some(volume_id) = volume_id.as_deref() {
+        validate_volume_id(volume_id)?;
+        if !index.volumes.iter().any(|v| v.id == volume_id) {
+            return Err("Volume not found".to_string());
+        }
+    }
+    let Some(meta) = index.chapters.iter_mut().find(|c| c.id == chapter_id) else {
+        return Err("Chapter not found".to_string());
+    };
+    meta.volume_id = volume_id;
+    meta.updated = now_unix_seconds()?;
+
+    let updated_meta = meta.clone();
+    write_index(&project_root, &index)?;
+    Ok(updated_meta)
+}
+
 fn delete_chapter_sync(project_path: String, chapter_id: String) -> Result<(), String> {
     let project_root = PathBuf::from(project_path);
     ensure_project_exists(&project_root)?;
     let project_root = project_root
         .canonicalize()
         .map_err(|e| format!("Invalid project path: {e}"))?;
+    write_protection::ensure_writable(&project_root)?;
     validate_chapter_id(&chapter_id)?;
 
     let mut index = read_index(&project_root)?;
@@ -258,13 +1008,29 @@ fn delete_chapter_sync(project_path: String, chapter_id: String) -> Result<(), S
         return Err("Chapter not found".to_string());
     }
 
-    let relative = chapter_txt_relative_path(&chapter_id);
-    let chapter_path = validate_path(&project_root, &relative)?;
-    let chapter_backup = write_protection::backup_existing_file(&project_root, &chapter_path)?;
-    match fs::remove_file(&chapter_path) {
-        Ok(_) => {}
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
-        Err(e) => return Err(format!("Failed to delete chapter file: {e}")),
+    // The chapter text and its notes both go to the trash before the index changes, so a
+    // failure at any step can put everything back.
+    let files = [
+        (chapter_txt_relative_path(&chapter_id), "chapter file"),
+        (chapter_notes_relative_path(&chapter_id), "chapter notes"),
+    ];
+    let mut trashed = Vec::new();
+    for (relative, label) in &files {
+        let path = validate_path(&project_root, relative)?;
+        if !path.exists() {
+            continue;
+        }
+        // The trash can be emptied; the backup stays restorable like any overwritten file.
+        let moved = write_protection::backup_existing_file(&project_root, &path)
+            .and_then(|_| trash::move_to_trash(&project_root, relative))
+            .map_err(|e| format!("Failed to delete {label}: {e}"));
+        match moved {
+            Ok(entry) => trashed.push(entry),
+            Err(e) => {
+                restore_trashed(&project_root, &trashed);
+                return Err(e);
+            }
+        }
     }
 
     // Recompute order for remaining chapters
@@ -279,14 +1045,243 @@ fn delete_chapter_sync(project_path: String, chapter_id: String) -> Result<(), S
     }
 
     if let Err(e) = write_index(&project_root, &index) {
-        if let Some(backup) = chapter_backup.as_ref() {
-            let _ = write_protection::restore_backup(&chapter_path, backup);
-        }
+        restore_trashed(&project_root, &trashed);
         return Err(e);
     }
     Ok(())
 }
 
+/// Best-effort rollback of files a failed chapter deletion already moved to the trash.
+fn restore_trashed(project_root: &Path, entries: &[trash::TrashEntry]) {
+    for entry in entries {
+        let _ = trash::restore_from_trash(project_root, &entry.id, false);
+    }
+}
+
+fn get_chapter_notes_sync(project_path: String, chapter_id: String) -> Result<String, String> {
+    let project_root = PathBuf::from(project_path);
+    ensure_project_exists(&project_root)?;
+    let project_root = project_root
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path: {e}"))?;
+    validate_chapter_id(&chapter_id)?;
+
+    let index = read_index(&project_root)?;
+    if !index.chapters.iter().any(|c| c.id == chapter_id) {
+        return Err("Chapter not found".to_string());
+    }
+    Ok(read_chapter_notes(&project_root, &chapter_id)?.unwrap_or_default())
+}
+
+fn save_chapter_notes_sync(
+    project_path: String,
+    chapter_id: String,
+    notes: String,
+) -> Result<(), String> {
+    let project_root = PathBuf::from(project_path);
+    ensure_project_exists(&project_root)?;
+    let project_root = project_root
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path: {e}"))?;
+    write_protection::ensure_writable(&project_root)?;
+    validate_chapter_id(&chapter_id)?;
+
+    let index = read_index(&project_root)?;
+    if !index.chapters.iter().any(|c| c.id == chapter_id) {
+        return Err("Chapter not found".to_string());
+    }
+    let notes_path = validate_path(&project_root, &chapter_notes_relative_path(&chapter_id))?;
+    write_protection::write_string_with_backup(&project_root, &notes_path, &notes)?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChapterSplit {
+    /// The split chapter, now holding the lines before the split.
+    pub original: ChapterMeta,
+    /// The new chapter right after it, holding the split line and everything below.
+    pub created: ChapterMeta,
+}
+
+/// Moves lines `split_line..` (1-based) of a chapter into a new chapter placed right
+/// after it. Both files and the index change in one transaction.
+fn split_chapter_sync(
+    project_path: String,
+    chapter_id: String,
+    split_line: u32,
+    new_title: String,
+) -> Result<ChapterSplit, String> {
+    let project_root = PathBuf::from(project_path);
+    ensure_project_exists(&project_root)?;
+    let project_root = project_root
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path: {e}"))?;
+    write_protection::ensure_writable(&project_root)?;
+    validate_chapter_id(&chapter_id)?;
+
+    let mut index = read_index(&project_root)?;
+    let Some(position) = index.chapters.iter().position(|c| c.id == chapter_id) else {
+        return Err("Chapter not found".to_string());
+    };
+
+    let relative = chapter_txt_relative_path(&chapter_id);
+    let chapter_path = validate_path(&project_root, &relative)?;
+    if !chapter_path.exists() {
+        return Err("Chapter file does not exist".to_string());
+    }
+    write_protection::check_read_size(&chapter_path)?;
+    let content = fs::read_to_string(&chapter_path)
+        .map_err(|e| format!("Failed to read chapter content: {e}"))?;
+
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    if split_line < 2 || split_line as usize > lines.len() {
+        return Err(format!(
+            "Split line must be between 2 and {} (the chapter's last line)",
+            lines.len()
+        ));
+    }
+    let head = lines[..split_line as usize - 1].concat();
+    let tail = lines[split_line as usize - 1..].concat();
+
+    let new_id = format!("chapter_{:03}", index.next_id);
+    let new_relative = chapter_txt_relative_path(&new_id);
+    if index.chapters.iter().any(|c| c.id == new_id)
+        || validate_path(&project_root, &new_relative)?.exists()
+    {
+        return Err("Chapter id already exists".to_string());
+    }
+
+    let now = now_unix_seconds()?;
+    let original_order = index.chapters[position].order;
+    for chapter in index
+        .chapters
+        .iter_mut()
+        .filter(|c| c.order > original_order)
+    {
+        chapter.order = chapter.order.saturating_add(1);
+        chapter.updated = now;
+    }
+    let original = &mut index.chapters[position];
+    original.word_count = count_words(&head);
+    original.updated = now;
+    let original = original.clone();
+    let created = ChapterMeta {
+        id: new_id,
+        title: new_title,
+        order: original_order.saturating_add(1),
+        created: now,
+        updated: now,
+        word_count: count_words(&tail),
+        status: original.status,
+        volume_id: original.volume_id.clone(),
+    };
+    index.chapters.push(created.clone());
+    index.next_id = index.next_id.saturating_add(1);
+
+    write_protection::transaction(&project_root)
+        .write(&relative, head)
+        .write(&new_relative, tail)
+        .write("chapters/index.json", index_json(&index)?)
+        .commit()?;
+    Ok(ChapterSplit { original, created })
+}
+
+/// Appends `second_id`'s text to `first_id` (joined by `separator`, default a blank line)
+/// and removes the second chapter. Only adjacent chapters, first before second, are
+/// merged unless `force` is set. Both files and the index change in one transaction,
+/// which backs up each of them first.
+fn merge_chapters_sync(
+    project_path: String,
+    first_id: String,
+    second_id: String,
+    separator: Option<String>,
+    force: bool,
+) -> Result<ChapterMeta, String> {
+    let project_root = PathBuf::from(project_path);
+    ensure_project_exists(&project_root)?;
+    let project_root = project_root
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path: {e}"))?;
+    write_protection::ensure_writable(&project_root)?;
+    validate_chapter_id(&first_id)?;
+    validate_chapter_id(&second_id)?;
+    if first_id == second_id {
+        return Err("Cannot merge a chapter into itself".to_string());
+    }
+
+    let mut index = read_index(&project_root)?;
+    index.chapters.sort_by_key(|c| c.order);
+    let position = |id: &str| index.chapters.iter().position(|c| c.id == id);
+    let (Some(first), Some(second)) = (position(&first_id), position(&second_id)) else {
+        return Err("Chapter not found".to_string());
+    };
+    if second != first + 1 && !force {
+        return Err("Chapters are not adjacent; merge with force to merge them anyway".to_string());
+    }
+
+    let read = |id: &str| -> Result<(String, String), String> {
+        let relative = chapter_txt_relative_path(id);
+        let path = validate_path(&project_root, &relative)?;
+        if !path.exists() {
+            return Err(format!("Chapter file does not exist: {relative}"));
+        }
+        write_protection::check_read_size(&path)?;
+        let content = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read chapter content: {e}"))?;
+        Ok((relative, content))
+    };
+    let (first_relative, first_content) = read(&first_id)?;
+    let (second_relative, second_content) = read(&second_id)?;
+
+    let separator = separator.unwrap_or_else(|| "\n\n".to_string());
+    let merged = if first_content.is_empty() || second_content.is_empty() {
+        format!("{first_content}{second_content}")
+    } else {
+        format!("{first_content}{separator}{second_content}")
+    };
+    write_protection::check_write_size(
+        &project_root,
+        &validate_path(&project_root, &first_relative)?,
+        merged.len() as u64,
+    )?;
+
+    let now = now_unix_seconds()?;
+    index.chapters.remove(second);
+    for (i, chapter) in index.chapters.iter_mut().enumerate() {
+        let new_order = (i + 1) as u32;
+        if chapter.order != new_order {
+            chapter.order = new_order;
+            chapter.updated = now;
+        }
+        if chapter.id == first_id {
+            chapter.word_count = count_words(&merged);
+            chapter.updated = now;
+        }
+    }
+    let merged_meta = index.chapters[if second < first { first - 1 } else { first }].clone();
+
+    let mut transaction = write_protection::transaction(&project_root)
+        .write(&first_relative, merged)
+        .delete(&second_relative)
+        .write("chapters/index.json", index_json(&index)?);
+    // The second chapter's notes follow its text into the first chapter.
+    if let Some(second_notes) = read_chapter_notes(&project_root, &second_id)? {
+        let merged_notes = match read_chapter_notes(&project_root, &first_id)? {
+            Some(first_notes) if !first_notes.is_empty() && !second_notes.is_empty() => {
+                format!("{first_notes}\n\n{second_notes}")
+            }
+            Some(first_notes) => format!("{first_notes}{second_notes}"),
+            None => second_notes,
+        };
+        transaction = transaction
+            .write(&chapter_notes_relative_path(&first_id), merged_notes)
+            .delete(&chapter_notes_relative_path(&second_id));
+    }
+    transaction.commit()?;
+    Ok(merged_meta)
+}
+
 fn reorder_chapters_sync(
     project_path: String,
     chapter_ids: Vec<String>,
@@ -296,6 +1291,7 @@ fn reorder_chapters_sync(
     let project_root = project_root
         .canonicalize()
         .map_err(|e| format!("Invalid project path: {e}"))?;
+    write_protection::ensure_writable(&project_root)?;
 
     if chapter_ids.is_empty() {
         return Err("chapter_ids is empty".to_string());
@@ -345,6 +1341,33 @@ fn reorder_chapters_sync(
     Ok(index.chapters)
 }
 
+fn recover_chapter_index_sync(project_path: String) -> Result<IndexRecovery, String> {
+    let project_root = PathBuf::from(project_path);
+    ensure_project_config_exists(&project_root)?;
+    let project_root = project_root
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path: {e}"))?;
+    write_protection::ensure_writable(&project_root)?;
+    recover_index(&project_root)
+}
+
+/// Recomputes `chapters/index.json` from the chapter files, whether or not the current
+/// index is readable. A broken index is backed up before it is replaced.
+fn rebuild_chapter_index_sync(project_path: String) -> Result<Vec<ChapterMeta>, String> {
+    let project_root = PathBuf::from(project_path);
+    ensure_project_config_exists(&project_root)?;
+    let project_root = project_root
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path: {e}"))?;
+    write_protection::ensure_writable(&project_root)?;
+
+    let previous = read_index(&project_root).ok();
+    let mut index = rebuild_index_from_disk(&project_root, previous.as_ref())?;
+    write_index(&project_root, &index)?;
+    index.chapters.sort_by_key(|c| c.order);
+    Ok(index.chapters)
+}
+
 #[tauri::command(rename_all = "camelCase")]
 pub async fn list_chapters(project_path: String) -> Result<Vec<ChapterMeta>, String> {
     tauri::async_runtime::spawn_blocking(move || list_chapters_sync(project_path))
@@ -359,6 +1382,21 @@ pub async fn create_chapter(project_path: String, title: String) -> Result<Chapt
         .map_err(|e| format!("Task join error: {e}"))?
 }
 
+/// Creates a chapter after `insert_after` or at 1-based `position`, shifting later chapters.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn create_chapter_at(
+    project_path: String,
+    title: String,
+    insert_after: Option<String>,
+    position: Option<u32>,
+) -> Result<ChapterMeta, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        create_chapter_at_sync(project_path, title, insert_after, position)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
 #[tauri::command(rename_all = "camelCase")]
 pub async fn get_chapter_content(
     project_path: String,
@@ -374,7 +1412,7 @@ pub async fn save_chapter_content(
     project_path: String,
     chapter_id: String,
     content: String,
-) -> Result<ChapterMeta, String> {
+) -> Result<ChapterSaveResult, String> {
     tauri::async_runtime::spawn_blocking(move || {
         save_chapter_content_sync(project_path, chapter_id, content)
     })
@@ -395,6 +1433,66 @@ pub async fn rename_chapter(
     .map_err(|e| format!("Task join error: {e}"))?
 }
 
+#[tauri::command(rename_all = "camelCase")]
+pub async fn set_chapter_status(
+    project_path: String,
+    chapter_id: String,
+    status: ChapterStatus,
+) -> Result<ChapterMeta, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        set_chapter_status_sync(project_path, chapter_id, status)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn list_volumes(project_path: String) -> Result<Vec<Volume>, String> {
+    tauri::async_runtime::spawn_blocking(move || list_volumes_sync(project_path))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn create_volume(project_path: String, title: String) -> Result<Volume, String> {
+    tauri::async_runtime::spawn_blocking(move || create_volume_sync(project_path, title))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn rename_volume(
+    project_path: String,
+    volume_id: String,
+    new_title: String,
+) -> Result<Volume, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        rename_volume_sync(project_path, volume_id, new_title)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn delete_volume(project_path: String, volume_id: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || delete_volume_sync(project_path, volume_id))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn assign_chapter_volume(
+    project_path: String,
+    chapter_id: String,
+    volume_id: Option<String>,
+) -> Result<ChapterMeta, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        assign_chapter_volume_sync(project_path, chapter_id, volume_id)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
 #[tauri::command(rename_all = "camelCase")]
 pub async fn delete_chapter(project_path: String, chapter_id: String) -> Result<(), String> {
     tauri::async_runtime::spawn_blocking(move || delete_chapter_sync(project_path, chapter_id))
@@ -402,6 +1500,73 @@ pub async fn delete_chapter(project_path: String, chapter_id: String) -> Result<
         .map_err(|e| format!("Task join error: {e}"))?
 }
 
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_chapters_content(
+    project_path: String,
+    chapter_ids: Vec<String>,
+) -> Result<ChapterContents, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        get_chapters_content_sync(project_path, chapter_ids)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_chapter_notes(project_path: String, chapter_id: String) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || get_chapter_notes_sync(project_path, chapter_id))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn save_chapter_notes(
+    project_path: String,
+    chapter_id: String,
+    notes: String,
+) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        save_chapter_notes_sync(project_path, chapter_id, notes)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn split_chapter(
+    project_path: String,
+    chapter_id: String,
+    split_line: u32,
+    new_title: String,
+) -> Result<ChapterSplit, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        split_chapter_sync(project_path, chapter_id, split_line, new_title)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn merge_chapters(
+    project_path: String,
+    first_id: String,
+    second_id: String,
+    separator: Option<String>,
+    force: Option<bool>,
+) -> Result<ChapterMeta, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        merge_chapters_sync(
+            project_path,
+            first_id,
+            second_id,
+            separator,
+            force.unwrap_or(false),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
 #[tauri::command(rename_all = "camelCase")]
 pub async fn reorder_chapters(
     project_path: String,
@@ -411,3 +1576,17 @@ pub async fn reorder_chapters(
         .await
         .map_err(|e| format!("Task join error: {e}"))?
 }
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn rebuild_chapter_index(project_path: String) -> Result<Vec<ChapterMeta>, String> {
+    tauri::async_runtime::spawn_blocking(move || rebuild_chapter_index_sync(project_path))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn recover_chapter_index(project_path: String) -> Result<IndexRecovery, String> {
+    tauri::async_runtime::spawn_blocking(move || recover_chapter_index_sync(project_path))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
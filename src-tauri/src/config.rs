@@ -1,7 +1,11 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::redact;
 
 const LEGACY_GLM_DEMO_PROVIDER_ID: &str = "builtin_glm_4_7_demo";
 const BUILTIN_DEMO_PROVIDER_ID: &str = "builtin_dashscope_qwen_demo";
@@ -9,12 +13,62 @@ const BUILTIN_DEMO_PROVIDER_NAME: &str = "DashScope Qwen Demo";
 const BUILTIN_DEMO_BASE_URL: &str = "https://dashscope.aliyuncs.com/compatible-mode/v1";
 const BUILTIN_DEMO_MODEL: &str = "qwen-plus";
 
+/// Schema version written by this build. Bump it together with a new entry in
+/// `CONFIG_MIGRATIONS`.
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+/// Entry `n` upgrades a raw config from schema `n` to `n + 1`.
+type ConfigMigration = fn(&mut Value) -> Result<(), String>;
+const CONFIG_MIGRATIONS: &[ConfigMigration] = &[migrate_v0_to_v1, migrate_v1_to_v2];
+
+const DEFAULT_PROFILE_ID: &str = "default";
+const DEFAULT_PROFILE_NAME: &str = "默认";
+
+const CONFIG_BACKUP_DIR: &str = "config_backups";
+const MAX_CONFIG_BACKUPS: usize = 10;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GlobalConfig {
     pub schema_version: u32,
     pub providers: Vec<Provider>,
     pub active_provider_id: Option<String>,
+    /// Mirror of the parameters of the profile flagged `is_default`.
     pub default_parameters: ModelParameters,
+    #[serde(default)]
+    pub parameter_profiles: Vec<NamedParameters>,
+    /// Proxy for all AI requests; `None` or an empty URL means the system default.
+    #[serde(default)]
+    pub proxy: Option<ProxySettings>,
+    /// Folders outside any project that AI reads may reach as `ext://<id>/...`.
+    #[serde(default)]
+    pub reference_roots: Vec<ReferenceRoot>,
+    /// Saved chapter-split regexes for txt import.
+    #[serde(default)]
+    pub import_patterns: Vec<ImportPattern>,
+}
+
+/// A named chapter-split regex, e.g. for one source site's txt files.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ImportPattern {
+    pub id: String,
+    pub name: String,
+    pub pattern: String,
+}
+
+/// A user-approved read-only folder; nothing is ever written under it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReferenceRoot {
+    pub id: String,
+    /// Absolute path.
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ProxySettings {
+    pub url: String,
+    /// Hosts (or `*.suffix` patterns) that connect directly.
+    #[serde(default)]
+    pub bypass: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,6 +81,52 @@ pub struct Provider {
     pub models_updated_at: Option<u64>,
     pub provider_type: ProviderType,
     pub headers: Option<HashMap<String, String>>,
+    /// Overrides `GlobalConfig::default_parameters` for this provider when set.
+    #[serde(default)]
+    pub default_parameters: Option<ModelParameters>,
+    /// Overrides `GlobalConfig::proxy` for this provider when set.
+    #[serde(default)]
+    pub proxy: Option<ProxySettings>,
+    #[serde(default)]
+    pub favorite: bool,
+    /// Models left out of the model picker.
+    #[serde(default)]
+    pub hidden_models: Vec<String>,
+    /// Display name -> real model id.
+    #[serde(default)]
+    pub model_aliases: HashMap<String, String>,
+    #[serde(default)]
+    pub default_model: Option<String>,
+    /// Environment variable consulted when the keyring has no key for this provider.
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+    /// Requests allowed in flight at once; `None` means unlimited.
+    #[serde(default)]
+    pub max_concurrent_requests: Option<u32>,
+    /// Minimum gap between the starts of two requests.
+    #[serde(default)]
+    pub min_request_interval_ms: Option<u64>,
+    /// Azure OpenAI `api-version` query parameter.
+    #[serde(default)]
+    pub api_version: Option<String>,
+    /// Azure OpenAI: model id -> deployment name. Unmapped models are used as the
+    /// deployment name directly.
+    #[serde(default)]
+    pub deployments: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelOption {
+    pub id: String,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderModels {
+    pub models: Vec<ModelOption>,
+    pub default_model: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,9 +135,110 @@ pub enum ProviderType {
     OpenaiCompatible,
     Google,
     Anthropic,
+    AzureOpenai,
+    /// Local Ollama server: no API key, models listed via `/api/tags`.
+    Ollama,
+}
+
+impl ProviderType {
+    /// Wire name used by the ai-engine.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProviderType::OpenaiCompatible => "openai-compatible",
+            ProviderType::Google => "google",
+            ProviderType::Anthropic => "anthropic",
+            ProviderType::AzureOpenai => "azure-openai",
+            ProviderType::Ollama => "ollama",
+        }
+    }
+}
+
+impl Provider {
+    /// Model list for pickers: hidden models dropped, aliases applied as labels.
+    pub fn visible_models(&self) -> ProviderModels {
+        let hidden: HashSet<&str> = self.hidden_models.iter().map(String::as_str).collect();
+        let models = self
+            .models
+            .iter()
+            .filter(|id| !hidden.contains(id.as_str()))
+            .map(|id| {
+                let label = self
+                    .model_aliases
+                    .iter()
+                    .filter(|(_, target)| *target == id)
+                    .map(|(alias, _)| alias)
+                    .min()
+                    .unwrap_or(id);
+                ModelOption {
+                    id: id.clone(),
+                    label: label.clone(),
+                }
+            })
+            .collect();
+        ProviderModels {
+            models,
+            default_model: self.default_model.clone(),
+        }
+    }
+
+    /// Replaces the model list, keeping hidden/alias/default settings only for models that
+    /// still exist.
+    pub fn set_models(&mut self, models: Vec<String>) {
+        let available: HashSet<&str> = models.iter().map(String::as_str).collect();
+        self.hidden_models.retain(|id| available.contains(id.as_str()));
+        self.model_aliases
+            .retain(|_, target| available.contains(target.as_str()));
+        if self
+            .default_model
+            .as_deref()
+            .is_some_and(|id| !available.contains(id))
+        {
+            self.default_model = None;
+        }
+        self.models = models;
+    }
+
+    /// Local servers such as Ollama run without a key.
+    pub fn requires_api_key(&self) -> bool {
+        !matches!(self.provider_type, ProviderType::Ollama)
+    }
+
+    /// Base URL as the ai-engine expects it: OpenAI-compatible endpoints always end in `/v1`.
+    pub fn normalized_base_url(&self) -> String {
+        if !matches!(self.provider_type, ProviderType::OpenaiCompatible) {
+            return self.base_url.clone();
+        }
+        let trimmed = self.base_url.trim_end_matches('/');
+        if trimmed.ends_with("/v1") {
+            trimmed.to_string()
+        } else {
+            format!("{trimmed}/v1")
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamedParameters {
+    pub id: String,
+    pub name: String,
+    pub parameters: ModelParameters,
+    /// Exactly one profile is the default; its parameters back `default_parameters`.
+    #[serde(default)]
+    pub is_default: bool,
+}
+
+impl NamedParameters {
+    fn default_profile(parameters: ModelParameters) -> Self {
+        Self {
+            id: DEFAULT_PROFILE_ID.to_string(),
+            name: DEFAULT_PROFILE_NAME.to_string(),
+            parameters,
+            is_default: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ModelParameters {
     pub model: String,
     pub temperature: f32,
@@ -49,16 +250,234 @@ pub struct ModelParameters {
 impl Default for GlobalConfig {
     fn default() -> Self {
         let mut config = Self {
-            schema_version: 1,
+            schema_version: CURRENT_SCHEMA_VERSION,
             providers: vec![],
             active_provider_id: None,
             default_parameters: ModelParameters::default(),
+            parameter_profiles: vec![NamedParameters::default_profile(ModelParameters::default())],
+            proxy: None,
+            reference_roots: vec![],
+            import_patterns: vec![],
         };
         ensure_builtin_demo_provider(&mut config, false);
         config
     }
 }
 
+impl GlobalConfig {
+    /// Parameters to use for `provider_id`: the provider's own defaults, falling back to
+    /// the global defaults.
+    pub fn parameters_for(&self, provider_id: &str) -> &ModelParameters {
+        self.providers
+            .iter()
+            .find(|p| p.id == provider_id)
+            .and_then(|p| p.default_parameters.as_ref())
+            .unwrap_or(&self.default_parameters)
+    }
+
+    pub fn parameter_profile(&self, profile_id: &str) -> Option<&NamedParameters> {
+        self.parameter_profiles.iter().find(|p| p.id == profile_id)
+    }
+
+    /// Updates the global defaults together with the default profile they mirror.
+    pub fn set_default_parameters(&mut self, parameters: ModelParameters) {
+        if let Some(profile) = self.parameter_profiles.iter_mut().find(|p| p.is_default) {
+            profile.parameters = parameters.clone();
+        }
+        self.default_parameters = parameters;
+    }
+
+    /// Inserts or replaces a profile (matched by id; an empty id gets a fresh one) and
+    /// returns the stored copy. Flagging it default moves the flag off the previous default.
+    pub fn save_parameter_profile(&mut self, mut profile: NamedParameters) -> Result<NamedParameters, String> {
+        profile.name = profile.name.trim().to_string();
+        if profile.name.is_empty() {
+            return Err("Profile name is empty".to_string());
+        }
+        if profile.id.trim().is_empty() {
+            profile.id = uuid::Uuid::new_v4().to_string();
+        }
+        if self
+            .parameter_profiles
+            .iter()
+            .any(|p| p.id != profile.id && p.name == profile.name)
+        {
+            return Err(format!("A profile named {} already exists", profile.name));
+        }
+
+        let existing = self.parameter_profiles.iter().position(|p| p.id == profile.id);
+        // The default can only be moved, never cleared.
+        if existing.is_some_and(|pos| self.parameter_profiles[pos].is_default) {
+            profile.is_default = true;
+        }
+        if profile.is_default {
+            for other in &mut self.parameter_profiles {
+                other.is_default = false;
+            }
+            self.default_parameters = profile.parameters.clone();
+        }
+        match existing {
+            Some(pos) => self.parameter_profiles[pos] = profile.clone(),
+            None => self.parameter_profiles.push(profile.clone()),
+        }
+        Ok(profile)
+    }
+
+    pub fn delete_parameter_profile(&mut self, profile_id: &str) -> Result<(), String> {
+        let pos = self
+            .parameter_profiles
+            .iter()
+            .position(|p| p.id == profile_id)
+            .ok_or(format!("Profile {} not found", profile_id))?;
+        if self.parameter_profiles[pos].is_default {
+            return Err("The default profile cannot be deleted; make another profile the default first".to_string());
+        }
+        self.parameter_profiles.remove(pos);
+        Ok(())
+    }
+
+    /// Inserts or replaces an import pattern (matched by id; an empty id gets a fresh one)
+    /// and returns the stored copy. The regex must compile.
+    pub fn save_import_pattern(&mut self, mut pattern: ImportPattern) -> Result<ImportPattern, String> {
+        pattern.name = pattern.name.trim().to_string();
+        if pattern.name.is_empty() {
+            return Err("Pattern name is empty".to_string());
+        }
+        if pattern.pattern.trim().is_empty() {
+            return Err("Pattern is empty".to_string());
+        }
+        crate::import::chapter_regex(&pattern.pattern)?;
+        if pattern.id.trim().is_empty() {
+            pattern.id = uuid::Uuid::new_v4().to_string();
+        }
+        if self
+            .import_patterns
+            .iter()
+            .any(|p| p.id != pattern.id && p.name == pattern.name)
+        {
+            return Err(format!("A pattern named {} already exists", pattern.name));
+        }
+
+        match self.import_patterns.iter().position(|p| p.id == pattern.id) {
+            Some(pos) => self.import_patterns[pos] = pattern.clone(),
+            None => self.import_patterns.push(pattern.clone()),
+        }
+        Ok(pattern)
+    }
+
+    pub fn delete_import_pattern(&mut self, pattern_id: &str) -> Result<(), String> {
+        let pos = self
+            .import_patterns
+            .iter()
+            .position(|p| p.id == pattern_id)
+            .ok_or(format!("Import pattern {} not found", pattern_id))?;
+        self.import_patterns.remove(pos);
+        Ok(())
+    }
+
+    /// Proxy to use for `provider_id`: the provider override, then the global setting.
+    pub fn proxy_for(&self, provider_id: Option<&str>) -> Option<&ProxySettings> {
+        let provider_proxy = provider_id
+            .and_then(|id| self.providers.iter().find(|p| p.id == id))
+            .and_then(|p| non_empty_proxy(p.proxy.as_ref()));
+        provider_proxy.or_else(|| non_empty_proxy(self.proxy.as_ref()))
+    }
+}
+
+fn non_empty_proxy(proxy: Option<&ProxySettings>) -> Option<&ProxySettings> {
+    proxy.filter(|p| !p.url.trim().is_empty())
+}
+
+pub fn validate_proxy(proxy: &ProxySettings) -> Result<(), String> {
+    let raw = proxy.url.trim();
+    if raw.is_empty() {
+        return Ok(());
+    }
+    // Proxy URLs may embed credentials.
+    let shown = || redact::redact(raw);
+    let url = reqwest::Url::parse(raw)
+        .map_err(|e| format!("Invalid proxy URL '{}': {e}", shown()))?;
+    if !matches!(url.scheme(), "http" | "https" | "socks5" | "socks5h") {
+        return Err(format!(
+            "Invalid proxy URL '{}': scheme must be http, https, socks5 or socks5h",
+            shown()
+        ));
+    }
+    if url.host_str().map_or(true, str::is_empty) {
+        return Err(format!("Invalid proxy URL '{}': missing host", shown()));
+    }
+    if proxy.bypass.iter().any(|entry| entry.trim().is_empty()) {
+        return Err("Proxy bypass list contains an empty entry".to_string());
+    }
+    Ok(())
+}
+
+/// Headers the ai-engine derives from the API key; overriding them needs explicit consent.
+const AUTH_HEADER_NAMES: &[&str] = &["authorization", "x-api-key", "x-goog-api-key"];
+
+fn is_header_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c)
+}
+
+/// Validates custom provider headers and returns them with surrounding whitespace trimmed.
+pub fn validate_provider_headers(
+    headers: HashMap<String, String>,
+    allow_auth_override: bool,
+) -> Result<HashMap<String, String>, String> {
+    let mut normalized = HashMap::with_capacity(headers.len());
+    for (name, value) in headers {
+        let name = name.trim().to_string();
+        if name.is_empty() || !name.chars().all(is_header_token_char) {
+            return Err(format!("Invalid header name '{name}'"));
+        }
+        if !allow_auth_override && AUTH_HEADER_NAMES.contains(&name.to_ascii_lowercase().as_str()) {
+            return Err(format!(
+                "Header '{name}' is set from the API key; enable auth override to replace it"
+            ));
+        }
+        let value = value.trim().to_string();
+        if value.chars().any(|c| c.is_control() && c != '\t') {
+            return Err(format!("Header '{name}' contains a line break or control character"));
+        }
+        if normalized
+            .keys()
+            .any(|existing: &String| existing.eq_ignore_ascii_case(&name))
+        {
+            return Err(format!("Header '{name}' is listed more than once"));
+        }
+        normalized.insert(name, value);
+    }
+    Ok(normalized)
+}
+
+pub fn validate_reference_roots(roots: &[ReferenceRoot]) -> Result<(), String> {
+    for (i, root) in roots.iter().enumerate() {
+        let id = root.id.as_str();
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            return Err(format!("Invalid reference root id '{id}': use letters, digits, '_' or '-'"));
+        }
+        if roots[..i].iter().any(|other| other.id == id) {
+            return Err(format!("Reference root id '{id}' is listed more than once"));
+        }
+        if !Path::new(&root.path).is_absolute() {
+            return Err(format!("Reference root '{id}' must be an absolute path"));
+        }
+    }
+    Ok(())
+}
+
+fn validate_proxies(config: &GlobalConfig) -> Result<(), String> {
+    if let Some(proxy) = &config.proxy {
+        validate_proxy(proxy)?;
+    }
+    for provider in &config.providers {
+        if let Some(proxy) = &provider.proxy {
+            validate_proxy(proxy).map_err(|e| format!("Provider {}: {e}", provider.id))?;
+        }
+    }
+    Ok(())
+}
+
 impl Default for ModelParameters {
     fn default() -> Self {
         Self {
@@ -71,6 +490,38 @@ impl Default for ModelParameters {
     }
 }
 
+/// Reorders `config.providers` to match `provider_ids`, which must list every provider
+/// exactly once. The active provider is left as is.
+pub fn reorder_providers(config: &mut GlobalConfig, provider_ids: &[String]) -> Result<(), String> {
+    if provider_ids.len() != config.providers.len() {
+        return Err("provider_ids must include all providers".to_string());
+    }
+    let unique: HashSet<&str> = provider_ids.iter().map(String::as_str).collect();
+    if unique.len() != provider_ids.len() {
+        return Err("provider_ids contains duplicates".to_string());
+    }
+
+    let mut by_id: HashMap<String, Provider> = config
+        .providers
+        .drain(..)
+        .map(|p| (p.id.clone(), p))
+        .collect();
+    let mut reordered = Vec::with_capacity(provider_ids.len());
+    for id in provider_ids {
+        let Some(provider) = by_id.remove(id) else {
+            config.providers = reordered.into_iter().chain(by_id.into_values()).collect();
+            return Err(format!("Unknown provider id: {id}"));
+        };
+        reordered.push(provider);
+    }
+    config.providers = reordered;
+    Ok(())
+}
+
+pub fn is_builtin_provider(provider_id: &str) -> bool {
+    provider_id == BUILTIN_DEMO_PROVIDER_ID
+}
+
 fn builtin_demo_provider() -> Provider {
     Provider {
         id: BUILTIN_DEMO_PROVIDER_ID.to_string(),
@@ -80,6 +531,17 @@ fn builtin_demo_provider() -> Provider {
         models_updated_at: None,
         provider_type: ProviderType::OpenaiCompatible,
         headers: None,
+        default_parameters: None,
+        proxy: None,
+        favorite: false,
+        hidden_models: vec![],
+        model_aliases: HashMap::new(),
+        default_model: None,
+        api_key_env: None,
+        max_concurrent_requests: None,
+        min_request_interval_ms: None,
+        api_version: None,
+        deployments: HashMap::new(),
     }
 }
 
@@ -96,7 +558,7 @@ fn normalize_builtin_demo_provider(provider: &mut Provider) -> bool {
         changed = true;
     }
     if provider.models != canonical.models {
-        provider.models = canonical.models;
+        provider.set_models(canonical.models);
         changed = true;
     }
     if provider.models_updated_at.is_some() {
@@ -194,7 +656,7 @@ fn get_config_dir() -> Result<PathBuf, String> {
     Ok(config_dir)
 }
 
-fn get_config_path() -> Result<PathBuf, String> {
+pub(crate) fn get_config_path() -> Result<PathBuf, String> {
     Ok(get_config_dir()?.join("config.json"))
 }
 
@@ -202,29 +664,294 @@ pub fn get_global_config_dir() -> Result<PathBuf, String> {
     get_config_dir()
 }
 
+/// Configs written before `schema_version` existed: fill in fields that later became
+/// mandatory so the typed deserialization succeeds.
+fn migrate_v0_to_v1(value: &mut Value) -> Result<(), String> {
+    let root = value
+        .as_object_mut()
+        .ok_or("config.json is not a JSON object")?;
+
+    let providers = root
+        .entry("providers")
+        .or_insert_with(|| Value::Array(vec![]));
+    if providers.is_null() {
+        *providers = Value::Array(vec![]);
+    }
+    let providers = providers
+        .as_array_mut()
+        .ok_or("config.json: providers is not an array")?;
+    for provider in providers.iter_mut() {
+        let Some(provider) = provider.as_object_mut() else {
+            return Err("config.json: provider entry is not an object".to_string());
+        };
+        provider
+            .entry("models")
+            .or_insert_with(|| Value::Array(vec![]));
+        provider
+            .entry("provider_type")
+            .or_insert_with(|| Value::String("openai-compatible".to_string()));
+    }
+
+    root.entry("active_provider_id").or_insert(Value::Null);
+    if !root.contains_key("default_parameters") {
+        let defaults = serde_json::to_value(ModelParameters::default())
+            .map_err(|e| format!("Serialize JSON failed: {e}"))?;
+        root.insert("default_parameters".to_string(), defaults);
+    }
+    Ok(())
+}
+
+/// `default_parameters` becomes the first entry of `parameter_profiles`, flagged default.
+fn migrate_v1_to_v2(value: &mut Value) -> Result<(), String> {
+    let root = value
+        .as_object_mut()
+        .ok_or("config.json is not a JSON object")?;
+    let has_profiles = root
+        .get("parameter_profiles")
+        .and_then(Value::as_array)
+        .is_some_and(|profiles| !profiles.is_empty());
+    if has_profiles {
+        return Ok(());
+    }
+    let defaults = root
+        .get("default_parameters")
+        .cloned()
+        .ok_or("config.json: default_parameters is missing")?;
+    let parameters: ModelParameters = serde_json::from_value(defaults)
+        .map_err(|e| format!("config.json: invalid default_parameters: {e}"))?;
+    let profiles = serde_json::to_value(vec![NamedParameters::default_profile(parameters)])
+        .map_err(|e| format!("Serialize JSON failed: {e}"))?;
+    root.insert("parameter_profiles".to_string(), profiles);
+    Ok(())
+}
+
+/// Keeps exactly one default profile whose parameters match `default_parameters`, e.g.
+/// after a settings import that replaced the config wholesale.
+fn ensure_default_profile(config: &mut GlobalConfig) -> bool {
+    let defaults = config.parameter_profiles.iter().filter(|p| p.is_default).count();
+    if defaults == 1 {
+        let Some(profile) = config.parameter_profiles.iter_mut().find(|p| p.is_default) else {
+            return false;
+        };
+        if profile.parameters == config.default_parameters {
+            return false;
+        }
+        profile.parameters = config.default_parameters.clone();
+        return true;
+    }
+
+    let mut seen_default = false;
+    for profile in &mut config.parameter_profiles {
+        profile.is_default = profile.is_default && !seen_default;
+        seen_default |= profile.is_default;
+    }
+    match config.parameter_profiles.iter_mut().find(|p| p.is_default) {
+        Some(profile) => profile.parameters = config.default_parameters.clone(),
+        None => config
+            .parameter_profiles
+            .insert(0, NamedParameters::default_profile(config.default_parameters.clone())),
+    }
+    true
+}
+
+/// Why raw config text could not be loaded.
+#[derive(Debug)]
+pub(crate) enum ConfigParseError {
+    /// Written by a newer app version. Not corruption, so never replaced by a backup.
+    NewerSchema(u32),
+    Invalid(String),
+}
+
+impl std::fmt::Display for ConfigParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NewerSchema(found) => write!(
+                f,
+                "config.json was created by a newer version of the app (schema {found}, supported {CURRENT_SCHEMA_VERSION}); please upgrade"
+            ),
+            Self::Invalid(message) => f.write_str(message),
+        }
+    }
+}
+
+impl From<ConfigParseError> for String {
+    fn from(error: ConfigParseError) -> Self {
+        error.to_string()
+    }
+}
+
+/// Applies every pending migration to `value`. Returns the schema version the value
+/// started at.
+fn migrate_config_value(value: &mut Value) -> Result<u32, ConfigParseError> {
+    let from = match value.get("schema_version") {
+        None | Some(Value::Null) => 0,
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| {
+                ConfigParseError::Invalid("config.json: schema_version is not a number".into())
+            })?,
+    };
+    if from > CURRENT_SCHEMA_VERSION {
+        return Err(ConfigParseError::NewerSchema(from));
+    }
+
+    for version in from..CURRENT_SCHEMA_VERSION {
+        let step = CONFIG_MIGRATIONS.get(version as usize).ok_or_else(|| {
+            ConfigParseError::Invalid(format!("No config migration from schema {version}"))
+        })?;
+        step(value).map_err(|e| {
+            ConfigParseError::Invalid(format!(
+                "Config migration {version} -> {} failed: {e}",
+                version + 1
+            ))
+        })?;
+        value["schema_version"] = Value::from(version + 1);
+    }
+    Ok(from)
+}
+
+fn now_millis() -> Result<u64, String> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .map_err(|e| format!("Failed to read system time: {e}"))
+}
+
+fn get_backup_dir() -> Result<PathBuf, String> {
+    Ok(get_config_dir()?.join(CONFIG_BACKUP_DIR))
+}
+
+/// Backup timestamps (unix millis) in `dir`, oldest first.
+fn backup_timestamps(dir: &Path) -> Vec<u64> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut timestamps: Vec<u64> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            name.to_str()?.strip_suffix(".json")?.parse::<u64>().ok()
+        })
+        .collect();
+    timestamps.sort_unstable();
+    timestamps
+}
+
+/// Copies the current config file into `dir` and prunes the oldest copies beyond
+/// `MAX_CONFIG_BACKUPS`.
+fn snapshot_config_file(path: &Path, dir: &Path) -> Result<Option<u64>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create config backup dir: {e}"))?;
+
+    // Keep names strictly increasing so "newest" stays well defined within one millisecond.
+    let newest = backup_timestamps(dir).last().copied();
+    let timestamp = now_millis()?.max(newest.map_or(0, |t| t + 1));
+    fs::copy(path, dir.join(format!("{timestamp}.json")))
+        .map_err(|e| format!("Failed to back up config.json: {e}"))?;
+
+    let timestamps = backup_timestamps(dir);
+    let excess = timestamps.len().saturating_sub(MAX_CONFIG_BACKUPS);
+    for old in &timestamps[..excess] {
+        let _ = fs::remove_file(dir.join(format!("{old}.json")));
+    }
+    Ok(Some(timestamp))
+}
+
+/// Parses and migrates raw config text. Returns the config and the schema version the
+/// text was written with.
+pub(crate) fn parse_config(content: &str) -> Result<(GlobalConfig, u32), ConfigParseError> {
+    let content = content.trim_start_matches('\u{feff}');
+    // serde errors quote the offending value, which may be a pasted key.
+    let value: Value = serde_json::from_str(content)
+        .map_err(|e| ConfigParseError::Invalid(redact::redact(&e.to_string())))?;
+    parse_config_value(value)
+}
+
+/// [`parse_config`] for config JSON that is already parsed, e.g. embedded in another file.
+pub(crate) fn parse_config_value(
+    mut value: Value,
+) -> Result<(GlobalConfig, u32), ConfigParseError> {
+    let invalid =
+        |e: &dyn std::fmt::Display| ConfigParseError::Invalid(redact::redact(&e.to_string()));
+    let from = migrate_config_value(&mut value).map_err(|e| match e {
+        ConfigParseError::Invalid(message) => invalid(&message),
+        newer => newer,
+    })?;
+    let config = serde_json::from_value(value).map_err(|e| invalid(&e))?;
+    Ok((config, from))
+}
+
+/// Newest backup in `dir` that still parses, if any.
+fn newest_valid_backup(dir: &Path) -> Option<(u64, GlobalConfig, u32)> {
+    backup_timestamps(dir).into_iter().rev().find_map(|timestamp| {
+        let content = fs::read_to_string(dir.join(format!("{timestamp}.json"))).ok()?;
+        let (config, from) = parse_config(&content).ok()?;
+        Some((timestamp, config, from))
+    })
+}
+
 pub fn load_config() -> Result<GlobalConfig, String> {
     let path = get_config_path()?;
-    let (mut config, loaded_from_disk) = if !path.exists() {
-        (GlobalConfig::default(), false)
+    let (mut config, loaded_from_disk, needs_save) = if !path.exists() {
+        (GlobalConfig::default(), false, false)
     } else {
         let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
-        let content = content.trim_start_matches('\u{feff}').to_string();
-        (
-            serde_json::from_str(&content).map_err(|e| e.to_string())?,
-            true,
-        )
+        match parse_config(&content) {
+            Ok((config, from)) => (config, true, from < CURRENT_SCHEMA_VERSION),
+            // A newer schema is not corruption; never fall back over it.
+            Err(e @ ConfigParseError::NewerSchema(_)) => return Err(e.into()),
+            Err(e) => {
+                let Some((timestamp, config, _)) = newest_valid_backup(&get_backup_dir()?) else {
+                    return Err(e.into());
+                };
+                eprintln!("[config] config.json is unreadable ({e}); using backup {timestamp}");
+                let _ = fs::rename(&path, path.with_file_name("config.json.corrupt"));
+                (config, true, true)
+            }
+        }
     };
     let changed = ensure_builtin_demo_provider(&mut config, true);
-    if loaded_from_disk && changed {
+    let changed = ensure_default_profile(&mut config) || changed;
+    if loaded_from_disk && (changed || needs_save) {
         save_config(&config)?;
     }
     Ok(config)
 }
 
 pub fn save_config(config: &GlobalConfig) -> Result<(), String> {
+    validate_proxies(config)?;
+    validate_reference_roots(&config.reference_roots)?;
     let path = get_config_path()?;
     let content = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
-    fs::write(&path, content).map_err(|e| e.to_string())
+    snapshot_config_file(&path, &get_backup_dir()?)?;
+    crate::write_protection::atomic_write_bytes(&path, content.as_bytes(), None)
+}
+
+/// Timestamps (unix millis) of the kept config backups, newest first.
+pub fn list_config_backups() -> Result<Vec<u64>, String> {
+    let mut timestamps = backup_timestamps(&get_backup_dir()?);
+    timestamps.reverse();
+    Ok(timestamps)
+}
+
+/// Replaces config.json with the backup taken at `timestamp`. The current file is backed
+/// up first so the restore itself can be undone.
+pub fn restore_config_backup(timestamp: u64) -> Result<GlobalConfig, String> {
+    let backup_path = get_backup_dir()?.join(format!("{timestamp}.json"));
+    if !backup_path.exists() {
+        return Err(format!("Config backup {timestamp} not found"));
+    }
+    let content = fs::read_to_string(&backup_path)
+        .map_err(|e| format!("Failed to read config backup: {e}"))?;
+    parse_config(&content).map_err(|e| format!("Config backup {timestamp} is invalid: {e}"))?;
+
+    let path = get_config_path()?;
+    snapshot_config_file(&path, &get_backup_dir()?)?;
+    crate::write_protection::atomic_write_bytes(&path, content.as_bytes(), None)?;
+    load_config()
 }
 
 #[cfg(test)]
@@ -250,6 +977,17 @@ mod tests {
             models_updated_at: None,
             provider_type: ProviderType::OpenaiCompatible,
             headers: None,
+            default_parameters: None,
+            proxy: None,
+            favorite: false,
+            hidden_models: vec![],
+            model_aliases: HashMap::new(),
+            default_model: None,
+            api_key_env: None,
+            max_concurrent_requests: None,
+            min_request_interval_ms: None,
+            api_version: None,
+            deployments: HashMap::new(),
         });
 
         save_config(&config).expect("save_config should succeed");
@@ -264,6 +1002,204 @@ mod tests {
         std::env::remove_var("CREATORAI_CONFIG_DIR");
     }
 
+    #[test]
+    fn unversioned_config_is_migrated_to_current_schema() {
+        let mut value = serde_json::json!({
+            "providers": [{
+                "id": "legacy",
+                "name": "Legacy",
+                "base_url": "http://localhost:3000/v1"
+            }]
+        });
+
+        let from = migrate_config_value(&mut value).expect("migrate v0");
+        assert_eq!(from, 0);
+        assert_eq!(value["schema_version"], CURRENT_SCHEMA_VERSION);
+
+        let config: GlobalConfig = serde_json::from_value(value).expect("deserialize migrated");
+        assert_eq!(config.providers.len(), 1);
+        assert!(matches!(
+            config.providers[0].provider_type,
+            ProviderType::OpenaiCompatible
+        ));
+        assert!(config.providers[0].models.is_empty());
+        assert!(config.active_provider_id.is_none());
+        assert_eq!(config.default_parameters.model, BUILTIN_DEMO_MODEL);
+    }
+
+    #[test]
+    fn current_config_is_left_untouched_and_newer_is_rejected() {
+        let mut current = serde_json::to_value(GlobalConfig::default()).unwrap();
+        let before = current.clone();
+        assert_eq!(
+            migrate_config_value(&mut current).unwrap(),
+            CURRENT_SCHEMA_VERSION
+        );
+        assert_eq!(current, before);
+
+        let mut newer = serde_json::json!({ "schema_version": CURRENT_SCHEMA_VERSION + 1 });
+        let err = migrate_config_value(&mut newer).expect_err("newer schema should fail");
+        assert!(matches!(err, ConfigParseError::NewerSchema(v) if v == CURRENT_SCHEMA_VERSION + 1));
+        assert!(err.to_string().contains("newer version"));
+    }
+
+    #[test]
+    fn config_backups_are_pruned_and_corrupt_ones_skipped() {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let tmp_dir = std::env::temp_dir().join(format!("creatorai-config-backup-test-{unique}"));
+        let backup_dir = tmp_dir.join(CONFIG_BACKUP_DIR);
+        fs::create_dir_all(&tmp_dir).unwrap();
+        let path = tmp_dir.join("config.json");
+
+        let valid = serde_json::to_string_pretty(&GlobalConfig::default()).unwrap();
+        fs::write(&path, &valid).unwrap();
+        for _ in 0..(MAX_CONFIG_BACKUPS + 3) {
+            snapshot_config_file(&path, &backup_dir).unwrap();
+        }
+        assert_eq!(backup_timestamps(&backup_dir).len(), MAX_CONFIG_BACKUPS);
+
+        fs::write(&path, "{ not json").unwrap();
+        let corrupt = snapshot_config_file(&path, &backup_dir).unwrap().unwrap();
+        let (timestamp, config, _) = newest_valid_backup(&backup_dir).expect("valid backup");
+        assert!(timestamp < corrupt);
+        assert_eq!(config.schema_version, CURRENT_SCHEMA_VERSION);
+
+        let _ = fs::remove_dir_all(&tmp_dir);
+    }
+
+    #[test]
+    fn provider_parameters_override_global_defaults() {
+        let mut config: GlobalConfig = serde_json::from_value(serde_json::json!({
+            "schema_version": CURRENT_SCHEMA_VERSION,
+            "providers": [{
+                "id": "local",
+                "name": "Local",
+                "base_url": "http://localhost:11434/v1",
+                "models": [],
+                "models_updated_at": null,
+                "provider_type": "openai-compatible",
+                "headers": null
+            }],
+            "active_provider_id": "local",
+            "default_parameters": ModelParameters::default()
+        }))
+        .expect("config without provider parameters should deserialize");
+        assert!(config.providers[0].default_parameters.is_none());
+        assert_eq!(config.parameters_for("local").max_tokens, 2000);
+
+        config.providers[0].default_parameters = Some(ModelParameters {
+            temperature: 0.9,
+            max_tokens: 4096,
+            ..ModelParameters::default()
+        });
+        assert_eq!(config.parameters_for("local").max_tokens, 4096);
+        assert_eq!(config.parameters_for("missing").max_tokens, 2000);
+    }
+
+    #[test]
+    fn proxy_resolution_and_validation() {
+        let mut config = GlobalConfig::default();
+        assert!(config.proxy_for(Some(BUILTIN_DEMO_PROVIDER_ID)).is_none());
+
+        config.proxy = Some(ProxySettings {
+            url: "http://127.0.0.1:7890".to_string(),
+            bypass: vec!["localhost".to_string()],
+        });
+        config.providers[0].proxy = Some(ProxySettings {
+            url: "socks5://10.0.0.1:1080".to_string(),
+            bypass: vec![],
+        });
+        assert!(validate_proxies(&config).is_ok());
+        assert_eq!(
+            config.proxy_for(Some(BUILTIN_DEMO_PROVIDER_ID)).unwrap().url,
+            "socks5://10.0.0.1:1080"
+        );
+        assert_eq!(config.proxy_for(None).unwrap().url, "http://127.0.0.1:7890");
+
+        config.providers[0].proxy = Some(ProxySettings::default());
+        assert_eq!(
+            config.proxy_for(Some(BUILTIN_DEMO_PROVIDER_ID)).unwrap().url,
+            "http://127.0.0.1:7890"
+        );
+
+        for bad in ["127.0.0.1:7890", "ftp://proxy:21", "http://"] {
+            config.proxy = Some(ProxySettings {
+                url: bad.to_string(),
+                bypass: vec![],
+            });
+            assert!(validate_proxies(&config).is_err(), "{bad} should be rejected");
+        }
+    }
+
+    #[test]
+    fn reorder_providers_requires_every_id_once() {
+        let mut config = GlobalConfig::default();
+        for id in ["a", "b"] {
+            config.providers.push(Provider {
+                id: id.to_string(),
+                name: id.to_string(),
+                base_url: "http://localhost:3000".to_string(),
+                models: vec![],
+                models_updated_at: None,
+                provider_type: ProviderType::OpenaiCompatible,
+                headers: None,
+                default_parameters: None,
+                proxy: None,
+                favorite: false,
+                hidden_models: vec![],
+                model_aliases: HashMap::new(),
+                default_model: None,
+                api_key_env: None,
+                max_concurrent_requests: None,
+                min_request_interval_ms: None,
+                api_version: None,
+                deployments: HashMap::new(),
+            });
+        }
+        let active = config.active_provider_id.clone();
+
+        let ids = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(reorder_providers(&mut config, &ids(&["b", "a"])).is_err());
+        assert!(reorder_providers(&mut config, &ids(&["b", "b", "a"])).is_err());
+        assert!(reorder_providers(&mut config, &ids(&["b", "x", "a"])).is_err());
+        assert_eq!(config.providers.len(), 3);
+
+        reorder_providers(&mut config, &ids(&["b", BUILTIN_DEMO_PROVIDER_ID, "a"])).unwrap();
+        let order: Vec<&str> = config.providers.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(order, vec!["b", BUILTIN_DEMO_PROVIDER_ID, "a"]);
+        assert_eq!(config.active_provider_id, active);
+    }
+
+    #[test]
+    fn refreshed_models_keep_settings_for_surviving_models() {
+        let mut provider = builtin_demo_provider();
+        provider.set_models(vec!["m1".to_string(), "m2".to_string(), "embed".to_string()]);
+        provider.hidden_models = vec!["embed".to_string()];
+        provider.model_aliases = HashMap::from([
+            ("Fast".to_string(), "m1".to_string()),
+            ("Smart".to_string(), "m2".to_string()),
+        ]);
+        provider.default_model = Some("m2".to_string());
+
+        let visible = provider.visible_models();
+        assert_eq!(
+            visible.models,
+            vec![
+                ModelOption { id: "m1".to_string(), label: "Fast".to_string() },
+                ModelOption { id: "m2".to_string(), label: "Smart".to_string() },
+            ]
+        );
+        assert_eq!(visible.default_model.as_deref(), Some("m2"));
+
+        provider.set_models(vec!["m1".to_string(), "embed".to_string(), "m3".to_string()]);
+        assert_eq!(provider.hidden_models, vec!["embed".to_string()]);
+        assert_eq!(provider.model_aliases.len(), 1);
+        assert!(provider.default_model.is_none());
+    }
+
     #[test]
     fn default_config_contains_builtin_dashscope_demo_provider() {
         let config = GlobalConfig::default();
@@ -292,6 +1228,17 @@ mod tests {
                 models_updated_at: None,
                 provider_type: ProviderType::Anthropic,
                 headers: None,
+                default_parameters: None,
+                proxy: None,
+                favorite: false,
+                hidden_models: vec![],
+                model_aliases: HashMap::new(),
+                default_model: None,
+                api_key_env: None,
+                max_concurrent_requests: None,
+                min_request_interval_ms: None,
+                api_version: None,
+                deployments: HashMap::new(),
             }],
             active_provider_id: Some("provider_legacy".to_string()),
             default_parameters: ModelParameters {
@@ -301,6 +1248,10 @@ mod tests {
                 top_k: None,
                 max_tokens: 2000,
             },
+            parameter_profiles: vec![],
+            proxy: None,
+            reference_roots: vec![],
+            import_patterns: vec![],
         };
 
         let changed = ensure_builtin_demo_provider(&mut config, false);
@@ -329,9 +1280,24 @@ mod tests {
                     "x-test".to_string(),
                     "1".to_string(),
                 )])),
+                default_parameters: None,
+                proxy: None,
+                favorite: false,
+                hidden_models: vec![],
+                model_aliases: HashMap::new(),
+                default_model: None,
+                api_key_env: None,
+                max_concurrent_requests: None,
+                min_request_interval_ms: None,
+                api_version: None,
+                deployments: HashMap::new(),
             }],
             active_provider_id: Some(BUILTIN_DEMO_PROVIDER_ID.to_string()),
             default_parameters: ModelParameters::default(),
+            parameter_profiles: vec![],
+            proxy: None,
+            reference_roots: vec![],
+            import_patterns: vec![],
         };
 
         let changed = ensure_builtin_demo_provider(&mut config, false);
@@ -347,5 +1313,119 @@ mod tests {
         ));
         assert!(config.providers[0].headers.is_none());
     }
-}
 
+    #[test]
+    fn provider_headers_are_validated() {
+        let ok = validate_provider_headers(
+            HashMap::from([(" X-Relay-Token ".to_string(), " abc ".to_string())]),
+            false,
+        )
+        .unwrap();
+        assert_eq!(ok.get("X-Relay-Token").map(String::as_str), Some("abc"));
+
+        let newline = HashMap::from([("X-Relay".to_string(), "a\r\nb".to_string())]);
+        assert!(validate_provider_headers(newline, false).is_err());
+
+        let bad_name = HashMap::from([("X Relay".to_string(), "a".to_string())]);
+        assert!(validate_provider_headers(bad_name, false).is_err());
+
+        let auth = HashMap::from([("authorization".to_string(), "Bearer x".to_string())]);
+        assert!(validate_provider_headers(auth.clone(), false).is_err());
+        assert!(validate_provider_headers(auth, true).is_ok());
+    }
+
+    #[test]
+    fn default_parameters_become_the_default_profile() {
+        let mut value = serde_json::json!({
+            "schema_version": 1,
+            "providers": [],
+            "active_provider_id": null,
+            "default_parameters": ModelParameters {
+                temperature: 0.3,
+                ..ModelParameters::default()
+            }
+        });
+        migrate_config_value(&mut value).expect("migrate v1");
+        let mut config: GlobalConfig = serde_json::from_value(value).expect("deserialize migrated");
+        assert_eq!(config.parameter_profiles.len(), 1);
+        assert!(config.parameter_profiles[0].is_default);
+        assert_eq!(config.parameter_profiles[0].parameters.temperature, 0.3);
+
+        let brainstorm = config
+            .save_parameter_profile(NamedParameters {
+                id: String::new(),
+                name: "头脑风暴".to_string(),
+                parameters: ModelParameters {
+                    temperature: 1.0,
+                    max_tokens: 4096,
+                    ..ModelParameters::default()
+                },
+                is_default: true,
+            })
+            .expect("save profile");
+        assert!(!brainstorm.id.is_empty());
+        assert_eq!(config.default_parameters.max_tokens, 4096);
+        assert_eq!(config.parameter_profiles.iter().filter(|p| p.is_default).count(), 1);
+
+        assert!(config.delete_parameter_profile(&brainstorm.id).is_err());
+        config
+            .delete_parameter_profile(DEFAULT_PROFILE_ID)
+            .expect("former default can be deleted");
+
+        config.parameter_profiles.clear();
+        assert!(ensure_default_profile(&mut config));
+        assert_eq!(config.parameter_profiles[0].parameters, config.default_parameters);
+    }
+
+    #[test]
+    fn import_patterns_are_validated_and_default_to_empty() {
+        let mut config: GlobalConfig = serde_json::from_value(serde_json::json!({
+            "schema_version": CURRENT_SCHEMA_VERSION,
+            "providers": [],
+            "active_provider_id": null,
+            "default_parameters": ModelParameters::default()
+        }))
+        .expect("config without import_patterns");
+        assert!(config.import_patterns.is_empty());
+
+        let pattern = |id: &str, name: &str, pattern: &str| ImportPattern {
+            id: id.to_string(),
+            name: name.to_string(),
+            pattern: pattern.to_string(),
+        };
+        let err = config.save_import_pattern(pattern("", "某站", "^第(.+章")).unwrap_err();
+        assert!(err.starts_with("Invalid regex pattern"), "{err}");
+        assert!(config.save_import_pattern(pattern("", " ", "^第.+章")).is_err());
+
+        let saved = config
+            .save_import_pattern(pattern("", " 某站 ", r"^正文\s+第.+章.*"))
+            .expect("save pattern");
+        assert!(!saved.id.is_empty());
+        assert_eq!(saved.name, "某站");
+        assert!(config.save_import_pattern(pattern("", "某站", "^卷.+")).is_err());
+
+        let renamed = config
+            .save_import_pattern(pattern(&saved.id, "某站（新）", "^第.+章.*"))
+            .expect("update pattern");
+        assert_eq!(config.import_patterns, vec![renamed]);
+
+        config.delete_import_pattern(&saved.id).expect("delete pattern");
+        assert!(config.import_patterns.is_empty());
+        assert!(config.delete_import_pattern(&saved.id).is_err());
+    }
+
+    #[test]
+    fn provider_type_tags_stay_kebab_case() {
+        for (tag, expected) in [
+            ("openai-compatible", "openai-compatible"),
+            ("google", "google"),
+            ("anthropic", "anthropic"),
+            ("azure-openai", "azure-openai"),
+            ("ollama", "ollama"),
+        ] {
+            let parsed: ProviderType = serde_json::from_value(serde_json::json!(tag)).unwrap();
+            assert_eq!(parsed.as_str(), expected);
+            assert_eq!(serde_json::to_value(&parsed).unwrap(), serde_json::json!(tag));
+        }
+    }
+}
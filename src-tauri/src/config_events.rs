@@ -0,0 +1,185 @@
+//! `config:changed` notifications so every open window sees config edits.
+//!
+//! Commands that save the config go through [`save`], which emits the event after a
+//! successful write. A background thread also polls `config.json` and reports edits made
+//! outside the app once the file has stopped changing.
+
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter};
+
+use crate::config::{self, GlobalConfig};
+
+pub const CONFIG_CHANGED_EVENT: &str = "config:changed";
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+/// Hash of the config file content last written or seen by this process.
+static LAST_CONTENT_HASH: Mutex<Option<u64>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigSection {
+    /// Unknown or several sections: a full save, restore, import or external edit.
+    All,
+    Providers,
+    ActiveProvider,
+    Parameters,
+    ImportPatterns,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigChanged {
+    pub section: ConfigSection,
+    pub active_provider_id: Option<String>,
+    /// True when the file was edited outside the app.
+    pub external: bool,
+}
+
+/// Registers the app handle used for emitting and starts the file watcher.
+pub fn init(app: AppHandle) {
+    if APP_HANDLE.set(app).is_err() {
+        return;
+    }
+    if let Err(e) = std::thread::Builder::new()
+        .name("config-watcher".to_string())
+        .spawn(watch_config_file)
+    {
+        eprintln!("[config] Failed to start config watcher: {e}");
+    }
+}
+
+/// Saves the config and tells open windows which section changed.
+pub fn save(config: &GlobalConfig, section: ConfigSection) -> Result<(), String> {
+    config::save_config(config)?;
+    notify(section, config);
+    Ok(())
+}
+
+/// Emits `config:changed` for a config that is already on disk.
+pub fn notify(section: ConfigSection, config: &GlobalConfig) {
+    remember_current_file();
+    emit(section, config, false);
+}
+
+fn emit(section: ConfigSection, config: &GlobalConfig, external: bool) {
+    let Some(app) = APP_HANDLE.get() else {
+        return;
+    };
+    let payload = ConfigChanged {
+        section,
+        active_provider_id: config.active_provider_id.clone(),
+        external,
+    };
+    if let Err(e) = app.emit(CONFIG_CHANGED_EVENT, payload) {
+        eprintln!("[config] Failed to emit {CONFIG_CHANGED_EVENT}: {e}");
+    }
+}
+
+fn content_hash(content: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Records `content` as known. Returns false when it was already the last known content.
+fn remember_content(content: &[u8]) -> bool {
+    let hash = content_hash(content);
+    let mut last = LAST_CONTENT_HASH.lock().unwrap_or_else(|e| e.into_inner());
+    if *last == Some(hash) {
+        return false;
+    }
+    *last = Some(hash);
+    true
+}
+
+fn remember_current_file() {
+    let content = config::get_config_path().and_then(|p| fs::read(p).map_err(|e| e.to_string()));
+    if let Ok(content) = content {
+        remember_content(&content);
+    }
+}
+
+fn modified_at(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn watch_config_file() {
+    let path = match config::get_config_path() {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("[config] Config watcher disabled: {e}");
+            return;
+        }
+    };
+    remember_current_file();
+    let mut last_modified = modified_at(&path);
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        let mut modified = modified_at(&path);
+        if modified == last_modified {
+            continue;
+        }
+        // Wait for the editor to finish writing before reading.
+        loop {
+            std::thread::sleep(DEBOUNCE);
+            let now = modified_at(&path);
+            if now == modified {
+                break;
+            }
+            modified = now;
+        }
+        last_modified = modified;
+
+        let Ok(content) = fs::read(&path) else {
+            continue;
+        };
+        if !remember_content(&content) {
+            continue;
+        }
+        // A half-finished hand edit must not send load_config into its corrupt-file recovery.
+        if let Err(e) = config::parse_config(&String::from_utf8_lossy(&content)) {
+            eprintln!("[config] Ignoring external config edit: {e}");
+            continue;
+        }
+        match config::load_config() {
+            Ok(config) => {
+                // load_config may have rewritten the file while migrating it.
+                remember_current_file();
+                last_modified = modified_at(&path);
+                emit(ConfigSection::All, &config, true);
+            }
+            Err(e) => eprintln!("[config] Failed to reload edited config: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_uses_camel_case_fields_and_snake_case_sections() {
+        let payload = ConfigChanged {
+            section: ConfigSection::ActiveProvider,
+            active_provider_id: Some("p1".to_string()),
+            external: false,
+        };
+        let value = serde_json::to_value(payload).expect("serialize");
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "section": "active_provider",
+                "activeProviderId": "p1",
+                "external": false
+            })
+        );
+    }
+}
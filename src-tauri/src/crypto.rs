@@ -0,0 +1,125 @@
+//! Passphrase-based encryption for secrets that leave the OS keychain.
+//!
+//! PBKDF2-HMAC-SHA256 derives an AES-256-GCM key from the passphrase; salt and nonce are
+//! random per blob and stored alongside the ciphertext.
+
+use ring::aead::{self, Aad, LessSafeKey, Nonce, UnboundKey};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::num::NonZeroU32;
+
+const PBKDF2_ITERATIONS: u32 = 210_000;
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptedBlob {
+    pub iterations: u32,
+    /// Hex-encoded.
+    pub salt: String,
+    /// Hex-encoded.
+    pub nonce: String,
+    /// Hex-encoded ciphertext with the GCM tag appended.
+    pub ciphertext: String,
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn from_hex(raw: &str) -> Result<Vec<u8>, String> {
+    if raw.len() % 2 != 0 {
+        return Err("Invalid hex string".to_string());
+    }
+    (0..raw.len())
+        .step_by(2)
+        .map(|i| {
+            raw.get(i..i + 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| "Invalid hex string".to_string())
+        })
+        .collect()
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> Result<LessSafeKey, String> {
+    let iterations = NonZeroU32::new(iterations).ok_or("Invalid key derivation iteration count")?;
+    let mut key_bytes = [0u8; KEY_LEN];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        passphrase.as_bytes(),
+        &mut key_bytes,
+    );
+    let unbound = UnboundKey::new(&aead::AES_256_GCM, &key_bytes)
+        .map_err(|_| "Failed to create encryption key".to_string())?;
+    Ok(LessSafeKey::new(unbound))
+}
+
+pub fn encrypt_with_passphrase(
+    passphrase: &str,
+    plaintext: &[u8],
+) -> Result<EncryptedBlob, String> {
+    if passphrase.is_empty() {
+        return Err("Passphrase is empty".to_string());
+    }
+    let rng = SystemRandom::new();
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; aead::NONCE_LEN];
+    rng.fill(&mut salt)
+        .and_then(|_| rng.fill(&mut nonce))
+        .map_err(|_| "Failed to generate random bytes".to_string())?;
+
+    let key = derive_key(passphrase, &salt, PBKDF2_ITERATIONS)?;
+    let mut in_out = plaintext.to_vec();
+    key.seal_in_place_append_tag(
+        Nonce::assume_unique_for_key(nonce),
+        Aad::empty(),
+        &mut in_out,
+    )
+    .map_err(|_| "Encryption failed".to_string())?;
+
+    Ok(EncryptedBlob {
+        iterations: PBKDF2_ITERATIONS,
+        salt: to_hex(&salt),
+        nonce: to_hex(&nonce),
+        ciphertext: to_hex(&in_out),
+    })
+}
+
+pub fn decrypt_with_passphrase(passphrase: &str, blob: &EncryptedBlob) -> Result<Vec<u8>, String> {
+    let salt = from_hex(&blob.salt)?;
+    let nonce: [u8; aead::NONCE_LEN] = from_hex(&blob.nonce)?
+        .try_into()
+        .map_err(|_| "Invalid nonce length".to_string())?;
+    let mut in_out = from_hex(&blob.ciphertext)?;
+
+    let key = derive_key(passphrase, &salt, blob.iterations)?;
+    let plaintext = key
+        .open_in_place(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::empty(),
+            &mut in_out,
+        )
+        .map_err(|_| "Wrong passphrase or corrupted data".to_string())?;
+    Ok(plaintext.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip_and_wrong_passphrase() {
+        let blob = encrypt_with_passphrase("correct horse", b"sk-secret").expect("encrypt");
+        assert_ne!(blob.ciphertext, to_hex(b"sk-secret"));
+        assert_eq!(
+            decrypt_with_passphrase("correct horse", &blob).expect("decrypt"),
+            b"sk-secret"
+        );
+        let err = decrypt_with_passphrase("wrong", &blob).expect_err("wrong passphrase");
+        assert!(err.contains("Wrong passphrase"));
+    }
+}
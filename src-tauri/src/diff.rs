@@ -0,0 +1,281 @@
+use serde::Serialize;
+
+/// Unchanged lines shown around each change.
+const CONTEXT_LINES: usize = 3;
+/// Beyond this many edits the changed middle is reported as one replacement, which keeps
+/// the search's memory bounded for unrelated texts.
+const MAX_EDIT_DISTANCE: usize = 1000;
+/// Lines returned across all hunks before the diff is cut off.
+const MAX_DIFF_LINES: usize = 5000;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DiffLineKind {
+    Context,
+    Removed,
+    Added,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    pub text: String,
+}
+
+/// A run of changes with its context, numbered like a unified diff (1-based).
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffHunk {
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TextDiff {
+    pub hunks: Vec<DiffHunk>,
+    /// Hunks were cut off after [`MAX_DIFF_LINES`] lines.
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Op {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// Shortest edit script from `a` to `b` (Myers), or `None` past [`MAX_EDIT_DISTANCE`].
+fn myers<T: PartialEq>(a: &[T], b: &[T]) -> Option<Vec<Op>> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let limit = (a.len() + b.len()).min(MAX_EDIT_DISTANCE);
+    let offset = limit as isize + 1;
+    let mut v = vec![0isize; 2 * limit + 3];
+    let mut trace = Vec::new();
+
+    for d in 0..=limit as isize {
+        trace.push(v.clone());
+        for k in (-d..=d).step_by(2) {
+            let idx = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && v[idx - 1] < v[idx + 1]) {
+                v[idx + 1]
+            } else {
+                v[idx - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[idx] = x;
+            if x >= n && y >= m {
+                return Some(backtrack(&trace, n, m, offset));
+            }
+        }
+    }
+    None
+}
+
+fn backtrack(trace: &[Vec<isize>], n: isize, m: isize, offset: isize) -> Vec<Op> {
+    let (mut x, mut y) = (n, m);
+    let mut ops = Vec::new();
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let idx = (k + offset) as usize;
+        let prev_k = if k == -d || (k != d && v[idx - 1] < v[idx + 1]) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = v[(prev_k + offset) as usize];
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            ops.push(Op::Equal);
+            x -= 1;
+            y -= 1;
+        }
+        if d > 0 {
+            ops.push(if x == prev_x { Op::Insert } else { Op::Delete });
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+    ops.reverse();
+    ops
+}
+
+/// Edit script from `a` to `b`: the common prefix and suffix, with Myers in between. Past
+/// [`MAX_EDIT_DISTANCE`] the middle is deleted whole and then inserted whole.
+pub(crate) fn edit_script<T: PartialEq>(a: &[T], b: &[T]) -> Vec<Op> {
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (a_mid, b_mid) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+    let mut ops = vec![Op::Equal; prefix];
+    match myers(a_mid, b_mid) {
+        Some(middle) => ops.extend(middle),
+        None => {
+            ops.resize(ops.len() + a_mid.len(), Op::Delete);
+            ops.resize(ops.len() + b_mid.len(), Op::Insert);
+        }
+    }
+    ops.resize(ops.len() + suffix, Op::Equal);
+    ops
+}
+
+/// Line diff from `old` to `new`, grouped into hunks with [`CONTEXT_LINES`] of context.
+pub fn diff_lines(old: &str, new: &str) -> TextDiff {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    let ops = edit_script(&a, &b);
+
+    // Position in `a` and `b` before each op.
+    let mut positions = Vec::with_capacity(ops.len());
+    let (mut i, mut j) = (0, 0);
+    for op in &ops {
+        positions.push((i, j));
+        match op {
+            Op::Equal => {
+                i += 1;
+                j += 1;
+            }
+            Op::Delete => i += 1,
+            Op::Insert => j += 1,
+        }
+    }
+
+    // Op ranges to show: each change widened by the context, overlapping ones merged.
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for (n, _) in ops.iter().enumerate().filter(|(_, op)| **op != Op::Equal) {
+        let start = n.saturating_sub(CONTEXT_LINES);
+        let end = (n + CONTEXT_LINES + 1).min(ops.len());
+        match ranges.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+
+    let mut hunks = Vec::new();
+    let mut emitted = 0;
+    let mut truncated = false;
+    'ranges: for (start, end) in ranges {
+        let (old_start, new_start) = positions[start];
+        let mut hunk = DiffHunk {
+            old_start: old_start + 1,
+            old_lines: 0,
+            new_start: new_start + 1,
+            new_lines: 0,
+            lines: Vec::new(),
+        };
+        for n in start..end {
+            if emitted == MAX_DIFF_LINES {
+                truncated = true;
+                if !hunk.lines.is_empty() {
+                    hunks.push(hunk);
+                }
+                break 'ranges;
+            }
+            let (i, j) = positions[n];
+            let (kind, text) = match ops[n] {
+                Op::Equal => {
+                    hunk.old_lines += 1;
+                    hunk.new_lines += 1;
+                    (DiffLineKind::Context, a[i])
+                }
+                Op::Delete => {
+                    hunk.old_lines += 1;
+                    (DiffLineKind::Removed, a[i])
+                }
+                Op::Insert => {
+                    hunk.new_lines += 1;
+                    (DiffLineKind::Added, b[j])
+                }
+            };
+            hunk.lines.push(DiffLine {
+                kind,
+                text: text.to_string(),
+            });
+            emitted += 1;
+        }
+        hunks.push(hunk);
+    }
+    TextDiff { hunks, truncated }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(diff: &TextDiff) -> Vec<String> {
+        diff.hunks
+            .iter()
+            .flat_map(|h| {
+                let header = format!("@@ -{},{} +{},{} @@", h.old_start, h.old_lines, h.new_start, h.new_lines);
+                std::iter::once(header).chain(h.lines.iter().map(|l| {
+                    let sign = match l.kind {
+                        DiffLineKind::Context => ' ',
+                        DiffLineKind::Removed => '-',
+                        DiffLineKind::Added => '+',
+                    };
+                    format!("{sign}{}", l.text)
+                }))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn hunks_carry_context_and_unified_line_numbers() {
+        let old = (1..=12).map(|n| format!("line {n}")).collect::<Vec<_>>().join("\n");
+        let new = old
+            .replace("line 2\n", "line two\n")
+            .replace("line 11\n", "")
+            .replace("line 12", "line 12\nline 13");
+
+        let diff = diff_lines(&old, &new);
+        assert!(!diff.truncated);
+        assert_eq!(
+            render(&diff),
+            [
+                "@@ -1,5 +1,5 @@",
+                " line 1",
+                "-line 2",
+                "+line two",
+                " line 3",
+                " line 4",
+                " line 5",
+                "@@ -8,5 +8,5 @@",
+                " line 8",
+                " line 9",
+                " line 10",
+                "-line 11",
+                " line 12",
+                "+line 13",
+            ]
+        );
+        assert!(diff_lines(&old, &old).hunks.is_empty());
+    }
+
+    #[test]
+    fn handles_empty_sides_and_unrelated_texts() {
+        assert_eq!(render(&diff_lines("", "a\nb")), ["@@ -1,0 +1,2 @@", "+a", "+b"]);
+        assert_eq!(render(&diff_lines("a", "")), ["@@ -1,1 +1,0 @@", "-a"]);
+
+        // Past the edit limit everything in between becomes one replacement.
+        let old = (0..1500).map(|n| format!("o{n}")).collect::<Vec<_>>().join("\n");
+        let new = (0..1500).map(|n| format!("n{n}")).collect::<Vec<_>>().join("\n");
+        let diff = diff_lines(&old, &new);
+        assert_eq!(diff.hunks.len(), 1);
+        assert_eq!((diff.hunks[0].old_lines, diff.hunks[0].new_lines), (1500, 1500));
+        assert_eq!(diff.hunks[0].lines[0].kind, DiffLineKind::Removed);
+    }
+}
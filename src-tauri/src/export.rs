@@ -0,0 +1,417 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::Emitter;
+
+use crate::activity_log::{self, Actor};
+use crate::chapter::{get_chapter_content_sync, list_chapters_sync};
+use crate::project::{ChapterMeta, ChapterStatus};
+use crate::write_protection;
+
+const DEFAULT_HEADING_TEMPLATE: &str = "第{n}章 {title}";
+const EXPORT_PROGRESS_EVENT: &str = "creatorai:exportProgress";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Txt,
+    Markdown,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExportOptions {
+    /// `{n}` is the chapter's position in the export (1-based), `{title}` its title.
+    /// Defaults to `第{n}章 {title}`.
+    pub heading_template: Option<String>,
+    /// Blank lines between chapters; defaults to 1.
+    pub blank_lines: Option<u32>,
+    /// Leave out chapters with fewer words than this.
+    pub min_words: Option<u32>,
+    /// Leave out chapters whose status is not `done`.
+    pub only_done: bool,
+    /// Replace an existing file at the output path.
+    pub overwrite: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportProgress {
+    pub request_id: String,
+    pub total: u32,
+    pub completed: u32,
+    pub current_title: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportResult {
+    pub output_path: String,
+    pub chapter_count: u32,
+    /// Chapters left out by `minWords` / `onlyDone`.
+    pub skipped: u32,
+    /// Sum of the exported chapters' word counts.
+    pub word_count: u32,
+    /// Size of the written file.
+    pub bytes: u64,
+    /// Bookkeeping that could not be updated after the export.
+    pub warnings: Vec<String>,
+}
+
+fn render_heading(template: &str, n: usize, title: &str) -> String {
+    template.replace("{n}", &n.to_string()).replace("{title}", title).trim().to_string()
+}
+
+fn included(chapter: &ChapterMeta, options: &ExportOptions) -> bool {
+    if options.only_done && chapter.status != ChapterStatus::Done {
+        return false;
+    }
+    chapter.word_count >= options.min_words.unwrap_or(0)
+}
+
+/// The output file is written outside the project on purpose, so it is only checked for a
+/// usable parent directory and an existing file that would be replaced.
+fn check_output_path(output_path: &str, overwrite: bool) -> Result<PathBuf, String> {
+    let path = PathBuf::from(output_path);
+    if !path.is_absolute() {
+        return Err(format!("Output path must be absolute: '{output_path}'"));
+    }
+    let parent = path
+        .parent()
+        .ok_or_else(|| format!("Invalid output path: '{output_path}'"))?;
+    if !parent.is_dir() {
+        return Err(format!("Output directory does not exist: '{}'", parent.display()));
+    }
+    if path.is_dir() {
+        return Err(format!("'{output_path}' is a directory"));
+    }
+    if path.exists() && !overwrite {
+        return Err(format!(
+            "'{output_path}' already exists; export with overwrite to replace it"
+        ));
+    }
+    Ok(path)
+}
+
+/// Writes `chapters` (already in export order) to `output`. `on_progress` is called with
+/// `(completed, total, title)` after each chapter.
+fn write_export(
+    project_path: &str,
+    chapters: &[ChapterMeta],
+    format: ExportFormat,
+    output: &Path,
+    options: &ExportOptions,
+    default_template: &str,
+    mut on_progress: impl FnMut(u32, u32, &str),
+) -> Result<(u64, u32), String> {
+    let total = chapters.len() as u32;
+    let template = options.heading_template.as_deref().unwrap_or(default_template);
+    let separator = "\n".repeat(options.blank_lines.unwrap_or(1) as usize);
+    let mut blocks = Vec::with_capacity(chapters.len());
+    for (i, chapter) in chapters.iter().enumerate() {
+        let content = get_chapter_content_sync(project_path.to_string(), chapter.id.clone())
+            .map_err(|e| format!("Failed to export {}: {e}", chapter.id))?;
+        let heading = render_heading(template, i + 1, &chapter.title);
+        let heading = match format {
+            ExportFormat::Txt => heading,
+            ExportFormat::Markdown => format!("## {heading}"),
+        };
+        let body = content.trim_end();
+        blocks.push(if body.is_empty() {
+            format!("{heading}\n")
+        } else {
+            format!("{heading}\n\n{body}\n")
+        });
+        on_progress((i + 1) as u32, total, &chapter.title);
+    }
+
+    let text = blocks.join(&separator);
+    write_protection::atomic_write_bytes(output, text.as_bytes(), None)?;
+    Ok((text.len() as u64, chapters.iter().map(|c| c.word_count).sum()))
+}
+
+/// Records a finished export in the project's activity log under its output path.
+fn log_export(project_path: &str, output: &Path, bytes: u64) -> Vec<String> {
+    activity_log::record(
+        Path::new(project_path),
+        Actor::User,
+        "export",
+        &output.to_string_lossy(),
+        bytes as i64,
+    )
+    .into_iter()
+    .collect()
+}
+
+/// Concatenates the project's chapters in index order into one file.
+fn export_project_sync(
+    project_path: String,
+    format: ExportFormat,
+    output_path: String,
+    options: ExportOptions,
+    on_progress: impl FnMut(u32, u32, &str),
+) -> Result<ExportResult, String> {
+    let output = check_output_path(&output_path, options.overwrite)?;
+    let chapters = list_chapters_sync(project_path.clone())?;
+    let selected: Vec<ChapterMeta> = chapters
+        .iter()
+        .filter(|c| included(c, &options))
+        .cloned()
+        .collect();
+
+    let (bytes, word_count) = write_export(
+        &project_path,
+        &selected,
+        format,
+        &output,
+        &options,
+        DEFAULT_HEADING_TEMPLATE,
+        on_progress,
+    )?;
+    let warnings = log_export(&project_path, &output, bytes);
+    Ok(ExportResult {
+        output_path: output.to_string_lossy().to_string(),
+        chapter_count: selected.len() as u32,
+        skipped: (chapters.len() - selected.len()) as u32,
+        word_count,
+        bytes,
+        warnings,
+    })
+}
+
+/// Exports the given chapters in index order, whatever order the ids came in. Every id is
+/// checked against the index before anything is written, then `minWords` / `onlyDone` apply
+/// as in a full export. Headings default to the bare title, since numbering a partial export
+/// from 1 would mislabel the chapters.
+fn export_chapters_sync(
+    project_path: String,
+    chapter_ids: Vec<String>,
+    format: ExportFormat,
+    output_path: String,
+    options: ExportOptions,
+    on_progress: impl FnMut(u32, u32, &str),
+) -> Result<ExportResult, String> {
+    if chapter_ids.is_empty() {
+        return Err("chapter_ids is empty".to_string());
+    }
+    let chapters = list_chapters_sync(project_path.clone())?;
+    if let Some(unknown) = chapter_ids.iter().find(|id| !chapters.iter().any(|c| &c.id == *id)) {
+        return Err(format!("Chapter not found: {unknown}"));
+    }
+    let output = check_output_path(&output_path, options.overwrite)?;
+    let requested: Vec<ChapterMeta> = chapters
+        .into_iter()
+        .filter(|c| chapter_ids.contains(&c.id))
+        .collect();
+    let selected: Vec<ChapterMeta> = requested
+        .iter()
+        .filter(|c| included(c, &options))
+        .cloned()
+        .collect();
+
+    let (bytes, word_count) = write_export(
+        &project_path,
+        &selected,
+        format,
+        &output,
+        &options,
+        "{title}",
+        on_progress,
+    )?;
+    let warnings = log_export(&project_path, &output, bytes);
+    Ok(ExportResult {
+        output_path: output.to_string_lossy().to_string(),
+        chapter_count: selected.len() as u32,
+        skipped: (requested.len() - selected.len()) as u32,
+        word_count,
+        bytes,
+        warnings,
+    })
+}
+
+fn progress_emitter(window: tauri::Window, request_id: String) -> impl FnMut(u32, u32, &str) {
+    move |completed, total, title| {
+        let _ = window.emit(
+            EXPORT_PROGRESS_EVENT,
+            ExportProgress {
+                request_id: request_id.clone(),
+                total,
+                completed,
+                current_title: Some(title.to_string()),
+            },
+        );
+    }
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn export_project(
+    window: tauri::Window,
+    project_path: String,
+    format: ExportFormat,
+    output_path: String,
+    options: Option<ExportOptions>,
+    request_id: String,
+) -> Result<ExportResult, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        export_project_sync(
+            project_path,
+            format,
+            output_path,
+            options.unwrap_or_default(),
+            progress_emitter(window, request_id),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn export_chapters(
+    window: tauri::Window,
+    project_path: String,
+    chapter_ids: Vec<String>,
+    format: ExportFormat,
+    output_path: String,
+    options: Option<ExportOptions>,
+    request_id: String,
+) -> Result<ExportResult, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        export_chapters_sync(
+            project_path,
+            chapter_ids,
+            format,
+            output_path,
+            options.unwrap_or_default(),
+            progress_emitter(window, request_id),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use std::fs;
+
+    fn temp_project(prefix: &str) -> TempDir {
+        let temp = TempDir::new(prefix);
+        fs::create_dir_all(temp.path.join("MyNovel/.creatorai")).expect("create temp dir");
+        fs::create_dir_all(temp.path.join("MyNovel/chapters")).expect("create temp dir");
+        fs::write(temp.path.join("MyNovel/.creatorai/config.json"), "{}\n").expect("write config");
+        temp
+    }
+
+    /// Three chapters whose index order differs from their id order.
+    fn write_chapters(project_root: &Path) {
+        fs::write(
+            project_root.join("chapters/index.json"),
+            r#"{ "chapters": [
+  { "id": "chapter_001", "title": "尾声", "order": 3, "created": 1, "updated": 1, "wordCount": 2, "status": "done" },
+  { "id": "chapter_002", "title": "开端", "order": 1, "created": 1, "updated": 1, "wordCount": 4, "status": "done" },
+  { "id": "chapter_003", "title": "草稿", "order": 2, "created": 1, "updated": 1, "wordCount": 1 }
+], "nextId": 4 }
+"#,
+        )
+        .unwrap();
+        fs::write(project_root.join("chapters/chapter_001.txt"), "完结\n\n").unwrap();
+        fs::write(project_root.join("chapters/chapter_002.txt"), "雨夜\n出发").unwrap();
+        fs::write(project_root.join("chapters/chapter_003.txt"), "略").unwrap();
+    }
+
+    fn export(temp: &TempDir, format: ExportFormat, name: &str, options: ExportOptions) -> Result<(ExportResult, Vec<u32>), String> {
+        let mut progress = Vec::new();
+        let result = export_project_sync(
+            temp.path.join("MyNovel").to_string_lossy().to_string(),
+            format,
+            temp.path.join(name).to_string_lossy().to_string(),
+            options,
+            |completed, _, _| progress.push(completed),
+        )?;
+        Ok((result, progress))
+    }
+
+    #[test]
+    fn chapters_are_exported_in_index_order_with_headings() {
+        let temp = temp_project("creatorai-v2-export");
+        write_chapters(&temp.path.join("MyNovel"));
+
+        let (result, progress) = export(&temp, ExportFormat::Txt, "book.txt", ExportOptions::default()).unwrap();
+        assert_eq!((result.chapter_count, result.skipped), (3, 0));
+        assert_eq!(progress, [1, 2, 3]);
+        assert_eq!(
+            fs::read_to_string(temp.path.join("book.txt")).unwrap(),
+            "第1章 开端\n\n雨夜\n出发\n\n第2章 草稿\n\n略\n\n第3章 尾声\n\n完结\n"
+        );
+
+        let options = ExportOptions {
+            heading_template: Some("{title}".to_string()),
+            blank_lines: Some(2),
+            only_done: true,
+            ..Default::default()
+        };
+        let (result, _) = export(&temp, ExportFormat::Markdown, "book.md", options).unwrap();
+        assert_eq!((result.chapter_count, result.skipped), (2, 1));
+        assert_eq!(
+            fs::read_to_string(temp.path.join("book.md")).unwrap(),
+            "## 开端\n\n雨夜\n出发\n\n\n## 尾声\n\n完结\n"
+        );
+
+        let options = ExportOptions { min_words: Some(3), ..Default::default() };
+        let err = export(&temp, ExportFormat::Txt, "book.txt", options.clone()).unwrap_err();
+        assert!(err.contains("overwrite"), "{err}");
+        let options = ExportOptions { overwrite: true, ..options };
+        let (result, _) = export(&temp, ExportFormat::Txt, "book.txt", options).unwrap();
+        assert_eq!(result.chapter_count, 1);
+        assert_eq!(fs::read_to_string(temp.path.join("book.txt")).unwrap(), "第1章 开端\n\n雨夜\n出发\n");
+
+        let err = export(&temp, ExportFormat::Txt, "missing/book.txt", ExportOptions::default()).unwrap_err();
+        assert!(err.contains("does not exist"), "{err}");
+    }
+
+    #[test]
+    fn selected_chapters_keep_index_order() {
+        let temp = temp_project("creatorai-v2-export-selected");
+        let project_path = temp.path.join("MyNovel");
+        write_chapters(&project_path);
+        let run_with = |ids: &[&str], name: &str, options: ExportOptions| {
+            export_chapters_sync(
+                project_path.to_string_lossy().to_string(),
+                ids.iter().map(|id| id.to_string()).collect(),
+                ExportFormat::Markdown,
+                temp.path.join(name).to_string_lossy().to_string(),
+                options,
+                |_, _, _| {},
+            )
+        };
+        let run = |ids: &[&str], name: &str| run_with(ids, name, ExportOptions::default());
+
+        // The third chapter in the index (chapter_001) is asked for before the first.
+        let result = run(&["chapter_001", "chapter_002"], "part.md").unwrap();
+        let text = fs::read_to_string(temp.path.join("part.md")).unwrap();
+        assert_eq!(text, "## 开端\n\n雨夜\n出发\n\n## 尾声\n\n完结\n");
+        assert_eq!((result.chapter_count, result.word_count), (2, 6));
+        assert_eq!(result.bytes, text.len() as u64);
+        assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+        let logged = crate::activity_log::read_log(&project_path, 10, 0).unwrap();
+        assert_eq!(logged.len(), 1);
+        assert_eq!((logged[0].action.as_str(), logged[0].byte_delta), ("export", text.len() as i64));
+        assert_eq!(logged[0].path, result.output_path);
+
+        let err = run(&["chapter_001", "chapter_009"], "other.md").unwrap_err();
+        assert!(err.contains("chapter_009"), "{err}");
+        assert!(!temp.path.join("other.md").exists());
+
+        // Filters apply to the selection: chapter_003 is neither done nor long enough.
+        let options = ExportOptions { only_done: true, ..Default::default() };
+        let result = run_with(&["chapter_002", "chapter_003"], "done.md", options).unwrap();
+        assert_eq!((result.chapter_count, result.skipped), (1, 1));
+        let options = ExportOptions { min_words: Some(3), ..Default::default() };
+        let result = run_with(&["chapter_001", "chapter_002", "chapter_003"], "long.md", options).unwrap();
+        assert_eq!((result.chapter_count, result.skipped), (1, 2));
+        assert_eq!(
+            fs::read_to_string(temp.path.join("long.md")).unwrap(),
+            "## 开端\n\n雨夜\n出发\n"
+        );
+    }
+}
@@ -3,7 +3,7 @@ use std::fs::{self, File, OpenOptions};
 use std::io::{Read, Seek, SeekFrom, Write};
 use std::path::Path;
 
-use crate::security::validate_path;
+use crate::security::{check_not_protected, validate_path};
 use crate::write_protection;
 
 #[derive(Debug, Deserialize)]
@@ -18,6 +18,11 @@ pub fn append_file(project_dir: &Path, params: AppendParams) -> Result<(), Strin
         .map_err(|e| format!("Invalid project_dir: {e}"))?;
 
     let full_path = validate_path(&project_root, &params.path)?;
+    check_not_protected(&project_root, &params.path)?;
+    write_protection::ensure_writable(&project_root)?;
+    let current_len = fs::metadata(&full_path).map(|m| m.len()).unwrap_or(0);
+    // One extra byte for the newline that may be inserted first.
+    write_protection::check_write_size(&project_root, &full_path, current_len + params.content.len() as u64 + 1)?;
     let backup_path = write_protection::backup_existing_file(&project_root, &full_path)?;
 
     let result: Result<(), String> = (|| {
@@ -0,0 +1,29 @@
+use std::path::Path;
+
+use crate::chapter::{now_unix_seconds, read_index};
+use crate::write_protection::indexed_chapter_id;
+
+/// `chapters/index.json` with the word count and update time refreshed for each
+/// `(project-relative path, word count)` that is an indexed chapter file, or `None` when
+/// none of them is.
+pub(crate) fn refreshed_index(project_root: &Path, word_counts: &[(&str, u32)]) -> Result<Option<String>, String> {
+    let counts: Vec<(String, u32)> = word_counts
+        .iter()
+        .filter_map(|(relative, count)| indexed_chapter_id(project_root, relative).map(|id| (id, *count)))
+        .collect();
+    if counts.is_empty() {
+        return Ok(None);
+    }
+    let mut index = read_index(project_root)?;
+
+    let now = now_unix_seconds()?;
+    for meta in index.chapters.iter_mut() {
+        let Some((_, count)) = counts.iter().find(|(id, _)| *id == meta.id) else {
+            continue;
+        };
+        meta.word_count = *count;
+        meta.updated = now;
+    }
+    let json = serde_json::to_string_pretty(&index).map_err(|e| format!("Serialize JSON failed: {e}"))?;
+    Ok(Some(format!("{json}\n")))
+}
@@ -0,0 +1,53 @@
+use serde::Deserialize;
+use std::fs;
+use std::path::{Component, Path};
+
+use crate::security::{check_not_protected, validate_path};
+use crate::write_protection;
+
+/// Top-level directories holding project bookkeeping; nothing in them can be deleted.
+const UNDELETABLE_DIRS: &[&str] = &[".creatorai", ".backup"];
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteParams {
+    pub path: String,
+}
+
+/// Removes a file inside the project after copying it to `.backup/<ts>/<relative>`.
+/// Indexed chapter files are refused: deleting one here would leave its index entry behind.
+pub fn delete_file(project_dir: &Path, params: DeleteParams) -> Result<(), String> {
+    let project_root = project_dir
+        .canonicalize()
+        .map_err(|e| format!("Invalid project_dir: {e}"))?;
+
+    let full_path = validate_path(&project_root, &params.path)?;
+    let top = full_path
+        .strip_prefix(&project_root)
+        .ok()
+        .and_then(|relative| relative.components().next());
+    if let Some(Component::Normal(dir)) = top {
+        if UNDELETABLE_DIRS.iter().any(|d| dir.eq_ignore_ascii_case(d)) {
+            return Err(format!("Cannot delete '{}': project data is not deletable", params.path));
+        }
+    }
+    check_not_protected(&project_root, &params.path)?;
+    write_protection::ensure_writable(&project_root)?;
+    if let Ok(relative) = full_path.strip_prefix(&project_root) {
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        if let Some(id) = write_protection::indexed_chapter_id(&project_root, &relative) {
+            return Err(format!(
+                "'{}' is the text of chapter {id}; delete the chapter instead (delete_chapter) so the chapter index stays consistent",
+                params.path
+            ));
+        }
+    }
+
+    let meta = fs::symlink_metadata(&full_path)
+        .map_err(|e| format!("Failed to stat '{}': {e}", params.path))?;
+    if meta.file_type().is_dir() {
+        return Err(format!("'{}' is a directory", params.path));
+    }
+
+    write_protection::backup_existing_file(&project_root, &full_path)?;
+    fs::remove_file(&full_path).map_err(|e| format!("Failed to delete '{}': {e}", params.path))
+}
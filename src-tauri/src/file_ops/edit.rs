@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use super::chapter_index::refreshed_index;
+use crate::chapter::count_words;
+use crate::security::{check_not_protected, validate_path};
+use crate::write_protection;
+
+#[derive(Debug, Deserialize)]
+pub struct EditParams {
+    pub path: String,
+    /// First line to replace, 1-based.
+    pub start_line: u32,
+    /// Last line to replace, inclusive.
+    pub end_line: u32,
+    /// Replacement text; empty removes the lines.
+    pub content: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EditResult {
+    pub total_lines: u32,
+    pub word_count: u32,
+}
+
+/// `text` with lines `start..=end` (1-based) replaced by `content`, keeping the file's
+/// line ending style.
+fn replace_lines(text: &str, start: u32, end: u32, content: &str) -> Result<String, String> {
+    let lines: Vec<&str> = text.split_inclusive('\n').collect();
+    let total = lines.len() as u32;
+    if start == 0 || start > end || end > total {
+        return Err(format!(
+            "Line range {start}-{end} is outside the file ({total} lines)"
+        ));
+    }
+    let (start, end) = (start as usize, end as usize);
+
+    let eol = if text.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut next: String = lines[..start - 1].concat();
+    if !content.is_empty() {
+        next.push_str(&content.lines().collect::<Vec<_>>().join(eol));
+        if lines[end - 1].ends_with('\n') {
+            next.push_str(eol);
+        }
+    }
+    next.push_str(&lines[end..].concat());
+    Ok(next)
+}
+
+/// Replaces an inclusive line range of a text file. Chapter files get their index entry
+/// refreshed in the same transaction.
+pub fn edit_file(project_dir: &Path, params: EditParams) -> Result<EditResult, String> {
+    let project_root = project_dir
+        .canonicalize()
+        .map_err(|e| format!("Invalid project_dir: {e}"))?;
+
+    let full_path = validate_path(&project_root, &params.path)?;
+    check_not_protected(&project_root, &params.path)?;
+    write_protection::ensure_writable(&project_root)?;
+    write_protection::check_read_size(&full_path)?;
+
+    let text = match fs::read(&full_path) {
+        Ok(bytes) => String::from_utf8(bytes).map_err(|_| "Binary files are not supported".to_string())?,
+        Err(e) => return Err(format!("Failed to read file '{}': {e}", params.path)),
+    };
+    let next = replace_lines(&text, params.start_line, params.end_line, &params.content)?;
+    write_protection::check_write_size(&project_root, &full_path, next.len() as u64)?;
+    let result = EditResult {
+        total_lines: next.lines().count() as u32,
+        word_count: count_words(&next),
+    };
+
+    let relative = full_path
+        .strip_prefix(&project_root)
+        .map(|r| r.to_string_lossy().replace('\\', "/"))
+        .map_err(|_| format!("Invalid path: '{}'", params.path))?;
+    match refreshed_index(&project_root, &[(relative.as_str(), result.word_count)])? {
+        Some(index) => write_protection::transaction(&project_root)
+            .write(&relative, next)
+            .write("chapters/index.json", index)
+            .commit()?,
+        None => {
+            write_protection::write_string_with_backup(&project_root, &full_path, &next)?;
+        }
+    }
+    Ok(result)
+}
@@ -1,25 +1,53 @@
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::security::validate_path;
 
-const MAX_ENTRIES: usize = 100;
+/// Default page size.
+const MAX_ENTRIES: u32 = 100;
+/// Default page size for recursive listings, which cover a whole tree in one call; also
+/// the upper bound for `ListParams.limit`.
+const MAX_RECURSIVE_ENTRIES: u32 = 2000;
+/// Entries collected before the walk gives up and reports `truncated`.
+const MAX_SCANNED_ENTRIES: usize = 20_000;
+const DEFAULT_MAX_DEPTH: u32 = 8;
+/// Upper bound for `ListParams.max_depth`.
+const MAX_DEPTH_LIMIT: u32 = 32;
 
 #[derive(Debug, Deserialize)]
 pub struct ListParams {
     pub path: Option<String>,
+    /// Also list the contents of subdirectories, down to `max_depth` levels.
+    pub recursive: Option<bool>,
+    /// Levels to list when recursive; 1 is the directory itself. Defaults to
+    /// [`DEFAULT_MAX_DEPTH`], at most [`MAX_DEPTH_LIMIT`].
+    pub max_depth: Option<u32>,
+    /// Entries to skip, in the listing order (directories first, then by path).
+    pub offset: Option<u32>,
+    /// Page size, at most [`MAX_RECURSIVE_ENTRIES`].
+    pub limit: Option<u32>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct ListResult {
     pub entries: Vec<FileEntry>,
+    /// Entries across all pages.
+    pub total: u32,
+    /// Entries remain after this page.
+    pub has_more: bool,
+    /// The directory had more than [`MAX_SCANNED_ENTRIES`] entries and the rest were not
+    /// counted.
+    pub truncated: bool,
 }
 
 #[derive(Debug, Serialize)]
 pub struct FileEntry {
     pub name: String,
+    /// Project-relative path with forward slashes.
+    pub path: String,
     pub is_dir: bool,
     pub size: u64,
     pub modified: u64,
@@ -45,43 +73,80 @@ pub fn list_dir(project_dir: &Path, params: ListParams) -> Result<ListResult, St
         return Err(format!("'{}' is not a directory", relative));
     }
 
+    let (max_depth, default_limit) = if params.recursive.unwrap_or(false) {
+        let depth = params.max_depth.unwrap_or(DEFAULT_MAX_DEPTH).clamp(1, MAX_DEPTH_LIMIT);
+        (depth, MAX_RECURSIVE_ENTRIES)
+    } else {
+        (1, MAX_ENTRIES)
+    };
+    let limit = params.limit.unwrap_or(default_limit).clamp(1, MAX_RECURSIVE_ENTRIES) as usize;
+    let offset = params.offset.unwrap_or(0) as usize;
+    let project_root = validate_path(project_dir, "")?;
+    let base = full_path
+        .strip_prefix(&project_root)
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_default();
+
     let mut entries = Vec::new();
-    for entry in fs::read_dir(&full_path)
-        .map_err(|e| format!("Failed to read directory '{}': {e}", relative))?
-    {
-        if entries.len() >= MAX_ENTRIES {
-            break;
-        }
-        let entry = entry.map_err(|e| format!("Failed to read directory entry: {e}"))?;
-        let name = entry.file_name().to_string_lossy().to_string();
-        if name.starts_with('.') {
-            continue;
-        }
+    let mut truncated = false;
+    // Breadth-first, so a truncated walk still covers the upper levels.
+    let mut queue: VecDeque<(PathBuf, String, u32)> = VecDeque::from([(full_path, base, 1)]);
+    'dirs: while let Some((dir, dir_relative, depth)) = queue.pop_front() {
+        for entry in fs::read_dir(&dir)
+            .map_err(|e| format!("Failed to read directory '{}': {e}", dir_relative))?
+        {
+            let entry = entry.map_err(|e| format!("Failed to read directory entry: {e}"))?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') {
+                continue;
+            }
 
-        let file_type = entry
-            .file_type()
-            .map_err(|e| format!("Failed to stat directory entry '{}': {e}", name))?;
-        if file_type.is_symlink() {
-            continue;
-        }
-        if file_type.is_dir() && is_ignored_dir_name(&name) {
-            continue;
-        }
+            let file_type = entry
+                .file_type()
+                .map_err(|e| format!("Failed to stat directory entry '{}': {e}", name))?;
+            if file_type.is_symlink() {
+                continue;
+            }
+            if file_type.is_dir() && is_ignored_dir_name(&name) {
+                continue;
+            }
+            if entries.len() >= MAX_SCANNED_ENTRIES {
+                truncated = true;
+                break 'dirs;
+            }
 
-        let meta = entry
-            .metadata()
-            .map_err(|e| format!("Failed to read metadata for '{}': {e}", name))?;
+            let meta = entry
+                .metadata()
+                .map_err(|e| format!("Failed to read metadata for '{}': {e}", name))?;
+            let path = if dir_relative.is_empty() {
+                name.clone()
+            } else {
+                format!("{dir_relative}/{name}")
+            };
+            if file_type.is_dir() && depth < max_depth {
+                queue.push_back((entry.path(), path.clone(), depth + 1));
+            }
 
-        entries.push(FileEntry {
-            name,
-            is_dir: file_type.is_dir(),
-            size: if file_type.is_file() { meta.len() } else { 0 },
-            modified: meta
-                .modified()
-                .map(system_time_to_unix_seconds)
-                .unwrap_or(0),
-        });
+            entries.push(FileEntry {
+                name,
+                path,
+                is_dir: file_type.is_dir(),
+                size: if file_type.is_file() { meta.len() } else { 0 },
+                modified: meta
+                    .modified()
+                    .map(system_time_to_unix_seconds)
+                    .unwrap_or(0),
+            });
+        }
     }
 
-    Ok(ListResult { entries })
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.path.cmp(&b.path)));
+    let total = entries.len();
+    let page: Vec<FileEntry> = entries.into_iter().skip(offset).take(limit).collect();
+    Ok(ListResult {
+        has_more: offset.saturating_add(page.len()) < total,
+        total: total as u32,
+        entries: page,
+        truncated,
+    })
 }
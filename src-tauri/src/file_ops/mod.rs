@@ -1,11 +1,20 @@
 pub mod append;
+pub(crate) mod chapter_index;
+pub mod delete;
+pub mod edit;
 pub mod list;
 pub mod read;
+pub mod rename;
 pub mod search;
+pub mod stat;
 pub mod write;
 
 pub use append::{append_file, AppendParams};
+pub use delete::{delete_file, DeleteParams};
+pub use edit::{edit_file, EditParams, EditResult};
 pub use list::{list_dir, ListParams, ListResult};
 pub use read::{read_file, ReadParams, ReadResult};
+pub use rename::{rename_file, RenameParams};
 pub use search::{search_in_files, SearchParams, SearchResult};
+pub use stat::{stat_file, FileStat, StatParams};
 pub use write::{write_file, WriteParams};
@@ -1,9 +1,13 @@
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_8};
 use serde::{Deserialize, Serialize};
-use std::fs::File;
-use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::fs::{self, File};
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Cursor, Read, Seek, SeekFrom};
 use std::path::Path;
 
-use crate::security::validate_path;
+use crate::security::resolve_read_path;
+use crate::write_protection;
 
 const DEFAULT_LIMIT: u32 = 2000;
 const MAX_LINE_CHARS: usize = 2000;
@@ -15,17 +19,42 @@ pub struct ReadParams {
     pub path: String,
     pub offset: Option<i64>,
     pub limit: Option<u32>,
+    /// Switches to byte-range mode: raw text from this byte on, without line numbers.
+    pub byte_offset: Option<u64>,
+    /// Bytes to read in byte-range mode, from 4 (one character) up to [`MAX_OUTPUT_BYTES`].
+    pub byte_limit: Option<u32>,
+    /// Text encoding label such as `"utf-8"` or `"gbk"`, or `"auto"` to detect it. The file
+    /// is decoded whole, so the read size limit always applies. Ignored in byte-range mode,
+    /// which reads UTF-8.
+    pub encoding: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct ReadResult {
     pub content: String,
+    /// Not counted in byte-range mode, where it is 0.
     pub total_lines: u32,
     pub truncated: bool,
+    /// Byte-range mode only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_size: Option<u64>,
+    /// Byte-range mode only: where `content` starts, which is the requested offset moved
+    /// back to a character boundary. The next window starts at this plus `content.len()`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub byte_offset: Option<u64>,
+    /// The encoding the file was decoded as, when one was requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
 }
 
-fn read_line_strip_newline(
-    reader: &mut BufReader<File>,
+#[cfg(test)]
+thread_local! {
+    /// Lines read from files on this thread, for tests that check a read is one pass.
+    pub(crate) static LINES_READ: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+fn read_line_strip_newline<R: BufRead>(
+    reader: &mut R,
     buf: &mut String,
     path: &str,
 ) -> Result<usize, String> {
@@ -40,6 +69,8 @@ fn read_line_strip_newline(
     if bytes_read == 0 {
         return Ok(0);
     }
+    #[cfg(test)]
+    LINES_READ.with(|n| n.set(n.get() + 1));
 
     if buf.ends_with('\n') {
         buf.pop();
@@ -50,23 +81,44 @@ fn read_line_strip_newline(
     Ok(bytes_read)
 }
 
-fn count_total_lines(reader: &mut BufReader<File>, path: &str) -> Result<u64, String> {
-    let mut total: u64 = 0;
-    let mut line = String::new();
-    loop {
-        let bytes = read_line_strip_newline(reader, &mut line, path)?;
-        if bytes == 0 {
-            break;
-        }
-        total = total.saturating_add(1);
+/// `line` cut to [`MAX_LINE_CHARS`] with an ellipsis, and whether it was cut.
+fn clip_line(line: &str) -> (String, bool) {
+    match line.char_indices().nth(MAX_LINE_CHARS) {
+        Some((cut, _)) => (format!("{}...", &line[..cut]), true),
+        None => (line.to_string(), false),
     }
-    Ok(total)
 }
 
 pub fn read_file(project_dir: &Path, params: ReadParams) -> Result<ReadResult, String> {
-    let full_path = validate_path(project_dir, &params.path)?;
+    let full_path = resolve_read_path(project_dir, &params.path)?.full_path;
+    read_resolved(&full_path, params)
+}
 
-    let mut file = File::open(&full_path)
+/// [`read_file`] for a path the caller already validated; `params.path` is only used in
+/// messages.
+pub(crate) fn read_resolved(full_path: &Path, params: ReadParams) -> Result<ReadResult, String> {
+    if let Some(byte_offset) = params.byte_offset {
+        return read_byte_range(full_path, &params, byte_offset);
+    }
+    if let Some(label) = params.encoding.as_deref() {
+        write_protection::check_read_size(full_path)?;
+        let bytes = fs::read(full_path)
+            .map_err(|e| format!("Failed to read file '{}': {e}", params.path))?;
+        let decoded = decode_text(&bytes, Some(label))?;
+        // A detected encoding that still needed replacements means the bytes are not text.
+        let detected = label.trim().eq_ignore_ascii_case("auto");
+        if decoded.text.contains('\0') || (detected && decoded.lossy) {
+            return Err("Binary files are not supported".to_string());
+        }
+        let mut result = read_lines(Cursor::new(decoded.text.into_bytes()), &params)?;
+        result.encoding = Some(decoded.encoding.name().to_ascii_lowercase());
+        return Ok(result);
+    }
+    if params.offset.is_none() {
+        write_protection::check_read_size(full_path)?;
+    }
+
+    let mut file = File::open(full_path)
         .map_err(|e| format!("Failed to open file '{}': {e}", params.path))?;
 
     let mut probe = vec![0u8; BINARY_PROBE_BYTES];
@@ -79,53 +131,76 @@ pub fn read_file(project_dir: &Path, params: ReadParams) -> Result<ReadResult, S
 
     file.seek(SeekFrom::Start(0))
         .map_err(|e| format!("Failed to seek file '{}': {e}", params.path))?;
-    let limit = params.limit.unwrap_or(DEFAULT_LIMIT).min(DEFAULT_LIMIT);
-
-    let offset = params.offset.unwrap_or(0);
+    read_lines(BufReader::new(file), &params)
+}
 
-    if offset < 0 {
-        let mut reader = BufReader::new(file);
-        let total_lines_u64 = count_total_lines(&mut reader, &params.path)?;
-        let total_lines = u32::try_from(total_lines_u64).unwrap_or(u32::MAX);
+/// Text decoded by [`decode_text`].
+pub(crate) struct DecodedText {
+    pub text: String,
+    pub encoding: &'static Encoding,
+    /// Some bytes were not valid in `encoding` and became U+FFFD.
+    pub lossy: bool,
+}
 
-        let tail = if offset == i64::MIN {
-            u64::MAX
-        } else {
-            (-offset) as u64
-        };
-        let start_index = total_lines_u64.saturating_sub(tail);
+/// Decodes `bytes` as the encoding `label` names (a BOM still wins). Without a label, or
+/// with `"auto"`, the BOM decides, then valid UTF-8, then chardetng's guess, which tells
+/// GBK/GB18030 and Big5 apart. Shared by `file_read` and txt imports.
+pub(crate) fn decode_text(bytes: &[u8], label: Option<&str>) -> Result<DecodedText, String> {
+    let label = label
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.eq_ignore_ascii_case("auto"));
+    let encoding = match label {
+        Some(label) => Encoding::for_label(label.as_bytes())
+            .ok_or_else(|| format!("Unsupported encoding '{label}'"))?,
+        None => match Encoding::for_bom(bytes) {
+            Some((encoding, _)) => encoding,
+            None if std::str::from_utf8(bytes).is_ok() => UTF_8,
+            None => {
+                let mut detector = EncodingDetector::new();
+                detector.feed(bytes, true);
+                detector.guess(None, false)
+            }
+        },
+    };
+    let (text, encoding, lossy) = encoding.decode(bytes);
+    Ok(DecodedText {
+        text: text.into_owned(),
+        encoding,
+        lossy,
+    })
+}
 
-        let mut file = reader.into_inner();
-        file.seek(SeekFrom::Start(0))
-            .map_err(|e| format!("Failed to seek file '{}': {e}", params.path))?;
-        let mut reader = BufReader::new(file);
+/// Numbered lines from `reader` per `params.offset` and `params.limit`.
+fn read_lines<R: BufRead>(mut reader: R, params: &ReadParams) -> Result<ReadResult, String> {
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT).min(DEFAULT_LIMIT);
 
-        let mut content = String::new();
-        let mut truncated = total_lines_u64.saturating_sub(start_index) > u64::from(limit);
-        let mut collected: u32 = 0;
+    let offset = params.offset.unwrap_or(0);
 
+    if offset < 0 {
+        let tail = offset.unsigned_abs();
+        // The last `tail` lines seen so far, already clipped for display; one pass counts
+        // the lines and keeps the window.
+        let mut window: VecDeque<(u64, String, bool)> = VecDeque::new();
+        let mut total_lines_u64: u64 = 0;
         let mut line = String::new();
-        let mut line_index: u64 = 0;
-        while line_index < start_index {
+        loop {
             let bytes = read_line_strip_newline(&mut reader, &mut line, &params.path)?;
             if bytes == 0 {
                 break;
             }
-            line_index = line_index.saturating_add(1);
-        }
-
-        while collected < limit {
-            let bytes = read_line_strip_newline(&mut reader, &mut line, &params.path)?;
-            if bytes == 0 {
-                break;
+            if window.len() as u64 == tail {
+                window.pop_front();
             }
+            let (display, clipped) = clip_line(&line);
+            window.push_back((total_lines_u64, display, clipped));
+            total_lines_u64 = total_lines_u64.saturating_add(1);
+        }
+        let total_lines = u32::try_from(total_lines_u64).unwrap_or(u32::MAX);
 
-            let mut display = line.clone();
-            if display.chars().count() > MAX_LINE_CHARS {
-                display = display.chars().take(MAX_LINE_CHARS).collect::<String>();
-                display.push_str("...");
-                truncated = true;
-            }
+        let mut content = String::new();
+        let mut truncated = window.len() as u64 > u64::from(limit);
+        for (line_index, display, clipped) in window.into_iter().take(limit as usize) {
+            truncated |= clipped;
 
             let formatted = format!("{:05}| {}", line_index + 1, display);
             let additional_bytes = formatted.len() + if content.is_empty() { 0 } else { 1 };
@@ -138,19 +213,19 @@ pub fn read_file(project_dir: &Path, params: ReadParams) -> Result<ReadResult, S
                 content.push('\n');
             }
             content.push_str(&formatted);
-            collected = collected.saturating_add(1);
-            line_index = line_index.saturating_add(1);
         }
 
         return Ok(ReadResult {
             content,
             total_lines,
             truncated,
+            file_size: None,
+            byte_offset: None,
+            encoding: None,
         });
     }
 
     let offset_u32 = u32::try_from(offset).unwrap_or(0);
-    let mut reader = BufReader::new(file);
     let mut content = String::new();
     let mut total_lines: u32 = 0;
     let mut collected: u32 = 0;
@@ -177,12 +252,8 @@ pub fn read_file(project_dir: &Path, params: ReadParams) -> Result<ReadResult, S
             continue;
         }
 
-        let mut display = line.clone();
-        if display.chars().count() > MAX_LINE_CHARS {
-            display = display.chars().take(MAX_LINE_CHARS).collect::<String>();
-            display.push_str("...");
-            truncated = true;
-        }
+        let (display, clipped) = clip_line(&line);
+        truncated |= clipped;
 
         let formatted = format!("{:05}| {}", line_index + 1, display);
         let additional_bytes = formatted.len() + if content.is_empty() { 0 } else { 1 };
@@ -203,5 +274,74 @@ pub fn read_file(project_dir: &Path, params: ReadParams) -> Result<ReadResult, S
         content,
         total_lines,
         truncated,
+        file_size: None,
+        byte_offset: None,
+        encoding: None,
+    })
+}
+
+fn is_utf8_continuation(byte: u8) -> bool {
+    byte & 0b1100_0000 == 0b1000_0000
+}
+
+/// Reads a window of raw text starting at `byte_offset`, widened backwards and trimmed at
+/// the end so it never splits a character. Meant for files too large, or with lines too
+/// long, for the line-oriented mode.
+fn read_byte_range(full_path: &Path, params: &ReadParams, byte_offset: u64) -> Result<ReadResult, String> {
+    let mut file = File::open(full_path)
+        .map_err(|e| format!("Failed to open file '{}': {e}", params.path))?;
+    let file_size = file
+        .metadata()
+        .map_err(|e| format!("Failed to stat file '{}': {e}", params.path))?
+        .len();
+
+    let mut probe = vec![0u8; BINARY_PROBE_BYTES];
+    let n = file
+        .read(&mut probe)
+        .map_err(|e| format!("Failed to read file '{}': {e}", params.path))?;
+    if probe[..n].contains(&0u8) {
+        return Err("Binary files are not supported".to_string());
+    }
+
+    let requested = byte_offset.min(file_size);
+    // A UTF-8 character is at most 4 bytes, so its start is at most 3 bytes back.
+    let lead = requested.min(3);
+    let limit = params
+        .byte_limit
+        .map_or(MAX_OUTPUT_BYTES, |l| l as usize)
+        .clamp(4, MAX_OUTPUT_BYTES);
+    file.seek(SeekFrom::Start(requested - lead))
+        .map_err(|e| format!("Failed to seek file '{}': {e}", params.path))?;
+    // One extra byte tells whether the window ends on a character boundary.
+    let mut buf = Vec::with_capacity(lead as usize + limit + 1);
+    file.take(lead + limit as u64 + 1)
+        .read_to_end(&mut buf)
+        .map_err(|e| format!("Failed to read file '{}': {e}", params.path))?;
+
+    let mut start = lead as usize;
+    while start > 0 && buf.get(start).is_some_and(|b| is_utf8_continuation(*b)) {
+        start -= 1;
+    }
+    let mut end = buf.len().min(lead as usize + limit);
+    while end > start && buf.get(end).is_some_and(|b| is_utf8_continuation(*b)) {
+        end -= 1;
+    }
+
+    let slice = &buf[start..end];
+    if slice.contains(&0u8) {
+        return Err("Binary files are not supported".to_string());
+    }
+    let content = std::str::from_utf8(slice)
+        .map_err(|_| "Binary files are not supported".to_string())?
+        .to_string();
+    let byte_offset = requested - lead + start as u64;
+
+    Ok(ReadResult {
+        truncated: byte_offset + (content.len() as u64) < file_size,
+        content,
+        total_lines: 0,
+        file_size: Some(file_size),
+        byte_offset: Some(byte_offset),
+        encoding: None,
     })
 }
@@ -0,0 +1,78 @@
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+use super::chapter_index::refreshed_index;
+use crate::chapter::count_words;
+use crate::security::{check_not_protected, validate_path};
+use crate::write_protection;
+
+#[derive(Debug, Deserialize)]
+pub struct RenameParams {
+    pub from: String,
+    pub to: String,
+    /// Replace an existing destination (it is backed up first).
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+/// Moves a file within the project. The move, and the index entry of a chapter file it
+/// overwrites, are applied in one transaction; an overwritten destination is backed up.
+/// Indexed chapter files cannot be moved: the index would keep pointing at the old path.
+pub fn rename_file(project_dir: &Path, params: RenameParams) -> Result<(), String> {
+    let project_root = project_dir
+        .canonicalize()
+        .map_err(|e| format!("Invalid project_dir: {e}"))?;
+
+    let from_path = validate_path(&project_root, &params.from)?;
+    let to_path = validate_path(&project_root, &params.to)?;
+    check_not_protected(&project_root, &params.from)?;
+    check_not_protected(&project_root, &params.to)?;
+    write_protection::ensure_writable(&project_root)?;
+
+    let meta = fs::symlink_metadata(&from_path)
+        .map_err(|e| format!("Failed to stat '{}': {e}", params.from))?;
+    if meta.file_type().is_dir() {
+        return Err(format!("'{}' is a directory", params.from));
+    }
+    if from_path == to_path {
+        return Ok(());
+    }
+    if to_path.is_dir() {
+        return Err(format!("'{}' is a directory", params.to));
+    }
+    if to_path.exists() && !params.overwrite {
+        return Err(format!(
+            "'{}' already exists; rename with overwrite to replace it",
+            params.to
+        ));
+    }
+
+    let content = fs::read(&from_path).map_err(|e| format!("Failed to read '{}': {e}", params.from))?;
+    let relative = |path: &Path| {
+        path.strip_prefix(&project_root)
+            .map(|r| r.to_string_lossy().replace('\\', "/"))
+            .map_err(|_| format!("Invalid path: '{}'", path.display()))
+    };
+    let (from, to) = (relative(&from_path)?, relative(&to_path)?);
+    if let Some(id) = write_protection::indexed_chapter_id(&project_root, &from) {
+        return Err(format!(
+            "'{}' is the text of chapter {id}; moving it would leave the chapter index pointing at a missing file",
+            params.from
+        ));
+    }
+    let word_counts = [(to.as_str(), count_words(&String::from_utf8_lossy(&content)))];
+    let index = refreshed_index(&project_root, &word_counts)?;
+
+    if let Some(parent) = to_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory '{}': {e}", parent.display()))?;
+    }
+    let mut transaction = write_protection::transaction(&project_root)
+        .write(&to, content)
+        .delete(&from);
+    if let Some(json) = index {
+        transaction = transaction.write("chapters/index.json", json);
+    }
+    transaction.commit()
+}
@@ -1,29 +1,100 @@
+use regex::{Regex, RegexBuilder};
 use serde::{Deserialize, Serialize};
 use std::fs::{self, File};
 use std::io::{BufRead, BufReader, Read, Seek};
+use std::collections::{HashSet, VecDeque};
 use std::path::{Path, PathBuf};
 
-use crate::security::validate_path;
+use crate::security::{resolve_read_path, SymlinkRules, REFERENCE_PREFIX};
+use crate::write_protection;
 
-const MAX_MATCHES: usize = 50;
+const DEFAULT_MAX_MATCHES: u32 = 50;
+/// Upper bound for `SearchParams.max_matches`.
+const MAX_MATCHES_LIMIT: u32 = 500;
+/// Upper bound for `SearchParams.context`.
+const MAX_CONTEXT_LINES: u32 = 5;
 const BINARY_PROBE_BYTES: usize = 4096;
+/// Compiled-size cap for regex queries, so a pathological pattern fails to compile
+/// instead of eating memory.
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
 
 #[derive(Debug, Deserialize)]
 pub struct SearchParams {
     pub query: String,
     pub path: Option<String>,
+    /// Treat `query` as a regular expression instead of a plain substring.
+    #[serde(default)]
+    pub regex: bool,
+    /// Defaults to `true`; `false` folds case per line with `to_lowercase`.
+    pub case_sensitive: Option<bool>,
+    /// Lines of context to return on each side of a match, at most [`MAX_CONTEXT_LINES`].
+    pub context: Option<u32>,
+    /// Matches returned, default [`DEFAULT_MAX_MATCHES`] and at most [`MAX_MATCHES_LIMIT`];
+    /// further hits are only counted.
+    pub max_matches: Option<u32>,
 }
 
 #[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct SearchResult {
     pub matches: Vec<SearchMatch>,
+    /// More hits were found than returned.
+    pub truncated: bool,
+    pub total_matches: u32,
+}
+
+/// Matches kept so far plus the count of every hit, including those past the limit.
+struct Hits {
+    matches: Vec<SearchMatch>,
+    limit: usize,
+    total: u32,
 }
 
 #[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct SearchMatch {
     pub file: String,
     pub line: u32,
     pub content: String,
+    /// Lines before the match, oldest first; only set when context was requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<Vec<String>>,
+}
+
+enum Matcher {
+    Substring(String),
+    /// Holds the lowercased query.
+    FoldedSubstring(String),
+    Regex(Regex),
+}
+
+impl Matcher {
+    fn new(query: &str, regex: bool, case_sensitive: bool) -> Result<Self, String> {
+        if !regex {
+            return Ok(if case_sensitive {
+                Self::Substring(query.to_string())
+            } else {
+                Self::FoldedSubstring(query.to_lowercase())
+            });
+        }
+        RegexBuilder::new(query)
+            .case_insensitive(!case_sensitive)
+            .size_limit(REGEX_SIZE_LIMIT)
+            .build()
+            .map(Self::Regex)
+            .map_err(|e| format!("Invalid regex '{query}': {e}"))
+    }
+
+    fn is_match(&self, line: &str) -> bool {
+        match self {
+            Self::Substring(query) => line.contains(query.as_str()),
+            Self::FoldedSubstring(query) => line.to_lowercase().contains(query.as_str()),
+            // Without the line ending, so `$` anchors at the end of the text.
+            Self::Regex(re) => re.is_match(line.trim_end_matches(['\n', '\r'])),
+        }
+    }
 }
 
 fn is_ignored_dir_name(name: &str) -> bool {
@@ -41,13 +112,13 @@ fn is_probably_binary(file: &mut File) -> Result<bool, String> {
 fn search_file(
     project_root: &Path,
     path: &Path,
-    query: &str,
-    matches: &mut Vec<SearchMatch>,
+    matcher: &Matcher,
+    context: usize,
+    hits: &mut Hits,
 ) -> Result<(), String> {
-    if matches.len() >= MAX_MATCHES {
+    if write_protection::check_read_size(path).is_err() {
         return Ok(());
     }
-
     let mut f = File::open(path).map_err(|e| format!("Failed to open file: {e}"))?;
     if is_probably_binary(&mut f)? {
         return Ok(());
@@ -57,11 +128,10 @@ fn search_file(
     let mut reader = BufReader::new(f);
     let mut line_no: u32 = 0;
     let mut line = String::new();
+    // The last `context` lines, and the matches still collecting lines after them.
+    let mut previous: VecDeque<String> = VecDeque::with_capacity(context);
+    let mut awaiting_after: Vec<usize> = Vec::new();
     loop {
-        if matches.len() >= MAX_MATCHES {
-            break;
-        }
-
         line.clear();
         let bytes_read = match reader.read_line(&mut line) {
             Ok(n) => n,
@@ -75,19 +145,41 @@ fn search_file(
             break;
         }
         line_no = line_no.saturating_add(1);
+        let content = line.trim_end_matches(['\n', '\r']);
 
-        if line.contains(query) {
-            let content = line.trim_end_matches(['\n', '\r']).to_string();
-            let rel = path
-                .strip_prefix(project_root)
-                .unwrap_or(path)
-                .to_string_lossy()
-                .to_string();
-            matches.push(SearchMatch {
-                file: rel,
-                line: line_no,
-                content,
-            });
+        awaiting_after.retain(|&i| {
+            let after = hits.matches[i].after.get_or_insert_with(Vec::new);
+            after.push(content.to_string());
+            after.len() < context
+        });
+
+        if matcher.is_match(&line) {
+            hits.total = hits.total.saturating_add(1);
+            if hits.matches.len() < hits.limit {
+                let rel = path
+                    .strip_prefix(project_root)
+                    .unwrap_or(path)
+                    .to_string_lossy()
+                    .to_string();
+                let with_context = context > 0;
+                hits.matches.push(SearchMatch {
+                    file: rel,
+                    line: line_no,
+                    content: content.to_string(),
+                    before: with_context.then(|| previous.iter().cloned().collect()),
+                    after: with_context.then(Vec::new),
+                });
+                if with_context {
+                    awaiting_after.push(hits.matches.len() - 1);
+                }
+            }
+        }
+
+        if context > 0 {
+            if previous.len() == context {
+                previous.pop_front();
+            }
+            previous.push_back(content.to_string());
         }
     }
 
@@ -97,18 +189,16 @@ fn search_file(
 fn walk_and_search(
     project_root: &Path,
     root: &Path,
-    query: &str,
-    matches: &mut Vec<SearchMatch>,
+    matcher: &Matcher,
+    context: usize,
+    rules: &SymlinkRules,
+    hits: &mut Hits,
 ) -> Result<(), String> {
+    // Canonical dirs already queued, so symlink loops are walked once.
+    let mut visited: HashSet<PathBuf> = root.canonicalize().into_iter().collect();
     let mut stack: Vec<PathBuf> = vec![root.to_path_buf()];
     while let Some(dir) = stack.pop() {
-        if matches.len() >= MAX_MATCHES {
-            break;
-        }
         for entry in fs::read_dir(&dir).map_err(|e| format!("Failed to read directory: {e}"))? {
-            if matches.len() >= MAX_MATCHES {
-                break;
-            }
             let entry = entry.map_err(|e| format!("Failed to read directory entry: {e}"))?;
             let name = entry.file_name().to_string_lossy().to_string();
             if name.starts_with('.') {
@@ -118,11 +208,21 @@ fn walk_and_search(
             let file_type = entry
                 .file_type()
                 .map_err(|e| format!("Failed to stat entry '{}': {e}", name))?;
+            let path = entry.path();
             if file_type.is_symlink() {
+                let Some(target) = rules.follow(project_root, &path) else {
+                    continue;
+                };
+                if target.is_dir() {
+                    if !is_ignored_dir_name(&name) && visited.insert(target) {
+                        stack.push(path);
+                    }
+                } else if target.is_file() {
+                    search_file(project_root, &path, matcher, context, hits)?;
+                }
                 continue;
             }
 
-            let path = entry.path();
             if file_type.is_dir() {
                 if is_ignored_dir_name(&name) {
                     continue;
@@ -135,31 +235,52 @@ fn walk_and_search(
                 continue;
             }
 
-            search_file(project_root, &path, query, matches)?;
+            search_file(project_root, &path, matcher, context, hits)?;
         }
     }
     Ok(())
 }
 
 pub fn search_in_files(project_dir: &Path, params: SearchParams) -> Result<SearchResult, String> {
-    let project_root = project_dir
-        .canonicalize()
-        .map_err(|e| format!("Invalid project_dir: {e}"))?;
-
+    let matcher = Matcher::new(&params.query, params.regex, params.case_sensitive.unwrap_or(true))?;
+    let context = params.context.unwrap_or(0).min(MAX_CONTEXT_LINES) as usize;
+    let limit = params.max_matches.unwrap_or(DEFAULT_MAX_MATCHES).clamp(1, MAX_MATCHES_LIMIT);
     let relative = params.path.unwrap_or_else(|| "".to_string());
-    let full_path = validate_path(&project_root, &relative)?;
+    // `ext://` paths search a reference root instead of the project.
+    let resolved = resolve_read_path(project_dir, &relative)?;
+    let project_root = resolved.base;
+    // Project paths are walked through any symlinks they contain, so results keep the
+    // path the caller asked for; the resolution above already applied the project policy.
+    let (full_path, rules) = match resolved.reference_root {
+        Some(_) => (resolved.full_path, SymlinkRules::default()),
+        None => (project_root.join(relative.trim()), SymlinkRules::for_project(&project_root)),
+    };
 
-    let meta = fs::symlink_metadata(&full_path)
+    let meta = fs::metadata(&full_path)
         .map_err(|e| format!("Failed to stat '{}': {e}", relative))?;
 
-    let mut matches = Vec::new();
+    let mut hits = Hits {
+        matches: Vec::new(),
+        limit: limit as usize,
+        total: 0,
+    };
     if meta.file_type().is_dir() {
-        walk_and_search(&project_root, &full_path, &params.query, &mut matches)?;
+        walk_and_search(&project_root, &full_path, &matcher, context, &rules, &mut hits)?;
     } else if meta.file_type().is_file() {
-        search_file(&project_root, &full_path, &params.query, &mut matches)?;
+        search_file(&project_root, &full_path, &matcher, context, &mut hits)?;
     } else {
         return Err(format!("'{}' is not a file or directory", relative));
     }
 
-    Ok(SearchResult { matches })
+    if let Some(root_id) = resolved.reference_root {
+        for m in &mut hits.matches {
+            m.file = format!("{REFERENCE_PREFIX}{root_id}/{}", m.file.replace('\\', "/"));
+        }
+    }
+
+    Ok(SearchResult {
+        truncated: hits.total as usize > hits.matches.len(),
+        total_matches: hits.total,
+        matches: hits.matches,
+    })
 }
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::security::resolve_read_path;
+
+/// Files up to this size get line and word counts.
+const MAX_COUNTED_BYTES: u64 = 2 * 1024 * 1024;
+
+#[derive(Debug, Deserialize)]
+pub struct StatParams {
+    pub path: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileStat {
+    pub exists: bool,
+    pub is_dir: bool,
+    pub size: u64,
+    pub modified: u64,
+    /// Only for UTF-8 text files up to [`MAX_COUNTED_BYTES`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_count: Option<u32>,
+    /// Non-whitespace characters, as counted for chapters.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub word_count: Option<u32>,
+}
+
+fn system_time_to_unix_seconds(t: SystemTime) -> u64 {
+    t.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Metadata for one path. A missing file is not an error: it reports `exists: false`.
+pub fn stat_file(project_dir: &Path, params: StatParams) -> Result<FileStat, String> {
+    let full_path = resolve_read_path(project_dir, &params.path)?.full_path;
+
+    let meta = match fs::metadata(&full_path) {
+        Ok(meta) => meta,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(FileStat {
+                exists: false,
+                is_dir: false,
+                size: 0,
+                modified: 0,
+                line_count: None,
+                word_count: None,
+            });
+        }
+        Err(e) => return Err(format!("Failed to stat '{}': {e}", params.path)),
+    };
+
+    let text = if meta.is_file() && meta.len() <= MAX_COUNTED_BYTES {
+        fs::read(&full_path)
+            .ok()
+            .filter(|bytes| !bytes.contains(&0u8))
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+    } else {
+        None
+    };
+
+    Ok(FileStat {
+        exists: true,
+        is_dir: meta.is_dir(),
+        size: if meta.is_file() { meta.len() } else { 0 },
+        modified: meta
+            .modified()
+            .map(system_time_to_unix_seconds)
+            .unwrap_or(0),
+        line_count: text.as_ref().map(|t| t.lines().count() as u32),
+        word_count: text
+            .as_ref()
+            .map(|t| t.chars().filter(|c| !c.is_whitespace()).count() as u32),
+    })
+}
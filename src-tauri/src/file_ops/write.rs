@@ -1,7 +1,7 @@
 use serde::Deserialize;
 use std::path::Path;
 
-use crate::security::validate_path;
+use crate::security::{check_not_protected, validate_path};
 use crate::write_protection;
 
 #[derive(Debug, Deserialize)]
@@ -16,6 +16,8 @@ pub fn write_file(project_dir: &Path, params: WriteParams) -> Result<(), String>
         .map_err(|e| format!("Invalid project_dir: {e}"))?;
 
     let full_path = validate_path(&project_root, &params.path)?;
+    check_not_protected(&project_root, &params.path)?;
+    write_protection::check_write_size(&project_root, &full_path, params.content.len() as u64)?;
 
     write_protection::write_string_with_backup(&project_root, &full_path, &params.content)?;
 
@@ -1,11 +1,23 @@
-use regex::RegexBuilder;
+use regex::{Regex, RegexBuilder};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
-use tauri::Emitter;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{Emitter, Manager};
 
+use crate::activity_log::{self, Actor};
+use crate::chapter::{self, BulkStep};
+use crate::config::{self, ImportPattern};
+use crate::file_ops::read::decode_text;
 use crate::project::ChapterMeta;
+use crate::summary::{self, SummaryProvenance};
 
 const DEFAULT_CHAPTER_PATTERN: &str = "^第.+章.*";
+const DEFAULT_PREAMBLE_TITLE: &str = "前言";
+const DEFAULT_MARKDOWN_HEADING_LEVEL: u8 = 1;
+const UNTITLED_CHAPTER_TITLE: &str = "无标题";
 const IMPORT_TXT_PROGRESS_EVENT: &str = "creatorai:importTxtProgress";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,6 +25,61 @@ pub struct ChapterPreview {
     pub title: String,
     #[serde(rename = "wordCount")]
     pub word_count: u32,
+    /// Text before the first chapter heading, imported as its own chapter.
+    #[serde(rename = "isPreamble", default)]
+    pub is_preamble: bool,
+    /// An existing chapter with the same title, when the preview was given a project.
+    #[serde(rename = "existingChapterId", default, skip_serializing_if = "Option::is_none")]
+    pub existing_chapter_id: Option<String>,
+}
+
+/// What an import does with a chapter whose title is already in the project.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictStrategy {
+    /// Leave the existing chapter alone and don't import this one.
+    Skip,
+    /// Replace the existing chapter's content, keeping its id and order.
+    Overwrite,
+    /// Import it as a new chapter anyway.
+    #[default]
+    Duplicate,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ImportTxtOptions {
+    /// Import text before the first heading as a chapter; defaults to true.
+    pub include_preamble: Option<bool>,
+    /// Title for that chapter; defaults to "前言".
+    pub preamble_title: Option<String>,
+    /// `import_txt` only: positions in the preview list to import; all when absent.
+    pub selected_indices: Option<Vec<u32>>,
+    /// `import_txt` only: on cancel, remove the chapters already created, restore the
+    /// chapter index from before they were added and put back the content of overwritten
+    /// chapters.
+    pub rollback_on_cancel: bool,
+    /// Import only: how to handle chapters whose title already exists; defaults to
+    /// duplicating them.
+    pub conflict_strategy: ConflictStrategy,
+}
+
+impl ImportTxtOptions {
+    fn preamble_title(&self) -> Option<&str> {
+        self.include_preamble
+            .unwrap_or(true)
+            .then(|| self.preamble_title.as_deref().unwrap_or(DEFAULT_PREAMBLE_TITLE))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportTxtPreview {
+    /// Encoding the file was read with, e.g. "UTF-8", "GBK" or "Big5".
+    pub encoding: String,
+    pub chapters: Vec<ChapterPreview>,
+    /// Word count of the text before the first heading, whether or not it is imported.
+    #[serde(rename = "preambleWordCount")]
+    pub preamble_word_count: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +90,81 @@ pub struct ImportTxtProgress {
     pub completed: u32,
     #[serde(rename = "currentTitle")]
     pub current_title: Option<String>,
+    /// Set on the final event of an import that was cancelled.
+    #[serde(default)]
+    pub cancelled: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportTxtResult {
+    /// Chapters created or overwritten and kept by this import.
+    pub chapters: Vec<ChapterMeta>,
+    pub cancelled: bool,
+    /// The import was cancelled, the chapters it had created were removed again and the
+    /// chapters it had overwritten were restored.
+    pub rolled_back: bool,
+    /// Chapters left out because their title already existed.
+    pub skipped: u32,
+    /// Existing chapters whose content was replaced.
+    pub overwritten: u32,
+    /// Bookkeeping that could not be updated for imported chapters; the chapters were saved.
+    pub warnings: Vec<String>,
+}
+
+/// Cancel flags of running `import_txt` calls, keyed by request id.
+#[derive(Default)]
+pub struct ImportRuntime {
+    cancel_flags: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl ImportRuntime {
+    fn begin(&self, request_id: &str) -> Result<Arc<AtomicBool>, String> {
+        let flag = Arc::new(AtomicBool::new(false));
+        self.cancel_flags
+            .lock()
+            .map_err(|_| "import_txt lock poisoned".to_string())?
+            .insert(request_id.to_string(), flag.clone());
+        Ok(flag)
+    }
+
+    fn finish(&self, request_id: &str) {
+        if let Ok(mut flags) = self.cancel_flags.lock() {
+            flags.remove(request_id);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SummaryMatch {
+    /// Section heading equals the chapter title.
+    Title,
+    /// The n-th section went to the chapter with order n.
+    Order,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SummaryImportMapping {
+    pub section_title: String,
+    pub chapter_id: String,
+    pub chapter_title: String,
+    pub matched_by: SummaryMatch,
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SummaryImportPlan {
+    pub mappings: Vec<SummaryImportMapping>,
+    /// Headings of sections that matched no chapter.
+    pub unmatched_sections: Vec<String>,
+    /// Chapters that still have no summary once the mappings are saved.
+    pub chapters_without_summary: Vec<String>,
+    /// Set by the import itself: bookkeeping that could not be updated for saved summaries.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -30,12 +172,34 @@ struct ChapterData {
     title: String,
     content: String,
     word_count: u32,
+    is_preamble: bool,
+}
+
+/// Parsed chapters sorted by what the import does with them.
+#[derive(Debug, Default)]
+struct ImportPlan {
+    create: Vec<ChapterData>,
+    /// Id of the existing chapter each parsed chapter replaces.
+    overwrite: Vec<(String, ChapterData)>,
+    skipped: u32,
 }
 
 fn count_words(content: &str) -> u32 {
     content.chars().filter(|c| !c.is_whitespace()).count() as u32
 }
 
+/// Decodes a txt file with the shared `file_read` decoder. Returns the text and the
+/// encoding's name.
+fn decode_txt(bytes: &[u8], encoding: Option<&str>) -> Result<(String, &'static str), String> {
+    let decoded = decode_text(bytes, encoding)?;
+    Ok((decoded.text, decoded.encoding.name()))
+}
+
+fn read_txt(file_path: &str, encoding: Option<&str>) -> Result<(String, &'static str), String> {
+    let bytes = fs::read(file_path).map_err(|e| format!("Failed to read txt file: {e}"))?;
+    decode_txt(&bytes, encoding)
+}
+
 fn normalize_content(mut content: String) -> String {
     if content.starts_with('\u{feff}') {
         content = content.trim_start_matches('\u{feff}').to_string();
@@ -43,34 +207,92 @@ fn normalize_content(mut content: String) -> String {
     content
 }
 
-fn parse_chapters_from_text(content: &str, pattern: &str) -> Result<Vec<ChapterData>, String> {
+/// Compiles a chapter-split pattern the way imports apply it: `^` and `$` match at line
+/// boundaries.
+pub(crate) fn chapter_regex(pattern: &str) -> Result<Regex, String> {
+    RegexBuilder::new(pattern)
+        .multi_line(true)
+        .build()
+        .map_err(|e| format!("Invalid regex pattern: {e}"))
+}
+
+/// The split pattern a preview asked for: `pattern` itself, or the saved pattern with id
+/// `pattern_id` from `saved`.
+fn resolve_pattern(
+    pattern: Option<String>,
+    pattern_id: Option<&str>,
+    saved: &[ImportPattern],
+) -> Result<String, String> {
+    match (pattern.filter(|p| !p.trim().is_empty()), pattern_id) {
+        (Some(_), Some(_)) => Err("Pass either pattern or pattern_id, not both".to_string()),
+        (None, Some(id)) => saved
+            .iter()
+            .find(|p| p.id == id)
+            .map(|p| p.pattern.clone())
+            .ok_or_else(|| format!("Import pattern {id} not found")),
+        (pattern, None) => Ok(pattern.unwrap_or_default()),
+    }
+}
+
+/// Splits `content` at lines matching `pattern`. With `preamble_title`, non-empty text
+/// before the first heading becomes a leading chapter with that title; without a heading
+/// there are no chapters at all.
+fn parse_chapters_from_text(
+    content: &str,
+    pattern: &str,
+    preamble_title: Option<&str>,
+) -> Result<Vec<ChapterData>, String> {
     let effective_pattern = if pattern.trim().is_empty() {
         DEFAULT_CHAPTER_PATTERN
     } else {
         pattern
     };
 
-    let regex = RegexBuilder::new(effective_pattern)
-        .multi_line(true)
-        .build()
-        .map_err(|e| format!("Invalid regex pattern: {e}"))?;
+    let regex = chapter_regex(effective_pattern)?;
 
+    let headings = regex
+        .find_iter(content)
+        .map(|mat| (mat.start(), mat.end(), mat.as_str().trim().to_string()));
+    Ok(split_at_headings(content, headings, preamble_title))
+}
+
+/// Builds chapters from `(start, end, title)` heading spans in `content`, in order. Each
+/// chapter's text runs from the end of its heading to the start of the next one.
+fn split_at_headings(
+    content: &str,
+    headings: impl IntoIterator<Item = (usize, usize, String)>,
+    preamble_title: Option<&str>,
+) -> Vec<ChapterData> {
     let mut chapters = Vec::new();
     let mut last_end = 0;
     let mut last_title: Option<String> = None;
 
-    for mat in regex.find_iter(content) {
-        if let Some(title) = last_title.take() {
-            let chapter_content = content[last_end..mat.start()].trim().to_string();
-            chapters.push(ChapterData {
-                title,
-                word_count: count_words(&chapter_content),
-                content: chapter_content,
-            });
+    for (start, end, title) in headings {
+        match last_title.take() {
+            Some(title) => {
+                let chapter_content = content[last_end..start].trim().to_string();
+                chapters.push(ChapterData {
+                    title,
+                    word_count: count_words(&chapter_content),
+                    content: chapter_content,
+                    is_preamble: false,
+                });
+            }
+            None => {
+                let preamble = content[..start].trim();
+                if let (Some(title), false) = (preamble_title, preamble.is_empty()) {
+                    chapters.push(ChapterData {
+                        title: title.to_string(),
+                        word_count: count_words(preamble),
+                        content: preamble.to_string(),
+                        is_preamble: true,
+                    });
+                }
+            }
         }
 
-        last_title = Some(mat.as_str().trim().to_string());
-        last_end = mat.end();
+        last_title = Some(title);
+        last_end = end;
     }
 
     if let Some(title) = last_title {
@@ -79,39 +301,510 @@ fn parse_chapters_from_text(content: &str, pattern: &str) -> Result<Vec<ChapterD
             title,
             word_count: count_words(&chapter_content),
             content: chapter_content,
+            is_preamble: false,
         });
     }
 
-    Ok(chapters)
+    chapters
+}
+
+/// The title of an ATX heading (`## Title ##`) of exactly `level`, or `None` for any
+/// other line.
+fn markdown_heading_title(line: &str, level: usize) -> Option<String> {
+    let line = line.trim_end();
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    if indent > 3 {
+        return None;
+    }
+    let rest = &line[indent..];
+    let hashes = rest.len() - rest.trim_start_matches('#').len();
+    if hashes != level {
+        return None;
+    }
+    let rest = &rest[hashes..];
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    // A closing run of hashes only counts when separated from the title by a space.
+    let title = rest.trim();
+    let without_closing = title.trim_end_matches('#');
+    let title = if without_closing.is_empty() || without_closing.ends_with([' ', '\t']) {
+        without_closing.trim_end()
+    } else {
+        title
+    };
+    Some(if title.is_empty() {
+        UNTITLED_CHAPTER_TITLE.to_string()
+    } else {
+        title.to_string()
+    })
+}
+
+/// The fence marker (a run of three or more backticks or tildes) that opens or closes a
+/// fenced code block on this line.
+fn code_fence(line: &str) -> Option<(char, usize)> {
+    let trimmed = line.trim_start_matches(' ');
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    let marker = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = trimmed.len() - trimmed.trim_start_matches(marker).len();
+    (len >= 3).then_some((marker, len))
+}
+
+/// Splits Markdown at ATX headings of `level` (1–3). Headings inside fenced code blocks
+/// are ignored, and setext (underlined) headings are left as ordinary text. A file with
+/// text but no such heading becomes one chapter titled `fallback_title`.
+fn parse_chapters_from_markdown(
+    content: &str,
+    level: u8,
+    preamble_title: Option<&str>,
+    fallback_title: &str,
+) -> Result<Vec<ChapterData>, String> {
+    if !(1..=3).contains(&level) {
+        return Err("Heading level must be between 1 and 3".to_string());
+    }
+
+    let mut headings = Vec::new();
+    let mut fence: Option<(char, usize)> = None;
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let line = line.trim_end_matches(['\n', '\r']);
+
+        match (fence, code_fence(line)) {
+            (None, Some(opened)) => fence = Some(opened),
+            (Some((marker, len)), Some((closing, closing_len)))
+                if closing == marker
+                    && closing_len >= len
+                    && line.trim().trim_start_matches(marker).is_empty() =>
+            {
+                fence = None
+            }
+            (Some(_), _) => {}
+            (None, None) => {
+                if let Some(title) = markdown_heading_title(line, level as usize) {
+                    headings.push((start, start + line.len(), title));
+                }
+            }
+        }
+    }
+
+    if headings.is_empty() {
+        let text = content.trim();
+        if text.is_empty() {
+            return Ok(Vec::new());
+        }
+        return Ok(vec![ChapterData {
+            title: fallback_title.to_string(),
+            word_count: count_words(text),
+            content: text.to_string(),
+            is_preamble: false,
+        }]);
+    }
+    Ok(split_at_headings(content, headings, preamble_title))
+}
+
+/// The project's chapters, for previews that were given a project to check titles against.
+fn existing_chapters(project_path: Option<String>) -> Result<Vec<ChapterMeta>, String> {
+    project_path.map_or(Ok(Vec::new()), chapter::list_chapters_sync)
+}
+
+fn preview_import_txt_sync(
+    file_path: String,
+    pattern: String,
+    encoding: Option<String>,
+    options: ImportTxtOptions,
+    project_path: Option<String>,
+) -> Result<ImportTxtPreview, String> {
+    let (content, encoding) = read_txt(&file_path, encoding.as_deref())?;
+    let content = normalize_content(content);
+    // Always look for a preamble so the UI can offer it even when it is excluded.
+    let title = options.preamble_title().unwrap_or(DEFAULT_PREAMBLE_TITLE);
+    let chapters = parse_chapters_from_text(&content, &pattern, Some(title))?;
+    Ok(build_preview(encoding, chapters, &options, &existing_chapters(project_path)?))
+}
+
+/// Preview of chapters parsed with a preamble, dropping it again if `options` exclude it.
+/// Titles are checked against `existing` chapters.
+fn build_preview(
+    encoding: &str,
+    mut chapters: Vec<ChapterData>,
+    options: &ImportTxtOptions,
+    existing: &[ChapterMeta],
+) -> ImportTxtPreview {
+    let preamble_word_count = chapters.first().filter(|c| c.is_preamble).map(|c| c.word_count);
+    if options.preamble_title().is_none() {
+        chapters.retain(|c| !c.is_preamble);
+    }
+
+    ImportTxtPreview {
+        encoding: encoding.to_string(),
+        chapters: chapters
+            .into_iter()
+            .map(|c| ChapterPreview {
+                existing_chapter_id: existing
+                    .iter()
+                    .find(|e| same_title(&e.title, &c.title))
+                    .map(|e| e.id.clone()),
+                title: c.title,
+                word_count: c.word_count,
+                is_preamble: c.is_preamble,
+            })
+            .collect(),
+        preamble_word_count,
+    }
+}
+
+/// Splits with `pattern`, or with the saved pattern `pattern_id`. With `project_path`,
+/// each previewed chapter names the existing chapter sharing its title.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn preview_import_txt(
+    file_path: String,
+    pattern: Option<String>,
+    pattern_id: Option<String>,
+    encoding: Option<String>,
+    options: Option<ImportTxtOptions>,
+    project_path: Option<String>,
+) -> Result<ImportTxtPreview, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let saved = match pattern_id {
+            Some(_) => config::load_config()?.import_patterns,
+            None => Vec::new(),
+        };
+        let pattern = resolve_pattern(pattern, pattern_id.as_deref(), &saved)?;
+        preview_import_txt_sync(file_path, pattern, encoding, options.unwrap_or_default(), project_path)
+    })
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
 }
 
-fn preview_import_txt_sync(file_path: String, pattern: String) -> Result<Vec<ChapterPreview>, String> {
-    let content =
-        fs::read_to_string(&file_path).map_err(|e| format!("Failed to read txt file: {e}"))?;
+fn parse_import_txt_sync(
+    file_path: String,
+    pattern: String,
+    encoding: Option<String>,
+    preamble_title: Option<&str>,
+) -> Result<Vec<ChapterData>, String> {
+    let (content, _) = read_txt(&file_path, encoding.as_deref())?;
     let content = normalize_content(content);
-    let chapters = parse_chapters_from_text(&content, &pattern)?;
+    parse_chapters_from_text(&content, &pattern, preamble_title)
+}
 
+/// The chapters at `indices` (preview positions), in file order; all of them for `None`.
+fn select_chapters(chapters: Vec<ChapterData>, indices: Option<&[u32]>) -> Result<Vec<ChapterData>, String> {
+    let Some(indices) = indices else {
+        return Ok(chapters);
+    };
+    if let Some(bad) = indices.iter().find(|&&i| i as usize >= chapters.len()) {
+        return Err(format!(
+            "Chapter index {bad} is out of range ({} chapters parsed)",
+            chapters.len()
+        ));
+    }
     Ok(chapters
         .into_iter()
-        .map(|c| ChapterPreview {
-            title: c.title,
-            word_count: c.word_count,
-        })
+        .enumerate()
+        .filter(|(i, _)| indices.contains(&(*i as u32)))
+        .map(|(_, chapter)| chapter)
         .collect())
 }
 
+fn same_title(a: &str, b: &str) -> bool {
+    a.split_whitespace().eq(b.split_whitespace())
+}
+
+/// Pairs outline sections with chapters, by title first and then by position, so each
+/// chapter receives at most one section. Sections without text are ignored.
+fn plan_summary_import(project_path: &str, file_path: &str, pattern: &str) -> Result<SummaryImportPlan, String> {
+    let sections = parse_import_txt_sync(file_path.to_string(), pattern.to_string(), None, None)?;
+    if sections.is_empty() {
+        return Err("No chapters matched the pattern".to_string());
+    }
+    let chapters = crate::chapter::list_chapters_sync(project_path.to_string())?;
+
+    let mut claimed = vec![false; chapters.len()];
+    let mut matches: Vec<Option<(usize, SummaryMatch)>> = sections
+        .iter()
+        .map(|section| {
+            let index = chapters
+                .iter()
+                .enumerate()
+                .position(|(i, c)| !claimed[i] && same_title(&c.title, &section.title))?;
+            claimed[index] = true;
+            Some((index, SummaryMatch::Title))
+        })
+        .collect();
+    for (position, slot) in matches.iter_mut().enumerate() {
+        if slot.is_some() {
+            continue;
+        }
+        let order = position as u32 + 1;
+        if let Some(index) = (0..chapters.len()).find(|&i| !claimed[i] && chapters[i].order == order) {
+            claimed[index] = true;
+            *slot = Some((index, SummaryMatch::Order));
+        }
+    }
+
+    let mut mappings = Vec::new();
+    let mut unmatched_sections = Vec::new();
+    for (section, slot) in sections.into_iter().zip(matches) {
+        if section.content.is_empty() {
+            continue;
+        }
+        match slot {
+            Some((index, matched_by)) => mappings.push(SummaryImportMapping {
+                section_title: section.title,
+                chapter_id: chapters[index].id.clone(),
+                chapter_title: chapters[index].title.clone(),
+                matched_by,
+                summary: section.content,
+            }),
+            None => unmatched_sections.push(section.title),
+        }
+    }
+
+    let refs = summary::summary_refs(Path::new(project_path), &chapters)?;
+    let chapters_without_summary = refs
+        .into_iter()
+        .filter(|r| r.summary.is_none() && !mappings.iter().any(|m| m.chapter_id == r.chapter_id))
+        .map(|r| r.chapter_id)
+        .collect();
+
+    Ok(SummaryImportPlan {
+        mappings,
+        unmatched_sections,
+        chapters_without_summary,
+        warnings: Vec::new(),
+    })
+}
+
+fn import_summaries_sync(project_path: String, file_path: String, pattern: String) -> Result<SummaryImportPlan, String> {
+    let mut plan = plan_summary_import(&project_path, &file_path, &pattern)?;
+    let mut warnings = Vec::new();
+    for mapping in &plan.mappings {
+        summary::save_summary(
+            Path::new(&project_path),
+            mapping.chapter_id.clone(),
+            mapping.summary.clone(),
+            SummaryProvenance::manual(),
+        )?;
+        if let Some(warning) = activity_log::record(
+            Path::new(&project_path),
+            Actor::User,
+            "import_summary",
+            &format!("summaries/{}.json", mapping.chapter_id),
+            mapping.summary.len() as i64,
+        ) {
+            warnings.push(warning);
+        }
+    }
+    plan.warnings = warnings;
+    Ok(plan)
+}
+
+/// Shows how an outline file would map onto chapters without writing anything.
 #[tauri::command(rename_all = "camelCase")]
-pub async fn preview_import_txt(file_path: String, pattern: String) -> Result<Vec<ChapterPreview>, String> {
-    tauri::async_runtime::spawn_blocking(move || preview_import_txt_sync(file_path, pattern))
+pub async fn preview_import_summaries(
+    project_path: String,
+    file_path: String,
+    pattern: String,
+) -> Result<SummaryImportPlan, String> {
+    tauri::async_runtime::spawn_blocking(move || plan_summary_import(&project_path, &file_path, &pattern))
         .await
         .map_err(|e| format!("Task join error: {e}"))?
 }
 
-fn parse_import_txt_sync(file_path: String, pattern: String) -> Result<Vec<ChapterData>, String> {
-    let content =
-        fs::read_to_string(&file_path).map_err(|e| format!("Failed to read txt file: {e}"))?;
-    let content = normalize_content(content);
-    parse_chapters_from_text(&content, &pattern)
+/// Saves each outline section as a new summary of its matched chapter.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn import_summaries(
+    project_path: String,
+    file_path: String,
+    pattern: String,
+) -> Result<SummaryImportPlan, String> {
+    tauri::async_runtime::spawn_blocking(move || import_summaries_sync(project_path, file_path, pattern))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+/// Sorts `chapters` by `strategy` against the project's existing titles. Each existing
+/// chapter is overwritten at most once; further chapters with its title are created.
+fn plan_import(
+    project_path: &str,
+    chapters: Vec<ChapterData>,
+    strategy: ConflictStrategy,
+) -> Result<ImportPlan, String> {
+    if strategy == ConflictStrategy::Duplicate {
+        return Ok(ImportPlan {
+            create: chapters,
+            ..ImportPlan::default()
+        });
+    }
+
+    let existing = chapter::list_chapters_sync(project_path.to_string())?;
+    let mut claimed = vec![false; existing.len()];
+    let mut plan = ImportPlan::default();
+    for chapter in chapters {
+        let mut matches = existing
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| same_title(&e.title, &chapter.title))
+            .map(|(i, _)| i)
+            .peekable();
+        match strategy {
+            ConflictStrategy::Skip if matches.peek().is_some() => plan.skipped += 1,
+            ConflictStrategy::Overwrite => match matches.find(|&i| !claimed[i]) {
+                Some(i) => {
+                    claimed[i] = true;
+                    plan.overwrite.push((existing[i].id.clone(), chapter));
+                }
+                None => plan.create.push(chapter),
+            },
+            _ => plan.create.push(chapter),
+        }
+    }
+    Ok(plan)
+}
+
+/// Records an `action` activity-log entry for each written chapter, returning the warnings.
+fn log_written(
+    project_root: &Path,
+    action: &str,
+    written: &[ChapterMeta],
+    content_lens: &[i64],
+) -> Vec<String> {
+    written
+        .iter()
+        .zip(content_lens)
+        .filter_map(|(meta, &content_len)| {
+            let relative = format!("chapters/{}.txt", meta.id);
+            activity_log::record(project_root, Actor::User, action, &relative, content_len)
+        })
+        .collect()
+}
+
+/// Puts back the content the overwritten chapters had before the import. If that fails the
+/// overwrites are kept, `rolled_back` stays false and a warning names them.
+fn restore_overwritten(
+    project_root: &Path,
+    project_path: &str,
+    originals: Vec<(String, String)>,
+    result: &mut ImportTxtResult,
+) {
+    if originals.is_empty() {
+        result.rolled_back = true;
+        return;
+    }
+    let content_lens: Vec<i64> = originals.iter().map(|(_, c)| c.len() as i64).collect();
+    match chapter::replace_chapters_content(project_path, originals) {
+        Ok(restored) => {
+            let warnings = log_written(project_root, "import_rollback", &restored, &content_lens);
+            result.warnings.extend(warnings);
+            result.chapters.retain(|c| !restored.iter().any(|r| r.id == c.id));
+            result.overwritten = 0;
+            result.rolled_back = true;
+        }
+        Err(e) => {
+            let kept: Vec<&str> = result.chapters.iter().map(|c| c.title.as_str()).collect();
+            result.warnings.push(format!(
+                "Overwritten chapters could not be restored and were kept ({}): {e}",
+                kept.join(", ")
+            ));
+        }
+    }
+}
+
+/// Overwrites the conflicting chapters in one transaction, then creates the plan's new
+/// chapters in order with a single index write, checking `cancel` after each one. A failed
+/// overwrite leaves the project untouched; overwrites that landed are logged before any
+/// chapter is created and are kept if creating fails. On cancel they are kept too, unless
+/// `rollback_on_cancel` is set, in which case their previous content is written back.
+/// `cancel` is also checked before each phase. `on_progress` gets the number written so far
+/// and the latest title.
+fn import_chapters_sync(
+    project_path: &str,
+    plan: ImportPlan,
+    cancel: &AtomicBool,
+    rollback_on_cancel: bool,
+    mut on_progress: impl FnMut(u32, &str),
+) -> Result<ImportTxtResult, String> {
+    let project_root: PathBuf = Path::new(project_path)
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path: {e}"))?;
+    let ImportPlan {
+        create,
+        overwrite,
+        skipped,
+    } = plan;
+    let mut result = ImportTxtResult {
+        chapters: Vec::new(),
+        cancelled: false,
+        rolled_back: false,
+        skipped,
+        overwritten: 0,
+        warnings: Vec::new(),
+    };
+    let mut completed = 0u32;
+    let mut originals = Vec::new();
+
+    if !overwrite.is_empty() {
+        if cancel.load(Ordering::SeqCst) {
+            result.cancelled = true;
+            return Ok(result);
+        }
+        if rollback_on_cancel {
+            for (id, _) in &overwrite {
+                let content = chapter::get_chapter_content_sync(project_path.to_string(), id.clone())?;
+                originals.push((id.clone(), content));
+            }
+        }
+        let content_lens: Vec<i64> = overwrite.iter().map(|(_, c)| c.content.len() as i64).collect();
+        let replacements = overwrite.into_iter().map(|(id, c)| (id, c.content)).collect();
+        let replaced = chapter::replace_chapters_content(project_path, replacements)?;
+        for meta in &replaced {
+            completed += 1;
+            on_progress(completed, &meta.title);
+        }
+        result.warnings = log_written(&project_root, "import", &replaced, &content_lens);
+        result.overwritten = replaced.len() as u32;
+        result.chapters = replaced;
+    }
+
+    if create.is_empty() {
+        return Ok(result);
+    }
+    if cancel.load(Ordering::SeqCst) {
+        result.cancelled = true;
+        if rollback_on_cancel {
+            restore_overwritten(&project_root, project_path, originals, &mut result);
+        }
+        return Ok(result);
+    }
+    let content_lens: Vec<i64> = create.iter().map(|c| c.content.len() as i64).collect();
+    let chapters = create.into_iter().map(|c| (c.title, c.content)).collect();
+    let created = chapter::create_chapters_bulk(project_path, chapters, |meta| {
+        completed += 1;
+        on_progress(completed, &meta.title);
+        match (cancel.load(Ordering::SeqCst), rollback_on_cancel) {
+            (false, _) => BulkStep::Continue,
+            (true, false) => BulkStep::StopAndKeep,
+            (true, true) => BulkStep::StopAndDiscard,
+        }
+    })
+    .map_err(|e| match result.overwritten {
+        0 => e,
+        n => format!("Import stopped after overwriting {n} chapters: {e}"),
+    })?;
+
+    result
+        .warnings
+        .extend(log_written(&project_root, "import", &created.chapters, &content_lens));
+    result.cancelled = created.stopped;
+    result.chapters.extend(created.chapters);
+    if created.stopped && rollback_on_cancel {
+        restore_overwritten(&project_root, project_path, originals, &mut result);
+    }
+    Ok(result)
 }
 
 #[tauri::command(rename_all = "camelCase")]
@@ -121,57 +814,175 @@ pub async fn import_txt(
     file_path: String,
     pattern: String,
     request_id: String,
-) -> Result<Vec<ChapterMeta>, String> {
-    let chapters = tauri::async_runtime::spawn_blocking(move || parse_import_txt_sync(file_path, pattern))
-        .await
-        .map_err(|e| format!("Task join error: {e}"))??;
+    encoding: Option<String>,
+    options: Option<ImportTxtOptions>,
+) -> Result<ImportTxtResult, String> {
+    let options = options.unwrap_or_default();
+    let rollback_on_cancel = options.rollback_on_cancel;
+    let project_path_for_plan = project_path.clone();
+    let plan = tauri::async_runtime::spawn_blocking(move || {
+        let chapters = parse_import_txt_sync(file_path, pattern, encoding, options.preamble_title())?;
+        if chapters.is_empty() {
+            return Err("No chapters matched the pattern".to_string());
+        }
+        plan_selected(&project_path_for_plan, chapters, &options)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??;
+
+    run_import(window, project_path, request_id, plan, rollback_on_cancel).await
+}
 
+/// Applies the preview selection and then the conflict strategy to parsed chapters.
+fn plan_selected(
+    project_path: &str,
+    chapters: Vec<ChapterData>,
+    options: &ImportTxtOptions,
+) -> Result<ImportPlan, String> {
+    let chapters = select_chapters(chapters, options.selected_indices.as_deref())?;
     if chapters.is_empty() {
-        return Err("No chapters matched the pattern".to_string());
+        return Err("No chapters selected for import".to_string());
     }
+    plan_import(project_path, chapters, options.conflict_strategy)
+}
 
-    let total = chapters.len() as u32;
-    let _ = window.emit(
-        IMPORT_TXT_PROGRESS_EVENT,
+/// Carries out `plan`, emitting progress events under `request_id` and honouring
+/// `import_txt_cancel` for it.
+async fn run_import(
+    window: tauri::Window,
+    project_path: String,
+    request_id: String,
+    plan: ImportPlan,
+    rollback_on_cancel: bool,
+) -> Result<ImportTxtResult, String> {
+    let total = (plan.create.len() + plan.overwrite.len()) as u32;
+    if total == 0 {
+        return Ok(ImportTxtResult {
+            chapters: Vec::new(),
+            cancelled: false,
+            rolled_back: false,
+            skipped: plan.skipped,
+            overwritten: 0,
+            warnings: Vec::new(),
+        });
+    }
+
+    let progress = move |request_id: &str, completed: u32, current_title: Option<String>, cancelled: bool| {
         ImportTxtProgress {
-            request_id: request_id.clone(),
+            request_id: request_id.to_string(),
             total,
-            completed: 0,
-            current_title: None,
-        },
-    );
-
-    let mut created = Vec::with_capacity(chapters.len());
-    for (index, chapter) in chapters.into_iter().enumerate() {
-        let project_path_for_task = project_path.clone();
-        let title_for_task = chapter.title.clone();
-        let content_for_task = chapter.content;
-
-        let meta = tauri::async_runtime::spawn_blocking(move || {
-            crate::chapter::create_chapter_with_content_sync(
-                project_path_for_task,
-                title_for_task,
-                content_for_task,
-            )
+            completed,
+            current_title,
+            cancelled,
+        }
+    };
+    let _ = window.emit(IMPORT_TXT_PROGRESS_EVENT, progress(&request_id, 0, None, false));
+
+    let cancel = window.state::<ImportRuntime>().begin(&request_id)?;
+    let window_for_task = window.clone();
+    let request_id_for_task = request_id.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        import_chapters_sync(&project_path, plan, &cancel, rollback_on_cancel, |completed, title| {
+            let _ = window_for_task.emit(
+                IMPORT_TXT_PROGRESS_EVENT,
+                progress(&request_id_for_task, completed, Some(title.to_string()), false),
+            );
         })
-        .await
-        .map_err(|e| format!("Task join error: {e}"))??;
-
-        created.push(meta);
-
-        let completed = (index + 1) as u32;
-        let _ = window.emit(
-            IMPORT_TXT_PROGRESS_EVENT,
-            ImportTxtProgress {
-                request_id: request_id.clone(),
-                total,
-                completed,
-                current_title: Some(chapter.title),
-            },
-        );
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"));
+    window.state::<ImportRuntime>().finish(&request_id);
+
+    let result = result??;
+    if result.cancelled {
+        let completed = result.chapters.len() as u32;
+        let _ = window.emit(IMPORT_TXT_PROGRESS_EVENT, progress(&request_id, completed, None, true));
     }
+    Ok(result)
+}
+
+/// Chapters of a Markdown file; a file without headings is one chapter named after it.
+fn parse_import_markdown_sync(
+    file_path: &str,
+    heading_level: Option<u8>,
+    encoding: Option<&str>,
+    preamble_title: Option<&str>,
+) -> Result<(Vec<ChapterData>, &'static str), String> {
+    let (content, encoding) = read_txt(file_path, encoding)?;
+    let content = normalize_content(content);
+    let file_title = Path::new(file_path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| UNTITLED_CHAPTER_TITLE.to_string());
+    let level = heading_level.unwrap_or(DEFAULT_MARKDOWN_HEADING_LEVEL);
+    let chapters = parse_chapters_from_markdown(&content, level, preamble_title, &file_title)?;
+    Ok((chapters, encoding))
+}
+
+/// Like `preview_import_txt`, splitting at Markdown headings of `heading_level` (1–3,
+/// default 1) instead of a pattern.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn preview_import_markdown(
+    file_path: String,
+    heading_level: Option<u8>,
+    encoding: Option<String>,
+    options: Option<ImportTxtOptions>,
+    project_path: Option<String>,
+) -> Result<ImportTxtPreview, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let options = options.unwrap_or_default();
+        // Always look for a preamble so the UI can offer it even when it is excluded.
+        let title = options.preamble_title().unwrap_or(DEFAULT_PREAMBLE_TITLE);
+        let (chapters, encoding) =
+            parse_import_markdown_sync(&file_path, heading_level, encoding.as_deref(), Some(title))?;
+        Ok(build_preview(encoding, chapters, &options, &existing_chapters(project_path)?))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+/// Like `import_txt` for a Markdown file; progress events and cancellation are shared.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn import_markdown(
+    window: tauri::Window,
+    project_path: String,
+    file_path: String,
+    heading_level: Option<u8>,
+    request_id: String,
+    encoding: Option<String>,
+    options: Option<ImportTxtOptions>,
+) -> Result<ImportTxtResult, String> {
+    let options = options.unwrap_or_default();
+    let rollback_on_cancel = options.rollback_on_cancel;
+    let project_path_for_plan = project_path.clone();
+    let plan = tauri::async_runtime::spawn_blocking(move || {
+        let (chapters, _) =
+            parse_import_markdown_sync(&file_path, heading_level, encoding.as_deref(), options.preamble_title())?;
+        if chapters.is_empty() {
+            return Err("The Markdown file is empty".to_string());
+        }
+        plan_selected(&project_path_for_plan, chapters, &options)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??;
 
-    Ok(created)
+    run_import(window, project_path, request_id, plan, rollback_on_cancel).await
+}
+
+/// Asks the running `import_txt` with this request id to stop after its current chapter.
+#[tauri::command(rename_all = "camelCase")]
+pub fn import_txt_cancel(runtime: tauri::State<ImportRuntime>, request_id: String) -> Result<(), String> {
+    let flags = runtime
+        .cancel_flags
+        .lock()
+        .map_err(|_| "import_txt_cancel lock poisoned".to_string())?;
+    match flags.get(&request_id) {
+        Some(flag) => {
+            flag.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+        None => Err("No running import with this request id".to_string()),
+    }
 }
 
 #[cfg(test)]
@@ -181,7 +992,7 @@ mod tests {
     #[test]
     fn parse_chapters_uses_multiline_anchors() {
         let text = "前言\n第一章 开端\nhello\n\n第二章 转折\nworld\n";
-        let chapters = parse_chapters_from_text(text, "^第.+章.*").expect("parse");
+        let chapters = parse_chapters_from_text(text, "^第.+章.*", None).expect("parse");
         assert_eq!(chapters.len(), 2);
         assert_eq!(chapters[0].title, "第一章 开端");
         assert_eq!(chapters[0].content, "hello");
@@ -189,16 +1000,453 @@ mod tests {
         assert_eq!(chapters[1].title, "第二章 转折");
         assert_eq!(chapters[1].content, "world");
         assert_eq!(chapters[1].word_count, 5);
+
+        let text = "楔子\n\n那一年大雪封山。\n\n没有人知道他从哪里来。\n第一章 开端\nhello\n";
+        let chapters = parse_chapters_from_text(text, "^第.+章.*", Some("前言")).expect("parse");
+        assert_eq!(chapters.len(), 2);
+        assert!(chapters[0].is_preamble);
+        assert_eq!(chapters[0].title, "前言");
+        assert_eq!(chapters[0].content, "楔子\n\n那一年大雪封山。\n\n没有人知道他从哪里来。");
+        assert_eq!(chapters[0].word_count, 21);
+        assert!(!chapters[1].is_preamble);
+        assert_eq!(chapters[1].title, "第一章 开端");
+
+        let chapters = parse_chapters_from_text(text, "^第.+章.*", None).expect("parse");
+        assert_eq!(chapters.len(), 1);
+        // Without any heading there is nothing to import, preamble or not.
+        assert!(parse_chapters_from_text("只有正文", "^第.+章.*", Some("前言")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn markdown_splits_at_the_chosen_heading_level() {
+        let text = "# 长夜\n\n卷首语\n\n## 第一章 开端 ##\n正文一\n### 场景\n细节\n\n\
+```markdown\n## 不是标题\n```\n\n## 第二章\n~~~~\n# 也不是\n```\n~~~~\n正文二\n\n\
+设定\n====\n##不是标题\n";
+
+        let chapters = parse_chapters_from_markdown(text, 2, Some("前言"), "草稿").expect("parse");
+        let titles: Vec<&str> = chapters.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, ["前言", "第一章 开端", "第二章"]);
+        assert_eq!(chapters[0].content, "# 长夜\n\n卷首语");
+        assert!(chapters[1].content.starts_with("正文一\n### 场景\n细节"));
+        assert!(chapters[1].content.ends_with("## 不是标题\n```"));
+        assert!(chapters[2].content.ends_with("正文二\n\n设定\n====\n##不是标题"));
+        assert_eq!(chapters[2].word_count, count_words(&chapters[2].content));
+
+        let chapters = parse_chapters_from_markdown(text, 1, None, "草稿").expect("parse");
+        assert_eq!(chapters.len(), 1);
+        assert_eq!(chapters[0].title, "长夜");
+
+        let titles: Vec<String> = parse_chapters_from_markdown(text, 3, None, "草稿")
+            .expect("parse")
+            .into_iter()
+            .map(|c| c.title)
+            .collect();
+        assert_eq!(titles, ["场景"]);
+        assert!(parse_chapters_from_markdown(text, 4, None, "草稿").is_err());
+    }
+
+    #[test]
+    fn markdown_without_headings_is_one_chapter() {
+        let text = "标题\n===\n\n只有正文，没有标题。\n```\n# 代码\n```\n";
+        let chapters = parse_chapters_from_markdown(text, 1, Some("前言"), "草稿").expect("parse");
+        assert_eq!(chapters.len(), 1);
+        assert_eq!(chapters[0].title, "草稿");
+        assert!(!chapters[0].is_preamble);
+        assert_eq!(chapters[0].content, text.trim());
+        assert!(parse_chapters_from_markdown(" \n\n", 1, Some("前言"), "草稿").unwrap().is_empty());
+
+        let path = std::env::temp_dir().join(format!("creatorai-v2-import-md-{}.md", std::process::id()));
+        fs::write(&path, text).unwrap();
+        let (chapters, _) = parse_import_markdown_sync(&path.to_string_lossy(), None, None, None).expect("read");
+        let _ = fs::remove_file(&path);
+        assert_eq!(chapters[0].title, path.file_stem().unwrap().to_string_lossy());
+    }
+
+    #[test]
+    fn preview_pattern_comes_from_the_request_or_saved_patterns() {
+        let saved = [ImportPattern {
+            id: "site".to_string(),
+            name: "某站".to_string(),
+            pattern: r"^正文\s+第.+章.*".to_string(),
+        }];
+        assert_eq!(resolve_pattern(None, Some("site"), &saved).unwrap(), saved[0].pattern);
+        assert_eq!(resolve_pattern(Some(" ".to_string()), Some("site"), &saved).unwrap(), saved[0].pattern);
+        assert_eq!(resolve_pattern(Some("^卷.+".to_string()), None, &saved).unwrap(), "^卷.+");
+        assert_eq!(resolve_pattern(None, None, &saved).unwrap(), "");
+        assert!(resolve_pattern(Some("^卷.+".to_string()), Some("site"), &saved).is_err());
+        let err = resolve_pattern(None, Some("gone"), &saved).unwrap_err();
+        assert!(err.contains("gone"), "{err}");
+    }
+
+    #[test]
+    fn selected_indices_pick_chapters_from_the_preview() {
+        let text = "第一章 开端\nA\n上架感言\n第二章 求票\n求月票\n第三章 转折\nC\n第四章 请假\n今天请假\n";
+        let parse = || parse_chapters_from_text(text, "^第.+章.*", Some("前言")).expect("parse");
+        assert_eq!(parse().len(), 4);
+
+        let chosen = select_chapters(parse(), Some(&[2, 0])).expect("select");
+        let titles: Vec<&str> = chosen.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, ["第一章 开端", "第三章 转折"]);
+        assert_eq!(chosen[0].content, "A\n上架感言");
+
+        assert_eq!(select_chapters(parse(), None).unwrap().len(), 4);
+        let err = select_chapters(parse(), Some(&[1, 4])).unwrap_err();
+        assert!(err.contains("4 is out of range"), "{err}");
+    }
+
+    fn min_project(name: &str) -> PathBuf {
+        let ts = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("{name}-{ts}"));
+        fs::create_dir_all(root.join(".creatorai")).unwrap();
+        fs::create_dir_all(root.join("chapters")).unwrap();
+        fs::write(root.join(".creatorai/config.json"), "{}\n").unwrap();
+        fs::write(
+            root.join("chapters/index.json"),
+            r#"{ "chapters": [
+  { "id": "chapter_001", "title": "旧章", "order": 1, "created": 1, "updated": 1, "wordCount": 2 }
+], "nextId": 2 }
+"#,
+        )
+        .unwrap();
+        fs::write(root.join("chapters/chapter_001.txt"), "旧文").unwrap();
+        root
+    }
+
+    fn duplicate_all(chapters: Vec<ChapterData>) -> ImportPlan {
+        ImportPlan {
+            create: chapters,
+            ..ImportPlan::default()
+        }
+    }
+
+    #[test]
+    fn conflict_strategies_handle_existing_titles() {
+        let text = "第一章 开端\n新一\n第二章 转折\n新二\n第三章 高潮\n新三\n第四章 结局\n新四\n";
+        let import = |strategy| {
+            let root = min_project("creatorai-v2-import-conflict");
+            fs::write(
+                root.join("chapters/index.json"),
+                r#"{ "chapters": [
+  { "id": "chapter_001", "title": "第一章 开端", "order": 1, "created": 1, "updated": 1, "wordCount": 2 },
+  { "id": "chapter_002", "title": "第三章  高潮", "order": 2, "created": 1, "updated": 1, "wordCount": 2 }
+], "nextId": 3 }
+"#,
+            )
+            .unwrap();
+            fs::write(root.join("chapters/chapter_002.txt"), "旧三").unwrap();
+
+            let chapters = parse_chapters_from_text(text, "", None).expect("parse");
+            let existing = existing_chapters(Some(root.to_string_lossy().into_owned())).unwrap();
+            let preview = build_preview("UTF-8", chapters.clone(), &ImportTxtOptions::default(), &existing);
+            let existing: Vec<Option<&str>> = preview.chapters.iter().map(|c| c.existing_chapter_id.as_deref()).collect();
+            assert_eq!(existing, [Some("chapter_001"), None, Some("chapter_002"), None]);
+
+            let project_path = root.to_string_lossy().into_owned();
+            let plan = plan_import(&project_path, chapters, strategy).expect("plan");
+            let result = import_chapters_sync(&project_path, plan, &AtomicBool::new(false), false, |_, _| {}).expect("import");
+            let index: crate::project::ChapterIndex =
+                serde_json::from_str(&fs::read_to_string(root.join("chapters/index.json")).unwrap()).unwrap();
+            (root, result, index)
+        };
+
+        let (root, result, index) = import(ConflictStrategy::Duplicate);
+        assert_eq!((result.chapters.len(), result.skipped, result.overwritten), (4, 0, 0));
+        assert_eq!(index.chapters.len(), 6);
+        let _ = fs::remove_dir_all(&root);
+
+        let (root, result, index) = import(ConflictStrategy::Skip);
+        let titles: Vec<&str> = result.chapters.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, ["第二章 转折", "第四章 结局"]);
+        assert_eq!((result.skipped, result.overwritten), (2, 0));
+        assert_eq!(index.chapters.len(), 4);
+        assert_eq!(fs::read_to_string(root.join("chapters/chapter_002.txt")).unwrap(), "旧三");
+        let _ = fs::remove_dir_all(&root);
+
+        let (root, result, index) = import(ConflictStrategy::Overwrite);
+        assert_eq!((result.chapters.len(), result.skipped, result.overwritten), (4, 0, 2));
+        assert_eq!(index.chapters.len(), 4);
+        assert_eq!(index.next_id, 5);
+        let third = index.chapters.iter().find(|c| c.id == "chapter_002").unwrap();
+        assert_eq!((third.title.as_str(), third.order, third.word_count), ("第三章  高潮", 2, 2));
+        assert!(third.updated > 1);
+        assert_eq!(fs::read_to_string(root.join("chapters/chapter_002.txt")).unwrap(), "新三");
+        assert_eq!(fs::read_to_string(root.join("chapters/chapter_001.txt")).unwrap(), "新一");
+        let created: Vec<(&str, u32)> = index.chapters[2..].iter().map(|c| (c.title.as_str(), c.order)).collect();
+        assert_eq!(created, [("第二章 转折", 3), ("第四章 结局", 4)]);
+        let backups =
+            crate::write_protection::list_backups(&root.canonicalize().unwrap(), Some("chapters/chapter_002.txt"))
+                .unwrap();
+        assert_eq!(backups.iter().map(|f| f.backups.len()).sum::<usize>(), 1);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn cancelled_import_keeps_or_rolls_back_created_chapters() {
+        let text = "第一章\nA\n第二章\nB\n第三章\nC\n第四章\nD\n";
+        for rollback in [false, true] {
+            let root = min_project("creatorai-v2-import-cancel");
+            let index_before = fs::read_to_string(root.join("chapters/index.json")).unwrap();
+            let chapters = parse_chapters_from_text(text, "", None).expect("parse");
+            let cancel = AtomicBool::new(false);
+            let mut seen = Vec::new();
+
+            // A slow import the user cancels while the second chapter is being written.
+            let result = import_chapters_sync(&root.to_string_lossy(), duplicate_all(chapters), &cancel, rollback, |completed, title| {
+                std::thread::sleep(std::time::Duration::from_millis(5));
+                seen.push(title.to_string());
+                if completed == 2 {
+                    cancel.store(true, Ordering::SeqCst);
+                }
+            })
+            .expect("import");
+
+            assert!(result.cancelled);
+            assert_eq!(seen, ["第一章", "第二章"]);
+            assert!(!root.join("chapters/chapter_004.txt").exists());
+            if rollback {
+                assert!(result.rolled_back);
+                assert!(result.chapters.is_empty());
+                assert_eq!(fs::read_to_string(root.join("chapters/index.json")).unwrap(), index_before);
+                assert!(!root.join("chapters/chapter_002.txt").exists());
+                assert!(root.join("chapters/chapter_001.txt").exists());
+            } else {
+                assert!(!result.rolled_back);
+                let ids: Vec<&str> = result.chapters.iter().map(|c| c.id.as_str()).collect();
+                assert_eq!(ids, ["chapter_002", "chapter_003"]);
+                let index = fs::read_to_string(root.join("chapters/index.json")).unwrap();
+                assert!(index.contains("chapter_003") && !index.contains("chapter_004"), "{index}");
+            }
+            let _ = fs::remove_dir_all(&root);
+        }
+    }
+
+    #[test]
+    fn overwrites_run_first_and_all_or_nothing() {
+        let root = min_project("creatorai-v2-import-overwrite-first");
+        let project_path = root.to_string_lossy().into_owned();
+        let index_before = fs::read_to_string(root.join("chapters/index.json")).unwrap();
+        let chapter = |title: &str, content: &str| ChapterData {
+            title: title.to_string(),
+            content: content.to_string(),
+            word_count: count_words(content),
+            is_preamble: false,
+        };
+        let plan = |overwrite_id: &str| ImportPlan {
+            create: vec![chapter("新章", "新文")],
+            overwrite: vec![
+                ("chapter_001".to_string(), chapter("旧章", "改写")),
+                (overwrite_id.to_string(), chapter("不存在", "无")),
+            ],
+            skipped: 0,
+        };
+
+        // One overwrite target is missing: nothing is replaced and nothing is created.
+        let err = import_chapters_sync(&project_path, plan("chapter_009"), &AtomicBool::new(false), false, |_, _| {})
+            .unwrap_err();
+        assert!(err.contains("chapter_009"), "{err}");
+        assert_eq!(fs::read_to_string(root.join("chapters/index.json")).unwrap(), index_before);
+        assert_eq!(fs::read_to_string(root.join("chapters/chapter_001.txt")).unwrap(), "旧文");
+        assert!(!root.join("chapters/chapter_002.txt").exists());
+
+        // Cancelled before it starts: nothing is written.
+        let result = import_chapters_sync(&project_path, plan("chapter_001"), &AtomicBool::new(true), false, |_, _| {})
+            .expect("cancelled import");
+        assert!(result.cancelled);
+        assert_eq!((result.chapters.len(), result.overwritten), (0, 0));
+        assert_eq!(fs::read_to_string(root.join("chapters/chapter_001.txt")).unwrap(), "旧文");
+
+        // Cancelled during the overwrites: they land, the new chapter is not created.
+        let cancel = AtomicBool::new(false);
+        let plan = ImportPlan {
+            create: vec![chapter("新章", "新文")],
+            overwrite: vec![("chapter_001".to_string(), chapter("旧章", "改写"))],
+            skipped: 0,
+        };
+        let result = import_chapters_sync(&project_path, plan, &cancel, false, |_, _| {
+            cancel.store(true, Ordering::SeqCst);
+        })
+        .expect("import");
+        assert!(result.cancelled);
+        assert_eq!(result.overwritten, 1);
+        assert_eq!(fs::read_to_string(root.join("chapters/chapter_001.txt")).unwrap(), "改写");
+        assert!(!root.join("chapters/chapter_002.txt").exists());
+        let log = fs::read_to_string(root.join(".creatorai/activity.jsonl")).unwrap_or_default();
+        assert!(log.contains("chapters/chapter_001.txt"), "{log}");
+
+        // The same cancel with rollback puts the overwritten content back.
+        let cancel = AtomicBool::new(false);
+        let plan = ImportPlan {
+            create: vec![chapter("新章", "新文")],
+            overwrite: vec![("chapter_001".to_string(), chapter("旧章", "再改写"))],
+            skipped: 0,
+        };
+        let result = import_chapters_sync(&project_path, plan, &cancel, true, |_, _| {
+            cancel.store(true, Ordering::SeqCst);
+        })
+        .expect("import");
+        assert!(result.cancelled && result.rolled_back);
+        assert_eq!((result.chapters.len(), result.overwritten), (0, 0));
+        assert_eq!(fs::read_to_string(root.join("chapters/chapter_001.txt")).unwrap(), "改写");
+        assert!(!root.join("chapters/chapter_002.txt").exists());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn hundred_chapter_import_writes_the_index_once() {
+        let root = min_project("creatorai-v2-import-bulk");
+        let text: String = (1..=100).map(|n| format!("第{n}章 标题{n}\n正文{n}\n")).collect();
+        let chapters = parse_chapters_from_text(&text, "", None).expect("parse");
+        assert_eq!(chapters.len(), 100);
+
+        let writes_before = chapter::INDEX_WRITES.with(|n| n.get());
+        let mut progress = 0;
+        let result = import_chapters_sync(&root.to_string_lossy(), duplicate_all(chapters), &AtomicBool::new(false), false, |completed, _| {
+            progress = completed;
+        })
+        .expect("import");
+        assert_eq!(chapter::INDEX_WRITES.with(|n| n.get()) - writes_before, 1);
+        assert_eq!(progress, 100);
+
+        assert_eq!(result.chapters.len(), 100);
+        assert_eq!(result.chapters[0].id, "chapter_002");
+        assert_eq!(result.chapters[99].id, "chapter_101");
+        assert_eq!(result.chapters[99].order, 101);
+        assert_eq!(result.chapters[99].word_count, count_words("正文100"));
+        assert_eq!(fs::read_to_string(root.join("chapters/chapter_101.txt")).unwrap(), "正文100");
+        let index: crate::project::ChapterIndex =
+            serde_json::from_str(&fs::read_to_string(root.join("chapters/index.json")).unwrap()).unwrap();
+        assert_eq!(index.chapters.len(), 101);
+        assert_eq!(index.next_id, 102);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn import_runtime_cancels_by_request_id() {
+        let runtime = ImportRuntime::default();
+        let flag = runtime.begin("req-1").unwrap();
+        runtime.cancel_flags.lock().unwrap()["req-1"].store(true, Ordering::SeqCst);
+        assert!(flag.load(Ordering::SeqCst));
+        runtime.finish("req-1");
+        assert!(runtime.cancel_flags.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn preview_reports_preamble_even_when_excluded() {
+        let ts = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let file = std::env::temp_dir().join(format!("creatorai-v2-import-preamble-{ts}.txt"));
+        fs::write(&file, "作者的话\n\n第一章 开端\nhello\n").unwrap();
+        let preview = |options: ImportTxtOptions| {
+            preview_import_txt_sync(file.to_string_lossy().into_owned(), String::new(), None, options, None)
+                .expect("preview")
+        };
+
+        let included = preview(ImportTxtOptions::default());
+        let titles: Vec<(&str, bool)> = included.chapters.iter().map(|c| (c.title.as_str(), c.is_preamble)).collect();
+        assert_eq!(titles, [("前言", true), ("第一章 开端", false)]);
+        assert_eq!(included.preamble_word_count, Some(4));
+
+        let renamed = preview(ImportTxtOptions { preamble_title: Some("楔子".to_string()), ..Default::default() });
+        assert_eq!(renamed.chapters[0].title, "楔子");
+
+        let excluded = preview(ImportTxtOptions { include_preamble: Some(false), ..Default::default() });
+        assert_eq!(excluded.chapters.len(), 1);
+        assert_eq!(excluded.preamble_word_count, Some(4));
+        let _ = fs::remove_file(&file);
+    }
+
+    #[test]
+    fn gbk_and_big5_files_are_detected_and_transcoded() {
+        let simplified = "第一章 风起\n江湖上的传说从来没有停止过，少年背着剑走出了小镇。\n\n第二章 云涌\n他在客栈里遇到了一个神秘的老人，老人告诉他关于门派的故事。\n";
+        let (gbk, _, _) = encoding_rs::GBK.encode(simplified);
+        let (text, encoding) = decode_txt(&gbk, None).expect("decode gbk");
+        assert_eq!((text.as_str(), encoding), (simplified, "GBK"));
+        let chapters = parse_chapters_from_text(&text, DEFAULT_CHAPTER_PATTERN, None).expect("parse");
+        let titles: Vec<&str> = chapters.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, ["第一章 风起", "第二章 云涌"]);
+
+        let traditional = "第一章 風起\n江湖上的傳說從來沒有停止過，少年背著劍走出了小鎮。\n\n第二章 雲湧\n他在客棧裡遇到了一個神秘的老人，老人告訴他關於門派的故事。\n";
+        let (big5, _, _) = encoding_rs::BIG5.encode(traditional);
+        let (text, encoding) = decode_txt(&big5, None).expect("decode big5");
+        assert_eq!((text.as_str(), encoding), (traditional, "Big5"));
+        let chapters = parse_chapters_from_text(&text, DEFAULT_CHAPTER_PATTERN, None).expect("parse");
+        assert_eq!(chapters[1].title, "第二章 雲湧");
+
+        // A forced codec overrides detection; "auto" and UTF-8 files behave as before.
+        let (_, encoding) = decode_txt(&gbk, Some("gb18030")).expect("forced");
+        assert_eq!(encoding, "gb18030");
+        assert_eq!(decode_txt(simplified.as_bytes(), Some("auto")).unwrap().1, "UTF-8");
+        assert!(decode_txt(&gbk, Some("klingon")).is_err());
     }
 
     #[test]
     fn parse_chapters_empty_pattern_falls_back_to_default() {
         let text = "第一章\nA\n第二章\nB\n";
-        let chapters = parse_chapters_from_text(text, "").expect("parse");
+        let chapters = parse_chapters_from_text(text, "", None).expect("parse");
         assert_eq!(chapters.len(), 2);
         assert_eq!(chapters[0].title, "第一章");
         assert_eq!(chapters[0].content, "A");
         assert_eq!(chapters[1].title, "第二章");
         assert_eq!(chapters[1].content, "B");
     }
+
+    #[test]
+    fn outline_sections_map_to_chapters_by_title_then_order() {
+        let ts = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("creatorai-v2-import-summaries-{ts}"));
+        fs::create_dir_all(root.join(".creatorai")).unwrap();
+        fs::create_dir_all(root.join("chapters")).unwrap();
+        fs::write(root.join(".creatorai/config.json"), "{}\n").unwrap();
+        fs::write(
+            root.join("chapters/index.json"),
+            r#"{ "chapters": [
+  { "id": "chapter_001", "title": "第一章 开端", "order": 1, "created": 1, "updated": 1, "wordCount": 0 },
+  { "id": "chapter_002", "title": "第二章 夜行", "order": 2, "created": 1, "updated": 1, "wordCount": 0 },
+  { "id": "chapter_003", "title": "第三章 重逢", "order": 3, "created": 1, "updated": 1, "wordCount": 0 }
+], "nextId": 4 }
+"#,
+        )
+        .unwrap();
+        let outline = root.join("大纲.txt");
+        fs::write(&outline, "第一章\n主角登场。\n第九章 尾声\n大结局。\n第二章  夜行\n主角连夜出城。\n").unwrap();
+        let (project, file) = (root.to_string_lossy().into_owned(), outline.to_string_lossy().into_owned());
+
+        let plan = plan_summary_import(&project, &file, "").expect("preview");
+        let mapped: Vec<(&str, &str, SummaryMatch)> = plan
+            .mappings
+            .iter()
+            .map(|m| (m.section_title.as_str(), m.chapter_id.as_str(), m.matched_by))
+            .collect();
+        assert_eq!(
+            mapped,
+            [
+                ("第一章", "chapter_001", SummaryMatch::Order),
+                ("第二章  夜行", "chapter_002", SummaryMatch::Title),
+            ]
+        );
+        // Position 2 already went to the title match.
+        assert_eq!(plan.unmatched_sections, ["第九章 尾声"]);
+        assert_eq!(plan.chapters_without_summary, ["chapter_003"]);
+        assert!(!root.join("summaries/chapter_001.json").exists());
+
+        // Empty sections are skipped, leaving their chapter uncovered.
+        fs::write(&outline, "第一章 开端\n主角登场。\n第二章 夜行\n\n第三章 重逢\n故人相见。\n第四章\n无处安放。\n")
+            .unwrap();
+        let plan = import_summaries_sync(project.clone(), file, String::new()).expect("import");
+        assert_eq!(plan.mappings.len(), 2);
+        assert_eq!(plan.unmatched_sections, ["第四章"]);
+        assert_eq!(plan.chapters_without_summary, ["chapter_002"]);
+        let saved = summary::get_latest_summary(&root, "chapter_001").expect("latest").expect("saved");
+        assert_eq!(saved.summary, "主角登场。");
+
+        let _ = fs::remove_dir_all(&root);
+    }
 }
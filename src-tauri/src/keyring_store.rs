@@ -1,14 +1,46 @@
 use keyring::Entry;
+use serde::Serialize;
 use sha2::{Digest, Sha256};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::crypto::{self, EncryptedBlob};
 
 const SERVICE_NAME: &str = "creatorai";
 const BUILTIN_DEMO_PROVIDER_ID: &str = "builtin_dashscope_qwen_demo";
 const LEAKED_BUILTIN_DEMO_API_KEY_SHA256: &str =
     "3a8e03e89c2bfa7d360dea9f57476bac4e922cbcf6a876ae68d662a388331a0e";
-const LOCAL_API_KEYS_FILE: &str = "api_keys.local.json";
+/// Plaintext store used by older releases; migrated into `SECRETS_FILE` on first read.
+const LEGACY_LOCAL_API_KEYS_FILE: &str = "api_keys.local.json";
+/// Fallback store for keys the OS keyring could not take.
+const SECRETS_FILE: &str = "secrets.bin";
+const KEY_INDEX_FILE: &str = "api_key_ids.json";
+/// When set, the secrets file is encrypted with this passphrase instead of a key derived
+/// from the machine identifier.
+const SECRETS_PASSPHRASE_ENV: &str = "CREATORAI_SECRETS_PASSPHRASE";
+const KEYRING_PROBE_USER: &str = "__creatorai_probe__";
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum KeyStorageBackend {
+    Keyring,
+    EncryptedFile,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyStorageInfo {
+    pub backend: KeyStorageBackend,
+    /// Why the OS keyring is unusable, when `backend` is `EncryptedFile`.
+    pub keyring_error: Option<String>,
+    pub uses_passphrase: bool,
+    pub secrets_path: String,
+}
+
+/// Decrypted contents of the secrets file, keyed by the file path they came from.
+static LOCAL_KEYS_CACHE: Mutex<Option<(PathBuf, HashMap<String, String>)>> = Mutex::new(None);
 
 fn sha256_hex(input: &str) -> String {
     let mut hasher = Sha256::new();
@@ -16,65 +48,162 @@ fn sha256_hex(input: &str) -> String {
     format!("{:x}", hasher.finalize())
 }
 
-fn local_api_keys_path() -> Result<PathBuf, String> {
-    Ok(crate::config::get_global_config_dir()?.join(LOCAL_API_KEYS_FILE))
+fn secrets_path(dir: &Path) -> PathBuf {
+    dir.join(SECRETS_FILE)
+}
+
+fn legacy_local_api_keys_path(dir: &Path) -> PathBuf {
+    dir.join(LEGACY_LOCAL_API_KEYS_FILE)
+}
+
+fn key_index_path(dir: &Path) -> PathBuf {
+    dir.join(KEY_INDEX_FILE)
 }
 
-fn read_local_api_keys() -> Result<HashMap<String, String>, String> {
-    let path = local_api_keys_path()?;
-    if !path.exists() {
-        return Ok(HashMap::new());
+fn machine_identifier() -> String {
+    for path in ["/etc/machine-id", "/var/lib/dbus/machine-id"] {
+        if let Ok(id) = fs::read_to_string(path) {
+            let id = id.trim();
+            if !id.is_empty() {
+                return id.to_string();
+            }
+        }
     }
-    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
-    serde_json::from_str(&content).map_err(|e| e.to_string())
+    // No stable OS identifier: fall back to values that rarely change for one user account.
+    let host = std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .unwrap_or_default();
+    let user = std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_default();
+    let home = dirs::home_dir()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+    format!("{host}|{user}|{home}")
 }
 
-fn write_local_api_keys(keys: &HashMap<String, String>) -> Result<(), String> {
-    let path = local_api_keys_path()?;
-    let content = serde_json::to_string_pretty(keys).map_err(|e| e.to_string())?;
-    fs::write(path, content).map_err(|e| e.to_string())
+fn user_passphrase() -> Option<String> {
+    std::env::var(SECRETS_PASSPHRASE_ENV)
+        .ok()
+        .filter(|p| !p.is_empty())
 }
 
-fn store_local_api_key(provider_id: &str, api_key: &str) -> Result<(), String> {
-    let mut keys = read_local_api_keys()?;
-    keys.insert(provider_id.to_string(), api_key.to_string());
-    write_local_api_keys(&keys)
+fn secrets_passphrase() -> String {
+    user_passphrase().unwrap_or_else(|| format!("creatorai-secrets:{}", machine_identifier()))
 }
 
-fn get_local_api_key(provider_id: &str) -> Result<Option<String>, String> {
-    let keys = read_local_api_keys()?;
-    Ok(keys.get(provider_id).cloned())
+fn decrypt_secrets_file(path: &Path) -> Result<HashMap<String, String>, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read {SECRETS_FILE}: {e}"))?;
+    let blob: EncryptedBlob = serde_json::from_slice(&bytes)
+        .map_err(|e| format!("Failed to parse {SECRETS_FILE}: {e}"))?;
+    let plaintext = crypto::decrypt_with_passphrase(&secrets_passphrase(), &blob).map_err(|e| {
+        format!("Failed to decrypt {SECRETS_FILE} ({e}); was {SECRETS_PASSPHRASE_ENV} changed?")
+    })?;
+    serde_json::from_slice(&plaintext).map_err(|e| format!("Invalid {SECRETS_FILE} payload: {e}"))
 }
 
-fn delete_local_api_key(provider_id: &str) -> Result<(), String> {
-    let mut keys = read_local_api_keys()?;
-    if keys.remove(provider_id).is_some() {
-        write_local_api_keys(&keys)?;
+fn read_local_api_keys(dir: &Path) -> Result<HashMap<String, String>, String> {
+    let path = secrets_path(dir);
+    if let Ok(cache) = LOCAL_KEYS_CACHE.lock() {
+        if let Some((cached_path, keys)) = cache.as_ref() {
+            if *cached_path == path {
+                return Ok(keys.clone());
+            }
+        }
+    }
+
+    let keys = if path.exists() {
+        decrypt_secrets_file(&path)?
+    } else {
+        let legacy = legacy_local_api_keys_path(dir);
+        if !legacy.exists() {
+            return Ok(HashMap::new());
+        }
+        let content = fs::read_to_string(&legacy).map_err(|e| e.to_string())?;
+        let keys: HashMap<String, String> =
+            serde_json::from_str(&content).map_err(|e| e.to_string())?;
+        write_local_api_keys(dir, &keys)?;
+        let _ = fs::remove_file(&legacy);
+        keys
+    };
+
+    if let Ok(mut cache) = LOCAL_KEYS_CACHE.lock() {
+        *cache = Some((path, keys.clone()));
+    }
+    Ok(keys)
+}
+
+fn write_local_api_keys(dir: &Path, keys: &HashMap<String, String>) -> Result<(), String> {
+    let path = secrets_path(dir);
+    let plaintext = serde_json::to_vec(keys).map_err(|e| e.to_string())?;
+    let blob = crypto::encrypt_with_passphrase(&secrets_passphrase(), &plaintext)?;
+    let content = serde_json::to_vec(&blob).map_err(|e| e.to_string())?;
+    crate::write_protection::atomic_write_bytes(&path, &content, None)?;
+    if let Ok(mut cache) = LOCAL_KEYS_CACHE.lock() {
+        *cache = Some((path, keys.clone()));
     }
     Ok(())
 }
 
-pub fn store_api_key(provider_id: &str, api_key: &str) -> Result<(), String> {
-    let local_result = store_local_api_key(provider_id, api_key);
-    let keychain_result = Entry::new(SERVICE_NAME, provider_id)
-        .map_err(|e| e.to_string())
-        .and_then(|entry| entry.set_password(api_key).map_err(|e| e.to_string()));
+/// Ids of every stored key. The OS keyring cannot be enumerated, so this list is what
+/// orphaned-key detection works from; it holds no secrets.
+fn read_key_index(dir: &Path) -> BTreeSet<String> {
+    fs::read_to_string(key_index_path(dir))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
 
-    match (local_result, keychain_result) {
-        (Ok(()), _) => Ok(()),
-        (Err(_), Ok(())) => Ok(()),
-        (Err(local_err), Err(keychain_err)) => Err(format!(
-            "Failed to store API key locally ({local_err}) and in keychain ({keychain_err})"
-        )),
+fn update_key_index(dir: &Path, provider_id: &str, stored: bool) -> Result<(), String> {
+    let mut ids = read_key_index(dir);
+    let changed = if stored {
+        ids.insert(provider_id.to_string())
+    } else {
+        ids.remove(provider_id)
+    };
+    if !changed {
+        return Ok(());
     }
+    let content = serde_json::to_vec_pretty(&ids).map_err(|e| e.to_string())?;
+    crate::write_protection::atomic_write_bytes(&key_index_path(dir), &content, None)
 }
 
-pub fn get_api_key(provider_id: &str) -> Result<Option<String>, String> {
-    if let Some(key) = get_local_api_key(provider_id)? {
-        return Ok(Some(key));
+/// Errors meaning "no usable keyring on this machine" rather than a problem with one entry.
+fn is_keyring_unavailable(error: &keyring::Error) -> bool {
+    matches!(
+        error,
+        keyring::Error::PlatformFailure(_) | keyring::Error::NoStorageAccess(_)
+    )
+}
+
+fn keyring_status() -> Result<(), String> {
+    let entry = Entry::new(SERVICE_NAME, KEYRING_PROBE_USER).map_err(|e| e.to_string())?;
+    match entry.get_password() {
+        Err(e) if is_keyring_unavailable(&e) => Err(e.to_string()),
+        _ => Ok(()),
     }
+}
 
+/// Stores `api_key` in the keyring and reads it back, so a backend that accepts writes
+/// without persisting them doesn't count as holding the key.
+fn set_keyring_api_key(provider_id: &str, api_key: &str) -> Result<(), String> {
     let entry = Entry::new(SERVICE_NAME, provider_id).map_err(|e| e.to_string())?;
+    entry.set_password(api_key).map_err(|e| e.to_string())?;
+    match entry.get_password() {
+        Ok(stored) if stored == api_key => Ok(()),
+        Ok(_) | Err(keyring::Error::NoEntry) => {
+            Err("keychain did not keep the stored key".to_string())
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn get_keyring_api_key(provider_id: &str) -> Result<Option<String>, String> {
+    let entry = match Entry::new(SERVICE_NAME, provider_id) {
+        Ok(entry) => entry,
+        Err(e) if is_keyring_unavailable(&e) => return Ok(None),
+        Err(e) => return Err(e.to_string()),
+    };
     match entry.get_password() {
         Ok(key)
             if provider_id == BUILTIN_DEMO_PROVIDER_ID
@@ -83,17 +212,226 @@ pub fn get_api_key(provider_id: &str) -> Result<Option<String>, String> {
             let _ = entry.delete_password();
             Ok(None)
         }
-        Ok(key) => {
-            let _ = store_local_api_key(provider_id, &key);
-            Ok(Some(key))
-        }
+        Ok(key) => Ok(Some(key)),
         Err(keyring::Error::NoEntry) => Ok(None),
+        // Keys live only in the secrets file when there is no usable keyring.
+        Err(e) if is_keyring_unavailable(&e) => Ok(None),
         Err(e) => Err(e.to_string()),
     }
 }
 
-pub fn delete_api_key(provider_id: &str) -> Result<(), String> {
-    let local_result = delete_local_api_key(provider_id);
+/// Drops the secrets-file copy of a key the keyring now holds. An unreadable secrets file
+/// is left alone: lookups fall back to the keyring when it cannot be read.
+fn drop_local_api_key(dir: &Path, provider_id: &str) -> Result<(), String> {
+    let Ok(mut keys) = read_local_api_keys(dir) else {
+        return Ok(());
+    };
+    if keys.remove(provider_id).is_some() {
+        write_local_api_keys(dir, &keys)?;
+    }
+    Ok(())
+}
+
+/// Moves a key found only in the secrets file into the keyring once it is usable again.
+/// The secrets-file copy is removed only after the keyring reads the key back.
+fn migrate_local_key_to_keyring(dir: &Path, provider_id: &str, api_key: &str) {
+    if set_keyring_api_key(provider_id, api_key).is_err() {
+        return;
+    }
+    if drop_local_api_key(dir, provider_id).is_ok() {
+        let _ = update_key_index(dir, provider_id, true);
+    }
+}
+
+pub fn get_key_storage_info() -> Result<KeyStorageInfo, String> {
+    let keyring_error = keyring_status().err();
+    let dir = crate::config::get_global_config_dir()?;
+    Ok(KeyStorageInfo {
+        backend: if keyring_error.is_none() {
+            KeyStorageBackend::Keyring
+        } else {
+            KeyStorageBackend::EncryptedFile
+        },
+        keyring_error,
+        uses_passphrase: user_passphrase().is_some(),
+        secrets_path: secrets_path(&dir).to_string_lossy().to_string(),
+    })
+}
+
+fn store_api_key_in(dir: &Path, provider_id: &str, api_key: &str) -> Result<(), String> {
+    crate::redact::remember_secret(api_key);
+    match set_keyring_api_key(provider_id, api_key) {
+        // A stale secrets-file copy would otherwise shadow the new key.
+        Ok(()) => drop_local_api_key(dir, provider_id)?,
+        Err(keychain_err) => {
+            let mut keys = read_local_api_keys(dir).map_err(|local_err| {
+                format!(
+                    "Failed to store API key in keychain ({keychain_err}) and locally ({local_err})"
+                )
+            })?;
+            keys.insert(provider_id.to_string(), api_key.to_string());
+            write_local_api_keys(dir, &keys).map_err(|local_err| {
+                format!(
+                    "Failed to store API key in keychain ({keychain_err}) and locally ({local_err})"
+                )
+            })?;
+        }
+    }
+    update_key_index(dir, provider_id, true)
+}
+
+pub fn store_api_key(provider_id: &str, api_key: &str) -> Result<(), String> {
+    store_api_key_in(
+        &crate::config::get_global_config_dir()?,
+        provider_id,
+        api_key,
+    )
+}
+
+fn get_api_key_in(dir: &Path, provider_id: &str) -> Result<Option<String>, String> {
+    let local = read_local_api_keys(dir).map(|keys| keys.get(provider_id).cloned());
+    if let Ok(Some(key)) = &local {
+        migrate_local_key_to_keyring(dir, provider_id, key);
+        return Ok(Some(key.clone()));
+    }
+
+    // An unreadable secrets file must not hide keys the keyring holds.
+    match (local, get_keyring_api_key(provider_id)) {
+        (_, Ok(Some(key))) => Ok(Some(key)),
+        (Ok(_), keyring_result) => keyring_result,
+        (Err(local_err), Ok(None)) => Err(local_err),
+        (Err(local_err), Err(keychain_err)) => Err(format!(
+            "Failed to read API key locally ({local_err}) and from keychain ({keychain_err})"
+        )),
+    }
+}
+
+pub fn get_api_key(provider_id: &str) -> Result<Option<String>, String> {
+    get_api_key_in(&crate::config::get_global_config_dir()?, provider_id)
+}
+
+/// Copies the key stored under `old_id` to `new_id` and removes the old entry once the copy
+/// reads back. Returns `false` when there was nothing to migrate.
+pub fn migrate_api_key(old_id: &str, new_id: &str) -> Result<bool, String> {
+    if old_id == new_id {
+        return Ok(false);
+    }
+    let Some(key) = get_api_key(old_id)? else {
+        return Ok(false);
+    };
+    store_api_key(new_id, &key)?;
+    if get_api_key(new_id)?.as_deref() != Some(key.as_str()) {
+        return Err(format!("Failed to verify migrated API key for {new_id}"));
+    }
+    delete_api_key(old_id)?;
+    Ok(true)
+}
+
+fn list_orphaned_keys_in(
+    dir: &Path,
+    provider_ids: &HashSet<String>,
+) -> Result<Vec<String>, String> {
+    let mut stored = read_key_index(dir);
+    stored.extend(read_local_api_keys(dir)?.into_keys());
+    Ok(stored
+        .into_iter()
+        .filter(|id| !provider_ids.contains(id))
+        .filter(|id| !id.starts_with(crate::rag::RAG_API_SECRET_PREFIX))
+        .collect())
+}
+
+/// Stored secret ids that belong to no configured provider, from the key index and the
+/// secrets file. Per-project RAG embedding secrets are not provider keys and are never
+/// reported.
+pub fn list_orphaned_keys(provider_ids: &HashSet<String>) -> Result<Vec<String>, String> {
+    list_orphaned_keys_in(&crate::config::get_global_config_dir()?, provider_ids)
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiKeySource {
+    Keyring,
+    Env,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedApiKey {
+    pub key: String,
+    pub source: ApiKeySource,
+    /// Name of the environment variable when `source` is `Env`.
+    pub env_var: Option<String>,
+}
+
+fn key_from_env(var: &str) -> Option<String> {
+    std::env::var(var.trim())
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Looks up a key in the keyring first and then in `api_key_env`. A keyring error is only
+/// reported when the environment variable doesn't provide a key either.
+pub fn resolve_api_key(
+    provider_id: &str,
+    api_key_env: Option<&str>,
+) -> Result<Option<ResolvedApiKey>, String> {
+    let keyring_result = get_api_key(provider_id);
+    if let Ok(Some(key)) = &keyring_result {
+        return Ok(Some(ResolvedApiKey {
+            key: key.clone(),
+            source: ApiKeySource::Keyring,
+            env_var: None,
+        }));
+    }
+    let env_var = api_key_env.map(str::trim).filter(|v| !v.is_empty());
+    if let Some((var, key)) = env_var.and_then(|var| key_from_env(var).map(|key| (var, key))) {
+        return Ok(Some(ResolvedApiKey {
+            key,
+            source: ApiKeySource::Env,
+            env_var: Some(var.to_string()),
+        }));
+    }
+    keyring_result.map(|_| None)
+}
+
+/// Like `resolve_api_key`, taking the environment variable name from the saved provider.
+pub fn resolve_provider_api_key(provider_id: &str) -> Result<Option<ResolvedApiKey>, String> {
+    let api_key_env = crate::config::load_config().ok().and_then(|config| {
+        config
+            .providers
+            .into_iter()
+            .find(|p| p.id == provider_id)
+            .and_then(|p| p.api_key_env)
+    });
+    resolve_api_key(provider_id, api_key_env.as_deref())
+}
+
+/// Key value only, from the keyring or the provider's environment variable.
+pub fn get_provider_api_key(provider_id: &str) -> Result<Option<String>, String> {
+    Ok(resolve_provider_api_key(provider_id)?.map(|resolved| resolved.key))
+}
+
+/// Key for a request to `provider`. Providers that run without a key get an empty one
+/// unless the user stored a key anyway (e.g. for an authenticating reverse proxy).
+pub fn api_key_for_provider(provider: &crate::config::Provider) -> Result<String, String> {
+    match resolve_api_key(&provider.id, provider.api_key_env.as_deref())? {
+        Some(resolved) => {
+            crate::redact::remember_secret(&resolved.key);
+            Ok(resolved.key)
+        }
+        None if !provider.requires_api_key() => Ok(String::new()),
+        None => Err(format!("API Key not found for provider {}", provider.id)),
+    }
+}
+
+fn delete_api_key_in(dir: &Path, provider_id: &str) -> Result<(), String> {
+    let local_result = read_local_api_keys(dir).and_then(|mut keys| {
+        if keys.remove(provider_id).is_some() {
+            write_local_api_keys(dir, &keys)?;
+        }
+        Ok(())
+    });
     let keychain_result = Entry::new(SERVICE_NAME, provider_id)
         .map_err(|e| e.to_string())
         .and_then(|entry| match entry.delete_password() {
@@ -103,14 +441,17 @@ pub fn delete_api_key(provider_id: &str) -> Result<(), String> {
         });
 
     match (local_result, keychain_result) {
-        (Ok(()), _) => Ok(()),
-        (Err(_), Ok(())) => Ok(()),
+        (Ok(()), _) | (Err(_), Ok(())) => update_key_index(dir, provider_id, false),
         (Err(local_err), Err(keychain_err)) => Err(format!(
             "Failed to delete API key locally ({local_err}) and from keychain ({keychain_err})"
         )),
     }
 }
 
+pub fn delete_api_key(provider_id: &str) -> Result<(), String> {
+    delete_api_key_in(&crate::config::get_global_config_dir()?, provider_id)
+}
+
 pub fn purge_leaked_builtin_demo_key() -> Result<bool, String> {
     let entry = Entry::new(SERVICE_NAME, BUILTIN_DEMO_PROVIDER_ID).map_err(|e| e.to_string())?;
     match entry.get_password() {
@@ -122,3 +463,184 @@ pub fn purge_leaked_builtin_demo_key() -> Result<bool, String> {
         Err(e) => Err(e.to_string()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use keyring::credential::{Credential, CredentialApi, CredentialBuilderApi};
+    use std::any::Any;
+    use std::sync::Once;
+
+    /// Keyring users with this prefix behave like a machine without a usable keyring.
+    const UNAVAILABLE_PREFIX: &str = "offline-";
+
+    static TEST_KEYRING: Mutex<Option<HashMap<String, String>>> = Mutex::new(None);
+
+    #[derive(Debug)]
+    struct MemoryCredential {
+        user: String,
+    }
+
+    impl MemoryCredential {
+        fn check_available(&self) -> keyring::Result<()> {
+            if self.user.starts_with(UNAVAILABLE_PREFIX) {
+                return Err(keyring::Error::PlatformFailure("no keyring".into()));
+            }
+            Ok(())
+        }
+    }
+
+    impl CredentialApi for MemoryCredential {
+        fn set_password(&self, password: &str) -> keyring::Result<()> {
+            self.check_available()?;
+            let mut store = TEST_KEYRING.lock().unwrap();
+            store
+                .get_or_insert_with(HashMap::new)
+                .insert(self.user.clone(), password.to_string());
+            Ok(())
+        }
+
+        fn get_password(&self) -> keyring::Result<String> {
+            self.check_available()?;
+            let store = TEST_KEYRING.lock().unwrap();
+            store
+                .as_ref()
+                .and_then(|store| store.get(&self.user).cloned())
+                .ok_or(keyring::Error::NoEntry)
+        }
+
+        fn delete_password(&self) -> keyring::Result<()> {
+            self.check_available()?;
+            let mut store = TEST_KEYRING.lock().unwrap();
+            store
+                .as_mut()
+                .and_then(|store| store.remove(&self.user))
+                .map(|_| ())
+                .ok_or(keyring::Error::NoEntry)
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    #[derive(Debug)]
+    struct MemoryCredentialBuilder;
+
+    impl CredentialBuilderApi for MemoryCredentialBuilder {
+        fn build(
+            &self,
+            _target: Option<&str>,
+            _service: &str,
+            user: &str,
+        ) -> keyring::Result<Box<Credential>> {
+            Ok(Box::new(MemoryCredential {
+                user: user.to_string(),
+            }))
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    fn use_memory_keyring() {
+        static INSTALL: Once = Once::new();
+        INSTALL.call_once(|| {
+            keyring::set_default_credential_builder(Box::new(MemoryCredentialBuilder));
+        });
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("creatorai-keyring-{name}-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn keyring_value(provider_id: &str) -> Option<String> {
+        Entry::new(SERVICE_NAME, provider_id)
+            .unwrap()
+            .get_password()
+            .ok()
+    }
+
+    #[test]
+    fn unreadable_secrets_file_falls_back_to_the_keyring() {
+        use_memory_keyring();
+        let dir = temp_dir("unreadable");
+        fs::write(secrets_path(&dir), b"not an encrypted blob").unwrap();
+        let provider_id = format!("fallback-{}", uuid::Uuid::new_v4());
+        Entry::new(SERVICE_NAME, &provider_id)
+            .unwrap()
+            .set_password("sk-from-keyring")
+            .unwrap();
+
+        assert_eq!(
+            get_api_key_in(&dir, &provider_id).unwrap().as_deref(),
+            Some("sk-from-keyring")
+        );
+        // Without a keyring entry the secrets file might hold the key, so its error surfaces.
+        let missing = format!("missing-{}", uuid::Uuid::new_v4());
+        let err = get_api_key_in(&dir, &missing).unwrap_err();
+        assert!(err.contains(SECRETS_FILE), "{err}");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn secrets_file_key_moves_into_the_keyring_once_available() {
+        use_memory_keyring();
+        let dir = temp_dir("migrate");
+        let provider_id = format!("migrate-{}", uuid::Uuid::new_v4());
+        let keys = HashMap::from([(provider_id.clone(), "sk-local".to_string())]);
+        write_local_api_keys(&dir, &keys).unwrap();
+
+        assert_eq!(
+            get_api_key_in(&dir, &provider_id).unwrap().as_deref(),
+            Some("sk-local")
+        );
+        assert_eq!(keyring_value(&provider_id).as_deref(), Some("sk-local"));
+        assert!(!read_local_api_keys(&dir)
+            .unwrap()
+            .contains_key(&provider_id));
+        assert_eq!(
+            get_api_key_in(&dir, &provider_id).unwrap().as_deref(),
+            Some("sk-local")
+        );
+        assert_eq!(
+            list_orphaned_keys_in(&dir, &HashSet::new()).unwrap(),
+            vec![provider_id.clone()]
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn keys_stay_in_the_secrets_file_while_the_keyring_is_unavailable() {
+        use_memory_keyring();
+        let dir = temp_dir("offline");
+        let provider_id = format!("{UNAVAILABLE_PREFIX}{}", uuid::Uuid::new_v4());
+
+        store_api_key_in(&dir, &provider_id, "sk-offline").unwrap();
+        assert_eq!(
+            read_local_api_keys(&dir)
+                .unwrap()
+                .get(&provider_id)
+                .map(String::as_str),
+            Some("sk-offline")
+        );
+        assert_eq!(
+            get_api_key_in(&dir, &provider_id).unwrap().as_deref(),
+            Some("sk-offline")
+        );
+
+        delete_api_key_in(&dir, &provider_id).unwrap();
+        assert_eq!(get_api_key_in(&dir, &provider_id).unwrap(), None);
+        assert!(list_orphaned_keys_in(&dir, &HashSet::new())
+            .unwrap()
+            .is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,44 +1,89 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
+mod activity_log;
 mod ai_bridge;
 mod ai_daemon;
 mod ai_proxy;
+mod ai_write_log;
 mod chapter;
 mod config;
+mod config_events;
+mod crypto;
+mod diff;
+mod export;
 mod file_ops;
 mod import;
 mod keyring_store;
+mod migrations;
 mod presets;
 mod project;
+mod project_lock;
 mod recent_projects;
 mod rag;
+mod redact;
+mod rollup;
 mod security;
 mod session;
+mod settings_transfer;
+mod snapshot;
+mod stats;
 mod summary;
+#[cfg(test)]
+mod test_support;
+mod trash;
+mod usage_stats;
+mod word_history;
 mod write_protection;
 
+use activity_log::get_activity_log;
+use ai_write_log::{get_ai_write_log, undo_ai_apply};
 use chapter::{
-    create_chapter, delete_chapter, get_chapter_content, list_chapters, rename_chapter,
-    reorder_chapters, save_chapter_content,
+    assign_chapter_volume, create_chapter, create_chapter_at, create_volume, delete_chapter,
+    delete_volume, get_chapter_content, get_chapter_notes, get_chapters_content, list_chapters,
+    list_volumes, merge_chapters, rebuild_chapter_index, recover_chapter_index, rename_chapter,
+    rename_volume, reorder_chapters, save_chapter_content, save_chapter_notes, set_chapter_status,
+    split_chapter,
 };
 use config::{GlobalConfig, ModelParameters, Provider};
+use config_events::ConfigSection;
+use export::{export_chapters, export_project};
 use file_ops::{
-    append_file, list_dir, read_file, search_in_files, write_file, AppendParams, ListParams,
-    ListResult, ReadParams, ReadResult, SearchParams, SearchResult, WriteParams,
+    append_file, delete_file, edit_file, list_dir, read_file, rename_file, search_in_files,
+    stat_file, write_file, AppendParams, DeleteParams, EditParams, EditResult, FileStat,
+    ListParams, ListResult, ReadParams, ReadResult, RenameParams, SearchParams, SearchResult,
+    StatParams, WriteParams,
+};
+use import::{
+    import_markdown, import_summaries, import_txt, import_txt_cancel, preview_import_markdown,
+    preview_import_summaries, preview_import_txt, ImportRuntime,
+};
+use presets::{
+    add_builtin_preset, check_chapter_against_preset, copy_preset_to_project, delete_global_preset,
+    duplicate_preset, export_preset, get_presets, import_preset, list_builtin_presets,
+    list_global_presets, preview_preset_prompt, promote_preset_to_global, save_global_preset, save_presets, touch_preset,
+};
+use project::{create_project, get_project_info, open_project, save_project_config, set_project_read_only};
+use project_lock::release_project_lock;
+use recent_projects::{
+    add_recent_project, get_recent_projects, refresh_recent_project_stats,
+    set_recent_project_pinned,
 };
-use import::{import_txt, preview_import_txt};
-use presets::{get_presets, save_presets};
-use project::{create_project, get_project_info, open_project, save_project_config};
-use recent_projects::{add_recent_project, get_recent_projects};
 use rag::{append_doc as rag_append_doc_impl, build_index as rag_build_index_impl, embedding_status as rag_embedding_status_impl, get_rag_config as rag_get_config_impl, get_writing_context as rag_get_writing_context_impl, list_docs as rag_list_docs_impl, prepare_embedding_model as rag_prepare_embedding_model_impl, read_doc as rag_read_doc_impl, search as rag_search_impl, set_doc_enabled as rag_set_doc_enabled_impl, update_rag_config as rag_update_config_impl, write_doc as rag_write_doc_impl, KnowledgeDoc, RagConfigPayload, RagConfigUpdate, RagEmbeddingStatus, RagHit, RagIndexSummary, WritingContextResult};
 use session::{
     add_message, create_session, delete_session, get_session_messages, list_sessions,
-    rename_session, update_message_metadata, compact_session,
+    rename_session, set_session_profile, set_session_preset, get_effective_preset,
+    update_message_metadata, compact_session,
 };
+use settings_transfer::{export_settings, import_settings};
+use snapshot::{create_project_snapshot, list_project_snapshots, restore_project_snapshot};
+use stats::get_writing_stats;
+use trash::{empty_project_trash, list_trash_entries, restore_trash_entry};
+use usage_stats::{get_usage_summary, reset_usage_stats};
+use word_history::get_word_count_history;
 use std::fs;
 use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 fn clear_dir_if_exists(path: &Path) -> Result<(), String> {
     if !path.exists() {
@@ -117,7 +162,19 @@ fn get_config() -> Result<GlobalConfig, String> {
 
 #[tauri::command]
 fn save_config(config: GlobalConfig) -> Result<(), String> {
-    config::save_config(&config)
+    config_events::save(&config, ConfigSection::All)
+}
+
+#[tauri::command]
+fn list_config_backups() -> Result<Vec<u64>, String> {
+    config::list_config_backups()
+}
+
+#[tauri::command]
+fn restore_config_backup(timestamp: u64) -> Result<GlobalConfig, String> {
+    let config = config::restore_config_backup(timestamp)?;
+    config_events::notify(ConfigSection::All, &config);
+    Ok(config)
 }
 
 // ===== Provider Commands =====
@@ -139,33 +196,154 @@ fn get_provider(provider_id: String) -> Result<Provider, String> {
     Ok(provider.clone())
 }
 
+// Only a definite authentication failure blocks saving; an unreachable endpoint may just
+// be offline right now.
+fn ensure_key_not_rejected(provider: &Provider, api_key: &str) -> Result<(), String> {
+    let verification = verify_api_key_sync(provider, api_key);
+    if verification.status == ApiKeyStatus::InvalidKey {
+        return Err(format!(
+            "API Key rejected by provider {}: {}",
+            provider.id,
+            verification.message.unwrap_or_default()
+        ));
+    }
+    Ok(())
+}
+
 #[tauri::command(rename_all = "camelCase")]
-fn add_provider(provider: Provider, api_key: String) -> Result<(), String> {
-    keyring_store::store_api_key(&provider.id, &api_key)?;
+async fn add_provider(
+    provider: Provider,
+    api_key: String,
+    verify: Option<bool>,
+) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        add_provider_sync(provider, api_key, verify.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| format!("add_provider join error: {e}"))?
+}
 
+fn add_provider_sync(provider: Provider, api_key: String, verify: bool) -> Result<(), String> {
     let mut config = config::load_config()?;
     if config.providers.iter().any(|p| p.id == provider.id) {
         return Err(format!("Provider {} already exists", provider.id));
     }
+    if verify {
+        ensure_key_not_rejected(&provider, &api_key)?;
+    }
+    let stores_key = provider.requires_api_key() || !api_key.trim().is_empty();
+    if !stores_key {
+        config.providers.push(provider);
+        return config_events::save(&config, ConfigSection::Providers);
+    }
 
+    // A key left behind under this id must survive a failed save.
+    let id = provider.id.clone();
+    let previous_key = keyring_store::get_api_key(&id)?;
+    keyring_store::store_api_key(&id, &api_key)?;
     config.providers.push(provider);
-    config::save_config(&config)
+    if let Err(e) = config_events::save(&config, ConfigSection::Providers) {
+        if let Err(restore_err) = restore_provider_key(&id, &id, previous_key) {
+            return Err(format!("{e} (removing the API key also failed: {restore_err})"));
+        }
+        return Err(e);
+    }
+    Ok(())
 }
 
 #[tauri::command(rename_all = "camelCase")]
-fn update_provider(provider: Provider, api_key: Option<String>) -> Result<(), String> {
-    if let Some(key) = api_key {
-        keyring_store::store_api_key(&provider.id, &key)?;
-    }
+async fn update_provider(
+    provider: Provider,
+    api_key: Option<String>,
+    previous_id: Option<String>,
+    verify: Option<bool>,
+) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        update_provider_sync(provider, api_key, previous_id, verify.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| format!("update_provider join error: {e}"))?
+}
+
+fn update_provider_sync(
+    provider: Provider,
+    api_key: Option<String>,
+    previous_id: Option<String>,
+    verify: bool,
+) -> Result<(), String> {
+    let previous_id = previous_id.unwrap_or_else(|| provider.id.clone());
+    let id_changed = previous_id != provider.id;
 
     let mut config = config::load_config()?;
-    if let Some(p) = config.providers.iter_mut().find(|p| p.id == provider.id) {
-        *p = provider;
+    if id_changed && config.providers.iter().any(|p| p.id == provider.id) {
+        return Err(format!("Provider {} already exists", provider.id));
+    }
+    let Some(pos) = config.providers.iter().position(|p| p.id == previous_id) else {
+        return Err(format!("Provider {} not found", previous_id));
+    };
+
+    if let Some(key) = &api_key {
+        if verify {
+            ensure_key_not_rejected(&provider, key)?;
+        }
+    }
+    // Snapshot the stored key so a failed config save can put it back.
+    let touches_keys = api_key.is_some() || id_changed;
+    let previous_key = if touches_keys {
+        keyring_store::get_api_key(&previous_id)?
     } else {
-        return Err(format!("Provider {} not found", provider.id));
+        None
+    };
+
+    let new_id = provider.id.clone();
+    if config.active_provider_id.as_deref() == Some(previous_id.as_str()) {
+        config.active_provider_id = Some(new_id.clone());
+    }
+    config.providers[pos] = provider;
+
+    let saved = move_provider_key(&previous_id, &new_id, api_key.as_deref())
+        .and_then(|()| config_events::save(&config, ConfigSection::Providers));
+    if let Err(e) = saved {
+        if touches_keys {
+            if let Err(restore_err) = restore_provider_key(&previous_id, &new_id, previous_key) {
+                return Err(format!(
+                    "{e} (restoring the API key also failed: {restore_err})"
+                ));
+            }
+        }
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Stores `api_key` under `new_id`, or carries the existing key over when the id changed.
+fn move_provider_key(previous_id: &str, new_id: &str, api_key: Option<&str>) -> Result<(), String> {
+    let id_changed = previous_id != new_id;
+    if let Some(key) = api_key {
+        keyring_store::store_api_key(new_id, key)?;
+        if id_changed {
+            keyring_store::delete_api_key(previous_id)?;
+        }
+    } else if id_changed {
+        keyring_store::migrate_api_key(previous_id, new_id)?;
     }
+    Ok(())
+}
 
-    config::save_config(&config)
+/// Undoes a key change after a failed config save: `previous_key` goes back under
+/// `previous_id` and a key stored under a new id is removed.
+fn restore_provider_key(
+    previous_id: &str,
+    new_id: &str,
+    previous_key: Option<String>,
+) -> Result<(), String> {
+    if previous_id != new_id {
+        keyring_store::delete_api_key(new_id)?;
+    }
+    match previous_key {
+        Some(key) => keyring_store::store_api_key(previous_id, &key),
+        None => keyring_store::delete_api_key(previous_id),
+    }
 }
 
 #[tauri::command(rename_all = "camelCase")]
@@ -179,7 +357,7 @@ fn delete_provider(provider_id: String) -> Result<(), String> {
         config.active_provider_id = None;
     }
 
-    config::save_config(&config)
+    config_events::save(&config, ConfigSection::Providers)
 }
 
 #[tauri::command(rename_all = "camelCase")]
@@ -191,12 +369,66 @@ fn set_active_provider(provider_id: String) -> Result<(), String> {
     }
 
     config.active_provider_id = Some(provider_id);
-    config::save_config(&config)
+    config_events::save(&config, ConfigSection::ActiveProvider)
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn reorder_providers(provider_ids: Vec<String>) -> Result<Vec<Provider>, String> {
+    let mut config = config::load_config()?;
+    config::reorder_providers(&mut config, &provider_ids)?;
+    config_events::save(&config, ConfigSection::Providers)?;
+    Ok(config.providers)
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn set_provider_favorite(provider_id: String, favorite: bool) -> Result<(), String> {
+    update_provider_in_config(&provider_id, |provider| {
+        provider.favorite = favorite;
+        Ok(())
+    })
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn set_provider_headers(
+    provider_id: String,
+    headers: std::collections::HashMap<String, String>,
+    allow_auth_override: Option<bool>,
+) -> Result<(), String> {
+    if config::is_builtin_provider(&provider_id) {
+        return Err("The built-in provider does not support custom headers".to_string());
+    }
+    let headers = config::validate_provider_headers(headers, allow_auth_override.unwrap_or(false))?;
+    update_provider_in_config(&provider_id, |provider| {
+        provider.headers = (!headers.is_empty()).then_some(headers);
+        Ok(())
+    })
+}
+
+#[tauri::command]
+fn list_orphaned_keys() -> Result<Vec<String>, String> {
+    let config = config::load_config()?;
+    let ids = config.providers.into_iter().map(|p| p.id).collect();
+    keyring_store::list_orphaned_keys(&ids)
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn delete_orphaned_key(key_id: String) -> Result<(), String> {
+    let config = config::load_config()?;
+    let ids = config.providers.into_iter().map(|p| p.id).collect();
+    if !keyring_store::list_orphaned_keys(&ids)?.contains(&key_id) {
+        return Err(format!("{} is not an orphaned key", key_id));
+    }
+    keyring_store::delete_api_key(&key_id)
+}
+
+#[tauri::command]
+fn get_key_storage_info() -> Result<keyring_store::KeyStorageInfo, String> {
+    keyring_store::get_key_storage_info()
 }
 
 #[tauri::command(rename_all = "camelCase")]
-fn get_api_key(provider_id: String) -> Result<Option<String>, String> {
-    keyring_store::get_api_key(&provider_id)
+fn get_api_key(provider_id: String) -> Result<Option<keyring_store::ResolvedApiKey>, String> {
+    keyring_store::resolve_provider_api_key(&provider_id)
 }
 
 // ===== Parameters Commands =====
@@ -210,8 +442,68 @@ fn get_default_parameters() -> Result<ModelParameters, String> {
 #[tauri::command]
 fn set_default_parameters(parameters: ModelParameters) -> Result<(), String> {
     let mut config = config::load_config()?;
-    config.default_parameters = parameters;
-    config::save_config(&config)
+    config.set_default_parameters(parameters);
+    config_events::save(&config, ConfigSection::Parameters)
+}
+
+#[tauri::command]
+fn list_parameter_profiles() -> Result<Vec<config::NamedParameters>, String> {
+    Ok(config::load_config()?.parameter_profiles)
+}
+
+#[tauri::command]
+fn save_parameter_profile(profile: config::NamedParameters) -> Result<config::NamedParameters, String> {
+    let mut config = config::load_config()?;
+    let saved = config.save_parameter_profile(profile)?;
+    config_events::save(&config, ConfigSection::Parameters)?;
+    Ok(saved)
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn delete_parameter_profile(profile_id: String) -> Result<(), String> {
+    let mut config = config::load_config()?;
+    config.delete_parameter_profile(&profile_id)?;
+    config_events::save(&config, ConfigSection::Parameters)
+}
+
+#[tauri::command]
+fn list_import_patterns() -> Result<Vec<config::ImportPattern>, String> {
+    Ok(config::load_config()?.import_patterns)
+}
+
+#[tauri::command]
+fn save_import_pattern(pattern: config::ImportPattern) -> Result<config::ImportPattern, String> {
+    let mut config = config::load_config()?;
+    let saved = config.save_import_pattern(pattern)?;
+    config_events::save(&config, ConfigSection::ImportPatterns)?;
+    Ok(saved)
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn delete_import_pattern(pattern_id: String) -> Result<(), String> {
+    let mut config = config::load_config()?;
+    config.delete_import_pattern(&pattern_id)?;
+    config_events::save(&config, ConfigSection::ImportPatterns)
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn get_provider_parameters(provider_id: String) -> Result<ModelParameters, String> {
+    let config = config::load_config()?;
+    if !config.providers.iter().any(|p| p.id == provider_id) {
+        return Err(format!("Provider {} not found", provider_id));
+    }
+    Ok(config.parameters_for(&provider_id).clone())
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn set_provider_parameters(
+    provider_id: String,
+    parameters: Option<ModelParameters>,
+) -> Result<(), String> {
+    update_provider_in_config(&provider_id, |provider| {
+        provider.default_parameters = parameters;
+        Ok(())
+    })
 }
 
 // ===== Models Commands =====
@@ -221,42 +513,37 @@ async fn refresh_provider_models(
     daemon: tauri::State<'_, Arc<ai_daemon::AIDaemon>>,
     provider_id: String,
 ) -> Result<Vec<String>, String> {
-    let provider = {
+    let (provider, proxy) = {
         let config = config::load_config()?;
-        config
+        let provider = config
             .providers
             .iter()
             .find(|p| p.id == provider_id)
             .ok_or(format!("Provider {} not found", provider_id))?
-            .clone()
+            .clone();
+        (provider, config.proxy_for(Some(&provider_id)).cloned())
     };
 
-    let api_key = keyring_store::get_api_key(&provider_id)?
-        .ok_or(format!("API Key not found for provider {}", provider_id))?;
+    let api_key = keyring_store::api_key_for_provider(&provider)?;
 
-    let provider_type = match provider.provider_type {
-        config::ProviderType::OpenaiCompatible => "openai-compatible",
-        config::ProviderType::Google => "google",
-        config::ProviderType::Anthropic => "anthropic",
-    }
-    .to_string();
-    let normalized_base_url = if matches!(provider.provider_type, config::ProviderType::OpenaiCompatible)
-    {
-        let trimmed = provider.base_url.trim_end_matches('/').to_string();
-        if trimmed.ends_with("/v1") {
-            trimmed
-        } else {
-            format!("{trimmed}/v1")
-        }
-    } else {
-        provider.base_url.clone()
-    };
+    let provider_type = provider.provider_type.as_str().to_string();
+    let normalized_base_url = provider.normalized_base_url();
 
     // Use daemon HTTP proxy instead of spawning one-shot process
     let daemon_arc = daemon.inner().clone();
     let fetch_base_url = normalized_base_url.clone();
+    let fetch_provider_id = provider_id.clone();
+    let api_version = provider.api_version.clone();
     let models = tauri::async_runtime::spawn_blocking(move || {
-        ai_proxy::fetch_models(&daemon_arc, &provider_type, &fetch_base_url, &api_key)
+        ai_proxy::fetch_models(
+            &daemon_arc,
+            Some(&fetch_provider_id),
+            &provider_type,
+            &fetch_base_url,
+            &api_key,
+            proxy.as_ref(),
+            api_version.as_deref(),
+        )
     })
     .await
     .map_err(|e| format!("refresh_provider_models join error: {e}"))??;
@@ -266,7 +553,7 @@ async fn refresh_provider_models(
         if matches!(p.provider_type, config::ProviderType::OpenaiCompatible) {
             p.base_url = normalized_base_url.clone();
         }
-        p.models = models.clone();
+        p.set_models(models.clone());
         p.models_updated_at = Some(
             SystemTime::now()
                 .duration_since(UNIX_EPOCH)
@@ -274,20 +561,352 @@ async fn refresh_provider_models(
                 .as_secs(),
         );
     }
-    config::save_config(&config)?;
+    config_events::save(&config, ConfigSection::Providers)?;
 
     Ok(models)
 }
 
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProviderConnectionTest {
+    ok: bool,
+    latency_ms: u64,
+    models_found: usize,
+    error: Option<String>,
+}
+
+// An unsaved provider may not be in the config yet, so its own proxy is checked before
+// falling back to the saved global setting.
+fn resolve_provider_proxy(provider: &Provider) -> Option<config::ProxySettings> {
+    if let Some(proxy) = provider.proxy.as_ref().filter(|p| !p.url.trim().is_empty()) {
+        return Some(proxy.clone());
+    }
+    config::load_config()
+        .ok()
+        .and_then(|cfg| cfg.proxy.filter(|p| !p.url.trim().is_empty()))
+}
+
+fn provider_request_json(provider: &Provider, api_key: &str) -> serde_json::Value {
+    let mut headers = provider.headers.clone().unwrap_or_default();
+    match provider.provider_type {
+        config::ProviderType::Anthropic => {
+            headers.insert("x-api-key".to_string(), api_key.to_string());
+        }
+        config::ProviderType::Google => {
+            headers.insert("x-goog-api-key".to_string(), api_key.to_string());
+        }
+        config::ProviderType::AzureOpenai => {
+            headers.insert("api-key".to_string(), api_key.to_string());
+        }
+        config::ProviderType::OpenaiCompatible | config::ProviderType::Ollama => {}
+    }
+    serde_json::json!({
+        "id": provider.id,
+        "name": provider.name,
+        "baseURL": provider.normalized_base_url(),
+        "apiKey": api_key,
+        "models": provider.models,
+        "providerType": provider.provider_type.as_str(),
+        "headers": headers,
+        "apiVersion": provider.api_version,
+        "deployments": provider.deployments,
+        "proxy": resolve_provider_proxy(provider),
+    })
+}
+
+// Tries the model list first; providers without a list endpoint get a 1-token completion
+// against their first configured model instead.
+fn test_provider_connection_sync(provider: Provider, api_key: Option<String>) -> ProviderConnectionTest {
+    let started = Instant::now();
+    let api_key = match api_key.filter(|k| !k.trim().is_empty()) {
+        Some(key) => Some(key),
+        None => keyring_store::resolve_api_key(&provider.id, provider.api_key_env.as_deref())
+            .ok()
+            .flatten()
+            .map(|resolved| resolved.key),
+    };
+    let api_key = api_key.or_else(|| (!provider.requires_api_key()).then(String::new));
+    let Some(api_key) = api_key else {
+        return ProviderConnectionTest {
+            ok: false,
+            latency_ms: 0,
+            models_found: 0,
+            error: Some(format!("API Key not found for provider {}", provider.id)),
+        };
+    };
+
+    if let Some(Err(e)) = provider.proxy.as_ref().map(config::validate_proxy) {
+        return ProviderConnectionTest {
+            ok: false,
+            latency_ms: 0,
+            models_found: 0,
+            error: Some(e),
+        };
+    }
+
+    let list_result = ai_bridge::fetch_models(
+        Some(&provider.id),
+        provider.provider_type.as_str(),
+        &provider.normalized_base_url(),
+        &api_key,
+        resolve_provider_proxy(&provider).as_ref(),
+        provider.api_version.as_deref(),
+    );
+    let result = match (list_result, provider.models.first()) {
+        (Ok(models), _) => Ok(models.len()),
+        (Err(_), Some(model)) => ai_bridge::run_complete(
+            // No id, so run_complete can't swap in a stored key over the one under test.
+            {
+                let mut request = provider_request_json(&provider, &api_key);
+                request["id"] = serde_json::Value::String(String::new());
+                request
+            },
+            serde_json::json!({ "model": model, "maxTokens": 1, "temperature": 0 }),
+            String::new(),
+            vec![serde_json::json!({ "role": "user", "content": "ping" })],
+            None,
+        )
+        .map(|_| 0),
+        (Err(e), None) => Err(e),
+    };
+
+    let latency_ms = started.elapsed().as_millis() as u64;
+    let result = match provider.provider_type {
+        config::ProviderType::Ollama => result.map_err(|e| {
+            ai_bridge::explain_ollama_error(provider.models.first().map_or("", String::as_str), e)
+        }),
+        _ => result,
+    };
+    match result {
+        Ok(models_found) => ProviderConnectionTest {
+            ok: true,
+            latency_ms,
+            models_found,
+            error: None,
+        },
+        Err(e) => ProviderConnectionTest {
+            ok: false,
+            latency_ms,
+            models_found: 0,
+            error: Some(e),
+        },
+    }
+}
+
+#[tauri::command(rename_all = "camelCase")]
+async fn test_provider_connection(
+    provider: Provider,
+    api_key: Option<String>,
+) -> Result<ProviderConnectionTest, String> {
+    tauri::async_runtime::spawn_blocking(move || test_provider_connection_sync(provider, api_key))
+        .await
+        .map_err(|e| format!("test_provider_connection join error: {e}"))
+}
+
+const API_KEY_VERIFY_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, serde::Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum ApiKeyStatus {
+    Valid,
+    InvalidKey,
+    /// Authenticated, but the key cannot use any model (or the configured ones).
+    NoModelAccess,
+    Unreachable,
+    Error,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiKeyVerification {
+    status: ApiKeyStatus,
+    latency_ms: u64,
+    message: Option<String>,
+}
+
+/// A saved provider by id, or an unsaved provider config from the settings form.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(untagged)]
+enum ProviderRef {
+    Id(String),
+    Config(Box<Provider>),
+}
+
+// `model_request` marks a completion: there a 404 means the model is unavailable to this
+// key, while a 404 from the model list only says the endpoint doesn't offer one.
+fn classify_api_key_error(error: &ai_bridge::EngineError, model_request: bool) -> ApiKeyStatus {
+    match error.status {
+        Some(401) => ApiKeyStatus::InvalidKey,
+        Some(403) => ApiKeyStatus::NoModelAccess,
+        Some(404) if model_request => ApiKeyStatus::NoModelAccess,
+        Some(408 | 502..=504) => ApiKeyStatus::Unreachable,
+        Some(_) => ApiKeyStatus::Error,
+        None if error.timed_out || error.code.is_some() => ApiKeyStatus::Unreachable,
+        None => ApiKeyStatus::Error,
+    }
+}
+
+// Lists models first (the cheapest authenticated call every provider type supports); only
+// when that is inconclusive does it spend a 1-token completion on the first configured model.
+fn verify_api_key_sync(provider: &Provider, api_key: &str) -> ApiKeyVerification {
+    let started = Instant::now();
+    let finish = |status: ApiKeyStatus, message: Option<String>| ApiKeyVerification {
+        status,
+        latency_ms: started.elapsed().as_millis() as u64,
+        message,
+    };
+
+    if api_key.trim().is_empty() && provider.requires_api_key() {
+        return finish(ApiKeyStatus::InvalidKey, Some("API Key is empty".to_string()));
+    }
+    if let Some(Err(e)) = provider.proxy.as_ref().map(config::validate_proxy) {
+        return finish(ApiKeyStatus::Error, Some(e));
+    }
+
+    let list_error = match ai_bridge::fetch_models_within(
+        Some(&provider.id),
+        provider.provider_type.as_str(),
+        &provider.normalized_base_url(),
+        api_key,
+        resolve_provider_proxy(provider).as_ref(),
+        provider.api_version.as_deref(),
+        Some(API_KEY_VERIFY_TIMEOUT),
+    ) {
+        Ok(models) if models.is_empty() => {
+            return finish(
+                ApiKeyStatus::NoModelAccess,
+                Some("The key is valid but no models are available".to_string()),
+            );
+        }
+        Ok(models) => {
+            if !provider.models.is_empty() && !provider.models.iter().any(|m| models.contains(m)) {
+                return finish(
+                    ApiKeyStatus::NoModelAccess,
+                    Some("The key is valid but cannot access any configured model".to_string()),
+                );
+            }
+            return finish(ApiKeyStatus::Valid, None);
+        }
+        Err(e) => e,
+    };
+
+    let status = classify_api_key_error(&list_error, false);
+    let remaining = API_KEY_VERIFY_TIMEOUT.saturating_sub(started.elapsed());
+    let Some(model) = provider.models.first() else {
+        return finish(status, Some(list_error.message));
+    };
+    if matches!(status, ApiKeyStatus::InvalidKey | ApiKeyStatus::Unreachable) || remaining.is_zero() {
+        return finish(status, Some(list_error.message));
+    }
+
+    // No id, so run_complete can't swap in a stored key over the one under test.
+    let mut request = provider_request_json(provider, api_key);
+    request["id"] = serde_json::Value::String(String::new());
+    match ai_bridge::run_complete_within(
+        request,
+        serde_json::json!({ "model": model, "maxTokens": 1, "temperature": 0 }),
+        String::new(),
+        vec![serde_json::json!({ "role": "user", "content": "ping" })],
+        None,
+        remaining,
+    ) {
+        Ok(_) => finish(ApiKeyStatus::Valid, None),
+        Err(e) => finish(classify_api_key_error(&e, true), Some(e.message)),
+    }
+}
+
+#[tauri::command(rename_all = "camelCase")]
+async fn verify_api_key(provider: ProviderRef, api_key: String) -> Result<ApiKeyVerification, String> {
+    let provider = match provider {
+        ProviderRef::Id(provider_id) => config::load_config()?
+            .providers
+            .into_iter()
+            .find(|p| p.id == provider_id)
+            .ok_or(format!("Provider {} not found", provider_id))?,
+        ProviderRef::Config(provider) => *provider,
+    };
+    tauri::async_runtime::spawn_blocking(move || verify_api_key_sync(&provider, &api_key))
+        .await
+        .map_err(|e| format!("verify_api_key join error: {e}"))
+}
+
 #[tauri::command(rename_all = "camelCase")]
-fn get_provider_models(provider_id: String) -> Result<Vec<String>, String> {
+fn get_provider_models(provider_id: String) -> Result<config::ProviderModels, String> {
     let config = config::load_config()?;
     let provider = config
         .providers
         .iter()
         .find(|p| p.id == provider_id)
         .ok_or(format!("Provider {} not found", provider_id))?;
-    Ok(provider.models.clone())
+    Ok(provider.visible_models())
+}
+
+fn update_provider_in_config(
+    provider_id: &str,
+    update: impl FnOnce(&mut Provider) -> Result<(), String>,
+) -> Result<(), String> {
+    let mut config = config::load_config()?;
+    let provider = config
+        .providers
+        .iter_mut()
+        .find(|p| p.id == provider_id)
+        .ok_or(format!("Provider {} not found", provider_id))?;
+    update(provider)?;
+    config_events::save(&config, ConfigSection::Providers)
+}
+
+fn ensure_known_model(provider: &Provider, model_id: &str) -> Result<(), String> {
+    if provider.models.iter().any(|m| m == model_id) {
+        Ok(())
+    } else {
+        Err(format!("Model {} not found for provider {}", model_id, provider.id))
+    }
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn set_hidden_models(provider_id: String, hidden_models: Vec<String>) -> Result<(), String> {
+    update_provider_in_config(&provider_id, |provider| {
+        for model_id in &hidden_models {
+            ensure_known_model(provider, model_id)?;
+        }
+        provider.hidden_models = hidden_models;
+        Ok(())
+    })
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn set_model_alias(
+    provider_id: String,
+    alias: String,
+    model_id: Option<String>,
+) -> Result<(), String> {
+    let alias = alias.trim().to_string();
+    if alias.is_empty() {
+        return Err("Alias is empty".to_string());
+    }
+    update_provider_in_config(&provider_id, |provider| {
+        match model_id {
+            Some(model_id) => {
+                ensure_known_model(provider, &model_id)?;
+                provider.model_aliases.insert(alias, model_id);
+            }
+            None => {
+                provider.model_aliases.remove(&alias);
+            }
+        }
+        Ok(())
+    })
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn set_default_model(provider_id: String, model_id: Option<String>) -> Result<(), String> {
+    update_provider_in_config(&provider_id, |provider| {
+        if let Some(model_id) = &model_id {
+            ensure_known_model(provider, model_id)?;
+        }
+        provider.default_model = model_id;
+        Ok(())
+    })
 }
 
 #[tauri::command]
@@ -305,11 +924,31 @@ fn file_append(project_dir: String, params: AppendParams) -> Result<(), String>
     append_file(std::path::Path::new(&project_dir), params)
 }
 
+#[tauri::command]
+fn file_delete(project_dir: String, params: DeleteParams) -> Result<(), String> {
+    delete_file(std::path::Path::new(&project_dir), params)
+}
+
+#[tauri::command]
+fn file_rename(project_dir: String, params: RenameParams) -> Result<(), String> {
+    rename_file(std::path::Path::new(&project_dir), params)
+}
+
+#[tauri::command]
+fn file_edit(project_dir: String, params: EditParams) -> Result<EditResult, String> {
+    edit_file(std::path::Path::new(&project_dir), params)
+}
+
 #[tauri::command]
 fn file_list(project_dir: String, params: ListParams) -> Result<ListResult, String> {
     list_dir(std::path::Path::new(&project_dir), params)
 }
 
+#[tauri::command]
+fn file_stat(project_dir: String, path: String) -> Result<FileStat, String> {
+    stat_file(std::path::Path::new(&project_dir), StatParams { path })
+}
+
 #[tauri::command]
 fn file_search(project_dir: String, params: SearchParams) -> Result<SearchResult, String> {
     search_in_files(std::path::Path::new(&project_dir), params)
@@ -318,7 +957,7 @@ fn file_search(project_dir: String, params: SearchParams) -> Result<SearchResult
 // ===== Summary Commands =====
 
 #[tauri::command(rename_all = "camelCase")]
-fn load_summaries(project_path: String) -> Result<Vec<summary::SummaryEntry>, String> {
+fn load_summaries(project_path: String) -> Result<summary::LoadedSummaries, String> {
     summary::load_summaries(Path::new(&project_path))
 }
 
@@ -327,48 +966,230 @@ fn get_latest_summary(
     project_path: String,
     chapter_id: String,
 ) -> Result<Option<summary::SummaryEntry>, String> {
-    let summaries = summary::load_summaries(Path::new(&project_path))?;
-    let mut best: Option<summary::SummaryEntry> = None;
-    for entry in summaries {
-        if entry.chapter_id != chapter_id {
-            continue;
-        }
-        let should_replace = best
-            .as_ref()
-            .map(|b| entry.created_at >= b.created_at)
-            .unwrap_or(true);
-        if should_replace {
-            best = Some(entry);
-        }
-    }
-    Ok(best)
+    summary::get_latest_summary(Path::new(&project_path), &chapter_id)
 }
 
 #[tauri::command(rename_all = "camelCase")]
-fn save_summary_entry(
+async fn get_recent_summaries(
     project_path: String,
-    chapter_id: String,
-    summary: String,
-) -> Result<summary::SummaryEntry, String> {
-    summary::save_summary(Path::new(&project_path), chapter_id, summary)
+    before_chapter_id: String,
+    count: u32,
+) -> Result<Vec<summary::ChapterSummaryRef>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        summary::get_recent_summaries(Path::new(&project_path), &before_chapter_id, count)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
 }
 
-// ===== RAG Commands =====
-
 #[tauri::command(rename_all = "camelCase")]
-fn rag_list_docs(project_path: String) -> Result<Vec<KnowledgeDoc>, String> {
-    rag_list_docs_impl(Path::new(&project_path))
+async fn export_summaries_markdown(
+    project_path: String,
+    dest_path: String,
+    overwrite: Option<bool>,
+) -> Result<summary::SummaryExportReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        summary::export_summaries_markdown(
+            Path::new(&project_path),
+            Path::new(&dest_path),
+            overwrite.unwrap_or(false),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
 }
 
 #[tauri::command(rename_all = "camelCase")]
-fn rag_set_doc_enabled(project_path: String, doc_path: String, enabled: bool) -> Result<(), String> {
-    rag_set_doc_enabled_impl(Path::new(&project_path), &doc_path, enabled)
+async fn diff_summaries(
+    project_path: String,
+    chapter_id: String,
+    older_created_at: Option<u64>,
+    newer_created_at: Option<u64>,
+) -> Result<summary::SummaryDiff, String> {
+    let versions = match (older_created_at, newer_created_at) {
+        (Some(older), Some(newer)) => Some((older, newer)),
+        (None, None) => None,
+        _ => return Err("Pass both olderCreatedAt and newerCreatedAt, or neither".to_string()),
+    };
+    tauri::async_runtime::spawn_blocking(move || {
+        summary::diff_summaries(Path::new(&project_path), &chapter_id, versions)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
 }
 
 #[tauri::command(rename_all = "camelCase")]
-fn rag_read_doc(project_path: String, doc_path: String) -> Result<String, String> {
-    rag_read_doc_impl(Path::new(&project_path), &doc_path)
-}
+async fn search_summaries(
+    project_path: String,
+    query: String,
+    include_older: Option<bool>,
+) -> Result<summary::SummarySearchResult, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        summary::search_summaries(Path::new(&project_path), &query, include_older.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn save_summary_entry(
+    project_path: String,
+    chapter_id: String,
+    summary: String,
+) -> Result<summary::SavedSummary, String> {
+    let summary_len = summary.len() as i64;
+    let entry = summary::save_summary(
+        Path::new(&project_path),
+        chapter_id,
+        summary,
+        summary::SummaryProvenance::manual(),
+    )?;
+    let warnings = activity_log::record(
+        Path::new(&project_path),
+        activity_log::Actor::User,
+        "save_summary",
+        &format!("summaries/{}.json", entry.chapter_id),
+        summary_len,
+    )
+    .into_iter()
+    .collect();
+    Ok(summary::SavedSummary { entry, warnings })
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn update_summary(
+    project_path: String,
+    chapter_id: String,
+    created_at: u64,
+    new_text: String,
+) -> Result<summary::SavedSummary, String> {
+    let summary_len = new_text.len() as i64;
+    let entry = summary::update_summary(Path::new(&project_path), &chapter_id, created_at, new_text)?;
+    let warnings = activity_log::record(
+        Path::new(&project_path),
+        activity_log::Actor::User,
+        "update_summary",
+        &format!("summaries/{}.json", entry.chapter_id),
+        summary_len,
+    )
+    .into_iter()
+    .collect();
+    Ok(summary::SavedSummary { entry, warnings })
+}
+
+#[tauri::command(rename_all = "camelCase")]
+async fn prune_summaries(project_path: String, keep_latest: u32) -> Result<usize, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        summary::prune_summaries(Path::new(&project_path), keep_latest)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn delete_summary(
+    project_path: String,
+    chapter_id: String,
+    created_at: u64,
+) -> Result<summary::SavedSummary, String> {
+    let entry = summary::delete_summary(Path::new(&project_path), &chapter_id, created_at)?;
+    let warnings = activity_log::record(
+        Path::new(&project_path),
+        activity_log::Actor::User,
+        "delete_summary",
+        &format!("summaries/{}.json", entry.chapter_id),
+        -(entry.summary.len() as i64),
+    )
+    .into_iter()
+    .collect();
+    Ok(summary::SavedSummary { entry, warnings })
+}
+
+// ===== Backup Commands =====
+
+#[tauri::command(rename_all = "camelCase")]
+async fn list_backups(
+    project_path: String,
+    relative_path: Option<String>,
+) -> Result<Vec<write_protection::FileBackups>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        write_protection::list_backups(Path::new(&project_path), relative_path.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+async fn restore_file_backup(
+    project_path: String,
+    relative_path: String,
+    timestamp: u64,
+) -> Result<write_protection::RestoredBackup, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        write_protection::restore_file_backup(Path::new(&project_path), &relative_path, timestamp)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+async fn read_backup_file(
+    project_path: String,
+    relative_path: String,
+    timestamp: u64,
+    offset: Option<i64>,
+    limit: Option<u32>,
+) -> Result<ReadResult, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        write_protection::read_backup_file(Path::new(&project_path), &relative_path, timestamp, offset, limit)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+async fn diff_backups(
+    project_path: String,
+    relative_path: String,
+    timestamp_a: u64,
+    timestamp_b: u64,
+) -> Result<write_protection::BackupDiff, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        write_protection::diff_backups(Path::new(&project_path), &relative_path, timestamp_a, timestamp_b)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+/// Applies the project's `.backup/` retention settings; `dry_run` only reports.
+#[tauri::command(rename_all = "camelCase")]
+async fn prune_backups(
+    project_path: String,
+    dry_run: Option<bool>,
+) -> Result<write_protection::BackupPruneReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        write_protection::prune_backups(Path::new(&project_path), dry_run.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+// ===== RAG Commands =====
+
+#[tauri::command(rename_all = "camelCase")]
+fn rag_list_docs(project_path: String) -> Result<Vec<KnowledgeDoc>, String> {
+    rag_list_docs_impl(Path::new(&project_path))
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn rag_set_doc_enabled(project_path: String, doc_path: String, enabled: bool) -> Result<(), String> {
+    rag_set_doc_enabled_impl(Path::new(&project_path), &doc_path, enabled)
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn rag_read_doc(project_path: String, doc_path: String) -> Result<String, String> {
+    rag_read_doc_impl(Path::new(&project_path), &doc_path)
+}
 
 #[tauri::command(rename_all = "camelCase")]
 fn rag_write_doc(project_path: String, doc_path: String, content: String) -> Result<(), String> {
@@ -447,6 +1268,37 @@ struct AiCompleteRuntime {
     cancel_flag: Mutex<Option<Arc<AtomicBool>>>,
 }
 
+impl AiCompleteRuntime {
+    /// Cancels any running completion and registers a fresh flag for the next one.
+    fn begin(&self) -> Result<Arc<AtomicBool>, String> {
+        let cancel_flag = Arc::new(AtomicBool::new(false));
+        let mut guard = self
+            .cancel_flag
+            .lock()
+            .map_err(|_| "ai_complete lock poisoned".to_string())?;
+        if let Some(prev) = guard.take() {
+            prev.store(true, Ordering::SeqCst);
+        }
+        *guard = Some(cancel_flag.clone());
+        Ok(cancel_flag)
+    }
+
+    /// Clears `cancel_flag` unless a newer completion has replaced it.
+    fn finish(&self, cancel_flag: &Arc<AtomicBool>) -> Result<(), String> {
+        let mut guard = self
+            .cancel_flag
+            .lock()
+            .map_err(|_| "ai_complete lock poisoned".to_string())?;
+        if guard
+            .as_ref()
+            .is_some_and(|flag| Arc::ptr_eq(flag, cancel_flag))
+        {
+            *guard = None;
+        }
+        Ok(())
+    }
+}
+
 #[tauri::command]
 fn ai_cancel(runtime: tauri::State<AiChatRuntime>) -> Result<(), String> {
     let flag = runtime
@@ -494,21 +1346,10 @@ async fn ai_complete(
     system_prompt: String,
     messages: Vec<serde_json::Value>,
 ) -> Result<String, String> {
-    let cancel_flag = Arc::new(AtomicBool::new(false));
-    {
-        let mut guard = runtime
-            .cancel_flag
-            .lock()
-            .map_err(|_| "ai_complete lock poisoned".to_string())?;
-        if let Some(prev) = guard.take() {
-            prev.store(true, Ordering::SeqCst);
-        }
-        *guard = Some(cancel_flag.clone());
-    }
-
+    let cancel_flag = runtime.begin()?;
     let cancel_for_task = cancel_flag.clone();
     let response = match tauri::async_runtime::spawn_blocking(move || {
-        ai_bridge::run_complete(provider, parameters, system_prompt, messages, Some(cancel_for_task))
+        complete_with_usage(provider, parameters, system_prompt, messages, cancel_for_task)
     })
     .await
     {
@@ -516,22 +1357,206 @@ async fn ai_complete(
         Err(e) => Err(format!("ai_complete join error: {e}")),
     };
 
-    {
-        let mut guard = runtime
-            .cancel_flag
-            .lock()
-            .map_err(|_| "ai_complete lock poisoned".to_string())?;
-        if guard
-            .as_ref()
-            .is_some_and(|flag| Arc::ptr_eq(flag, &cancel_flag))
-        {
-            *guard = None;
-        }
+    runtime.finish(&cancel_flag)?;
+    response
+}
+
+/// `ai_bridge::run_complete` with usage recorded and provider errors explained.
+fn complete_with_usage(
+    provider: serde_json::Value,
+    parameters: serde_json::Value,
+    system_prompt: String,
+    messages: Vec<serde_json::Value>,
+    cancel: Arc<AtomicBool>,
+) -> Result<String, String> {
+    let input_chars = usage_stats::prompt_chars(&system_prompt, &messages);
+    let (usage_provider, usage_parameters) = (provider.clone(), parameters.clone());
+    let result = ai_bridge::run_complete(provider, parameters, system_prompt, messages, Some(cancel));
+    usage_stats::record_call(
+        &usage_provider,
+        &usage_parameters,
+        input_chars,
+        result.as_ref().map(|c| c.chars().count() as u64).map_err(String::as_str),
+    );
+    result.map_err(|e| ai_bridge::explain_provider_error(&usage_provider, &usage_parameters, e))
+}
+
+/// Chapter text sent for summarization; long chapters keep their ending.
+const SUMMARY_INPUT_CHAR_BUDGET: usize = 12_000;
+
+const CHAPTER_SUMMARY_SYSTEM_PROMPT: &str = "你是一个小说章节摘要助手。你的任务是把“章节正文”压缩成一段简短摘要，帮助作者快速回忆本章发生了什么。
+
+要求：
+- 输出一段中文摘要（约 50-120 字，尽量精炼）
+- 只陈述本章已发生的事件，不要脑补未写内容
+- 不要使用 Markdown、标题、列表、引号
+- 只输出摘要正文本身";
+
+fn tail_chars(text: &str, budget: usize) -> &str {
+    let total = text.chars().count();
+    if total <= budget {
+        return text;
+    }
+    let start = text
+        .char_indices()
+        .nth(total - budget)
+        .map_or(text.len(), |(byte, _)| byte);
+    &text[start..]
+}
+
+fn chapter_summary_user_prompt(chapter_id: &str, chapter_title: &str, content: &str) -> String {
+    let title = chapter_title.trim();
+    let title = if title.is_empty() { String::new() } else { format!("《{title}》") };
+    let excerpt = tail_chars(content.trim(), SUMMARY_INPUT_CHAR_BUDGET);
+    let note = if excerpt.len() < content.trim().len() {
+        "（正文较长，以下仅为结尾部分）\n"
+    } else {
+        ""
+    };
+    format!("请为章节 {title}（{chapter_id}）生成摘要。\n\n【章节正文】\n{note}{excerpt}")
+}
+
+fn generate_chapter_summary_sync(
+    project_path: String,
+    chapter_id: String,
+    provider: serde_json::Value,
+    parameters: serde_json::Value,
+    cancel: Arc<AtomicBool>,
+) -> Result<summary::SavedSummary, String> {
+    let content = chapter::get_chapter_content_sync(project_path.clone(), chapter_id.clone())?;
+    if content.trim().is_empty() {
+        return Err("章节内容为空，无法生成摘要".to_string());
+    }
+    let title = chapter::list_chapters_sync(project_path.clone())?
+        .into_iter()
+        .find(|c| c.id == chapter_id)
+        .map(|c| c.title)
+        .unwrap_or_default();
+
+    let model_name = parameters.get("model").and_then(|v| v.as_str()).map(str::to_string);
+    let messages = vec![serde_json::json!({
+        "role": "user",
+        "content": chapter_summary_user_prompt(&chapter_id, &title, &content),
+    })];
+    let text = complete_with_usage(
+        provider,
+        parameters,
+        CHAPTER_SUMMARY_SYSTEM_PROMPT.to_string(),
+        messages,
+        cancel,
+    )?;
+    let text = text.trim().to_string();
+    if text.is_empty() {
+        return Err("AI 未返回摘要，请重试".to_string());
+    }
+
+    let summary_len = text.len() as i64;
+    let entry = summary::save_summary(
+        Path::new(&project_path),
+        chapter_id,
+        text,
+        summary::SummaryProvenance {
+            source: summary::SummarySource::Generated,
+            session_id: None,
+            model: model_name,
+        },
+    )?;
+    let warnings = activity_log::record(
+        Path::new(&project_path),
+        activity_log::Actor::Ai,
+        "save_summary",
+        &format!("summaries/{}.json", entry.chapter_id),
+        summary_len,
+    )
+    .into_iter()
+    .collect();
+    Ok(summary::SavedSummary { entry, warnings })
+}
+
+/// Summarizes one chapter and saves the result; cancelled through `ai_complete_cancel`.
+#[tauri::command(rename_all = "camelCase")]
+async fn generate_chapter_summary(
+    runtime: tauri::State<'_, AiCompleteRuntime>,
+    project_path: String,
+    chapter_id: String,
+    provider: serde_json::Value,
+    parameters: serde_json::Value,
+) -> Result<summary::SavedSummary, String> {
+    let cancel_flag = runtime.begin()?;
+    let cancel_for_task = cancel_flag.clone();
+    let response = tauri::async_runtime::spawn_blocking(move || {
+        generate_chapter_summary_sync(project_path, chapter_id, provider, parameters, cancel_for_task)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))
+    .and_then(|inner| inner);
+
+    runtime.finish(&cancel_flag)?;
+    response
+}
+
+fn generate_story_rollup_sync(
+    project_path: String,
+    up_to_chapter_id: String,
+    provider: serde_json::Value,
+    parameters: serde_json::Value,
+    cancel: Arc<AtomicBool>,
+) -> Result<rollup::StoryRollup, String> {
+    let project_root = Path::new(&project_path);
+    let (chapters, refs) = rollup::rollup_sources(project_root, &up_to_chapter_id)?;
+    let messages = vec![serde_json::json!({
+        "role": "user",
+        "content": rollup::rollup_user_prompt(&refs),
+    })];
+    let text = complete_with_usage(
+        provider,
+        parameters,
+        rollup::ROLLUP_SYSTEM_PROMPT.to_string(),
+        messages,
+        cancel,
+    )?;
+    let text = text.trim().to_string();
+    if text.is_empty() {
+        return Err("AI 未返回前情提要，请重试".to_string());
     }
+    rollup::save_story_rollup(project_root, &chapters, &refs, text)
+}
+
+/// Consolidates chapter summaries through `up_to_chapter_id` into a story-so-far rollup;
+/// cancelled through `ai_complete_cancel`.
+#[tauri::command(rename_all = "camelCase")]
+async fn generate_story_rollup(
+    runtime: tauri::State<'_, AiCompleteRuntime>,
+    project_path: String,
+    up_to_chapter_id: String,
+    provider: serde_json::Value,
+    parameters: serde_json::Value,
+) -> Result<rollup::StoryRollup, String> {
+    let cancel_flag = runtime.begin()?;
+    let cancel_for_task = cancel_flag.clone();
+    let response = tauri::async_runtime::spawn_blocking(move || {
+        generate_story_rollup_sync(project_path, up_to_chapter_id, provider, parameters, cancel_for_task)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))
+    .and_then(|inner| inner);
 
+    runtime.finish(&cancel_flag)?;
     response
 }
 
+#[tauri::command(rename_all = "camelCase")]
+async fn get_story_rollup(
+    project_path: String,
+    up_to_chapter_id: Option<String>,
+) -> Result<Option<rollup::RollupStatus>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        rollup::get_story_rollup(Path::new(&project_path), up_to_chapter_id.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
 // ===== AI Chat Command =====
 
 #[tauri::command(rename_all = "camelCase")]
@@ -546,6 +1571,7 @@ async fn ai_chat(
     mode: session::SessionMode,
     chapter_id: Option<String>,
     allow_write: Option<bool>,
+    session_id: Option<String>,
 ) -> Result<ai_bridge::ChatResponse, String> {
     use tauri::Emitter;
 
@@ -558,6 +1584,7 @@ async fn ai_chat(
         mode,
         chapter_id,
         allow_write: allow_write.unwrap_or(false),
+        session_id,
     };
 
     let cancel_flag = Arc::new(AtomicBool::new(false));
@@ -585,7 +1612,19 @@ async fn ai_chat(
 
     let cancel_for_task = cancel_flag.clone();
     let response = match tauri::async_runtime::spawn_blocking(move || {
-        ai_bridge::run_chat_with_events(request, Some(events), Some(cancel_for_task))
+        let input_chars = usage_stats::prompt_chars(&request.system_prompt, &request.messages);
+        let (usage_provider, usage_parameters) = (request.provider.clone(), request.parameters.clone());
+        let result = ai_bridge::run_chat_with_events(request, Some(events), Some(cancel_for_task));
+        usage_stats::record_call(
+            &usage_provider,
+            &usage_parameters,
+            input_chars,
+            result
+                .as_ref()
+                .map(|r| r.content.chars().count() as u64)
+                .map_err(String::as_str),
+        );
+        result.map_err(|e| ai_bridge::explain_provider_error(&usage_provider, &usage_parameters, e))
     })
     .await
     {
@@ -618,7 +1657,19 @@ async fn ai_extract(
 ) -> Result<serde_json::Value, String> {
     let daemon_arc = daemon.inner().clone();
     tauri::async_runtime::spawn_blocking(move || {
-        ai_proxy::run_extract(&daemon_arc, provider, parameters, text)
+        let input_chars = text.chars().count() as u64;
+        let (usage_provider, usage_parameters) = (provider.clone(), parameters.clone());
+        let result = ai_proxy::run_extract(&daemon_arc, provider, parameters, text);
+        usage_stats::record_call(
+            &usage_provider,
+            &usage_parameters,
+            input_chars,
+            result
+                .as_ref()
+                .map(|v| v.to_string().chars().count() as u64)
+                .map_err(String::as_str),
+        );
+        result
     })
     .await
     .map_err(|e| format!("ai_extract join error: {e}"))?
@@ -635,7 +1686,16 @@ async fn ai_transform(
 ) -> Result<String, String> {
     let daemon_arc = daemon.inner().clone();
     tauri::async_runtime::spawn_blocking(move || {
-        ai_proxy::run_transform(&daemon_arc, provider, parameters, text, action, style)
+        let input_chars = text.chars().count() as u64;
+        let (usage_provider, usage_parameters) = (provider.clone(), parameters.clone());
+        let result = ai_proxy::run_transform(&daemon_arc, provider, parameters, text, action, style);
+        usage_stats::record_call(
+            &usage_provider,
+            &usage_parameters,
+            input_chars,
+            result.as_ref().map(|c| c.chars().count() as u64).map_err(String::as_str),
+        );
+        result
     })
     .await
     .map_err(|e| format!("ai_transform join error: {e}"))?
@@ -652,8 +1712,10 @@ pub fn run() {
                 .map(|_| ())
                 .map_err(|error| -> Box<dyn std::error::Error> { error.into() })?;
 
-            // Start AI daemon in background
             use tauri::Manager;
+            config_events::init(app.handle().clone());
+
+            // Start AI daemon in background
             let daemon = app.state::<Arc<ai_daemon::AIDaemon>>();
             if let Ok(engine_path) = ai_daemon::get_ai_engine_daemon_path() {
                 match daemon.start(&engine_path) {
@@ -668,30 +1730,72 @@ pub fn run() {
         })
         .manage(AiChatRuntime::default())
         .manage(AiCompleteRuntime::default())
+        .manage(ImportRuntime::default())
         .manage(Arc::new(ai_daemon::AIDaemon::new()))
         .invoke_handler(tauri::generate_handler![
             greet,
             get_config,
             save_config,
+            list_config_backups,
+            restore_config_backup,
+            export_settings,
+            import_settings,
             list_providers,
             get_provider,
             add_provider,
             update_provider,
             delete_provider,
             set_active_provider,
+            reorder_providers,
+            set_provider_favorite,
+            set_provider_headers,
             get_api_key,
+            get_key_storage_info,
+            list_orphaned_keys,
+            delete_orphaned_key,
             get_default_parameters,
             set_default_parameters,
+            list_parameter_profiles,
+            save_parameter_profile,
+            delete_parameter_profile,
+            get_provider_parameters,
+            set_provider_parameters,
             refresh_provider_models,
             get_provider_models,
+            set_hidden_models,
+            set_model_alias,
+            set_default_model,
+            test_provider_connection,
+            verify_api_key,
+            get_usage_summary,
+            reset_usage_stats,
             file_read,
             file_write,
             file_append,
+            file_delete,
+            file_rename,
+            file_edit,
             file_list,
             file_search,
+            file_stat,
             load_summaries,
             get_latest_summary,
+            get_recent_summaries,
+            export_summaries_markdown,
+            diff_summaries,
+            search_summaries,
             save_summary_entry,
+            update_summary,
+            delete_summary,
+            prune_summaries,
+            prune_backups,
+            list_backups,
+            restore_file_backup,
+            read_backup_file,
+            diff_backups,
+            create_project_snapshot,
+            list_project_snapshots,
+            restore_project_snapshot,
             rag_list_docs,
             rag_set_doc_enabled,
             rag_read_doc,
@@ -706,26 +1810,71 @@ pub fn run() {
             rag_get_writing_context,
             ai_cancel,
             ai_complete_cancel,
+            generate_chapter_summary,
+            generate_story_rollup,
+            get_story_rollup,
             ai_complete,
             ai_chat,
             get_recent_projects,
             add_recent_project,
+            set_recent_project_pinned,
+            refresh_recent_project_stats,
             create_project,
             open_project,
             get_project_info,
             save_project_config,
+            set_project_read_only,
+            release_project_lock,
             get_presets,
             save_presets,
+            export_preset,
+            import_preset,
+            list_builtin_presets,
+            add_builtin_preset,
+            duplicate_preset,
+            touch_preset,
+            list_global_presets,
+            save_global_preset,
+            delete_global_preset,
+            copy_preset_to_project,
+            promote_preset_to_global,
+            preview_preset_prompt,
+            check_chapter_against_preset,
             list_chapters,
             create_chapter,
+            create_chapter_at,
             get_chapter_content,
+            get_chapters_content,
             save_chapter_content,
+            get_chapter_notes,
+            save_chapter_notes,
             rename_chapter,
+            set_chapter_status,
             delete_chapter,
             reorder_chapters,
+            split_chapter,
+            merge_chapters,
+            recover_chapter_index,
+            rebuild_chapter_index,
+            list_volumes,
+            create_volume,
+            rename_volume,
+            delete_volume,
+            assign_chapter_volume,
+            get_activity_log,
+            get_ai_write_log,
+            undo_ai_apply,
+            get_word_count_history,
+            get_writing_stats,
+            list_trash_entries,
+            restore_trash_entry,
+            empty_project_trash,
             list_sessions,
             create_session,
             rename_session,
+            set_session_profile,
+            set_session_preset,
+            get_effective_preset,
             delete_session,
             get_session_messages,
             add_message,
@@ -734,41 +1883,44 @@ pub fn run() {
             consume_ui_cleanup_flag,
             preview_import_txt,
             import_txt,
+            import_txt_cancel,
+            list_import_patterns,
+            save_import_pattern,
+            delete_import_pattern,
+            preview_import_markdown,
+            import_markdown,
+            export_project,
+            export_chapters,
+            preview_import_summaries,
+            import_summaries,
             ai_extract,
             ai_transform
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|_app, event| {
+            match event {
+                tauri::RunEvent::WindowEvent {
+                    label,
+                    event: tauri::WindowEvent::Destroyed,
+                    ..
+                } => project_lock::release_owned_by(&label),
+                tauri::RunEvent::Exit => {
+                    if let Err(e) = usage_stats::flush() {
+                        eprintln!("[exit] Failed to save usage stats: {e}");
+                    }
+                    project_lock::release_all();
+                }
+                _ => {}
+            }
+        });
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::TempDir;
     use std::fs;
-    use std::path::PathBuf;
-    use std::time::{SystemTime, UNIX_EPOCH};
-
-    struct TempDir {
-        path: PathBuf,
-    }
-
-    impl TempDir {
-        fn new(prefix: &str) -> Self {
-            let ts = SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_millis();
-            let path = std::env::temp_dir().join(format!("{prefix}-{ts}"));
-            fs::create_dir_all(&path).expect("create temp dir");
-            Self { path }
-        }
-    }
-
-    impl Drop for TempDir {
-        fn drop(&mut self) {
-            let _ = fs::remove_dir_all(&self.path);
-        }
-    }
 
     #[test]
     fn file_ops_smoke_test() {
@@ -783,6 +1935,9 @@ mod tests {
                 path: "test.txt".to_string(),
                 offset: None,
                 limit: None,
+                byte_offset: None,
+                byte_limit: None,
+                encoding: None,
             },
         )
         .expect("file_read");
@@ -804,6 +1959,9 @@ mod tests {
                 path: "test.txt".to_string(),
                 offset: None,
                 limit: None,
+                byte_offset: None,
+                byte_limit: None,
+                encoding: None,
             },
         )
         .expect("file_read");
@@ -817,6 +1975,9 @@ mod tests {
                 path: "test.txt".to_string(),
                 offset: Some(-1),
                 limit: None,
+                byte_offset: None,
+                byte_limit: None,
+                encoding: None,
             },
         )
         .expect("file_read tail");
@@ -824,7 +1985,17 @@ mod tests {
         assert!(!read_tail.content.contains("00001| hello"));
         assert!(read_tail.content.contains("00002| world"));
 
-        let listed = file_list(project_dir.clone(), ListParams { path: None }).expect("file_list");
+        let listed = file_list(
+            project_dir.clone(),
+            ListParams {
+                path: None,
+                recursive: None,
+                max_depth: None,
+                offset: None,
+                limit: None,
+            },
+        )
+        .expect("file_list");
         assert!(listed
             .entries
             .iter()
@@ -835,6 +2006,10 @@ mod tests {
             SearchParams {
                 query: "world".to_string(),
                 path: None,
+                regex: false,
+                case_sensitive: None,
+                context: None,
+                max_matches: None,
             },
         )
         .expect("file_search");
@@ -848,6 +2023,10 @@ mod tests {
             SearchParams {
                 query: "hello".to_string(),
                 path: Some("test.txt".to_string()),
+                regex: false,
+                case_sensitive: None,
+                context: None,
+                max_matches: None,
             },
         )
         .expect("file_search file");
@@ -855,6 +2034,26 @@ mod tests {
             .matches
             .iter()
             .any(|m| m.file.ends_with("test.txt") && m.line == 1));
+        assert!(!searched_file.truncated);
+        assert_eq!(searched_file.total_matches, searched_file.matches.len() as u32);
+
+        fs::write(temp.path.join("many.txt"), "hit\n".repeat(600)).expect("write many.txt");
+        let search_many = |max_matches: Option<u32>| {
+            let params = SearchParams {
+                query: "hit".to_string(),
+                path: Some("many.txt".to_string()),
+                regex: false,
+                case_sensitive: None,
+                context: None,
+                max_matches,
+            };
+            file_search(project_dir.clone(), params).expect("file_search many")
+        };
+        let capped = search_many(None);
+        assert_eq!((capped.matches.len(), capped.total_matches, capped.truncated), (50, 600, true));
+        assert_eq!(search_many(Some(5)).matches.len(), 5);
+        assert_eq!(search_many(Some(10_000)).matches.len(), 500);
+        fs::remove_file(temp.path.join("many.txt")).expect("remove many.txt");
 
         file_write(
             project_dir.clone(),
@@ -868,46 +2067,803 @@ mod tests {
     }
 
     #[test]
-    fn project_create_open_save_smoke_test() {
-        let temp = TempDir::new("creatorai-v2-project");
-        let project_root = temp.path.join("MyNovel");
-        let project_path = project_root.to_string_lossy().to_string();
+    fn file_read_byte_ranges_stay_on_character_boundaries() {
+        let temp = TempDir::new("creatorai-v2-file-ops-read-bytes");
+        let project_dir = temp.path.to_string_lossy().to_string();
+        // One huge line, as in imported web-novel chapters: 3-byte characters only.
+        let text = "字".repeat(100_000);
+        fs::write(temp.path.join("huge.txt"), &text).unwrap();
+        let read = |byte_offset: u64, byte_limit: Option<u32>| {
+            let params = ReadParams {
+                path: "huge.txt".to_string(),
+                offset: None,
+                limit: None,
+                byte_offset: Some(byte_offset),
+                byte_limit,
+                encoding: None,
+            };
+            file_read(project_dir.clone(), params).expect("file_read bytes")
+        };
 
-        let config = tauri::async_runtime::block_on(create_project(
-            project_path.clone(),
-            "我的小说".to_string(),
-        ))
-        .expect("create_project");
-        assert_eq!(config.name, "我的小说");
+        let first = read(0, Some(10));
+        assert_eq!(first.content, "字字字");
+        assert_eq!((first.byte_offset, first.file_size, first.truncated), (Some(0), Some(300_000), true));
+
+        let mid = read(4, Some(10));
+        assert_eq!(mid.byte_offset, Some(3));
+        assert_eq!(mid.content, "字字字");
+
+        let default_window = read(0, None);
+        assert!(default_window.content.len() <= 50 * 1024);
+        assert!(default_window.truncated);
+
+        let tail = read(299_998, None);
+        assert_eq!((tail.content.as_str(), tail.byte_offset, tail.truncated), ("字", Some(299_997), false));
+        assert_eq!(read(400_000, None).content, "");
+
+        fs::write(temp.path.join("blob.bin"), [0u8, 1, 2, 3]).unwrap();
+        let params = ReadParams {
+            path: "blob.bin".to_string(),
+            offset: None,
+            limit: None,
+            byte_offset: Some(0),
+            byte_limit: None,
+            encoding: None,
+        };
+        assert_eq!(file_read(project_dir, params).unwrap_err(), "Binary files are not supported");
+    }
 
-        let opened = tauri::async_runtime::block_on(open_project(project_path.clone()))
-            .expect("open_project");
-        assert_eq!(opened.name, "我的小说");
+    #[test]
+    fn file_read_tail_of_a_large_file_is_a_single_pass() {
+        let temp = TempDir::new("creatorai-v2-file-ops-read-tail");
+        let project_dir = temp.path.to_string_lossy().to_string();
+        let text: String = (1..=100_000).map(|i| format!("第{i}行\n")).collect();
+        fs::write(temp.path.join("long.txt"), text).unwrap();
+        let read = |offset: i64, limit: Option<u32>| {
+            let params = ReadParams {
+                path: "long.txt".to_string(),
+                offset: Some(offset),
+                limit,
+                byte_offset: None,
+                byte_limit: None,
+                encoding: None,
+            };
+            file_read(project_dir.clone(), params).expect("file_read tail")
+        };
 
-        let info = tauri::async_runtime::block_on(get_project_info(project_path.clone()))
-            .expect("get_project_info");
-        assert_eq!(info.name, "我的小说");
+        let lines_before = file_ops::read::LINES_READ.with(|n| n.get());
+        let tail = read(-3, None);
+        assert_eq!(tail.content, "99998| 第99998行\n99999| 第99999行\n100000| 第100000行");
+        assert_eq!((tail.total_lines, tail.truncated), (100_000, false));
+        // Every line is read exactly once: no rescan to find the tail.
+        assert_eq!(file_ops::read::LINES_READ.with(|n| n.get()) - lines_before, 100_000);
+
+        // A tail longer than the limit keeps its first `limit` lines, as before.
+        let window = read(-5, Some(2));
+        assert_eq!(window.content, "99996| 第99996行\n99997| 第99997行");
+        assert!(window.truncated);
+
+        let everything = read(i64::MIN, None);
+        assert!(everything.content.starts_with("00001| 第1行\n"));
+        assert!(everything.truncated);
+    }
 
-        let mut updated = info.clone();
-        updated.name = "新名称".to_string();
-        tauri::async_runtime::block_on(save_project_config(project_path.clone(), updated))
-            .expect("save_project_config");
+    #[test]
+    fn file_read_decodes_gbk_and_bom_files() {
+        let temp = TempDir::new("creatorai-v2-file-ops-read-encoding");
+        let project_dir = temp.path.to_string_lossy().to_string();
+        let (gbk, _, _) = encoding_rs::GBK.encode("第一章\r\n天色已晚。\r\n");
+        fs::write(temp.path.join("gbk.txt"), &gbk).unwrap();
+        fs::write(temp.path.join("bom.txt"), b"\xEF\xBB\xBFhello\nworld\n").unwrap();
+        let read = |path: &str, offset: Option<i64>, encoding: Option<&str>| {
+            let params = ReadParams {
+                path: path.to_string(),
+                offset,
+                limit: None,
+                byte_offset: None,
+                byte_limit: None,
+                encoding: encoding.map(str::to_string),
+            };
+            file_read(project_dir.clone(), params)
+        };
 
-        let info2 = tauri::async_runtime::block_on(get_project_info(project_path.clone()))
-            .expect("get_project_info after save");
-        assert_eq!(info2.name, "新名称");
+        assert!(read("gbk.txt", None, None).is_err());
+        let auto = read("gbk.txt", None, Some("auto")).expect("auto gbk");
+        assert_eq!(auto.encoding.as_deref(), Some("gbk"));
+        assert_eq!(auto.content, "00001| 第一章\n00002| 天色已晚。");
+        assert_eq!(auto.total_lines, 2);
+        let tail = read("gbk.txt", Some(-1), Some("gbk")).expect("explicit gbk");
+        assert_eq!(tail.encoding.as_deref(), Some("gbk"));
+        assert_eq!(tail.content, "00002| 天色已晚。");
+
+        let bom = read("bom.txt", None, Some("auto")).expect("auto bom");
+        assert_eq!(bom.encoding.as_deref(), Some("utf-8"));
+        assert_eq!(bom.content, "00001| hello\n00002| world");
+        assert!(read("bom.txt", None, None).unwrap().encoding.is_none());
+
+        let err = read("bom.txt", None, Some("klingon")).unwrap_err();
+        assert!(err.contains("Unsupported encoding"), "{err}");
     }
 
     #[test]
-    fn chapter_crud_smoke_test() {
-        let temp = TempDir::new("creatorai-v2-chapter");
+    fn file_commands_refuse_protected_paths_including_project_extras() {
+        let temp = TempDir::new("creatorai-v2-file-ops-protected");
+        let project_dir = temp.path.to_string_lossy().to_string();
+        fs::create_dir_all(temp.path.join(".creatorai")).unwrap();
+        fs::write(
+            temp.path.join(".creatorai/config.json"),
+            r#"{ "name": "t", "created": 1, "updated": 1, "version": "1",
+  "settings": { "autoSave": true, "autoSaveInterval": 2000, "protectedPaths": ["world/", "Outline.md"] } }"#,
+        )
+        .unwrap();
+        let write = |path: &str| {
+            file_write(
+                project_dir.clone(),
+                WriteParams {
+                    path: path.to_string(),
+                    content: "x".to_string(),
+                },
+            )
+        };
+
+        let err = write(".creatorai/config.json").unwrap_err();
+        assert!(err.contains("use the dedicated API"), "{err}");
+        assert!(write("Chapters/Index.json").is_err());
+        assert!(write("world/map.md").is_err());
+        assert!(write("outline.md").is_err());
+        assert!(file_append(
+            project_dir.clone(),
+            AppendParams {
+                path: "world/map.md".to_string(),
+                content: "x".to_string(),
+            },
+        )
+        .is_err());
+        write("worldbuilding.md").expect("not under world/");
+        write("notes/outline.md").expect("only the top-level outline is protected");
+        assert!(fs::read_to_string(temp.path.join(".creatorai/config.json"))
+            .unwrap()
+            .contains("protectedPaths"));
+    }
+
+    #[test]
+    fn file_search_supports_regex_queries() {
+        let temp = TempDir::new("creatorai-v2-file-ops-search-regex");
+        let project_dir = temp.path.to_string_lossy().to_string();
+        fs::write(
+            temp.path.join("chapter.txt"),
+            "林舟：“走吧。”\n他看向林舟。\n林舟说：“等等。”\n",
+        )
+        .unwrap();
+        let search = |query: &str, regex: bool| {
+            file_search(project_dir.clone(), SearchParams { query: query.to_string(), path: None, regex, case_sensitive: None, context: None, max_matches: None })
+        };
+
+        let dialogue = search("^林舟[：说]", true).expect("regex search");
+        let lines: Vec<u32> = dialogue.matches.iter().map(|m| m.line).collect();
+        assert_eq!(lines, [1, 3]);
+        assert_eq!(dialogue.matches[1].content, "林舟说：“等等。”");
+        assert_eq!(search("。”$", true).expect("anchored at line end").matches.len(), 2);
+
+        // Without the flag the query is still a plain substring.
+        assert!(search("^林舟", false).expect("substring search").matches.is_empty());
+        assert_eq!(search("林舟", false).expect("substring search").matches.len(), 3);
+
+        let err = search("林舟(", true).unwrap_err();
+        assert!(err.starts_with("Invalid regex"), "{err}");
+    }
+
+    #[test]
+    fn file_search_can_ignore_case_across_files() {
+        let temp = TempDir::new("creatorai-v2-file-ops-search-case");
+        let project_dir = temp.path.to_string_lossy().to_string();
+        fs::create_dir_all(temp.path.join("notes")).unwrap();
+        fs::write(temp.path.join("chapter.txt"), "Intro\n\"LENA!\" he shouted.\nquiet\n").unwrap();
+        fs::write(temp.path.join("notes/cast.md"), "lena: the pilot\nÉLODIE and Lena\n").unwrap();
+        let search = |query: &str, case_sensitive: Option<bool>| {
+            let params = SearchParams { query: query.to_string(), path: None, regex: false, case_sensitive, context: None, max_matches: None };
+            let mut found: Vec<(String, u32)> = file_search(project_dir.clone(), params)
+                .expect("file_search")
+                .matches
+                .into_iter()
+                .map(|m| (m.file.replace('\\', "/"), m.line))
+                .collect();
+            found.sort();
+            found
+        };
+
+        let expected = [
+            ("chapter.txt".to_string(), 2),
+            ("notes/cast.md".to_string(), 1),
+            ("notes/cast.md".to_string(), 2),
+        ];
+        assert_eq!(search("Lena", Some(false)), expected);
+        assert_eq!(search("Lena", None), [("notes/cast.md".to_string(), 2)]);
+        assert_eq!(search("élodie", Some(false)), [("notes/cast.md".to_string(), 2)]);
+        assert!(search("élodie", Some(true)).is_empty());
+
+        let params = SearchParams { query: "^lena".to_string(), path: None, regex: true, case_sensitive: Some(false), context: None, max_matches: None };
+        let hits = file_search(project_dir.clone(), params).expect("regex ignoring case");
+        assert_eq!(hits.matches.len(), 1);
+        assert_eq!(hits.matches[0].content, "lena: the pilot");
+    }
+
+    #[test]
+    fn file_search_returns_context_lines_around_matches() {
+        let temp = TempDir::new("creatorai-v2-file-ops-search-context");
+        let project_dir = temp.path.to_string_lossy().to_string();
+        fs::write(temp.path.join("scene.txt"), "雨夜\n二\n三\n四\n五\n雨停\n").unwrap();
+        let search = |context: Option<u32>| {
+            let params = SearchParams {
+                query: "雨".to_string(),
+                path: Some("scene.txt".to_string()),
+                regex: false,
+                case_sensitive: None,
+                context,
+                max_matches: None,
+            };
+            file_search(project_dir.clone(), params).expect("file_search").matches
+        };
+
+        let hits = search(Some(2));
+        let lines: Vec<u32> = hits.iter().map(|m| m.line).collect();
+        assert_eq!(lines, [1, 6]);
+        // First line of the file: nothing before it.
+        assert_eq!(hits[0].before.as_deref(), Some(&[][..]));
+        assert_eq!(hits[0].after.as_deref(), Some(&["二".to_string(), "三".to_string()][..]));
+        // Last line of the file: nothing after it.
+        assert_eq!(hits[1].before.as_deref(), Some(&["四".to_string(), "五".to_string()][..]));
+        assert_eq!(hits[1].after.as_deref(), Some(&[][..]));
+
+        // Clamped to five lines a side.
+        assert_eq!(search(Some(50))[0].after.as_ref().map(Vec::len), Some(5));
+
+        let plain = search(None);
+        assert!(plain[0].before.is_none() && plain[0].after.is_none());
+        let json = serde_json::to_value(&plain[0]).unwrap();
+        assert!(json.get("before").is_none());
+    }
+
+    #[test]
+    fn file_list_walks_nested_directories_up_to_the_depth_limit() {
+        let temp = TempDir::new("creatorai-v2-file-ops-list-recursive");
+        let project_dir = temp.path.to_string_lossy().to_string();
+        fs::create_dir_all(temp.path.join("world/places/north")).unwrap();
+        fs::create_dir_all(temp.path.join(".backup/123/world")).unwrap();
+        fs::create_dir_all(temp.path.join("node_modules/pkg")).unwrap();
+        fs::write(temp.path.join("outline.md"), "大纲").unwrap();
+        fs::write(temp.path.join("world/places/city.md"), "城").unwrap();
+        fs::write(temp.path.join("world/places/north/ice.md"), "冰").unwrap();
+        fs::write(temp.path.join(".backup/123/world/old.md"), "旧").unwrap();
+        let list = |path: Option<&str>, recursive: Option<bool>, max_depth: Option<u32>| {
+            let params = ListParams {
+                path: path.map(str::to_string),
+                recursive,
+                max_depth,
+                offset: None,
+                limit: None,
+            };
+            let result = file_list(project_dir.clone(), params).expect("file_list");
+            assert!(!result.truncated);
+            let mut paths: Vec<String> = result.entries.into_iter().map(|e| e.path).collect();
+            paths.sort();
+            paths
+        };
+
+        assert_eq!(list(None, None, None), ["outline.md", "world"]);
+        assert_eq!(
+            list(None, Some(true), None),
+            [
+                "outline.md",
+                "world",
+                "world/places",
+                "world/places/city.md",
+                "world/places/north",
+                "world/places/north/ice.md",
+            ]
+        );
+        assert_eq!(
+            list(None, Some(true), Some(2)),
+            ["outline.md", "world", "world/places"]
+        );
+        assert_eq!(
+            list(Some("world/places"), Some(true), None),
+            ["world/places/city.md", "world/places/north", "world/places/north/ice.md"]
+        );
+    }
+
+    #[test]
+    fn file_list_pages_through_large_directories() {
+        let temp = TempDir::new("creatorai-v2-file-ops-list-pages");
+        let project_dir = temp.path.to_string_lossy().to_string();
+        for n in 0..250 {
+            fs::write(temp.path.join(format!("chapter_{n:03}.txt")), "").unwrap();
+        }
+        fs::create_dir_all(temp.path.join("zz_dir")).unwrap();
+        let page = |offset: Option<u32>, limit: Option<u32>| {
+            let params = ListParams { path: None, recursive: None, max_depth: None, offset, limit };
+            file_list(project_dir.clone(), params).expect("file_list")
+        };
+
+        let first = page(None, None);
+        assert_eq!((first.entries.len(), first.total, first.has_more), (100, 251, true));
+        // Directories come first.
+        assert_eq!(first.entries[0].name, "zz_dir");
+
+        let mut seen = Vec::new();
+        let mut offset = 0;
+        loop {
+            let result = page(Some(offset), Some(60));
+            seen.extend(result.entries.iter().map(|e| e.name.clone()));
+            offset += result.entries.len() as u32;
+            if !result.has_more {
+                break;
+            }
+        }
+        let mut expected: Vec<String> = (0..250).map(|n| format!("chapter_{n:03}.txt")).collect();
+        expected.insert(0, "zz_dir".to_string());
+        assert_eq!(seen, expected);
+        assert!(page(Some(400), None).entries.is_empty());
+    }
+
+    #[test]
+    fn file_stat_reports_metadata_and_missing_files() {
+        let temp = TempDir::new("creatorai-v2-file-ops-stat");
+        let project_dir = temp.path.to_string_lossy().to_string();
+        fs::create_dir_all(temp.path.join("notes")).unwrap();
+        fs::write(temp.path.join("notes/a.md"), "第一行\nsecond line\n").unwrap();
+        fs::write(temp.path.join("notes/blob.bin"), [0u8, 1, 2]).unwrap();
+        let stat = |path: &str| file_stat(project_dir.clone(), path.to_string());
+
+        let text = stat("notes/a.md").expect("stat text");
+        assert!(text.exists && !text.is_dir);
+        assert_eq!(text.size, "第一行\nsecond line\n".len() as u64);
+        assert!(text.modified > 0);
+        assert_eq!((text.line_count, text.word_count), (Some(2), Some(13)));
+
+        let binary = stat("notes/blob.bin").expect("stat binary");
+        assert_eq!((binary.size, binary.line_count, binary.word_count), (3, None, None));
+
+        let dir = stat("notes").expect("stat dir");
+        assert!(dir.exists && dir.is_dir && dir.line_count.is_none());
+
+        let missing = stat("notes/missing.md").expect("missing is not an error");
+        assert!(!missing.exists);
+        assert!(stat("../outside.md").is_err());
+
+        #[cfg(unix)]
+        {
+            let outside = TempDir::new("creatorai-v2-file-ops-stat-outside");
+            fs::write(outside.path.join("secret.txt"), "secret").unwrap();
+            std::os::unix::fs::symlink(outside.path.join("secret.txt"), temp.path.join("link.txt")).unwrap();
+            assert!(stat("link.txt").is_err());
+        }
+    }
+
+    #[test]
+    fn file_write_restores_the_original_when_the_rename_fails() {
+        let temp = TempDir::new("creatorai-v2-file-ops-write-rollback");
+        let project_dir = temp.path.to_string_lossy().to_string();
+        fs::create_dir_all(temp.path.join("chapters/chapter_001.txt")).unwrap();
+        fs::write(temp.path.join("chapters/chapter_001.txt/keep.txt"), "keep").unwrap();
+        fs::write(temp.path.join("chapters/chapter_002.txt"), "原稿").unwrap();
+        let write = |path: &str| {
+            let params = WriteParams { path: path.to_string(), content: "新稿".to_string() };
+            file_write(project_dir.clone(), params)
+        };
+
+        // A directory in the way is refused before anything is touched.
+        assert!(write("chapters/chapter_001.txt").unwrap_err().contains("is a directory"));
+        assert_eq!(fs::read_to_string(temp.path.join("chapters/chapter_001.txt/keep.txt")).unwrap(), "keep");
+
+        write_protection::fail_renames(true);
+        let err = write("chapters/chapter_002.txt").unwrap_err();
+        write_protection::fail_renames(false);
+        assert!(err.contains("injected rename failure"), "{err}");
+        assert_eq!(fs::read_to_string(temp.path.join("chapters/chapter_002.txt")).unwrap(), "原稿");
+        let leftovers: Vec<String> = fs::read_dir(temp.path.join("chapters"))
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .filter(|name| name.contains(".tmp."))
+            .collect();
+        assert!(leftovers.is_empty(), "{leftovers:?}");
+
+        write("chapters/chapter_002.txt").expect("write once renames work again");
+        assert_eq!(fs::read_to_string(temp.path.join("chapters/chapter_002.txt")).unwrap(), "新稿");
+    }
+
+    #[test]
+    fn file_delete_backs_up_the_file_and_refuses_unsafe_targets() {
+        let temp = TempDir::new("creatorai-v2-file-ops-delete");
+        let project_dir = temp.path.to_string_lossy().to_string();
+        fs::create_dir_all(temp.path.join(".creatorai")).unwrap();
+        fs::create_dir_all(temp.path.join("notes")).unwrap();
+        fs::write(temp.path.join(".creatorai/config.json"), "{}\n").unwrap();
+        fs::write(temp.path.join("notes/draft.md"), "草稿").unwrap();
+        let delete = |path: &str| {
+            file_delete(project_dir.clone(), DeleteParams { path: path.to_string() })
+        };
+
+        assert!(delete("../outside.txt").is_err());
+        assert!(delete("notes/missing.md").unwrap_err().contains("Failed to stat"));
+        assert!(delete("notes").unwrap_err().contains("is a directory"));
+        assert!(delete(".creatorai/config.json").is_err());
+        assert!(delete("./.backup/anything.txt").unwrap_err().contains("not deletable"));
+        assert!(temp.path.join(".creatorai/config.json").exists());
+
+        delete("notes/draft.md").expect("delete");
+        assert!(!temp.path.join("notes/draft.md").exists());
+        let backups: Vec<String> = fs::read_dir(temp.path.join(".backup"))
+            .unwrap()
+            .filter_map(|e| fs::read_to_string(e.unwrap().path().join("notes/draft.md")).ok())
+            .collect();
+        assert_eq!(backups, ["草稿"]);
+    }
+
+    #[test]
+    fn file_delete_refuses_indexed_chapter_files() {
+        let temp = TempDir::new("creatorai-v2-file-ops-delete-chapter");
+        let project_root = temp.path.join("MyNovel");
+        let project_path = project_root.to_string_lossy().to_string();
+        project::create_project_sync(project_path.clone(), "我的小说".to_string(), "main")
+            .expect("create_project");
+        let chapter = tauri::async_runtime::block_on(create_chapter(project_path.clone(), "第一章".to_string()))
+            .expect("create_chapter");
+        fs::write(project_root.join("chapters/chapter_999.txt"), "未编入目录").unwrap();
+
+        let relative = format!("chapters/{}.txt", chapter.id);
+        let err = file_delete(project_path.clone(), DeleteParams { path: relative.clone() }).unwrap_err();
+        assert!(err.contains("delete_chapter"), "{err}");
+        assert!(project_root.join(&relative).exists());
+
+        file_delete(project_path.clone(), DeleteParams { path: "chapters/chapter_999.txt".to_string() })
+            .expect("unindexed chapter-like file");
+        let chapters = tauri::async_runtime::block_on(list_chapters(project_path)).expect("list_chapters");
+        assert_eq!(chapters.len(), 1);
+    }
+
+    #[test]
+    fn file_rename_moves_files_and_backs_up_an_overwritten_destination() {
+        let temp = TempDir::new("creatorai-v2-file-ops-rename");
+        let project_dir = temp.path.to_string_lossy().to_string();
+        fs::create_dir_all(temp.path.join("knowledge")).unwrap();
+        fs::write(temp.path.join("knowledge/notes.md"), "角色笔记").unwrap();
+        fs::write(temp.path.join("knowledge/old.md"), "旧设定").unwrap();
+        let rename = |from: &str, to: &str, overwrite: bool| {
+            let params = RenameParams { from: from.to_string(), to: to.to_string(), overwrite };
+            file_rename(project_dir.clone(), params)
+        };
+
+        rename("knowledge/notes.md", "knowledge/characters/notes.md", false).expect("cross-directory move");
+        assert!(!temp.path.join("knowledge/notes.md").exists());
+        assert_eq!(
+            fs::read_to_string(temp.path.join("knowledge/characters/notes.md")).unwrap(),
+            "角色笔记"
+        );
+
+        rename("knowledge/old.md", "./knowledge/old.md", false).expect("same-name no-op");
+        assert_eq!(fs::read_to_string(temp.path.join("knowledge/old.md")).unwrap(), "旧设定");
+        assert!(rename("knowledge/old.md", "../old.md", false).is_err());
+
+        let err = rename("knowledge/characters/notes.md", "knowledge/old.md", false).unwrap_err();
+        assert!(err.contains("already exists"), "{err}");
+        rename("knowledge/characters/notes.md", "knowledge/old.md", true).expect("overwrite");
+        assert_eq!(fs::read_to_string(temp.path.join("knowledge/old.md")).unwrap(), "角色笔记");
+        let backups: Vec<String> = fs::read_dir(temp.path.join(".backup"))
+            .unwrap()
+            .filter_map(|e| fs::read_to_string(e.unwrap().path().join("knowledge/old.md")).ok())
+            .collect();
+        assert_eq!(backups, ["旧设定"]);
+    }
+
+    #[test]
+    fn file_rename_refreshes_chapter_word_counts_and_refuses_to_move_chapters() {
+        let temp = TempDir::new("creatorai-v2-file-ops-rename-chapter");
+        let project_root = temp.path.join("MyNovel");
+        let project_path = project_root.to_string_lossy().to_string();
+        project::create_project_sync(project_path.clone(), "我的小说".to_string(), "main")
+            .expect("create_project");
+        let chapter = tauri::async_runtime::block_on(create_chapter(project_path.clone(), "第一章".to_string()))
+            .expect("create_chapter");
+        fs::write(project_root.join("drafts.txt"), "新的 正文").unwrap();
+
+        let into_chapter = RenameParams {
+            from: "drafts.txt".to_string(),
+            to: format!("chapters/{}.txt", chapter.id),
+            overwrite: true,
+        };
+        file_rename(project_path.clone(), into_chapter).expect("rename into chapter");
+        let chapters = tauri::async_runtime::block_on(list_chapters(project_path.clone())).expect("list_chapters");
+        assert_eq!(chapters[0].word_count, 4);
+
+        let out_of_chapter = RenameParams {
+            from: format!("chapters/{}.txt", chapter.id),
+            to: "archive/chapter.txt".to_string(),
+            overwrite: false,
+        };
+        let err = file_rename(project_path.clone(), out_of_chapter).unwrap_err();
+        assert!(err.contains("chapter index"), "{err}");
+        assert!(project_root.join(format!("chapters/{}.txt", chapter.id)).exists());
+        assert!(!project_root.join("archive/chapter.txt").exists());
+        let chapters = tauri::async_runtime::block_on(list_chapters(project_path)).expect("list_chapters");
+        assert_eq!(chapters[0].word_count, 4);
+    }
+
+    #[test]
+    fn file_edit_replaces_line_ranges() {
+        let temp = TempDir::new("creatorai-v2-file-ops-edit");
+        let project_dir = temp.path.to_string_lossy().to_string();
+        fs::write(temp.path.join("notes.md"), "一\n二\n三\n").unwrap();
+        let edit = |path: &str, start_line: u32, end_line: u32, content: &str| {
+            let params = EditParams {
+                path: path.to_string(),
+                start_line,
+                end_line,
+                content: content.to_string(),
+            };
+            file_edit(project_dir.clone(), params)
+        };
+
+        let result = edit("notes.md", 1, 1, "壹\n零").expect("edit first line");
+        assert_eq!(fs::read_to_string(temp.path.join("notes.md")).unwrap(), "壹\n零\n二\n三\n");
+        assert_eq!((result.total_lines, result.word_count), (4, 4));
+
+        edit("notes.md", 3, 4, "").expect("delete last lines");
+        assert_eq!(fs::read_to_string(temp.path.join("notes.md")).unwrap(), "壹\n零\n");
+
+        let err = edit("notes.md", 2, 3, "x").unwrap_err();
+        assert!(err.contains("outside the file"), "{err}");
+        assert!(edit("notes.md", 0, 1, "x").is_err());
+        assert!(edit("notes.md", 2, 1, "x").is_err());
+        assert!(edit("missing.md", 1, 1, "x").is_err());
+
+        fs::write(temp.path.join("crlf.txt"), "a\r\nb\r\nc").unwrap();
+        let result = edit("crlf.txt", 2, 3, "B\nC").expect("edit crlf");
+        assert_eq!(fs::read_to_string(temp.path.join("crlf.txt")).unwrap(), "a\r\nB\r\nC");
+        assert_eq!(result.total_lines, 3);
+    }
+
+    #[test]
+    fn file_edit_refreshes_chapter_word_count() {
+        let temp = TempDir::new("creatorai-v2-file-ops-edit-chapter");
+        let project_root = temp.path.join("MyNovel");
+        let project_path = project_root.to_string_lossy().to_string();
+        project::create_project_sync(project_path.clone(), "我的小说".to_string(), "main")
+            .expect("create_project");
+        let chapter = tauri::async_runtime::block_on(create_chapter(project_path.clone(), "第一章".to_string()))
+            .expect("create_chapter");
+        let path = format!("chapters/{}.txt", chapter.id);
+        fs::write(project_root.join(&path), "开头\n结尾\n").unwrap();
+
+        let params = EditParams { path, start_line: 2, end_line: 2, content: "新的结尾".to_string() };
+        let result = file_edit(project_path.clone(), params).expect("edit chapter");
+        assert_eq!(result.word_count, 6);
+        let chapters = tauri::async_runtime::block_on(list_chapters(project_path)).expect("list_chapters");
+        assert_eq!(chapters[0].word_count, 6);
+    }
+
+    #[test]
+    fn chapter_saves_can_be_listed_and_restored_from_backups() {
+        let temp = TempDir::new("creatorai-v2-backup-commands");
         let project_root = temp.path.join("MyNovel");
         let project_path = project_root.to_string_lossy().to_string();
+        project::create_project_sync(project_path.clone(), "我的小说".to_string(), "main")
+            .expect("create_project");
+        let chapter = tauri::async_runtime::block_on(create_chapter(project_path.clone(), "第一章".to_string()))
+            .expect("create_chapter");
+        for content in ["初稿", "二稿内容", "三稿的内容"] {
+            tauri::async_runtime::block_on(save_chapter_content(
+                project_path.clone(),
+                chapter.id.clone(),
+                content.to_string(),
+            ))
+            .expect("save_chapter_content");
+        }
+        let relative = format!("chapters/{}.txt", chapter.id);
+        let versions = || {
+            let files = tauri::async_runtime::block_on(list_backups(project_path.clone(), Some(relative.clone())))
+                .expect("list_backups");
+            files.into_iter().next().map(|f| f.backups).unwrap_or_default()
+        };
 
-        tauri::async_runtime::block_on(create_project(
+        // The empty file create_chapter made, then the first two saves; newest first.
+        let before = versions();
+        assert_eq!(before.len(), 3);
+        let middle = &before[1];
+        assert_eq!(fs::read_to_string(project_root.join(&middle.backup_path)).unwrap(), "初稿");
+
+        let restored = tauri::async_runtime::block_on(restore_file_backup(
+            project_path.clone(),
+            relative.clone(),
+            middle.timestamp,
+        ))
+        .expect("restore_file_backup");
+        assert_eq!(restored.chapter.map(|c| c.word_count), Some(2));
+        assert_eq!(fs::read_to_string(project_root.join(&relative)).unwrap(), "初稿");
+
+        let after = versions();
+        assert_eq!(after.len(), 4);
+        assert_eq!(fs::read_to_string(project_root.join(&after[0].backup_path)).unwrap(), "三稿的内容");
+    }
+
+    #[test]
+    fn project_create_open_save_smoke_test() {
+        let temp = TempDir::new("creatorai-v2-project");
+        let project_root = temp.path.join("MyNovel");
+        let project_path = project_root.to_string_lossy().to_string();
+
+        let config = project::create_project_sync(
             project_path.clone(),
             "我的小说".to_string(),
+            "main",
+        )
+        .expect("create_project");
+        assert_eq!(config.name, "我的小说");
+
+        let opened = project::open_project_sync(project_path.clone(), false, false, "main")
+            .expect("open_project");
+        assert_eq!(opened.name, "我的小说");
+
+        let info = tauri::async_runtime::block_on(get_project_info(project_path.clone()))
+            .expect("get_project_info");
+        assert_eq!(info.name, "我的小说");
+
+        let mut updated = info.clone();
+        updated.name = "新名称".to_string();
+        tauri::async_runtime::block_on(save_project_config(project_path.clone(), updated))
+            .expect("save_project_config");
+
+        let info2 = tauri::async_runtime::block_on(get_project_info(project_path.clone()))
+            .expect("get_project_info after save");
+        assert_eq!(info2.name, "新名称");
+    }
+
+    #[test]
+    fn read_only_project_refuses_every_mutating_command() {
+        use tauri::async_runtime::block_on;
+
+        let temp = TempDir::new("creatorai-v2-read-only");
+        let project_root = temp.path.join("MyNovel");
+        let project_path = project_root.to_string_lossy().to_string();
+        project::create_project_sync(project_path.clone(), "我的小说".to_string(), "main").expect("create_project");
+        let chapter = block_on(create_chapter(project_path.clone(), "第一章".to_string())).expect("create_chapter");
+        block_on(save_chapter_content(project_path.clone(), chapter.id.clone(), "原文".to_string()))
+            .expect("save_chapter_content");
+        let session = block_on(create_session(
+            project_path.clone(),
+            "讨论".to_string(),
+            session::SessionMode::Discussion,
+            None,
+        ))
+        .expect("create_session");
+        let entry = save_summary_entry(project_path.clone(), chapter.id.clone(), "摘要".to_string())
+            .expect("save_summary_entry");
+        rag_write_doc(project_path.clone(), "knowledge/设定.md".to_string(), "设定".to_string())
+            .expect("rag_write_doc");
+        fs::write(project_root.join("notes.md"), "笔记").unwrap();
+
+        let config = block_on(set_project_read_only(project_path.clone(), true)).expect("set read-only");
+        assert!(config.settings.read_only);
+
+        let p = || project_path.clone();
+        let refused: Vec<(&str, Result<(), String>)> = vec![
+            ("create_chapter", block_on(create_chapter(p(), "第二章".to_string())).map(drop)),
+            (
+                "save_chapter_content",
+                block_on(save_chapter_content(p(), chapter.id.clone(), "改写".to_string())).map(drop),
+            ),
+            (
+                "rename_chapter",
+                block_on(rename_chapter(p(), chapter.id.clone(), "新标题".to_string())).map(drop),
+            ),
+            ("reorder_chapters", block_on(reorder_chapters(p(), vec![chapter.id.clone()])).map(drop)),
+            ("delete_chapter", block_on(delete_chapter(p(), chapter.id.clone()))),
+            (
+                "create_session",
+                block_on(create_session(p(), "新会话".to_string(), session::SessionMode::Discussion, None))
+                    .map(drop),
+            ),
+            (
+                "add_message",
+                block_on(add_message(p(), session.id.clone(), session::MessageRole::User, "你好".to_string(), None))
+                    .map(drop),
+            ),
+            ("rename_session", block_on(rename_session(p(), session.id.clone(), "改名".to_string()))),
+            ("delete_session", block_on(delete_session(p(), session.id.clone()))),
+            (
+                "save_summary_entry",
+                save_summary_entry(p(), chapter.id.clone(), "新摘要".to_string()).map(drop),
+            ),
+            (
+                "update_summary",
+                update_summary(p(), chapter.id.clone(), entry.entry.created_at, "改".to_string()).map(drop),
+            ),
+            ("delete_summary", delete_summary(p(), chapter.id.clone(), entry.entry.created_at).map(drop)),
+            ("prune_summaries", block_on(prune_summaries(p(), 1)).map(drop)),
+            (
+                "rag_write_doc",
+                rag_write_doc(p(), "knowledge/设定.md".to_string(), "改".to_string()),
+            ),
+            (
+                "rag_append_doc",
+                rag_append_doc(p(), "knowledge/设定.md".to_string(), "补充".to_string()),
+            ),
+            (
+                "file_write",
+                file_write(p(), WriteParams { path: "notes.md".to_string(), content: "改".to_string() }),
+            ),
+            (
+                "file_append",
+                file_append(p(), AppendParams { path: "notes.md".to_string(), content: "补充".to_string() }),
+            ),
+        ];
+        for (name, result) in refused {
+            assert_eq!(result, Err(write_protection::READ_ONLY_ERROR.to_string()), "{name}");
+        }
+
+        // Reads, searches and exports keep working and nothing changed.
+        assert_eq!(
+            block_on(get_chapter_content(p(), chapter.id.clone())).expect("get_chapter_content"),
+            "原文"
+        );
+        assert_eq!(block_on(list_chapters(p())).expect("list_chapters").len(), 1);
+        assert!(block_on(get_session_messages(p(), session.id.clone())).expect("messages").is_empty());
+        assert_eq!(load_summaries(p()).expect("load_summaries").summaries.len(), 1);
+        assert_eq!(rag_read_doc(p(), "knowledge/设定.md".to_string()).expect("rag_read_doc"), "设定");
+        let hits = file_search(p(), SearchParams { query: "笔记".to_string(), path: None, regex: false, case_sensitive: None, context: None, max_matches: None }).expect("file_search");
+        assert_eq!(hits.matches.len(), 1);
+        let dest = temp.path.join("outline.md");
+        block_on(export_summaries_markdown(p(), dest.to_string_lossy().to_string(), None))
+            .expect("export_summaries_markdown");
+        assert!(dest.exists());
+
+        block_on(set_project_read_only(p(), false)).expect("clear read-only");
+        block_on(save_chapter_content(p(), chapter.id.clone(), "改写".to_string()))
+            .expect("save_chapter_content after read-only");
+    }
+
+    #[test]
+    fn chapter_summary_rejects_empty_chapters_before_calling_the_provider() {
+        let temp = TempDir::new("creatorai-v2-chapter-summary");
+        let project_root = temp.path.join("MyNovel");
+        let project_path = project_root.to_string_lossy().to_string();
+        project::create_project_sync(project_path.clone(), "我的小说".to_string(), "main")
+            .expect("create_project");
+        let chapter = tauri::async_runtime::block_on(create_chapter(
+            project_path.clone(),
+            "第一章".to_string(),
         ))
+        .expect("create_chapter");
+
+        // An unusable provider: reaching it would produce a different error.
+        let err = generate_chapter_summary_sync(
+            project_path.clone(),
+            chapter.id.clone(),
+            serde_json::json!({}),
+            serde_json::json!({}),
+            Arc::new(AtomicBool::new(false)),
+        )
+        .expect_err("empty chapter");
+        assert_eq!(err, "章节内容为空，无法生成摘要");
+        assert!(summary::load_summaries(&project_root).expect("load").summaries.is_empty());
+
+        let prompt = chapter_summary_user_prompt("chapter_001", "第一章", &"字".repeat(SUMMARY_INPUT_CHAR_BUDGET + 10));
+        assert!(prompt.contains("仅为结尾部分"));
+        assert_eq!(tail_chars("开头中段结尾", 2), "结尾");
+        assert_eq!(tail_chars("短", 2), "短");
+    }
+
+    #[test]
+    fn chapter_crud_smoke_test() {
+        let temp = TempDir::new("creatorai-v2-chapter");
+        let project_root = temp.path.join("MyNovel");
+        let project_path = project_root.to_string_lossy().to_string();
+
+        project::create_project_sync(
+            project_path.clone(),
+            "我的小说".to_string(),
+            "main",
+        )
         .expect("create_project");
 
         let chapters =
@@ -935,7 +2891,25 @@ mod tests {
             "你好 世界".to_string(),
         ))
         .expect("save_chapter_content");
-        assert_eq!(saved.word_count, 4);
+        assert_eq!(saved.meta.word_count, 4);
+        assert!(saved.warnings.is_empty(), "{:?}", saved.warnings);
+
+        let resaved = tauri::async_runtime::block_on(save_chapter_content(
+            project_path.clone(),
+            ch1.id.clone(),
+            "重写的开头".to_string(),
+        ))
+        .expect("save_chapter_content again");
+        assert_eq!(resaved.meta.word_count, 5);
+        let backed_up = |relative: &str| -> Vec<String> {
+            write_protection::list_backups(&project_root, Some(relative))
+                .expect("list_backups")
+                .into_iter()
+                .flat_map(|f| f.backups)
+                .map(|b| fs::read_to_string(project_root.join(b.backup_path)).unwrap())
+                .collect()
+        };
+        assert!(backed_up("chapters/chapter_001.txt").contains(&"你好 世界".to_string()));
 
         let renamed = tauri::async_runtime::block_on(rename_chapter(
             project_path.clone(),
@@ -963,8 +2937,15 @@ mod tests {
         assert_eq!(reordered[1].id, "chapter_001");
         assert_eq!(reordered[1].order, 2);
 
+        tauri::async_runtime::block_on(save_chapter_content(
+            project_path.clone(),
+            ch2.id.clone(),
+            "第二章草稿".to_string(),
+        ))
+        .expect("save_chapter_content 2");
         tauri::async_runtime::block_on(delete_chapter(project_path.clone(), ch2.id.clone()))
             .expect("delete_chapter");
+        assert!(backed_up("chapters/chapter_002.txt").contains(&"第二章草稿".to_string()));
 
         let chapters2 =
             tauri::async_runtime::block_on(list_chapters(project_path.clone())).expect("list 2");
@@ -973,16 +2954,475 @@ mod tests {
         assert_eq!(chapters2[0].order, 1);
     }
 
+    #[test]
+    fn chapter_status_defaults_to_draft_for_old_indexes() {
+        let temp = TempDir::new("creatorai-v2-chapter-status");
+        let project_root = temp.path.join("MyNovel");
+        let project_path = project_root.to_string_lossy().to_string();
+        project::create_project_sync(project_path.clone(), "我的小说".to_string(), "main")
+            .expect("create_project");
+        // An index.json written before chapters had a status.
+        fs::write(
+            project_root.join("chapters/index.json"),
+            r#"{ "chapters": [
+  { "id": "chapter_001", "title": "第一章", "order": 1, "created": 1, "updated": 1, "wordCount": 0 }
+], "nextId": 2 }
+"#,
+        )
+        .unwrap();
+        fs::write(project_root.join("chapters/chapter_001.txt"), "").unwrap();
+        project::open_project_sync(project_path.clone(), false, false, "main").expect("open_project");
+
+        let chapters = tauri::async_runtime::block_on(list_chapters(project_path.clone())).expect("list");
+        assert_eq!(chapters[0].status, crate::project::ChapterStatus::Draft);
+
+        let updated = tauri::async_runtime::block_on(set_chapter_status(
+            project_path.clone(),
+            "chapter_001".to_string(),
+            crate::project::ChapterStatus::Done,
+        ))
+        .expect("set_chapter_status");
+        assert!(updated.updated > 1);
+        let index = fs::read_to_string(project_root.join("chapters/index.json")).unwrap();
+        assert!(index.contains(r#""status": "done""#), "{index}");
+        let chapters = tauri::async_runtime::block_on(list_chapters(project_path.clone())).expect("list");
+        assert_eq!(chapters[0].status, crate::project::ChapterStatus::Done);
+
+        assert!(tauri::async_runtime::block_on(set_chapter_status(
+            project_path.clone(),
+            "chapter_009".to_string(),
+            crate::project::ChapterStatus::Revising,
+        ))
+        .is_err());
+
+        // A status from a newer version still loads, but can't be written back as-is.
+        let index = index.replace(r#""status": "done""#, r#""status": "archived""#);
+        fs::write(project_root.join("chapters/index.json"), &index).unwrap();
+        let chapters = tauri::async_runtime::block_on(list_chapters(project_path.clone())).expect("list");
+        assert_eq!(chapters[0].status, crate::project::ChapterStatus::Unknown);
+        assert_eq!(chapters[0].title, "第一章");
+        assert!(tauri::async_runtime::block_on(set_chapter_status(
+            project_path,
+            "chapter_001".to_string(),
+            crate::project::ChapterStatus::Unknown,
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn get_chapters_content_reports_missing_and_unreadable_files_per_chapter() {
+        let temp = TempDir::new("creatorai-v2-chapters-batch");
+        let project_root = temp.path.join("MyNovel");
+        let project_path = project_root.to_string_lossy().to_string();
+        project::create_project_sync(project_path.clone(), "我的小说".to_string(), "main")
+            .expect("create_project");
+        for (title, content) in [("一", "第一章 开端"), ("二", "第二章"), ("三", "第三章 结尾")] {
+            let meta = tauri::async_runtime::block_on(create_chapter(project_path.clone(), title.to_string()))
+                .expect("create_chapter");
+            tauri::async_runtime::block_on(save_chapter_content(
+                project_path.clone(),
+                meta.id,
+                content.to_string(),
+            ))
+            .expect("save_chapter_content");
+        }
+        fs::remove_file(project_root.join("chapters/chapter_002.txt")).unwrap();
+
+        let ids = ["chapter_003", "chapter_002", "chapter_001"].map(str::to_string).to_vec();
+        let batch = tauri::async_runtime::block_on(get_chapters_content(project_path.clone(), ids))
+            .expect("get_chapters_content");
+        assert!(!batch.truncated);
+        let got: Vec<(&str, &str, u32, bool)> = batch
+            .chapters
+            .iter()
+            .map(|c| (c.id.as_str(), c.content.as_str(), c.word_count, c.missing))
+            .collect();
+        assert_eq!(
+            got,
+            [
+                ("chapter_003", "第三章 结尾", 5, false),
+                ("chapter_002", "", 0, true),
+                ("chapter_001", "第一章 开端", 5, false),
+            ]
+        );
+        assert!(batch.chapters.iter().all(|c| c.error.is_none()));
+
+        // A chapter that can't be read is reported on its own; the rest still come back.
+        fs::write(project_root.join("chapters/chapter_003.txt"), [0xff, 0xfe, 0xfd]).unwrap();
+        let ids = ["chapter_003", "chapter_001"].map(str::to_string).to_vec();
+        let batch = tauri::async_runtime::block_on(get_chapters_content(project_path.clone(), ids))
+            .expect("get_chapters_content with an unreadable chapter");
+        let error = batch.chapters[0].error.as_deref().expect("chapter_003 error");
+        assert!(error.contains("chapter_003"), "{error}");
+        assert_eq!(batch.chapters[0].content, "");
+        assert_eq!(batch.chapters[1].content, "第一章 开端");
+        assert!(batch.chapters[1].error.is_none());
+
+        let err = tauri::async_runtime::block_on(get_chapters_content(
+            project_path,
+            vec!["chapter_001".to_string(), "../secret".to_string()],
+        ))
+        .unwrap_err();
+        assert!(err.contains("Invalid chapter_id"), "{err}");
+    }
+
+    #[test]
+    fn chapter_save_reports_activity_log_failures_as_warnings() {
+        let temp = TempDir::new("creatorai-v2-save-warnings");
+        let project_root = temp.path.join("MyNovel");
+        let project_path = project_root.to_string_lossy().to_string();
+        project::create_project_sync(project_path.clone(), "我的小说".to_string(), "main")
+            .expect("create_project");
+        let chapter = tauri::async_runtime::block_on(create_chapter(project_path.clone(), "一".to_string()))
+            .expect("create_chapter");
+        // A directory where the log file belongs makes every append fail.
+        fs::create_dir_all(project_root.join(".creatorai/activity.jsonl")).unwrap();
+
+        let saved = tauri::async_runtime::block_on(save_chapter_content(
+            project_path.clone(),
+            chapter.id.clone(),
+            "正文".to_string(),
+        ))
+        .expect("save still succeeds");
+        assert_eq!(saved.meta.word_count, 2);
+        assert_eq!(saved.warnings.len(), 1, "{:?}", saved.warnings);
+        assert!(saved.warnings[0].contains("Activity log not updated"));
+
+        let summary = save_summary_entry(project_path, chapter.id, "摘要".to_string()).expect("save summary");
+        assert_eq!(summary.warnings.len(), 1, "{:?}", summary.warnings);
+    }
+
+    #[test]
+    fn chapter_saves_are_logged_to_writing_stats() {
+        let temp = TempDir::new("creatorai-v2-writing-stats");
+        let project_root = temp.path.join("MyNovel");
+        let project_path = project_root.to_string_lossy().to_string();
+        project::create_project_sync(project_path.clone(), "我的小说".to_string(), "main")
+            .expect("create_project");
+        let chapter = tauri::async_runtime::block_on(create_chapter(project_path.clone(), "一".to_string()))
+            .expect("create_chapter");
+        for content in ["一二三四五", "一二三四五", "一二"] {
+            tauri::async_runtime::block_on(save_chapter_content(
+                project_path.clone(),
+                chapter.id.clone(),
+                content.to_string(),
+            ))
+            .expect("save_chapter_content");
+        }
+
+        let log = fs::read_to_string(project_root.join(".creatorai/stats.jsonl")).unwrap();
+        let entries: Vec<stats::StatsEntry> =
+            log.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        let got: Vec<(i64, u32)> = entries.iter().map(|e| (e.delta, e.new_total)).collect();
+        assert_eq!(got, [(5, 5), (-3, 2)]);
+
+        let today = tauri::async_runtime::block_on(get_writing_stats(project_path, 1)).expect("stats");
+        assert_eq!(today.len(), 1);
+        assert_eq!(today[0].delta, 2);
+    }
+
+    #[test]
+    fn chapter_notes_follow_their_chapter() {
+        let temp = TempDir::new("creatorai-v2-chapter-notes");
+        let project_root = temp.path.join("MyNovel");
+        let project_path = project_root.to_string_lossy().to_string();
+        project::create_project_sync(project_path.clone(), "我的小说".to_string(), "main")
+            .expect("create_project");
+        let ch1 = tauri::async_runtime::block_on(create_chapter(project_path.clone(), "一".to_string()))
+            .expect("create_chapter");
+        let ch2 = tauri::async_runtime::block_on(create_chapter(project_path.clone(), "二".to_string()))
+            .expect("create_chapter");
+        let ch3 = tauri::async_runtime::block_on(create_chapter(project_path.clone(), "三".to_string()))
+            .expect("create_chapter");
+        let notes = |id: &str| {
+            tauri::async_runtime::block_on(get_chapter_notes(project_path.clone(), id.to_string()))
+        };
+        let save_notes = |id: &str, text: &str| {
+            tauri::async_runtime::block_on(save_chapter_notes(
+                project_path.clone(),
+                id.to_string(),
+                text.to_string(),
+            ))
+        };
+
+        assert_eq!(notes(&ch1.id).expect("notes"), "");
+        save_notes(&ch1.id, "伏笔：信").expect("save notes");
+        save_notes(&ch2.id, "人物：阿青").expect("save notes");
+        save_notes(&ch3.id, "结局待定").expect("save notes");
+        assert_eq!(notes(&ch1.id).expect("notes"), "伏笔：信");
+        assert!(project_root.join("chapters/chapter_001.notes.md").exists());
+        assert!(notes("chapter_009").is_err());
+
+        // Notes are not chapters: the index is untouched.
+        let chapters = tauri::async_runtime::block_on(list_chapters(project_path.clone())).expect("list");
+        assert_eq!(chapters.len(), 3);
+        assert!(chapters.iter().all(|c| c.word_count == 0));
+
+        tauri::async_runtime::block_on(merge_chapters(
+            project_path.clone(),
+            ch1.id.clone(),
+            ch2.id.clone(),
+            None,
+            None,
+        ))
+        .expect("merge_chapters");
+        assert_eq!(notes(&ch1.id).expect("notes"), "伏笔：信\n\n人物：阿青");
+        assert!(!project_root.join("chapters/chapter_002.notes.md").exists());
+
+        tauri::async_runtime::block_on(delete_chapter(project_path.clone(), ch3.id.clone()))
+            .expect("delete_chapter");
+        assert!(!project_root.join("chapters/chapter_003.notes.md").exists());
+        let backups = write_protection::list_backups(&project_root, Some("chapters/chapter_003.notes.md")).unwrap();
+        assert_eq!(backups.len(), 1);
+        let trashed: Vec<String> = trash::list_trash(&project_root)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.original_path)
+            .collect();
+        assert!(trashed.contains(&"chapters/chapter_003.notes.md".to_string()));
+        assert!(trashed.contains(&"chapters/chapter_003.txt".to_string()));
+    }
+
+    #[test]
+    fn volumes_group_chapters_without_changing_the_flat_list() {
+        let temp = TempDir::new("creatorai-v2-volumes");
+        let project_root = temp.path.join("MyNovel");
+        let project_path = project_root.to_string_lossy().to_string();
+        project::create_project_sync(project_path.clone(), "我的小说".to_string(), "main")
+            .expect("create_project");
+        // An index.json written before volumes existed.
+        fs::write(
+            project_root.join("chapters/index.json"),
+            r#"{ "chapters": [
+  { "id": "chapter_001", "title": "一", "order": 1, "created": 1, "updated": 1, "wordCount": 0 },
+  { "id": "chapter_002", "title": "二", "order": 2, "created": 1, "updated": 1, "wordCount": 0 }
+], "nextId": 3 }
+"#,
+        )
+        .unwrap();
+        fs::write(project_root.join("chapters/chapter_001.txt"), "").unwrap();
+        fs::write(project_root.join("chapters/chapter_002.txt"), "").unwrap();
+        let chapters = tauri::async_runtime::block_on(list_chapters(project_path.clone())).expect("list");
+        assert!(chapters.iter().all(|c| c.volume_id.is_none()));
+        assert!(tauri::async_runtime::block_on(list_volumes(project_path.clone())).expect("volumes").is_empty());
+
+        let v1 = tauri::async_runtime::block_on(create_volume(project_path.clone(), "第一卷".to_string()))
+            .expect("create_volume");
+        let v2 = tauri::async_runtime::block_on(create_volume(project_path.clone(), "第二卷".to_string()))
+            .expect("create_volume");
+        assert_eq!((v1.id.as_str(), v1.order, v2.id.as_str(), v2.order), ("volume_001", 1, "volume_002", 2));
+        let assign = |chapter: &str, volume: Option<&str>| {
+            tauri::async_runtime::block_on(assign_chapter_volume(
+                project_path.clone(),
+                chapter.to_string(),
+                volume.map(str::to_string),
+            ))
+        };
+        assign("chapter_001", Some(&v1.id)).expect("assign");
+        assign("chapter_002", Some(&v2.id)).expect("assign");
+        assert!(assign("chapter_001", Some("volume_009")).is_err());
+        let renamed = tauri::async_runtime::block_on(rename_volume(
+            project_path.clone(),
+            v2.id.clone(),
+            "终卷".to_string(),
+        ))
+        .expect("rename_volume");
+        assert_eq!(renamed.title, "终卷");
+
+        let chapters = tauri::async_runtime::block_on(list_chapters(project_path.clone())).expect("list");
+        let got: Vec<(&str, Option<&str>)> =
+            chapters.iter().map(|c| (c.id.as_str(), c.volume_id.as_deref())).collect();
+        assert_eq!(got, [("chapter_001", Some("volume_001")), ("chapter_002", Some("volume_002"))]);
+
+        tauri::async_runtime::block_on(delete_volume(project_path.clone(), v1.id.clone())).expect("delete_volume");
+        let volumes = tauri::async_runtime::block_on(list_volumes(project_path.clone())).expect("volumes");
+        assert_eq!(volumes, vec![crate::project::Volume { id: v2.id.clone(), title: "终卷".to_string(), order: 1 }]);
+        let chapters = tauri::async_runtime::block_on(list_chapters(project_path.clone())).expect("list");
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[0].volume_id, None);
+        assert_eq!(chapters[1].volume_id.as_deref(), Some("volume_002"));
+
+        assign("chapter_002", None).expect("unassign");
+        tauri::async_runtime::block_on(delete_volume(project_path.clone(), v2.id)).expect("delete_volume");
+        // With no volumes left only the id counter remains, so deleted ids aren't reused.
+        let index = fs::read_to_string(project_root.join("chapters/index.json")).unwrap();
+        assert!(!index.contains("volumes") && !index.contains("volume_"), "{index}");
+        assert!(index.contains(r#""nextVolumeId": 3"#), "{index}");
+        let v3 = tauri::async_runtime::block_on(create_volume(project_path.clone(), "新卷".to_string()))
+            .expect("create_volume");
+        assert_eq!(v3.id, "volume_003");
+    }
+
+    #[test]
+    fn create_chapter_at_inserts_and_shifts_later_chapters() {
+        let temp = TempDir::new("creatorai-v2-chapter-insert");
+        let project_root = temp.path.join("MyNovel");
+        let project_path = project_root.to_string_lossy().to_string();
+        project::create_project_sync(project_path.clone(), "我的小说".to_string(), "main")
+            .expect("create_project");
+        let insert = |title: &str, insert_after: Option<&str>, position: Option<u32>| {
+            tauri::async_runtime::block_on(create_chapter_at(
+                project_path.clone(),
+                title.to_string(),
+                insert_after.map(str::to_string),
+                position,
+            ))
+        };
+        let titles = || -> Vec<(String, u32)> {
+            tauri::async_runtime::block_on(list_chapters(project_path.clone()))
+                .expect("list")
+                .into_iter()
+                .map(|c| (c.title, c.order))
+                .collect()
+        };
+
+        let one = insert("一", None, None).expect("append");
+        let two = insert("二", None, None).expect("append");
+        insert("序章", None, Some(1)).expect("insert at front");
+        insert("幕间", Some(&one.id), None).expect("insert in the middle");
+        let last = insert("尾声", Some(&two.id), None).expect("insert after the last");
+        assert_eq!(last.order, 5);
+        assert_eq!(
+            titles(),
+            [("序章", 1), ("一", 2), ("幕间", 3), ("二", 4), ("尾声", 5)].map(|(t, o)| (t.to_string(), o))
+        );
+
+        let err = insert("x", Some("chapter_999"), None).unwrap_err();
+        assert!(err.contains("not found"), "{err}");
+        assert!(insert("x", None, Some(0)).is_err());
+        assert!(insert("x", None, Some(7)).unwrap_err().contains("between 1 and 6"));
+        assert!(insert("x", Some(&one.id), Some(1)).is_err());
+        assert_eq!(titles().len(), 5);
+    }
+
+    #[test]
+    fn split_chapter_moves_trailing_lines_into_a_new_chapter() {
+        let temp = TempDir::new("creatorai-v2-chapter-split");
+        let project_root = temp.path.join("MyNovel");
+        let project_path = project_root.to_string_lossy().to_string();
+        project::create_project_sync(project_path.clone(), "我的小说".to_string(), "main")
+            .expect("create_project");
+        let create = |title: &str| {
+            tauri::async_runtime::block_on(create_chapter(project_path.clone(), title.to_string()))
+                .expect("create_chapter")
+        };
+        let (ch1, ch2) = (create("第一章"), create("第二章"));
+        tauri::async_runtime::block_on(save_chapter_content(
+            project_path.clone(),
+            ch1.id.clone(),
+            "清晨，雾很浓。\n她推开了门。\n夜里，雨停了。\n他没有回来。".to_string(),
+        ))
+        .expect("save_chapter_content");
+
+        let split = |line: u32| {
+            tauri::async_runtime::block_on(split_chapter(
+                project_path.clone(),
+                ch1.id.clone(),
+                line,
+                "第一章（下）".to_string(),
+            ))
+        };
+        assert!(split(1).is_err());
+        assert!(split(5).is_err());
+
+        let result = split(3).expect("split_chapter");
+        assert_eq!(result.original.word_count, 13);
+        assert_eq!(result.created.word_count, 13);
+        assert_eq!(
+            fs::read_to_string(project_root.join("chapters/chapter_001.txt")).unwrap(),
+            "清晨，雾很浓。\n她推开了门。\n"
+        );
+        assert_eq!(
+            fs::read_to_string(project_root.join(format!("chapters/{}.txt", result.created.id))).unwrap(),
+            "夜里，雨停了。\n他没有回来。"
+        );
+
+        let chapters = tauri::async_runtime::block_on(list_chapters(project_path.clone())).expect("list");
+        let order: Vec<(&str, u32)> = chapters.iter().map(|c| (c.id.as_str(), c.order)).collect();
+        assert_eq!(order, [(ch1.id.as_str(), 1), (result.created.id.as_str(), 2), (ch2.id.as_str(), 3)]);
+        assert_eq!(chapters[1].title, "第一章（下）");
+
+        fs::remove_file(project_root.join("chapters/chapter_002.txt")).unwrap();
+        let err = tauri::async_runtime::block_on(split_chapter(
+            project_path,
+            ch2.id.clone(),
+            2,
+            "x".to_string(),
+        ))
+        .unwrap_err();
+        assert!(err.contains("does not exist"), "{err}");
+    }
+
+    #[test]
+    fn merge_chapters_joins_adjacent_chapters_and_renumbers() {
+        let temp = TempDir::new("creatorai-v2-chapter-merge");
+        let project_root = temp.path.join("MyNovel");
+        let project_path = project_root.to_string_lossy().to_string();
+        project::create_project_sync(project_path.clone(), "我的小说".to_string(), "main")
+            .expect("create_project");
+        let create = |title: &str, content: &str| {
+            let meta = tauri::async_runtime::block_on(create_chapter(project_path.clone(), title.to_string()))
+                .expect("create_chapter");
+            tauri::async_runtime::block_on(save_chapter_content(
+                project_path.clone(),
+                meta.id.clone(),
+                content.to_string(),
+            ))
+            .expect("save_chapter_content")
+            .meta
+        };
+        let (ch1, ch2, ch3, ch4) = (create("一", ""), create("二", "第二章"), create("三", "第三章"), create("四", "尾声"));
+        let merge = |first: &str, second: &str, separator: Option<&str>, force: Option<bool>| {
+            tauri::async_runtime::block_on(merge_chapters(
+                project_path.clone(),
+                first.to_string(),
+                second.to_string(),
+                separator.map(str::to_string),
+                force,
+            ))
+        };
+
+        let merged = merge(&ch1.id, &ch2.id, None, None).expect("merge into empty chapter");
+        assert_eq!(fs::read_to_string(project_root.join("chapters/chapter_001.txt")).unwrap(), "第二章");
+        assert_eq!((merged.word_count, merged.order), (3, 1));
+        assert!(!project_root.join("chapters/chapter_002.txt").exists());
+
+        let err = merge(&ch1.id, &ch4.id, None, None).unwrap_err();
+        assert!(err.contains("not adjacent"), "{err}");
+        let merged = merge(&ch1.id, &ch3.id, Some("\n***\n"), None).expect("merge with separator");
+        assert_eq!(
+            fs::read_to_string(project_root.join("chapters/chapter_001.txt")).unwrap(),
+            "第二章\n***\n第三章"
+        );
+        assert_eq!(merged.word_count, 9);
+        let chapters = tauri::async_runtime::block_on(list_chapters(project_path.clone())).expect("list");
+        let order: Vec<(&str, u32)> = chapters.iter().map(|c| (c.id.as_str(), c.order)).collect();
+        assert_eq!(order, [(ch1.id.as_str(), 1), (ch4.id.as_str(), 2)]);
+        let backups = write_protection::list_backups(&project_root, Some("chapters/chapter_003.txt")).unwrap();
+        assert_eq!(backups.len(), 1);
+
+        fs::remove_file(project_root.join("chapters/chapter_004.txt")).unwrap();
+        let err = merge(&ch1.id, &ch4.id, None, None).unwrap_err();
+        assert!(err.contains("does not exist"), "{err}");
+        assert_eq!(
+            fs::read_to_string(project_root.join("chapters/chapter_001.txt")).unwrap(),
+            "第二章\n***\n第三章"
+        );
+        let chapters = tauri::async_runtime::block_on(list_chapters(project_path)).expect("list");
+        assert_eq!(chapters.len(), 2);
+    }
+
     #[test]
     fn chapter_save_persists_latest_content_across_multiple_writes() {
         let temp = TempDir::new("creatorai-v2-chapter-save");
         let project_root = temp.path.join("MyNovel");
         let project_path = project_root.to_string_lossy().to_string();
 
-        tauri::async_runtime::block_on(create_project(
+        project::create_project_sync(
             project_path.clone(),
             "Test Novel".to_string(),
-        ))
+            "main",
+        )
         .expect("create_project");
 
         let chapter = tauri::async_runtime::block_on(create_chapter(
@@ -1021,6 +3461,127 @@ mod tests {
         assert_eq!(saved_meta.word_count, "first draft\nsecond line\nfinal paragraph".chars().filter(|c| !c.is_whitespace()).count() as u32);
     }
 
+    #[test]
+    fn corrupt_chapter_index_is_recovered_from_backup() {
+        let temp = TempDir::new("creatorai-v2-index-recovery");
+        let project_root = temp.path.join("MyNovel");
+        let project_path = project_root.to_string_lossy().to_string();
+
+        project::create_project_sync(
+            project_path.clone(),
+            "Test Novel".to_string(),
+            "main",
+        )
+        .expect("create_project");
+        let ch1 = tauri::async_runtime::block_on(create_chapter(
+            project_path.clone(),
+            "Chapter 1".to_string(),
+        ))
+        .expect("create_chapter 1");
+        let ch2 = tauri::async_runtime::block_on(create_chapter(
+            project_path.clone(),
+            "Chapter 2".to_string(),
+        ))
+        .expect("create_chapter 2");
+        tauri::async_runtime::block_on(save_chapter_content(
+            project_path.clone(),
+            ch2.id.clone(),
+            "content".to_string(),
+        ))
+        .expect("save_chapter_content");
+
+        fs::write(project_root.join("chapters/index.json"), "{ \"chapters\": [").expect("corrupt");
+
+        let err = tauri::async_runtime::block_on(list_chapters(project_path.clone()))
+            .expect_err("corrupt index should fail to parse");
+        assert!(err.contains("recover_chapter_index"));
+
+        let opened = project::open_project_sync(project_path.clone(), true, false, "main")
+            .expect("open_project with auto_recover");
+        assert_eq!(opened.name, "Test Novel");
+
+        let chapters =
+            tauri::async_runtime::block_on(list_chapters(project_path.clone())).expect("list");
+        let ids: Vec<&str> = chapters.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec![ch1.id.as_str(), ch2.id.as_str()]);
+        assert_eq!(chapters[0].title, "Chapter 1");
+        assert_eq!(chapters[1].title, "Chapter 2");
+    }
+
+    #[test]
+    fn chapter_index_is_rebuilt_from_chapter_files() {
+        let temp = TempDir::new("creatorai-v2-index-rebuild");
+        let project_root = temp.path.join("MyNovel");
+        let project_path = project_root.to_string_lossy().to_string();
+        project::create_project_sync(project_path.clone(), "我的小说".to_string(), "main")
+            .expect("create_project");
+        for (title, content) in [("开端", "\n  雨夜  \n正文"), ("中段", ""), ("结局", "尾声")] {
+            let meta = tauri::async_runtime::block_on(create_chapter(project_path.clone(), title.to_string()))
+                .expect("create_chapter");
+            tauri::async_runtime::block_on(save_chapter_content(
+                project_path.clone(),
+                meta.id,
+                content.to_string(),
+            ))
+            .expect("save_chapter_content");
+        }
+        tauri::async_runtime::block_on(delete_chapter(project_path.clone(), "chapter_003".to_string()))
+            .expect("delete_chapter");
+        // A file the index never knew about.
+        fs::write(project_root.join("chapters/chapter_010.txt"), "第十章").unwrap();
+        let index_path = project_root.join("chapters/index.json");
+
+        // Deleted index: open_project refuses unless asked to recover.
+        fs::remove_file(&index_path).unwrap();
+        let err = project::open_project_sync(project_path.clone(), false, false, "main").unwrap_err();
+        assert!(err.contains("rebuild_chapter_index"), "{err}");
+        let err = tauri::async_runtime::block_on(list_chapters(project_path.clone())).unwrap_err();
+        assert!(err.contains("rebuild_chapter_index"), "{err}");
+
+        let chapters =
+            tauri::async_runtime::block_on(rebuild_chapter_index(project_path.clone())).expect("rebuild");
+        let got: Vec<(&str, &str, u32, u32)> = chapters
+            .iter()
+            .map(|c| (c.id.as_str(), c.title.as_str(), c.order, c.word_count))
+            .collect();
+        assert_eq!(
+            got,
+            [
+                ("chapter_001", "雨夜", 1, 4),
+                ("chapter_002", "chapter_002", 2, 0),
+                ("chapter_010", "第十章", 3, 3),
+            ]
+        );
+        let index: crate::project::ChapterIndex =
+            serde_json::from_str(&fs::read_to_string(&index_path).unwrap()).unwrap();
+        assert_eq!(index.next_id, 11);
+        project::open_project_sync(project_path.clone(), false, false, "main").expect("open_project");
+
+        // Hand-edited into invalid JSON: the broken file is backed up, then replaced.
+        let before = write_protection::list_backups(&project_root, Some("chapters/index.json"))
+            .unwrap()
+            .first()
+            .map_or(0, |f| f.backups.len());
+        fs::write(&index_path, "{ \"chapters\": [ oops").unwrap();
+        let chapters =
+            tauri::async_runtime::block_on(rebuild_chapter_index(project_path.clone())).expect("rebuild");
+        assert_eq!(chapters.len(), 3);
+        let backups = write_protection::list_backups(&project_root, Some("chapters/index.json")).unwrap();
+        assert_eq!(backups[0].backups.len(), before + 1);
+        let newest = fs::read_to_string(project_root.join(&backups[0].backups[0].backup_path)).unwrap();
+        assert_eq!(newest, "{ \"chapters\": [ oops");
+
+        // A readable index keeps its titles.
+        tauri::async_runtime::block_on(rename_chapter(
+            project_path.clone(),
+            "chapter_002".to_string(),
+            "中段".to_string(),
+        ))
+        .expect("rename_chapter");
+        let chapters = tauri::async_runtime::block_on(rebuild_chapter_index(project_path)).expect("rebuild");
+        assert_eq!(chapters[1].title, "中段");
+    }
+
     #[test]
     fn session_storage_smoke_test() {
         use uuid::Uuid;
@@ -1029,10 +3590,11 @@ mod tests {
         let project_root = temp.path.join("MyNovel");
         let project_path = project_root.to_string_lossy().to_string();
 
-        tauri::async_runtime::block_on(create_project(
+        project::create_project_sync(
             project_path.clone(),
             "我的小说".to_string(),
-        ))
+            "main",
+        )
         .expect("create_project");
 
         let sessions = tauri::async_runtime::block_on(list_sessions(project_path.clone()))
@@ -1138,4 +3700,102 @@ mod tests {
             "sessions/index.json should exist"
         );
     }
+
+    #[test]
+    fn session_preset_override_falls_back_when_preset_removed() {
+        let temp = TempDir::new("creatorai-session-preset");
+        let project_path = temp.path.join("Novel").to_string_lossy().to_string();
+        project::create_project_sync(project_path.clone(), "小说".to_string(), "main")
+            .expect("create_project");
+
+        let payload = tauri::async_runtime::block_on(get_presets(project_path.clone()))
+            .expect("get_presets");
+        let override_id = payload
+            .presets
+            .iter()
+            .find(|p| p.id != payload.active_preset_id)
+            .expect("a second preset")
+            .id
+            .clone();
+
+        let session = tauri::async_runtime::block_on(create_session(
+            project_path.clone(),
+            "讨论：宣传文案".to_string(),
+            session::SessionMode::Discussion,
+            None,
+        ))
+        .expect("create_session");
+
+        let missing = tauri::async_runtime::block_on(set_session_preset(
+            project_path.clone(),
+            session.id.clone(),
+            Some("no-such-preset".to_string()),
+        ));
+        assert!(missing.is_err());
+
+        tauri::async_runtime::block_on(set_session_preset(
+            project_path.clone(),
+            session.id.clone(),
+            Some(override_id.clone()),
+        ))
+        .expect("set_session_preset");
+        let effective = tauri::async_runtime::block_on(get_effective_preset(
+            project_path.clone(),
+            session.id.clone(),
+        ))
+        .expect("get_effective_preset");
+        assert_eq!(effective.id, override_id);
+
+        let remaining = payload
+            .presets
+            .iter()
+            .filter(|p| p.id != override_id)
+            .cloned()
+            .collect();
+        let report = tauri::async_runtime::block_on(save_presets(
+            project_path.clone(),
+            remaining,
+            payload.active_preset_id.clone(),
+            None,
+        ))
+        .expect("save_presets");
+        assert!(report.saved);
+
+        let sessions = tauri::async_runtime::block_on(list_sessions(project_path.clone()))
+            .expect("list_sessions");
+        assert_eq!(sessions[0].preset_id, None);
+        let effective = tauri::async_runtime::block_on(get_effective_preset(
+            project_path.clone(),
+            session.id.clone(),
+        ))
+        .expect("get_effective_preset after removal");
+        assert_eq!(effective.id, payload.active_preset_id);
+    }
+
+    #[test]
+    fn api_key_errors_are_classified_by_status_and_error_code() {
+        let http = |status: u16| ai_bridge::EngineError {
+            status: Some(status),
+            .."Unauthorized: this message mentions 403 and a timeout".into()
+        };
+        assert_eq!(classify_api_key_error(&http(401), false), ApiKeyStatus::InvalidKey);
+        assert_eq!(classify_api_key_error(&http(403), false), ApiKeyStatus::NoModelAccess);
+        assert_eq!(classify_api_key_error(&http(404), false), ApiKeyStatus::Error);
+        assert_eq!(classify_api_key_error(&http(404), true), ApiKeyStatus::NoModelAccess);
+        assert_eq!(classify_api_key_error(&http(503), true), ApiKeyStatus::Unreachable);
+        assert_eq!(classify_api_key_error(&http(500), true), ApiKeyStatus::Error);
+
+        let refused = ai_bridge::EngineError {
+            code: Some("ECONNREFUSED".to_string()),
+            .."fetch failed".into()
+        };
+        assert_eq!(classify_api_key_error(&refused, false), ApiKeyStatus::Unreachable);
+        let timed_out = ai_bridge::EngineError {
+            timed_out: true,
+            .."Request timed out after 15s".into()
+        };
+        assert_eq!(classify_api_key_error(&timed_out, false), ApiKeyStatus::Unreachable);
+        let bare = ai_bridge::EngineError::from("401 unauthorized invalid api key");
+        assert_eq!(classify_api_key_error(&bare, false), ApiKeyStatus::Error);
+    }
 }
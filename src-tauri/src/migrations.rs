@@ -0,0 +1,152 @@
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+use crate::security::validate_path;
+use crate::write_protection;
+
+/// Project format version written by this build of the app.
+pub const CURRENT_PROJECT_VERSION: &str = "1.0";
+
+/// A single upgrade step from `from` to `to`.
+///
+/// Steps must write every file they touch through `write_protection` so the previous
+/// content ends up under `.backup/`.
+pub struct Migration {
+    pub from: &'static str,
+    pub to: &'static str,
+    pub run: fn(&Path) -> Result<(), String>,
+}
+
+/// Ordered upgrade ladder. Each entry's `from` must equal the previous entry's `to`.
+const MIGRATIONS: &[Migration] = &[];
+
+fn parse_version(raw: &str) -> Result<(u32, u32), String> {
+    let trimmed = raw.trim();
+    let (major, minor) = trimmed.split_once('.').unwrap_or((trimmed, "0"));
+    let major = major
+        .parse::<u32>()
+        .map_err(|_| format!("Invalid project version: '{raw}'"))?;
+    let minor = minor
+        .parse::<u32>()
+        .map_err(|_| format!("Invalid project version: '{raw}'"))?;
+    Ok((major, minor))
+}
+
+fn read_config_value(project_root: &Path) -> Result<Value, String> {
+    let cfg_path = validate_path(project_root, ".creatorai/config.json")?;
+    let bytes = fs::read(&cfg_path).map_err(|e| format!("Failed to read config.json: {e}"))?;
+    serde_json::from_slice::<Value>(&bytes).map_err(|e| format!("Failed to parse config.json: {e}"))
+}
+
+fn write_version(project_root: &Path, version: &str) -> Result<(), String> {
+    // Re-read so changes made to config.json by the step itself are preserved.
+    let mut config = read_config_value(project_root)?;
+    config["version"] = Value::String(version.to_string());
+    let cfg_path = validate_path(project_root, ".creatorai/config.json")?;
+    let content =
+        serde_json::to_string_pretty(&config).map_err(|e| format!("Serialize JSON failed: {e}"))?;
+    write_protection::write_string_with_backup(project_root, &cfg_path, &format!("{content}\n"))?;
+    Ok(())
+}
+
+fn run_migrations(project_root: &Path, steps: &[Migration], target: &str) -> Result<bool, String> {
+    let project_root = project_root
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path: {e}"))?;
+
+    let config = read_config_value(&project_root)?;
+    let mut version = config
+        .get("version")
+        .and_then(|v| v.as_str())
+        .unwrap_or("1.0")
+        .to_string();
+
+    let target_parsed = parse_version(target)?;
+    if parse_version(&version)? > target_parsed {
+        return Err(format!(
+            "该项目由更新版本的应用创建（项目版本 {version}，当前支持 {target}），请升级应用后再打开"
+        ));
+    }
+
+    let mut migrated = false;
+    while parse_version(&version)? < target_parsed {
+        let Some(step) = steps.iter().find(|m| m.from == version) else {
+            return Err(format!("No migration path from project version {version} to {target}"));
+        };
+        (step.run)(&project_root)
+            .map_err(|e| format!("Project migration {} -> {} failed: {e}", step.from, step.to))?;
+        write_version(&project_root, step.to)?;
+        version = step.to.to_string();
+        migrated = true;
+    }
+
+    Ok(migrated)
+}
+
+/// Brings the project at `project_root` up to `CURRENT_PROJECT_VERSION`.
+/// Returns `true` when at least one migration step ran.
+pub fn migrate_project(project_root: &Path) -> Result<bool, String> {
+    run_migrations(project_root, MIGRATIONS, CURRENT_PROJECT_VERSION)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn create_project_with_version(root: &Path, version: &str) {
+        fs::create_dir_all(root.join(".creatorai")).unwrap();
+        fs::create_dir_all(root.join("chapters")).unwrap();
+        fs::write(
+            root.join(".creatorai/config.json"),
+            format!("{{ \"name\": \"Test\", \"version\": \"{version}\", \"presets\": [] }}\n"),
+        )
+        .unwrap();
+        fs::write(root.join("chapters/index.json"), "{ \"chapters\": [], \"nextId\": 1 }\n").unwrap();
+    }
+
+    fn write_marker(project_root: &Path) -> Result<(), String> {
+        let path = validate_path(project_root, "chapters/index.json")?;
+        write_protection::write_string_with_backup(
+            project_root,
+            &path,
+            "{ \"chapters\": [], \"nextId\": 7 }\n",
+        )?;
+        Ok(())
+    }
+
+    const TEST_STEPS: &[Migration] = &[Migration {
+        from: "1.0",
+        to: "1.1",
+        run: write_marker,
+    }];
+
+    #[test]
+    fn runs_pending_steps_and_bumps_version() {
+        let temp = TempDir::new("creatorai-v2-migrations");
+        create_project_with_version(&temp.path, "1.0");
+
+        let migrated = run_migrations(&temp.path, TEST_STEPS, "1.1").expect("migrate");
+        assert!(migrated);
+
+        let config = read_config_value(&temp.path.canonicalize().unwrap()).unwrap();
+        assert_eq!(config["version"], "1.1");
+        assert!(config.get("presets").is_some(), "unknown keys are preserved");
+        let index = fs::read_to_string(temp.path.join("chapters/index.json")).unwrap();
+        assert!(index.contains("\"nextId\": 7"));
+        assert!(temp.path.join(".backup").exists());
+
+        let again = run_migrations(&temp.path, TEST_STEPS, "1.1").expect("migrate again");
+        assert!(!again);
+    }
+
+    #[test]
+    fn newer_project_version_is_rejected() {
+        let temp = TempDir::new("creatorai-v2-migrations-newer");
+        create_project_with_version(&temp.path, "9.0");
+
+        let err = migrate_project(&temp.path).expect_err("newer version should fail");
+        assert!(err.contains("请升级应用"));
+    }
+}
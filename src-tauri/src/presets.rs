@@ -1,11 +1,13 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::{Mutex, OnceLock};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::security::validate_path;
+use crate::write_protection;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -18,6 +20,17 @@ pub struct WritingPreset {
     pub rules: Vec<String>,
     #[serde(rename = "customPrompt")]
     pub custom_prompt: String,
+    /// Words the text must not contain.
+    #[serde(default)]
+    pub banned_words: Vec<String>,
+    /// `(wrong, correct)` spellings.
+    #[serde(default)]
+    pub glossary: Vec<(String, String)>,
+    /// Unix seconds of the last generation that used this preset.
+    #[serde(default)]
+    pub last_used_at: Option<u64>,
+    #[serde(default)]
+    pub use_count: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -35,6 +48,38 @@ pub struct PresetsPayload {
     pub active_preset_id: String,
 }
 
+/// Longest accepted rule, in characters.
+const MAX_RULE_CHARS: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueSeverity {
+    /// Always blocks the save.
+    Error,
+    /// Blocks the save only in strict mode; otherwise `normalize` resolves it.
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetIssue {
+    pub severity: IssueSeverity,
+    /// Index of the offending preset in the submitted list.
+    pub index: Option<usize>,
+    pub field: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavePresetsReport {
+    pub saved: bool,
+    pub issues: Vec<PresetIssue>,
+    /// What is persisted now: the normalized input when saved, the previous state otherwise.
+    pub presets: Vec<WritingPreset>,
+    pub active_preset_id: String,
+}
+
 static PRESETS_FS_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
 
 fn fs_lock() -> &'static Mutex<()> {
@@ -89,6 +134,10 @@ fn builtin_presets() -> Vec<WritingPreset> {
                 "段落之间保持自然过渡，不要跳剪式断层。".to_string(),
             ],
             custom_prompt: "默认追求稳健、自然、可持续连载的正文写法。".to_string(),
+            banned_words: Vec::new(),
+            glossary: Vec::new(),
+            last_used_at: None,
+            use_count: 0,
         },
         WritingPreset {
             id: "tight-pacing".to_string(),
@@ -106,6 +155,10 @@ fn builtin_presets() -> Vec<WritingPreset> {
                 "结尾尽量留下下一步张力。".to_string(),
             ],
             custom_prompt: "适合剧情推进、追逐、谈判、危机处理这类需要节奏的章节。".to_string(),
+            banned_words: Vec::new(),
+            glossary: Vec::new(),
+            last_used_at: None,
+            use_count: 0,
         },
         WritingPreset {
             id: "lyrical-detail".to_string(),
@@ -123,6 +176,10 @@ fn builtin_presets() -> Vec<WritingPreset> {
                 "句子可以稍微舒展，但仍要保持清晰。".to_string(),
             ],
             custom_prompt: "适合情感递进、关系升温、氛围场景和偏文学化段落。".to_string(),
+            banned_words: Vec::new(),
+            glossary: Vec::new(),
+            last_used_at: None,
+            use_count: 0,
         },
         WritingPreset {
             id: "cold-suspense".to_string(),
@@ -140,6 +197,10 @@ fn builtin_presets() -> Vec<WritingPreset> {
                 "避免角色突然话多，保持克制和压迫感。".to_string(),
             ],
             custom_prompt: "适合悬疑、调查、危险接近、人物互相试探的章节。".to_string(),
+            banned_words: Vec::new(),
+            glossary: Vec::new(),
+            last_used_at: None,
+            use_count: 0,
         },
         WritingPreset {
             id: "light-comedy".to_string(),
@@ -157,6 +218,10 @@ fn builtin_presets() -> Vec<WritingPreset> {
                 "轻快不等于轻飘，仍要保留剧情推进。".to_string(),
             ],
             custom_prompt: "适合轻喜、日常互动、反差萌和轻松群像场景。".to_string(),
+            banned_words: Vec::new(),
+            glossary: Vec::new(),
+            last_used_at: None,
+            use_count: 0,
         },
         WritingPreset {
             id: "webnovel-hook".to_string(),
@@ -174,10 +239,144 @@ fn builtin_presets() -> Vec<WritingPreset> {
                 "章末尽量留下钩子，吸引继续读。".to_string(),
             ],
             custom_prompt: "适合连载节奏、爽点兑现、反转和章末钩子设计。".to_string(),
+            banned_words: Vec::new(),
+            glossary: Vec::new(),
+            last_used_at: None,
+            use_count: 0,
+        },
+    ]
+}
+
+/// Genre presets users can copy into a project; they are not part of the seeded set.
+fn genre_presets() -> Vec<WritingPreset> {
+    vec![
+        WritingPreset {
+            id: "genre-urban".to_string(),
+            name: "都市".to_string(),
+            is_default: false,
+            style: WritingStyle {
+                tone: "真实贴地".to_string(),
+                perspective: "第三人称有限".to_string(),
+                tense: "过去式".to_string(),
+                description: "生活细节与人际张力".to_string(),
+            },
+            rules: vec![
+                "场景、职业和消费细节要贴近现实，不要悬浮。".to_string(),
+                "人物动机来自具体处境：钱、工作、家庭、关系。".to_string(),
+                "对话口语化，但避免流行梗堆砌。".to_string(),
+            ],
+            custom_prompt: "适合职场、家庭、情感和都市成长类故事。".to_string(),
+            banned_words: Vec::new(),
+            glossary: Vec::new(),
+            last_used_at: None,
+            use_count: 0,
+        },
+        WritingPreset {
+            id: "genre-suspense".to_string(),
+            name: "悬疑".to_string(),
+            is_default: false,
+            style: WritingStyle {
+                tone: "克制紧张".to_string(),
+                perspective: "第三人称有限".to_string(),
+                tense: "过去式".to_string(),
+                description: "线索清晰、信息分层".to_string(),
+            },
+            rules: vec![
+                "线索要公平地摆在读者面前，真相不能凭空出现。".to_string(),
+                "每章至少推进一个疑点，或抛出一个新的疑点。".to_string(),
+                "紧张感来自未知和时间压力，不靠形容词渲染。".to_string(),
+            ],
+            custom_prompt: "适合推理、刑侦、密室和心理悬疑类故事。".to_string(),
+            banned_words: Vec::new(),
+            glossary: Vec::new(),
+            last_used_at: None,
+            use_count: 0,
+        },
+        WritingPreset {
+            id: "genre-ancient".to_string(),
+            name: "古风".to_string(),
+            is_default: false,
+            style: WritingStyle {
+                tone: "典雅含蓄".to_string(),
+                perspective: "第三人称有限".to_string(),
+                tense: "过去式".to_string(),
+                description: "意境与礼俗细节".to_string(),
+            },
+            rules: vec![
+                "用词可以带古意，但要让现代读者读得顺。".to_string(),
+                "称谓、礼节和器物前后一致，避免现代词汇混入。".to_string(),
+                "情绪多借景物和举止表达，少直白剖白。".to_string(),
+            ],
+            custom_prompt: "适合古言、宫廷、江湖和架空历史类故事。".to_string(),
+            banned_words: Vec::new(),
+            glossary: Vec::new(),
+            last_used_at: None,
+            use_count: 0,
+        },
+        WritingPreset {
+            id: "genre-scifi".to_string(),
+            name: "科幻".to_string(),
+            is_default: false,
+            style: WritingStyle {
+                tone: "冷静理性".to_string(),
+                perspective: "第三人称有限".to_string(),
+                tense: "过去式".to_string(),
+                description: "设定严谨、画面开阔".to_string(),
+            },
+            rules: vec![
+                "设定规则一旦给出就要遵守，不为剧情临时破例。".to_string(),
+                "技术细节服务于人物选择，不写成说明书。".to_string(),
+                "用具体画面呈现尺度感，而不是堆砌数字。".to_string(),
+            ],
+            custom_prompt: "适合硬科幻、太空歌剧、赛博朋克和近未来类故事。".to_string(),
+            banned_words: Vec::new(),
+            glossary: Vec::new(),
+            last_used_at: None,
+            use_count: 0,
+        },
+        WritingPreset {
+            id: "genre-light-novel".to_string(),
+            name: "轻小说".to_string(),
+            is_default: false,
+            style: WritingStyle {
+                tone: "轻快活泼".to_string(),
+                perspective: "第一人称".to_string(),
+                tense: "过去式".to_string(),
+                description: "吐槽与角色互动".to_string(),
+            },
+            rules: vec![
+                "主角的内心吐槽要有个性，但不能打断剧情节奏。".to_string(),
+                "角色特征鲜明，第一次登场就让读者记住。".to_string(),
+                "段落短、对话多，保持阅读的轻快感。".to_string(),
+            ],
+            custom_prompt: "适合校园、异世界、日常和恋爱喜剧类故事。".to_string(),
+            banned_words: Vec::new(),
+            glossary: Vec::new(),
+            last_used_at: None,
+            use_count: 0,
         },
     ]
 }
 
+fn new_preset_id() -> String {
+    format!("preset_{}", uuid::Uuid::new_v4().simple())
+}
+
+/// `.creatorai/presets.json` layout version written by this build.
+const PRESETS_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PresetsFile {
+    schema_version: u32,
+    presets: Vec<WritingPreset>,
+    active_preset_id: String,
+}
+
+fn presets_path(project_root: &Path) -> Result<PathBuf, String> {
+    validate_path(project_root, ".creatorai/presets.json")
+}
+
 fn parse_presets(value: &Value) -> Result<Option<Vec<WritingPreset>>, String> {
     let Some(raw) = value.get("presets") else {
         return Ok(None);
@@ -201,11 +400,151 @@ fn write_config_json(project_root: &Path, json: &Value) -> Result<(), String> {
     let cfg_path = config_path(project_root)?;
     let content =
         serde_json::to_string_pretty(json).map_err(|e| format!("Serialize JSON failed: {e}"))?;
-    fs::write(&cfg_path, format!("{content}\n"))
-        .map_err(|e| format!("Failed to write config.json: {e}"))?;
+    write_protection::write_string_with_backup(project_root, &cfg_path, &format!("{content}\n"))?;
+    Ok(())
+}
+
+fn write_presets_file(project_root: &Path, file: &PresetsFile) -> Result<(), String> {
+    let path = presets_path(project_root)?;
+    let content =
+        serde_json::to_string_pretty(file).map_err(|e| format!("Serialize JSON failed: {e}"))?;
+    write_protection::write_string_with_backup(project_root, &path, &format!("{content}\n"))?;
     Ok(())
 }
 
+/// Moves presets embedded in `config.json` (older projects) into `presets.json`. The
+/// config keys are only removed once the copy is written; unreadable preset data is left
+/// in config.json untouched and the project starts over from the built-in presets.
+fn migrate_embedded_presets(project_root: &Path) -> Result<PresetsFile, String> {
+    let mut config = read_config_json(project_root)?;
+    let parsed = match parse_presets(&config) {
+        Ok(parsed) => Some(parsed),
+        Err(e) => {
+            eprintln!("[presets] Ignoring unreadable presets in config.json: {e}");
+            None
+        }
+    };
+    let active = config
+        .get("activePresetId")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    let (presets, active_preset_id) =
+        normalize(parsed.clone().flatten().unwrap_or_default(), active);
+    let file = PresetsFile {
+        schema_version: PRESETS_SCHEMA_VERSION,
+        presets,
+        active_preset_id,
+    };
+    write_presets_file(project_root, &file)?;
+
+    if parsed.is_some() {
+        if let Some(root) = config.as_object_mut() {
+            let had_presets = root.remove("presets").is_some();
+            let had_active = root.remove("activePresetId").is_some();
+            if had_presets || had_active {
+                write_config_json(project_root, &config)?;
+            }
+        }
+    }
+    Ok(file)
+}
+
+/// Reads `presets.json`, migrating embedded presets on first access.
+fn load_presets_file(project_root: &Path) -> Result<PresetsFile, String> {
+    let path = presets_path(project_root)?;
+    if !path.exists() {
+        return migrate_embedded_presets(project_root);
+    }
+    let bytes = fs::read(&path).map_err(|e| format!("Failed to read presets.json: {e}"))?;
+    let file = serde_json::from_slice::<PresetsFile>(&bytes)
+        .map_err(|e| format!("Failed to parse presets.json: {e}"))?;
+    if file.schema_version > PRESETS_SCHEMA_VERSION {
+        return Err(format!(
+            "presets.json schema {} is newer than supported schema {PRESETS_SCHEMA_VERSION}",
+            file.schema_version
+        ));
+    }
+    Ok(file)
+}
+
+fn canonical_project_root(project_path: String) -> Result<PathBuf, String> {
+    let project_root = PathBuf::from(project_path);
+    ensure_project_exists(&project_root)?;
+    project_root
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path: {e}"))
+}
+
+fn validate_presets(presets: &[WritingPreset], active_preset_id: &str) -> Vec<PresetIssue> {
+    let mut issues = Vec::new();
+    let mut issue = |severity, index, field: &str, message: String| {
+        issues.push(PresetIssue {
+            severity,
+            index,
+            field: field.to_string(),
+            message,
+        })
+    };
+
+    if presets.is_empty() {
+        issue(
+            IssueSeverity::Warning,
+            None,
+            "presets",
+            "预设列表为空，将恢复内置预设".to_string(),
+        );
+    }
+    let mut defaults = 0;
+    for (i, preset) in presets.iter().enumerate() {
+        if preset.id.trim().is_empty() {
+            issue(IssueSeverity::Error, Some(i), "id", format!("第 {} 个预设缺少 id", i + 1));
+        } else if presets[..i].iter().any(|p| p.id == preset.id) {
+            issue(IssueSeverity::Error, Some(i), "id", format!("预设 id「{}」重复", preset.id));
+        }
+
+        let name = preset.name.trim();
+        if name.is_empty() {
+            issue(IssueSeverity::Error, Some(i), "name", format!("第 {} 个预设名称为空", i + 1));
+        } else if presets[..i].iter().any(|p| p.name.trim() == name) {
+            issue(IssueSeverity::Warning, Some(i), "name", format!("预设名称「{name}」重复"));
+        }
+
+        for (r, rule) in preset.rules.iter().enumerate() {
+            let len = rule.chars().count();
+            if len > MAX_RULE_CHARS {
+                issue(
+                    IssueSeverity::Warning,
+                    Some(i),
+                    &format!("rules[{r}]"),
+                    format!("「{name}」的第 {} 条规则过长（{len} 字，上限 {MAX_RULE_CHARS}）", r + 1),
+                );
+            }
+        }
+
+        if preset.is_default {
+            defaults += 1;
+            if defaults > 1 {
+                issue(
+                    IssueSeverity::Warning,
+                    Some(i),
+                    "isDefault",
+                    format!("「{name}」也被标为默认，只保留第一个默认预设"),
+                );
+            }
+        }
+    }
+
+    if !presets.is_empty() && !presets.iter().any(|p| p.id == active_preset_id.trim()) {
+        issue(
+            IssueSeverity::Warning,
+            None,
+            "activePresetId",
+            format!("当前预设「{active_preset_id}」不存在，将改用默认预设"),
+        );
+    }
+    issues
+}
+
 fn normalize(presets: Vec<WritingPreset>, active: Option<String>) -> (Vec<WritingPreset>, String) {
     let mut presets = presets;
     if presets.is_empty() {
@@ -250,67 +589,803 @@ fn normalize(presets: Vec<WritingPreset>, active: Option<String>) -> (Vec<Writin
     (presets, resolved_active)
 }
 
-fn get_presets_sync(project_path: String) -> Result<PresetsPayload, String> {
+pub(crate) fn get_presets_sync(project_path: String) -> Result<PresetsPayload, String> {
     let _guard = fs_lock()
         .lock()
         .map_err(|_| "Failed to lock presets storage".to_string())?;
 
-    let project_root = PathBuf::from(project_path);
-    ensure_project_exists(&project_root)?;
+    let project_root = canonical_project_root(project_path)?;
+    let file = load_presets_file(&project_root)?;
+    let (presets, active) = normalize(file.presets.clone(), Some(file.active_preset_id.clone()));
 
-    let mut config = read_config_json(&project_root)?;
+    // Persist defaults / normalization back to presets.json.
+    if presets != file.presets
+        || active != file.active_preset_id
+        || file.schema_version != PRESETS_SCHEMA_VERSION
+    {
+        write_presets_file(
+            &project_root,
+            &PresetsFile {
+                schema_version: PRESETS_SCHEMA_VERSION,
+                presets: presets.clone(),
+                active_preset_id: active.clone(),
+            },
+        )?;
+    }
 
-    let parsed = parse_presets(&config)?;
-    let active = config
-        .get("activePresetId")
-        .and_then(|v| v.as_str())
-        .map(|s| s.to_string());
+    Ok(PresetsPayload {
+        presets: sorted_by_recency(presets),
+        active_preset_id: active,
+    })
+}
 
-    let (presets, active) = normalize(parsed.clone().unwrap_or_default(), active);
+/// Default preset first, then most recently used; never-used presets keep their order.
+fn sorted_by_recency(mut presets: Vec<WritingPreset>) -> Vec<WritingPreset> {
+    presets.sort_by_key(|p| (!p.is_default, std::cmp::Reverse(p.last_used_at)));
+    presets
+}
 
-    // Persist defaults / normalization back to config.
-    let mut should_write = match parsed.as_ref() {
-        None => true,
-        Some(existing) => existing != &presets,
-    };
-    should_write |= config.get("activePresetId").and_then(|v| v.as_str()) != Some(active.as_str());
-    if should_write {
-        config["presets"] = serde_json::to_value(&presets).unwrap_or(Value::Null);
-        config["activePresetId"] = Value::String(active.clone());
-        if let Ok(now) = now_unix_seconds() {
-            config["updated"] = Value::Number(now.into());
+fn touch_preset_sync(project_path: String, preset_id: String) -> Result<WritingPreset, String> {
+    let _guard = fs_lock()
+        .lock()
+        .map_err(|_| "Failed to lock presets storage".to_string())?;
+
+    let project_root = canonical_project_root(project_path)?;
+    let mut file = load_presets_file(&project_root)?;
+    let preset = file
+        .presets
+        .iter_mut()
+        .find(|p| p.id == preset_id)
+        .ok_or(format!("Preset {} not found", preset_id))?;
+    preset.last_used_at = Some(now_unix_seconds()?);
+    preset.use_count = preset.use_count.saturating_add(1);
+    let touched = preset.clone();
+
+    file.schema_version = PRESETS_SCHEMA_VERSION;
+    write_presets_file(&project_root, &file)?;
+    Ok(touched)
+}
+
+fn save_presets_sync(
+    project_path: String,
+    presets: Vec<WritingPreset>,
+    active_preset_id: String,
+) -> Result<PresetsPayload, String> {
+    let guard = fs_lock()
+        .lock()
+        .map_err(|_| "Failed to lock presets storage".to_string())?;
+
+    let project_root = canonical_project_root(project_path)?;
+    let previous = load_presets_file(&project_root)?.presets;
+
+    // The editor's copy may predate a touch_preset; keep the newer usage.
+    let mut presets = presets;
+    for preset in presets.iter_mut() {
+        if let Some(stored) = previous.iter().find(|p| p.id == preset.id) {
+            preset.last_used_at = preset.last_used_at.max(stored.last_used_at);
+            preset.use_count = preset.use_count.max(stored.use_count);
         }
-        write_config_json(&project_root, &config)?;
     }
+    let (presets, active) = normalize(presets, Some(active_preset_id));
+    let removed: HashSet<String> = previous
+        .into_iter()
+        .map(|p| p.id)
+        .filter(|id| !presets.iter().any(|p| &p.id == id))
+        .collect();
+
+    write_presets_file(
+        &project_root,
+        &PresetsFile {
+            schema_version: PRESETS_SCHEMA_VERSION,
+            presets: presets.clone(),
+            active_preset_id: active.clone(),
+        },
+    )?;
+    drop(guard);
 
+    // Sessions pointing at a removed preset fall back to the project's active preset.
+    crate::session::clear_session_presets(&project_root, &removed)?;
     Ok(PresetsPayload {
         presets,
         active_preset_id: active,
     })
 }
 
-fn save_presets_sync(
+// ===== Global preset library =====
+//
+// `~/.creatorai/presets.json` holds presets shared by all projects. Presets are copied
+// between it and a project with fresh ids, never linked.
+
+static GLOBAL_PRESETS_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GlobalPresetsFile {
+    schema_version: u32,
+    presets: Vec<WritingPreset>,
+}
+
+fn global_presets_path() -> Result<PathBuf, String> {
+    Ok(crate::config::get_global_config_dir()?.join("presets.json"))
+}
+
+fn read_global_presets(path: &Path) -> Result<Vec<WritingPreset>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read global presets: {e}"))?;
+    let file = serde_json::from_slice::<GlobalPresetsFile>(&bytes)
+        .map_err(|e| format!("Failed to parse global presets: {e}"))?;
+    if file.schema_version > PRESETS_SCHEMA_VERSION {
+        return Err(format!(
+            "Global presets schema {} is newer than supported schema {PRESETS_SCHEMA_VERSION}",
+            file.schema_version
+        ));
+    }
+    Ok(file.presets)
+}
+
+fn write_global_presets(path: &Path, presets: Vec<WritingPreset>) -> Result<(), String> {
+    let file = GlobalPresetsFile {
+        schema_version: PRESETS_SCHEMA_VERSION,
+        presets,
+    };
+    let content =
+        serde_json::to_string_pretty(&file).map_err(|e| format!("Serialize JSON failed: {e}"))?;
+    write_protection::atomic_write_bytes(path, format!("{content}\n").as_bytes(), None)
+}
+
+/// Runs `update` on the global library under its lock and saves the result.
+fn with_global_presets<T>(
+    update: impl FnOnce(&mut Vec<WritingPreset>) -> Result<T, String>,
+) -> Result<T, String> {
+    let _guard = GLOBAL_PRESETS_LOCK
+        .get_or_init(|| Mutex::new(()))
+        .lock()
+        .map_err(|_| "Failed to lock global presets".to_string())?;
+    let path = global_presets_path()?;
+    let mut presets = read_global_presets(&path)?;
+    let result = update(&mut presets)?;
+    write_global_presets(&path, presets)?;
+    Ok(result)
+}
+
+/// A preset as it enters another namespace: new id, not default, no usage history.
+fn detached_copy(preset: &WritingPreset) -> WritingPreset {
+    WritingPreset {
+        id: new_preset_id(),
+        is_default: false,
+        last_used_at: None,
+        use_count: 0,
+        ..preset.clone()
+    }
+}
+
+/// Inserts or replaces `preset` (by id) in the global library. Global presets have no
+/// default, and the same validation errors as `save_presets` reject the change.
+fn upsert_global_preset(
+    presets: &mut Vec<WritingPreset>,
+    mut preset: WritingPreset,
+) -> Result<WritingPreset, String> {
+    if preset.id.trim().is_empty() {
+        preset.id = new_preset_id();
+    }
+    preset.is_default = false;
+    let mut next = presets.clone();
+    match next.iter_mut().find(|p| p.id == preset.id) {
+        Some(existing) => *existing = preset.clone(),
+        None => next.push(preset.clone()),
+    }
+    let errors: Vec<String> = validate_presets(&next, &preset.id)
+        .into_iter()
+        .filter(|i| i.severity == IssueSeverity::Error)
+        .map(|i| i.message)
+        .collect();
+    if !errors.is_empty() {
+        return Err(errors.join("; "));
+    }
+    *presets = next;
+    Ok(preset)
+}
+
+fn list_global_presets_sync() -> Result<Vec<WritingPreset>, String> {
+    let _guard = GLOBAL_PRESETS_LOCK
+        .get_or_init(|| Mutex::new(()))
+        .lock()
+        .map_err(|_| "Failed to lock global presets".to_string())?;
+    read_global_presets(&global_presets_path()?)
+}
+
+fn copy_preset_to_project_sync(
+    project_path: String,
+    global_preset_id: String,
+) -> Result<WritingPreset, String> {
+    let copy = list_global_presets_sync()?
+        .iter()
+        .find(|p| p.id == global_preset_id)
+        .map(detached_copy)
+        .ok_or(format!("Global preset {} not found", global_preset_id))?;
+
+    let payload = get_presets_sync(project_path.clone())?;
+    let mut presets = payload.presets;
+    presets.push(copy.clone());
+    save_presets_sync(project_path, presets, payload.active_preset_id)?;
+    Ok(copy)
+}
+
+fn promote_preset_to_global_sync(
+    project_path: String,
+    preset_id: String,
+) -> Result<WritingPreset, String> {
+    let copy = get_presets_sync(project_path)?
+        .presets
+        .iter()
+        .find(|p| p.id == preset_id)
+        .map(detached_copy)
+        .ok_or(format!("Preset {} not found", preset_id))?;
+    with_global_presets(|presets| upsert_global_preset(presets, copy))
+}
+
+fn save_presets_checked(
     project_path: String,
     presets: Vec<WritingPreset>,
     active_preset_id: String,
-) -> Result<(), String> {
-    let _guard = fs_lock()
-        .lock()
-        .map_err(|_| "Failed to lock presets storage".to_string())?;
+    strict: bool,
+) -> Result<SavePresetsReport, String> {
+    let issues = validate_presets(&presets, &active_preset_id);
+    let blocked = (strict && !issues.is_empty())
+        || issues.iter().any(|i| i.severity == IssueSeverity::Error);
+    let (saved, payload) = if blocked {
+        (false, get_presets_sync(project_path)?)
+    } else {
+        (true, save_presets_sync(project_path, presets, active_preset_id)?)
+    };
+    Ok(SavePresetsReport {
+        saved,
+        issues,
+        presets: payload.presets,
+        active_preset_id: payload.active_preset_id,
+    })
+}
 
-    let project_root = PathBuf::from(project_path);
-    ensure_project_exists(&project_root)?;
+const PRESET_FILE_FORMAT: &str = "creatorai-preset";
+const PRESET_FILE_VERSION: u64 = 1;
 
-    let mut config = read_config_json(&project_root)?;
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PresetFile<'a> {
+    format: &'static str,
+    version: u64,
+    exported_at: u64,
+    preset: &'a WritingPreset,
+}
 
-    let (presets, active) = normalize(presets, Some(active_preset_id));
+fn field<'a>(object: &'a Value, path: &str, key: &str) -> Result<&'a Value, String> {
+    object
+        .get(key)
+        .ok_or_else(|| format!("Invalid preset file: missing field `{path}.{key}`"))
+}
 
-    config["presets"] = serde_json::to_value(&presets).unwrap_or(Value::Null);
-    config["activePresetId"] = Value::String(active);
-    config["updated"] = Value::Number(now_unix_seconds()?.into());
+fn string_field(object: &Value, path: &str, key: &str) -> Result<String, String> {
+    field(object, path, key)?
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| format!("Invalid preset file: `{path}.{key}` must be a string"))
+}
 
-    write_config_json(&project_root, &config)?;
-    Ok(())
+/// Checks an exported preset file field by field so a bad file names what is wrong.
+fn parse_preset_file(content: &str) -> Result<WritingPreset, String> {
+    let root: Value = serde_json::from_str(content.trim_start_matches('\u{feff}'))
+        .map_err(|e| format!("Invalid preset file: {e}"))?;
+    if !root.is_object() {
+        return Err("Invalid preset file: expected a JSON object".to_string());
+    }
+    if string_field(&root, "$", "format")? != PRESET_FILE_FORMAT {
+        return Err(format!("Invalid preset file: `$.format` must be \"{PRESET_FILE_FORMAT}\""));
+    }
+    let version = field(&root, "$", "version")?
+        .as_u64()
+        .ok_or("Invalid preset file: `$.version` must be a positive integer")?;
+    if version > PRESET_FILE_VERSION {
+        return Err(format!(
+            "Preset file version {version} is newer than supported version {PRESET_FILE_VERSION}"
+        ));
+    }
+
+    let preset = field(&root, "$", "preset")?;
+    if !preset.is_object() {
+        return Err("Invalid preset file: `$.preset` must be an object".to_string());
+    }
+    let id = string_field(preset, "$.preset", "id")?;
+    let name = string_field(preset, "$.preset", "name")?;
+    if name.trim().is_empty() {
+        return Err("Invalid preset file: `$.preset.name` is empty".to_string());
+    }
+    let is_default = field(preset, "$.preset", "isDefault")?
+        .as_bool()
+        .ok_or("Invalid preset file: `$.preset.isDefault` must be a boolean")?;
+
+    let style = field(preset, "$.preset", "style")?;
+    if !style.is_object() {
+        return Err("Invalid preset file: `$.preset.style` must be an object".to_string());
+    }
+    let style = WritingStyle {
+        tone: string_field(style, "$.preset.style", "tone")?,
+        perspective: string_field(style, "$.preset.style", "perspective")?,
+        tense: string_field(style, "$.preset.style", "tense")?,
+        description: string_field(style, "$.preset.style", "description")?,
+    };
+
+    let rules = field(preset, "$.preset", "rules")?
+        .as_array()
+        .ok_or("Invalid preset file: `$.preset.rules` must be an array")?
+        .iter()
+        .enumerate()
+        .map(|(i, rule)| {
+            rule.as_str()
+                .map(str::to_string)
+                .ok_or_else(|| format!("Invalid preset file: `$.preset.rules[{i}]` must be a string"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let banned_words = match preset.get("bannedWords") {
+        None | Some(Value::Null) => Vec::new(),
+        Some(words) => words
+            .as_array()
+            .ok_or("Invalid preset file: `$.preset.bannedWords` must be an array")?
+            .iter()
+            .enumerate()
+            .map(|(i, word)| {
+                word.as_str().map(str::to_string).ok_or_else(|| {
+                    format!("Invalid preset file: `$.preset.bannedWords[{i}]` must be a string")
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+    };
+    let glossary = match preset.get("glossary") {
+        None | Some(Value::Null) => Vec::new(),
+        Some(entries) => entries
+            .as_array()
+            .ok_or("Invalid preset file: `$.preset.glossary` must be an array")?
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| match entry.as_array().map(Vec::as_slice) {
+                Some([Value::String(wrong), Value::String(correct)]) => {
+                    Ok((wrong.clone(), correct.clone()))
+                }
+                _ => Err(format!(
+                    "Invalid preset file: `$.preset.glossary[{i}]` must be a [wrong, correct] pair of strings"
+                )),
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+    };
+
+    Ok(WritingPreset {
+        id,
+        name,
+        is_default,
+        style,
+        rules,
+        custom_prompt: string_field(preset, "$.preset", "customPrompt")?,
+        banned_words,
+        glossary,
+        // Usage belongs to the exporting project.
+        last_used_at: None,
+        use_count: 0,
+    })
+}
+
+fn export_preset_sync(project_path: String, preset_id: String, dest_path: String) -> Result<(), String> {
+    let payload = get_presets_sync(project_path)?;
+    let preset = payload
+        .presets
+        .iter()
+        .find(|p| p.id == preset_id)
+        .ok_or(format!("Preset {} not found", preset_id))?;
+
+    let file = PresetFile {
+        format: PRESET_FILE_FORMAT,
+        version: PRESET_FILE_VERSION,
+        exported_at: now_unix_seconds()?,
+        preset,
+    };
+    let json =
+        serde_json::to_string_pretty(&file).map_err(|e| format!("Serialize JSON failed: {e}"))?;
+    crate::write_protection::atomic_write_bytes(
+        Path::new(&dest_path),
+        format!("{json}\n").as_bytes(),
+        None,
+    )
+}
+
+fn import_preset_sync(project_path: String, file_path: String) -> Result<WritingPreset, String> {
+    let content =
+        fs::read_to_string(&file_path).map_err(|e| format!("Failed to read preset file: {e}"))?;
+    let mut preset = parse_preset_file(&content)?;
+
+    let payload = get_presets_sync(project_path.clone())?;
+    let mut presets = payload.presets;
+    if preset.id.trim().is_empty() || presets.iter().any(|p| p.id == preset.id) {
+        preset.id = new_preset_id();
+    }
+    // The project's current default stays the default.
+    preset.is_default = false;
+
+    presets.push(preset.clone());
+    save_presets_sync(project_path, presets, payload.active_preset_id)?;
+    Ok(preset)
+}
+
+fn add_builtin_preset_sync(project_path: String, builtin_id: String) -> Result<WritingPreset, String> {
+    let mut preset = genre_presets()
+        .into_iter()
+        .find(|p| p.id == builtin_id)
+        .ok_or(format!("Builtin preset {} not found", builtin_id))?;
+    preset.id = new_preset_id();
+
+    let payload = get_presets_sync(project_path.clone())?;
+    let mut presets = payload.presets;
+    presets.push(preset.clone());
+    save_presets_sync(project_path, presets, payload.active_preset_id)?;
+    Ok(preset)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StyleViolationKind {
+    BannedWord,
+    Glossary,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StyleViolation {
+    pub kind: StyleViolationKind,
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based character column.
+    pub column: usize,
+    pub text: String,
+    /// Correct form for glossary violations.
+    pub suggestion: Option<String>,
+}
+
+fn find_style_violations(content: &str, preset: &WritingPreset) -> Vec<StyleViolation> {
+    let mut violations = Vec::new();
+    for (line_no, line) in content.lines().enumerate() {
+        let column = |byte: usize| line[..byte].chars().count() + 1;
+
+        for word in preset.banned_words.iter().map(|w| w.trim()).filter(|w| !w.is_empty()) {
+            for (byte, text) in line.match_indices(word) {
+                violations.push(StyleViolation {
+                    kind: StyleViolationKind::BannedWord,
+                    line: line_no + 1,
+                    column: column(byte),
+                    text: text.to_string(),
+                    suggestion: None,
+                });
+            }
+        }
+
+        for (wrong, correct) in &preset.glossary {
+            let (wrong, correct) = (wrong.trim(), correct.trim());
+            if wrong.is_empty() || wrong == correct {
+                continue;
+            }
+            // A wrong form inside the correct one (张三 in 张三丰) is not a mistake.
+            let correct_spans: Vec<(usize, usize)> = if correct.is_empty() {
+                Vec::new()
+            } else {
+                line.match_indices(correct)
+                    .map(|(start, text)| (start, start + text.len()))
+                    .collect()
+            };
+            for (byte, text) in line.match_indices(wrong) {
+                let end = byte + text.len();
+                if correct_spans.iter().any(|&(start, stop)| start <= byte && end <= stop) {
+                    continue;
+                }
+                violations.push(StyleViolation {
+                    kind: StyleViolationKind::Glossary,
+                    line: line_no + 1,
+                    column: column(byte),
+                    text: text.to_string(),
+                    suggestion: Some(correct.to_string()),
+                });
+            }
+        }
+    }
+    violations.sort_by_key(|v| (v.line, v.column));
+    violations
+}
+
+fn check_chapter_against_preset_sync(
+    project_path: String,
+    chapter_id: String,
+    preset_id: String,
+) -> Result<Vec<StyleViolation>, String> {
+    let payload = get_presets_sync(project_path.clone())?;
+    let preset = payload
+        .presets
+        .iter()
+        .find(|p| p.id == preset_id)
+        .ok_or(format!("Preset {} not found", preset_id))?;
+    let content = crate::chapter::get_chapter_content_sync(project_path, chapter_id)?;
+    Ok(find_style_violations(&content, preset))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptSection {
+    pub key: String,
+    pub chars: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptPreview {
+    pub prompt: String,
+    /// Non-empty sections in render order.
+    pub sections: Vec<PromptSection>,
+    pub total_chars: usize,
+}
+
+/// Chapter the preview is rendered for, with its latest summary if one was saved.
+struct ChapterContext {
+    id: String,
+    title: String,
+    summary: Option<String>,
+}
+
+/// Renders the preset the way the chat system prompt embeds it, keyed by section. Joined by
+/// [`format_preset_prompt`] this must equal `formatWritingPreset` on the frontend; both are
+/// checked against `test-suite/fixtures/preset-prompt.json`.
+fn preset_prompt_sections(preset: &WritingPreset) -> Vec<(&'static str, String)> {
+    let mut sections = vec![(
+        "style",
+        format!(
+            "写作风格要求：\n- 文风：{}\n- 叙事视角：{}\n- 时态：{}\n- 描写风格：{}",
+            preset.style.tone, preset.style.perspective, preset.style.tense, preset.style.description
+        ),
+    )];
+    if !preset.rules.is_empty() {
+        let rules: Vec<String> = preset.rules.iter().map(|r| format!("- {r}")).collect();
+        sections.push(("rules", format!("写作规则：\n{}", rules.join("\n"))));
+    }
+    let banned: Vec<&str> = preset
+        .banned_words
+        .iter()
+        .map(|w| w.trim())
+        .filter(|w| !w.is_empty())
+        .collect();
+    if !banned.is_empty() {
+        sections.push(("bannedWords", format!("禁用词（正文中不得出现）：{}", banned.join("、"))));
+    }
+    let glossary: Vec<String> = preset
+        .glossary
+        .iter()
+        .filter(|(wrong, correct)| !wrong.trim().is_empty() && !correct.trim().is_empty())
+        .map(|(wrong, correct)| format!("- 写作「{correct}」，不要写成「{wrong}」"))
+        .collect();
+    if !glossary.is_empty() {
+        sections.push(("glossary", format!("术语规范：\n{}", glossary.join("\n"))));
+    }
+    if !preset.custom_prompt.is_empty() {
+        sections.push(("customPrompt", format!("额外要求：\n{}", preset.custom_prompt)));
+    }
+    sections
+}
+
+/// Preset sections sit on consecutive lines, as `formatWritingPreset` joins them.
+fn format_preset_prompt(sections: &[(&'static str, String)]) -> String {
+    sections
+        .iter()
+        .map(|(_, text)| text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn render_prompt_preview(preset: &WritingPreset, chapter: Option<&ChapterContext>) -> PromptPreview {
+    let mut sections = preset_prompt_sections(preset);
+    let mut blocks = vec![format_preset_prompt(&sections)];
+    if let Some(chapter) = chapter {
+        let text = format!("## 当前章节\n- 章节：{}（{}）", chapter.title, chapter.id);
+        blocks.push(text.clone());
+        sections.push(("chapter", text));
+        if let Some(summary) = chapter.summary.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            let text = format!("## 本章摘要\n{summary}");
+            blocks.push(text.clone());
+            sections.push(("summary", text));
+        }
+    }
+
+    let prompt = blocks.join("\n\n");
+    PromptPreview {
+        total_chars: prompt.chars().count(),
+        sections: sections
+            .iter()
+            .map(|(key, text)| PromptSection {
+                key: key.to_string(),
+                chars: text.chars().count(),
+            })
+            .collect(),
+        prompt,
+    }
+}
+
+fn preview_preset_prompt_sync(
+    project_path: String,
+    preset_id: String,
+    chapter_id: Option<String>,
+) -> Result<PromptPreview, String> {
+    let payload = get_presets_sync(project_path.clone())?;
+    let preset = payload
+        .presets
+        .iter()
+        .find(|p| p.id == preset_id)
+        .ok_or(format!("Preset {} not found", preset_id))?;
+
+    let chapter = match chapter_id.filter(|id| !id.trim().is_empty()) {
+        Some(chapter_id) => {
+            let meta = crate::chapter::list_chapters_sync(project_path.clone())?
+                .into_iter()
+                .find(|c| c.id == chapter_id)
+                .ok_or(format!("Chapter {} not found", chapter_id))?;
+            let project_root = canonical_project_root(project_path)?;
+            let summary = crate::summary::get_latest_summary(&project_root, &chapter_id)?
+                .map(|s| s.summary);
+            Some(ChapterContext {
+                id: meta.id,
+                title: meta.title,
+                summary,
+            })
+        }
+        None => None,
+    };
+    Ok(render_prompt_preview(preset, chapter.as_ref()))
+}
+
+fn duplicate_preset_sync(
+    project_path: String,
+    preset_id: String,
+    new_name: String,
+) -> Result<WritingPreset, String> {
+    let new_name = new_name.trim().to_string();
+    if new_name.is_empty() {
+        return Err("Preset name is empty".to_string());
+    }
+    let payload = get_presets_sync(project_path.clone())?;
+    let mut copy = payload
+        .presets
+        .iter()
+        .find(|p| p.id == preset_id)
+        .ok_or(format!("Preset {} not found", preset_id))?
+        .clone();
+    copy.id = new_preset_id();
+    copy.name = new_name;
+    copy.is_default = false;
+    copy.last_used_at = None;
+    copy.use_count = 0;
+
+    let mut presets = payload.presets;
+    presets.push(copy.clone());
+    save_presets_sync(project_path, presets, payload.active_preset_id)?;
+    Ok(copy)
+}
+
+#[tauri::command]
+pub fn list_builtin_presets() -> Vec<WritingPreset> {
+    genre_presets()
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn add_builtin_preset(
+    project_path: String,
+    builtin_id: String,
+) -> Result<WritingPreset, String> {
+    tauri::async_runtime::spawn_blocking(move || add_builtin_preset_sync(project_path, builtin_id))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn check_chapter_against_preset(
+    project_path: String,
+    chapter_id: String,
+    preset_id: String,
+) -> Result<Vec<StyleViolation>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        check_chapter_against_preset_sync(project_path, chapter_id, preset_id)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn preview_preset_prompt(
+    project_path: String,
+    preset_id: String,
+    chapter_id: Option<String>,
+) -> Result<PromptPreview, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        preview_preset_prompt_sync(project_path, preset_id, chapter_id)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command]
+pub async fn list_global_presets() -> Result<Vec<WritingPreset>, String> {
+    tauri::async_runtime::spawn_blocking(list_global_presets_sync)
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command]
+pub async fn save_global_preset(preset: WritingPreset) -> Result<WritingPreset, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        with_global_presets(|presets| upsert_global_preset(presets, preset))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn delete_global_preset(preset_id: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        with_global_presets(|presets| {
+            let before = presets.len();
+            presets.retain(|p| p.id != preset_id);
+            if presets.len() == before {
+                return Err(format!("Global preset {} not found", preset_id));
+            }
+            Ok(())
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn copy_preset_to_project(
+    project_path: String,
+    global_preset_id: String,
+) -> Result<WritingPreset, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        copy_preset_to_project_sync(project_path, global_preset_id)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn promote_preset_to_global(
+    project_path: String,
+    preset_id: String,
+) -> Result<WritingPreset, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        promote_preset_to_global_sync(project_path, preset_id)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn touch_preset(project_path: String, preset_id: String) -> Result<WritingPreset, String> {
+    tauri::async_runtime::spawn_blocking(move || touch_preset_sync(project_path, preset_id))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn duplicate_preset(
+    project_path: String,
+    preset_id: String,
+    new_name: String,
+) -> Result<WritingPreset, String> {
+    tauri::async_runtime::spawn_blocking(move || duplicate_preset_sync(project_path, preset_id, new_name))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
 }
 
 #[tauri::command(rename_all = "camelCase")]
@@ -325,8 +1400,400 @@ pub async fn save_presets(
     project_path: String,
     presets: Vec<WritingPreset>,
     active_preset_id: String,
+    strict: Option<bool>,
+) -> Result<SavePresetsReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        save_presets_checked(project_path, presets, active_preset_id, strict.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn export_preset(
+    project_path: String,
+    preset_id: String,
+    dest_path: String,
 ) -> Result<(), String> {
-    tauri::async_runtime::spawn_blocking(move || save_presets_sync(project_path, presets, active_preset_id))
+    tauri::async_runtime::spawn_blocking(move || export_preset_sync(project_path, preset_id, dest_path))
         .await
         .map_err(|e| format!("Task join error: {e}"))?
 }
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn import_preset(project_path: String, file_path: String) -> Result<WritingPreset, String> {
+    tauri::async_runtime::spawn_blocking(move || import_preset_sync(project_path, file_path))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use serde_json::json;
+
+    struct TempProject {
+        temp: TempDir,
+    }
+
+    impl TempProject {
+        fn with_config(prefix: &str, config: &str) -> Self {
+            let temp = TempDir::new(prefix);
+            fs::create_dir_all(temp.path.join(".creatorai")).expect("create project dir");
+            fs::write(temp.path.join(".creatorai/config.json"), config).expect("write config.json");
+            Self { temp }
+        }
+
+        fn path(&self) -> String {
+            self.temp.path.to_string_lossy().to_string()
+        }
+
+        fn config(&self) -> Value {
+            let content = fs::read_to_string(self.temp.path.join(".creatorai/config.json")).unwrap();
+            serde_json::from_str(&content).unwrap()
+        }
+    }
+
+    #[test]
+    fn embedded_presets_move_to_presets_json() {
+        let mut custom = builtin_presets().remove(1);
+        custom.id = "mine".to_string();
+        let config = json!({
+            "name": "小说",
+            "presets": [builtin_presets()[0], custom],
+            "activePresetId": "mine",
+        });
+        let project = TempProject::with_config("creatorai-presets-migrate", &config.to_string());
+
+        let payload = get_presets_sync(project.path()).expect("get_presets");
+        assert_eq!(payload.active_preset_id, "mine");
+        assert_eq!(payload.presets.len(), 2);
+        assert!(project.temp.path.join(".creatorai/presets.json").exists());
+
+        let config = project.config();
+        assert_eq!(config["name"], "小说");
+        assert!(config.get("presets").is_none());
+        assert!(config.get("activePresetId").is_none());
+
+        // Later reads come from presets.json alone.
+        let again = get_presets_sync(project.path()).expect("get_presets again");
+        assert_eq!(again.presets, payload.presets);
+    }
+
+    #[test]
+    fn touched_presets_sort_after_the_default_by_recency() {
+        let project = TempProject::with_config("creatorai-presets-touch", r#"{"name":"小说"}"#);
+        let ids: Vec<String> = builtin_presets().into_iter().map(|p| p.id).collect();
+
+        touch_preset_sync(project.path(), ids[3].clone()).expect("touch");
+        let touched = touch_preset_sync(project.path(), ids[3].clone()).expect("touch again");
+        assert_eq!(touched.use_count, 2);
+        assert!(touched.last_used_at.is_some());
+
+        let order: Vec<String> = get_presets_sync(project.path())
+            .expect("get_presets")
+            .presets
+            .into_iter()
+            .map(|p| p.id)
+            .collect();
+        assert_eq!(order[0], ids[0]);
+        assert_eq!(order[1], ids[3]);
+        assert_eq!(order[2], ids[1]);
+    }
+
+    #[test]
+    fn global_upsert_replaces_by_id_and_rejects_invalid_presets() {
+        let mut library = Vec::new();
+        let mut preset = builtin_presets().remove(0);
+        preset.id = String::new();
+        let saved = upsert_global_preset(&mut library, preset).expect("insert");
+        assert!(!saved.id.is_empty());
+        assert!(!saved.is_default);
+
+        let mut renamed = saved.clone();
+        renamed.name = "改名".to_string();
+        upsert_global_preset(&mut library, renamed).expect("replace");
+        assert_eq!(library.len(), 1);
+        assert_eq!(library[0].name, "改名");
+
+        let mut blank = saved.clone();
+        blank.name = " ".to_string();
+        assert!(upsert_global_preset(&mut library, blank).is_err());
+        assert_eq!(library[0].name, "改名");
+
+        let copy = detached_copy(&library[0]);
+        assert_ne!(copy.id, library[0].id);
+        assert_eq!(copy.use_count, 0);
+    }
+
+    #[test]
+    fn prompt_preview_template_is_stable() {
+        let mut preset = builtin_presets().remove(0);
+        preset.style = WritingStyle {
+            tone: "冷峻".to_string(),
+            perspective: "第三人称".to_string(),
+            tense: "过去时".to_string(),
+            description: "白描".to_string(),
+        };
+        preset.rules = vec!["少用形容词".to_string()];
+        preset.custom_prompt = String::new();
+        preset.banned_words = vec!["  ".to_string(), "竟然".to_string()];
+        preset.glossary = vec![("张三疯".to_string(), "张三丰".to_string())];
+
+        let chapter = ChapterContext {
+            id: "chapter_001".to_string(),
+            title: "第一章".to_string(),
+            summary: Some("主角下山。".to_string()),
+        };
+        let preview = render_prompt_preview(&preset, Some(&chapter));
+        assert_eq!(
+            preview.prompt,
+            "写作风格要求：\n- 文风：冷峻\n- 叙事视角：第三人称\n- 时态：过去时\n- 描写风格：白描\n\
+             写作规则：\n- 少用形容词\n\
+             禁用词（正文中不得出现）：竟然\n\
+             术语规范：\n- 写作「张三丰」，不要写成「张三疯」\n\n\
+             ## 当前章节\n- 章节：第一章（chapter_001）\n\n\
+             ## 本章摘要\n主角下山。"
+        );
+        let keys: Vec<&str> = preview.sections.iter().map(|s| s.key.as_str()).collect();
+        assert_eq!(
+            keys,
+            ["style", "rules", "bannedWords", "glossary", "chapter", "summary"]
+        );
+        assert_eq!(preview.total_chars, preview.prompt.chars().count());
+
+        let bare = render_prompt_preview(&preset, None);
+        assert!(!bare.prompt.contains("当前章节"));
+    }
+
+    #[test]
+    fn projects_without_presets_get_the_builtin_set() {
+        let project = TempProject::with_config("creatorai-presets-none", r#"{"name":"小说"}"#);
+
+        let payload = get_presets_sync(project.path()).expect("get_presets");
+        assert_eq!(payload.presets, builtin_presets());
+        assert_eq!(payload.active_preset_id, "default");
+        assert_eq!(project.config(), json!({ "name": "小说" }));
+    }
+
+    #[test]
+    fn corrupt_embedded_presets_are_left_in_config() {
+        let project = TempProject::with_config(
+            "creatorai-presets-corrupt",
+            r#"{"name":"小说","presets":[{"id":1}],"activePresetId":"x"}"#,
+        );
+
+        let payload = get_presets_sync(project.path()).expect("get_presets");
+        assert_eq!(payload.presets, builtin_presets());
+        let config = project.config();
+        assert_eq!(config["presets"], json!([{ "id": 1 }]));
+        assert_eq!(config["activePresetId"], "x");
+    }
+
+    fn fields(issues: &[PresetIssue], severity: IssueSeverity) -> Vec<(Option<usize>, String)> {
+        issues
+            .iter()
+            .filter(|i| i.severity == severity)
+            .map(|i| (i.index, i.field.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn validation_separates_errors_from_normalizable_warnings() {
+        let base = builtin_presets();
+        let mut presets = vec![base[0].clone(), base[1].clone(), base[2].clone(), base[3].clone()];
+        presets[1].id = presets[0].id.clone();
+        presets[2].name = "  ".to_string();
+        presets[3].name = presets[0].name.clone();
+        presets[3].is_default = true;
+        presets[3].rules.push("长".repeat(MAX_RULE_CHARS + 1));
+
+        let issues = validate_presets(&presets, "missing");
+        assert_eq!(
+            fields(&issues, IssueSeverity::Error),
+            vec![(Some(1), "id".to_string()), (Some(2), "name".to_string())]
+        );
+        let rule_field = format!("rules[{}]", presets[3].rules.len() - 1);
+        assert_eq!(
+            fields(&issues, IssueSeverity::Warning),
+            vec![
+                (Some(3), "name".to_string()),
+                (Some(3), rule_field),
+                (Some(3), "isDefault".to_string()),
+                (None, "activePresetId".to_string()),
+            ]
+        );
+
+        assert!(validate_presets(&base, &base[0].id).is_empty());
+    }
+
+    #[test]
+    fn style_check_reports_banned_words_and_wrong_glossary_forms() {
+        let mut preset = builtin_presets().remove(0);
+        preset.banned_words = vec!["突然".to_string(), " ".to_string()];
+        preset.glossary = vec![("张三".to_string(), "张三丰".to_string())];
+
+        let content = "他突然站起来。\n张三丰看着张三，顿时突然明白了。";
+        let found: Vec<_> = find_style_violations(content, &preset)
+            .into_iter()
+            .map(|v| (v.kind, v.line, v.column, v.suggestion))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (StyleViolationKind::BannedWord, 1, 2, None),
+                (StyleViolationKind::Glossary, 2, 6, Some("张三丰".to_string())),
+                (StyleViolationKind::BannedWord, 2, 11, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn exported_presets_import_as_new_non_default_copies() {
+        let project = TempProject::with_config("creatorai-presets-roundtrip", r#"{"name":"小说"}"#);
+        let mut original = builtin_presets().remove(0);
+        original.banned_words = vec!["竟然".to_string()];
+        original.glossary = vec![("张三疯".to_string(), "张三丰".to_string())];
+        save_presets_sync(project.path(), vec![original.clone()], original.id.clone())
+            .expect("save presets");
+        touch_preset_sync(project.path(), original.id.clone()).expect("touch");
+
+        let dest = project.temp.path.join("exported.json");
+        export_preset_sync(
+            project.path(),
+            original.id.clone(),
+            dest.to_string_lossy().to_string(),
+        )
+        .expect("export");
+        let imported = import_preset_sync(project.path(), dest.to_string_lossy().to_string())
+            .expect("import");
+
+        assert_ne!(imported.id, original.id);
+        assert!(!imported.is_default);
+        assert_eq!(imported.use_count, 0);
+        assert_eq!(imported.last_used_at, None);
+        assert_eq!(imported.name, original.name);
+        assert_eq!(imported.style, original.style);
+        assert_eq!(imported.rules, original.rules);
+        assert_eq!(imported.custom_prompt, original.custom_prompt);
+        assert_eq!(imported.banned_words, original.banned_words);
+        assert_eq!(imported.glossary, original.glossary);
+
+        let payload = get_presets_sync(project.path()).expect("get_presets");
+        assert_eq!(payload.presets.len(), 2);
+        assert_eq!(payload.active_preset_id, original.id);
+    }
+
+    #[test]
+    fn malformed_preset_files_name_the_bad_field() {
+        let preset = builtin_presets().remove(0);
+        let valid = json!({ "format": PRESET_FILE_FORMAT, "version": 1, "preset": preset });
+        assert!(parse_preset_file(&valid.to_string()).is_ok());
+        assert!(parse_preset_file(&format!("\u{feff}{valid}")).is_ok());
+
+        let err = parse_preset_file("{ not json").unwrap_err();
+        assert!(err.starts_with("Invalid preset file:"), "{err}");
+        let err = parse_preset_file("[]").unwrap_err();
+        assert!(err.contains("expected a JSON object"), "{err}");
+
+        let mut wrong_format = valid.clone();
+        wrong_format["format"] = json!("something-else");
+        let err = parse_preset_file(&wrong_format.to_string()).unwrap_err();
+        assert!(err.contains("`$.format`"), "{err}");
+
+        let mut missing_style = valid.clone();
+        missing_style["preset"]["style"]
+            .as_object_mut()
+            .unwrap()
+            .remove("tone");
+        let err = parse_preset_file(&missing_style.to_string()).unwrap_err();
+        assert!(err.contains("`$.preset.style.tone`"), "{err}");
+
+        let mut bad_rule = valid.clone();
+        bad_rule["preset"]["rules"] = json!(["ok", 3]);
+        let err = parse_preset_file(&bad_rule.to_string()).unwrap_err();
+        assert!(err.contains("`$.preset.rules[1]`"), "{err}");
+
+        let mut bad_glossary = valid.clone();
+        bad_glossary["preset"]["glossary"] = json!([["only-one"]]);
+        let err = parse_preset_file(&bad_glossary.to_string()).unwrap_err();
+        assert!(err.contains("`$.preset.glossary[0]`"), "{err}");
+
+        let mut blank_name = valid;
+        blank_name["preset"]["name"] = json!("  ");
+        let err = parse_preset_file(&blank_name.to_string()).unwrap_err();
+        assert!(err.contains("`$.preset.name` is empty"), "{err}");
+    }
+
+    #[test]
+    fn newer_preset_file_versions_are_rejected() {
+        let preset = builtin_presets().remove(0);
+        let file = json!({
+            "format": PRESET_FILE_FORMAT,
+            "version": PRESET_FILE_VERSION + 1,
+            "preset": preset,
+        });
+        let err = parse_preset_file(&file.to_string()).unwrap_err();
+        assert!(err.contains("newer than supported"), "{err}");
+
+        let project = TempProject::with_config("creatorai-presets-newer", r#"{"name":"小说"}"#);
+        let path = project.temp.path.join("newer.json");
+        fs::write(&path, file.to_string()).unwrap();
+        assert!(import_preset_sync(project.path(), path.to_string_lossy().to_string()).is_err());
+        assert_eq!(
+            get_presets_sync(project.path()).unwrap().presets,
+            builtin_presets()
+        );
+    }
+
+    #[test]
+    fn genre_presets_are_valid_with_unique_ids() {
+        let genres = genre_presets();
+        assert!(!genres.is_empty());
+        assert!(validate_presets(&genres, "").iter().all(|i| i.severity != IssueSeverity::Error));
+        assert!(genres.iter().all(|p| !p.is_default));
+
+        let builtins = builtin_presets();
+        let ids: Vec<&str> = genres.iter().chain(&builtins).map(|p| p.id.as_str()).collect();
+        let unique: HashSet<&str> = ids.iter().copied().collect();
+        assert_eq!(unique.len(), ids.len());
+    }
+
+    #[test]
+    fn adding_a_builtin_twice_makes_two_independent_copies() {
+        let project = TempProject::with_config("creatorai-presets-builtin", r#"{"name":"小说"}"#);
+        let first = add_builtin_preset_sync(project.path(), "genre-suspense".to_string())
+            .expect("add builtin");
+        let second = add_builtin_preset_sync(project.path(), "genre-suspense".to_string())
+            .expect("add builtin again");
+
+        assert_ne!(first.id, second.id);
+        assert_ne!(first.id, "genre-suspense");
+        assert_eq!(first.name, second.name);
+        assert!(!first.is_default && !second.is_default);
+
+        let payload = get_presets_sync(project.path()).expect("get_presets");
+        assert_eq!(payload.presets.len(), builtin_presets().len() + 2);
+        assert_eq!(payload.active_preset_id, "default");
+
+        assert!(add_builtin_preset_sync(project.path(), "genre-missing".to_string()).is_err());
+    }
+
+    #[test]
+    fn preset_prompt_matches_the_shared_frontend_fixture() {
+        let fixture: Value =
+            serde_json::from_str(include_str!("../../test-suite/fixtures/preset-prompt.json"))
+                .expect("parse fixture");
+        for case in fixture["cases"].as_array().expect("cases") {
+            let preset: WritingPreset =
+                serde_json::from_value(case["preset"].clone()).expect("fixture preset");
+            assert_eq!(
+                format_preset_prompt(&preset_prompt_sections(&preset)),
+                case["prompt"].as_str().expect("fixture prompt"),
+                "{}",
+                case["name"]
+            );
+        }
+    }
+}
@@ -1,8 +1,12 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs::{self, OpenOptions};
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::migrations;
+use crate::project_lock;
 use crate::write_protection;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,6 +24,86 @@ pub struct ProjectSettings {
     pub auto_save: bool,
     #[serde(rename = "autoSaveInterval")]
     pub auto_save_interval: u32,
+    /// Summary versions kept per chapter; `None` keeps every version.
+    #[serde(
+        rename = "maxSummariesPerChapter",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_summaries_per_chapter: Option<u32>,
+    /// Limits on `.backup/`; `None` keeps every backup.
+    #[serde(
+        rename = "backupRetention",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub backup_retention: Option<BackupRetention>,
+    /// Which symlinks inside the project may be followed.
+    #[serde(rename = "followSymlinks", default)]
+    pub follow_symlinks: SymlinkPolicy,
+    /// Absolute symlink targets accepted under [`SymlinkPolicy::AllowListed`].
+    #[serde(
+        rename = "symlinkAllowlist",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub symlink_allowlist: Vec<String>,
+    /// Largest file a single write may produce; `None` uses the 20 MB default.
+    #[serde(
+        rename = "maxFileBytes",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_file_bytes: Option<u64>,
+    /// Extra project-relative paths the generic file tools may not change, on top of
+    /// [`crate::security::PROTECTED_PATHS`]. A trailing `/` protects a whole directory.
+    #[serde(
+        rename = "protectedPaths",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub protected_paths: Vec<String>,
+    /// Refuse every change to the project's content (see `write_protection::ensure_writable`).
+    #[serde(rename = "readOnly", default)]
+    pub read_only: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymlinkPolicy {
+    /// Follow symlinks whose target stays inside the project.
+    #[default]
+    WithinProject,
+    /// Never follow symlinks.
+    Deny,
+    /// Like `WithinProject`, plus targets under `symlinkAllowlist`.
+    AllowListed,
+}
+
+/// Each limit is optional; the newest backup of every file is always kept.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupRetention {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_total_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_days: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_copies_per_file: Option<u32>,
+}
+
+/// How far along a chapter is; indexes written before this existed read as `Draft`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChapterStatus {
+    #[default]
+    Draft,
+    Revising,
+    Done,
+    /// A status this build doesn't know, e.g. from a newer version. Reading it keeps the
+    /// rest of the index usable; it can't be set.
+    #[serde(other)]
+    Unknown,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,6 +115,19 @@ pub struct ChapterMeta {
     pub updated: u64,
     #[serde(rename = "wordCount")]
     pub word_count: u32,
+    #[serde(default)]
+    pub status: ChapterStatus,
+    /// The volume (卷) the chapter belongs to, if any.
+    #[serde(rename = "volumeId", default, skip_serializing_if = "Option::is_none")]
+    pub volume_id: Option<String>,
+}
+
+/// A named group of chapters. Chapters keep their own global order; volumes only label them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Volume {
+    pub id: String,
+    pub title: String,
+    pub order: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,9 +135,18 @@ pub struct ChapterIndex {
     pub chapters: Vec<ChapterMeta>,
     #[serde(rename = "nextId")]
     pub next_id: u32,
+    /// Absent from indexes written before volumes existed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub volumes: Vec<Volume>,
+    /// Number of the next `volume_XXX` id, so ids of deleted volumes aren't handed out
+    /// again. 0 (and absent) until the first volume is created.
+    #[serde(rename = "nextVolumeId", default, skip_serializing_if = "is_zero")]
+    pub next_volume_id: u32,
 }
 
-const PROJECT_VERSION: &str = "1.0";
+fn is_zero(n: &u32) -> bool {
+    *n == 0
+}
 
 fn now_unix_seconds() -> Result<u64, String> {
     SystemTime::now()
@@ -109,26 +215,72 @@ fn write_json_pretty_overwrite<T: Serialize>(
     Ok(())
 }
 
-fn read_project_config(project_root: &Path) -> Result<ProjectConfig, String> {
+pub(crate) fn read_project_config(project_root: &Path) -> Result<ProjectConfig, String> {
     let path = config_path(project_root);
     let bytes = fs::read(&path).map_err(|e| format!("Failed to read config.json: {e}"))?;
     serde_json::from_slice::<ProjectConfig>(&bytes)
         .map_err(|e| format!("Failed to parse config.json: {e}"))
 }
 
-fn validate_project_structure(project_root: &Path) -> Result<(), String> {
+/// Parsed settings per config file, with the modification time and size they were read at.
+type SettingsCache = HashMap<PathBuf, ((Option<SystemTime>, u64), ProjectSettings)>;
+static SETTINGS_CACHE: Mutex<Option<SettingsCache>> = Mutex::new(None);
+
+/// The project's settings, parsed again only when `.creatorai/config.json` changed on disk.
+/// Path and write checks consult these on every file operation.
+pub(crate) fn project_settings(project_root: &Path) -> Result<ProjectSettings, String> {
+    let path = config_path(project_root);
+    let meta = fs::metadata(&path).map_err(|e| format!("Failed to read config.json: {e}"))?;
+    let stamp = (meta.modified().ok(), meta.len());
+    let mut cache = SETTINGS_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    let cache = cache.get_or_insert_with(HashMap::new);
+    if let Some((cached_stamp, settings)) = cache.get(&path) {
+        if *cached_stamp == stamp {
+            return Ok(settings.clone());
+        }
+    }
+    let settings = read_project_config(project_root)?.settings;
+    cache.insert(path, (stamp, settings.clone()));
+    Ok(settings)
+}
+
+/// A missing `chapters/index.json` is tolerated when chapter files exist and the caller
+/// will recover the index.
+fn validate_project_structure(project_root: &Path, auto_recover: bool) -> Result<(), String> {
     let cfg = config_path(project_root);
     if !cfg.exists() {
         return Err("Not a valid project: missing .creatorai/config.json".to_string());
     }
     let index = chapters_index_path(project_root);
     if !index.exists() {
-        return Err("Not a valid project: missing chapters/index.json".to_string());
+        if !has_chapter_files(project_root) {
+            return Err("Not a valid project: missing chapters/index.json".to_string());
+        }
+        if !auto_recover {
+            return Err(
+                "chapters/index.json is missing but chapter files exist; open with auto_recover or run rebuild_chapter_index"
+                    .to_string(),
+            );
+        }
     }
     Ok(())
 }
 
-fn create_project_sync(path: String, name: String) -> Result<ProjectConfig, String> {
+fn has_chapter_files(project_root: &Path) -> bool {
+    let Ok(entries) = fs::read_dir(project_root.join("chapters")) else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        let name = entry.file_name().to_string_lossy().to_string();
+        name.starts_with("chapter_") && name.ends_with(".txt")
+    })
+}
+
+pub(crate) fn create_project_sync(
+    path: String,
+    name: String,
+    owner: &str,
+) -> Result<ProjectConfig, String> {
     let project_root = PathBuf::from(path);
     ensure_project_root(&project_root)?;
 
@@ -156,39 +308,63 @@ fn create_project_sync(path: String, name: String) -> Result<ProjectConfig, Stri
         name,
         created: now,
         updated: now,
-        version: PROJECT_VERSION.to_string(),
+        version: migrations::CURRENT_PROJECT_VERSION.to_string(),
         settings: ProjectSettings {
             auto_save: true,
             auto_save_interval: 2000,
+            max_summaries_per_chapter: None,
+            backup_retention: None,
+            follow_symlinks: SymlinkPolicy::WithinProject,
+            symlink_allowlist: Vec::new(),
+            max_file_bytes: None,
+            protected_paths: Vec::new(),
+            read_only: false,
         },
     };
 
     let index = ChapterIndex {
         chapters: Vec::new(),
         next_id: 1,
+        volumes: Vec::new(),
+        next_volume_id: 0,
     };
 
     write_json_pretty_create_new(&cfg_path, &config)?;
     write_json_pretty_create_new(&idx_path, &index)?;
-    fs::write(&summaries_path, "[]\n")
-        .map_err(|e| format!("Failed to write '{}': {e}", summaries_path.display()))?;
+    let summaries_dir = project_root.join("summaries");
+    fs::create_dir_all(&summaries_dir)
+        .map_err(|e| format!("Failed to create '{}': {e}", summaries_dir.display()))?;
+    project_lock::acquire(&project_root, owner, false)?;
 
     Ok(config)
 }
 
-fn open_project_sync(path: String) -> Result<ProjectConfig, String> {
+pub(crate) fn open_project_sync(
+    path: String,
+    auto_recover: bool,
+    force_takeover: bool,
+    owner: &str,
+) -> Result<ProjectConfig, String> {
     let project_root = PathBuf::from(path);
     ensure_project_root(&project_root)?;
     if !project_root.exists() {
         return Err("Project path does not exist".to_string());
     }
 
-    validate_project_structure(&project_root)?;
-    let summaries_path = project_root.join("summaries.json");
-    if !summaries_path.exists() {
-        let _ = fs::write(&summaries_path, "[]\n");
+    validate_project_structure(&project_root, auto_recover)?;
+    project_lock::acquire(&project_root, owner, force_takeover)?;
+    migrations::migrate_project(&project_root)?;
+    if let Some(recovery) = crate::chapter::ensure_index_readable(&project_root, auto_recover)? {
+        eprintln!(
+            "[open_project] recovered chapters/index.json from {} ({} chapters)",
+            recovery.source,
+            recovery.chapters.len()
+        );
     }
     let _ = fs::create_dir_all(project_root.join("knowledge"));
+    if let Err(e) = crate::word_history::snapshot_if_due(&project_root) {
+        eprintln!("[open_project] word count snapshot skipped: {e}");
+    }
     read_project_config(&project_root)
 }
 
@@ -225,20 +401,45 @@ fn save_project_config_sync(path: String, mut config: ProjectConfig) -> Result<(
     Ok(())
 }
 
-#[tauri::command]
-pub async fn create_project(path: String, name: String) -> Result<ProjectConfig, String> {
-    tauri::async_runtime::spawn_blocking(move || create_project_sync(path, name))
-        .await
-        .map_err(|e| format!("Task join error: {e}"))?
+fn set_project_read_only_sync(path: String, read_only: bool) -> Result<ProjectConfig, String> {
+    let mut config = get_project_info_sync(path.clone())?;
+    config.settings.read_only = read_only;
+    save_project_config_sync(path.clone(), config)?;
+    get_project_info_sync(path)
 }
 
 #[tauri::command]
-pub async fn open_project(path: String) -> Result<ProjectConfig, String> {
-    tauri::async_runtime::spawn_blocking(move || open_project_sync(path))
+pub async fn create_project(
+    window: tauri::Window,
+    path: String,
+    name: String,
+) -> Result<ProjectConfig, String> {
+    let owner = window.label().to_string();
+    tauri::async_runtime::spawn_blocking(move || create_project_sync(path, name, &owner))
         .await
         .map_err(|e| format!("Task join error: {e}"))?
 }
 
+#[tauri::command(rename_all = "camelCase")]
+pub async fn open_project(
+    window: tauri::Window,
+    path: String,
+    auto_recover: Option<bool>,
+    force_takeover: Option<bool>,
+) -> Result<ProjectConfig, String> {
+    let owner = window.label().to_string();
+    tauri::async_runtime::spawn_blocking(move || {
+        open_project_sync(
+            path,
+            auto_recover.unwrap_or(false),
+            force_takeover.unwrap_or(false),
+            &owner,
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
 #[tauri::command]
 pub async fn get_project_info(path: String) -> Result<ProjectConfig, String> {
     tauri::async_runtime::spawn_blocking(move || get_project_info_sync(path))
@@ -252,3 +453,12 @@ pub async fn save_project_config(path: String, config: ProjectConfig) -> Result<
         .await
         .map_err(|e| format!("Task join error: {e}"))?
 }
+
+/// Turns the project's read-only mode on or off; reads, searches and exports keep working
+/// while it is on.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn set_project_read_only(path: String, read_only: bool) -> Result<ProjectConfig, String> {
+    tauri::async_runtime::spawn_blocking(move || set_project_read_only_sync(path, read_only))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
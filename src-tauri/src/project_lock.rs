@@ -0,0 +1,363 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const LOCK_RELATIVE_PATH: &str = ".creatorai/lock";
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+/// A lock whose heartbeat is older than this is considered abandoned.
+const STALE_AFTER_SECS: u64 = 120;
+
+/// Prefix of the error returned when another live instance holds the lock, so the UI can
+/// offer a "force takeover" action.
+pub const PROJECT_LOCKED_ERROR_PREFIX: &str = "PROJECT_LOCKED";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockInfo {
+    pub pid: u32,
+    pub instance_id: String,
+    pub acquired_at: u64,
+    pub heartbeat: u64,
+}
+
+static INSTANCE_ID: OnceLock<String> = OnceLock::new();
+/// Locks held by this process, keyed by project root, with the window that owns each one.
+static HELD_LOCKS: OnceLock<Mutex<HashMap<PathBuf, String>>> = OnceLock::new();
+static HEARTBEAT_STARTED: OnceLock<()> = OnceLock::new();
+
+fn instance_id() -> &'static str {
+    INSTANCE_ID.get_or_init(|| uuid::Uuid::new_v4().to_string())
+}
+
+fn held_locks() -> MutexGuard<'static, HashMap<PathBuf, String>> {
+    HELD_LOCKS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+fn now_unix_seconds() -> Result<u64, String> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .map_err(|e| format!("Failed to read system time: {e}"))
+}
+
+fn lock_path(project_root: &Path) -> PathBuf {
+    project_root.join(LOCK_RELATIVE_PATH)
+}
+
+fn read_lock(project_root: &Path) -> Option<LockInfo> {
+    let bytes = fs::read(lock_path(project_root)).ok()?;
+    serde_json::from_slice::<LockInfo>(&bytes).ok()
+}
+
+fn lock_content(info: &LockInfo) -> Result<String, String> {
+    let content =
+        serde_json::to_string_pretty(info).map_err(|e| format!("Serialize JSON failed: {e}"))?;
+    Ok(format!("{content}\n"))
+}
+
+fn write_lock(project_root: &Path, info: &LockInfo) -> Result<(), String> {
+    let path = lock_path(project_root);
+    crate::write_protection::atomic_write_bytes(&path, lock_content(info)?.as_bytes(), None)
+        .map_err(|e| format!("Failed to write project lock: {e}"))
+}
+
+/// Creates the lock file only if none exists, so two openers can't both win.
+fn create_lock(project_root: &Path, info: &LockInfo) -> std::io::Result<()> {
+    let path = lock_path(project_root);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = fs::OpenOptions::new().write(true).create_new(true).open(&path)?;
+    let written = lock_content(info)
+        .map_err(std::io::Error::other)
+        .and_then(|content| file.write_all(content.as_bytes()));
+    if written.is_err() {
+        let _ = fs::remove_file(&path);
+    }
+    written
+}
+
+/// Moves the current lock file out of the way. Fails with `NotFound` when another opener
+/// replaced it first.
+fn discard_lock(project_root: &Path) -> std::io::Result<()> {
+    let path = lock_path(project_root);
+    let discarded = path.with_extension(format!("{}.stale", uuid::Uuid::new_v4()));
+    fs::rename(&path, &discarded)?;
+    let _ = fs::remove_file(discarded);
+    Ok(())
+}
+
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    // Signal 0 only checks for existence; EPERM still means the process exists.
+    let res = unsafe { libc::kill(pid as i32, 0) };
+    res == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+fn process_alive(_pid: u32) -> bool {
+    // No cheap liveness probe here; rely on the heartbeat age instead.
+    true
+}
+
+fn is_live_foreign_lock(info: &LockInfo, now: u64) -> bool {
+    if info.instance_id == instance_id() {
+        return false;
+    }
+    if now.saturating_sub(info.heartbeat) > STALE_AFTER_SECS {
+        return false;
+    }
+    process_alive(info.pid)
+}
+
+/// A lock file that exists but can't be parsed is still being written unless it is old.
+fn is_fresh_unreadable_lock(project_root: &Path) -> bool {
+    fs::metadata(lock_path(project_root))
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age.as_secs() <= STALE_AFTER_SECS)
+}
+
+fn locked_error(detail: &str) -> String {
+    format!("{PROJECT_LOCKED_ERROR_PREFIX}: 项目已在其他窗口中打开 ({detail})")
+}
+
+fn start_heartbeat() {
+    HEARTBEAT_STARTED.get_or_init(|| {
+        std::thread::spawn(|| loop {
+            std::thread::sleep(HEARTBEAT_INTERVAL);
+            let roots: Vec<PathBuf> = held_locks().keys().cloned().collect();
+            for root in roots {
+                if refresh(&root).is_err() {
+                    held_locks().remove(&root);
+                }
+            }
+        });
+    });
+}
+
+/// Re-stamps the heartbeat of a lock held by this instance. Fails if the lock was taken over.
+fn refresh(project_root: &Path) -> Result<(), String> {
+    let Some(mut info) = read_lock(project_root) else {
+        return Err("Project lock disappeared".to_string());
+    };
+    if info.instance_id != instance_id() {
+        return Err("Project lock was taken over by another instance".to_string());
+    }
+    info.heartbeat = now_unix_seconds()?;
+    write_lock(project_root, &info)
+}
+
+/// Writes this instance's lock file, replacing a stale one, or a live one when
+/// `force_takeover` is set.
+fn claim_lock_file(project_root: &Path, force_takeover: bool) -> Result<LockInfo, String> {
+    // A few rounds, in case another opener replaces the lock between our steps.
+    for _ in 0..3 {
+        let now = now_unix_seconds()?;
+        let mut info = LockInfo {
+            pid: std::process::id(),
+            instance_id: instance_id().to_string(),
+            acquired_at: now,
+            heartbeat: now,
+        };
+        match create_lock(project_root, &info) {
+            Ok(()) => return Ok(info),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(format!("Failed to write project lock: {e}")),
+        }
+
+        match read_lock(project_root) {
+            Some(existing) if existing.instance_id == instance_id() => {
+                info.acquired_at = existing.acquired_at;
+                write_lock(project_root, &info)?;
+                return Ok(info);
+            }
+            Some(existing) if is_live_foreign_lock(&existing, now) && !force_takeover => {
+                return Err(locked_error(&format!("pid {}", existing.pid)));
+            }
+            None if is_fresh_unreadable_lock(project_root) && !force_takeover => {
+                return Err(locked_error("lock is being written"));
+            }
+            _ => {}
+        }
+        match discard_lock(project_root) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("Failed to replace project lock: {e}")),
+        }
+    }
+    Err(locked_error("lock kept changing"))
+}
+
+/// Takes the advisory lock for `project_root` on behalf of window `owner`. A live lock from
+/// another instance, or from another window of this one, is only replaced when
+/// `force_takeover` is set; stale locks are reclaimed silently. Other projects `owner` held
+/// are released, since a window shows one project at a time.
+pub fn acquire(project_root: &Path, owner: &str, force_takeover: bool) -> Result<LockInfo, String> {
+    let project_root = project_root
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path: {e}"))?;
+
+    if let Some(holder) = held_locks().get(&project_root) {
+        if holder != owner && !force_takeover {
+            return Err(locked_error(&format!("window {holder}")));
+        }
+    }
+    let info = claim_lock_file(&project_root, force_takeover)?;
+
+    let previous: Vec<PathBuf> = {
+        let mut held = held_locks();
+        held.insert(project_root.clone(), owner.to_string());
+        held.iter()
+            .filter(|(root, holder)| **root != project_root && holder.as_str() == owner)
+            .map(|(root, _)| root.clone())
+            .collect()
+    };
+    for root in previous {
+        if let Err(e) = release(&root, owner) {
+            eprintln!("[project_lock] Failed to release {}: {e}", root.display());
+        }
+    }
+    start_heartbeat();
+    Ok(info)
+}
+
+/// Drops the lock if window `owner` holds it and this instance still owns the file.
+pub fn release(project_root: &Path, owner: &str) -> Result<(), String> {
+    let project_root = project_root
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path: {e}"))?;
+    {
+        let mut held = held_locks();
+        if held.get(&project_root).map(String::as_str) != Some(owner) {
+            return Ok(());
+        }
+        held.remove(&project_root);
+    }
+    match read_lock(&project_root) {
+        Some(info) if info.instance_id == instance_id() => {
+            match fs::remove_file(lock_path(&project_root)) {
+                Ok(_) => Ok(()),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                Err(e) => Err(format!("Failed to remove project lock: {e}")),
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Releases every lock window `owner` holds, e.g. when the window is destroyed.
+pub fn release_owned_by(owner: &str) {
+    let roots: Vec<PathBuf> = held_locks()
+        .iter()
+        .filter(|(_, holder)| holder.as_str() == owner)
+        .map(|(root, _)| root.clone())
+        .collect();
+    for root in roots {
+        if let Err(e) = release(&root, owner) {
+            eprintln!("[project_lock] Failed to release {}: {e}", root.display());
+        }
+    }
+}
+
+/// Releases every lock this process holds; called on exit.
+pub fn release_all() {
+    let held: Vec<(PathBuf, String)> = held_locks()
+        .iter()
+        .map(|(root, owner)| (root.clone(), owner.clone()))
+        .collect();
+    for (root, owner) in held {
+        if let Err(e) = release(&root, &owner) {
+            eprintln!("[project_lock] Failed to release {}: {e}", root.display());
+        }
+    }
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn release_project_lock(window: tauri::Window, project_path: String) -> Result<(), String> {
+    let owner = window.label().to_string();
+    tauri::async_runtime::spawn_blocking(move || release(Path::new(&project_path), &owner))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn temp_project(prefix: &str) -> TempDir {
+        let temp = TempDir::new(prefix);
+        fs::create_dir_all(temp.path.join(".creatorai")).expect("create temp dir");
+        temp
+    }
+
+    fn foreign_lock(heartbeat: u64) -> LockInfo {
+        LockInfo {
+            pid: std::process::id(),
+            instance_id: "other-instance".to_string(),
+            acquired_at: heartbeat,
+            heartbeat,
+        }
+    }
+
+    #[test]
+    fn live_foreign_lock_requires_force_takeover() {
+        let temp = temp_project("creatorai-v2-lock");
+        let now = now_unix_seconds().unwrap();
+        write_lock(&temp.path, &foreign_lock(now)).unwrap();
+
+        let err = acquire(&temp.path, "main", false).expect_err("live lock should block");
+        assert!(err.starts_with(PROJECT_LOCKED_ERROR_PREFIX));
+
+        let taken = acquire(&temp.path, "main", true).expect("force takeover");
+        assert_eq!(taken.instance_id, instance_id());
+
+        release(&temp.path, "main").expect("release");
+        assert!(!lock_path(&temp.path).exists());
+    }
+
+    #[test]
+    fn stale_lock_is_reclaimed() {
+        let temp = temp_project("creatorai-v2-lock-stale");
+        let now = now_unix_seconds().unwrap();
+        write_lock(&temp.path, &foreign_lock(now - STALE_AFTER_SECS - 10)).unwrap();
+
+        let info = acquire(&temp.path, "main", false).expect("stale lock is reclaimed");
+        assert_eq!(info.pid, std::process::id());
+        release(&temp.path, "main").expect("release");
+    }
+
+    #[test]
+    fn second_window_needs_takeover_and_switching_releases_the_old_project() {
+        let first = temp_project("creatorai-v2-lock-window-a");
+        let second = temp_project("creatorai-v2-lock-window-b");
+
+        acquire(&first.path, "window-a", false).expect("first window");
+        acquire(&first.path, "window-a", false).expect("reopen in the same window");
+        let err = acquire(&first.path, "window-b", false).expect_err("second window is blocked");
+        assert!(err.starts_with(PROJECT_LOCKED_ERROR_PREFIX));
+
+        // Another window's release must not drop the lock.
+        release(&first.path, "window-b").expect("release by non-owner");
+        assert!(lock_path(&first.path).exists());
+
+        acquire(&first.path, "window-b", true).expect("takeover by the second window");
+        acquire(&second.path, "window-b", false).expect("switch project");
+        assert!(!lock_path(&first.path).exists());
+        acquire(&first.path, "window-a", false).expect("released project can be reopened");
+
+        release_owned_by("window-a");
+        release_owned_by("window-b");
+        assert!(!lock_path(&first.path).exists());
+        assert!(!lock_path(&second.path).exists());
+    }
+}
@@ -12,7 +12,7 @@ use std::sync::{Mutex, MutexGuard, OnceLock};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::project::{ChapterIndex, ChapterMeta};
-use crate::security::validate_path;
+use crate::security::{validate_path, SymlinkRules};
 use crate::summary::{self, SummaryEntry};
 use crate::write_protection;
 
@@ -26,7 +26,7 @@ const LOCAL_EMBEDDING_MODEL_DIR: &str = ".creatorai/rag/models/Xenova/bge-small-
 const LOCAL_EMBEDDING_MODEL_NAME: &str = "Xenova/bge-small-zh-v1.5";
 const HF_CACHE_DIR: &str = ".creatorai/rag/hf-cache";
 const HF_MIRROR_ENDPOINT: &str = "https://hf-mirror.com";
-const RAG_API_SECRET_PREFIX: &str = "rag_embedding_api";
+pub(crate) const RAG_API_SECRET_PREFIX: &str = "rag_embedding_api";
 
 fn now_unix_seconds() -> Result<u64, String> {
     SystemTime::now()
@@ -321,19 +321,33 @@ fn is_supported_doc_path(path: &Path) -> bool {
     matches!(ext.to_ascii_lowercase().as_str(), "txt" | "md" | "markdown")
 }
 
-fn read_dir_recursive(root: &Path) -> Result<Vec<PathBuf>, String> {
+/// Files below `root`, following only the symlinks the project's policy allows.
+fn read_dir_recursive(project_root: &Path, root: &Path) -> Result<Vec<PathBuf>, String> {
+    let rules = SymlinkRules::for_project(project_root);
+    let mut visited: HashSet<PathBuf> = root.canonicalize().into_iter().collect();
     let mut out = Vec::new();
     let mut stack = vec![root.to_path_buf()];
     while let Some(dir) = stack.pop() {
         let entries = fs::read_dir(&dir).map_err(|e| format!("Failed to read dir: {e}"))?;
         for entry in entries.flatten() {
             let path = entry.path();
-            let Ok(meta) = entry.metadata() else {
+            let Ok(file_type) = entry.file_type() else {
                 continue;
             };
-            if meta.is_dir() {
+            if file_type.is_symlink() {
+                let Some(target) = rules.follow(project_root, &path) else {
+                    continue;
+                };
+                if target.is_dir() {
+                    if visited.insert(target) {
+                        stack.push(path);
+                    }
+                } else if target.is_file() {
+                    out.push(path);
+                }
+            } else if file_type.is_dir() {
                 stack.push(path);
-            } else if meta.is_file() {
+            } else if file_type.is_file() {
                 out.push(path);
             }
         }
@@ -377,9 +391,11 @@ pub fn list_docs(project_root: &Path) -> Result<Vec<KnowledgeDoc>, String> {
     let config = load_config(&project_root)?;
     let enabled: HashSet<String> = config.enabled_paths.into_iter().collect();
 
-    let knowledge_abs = validate_path(&project_root, KNOWLEDGE_DIR)?;
+    validate_path(&project_root, KNOWLEDGE_DIR)?;
+    // Walk through the link when `knowledge/` is itself a symlink so paths stay relative.
+    let knowledge_abs = project_root.join(KNOWLEDGE_DIR);
     let mut docs = Vec::new();
-    for abs in read_dir_recursive(&knowledge_abs)? {
+    for abs in read_dir_recursive(&project_root, &knowledge_abs)? {
         if !is_supported_doc_path(&abs) {
             continue;
         }
@@ -432,6 +448,7 @@ pub fn read_doc(project_root: &Path, doc_path: &str) -> Result<String, String> {
     if !abs.exists() {
         return Err("Doc not found".to_string());
     }
+    write_protection::check_read_size(&abs)?;
     fs::read_to_string(&abs).map_err(|e| format!("Failed to read doc: {e}"))
 }
 
@@ -439,12 +456,14 @@ pub fn write_doc(project_root: &Path, doc_path: &str, content: &str) -> Result<(
     let project_root = project_root
         .canonicalize()
         .map_err(|e| format!("Invalid project path: {e}"))?;
+    write_protection::ensure_writable(&project_root)?;
     ensure_knowledge_dir(&project_root)?;
     let doc_path = normalize_doc_path(doc_path)?;
     let abs = validate_path(&project_root, &doc_path)?;
     if !is_supported_doc_path(&abs) {
         return Err("Only .txt/.md files are supported".to_string());
     }
+    write_protection::check_write_size(&project_root, &abs, content.len() as u64)?;
     write_protection::write_string_with_backup(&project_root, &abs, content).map(|_| ())
 }
 
@@ -452,6 +471,7 @@ pub fn append_doc(project_root: &Path, doc_path: &str, content: &str) -> Result<
     let project_root = project_root
         .canonicalize()
         .map_err(|e| format!("Invalid project path: {e}"))?;
+    write_protection::ensure_writable(&project_root)?;
     ensure_knowledge_dir(&project_root)?;
     let doc_path = normalize_doc_path(doc_path)?;
     let abs = validate_path(&project_root, &doc_path)?;
@@ -471,6 +491,7 @@ pub fn append_doc(project_root: &Path, doc_path: &str, content: &str) -> Result<
     if !next.ends_with('\n') {
         next.push('\n');
     }
+    write_protection::check_write_size(&project_root, &abs, next.len() as u64)?;
     write_protection::write_string_with_backup(&project_root, &abs, &next).map(|_| ())
 }
 
@@ -1110,7 +1131,7 @@ pub fn get_writing_context(
         });
     }
 
-    let summaries = summary::load_summaries(&project_root)?;
+    let summaries = summary::load_summaries(&project_root)?.summaries;
     let latest_summaries = latest_summary_by_chapter(&summaries);
     let mut ordered_chapters = index.chapters.clone();
     ordered_chapters.sort_by_key(|chapter| chapter.order);
@@ -1124,7 +1145,7 @@ pub fn get_writing_context(
                 .find(|entry| entry.chapter_id == chapter.id)
                 .map(|entry| WritingContextSection {
                     kind: "summary".to_string(),
-                    source: "summaries.json".to_string(),
+                    source: format!("summaries/{}.json", entry.chapter_id),
                     title: format!("章节摘要 · {}", chapter.title),
                     text: entry.summary.clone(),
                     score: None,
@@ -1404,4 +1425,81 @@ mod tests {
 
         let _ = fs::remove_dir_all(root);
     }
+
+    #[cfg(unix)]
+    fn write_symlink_policy(root: &Path, policy: &str, allowlist: &[&Path]) {
+        let config = serde_json::json!({
+            "name": "test",
+            "created": 1,
+            "updated": 1,
+            "version": "1",
+            "settings": {
+                "autoSave": true,
+                "autoSaveInterval": 2000,
+                "followSymlinks": policy,
+                "symlinkAllowlist": allowlist.iter().map(|p| p.to_string_lossy()).collect::<Vec<_>>(),
+            },
+        });
+        fs::write(root.join(".creatorai/config.json"), config.to_string()).unwrap();
+    }
+
+    #[cfg(unix)]
+    fn doc_paths(root: &Path) -> Result<Vec<String>, String> {
+        Ok(list_docs(root)?.into_iter().map(|d| d.path).collect())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_knowledge_dir_inside_project_follows_policy() {
+        let root = create_test_project("symlink-inside");
+        fs::create_dir_all(root.join("shared/kb")).unwrap();
+        fs::write(root.join("shared/kb/lore.md"), "北境设定").unwrap();
+        std::os::unix::fs::symlink(root.join("shared/kb"), root.join("knowledge")).unwrap();
+
+        assert_eq!(doc_paths(&root).expect("within project"), ["knowledge/lore.md"]);
+        let hits = crate::file_ops::search_in_files(
+            &root,
+            crate::file_ops::SearchParams {
+                query: "北境".to_string(),
+                path: Some("knowledge".to_string()),
+                regex: false,
+                case_sensitive: None,
+                context: None,
+                max_matches: None,
+            },
+        )
+        .expect("search");
+        assert_eq!(hits.matches.len(), 1);
+        assert_eq!(hits.matches[0].file, "knowledge/lore.md");
+
+        write_symlink_policy(&root, "deny", &[]);
+        assert!(doc_paths(&root).is_err());
+        assert!(validate_path(&root, "knowledge/lore.md").is_err());
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_knowledge_dir_outside_project_needs_allowlist() {
+        let root = create_test_project("symlink-outside");
+        let synced = unique_temp_project_dir("symlink-synced");
+        fs::create_dir_all(synced.join("kb")).unwrap();
+        fs::write(synced.join("kb/atlas.md"), "地图").unwrap();
+        std::os::unix::fs::symlink(synced.join("kb"), root.join("knowledge")).unwrap();
+
+        assert!(doc_paths(&root).is_err());
+        write_symlink_policy(&root, "allow_listed", &[&synced.join("other")]);
+        assert!(doc_paths(&root).is_err());
+
+        write_symlink_policy(&root, "allow_listed", &[&synced.join("kb")]);
+        assert_eq!(doc_paths(&root).expect("allowlisted"), ["knowledge/atlas.md"]);
+        assert_eq!(
+            fs::read_to_string(validate_path(&root, "knowledge/atlas.md").expect("resolve")).unwrap(),
+            "地图"
+        );
+
+        let _ = fs::remove_dir_all(root);
+        let _ = fs::remove_dir_all(synced);
+    }
 }
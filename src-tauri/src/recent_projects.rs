@@ -1,14 +1,25 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::project::ChapterIndex;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RecentProject {
     pub name: String,
     pub path: String,
     pub last_opened: u64,
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub word_count: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chapter_count: Option<u32>,
+    /// When `word_count` / `chapter_count` were last read successfully.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats_updated_at: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +33,8 @@ impl Default for RecentProjectsFile {
     }
 }
 
+const MAX_UNPINNED_RECENT: usize = 20;
+
 fn now_unix_seconds() -> Result<u64, String> {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -68,13 +81,77 @@ fn save_recent_file(file: &RecentProjectsFile) -> Result<(), String> {
     fs::write(path, format!("{content}\n")).map_err(|e| e.to_string())
 }
 
+pub(crate) fn read_project_stats(project_path: &str) -> Option<(u64, u32)> {
+    let index_path = Path::new(project_path).join("chapters").join("index.json");
+    let bytes = fs::read(index_path).ok()?;
+    let index = serde_json::from_slice::<ChapterIndex>(&bytes).ok()?;
+    let words = index.chapters.iter().map(|c| u64::from(c.word_count)).sum();
+    Some((words, index.chapters.len() as u32))
+}
+
+/// Re-reads the chapter index of `item`. Unreadable projects keep their previous
+/// numbers; `stats_updated_at` is left untouched so the UI can tell they are stale.
+fn refresh_stats(item: &mut RecentProject, now: u64) -> bool {
+    let Some((words, chapters)) = read_project_stats(&item.path) else {
+        return false;
+    };
+    item.word_count = Some(words);
+    item.chapter_count = Some(chapters);
+    item.stats_updated_at = Some(now);
+    true
+}
+
+fn sort_recent(recent: &mut [RecentProject]) {
+    recent.sort_by(|a, b| {
+        b.pinned
+            .cmp(&a.pinned)
+            .then_with(|| b.last_opened.cmp(&a.last_opened))
+    });
+}
+
+/// Drops the oldest unpinned entries until at most `MAX_UNPINNED_RECENT` remain.
+/// Pinned projects never count against the cap.
+fn evict_unpinned(recent: &mut Vec<RecentProject>) {
+    let unpinned = recent.iter().filter(|p| !p.pinned).count();
+    if unpinned <= MAX_UNPINNED_RECENT {
+        return;
+    }
+
+    // Sorted pinned-first, newest-first: the last unpinned entry is always the oldest.
+    sort_recent(recent);
+    for _ in 0..(unpinned - MAX_UNPINNED_RECENT) {
+        let Some(pos) = recent.iter().rposition(|p| !p.pinned) else {
+            break;
+        };
+        recent.remove(pos);
+    }
+}
+
 #[tauri::command]
 pub fn get_recent_projects() -> Result<Vec<RecentProject>, String> {
     let mut file = load_recent_file()?;
-    file.recent.sort_by(|a, b| b.last_opened.cmp(&a.last_opened));
+    sort_recent(&mut file.recent);
     Ok(file.recent)
 }
 
+#[tauri::command]
+pub fn set_recent_project_pinned(path: String, pinned: bool) -> Result<(), String> {
+    let path = path.trim().to_string();
+    if path.is_empty() {
+        return Err("Project path is empty".to_string());
+    }
+
+    let mut file = load_recent_file()?;
+    let Some(item) = file.recent.iter_mut().find(|p| p.path == path) else {
+        return Err(format!("Recent project not found: {path}"));
+    };
+    item.pinned = pinned;
+
+    sort_recent(&mut file.recent);
+    evict_unpinned(&mut file.recent);
+    save_recent_file(&file)
+}
+
 #[tauri::command]
 pub fn add_recent_project(name: String, path: String) -> Result<(), String> {
     let name = name.trim().to_string();
@@ -92,15 +169,93 @@ pub fn add_recent_project(name: String, path: String) -> Result<(), String> {
     if let Some(item) = file.recent.iter_mut().find(|p| p.path == path) {
         item.name = name;
         item.last_opened = now;
+        refresh_stats(item, now);
     } else {
-        file.recent.push(RecentProject {
+        let mut item = RecentProject {
             name,
             path,
             last_opened: now,
-        });
+            pinned: false,
+            word_count: None,
+            chapter_count: None,
+            stats_updated_at: None,
+        };
+        refresh_stats(&mut item, now);
+        file.recent.push(item);
     }
 
-    file.recent.sort_by(|a, b| b.last_opened.cmp(&a.last_opened));
+    evict_unpinned(&mut file.recent);
+    sort_recent(&mut file.recent);
     save_recent_file(&file)
 }
 
+#[tauri::command]
+pub fn refresh_recent_project_stats() -> Result<Vec<RecentProject>, String> {
+    let mut file = load_recent_file()?;
+    let now = now_unix_seconds()?;
+
+    let mut changed = false;
+    for item in file.recent.iter_mut() {
+        changed |= refresh_stats(item, now);
+    }
+
+    sort_recent(&mut file.recent);
+    if changed {
+        save_recent_file(&file)?;
+    }
+    Ok(file.recent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(path: &str, last_opened: u64, pinned: bool) -> RecentProject {
+        RecentProject {
+            name: path.to_string(),
+            path: path.to_string(),
+            last_opened,
+            pinned,
+            word_count: None,
+            chapter_count: None,
+            stats_updated_at: None,
+        }
+    }
+
+    #[test]
+    fn legacy_entries_without_pinned_field_still_load() {
+        let json = r#"{ "recent": [ { "name": "A", "path": "/a", "lastOpened": 1 } ] }"#;
+        let file: RecentProjectsFile = serde_json::from_str(json).expect("parse legacy recent.json");
+        assert_eq!(file.recent.len(), 1);
+        assert!(!file.recent[0].pinned);
+    }
+
+    #[test]
+    fn eviction_drops_oldest_unpinned_and_keeps_pinned() {
+        let mut recent = vec![project("/pinned-old", 0, true)];
+        for i in 0..(MAX_UNPINNED_RECENT as u64 + 3) {
+            recent.push(project(&format!("/p{i}"), i + 1, false));
+        }
+
+        evict_unpinned(&mut recent);
+
+        assert_eq!(recent.len(), MAX_UNPINNED_RECENT + 1);
+        assert_eq!(recent[0].path, "/pinned-old");
+        assert!(!recent.iter().any(|p| p.path == "/p0"));
+        assert!(!recent.iter().any(|p| p.path == "/p2"));
+        assert!(recent.iter().any(|p| p.path == "/p3"));
+    }
+
+    #[test]
+    fn unreadable_project_keeps_stale_stats() {
+        let mut item = project("/definitely/not/a/project", 1, false);
+        item.word_count = Some(1200);
+        item.chapter_count = Some(3);
+        item.stats_updated_at = Some(10);
+
+        assert!(!refresh_stats(&mut item, 99));
+        assert_eq!(item.word_count, Some(1200));
+        assert_eq!(item.chapter_count, Some(3));
+        assert_eq!(item.stats_updated_at, Some(10));
+    }
+}
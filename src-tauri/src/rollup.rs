@@ -0,0 +1,302 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::project::ChapterMeta;
+use crate::security::validate_path;
+use crate::summary::{self, ChapterSummaryRef};
+use crate::write_protection;
+
+const ROLLUP_RELATIVE_PATH: &str = ".creatorai/rollup.json";
+/// Summary text sent for consolidation; the oldest chapters are dropped first.
+const ROLLUP_INPUT_CHAR_BUDGET: usize = 40_000;
+
+pub const ROLLUP_SYSTEM_PROMPT: &str = "你是一个小说编辑助手。你将收到按顺序排列的各章摘要，请把它们整合成一份连贯的“前情提要”，帮助续写时快速掌握全书进展。
+
+要求：
+- 按故事时间顺序叙述，保留主线事件、关键转折、人物关系变化与尚未回收的伏笔
+- 合并重复信息，省略无关细节，篇幅控制在 300-800 字
+- 只陈述摘要中已有的内容，不要脑补
+- 不要使用 Markdown 标题，只输出前情提要正文";
+
+/// A consolidated summary of every chapter from the first one up to `up_to_chapter_id`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StoryRollup {
+    pub from_chapter_id: String,
+    pub up_to_chapter_id: String,
+    /// Chapters covered, in story order, when the rollup was generated.
+    pub chapter_ids: Vec<String>,
+    pub content: String,
+    pub generated_at: u64,
+    /// SHA-256 of each covered chapter's latest summary as sent for consolidation; chapters
+    /// without a summary are absent. Empty in rollups saved before it existed.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub summary_hashes: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RollupFile {
+    /// Keyed by `"{from}..{up_to}"`.
+    rollups: BTreeMap<String, StoryRollup>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RollupStatus {
+    pub rollup: StoryRollup,
+    /// Chapters were added, removed or reordered in the range, or their summaries changed.
+    pub stale: bool,
+    /// Chapters whose latest summary differs from the one the rollup was made from.
+    pub changed_chapter_ids: Vec<String>,
+}
+
+fn now_unix_seconds() -> Result<u64, String> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .map_err(|e| format!("Failed to read system time: {e}"))
+}
+
+fn read_rollups(project_root: &Path) -> Result<RollupFile, String> {
+    let path = validate_path(project_root, ROLLUP_RELATIVE_PATH)?;
+    if !path.exists() {
+        return Ok(RollupFile::default());
+    }
+    let bytes = fs::read(&path).map_err(|e| format!("Failed to read rollup.json: {e}"))?;
+    serde_json::from_slice::<RollupFile>(&bytes).map_err(|e| format!("Failed to parse rollup.json: {e}"))
+}
+
+fn write_rollups(project_root: &Path, file: &RollupFile) -> Result<(), String> {
+    let path = validate_path(project_root, ROLLUP_RELATIVE_PATH)?;
+    let json = serde_json::to_string_pretty(file).map_err(|e| format!("Serialize JSON failed: {e}"))?;
+    write_protection::write_string_with_backup(project_root, &path, &format!("{json}\n"))?;
+    Ok(())
+}
+
+fn canonical_root(project_root: &Path) -> Result<std::path::PathBuf, String> {
+    project_root
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path: {e}"))
+}
+
+/// Chapters from the first one through `up_to_chapter_id`, in story order.
+fn covered_chapters(project_root: &Path, up_to_chapter_id: &str) -> Result<Vec<ChapterMeta>, String> {
+    let mut chapters = crate::chapter::list_chapters_sync(project_root.to_string_lossy().into_owned())?;
+    let position = chapters
+        .iter()
+        .position(|c| c.id == up_to_chapter_id)
+        .ok_or(format!("Chapter not found: {up_to_chapter_id}"))?;
+    chapters.truncate(position + 1);
+    Ok(chapters)
+}
+
+/// Chapter summaries for the rollup prompt plus the chapters that are covered.
+pub fn rollup_sources(
+    project_root: &Path,
+    up_to_chapter_id: &str,
+) -> Result<(Vec<ChapterMeta>, Vec<ChapterSummaryRef>), String> {
+    let project_root = canonical_root(project_root)?;
+    let chapters = covered_chapters(&project_root, up_to_chapter_id)?;
+    let refs = summary::summary_refs(&project_root, &chapters)?;
+    if !refs
+        .iter()
+        .any(|r| r.summary.as_deref().is_some_and(|s| !s.trim().is_empty()))
+    {
+        return Err("所选范围内的章节都还没有摘要，请先生成章节摘要".to_string());
+    }
+    Ok((chapters, refs))
+}
+
+pub fn rollup_user_prompt(refs: &[ChapterSummaryRef]) -> String {
+    let sections: Vec<String> = refs
+        .iter()
+        .map(|r| {
+            let summary = r.summary.as_deref().map(str::trim).filter(|s| !s.is_empty());
+            format!("## {}\n{}", r.title, summary.unwrap_or("（暂无摘要）"))
+        })
+        .collect();
+
+    // Keep the newest chapters when everything doesn't fit.
+    let mut kept = Vec::new();
+    let mut used = 0;
+    for section in sections.iter().rev() {
+        let len = section.chars().count();
+        if used + len > ROLLUP_INPUT_CHAR_BUDGET && !kept.is_empty() {
+            break;
+        }
+        used += len;
+        kept.push(section.as_str());
+    }
+    kept.reverse();
+
+    let omitted = sections.len() - kept.len();
+    let note = if omitted > 0 {
+        format!("（篇幅所限，最早的 {omitted} 章摘要已省略）\n\n")
+    } else {
+        String::new()
+    };
+    format!("请把以下各章摘要整合成前情提要：\n\n{note}{}", kept.join("\n\n"))
+}
+
+/// Stores `content`, consolidated from `refs`, as the rollup for `chapters` (first through
+/// last) and returns it.
+pub fn save_story_rollup(
+    project_root: &Path,
+    chapters: &[ChapterMeta],
+    refs: &[ChapterSummaryRef],
+    content: String,
+) -> Result<StoryRollup, String> {
+    let (Some(first), Some(last)) = (chapters.first(), chapters.last()) else {
+        return Err("No chapters to cover".to_string());
+    };
+    let project_root = canonical_root(project_root)?;
+    let rollup = StoryRollup {
+        from_chapter_id: first.id.clone(),
+        up_to_chapter_id: last.id.clone(),
+        chapter_ids: chapters.iter().map(|c| c.id.clone()).collect(),
+        content,
+        generated_at: now_unix_seconds()?,
+        summary_hashes: refs
+            .iter()
+            .filter_map(|r| {
+                let summary = r.summary.as_deref()?;
+                Some((r.chapter_id.clone(), write_protection::sha256_hex(summary.as_bytes())))
+            })
+            .collect(),
+    };
+    let mut file = read_rollups(&project_root)?;
+    file.rollups.insert(
+        format!("{}..{}", rollup.from_chapter_id, rollup.up_to_chapter_id),
+        rollup.clone(),
+    );
+    write_rollups(&project_root, &file)?;
+    Ok(rollup)
+}
+
+fn rollup_status(project_root: &Path, rollup: StoryRollup) -> Result<RollupStatus, String> {
+    let chapter_ids: Vec<String> = covered_chapters(project_root, &rollup.up_to_chapter_id)
+        .map(|chapters| chapters.into_iter().map(|c| c.id).collect())
+        .unwrap_or_default();
+
+    let mut changed_chapter_ids = Vec::new();
+    for chapter_id in &chapter_ids {
+        let latest = summary::get_latest_summary(project_root, chapter_id)?;
+        let changed = if rollup.summary_hashes.is_empty() {
+            // Older rollups only have their timestamp; seconds are coarse, so a summary
+            // saved in the same second counts as changed.
+            latest.is_some_and(|l| l.updated_at.unwrap_or(0).max(l.created_at) >= rollup.generated_at)
+        } else {
+            let current = latest.map(|l| write_protection::sha256_hex(l.summary.as_bytes()));
+            current.as_ref() != rollup.summary_hashes.get(chapter_id)
+        };
+        if changed {
+            changed_chapter_ids.push(chapter_id.clone());
+        }
+    }
+
+    Ok(RollupStatus {
+        stale: chapter_ids != rollup.chapter_ids || !changed_chapter_ids.is_empty(),
+        changed_chapter_ids,
+        rollup,
+    })
+}
+
+/// The rollup ending at `up_to_chapter_id`, or when that is `None` the one reaching
+/// furthest into the story, with its staleness.
+pub fn get_story_rollup(
+    project_root: &Path,
+    up_to_chapter_id: Option<&str>,
+) -> Result<Option<RollupStatus>, String> {
+    let project_root = canonical_root(project_root)?;
+    let file = read_rollups(&project_root)?;
+    let order: BTreeMap<String, u32> =
+        crate::chapter::list_chapters_sync(project_root.to_string_lossy().into_owned())?
+            .into_iter()
+            .map(|c| (c.id, c.order))
+            .collect();
+
+    let candidates = file.rollups.into_values().filter(|r| match up_to_chapter_id {
+        Some(id) => r.up_to_chapter_id == id,
+        None => order.contains_key(&r.up_to_chapter_id),
+    });
+    let best = candidates.max_by_key(|r| (order.get(&r.up_to_chapter_id).copied(), r.generated_at));
+    best.map(|rollup| rollup_status(&project_root, rollup)).transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn temp_project(prefix: &str) -> TempDir {
+        let temp = TempDir::new(prefix);
+        fs::create_dir_all(temp.path.join(".creatorai")).expect("create temp dir");
+        fs::create_dir_all(temp.path.join("chapters")).expect("create chapters dir");
+        fs::write(temp.path.join(".creatorai/config.json"), "{}\n").unwrap();
+        fs::write(
+            temp.path.join("chapters/index.json"),
+            r#"{ "chapters": [
+  { "id": "chapter_001", "title": "第一章", "order": 1, "created": 1, "updated": 1, "wordCount": 0 },
+  { "id": "chapter_002", "title": "第二章", "order": 2, "created": 1, "updated": 1, "wordCount": 0 },
+  { "id": "chapter_003", "title": "第三章", "order": 3, "created": 1, "updated": 1, "wordCount": 0 }
+], "nextId": 4 }
+"#,
+        )
+        .unwrap();
+        temp
+    }
+
+    #[test]
+    fn rollup_is_stored_by_range_and_goes_stale_on_new_summaries() {
+        let temp = temp_project("creatorai-v2-rollup");
+        assert!(rollup_sources(&temp.path, "chapter_002").is_err());
+        summary::save_summary(
+            &temp.path,
+            "chapter_001".to_string(),
+            "主角入城。".to_string(),
+            summary::SummaryProvenance::manual(),
+        )
+        .expect("save summary");
+
+        let (chapters, refs) = rollup_sources(&temp.path, "chapter_002").expect("sources");
+        let prompt = rollup_user_prompt(&refs);
+        assert!(prompt.contains("## 第一章\n主角入城。"));
+        assert!(prompt.contains("## 第二章\n（暂无摘要）"));
+        assert!(!prompt.contains("第三章"));
+
+        let rollup = save_story_rollup(&temp.path, &chapters, &refs, "前情：主角入城。".to_string())
+            .expect("save rollup");
+        let stored = read_rollups(&temp.path).expect("read rollups");
+        assert!(stored.rollups.contains_key("chapter_001..chapter_002"));
+
+        let status = get_story_rollup(&temp.path, None).expect("get").expect("rollup exists");
+        assert!(!status.stale);
+        assert_eq!(status.rollup.chapter_ids, ["chapter_001", "chapter_002"]);
+        assert_eq!(get_story_rollup(&temp.path, Some("chapter_003")).expect("get"), None);
+
+        // A summary saved within the same second as the rollup is still noticed.
+        summary::save_summary(
+            &temp.path,
+            "chapter_002".to_string(),
+            "主角遇敌。".to_string(),
+            summary::SummaryProvenance::manual(),
+        )
+        .expect("save summary");
+        let status = get_story_rollup(&temp.path, None).expect("get").expect("rollup exists");
+        assert!(status.stale);
+        assert_eq!(status.changed_chapter_ids, ["chapter_002"]);
+
+        // Rollups without hashes fall back to timestamps.
+        let legacy = StoryRollup {
+            summary_hashes: BTreeMap::new(),
+            generated_at: 0,
+            ..rollup
+        };
+        let status = rollup_status(&temp.path.canonicalize().unwrap(), legacy).expect("status");
+        assert_eq!(status.changed_chapter_ids, ["chapter_001", "chapter_002"]);
+    }
+}
@@ -1,14 +1,204 @@
+use std::fs;
 use std::path::{Component, Path, PathBuf};
 
+use crate::config::ReferenceRoot;
+use crate::project::{ProjectSettings, SymlinkPolicy};
+
+/// Prefix for paths inside a configured reference root: `ext://<root-id>/relative`.
+pub const REFERENCE_PREFIX: &str = "ext://";
+
+/// Project state owned by dedicated modules; the generic file tools must not change it.
+pub const PROTECTED_PATHS: &[&str] = &[
+    ".creatorai/config.json",
+    "chapters/index.json",
+    "sessions/index.json",
+    ".creatorai/rag/index.bin",
+];
+
 pub fn validate_path(project_dir: &Path, relative_path: &str) -> Result<PathBuf, String> {
-    let project_dir = project_dir
+    validate_path_with(
+        project_dir,
+        relative_path,
+        &SymlinkRules::for_project(project_dir),
+    )
+}
+
+fn validate_path_with(
+    project_dir: &Path,
+    relative_path: &str,
+    rules: &SymlinkRules,
+) -> Result<PathBuf, String> {
+    if relative_path.trim_start().starts_with(REFERENCE_PREFIX) {
+        return Err("Reference roots are read-only".to_string());
+    }
+    resolve_within(project_dir, relative_path, "project_dir", rules)
+}
+
+fn path_key(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/").to_lowercase()
+}
+
+/// Rejects `relative_path` when it is, or lies in, a protected path: the built-in
+/// [`PROTECTED_PATHS`] plus the project's `protectedPaths`. Paths are compared after
+/// resolution and ignoring case, so `./`, symlinks and case-insensitive file systems
+/// can't be used to get around it.
+pub fn check_not_protected(project_dir: &Path, relative_path: &str) -> Result<(), String> {
+    let settings = crate::project::project_settings(project_dir).ok();
+    let rules = settings
+        .as_ref()
+        .map(SymlinkRules::from_settings)
+        .unwrap_or_default();
+    let target = path_key(&validate_path_with(project_dir, relative_path, &rules)?);
+    let extra = settings.map(|s| s.protected_paths).unwrap_or_default();
+    let entries = PROTECTED_PATHS
+        .iter()
+        .copied()
+        .chain(extra.iter().map(String::as_str));
+    for entry in entries {
+        let Ok(protected) = validate_path_with(project_dir, entry.trim_end_matches('/'), &rules)
+        else {
+            continue;
+        };
+        let protected = path_key(&protected);
+        let is_dir = entry.ends_with('/');
+        if target == protected || (is_dir && target.starts_with(&format!("{protected}/"))) {
+            return Err(format!(
+                "'{}' is a protected project file — use the dedicated API",
+                relative_path.trim()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Longest run of newly created path components accepted, in UTF-16 units; leaves room for
+/// the project dir under Windows' 260-character limit.
+const MAX_NEW_PATH_UNITS: usize = 200;
+/// Per-component limit shared by NTFS, APFS and ext4 (in UTF-16 units on Windows).
+const MAX_NAME_UNITS: usize = 255;
+const WINDOWS_RESERVED_NAMES: &[&str] = &["CON", "PRN", "AUX", "NUL"];
+const WINDOWS_INVALID_CHARS: &[char] = &['<', '>', ':', '"', '|', '?', '*'];
+
+/// Why `name` can't be used as a file or directory name on every platform, if it can't.
+fn non_portable_name(name: &str) -> Option<String> {
+    if let Some(c) = name.chars().find(|c| c.is_control()) {
+        return Some(format!(
+            "'{}' contains a control character (U+{:04X})",
+            name.escape_debug(),
+            c as u32
+        ));
+    }
+    if let Some(c) = name.chars().find(|c| WINDOWS_INVALID_CHARS.contains(c)) {
+        return Some(format!(
+            "'{name}' contains '{c}', which Windows does not allow"
+        ));
+    }
+    if name.ends_with('.') || name.ends_with(' ') {
+        return Some(format!(
+            "'{name}' ends with a dot or space, which Windows strips"
+        ));
+    }
+    // Windows reserves the device name regardless of extension: `con.txt` is `CON`.
+    let stem = name
+        .split('.')
+        .next()
+        .unwrap_or(name)
+        .trim_end()
+        .to_ascii_uppercase();
+    let numbered = ["COM", "LPT"].iter().any(|prefix| {
+        stem.strip_prefix(prefix)
+            .is_some_and(|n| n.len() == 1 && n.chars().all(|c| ('1'..='9').contains(&c)))
+    });
+    if WINDOWS_RESERVED_NAMES.contains(&stem.as_str()) || numbered {
+        return Some(format!("'{name}' is a reserved device name on Windows"));
+    }
+    if name.encode_utf16().count() > MAX_NAME_UNITS {
+        return Some(format!(
+            "'{name}' is longer than {MAX_NAME_UNITS} characters"
+        ));
+    }
+    None
+}
+
+/// Rejects names in `new_part` (the path components that don't exist yet) that would break
+/// on Windows, and a `new_part` too long to create there.
+fn check_portable(new_part: &Path) -> Result<(), String> {
+    for component in new_part.components() {
+        if let Some(reason) = non_portable_name(&component.as_os_str().to_string_lossy()) {
+            return Err(format!("Invalid file name: {reason}"));
+        }
+    }
+    let units = new_part.to_string_lossy().encode_utf16().count();
+    if units > MAX_NEW_PATH_UNITS {
+        return Err(format!(
+            "Path is too long ({units} characters, at most {MAX_NEW_PATH_UNITS} for new files)"
+        ));
+    }
+    Ok(())
+}
+
+/// Which symlinks may be followed below a base directory. The default follows those
+/// that stay inside the base; projects can tighten or widen that in their settings.
+#[derive(Debug, Clone, Default)]
+pub struct SymlinkRules {
+    policy: SymlinkPolicy,
+    /// Canonical allowlisted targets; only consulted for `AllowListed`.
+    allowed_targets: Vec<PathBuf>,
+}
+
+impl SymlinkRules {
+    /// Rules from the project's settings, or the default when they can't be read.
+    pub fn for_project(project_dir: &Path) -> Self {
+        crate::project::project_settings(project_dir)
+            .map(|settings| Self::from_settings(&settings))
+            .unwrap_or_default()
+    }
+
+    fn from_settings(settings: &ProjectSettings) -> Self {
+        Self {
+            policy: settings.follow_symlinks,
+            allowed_targets: settings
+                .symlink_allowlist
+                .iter()
+                .filter(|p| Path::new(p).is_absolute())
+                .filter_map(|p| Path::new(p).canonicalize().ok())
+                .collect(),
+        }
+    }
+
+    /// Whether a fully resolved path may be used; `base` is canonical.
+    fn permits(&self, base: &Path, resolved: &Path) -> bool {
+        resolved.starts_with(base)
+            || (self.policy == SymlinkPolicy::AllowListed
+                && self.allowed_targets.iter().any(|t| resolved.starts_with(t)))
+    }
+
+    /// Canonical target of the symlink at `link` when it may be followed.
+    pub fn follow(&self, base: &Path, link: &Path) -> Option<PathBuf> {
+        if self.policy == SymlinkPolicy::Deny {
+            return None;
+        }
+        let target = link.canonicalize().ok()?;
+        self.permits(base, &target).then_some(target)
+    }
+}
+
+/// Joins `relative_path` onto `base_dir`, rejecting absolute paths, `..` and symlinks
+/// that `rules` doesn't allow. `label` names the base in error messages.
+fn resolve_within(
+    base_dir: &Path,
+    relative_path: &str,
+    label: &str,
+    rules: &SymlinkRules,
+) -> Result<PathBuf, String> {
+    let base_dir = base_dir
         .canonicalize()
-        .map_err(|e| format!("Invalid project_dir: {e}"))?;
+        .map_err(|e| format!("Invalid {label}: {e}"))?;
 
     let raw = Path::new(relative_path);
 
     if raw.as_os_str().is_empty() {
-        return Ok(project_dir);
+        return Ok(base_dir);
     }
 
     if raw.is_absolute() {
@@ -29,7 +219,7 @@ pub fn validate_path(project_dir: &Path, relative_path: &str) -> Result<PathBuf,
         }
     }
 
-    let joined = project_dir.join(&cleaned);
+    let joined = base_dir.join(&cleaned);
 
     // Resolve the deepest existing ancestor so we can detect symlink escapes even when the
     // final path doesn't exist yet (e.g. on writes).
@@ -39,17 +229,38 @@ pub fn validate_path(project_dir: &Path, relative_path: &str) -> Result<PathBuf,
             break;
         };
         existing = parent.to_path_buf();
-        if existing == project_dir {
+        if existing == base_dir {
             break;
         }
     }
 
+    // Existing names stay usable even if they aren't portable; only new ones are checked.
+    if existing != joined {
+        if let Ok(new_part) = joined.strip_prefix(&existing) {
+            check_portable(new_part)?;
+        }
+    }
+
+    if rules.policy == SymlinkPolicy::Deny {
+        let mut current = base_dir.clone();
+        for part in cleaned.iter() {
+            current.push(part);
+            // Only components that exist can be links.
+            if !existing.starts_with(&current) {
+                break;
+            }
+            if fs::symlink_metadata(&current).is_ok_and(|m| m.file_type().is_symlink()) {
+                return Err("Symlinks are not allowed in this project".to_string());
+            }
+        }
+    }
+
     let existing_canon = existing
         .canonicalize()
         .map_err(|e| format!("Failed to resolve path: {e}"))?;
 
-    if !existing_canon.starts_with(&project_dir) {
-        return Err("Path escapes project_dir".to_string());
+    if !rules.permits(&base_dir, &existing_canon) {
+        return Err(format!("Path escapes {label}"));
     }
 
     if existing == joined {
@@ -62,3 +273,219 @@ pub fn validate_path(project_dir: &Path, relative_path: &str) -> Result<PathBuf,
 
     Ok(existing_canon.join(suffix))
 }
+
+/// A path resolved for reading, with the directory it was resolved against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadPath {
+    /// Canonical project dir or reference root.
+    pub base: PathBuf,
+    pub full_path: PathBuf,
+    /// `Some(id)` when the path lives in a reference root.
+    pub reference_root: Option<String>,
+}
+
+/// Like [`validate_path`], but also accepts `ext://<root-id>/relative` for one of `roots`.
+/// Reference roots get the same traversal and symlink checks as the project dir.
+pub fn resolve_read_path_in(
+    project_dir: &Path,
+    path: &str,
+    roots: &[ReferenceRoot],
+) -> Result<ReadPath, String> {
+    let Some(reference) = path.trim_start().strip_prefix(REFERENCE_PREFIX) else {
+        let base = project_dir
+            .canonicalize()
+            .map_err(|e| format!("Invalid project_dir: {e}"))?;
+        let full_path = validate_path(&base, path)?;
+        return Ok(ReadPath {
+            base,
+            full_path,
+            reference_root: None,
+        });
+    };
+
+    let (id, relative) = reference.split_once('/').unwrap_or((reference, ""));
+    let root = roots
+        .iter()
+        .find(|r| r.id == id)
+        .ok_or_else(|| format!("Unknown reference root '{id}'"))?;
+    let root_dir = Path::new(&root.path);
+    if !root_dir.is_absolute() {
+        return Err(format!("Reference root '{id}' must be an absolute path"));
+    }
+    let full_path = resolve_within(
+        root_dir,
+        relative,
+        "reference root",
+        &SymlinkRules::default(),
+    )?;
+    Ok(ReadPath {
+        base: root_dir
+            .canonicalize()
+            .map_err(|e| format!("Invalid reference root: {e}"))?,
+        full_path,
+        reference_root: Some(id.to_string()),
+    })
+}
+
+/// [`resolve_read_path_in`] with the reference roots from the global config.
+pub fn resolve_read_path(project_dir: &Path, path: &str) -> Result<ReadPath, String> {
+    let is_reference = path.trim_start().starts_with(REFERENCE_PREFIX);
+    let roots = if is_reference {
+        crate::config::load_config()?.reference_roots
+    } else {
+        Vec::new()
+    };
+    resolve_read_path_in(project_dir, path, &roots)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn non_portable_names_match_windows_rules() {
+        let rejected = [
+            "CON",
+            "con.txt",
+            "Aux.md",
+            "nul.tar.gz",
+            "PRN",
+            "COM1",
+            "lpt9.log",
+            "CON .txt",
+            "chapter_001.",
+            "notes ",
+            "a\u{0}b",
+            "tab\there",
+            "line\n",
+            "what?.md",
+            "a:b",
+            "x|y",
+            "<tag>",
+            "\"quoted\"",
+            "star*",
+        ];
+        for name in rejected {
+            assert!(
+                non_portable_name(name).is_some(),
+                "{name:?} should be rejected"
+            );
+        }
+        assert!(non_portable_name(&"长".repeat(MAX_NAME_UNITS + 1)).is_some());
+
+        let accepted = [
+            "chapter_001.txt",
+            "console.md",
+            "CONFIG",
+            "auxiliary.txt",
+            "COM10",
+            "COM0",
+            "LPT",
+            "nul_notes.md",
+            ".creatorai",
+            "第一章：开端.txt",
+            "v1.2.txt",
+            " leading.md",
+        ];
+        for name in accepted {
+            assert_eq!(non_portable_name(name), None, "{name:?} should be accepted");
+        }
+        assert_eq!(non_portable_name(&"长".repeat(MAX_NAME_UNITS)), None);
+    }
+
+    #[test]
+    fn only_new_path_components_must_be_portable() {
+        let temp = TempDir::new("creatorai-v2-portable-names");
+        let root = temp.path.as_path();
+        assert!(validate_path(root, "notes/aux.md")
+            .unwrap_err()
+            .contains("reserved device name"));
+        assert!(validate_path(root, "chapter_001.")
+            .unwrap_err()
+            .contains("dot or space"));
+        assert!(validate_path(root, "CON/ideas.md").is_err());
+        assert!(
+            validate_path(root, &"a/".repeat(MAX_NEW_PATH_UNITS / 2 + 1))
+                .unwrap_err()
+                .contains("too long")
+        );
+        assert!(validate_path(root, "notes/idea.md").is_ok());
+
+        // A file created elsewhere (e.g. on Linux) can still be read and overwritten.
+        #[cfg(not(windows))]
+        {
+            fs::create_dir_all(root.join("aux")).unwrap();
+            fs::write(root.join("aux/trailing. "), "old").unwrap();
+            assert!(validate_path(root, "aux/trailing. ").is_ok());
+            assert!(validate_path(root, "aux/new.md").is_ok());
+            assert!(validate_path(root, "aux/nul").is_err());
+        }
+
+        // Only the components being created count towards the length limit.
+        let long_dir = "a/".repeat(MAX_NEW_PATH_UNITS / 2 + 1);
+        fs::create_dir_all(root.join(&long_dir)).unwrap();
+        fs::write(root.join(&long_dir).join("old.md"), "old").unwrap();
+        assert!(validate_path(root, &format!("{long_dir}old.md")).is_ok());
+        assert!(validate_path(root, &format!("{long_dir}new.md")).is_ok());
+    }
+
+    #[test]
+    fn reference_roots_are_readable_but_confined_and_never_writable() {
+        let temp = TempDir::new("creatorai-v2-reference-roots");
+        let project = temp.path.join("project");
+        let maps = temp.path.join("maps");
+        fs::create_dir_all(&project).unwrap();
+        fs::create_dir_all(maps.join("world")).unwrap();
+        fs::write(maps.join("world/north.md"), "北境").unwrap();
+        fs::write(temp.path.join("secret.txt"), "secret").unwrap();
+        let roots = vec![ReferenceRoot {
+            id: "maps".to_string(),
+            path: maps.to_string_lossy().into_owned(),
+        }];
+
+        let resolved =
+            resolve_read_path_in(&project, "ext://maps/world/north.md", &roots).expect("resolve");
+        assert_eq!(resolved.reference_root.as_deref(), Some("maps"));
+        assert_eq!(
+            resolved.full_path,
+            maps.join("world/north.md").canonicalize().unwrap()
+        );
+        let local = resolve_read_path_in(&project, "notes.md", &roots).expect("project path");
+        assert_eq!(local.reference_root, None);
+
+        assert!(resolve_read_path_in(&project, "ext://maps/../secret.txt", &roots).is_err());
+        assert!(resolve_read_path_in(&project, "ext://other/north.md", &roots).is_err());
+        assert!(validate_path(&project, "ext://maps/world/north.md").is_err());
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(temp.path.join("secret.txt"), maps.join("leak.txt"))
+                .unwrap();
+            let err = resolve_read_path_in(&project, "ext://maps/leak.txt", &roots).unwrap_err();
+            assert_eq!(err, "Path escapes reference root");
+        }
+    }
+
+    #[test]
+    fn protected_path_settings_follow_config_changes() {
+        let temp = TempDir::new("creatorai-v2-protected-settings");
+        let root = temp.path.as_path();
+        fs::create_dir_all(root.join(".creatorai")).unwrap();
+        let write_config = |protected: &str| {
+            let config = format!(
+                r#"{{ "name": "t", "created": 0, "updated": 0, "version": "1",
+  "settings": {{ "autoSave": true, "autoSaveInterval": 2000, "protectedPaths": [{protected}] }} }}"#
+            );
+            fs::write(root.join(".creatorai/config.json"), config).unwrap();
+        };
+
+        write_config(r#""outline.md""#);
+        assert!(check_not_protected(root, "outline.md").is_err());
+        assert!(check_not_protected(root, "world/map.md").is_ok());
+
+        write_config(r#""world/""#);
+        assert!(check_not_protected(root, "outline.md").is_ok());
+        assert!(check_not_protected(root, "world/map.md").is_err());
+    }
+}
@@ -1,13 +1,15 @@
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::{Mutex, OnceLock};
 use std::time::{SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 
-use crate::{config, keyring_store};
+use crate::{config, keyring_store, presets};
 use crate::security::validate_path;
+use crate::trash;
 use crate::write_protection;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -18,6 +20,12 @@ pub struct Session {
     pub chapter_id: Option<String>,
     pub created_at: i64,
     pub updated_at: i64,
+    /// Parameter profile (see `GlobalConfig::parameter_profiles`) this session uses.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile_id: Option<String>,
+    /// Writing preset overriding the project's active preset for this session.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -273,6 +281,7 @@ fn create_session_sync(
     let project_root = project_root
         .canonicalize()
         .map_err(|e| format!("Invalid project path: {e}"))?;
+    write_protection::ensure_writable(&project_root)?;
 
     let mut index = read_sessions_index(&project_root)?;
     let now = now_unix_seconds()?;
@@ -289,6 +298,8 @@ fn create_session_sync(
         chapter_id,
         created_at: now,
         updated_at: now,
+        profile_id: None,
+        preset_id: None,
     };
 
     let file = SessionFile {
@@ -311,6 +322,112 @@ fn rename_session_sync(
     project_path: String,
     session_id: String,
     new_name: String,
+) -> Result<(), String> {
+    update_session_sync(project_path, session_id, |session| session.name = new_name.clone())
+}
+
+fn set_session_profile_sync(
+    project_path: String,
+    session_id: String,
+    profile_id: Option<String>,
+) -> Result<(), String> {
+    if let Some(profile_id) = &profile_id {
+        if config::load_config()?.parameter_profile(profile_id).is_none() {
+            return Err(format!("Profile {} not found", profile_id));
+        }
+    }
+    update_session_sync(project_path, session_id, |session| {
+        session.profile_id = profile_id.clone()
+    })
+}
+
+fn set_session_preset_sync(
+    project_path: String,
+    session_id: String,
+    preset_id: Option<String>,
+) -> Result<(), String> {
+    if let Some(preset_id) = &preset_id {
+        let payload = presets::get_presets_sync(project_path.clone())?;
+        if !payload.presets.iter().any(|p| &p.id == preset_id) {
+            return Err(format!("Preset {} not found", preset_id));
+        }
+    }
+    update_session_sync(project_path, session_id, |session| {
+        session.preset_id = preset_id.clone()
+    })
+}
+
+/// Session preset, then the project's active preset, then its default preset.
+fn get_effective_preset_sync(
+    project_path: String,
+    session_id: String,
+) -> Result<presets::WritingPreset, String> {
+    let session_preset = {
+        let _guard = fs_lock()
+            .lock()
+            .map_err(|_| "Failed to lock sessions storage".to_string())?;
+        let project_root = PathBuf::from(&project_path);
+        ensure_project_exists(&project_root)?;
+        let id = normalize_session_id(&session_id)?;
+        read_sessions_index(&project_root)?
+            .sessions
+            .into_iter()
+            .find(|s| s.id == id)
+            .ok_or("Session not found")?
+            .preset_id
+    };
+
+    let payload = presets::get_presets_sync(project_path)?;
+    let find = |id: &str| payload.presets.iter().find(|p| p.id == id);
+    session_preset
+        .as_deref()
+        .and_then(find)
+        .or_else(|| find(&payload.active_preset_id))
+        .or_else(|| payload.presets.iter().find(|p| p.is_default))
+        .or_else(|| payload.presets.first())
+        .cloned()
+        .ok_or("Project has no presets".to_string())
+}
+
+/// Drops session references to presets that no longer exist.
+pub(crate) fn clear_session_presets(
+    project_root: &Path,
+    removed: &HashSet<String>,
+) -> Result<(), String> {
+    if removed.is_empty() {
+        return Ok(());
+    }
+    let _guard = fs_lock()
+        .lock()
+        .map_err(|_| "Failed to lock sessions storage".to_string())?;
+
+    let mut index = read_sessions_index(project_root)?;
+    let mut changed = false;
+    for session in index.sessions.iter_mut() {
+        if !session.preset_id.as_ref().is_some_and(|id| removed.contains(id)) {
+            continue;
+        }
+        session.preset_id = None;
+        changed = true;
+        match read_session_file(project_root, &session.id) {
+            Ok(mut file) => {
+                file.session.preset_id = None;
+                write_session_file(project_root, &session.id, &file)?;
+            }
+            Err(e) => eprintln!("[session] Failed to clear preset of session {}: {e}", session.id),
+        }
+    }
+    if changed {
+        write_sessions_index(project_root, &index)?;
+    }
+    Ok(())
+}
+
+/// Applies `update` to a session's metadata in both the index and its session file.
+fn update_session_sync(
+    project_path: String,
+    session_id: String,
+    update: impl Fn(&mut Session),
 ) -> Result<(), String> {
     let _guard = fs_lock()
         .lock()
@@ -321,6 +438,7 @@ fn rename_session_sync(
     let project_root = project_root
         .canonicalize()
         .map_err(|e| format!("Invalid project path: {e}"))?;
+    write_protection::ensure_writable(&project_root)?;
 
     let id = normalize_session_id(&session_id)?;
     let mut index = read_sessions_index(&project_root)?;
@@ -334,10 +452,10 @@ fn rename_session_sync(
     let old_file_content = serialize_json_pretty(&file)?;
 
     let now = now_unix_seconds()?;
-    index.sessions[pos].name = new_name.clone();
+    update(&mut index.sessions[pos]);
     index.sessions[pos].updated_at = now;
 
-    file.session.name = new_name;
+    update(&mut file.session);
     file.session.updated_at = now;
 
     write_session_file(&project_root, &id, &file)?;
@@ -361,6 +479,7 @@ fn delete_session_sync(project_path: String, session_id: String) -> Result<(), S
     let project_root = project_root
         .canonicalize()
         .map_err(|e| format!("Invalid project path: {e}"))?;
+    write_protection::ensure_writable(&project_root)?;
 
     let id = normalize_session_id(&session_id)?;
     let mut index = read_sessions_index(&project_root)?;
@@ -382,23 +501,18 @@ fn delete_session_sync(project_path: String, session_id: String) -> Result<(), S
     };
 
     let session_path = session_file_path(&project_root, &id)?;
-    let old_session_content = if session_path.exists() {
+    let trashed = if session_path.exists() {
         Some(
-            fs::read_to_string(&session_path)
-                .map_err(|e| format!("Failed to read session file: {e}"))?,
+            trash::move_to_trash(&project_root, &format!("sessions/{id}.json"))
+                .map_err(|e| format!("Failed to delete session file: {e}"))?,
         )
     } else {
         None
     };
 
-    if session_path.exists() {
-        fs::remove_file(&session_path)
-            .map_err(|e| format!("Failed to delete session file: {e}"))?;
-    }
-
     if let Err(e) = write_sessions_index(&project_root, &index) {
-        if let Some(content) = old_session_content {
-            let _ = fs::write(&session_path, content);
+        if let Some(entry) = trashed.as_ref() {
+            let _ = trash::restore_from_trash(&project_root, &entry.id, false);
         }
         if let Some(content) = old_index_content {
             let _ = fs::write(&index_path, content);
@@ -446,6 +560,7 @@ fn add_message_sync(
     let project_root = project_root
         .canonicalize()
         .map_err(|e| format!("Invalid project path: {e}"))?;
+    write_protection::ensure_writable(&project_root)?;
 
     let id = normalize_session_id(&session_id)?;
     let mut index = read_sessions_index(&project_root)?;
@@ -499,6 +614,7 @@ fn update_message_metadata_sync(
     let project_root = project_root
         .canonicalize()
         .map_err(|e| format!("Invalid project path: {e}"))?;
+    write_protection::ensure_writable(&project_root)?;
 
     let id = normalize_session_id(&session_id)?;
     let mut index = read_sessions_index(&project_root)?;
@@ -563,6 +679,7 @@ fn compact_session_sync(project_path: String, session_id: String, keep_recent: u
     let project_root = project_root
         .canonicalize()
         .map_err(|e| format!("Invalid project path: {e}"))?;
+    write_protection::ensure_writable(&project_root)?;
 
     let id = normalize_session_id(&session_id)?;
     let mut index = read_sessions_index(&project_root)?;
@@ -593,6 +710,7 @@ fn compact_session_sync(project_path: String, session_id: String, keep_recent: u
     let cfg = config::load_config()?;
     let provider_id = cfg
         .active_provider_id
+        .clone()
         .ok_or("No active provider configured".to_string())?;
     let provider = cfg
         .providers
@@ -600,14 +718,7 @@ fn compact_session_sync(project_path: String, session_id: String, keep_recent: u
         .find(|p| p.id == provider_id)
         .ok_or("Active provider not found".to_string())?;
 
-    let api_key = keyring_store::get_api_key(&provider_id)?
-        .ok_or("API Key not found for active provider".to_string())?;
-
-    let provider_type = match &provider.provider_type {
-        config::ProviderType::OpenaiCompatible => "openai-compatible",
-        config::ProviderType::Google => "google",
-        config::ProviderType::Anthropic => "anthropic",
-    };
+    let api_key = keyring_store::api_key_for_provider(provider)?;
 
     let provider_json = json!({
         "id": provider.id.clone(),
@@ -615,16 +726,26 @@ fn compact_session_sync(project_path: String, session_id: String, keep_recent: u
         "baseURL": provider.base_url.clone(),
         "apiKey": api_key,
         "models": provider.models.clone(),
-        "providerType": provider_type,
+        "providerType": provider.provider_type.as_str(),
         "headers": provider.headers.clone(),
+        "apiVersion": provider.api_version.clone(),
+        "deployments": provider.deployments.clone(),
     });
 
+    // The session's own profile wins over the provider/global defaults.
+    let parameters = file
+        .session
+        .profile_id
+        .as_deref()
+        .and_then(|id| cfg.parameter_profile(id))
+        .map(|profile| &profile.parameters)
+        .unwrap_or_else(|| cfg.parameters_for(&provider_id));
     let parameters_json = json!({
-        "model": cfg.default_parameters.model.clone(),
-        "temperature": cfg.default_parameters.temperature,
-        "topP": cfg.default_parameters.top_p,
-        "topK": cfg.default_parameters.top_k,
-        "maxTokens": cfg.default_parameters.max_tokens,
+        "model": parameters.model.clone(),
+        "temperature": parameters.temperature,
+        "topP": parameters.top_p,
+        "topK": parameters.top_k,
+        "maxTokens": parameters.max_tokens,
     });
 
     let messages_json = to_summarize
@@ -642,7 +763,17 @@ fn compact_session_sync(project_path: String, session_id: String, keep_recent: u
         })
         .collect::<Vec<_>>();
 
-    let summary = crate::ai_bridge::generate_compact_summary(provider_json, parameters_json, messages_json)?;
+    let input_chars = crate::usage_stats::prompt_chars("", &messages_json);
+    let (usage_provider, usage_parameters) = (provider_json.clone(), parameters_json.clone());
+    let summary =
+        crate::ai_bridge::generate_compact_summary(provider_json, parameters_json, messages_json);
+    crate::usage_stats::record_call(
+        &usage_provider,
+        &usage_parameters,
+        input_chars,
+        summary.as_ref().map(|s| s.chars().count() as u64).map_err(String::as_str),
+    );
+    let summary = summary?;
     let summary = summary.trim();
     if summary.is_empty() {
         return Err("Compact summary is empty".to_string());
@@ -716,6 +847,44 @@ pub async fn rename_session(
     .map_err(|e| format!("Task join error: {e}"))?
 }
 
+#[tauri::command(rename_all = "camelCase")]
+pub async fn set_session_profile(
+    project_path: String,
+    session_id: String,
+    profile_id: Option<String>,
+) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        set_session_profile_sync(project_path, session_id, profile_id)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn set_session_preset(
+    project_path: String,
+    session_id: String,
+    preset_id: Option<String>,
+) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        set_session_preset_sync(project_path, session_id, preset_id)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_effective_preset(
+    project_path: String,
+    session_id: String,
+) -> Result<presets::WritingPreset, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        get_effective_preset_sync(project_path, session_id)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
 #[tauri::command(rename_all = "camelCase")]
 pub async fn delete_session(project_path: String, session_id: String) -> Result<(), String> {
     tauri::async_runtime::spawn_blocking(move || delete_session_sync(project_path, session_id))
@@ -0,0 +1,365 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::{self, ConfigParseError, GlobalConfig};
+use crate::config_events::{self, ConfigSection};
+use crate::crypto::{self, EncryptedBlob};
+use crate::keyring_store;
+
+const SETTINGS_FORMAT: &str = "creatorai-settings";
+const SETTINGS_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SettingsFile {
+    format: String,
+    version: u32,
+    exported_at: u64,
+    /// Raw so it goes through the config migrations on import.
+    config: serde_json::Value,
+    /// Provider id -> API key map, JSON-encoded then encrypted with the export passphrase.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    api_keys: Option<EncryptedBlob>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsImportReport {
+    /// Providers that did not exist before.
+    pub added: Vec<String>,
+    /// Existing providers overwritten because the import ran in replace mode.
+    pub replaced: Vec<String>,
+    /// Existing providers left untouched because the import ran in merge mode.
+    pub conflicts: Vec<String>,
+    pub keys_restored: usize,
+}
+
+fn now_unix_seconds() -> Result<u64, String> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .map_err(|e| format!("Failed to read system time: {e}"))
+}
+
+fn build_settings_file(
+    config: GlobalConfig,
+    keys: Option<(&BTreeMap<String, String>, &str)>,
+) -> Result<SettingsFile, String> {
+    let api_keys = match keys {
+        Some((keys, passphrase)) => {
+            let plaintext =
+                serde_json::to_vec(keys).map_err(|e| format!("Serialize JSON failed: {e}"))?;
+            Some(crypto::encrypt_with_passphrase(passphrase, &plaintext)?)
+        }
+        None => None,
+    };
+    Ok(SettingsFile {
+        format: SETTINGS_FORMAT.to_string(),
+        version: SETTINGS_FORMAT_VERSION,
+        exported_at: now_unix_seconds()?,
+        config: serde_json::to_value(config).map_err(|e| format!("Serialize JSON failed: {e}"))?,
+        api_keys,
+    })
+}
+
+/// Parses a settings file and migrates its embedded config to the current schema.
+fn parse_settings_file(content: &str) -> Result<(SettingsFile, GlobalConfig), String> {
+    let file: SettingsFile = serde_json::from_str(content.trim_start_matches('\u{feff}'))
+        .map_err(|e| format!("Not a valid settings file: {e}"))?;
+    if file.format != SETTINGS_FORMAT {
+        return Err("Not a valid settings file".to_string());
+    }
+    if file.version > SETTINGS_FORMAT_VERSION {
+        return Err(format!(
+            "Settings file was exported by a newer version of the app (format {})",
+            file.version
+        ));
+    }
+    let config = match config::parse_config_value(file.config.clone()) {
+        Ok((config, _)) => config,
+        Err(ConfigParseError::NewerSchema(found)) => {
+            return Err(format!(
+                "Settings file was exported by a newer version of the app (config schema {found})"
+            ));
+        }
+        Err(e) => return Err(format!("Not a valid settings file: {e}")),
+    };
+    Ok((file, config))
+}
+
+fn decrypt_keys(
+    blob: Option<&EncryptedBlob>,
+    passphrase: Option<&str>,
+) -> Result<BTreeMap<String, String>, String> {
+    let Some(blob) = blob else {
+        return Ok(BTreeMap::new());
+    };
+    let passphrase = passphrase
+        .filter(|p| !p.is_empty())
+        .ok_or("This settings file contains API keys; a passphrase is required")?;
+    let plaintext = crypto::decrypt_with_passphrase(passphrase, blob)?;
+    serde_json::from_slice(&plaintext).map_err(|e| format!("Invalid API key payload: {e}"))
+}
+
+/// Folds `imported` into `current`. In merge mode existing providers win and are reported
+/// as conflicts; in replace mode imported providers overwrite those with the same id and the
+/// imported settings replace the rest of the config. Providers only present locally are kept
+/// either way, so their stored API keys stay usable. Returns the ids whose API keys should be
+/// restored.
+fn apply_import(
+    current: &mut GlobalConfig,
+    imported: GlobalConfig,
+    merge: bool,
+    report: &mut SettingsImportReport,
+) -> HashSet<String> {
+    let mut providers = std::mem::take(&mut current.providers);
+    let mut accepted = HashSet::new();
+    let mut rest = imported;
+
+    for provider in std::mem::take(&mut rest.providers) {
+        if config::is_builtin_provider(&provider.id) {
+            continue;
+        }
+        match providers.iter_mut().find(|p| p.id == provider.id) {
+            Some(_) if merge => {
+                report.conflicts.push(provider.id);
+                continue;
+            }
+            Some(existing) => {
+                report.replaced.push(provider.id.clone());
+                accepted.insert(provider.id.clone());
+                *existing = provider;
+            }
+            None => {
+                report.added.push(provider.id.clone());
+                accepted.insert(provider.id.clone());
+                providers.push(provider);
+            }
+        }
+    }
+
+    if !merge {
+        *current = rest;
+        current.schema_version = config::CURRENT_SCHEMA_VERSION;
+    }
+    current.providers = providers;
+    accepted
+}
+
+pub fn export_settings_to(
+    dest_path: &Path,
+    include_api_keys: bool,
+    passphrase: Option<&str>,
+) -> Result<(), String> {
+    let config = config::load_config()?;
+    let keys = if include_api_keys {
+        let passphrase = passphrase
+            .filter(|p| !p.is_empty())
+            .ok_or("A passphrase is required to export API keys")?;
+        let mut keys = BTreeMap::new();
+        for provider in &config.providers {
+            if config::is_builtin_provider(&provider.id) {
+                continue;
+            }
+            if let Some(key) = keyring_store::get_api_key(&provider.id)? {
+                keys.insert(provider.id.clone(), key);
+            }
+        }
+        Some((keys, passphrase))
+    } else {
+        None
+    };
+
+    let file = build_settings_file(config, keys.as_ref().map(|(k, p)| (k, *p)))?;
+    let json =
+        serde_json::to_string_pretty(&file).map_err(|e| format!("Serialize JSON failed: {e}"))?;
+    crate::write_protection::atomic_write_bytes(dest_path, format!("{json}\n").as_bytes(), None)
+}
+
+pub fn import_settings_from(
+    file_path: &Path,
+    passphrase: Option<&str>,
+    merge: bool,
+) -> Result<SettingsImportReport, String> {
+    let content =
+        fs::read_to_string(file_path).map_err(|e| format!("Failed to read settings file: {e}"))?;
+    let (file, imported) = parse_settings_file(&content)?;
+    // Decrypt before touching anything so a wrong passphrase leaves the config as it was.
+    let keys = decrypt_keys(file.api_keys.as_ref(), passphrase)?;
+
+    let mut current = config::load_config()?;
+    let mut report = SettingsImportReport::default();
+    let accepted = apply_import(&mut current, imported, merge, &mut report);
+    config_events::save(&current, ConfigSection::All)?;
+
+    for (provider_id, key) in keys {
+        if accepted.contains(&provider_id) {
+            keyring_store::store_api_key(&provider_id, &key)?;
+            report.keys_restored += 1;
+        }
+    }
+    Ok(report)
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn export_settings(
+    dest_path: String,
+    include_api_keys: bool,
+    passphrase: Option<String>,
+) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        export_settings_to(
+            Path::new(&dest_path),
+            include_api_keys,
+            passphrase.as_deref(),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn import_settings(
+    file_path: String,
+    passphrase: Option<String>,
+    merge: bool,
+) -> Result<SettingsImportReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        import_settings_from(Path::new(&file_path), passphrase.as_deref(), merge)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Provider, ProviderType};
+
+    fn provider(id: &str, name: &str) -> Provider {
+        Provider {
+            id: id.to_string(),
+            name: name.to_string(),
+            base_url: "http://localhost:3000/v1".to_string(),
+            models: vec![],
+            models_updated_at: None,
+            provider_type: ProviderType::OpenaiCompatible,
+            headers: None,
+            default_parameters: None,
+            proxy: None,
+            favorite: false,
+            hidden_models: vec![],
+            model_aliases: Default::default(),
+            default_model: None,
+            api_key_env: None,
+            max_concurrent_requests: None,
+            min_request_interval_ms: None,
+            api_version: None,
+            deployments: Default::default(),
+        }
+    }
+
+    #[test]
+    fn merge_keeps_existing_providers_and_reports_conflicts() {
+        let mut current = GlobalConfig::default();
+        current.providers.push(provider("shared", "Local copy"));
+
+        let mut imported = GlobalConfig::default();
+        imported.providers.push(provider("shared", "Imported copy"));
+        imported.providers.push(provider("fresh", "Fresh"));
+
+        let mut report = SettingsImportReport::default();
+        let accepted = apply_import(&mut current, imported.clone(), true, &mut report);
+        assert_eq!(report.added, vec!["fresh".to_string()]);
+        assert_eq!(report.conflicts, vec!["shared".to_string()]);
+        assert!(!accepted.contains("shared"));
+        let shared = current.providers.iter().find(|p| p.id == "shared").unwrap();
+        assert_eq!(shared.name, "Local copy");
+
+        let mut report = SettingsImportReport::default();
+        apply_import(&mut current, imported, false, &mut report);
+        assert_eq!(
+            report.replaced,
+            vec!["shared".to_string(), "fresh".to_string()]
+        );
+        let shared = current.providers.iter().find(|p| p.id == "shared").unwrap();
+        assert_eq!(shared.name, "Imported copy");
+    }
+
+    #[test]
+    fn replace_keeps_providers_missing_from_the_import() {
+        let mut current = GlobalConfig::default();
+        current.providers.push(provider("local-only", "Local only"));
+        current.providers.push(provider("shared", "Local copy"));
+
+        let mut imported = GlobalConfig::default();
+        imported.providers.push(provider("shared", "Imported copy"));
+
+        let mut report = SettingsImportReport::default();
+        let accepted = apply_import(&mut current, imported, false, &mut report);
+        assert_eq!(report.replaced, vec!["shared".to_string()]);
+        assert!(accepted.contains("shared"));
+        assert!(!accepted.contains("local-only"));
+        let ids: Vec<&str> = current.providers.iter().map(|p| p.id.as_str()).collect();
+        assert!(ids.contains(&"local-only"));
+        assert_eq!(
+            current
+                .providers
+                .iter()
+                .find(|p| p.id == "shared")
+                .unwrap()
+                .name,
+            "Imported copy"
+        );
+    }
+
+    #[test]
+    fn encrypted_keys_require_the_right_passphrase() {
+        let keys = BTreeMap::from([("fresh".to_string(), "sk-123".to_string())]);
+        let file = build_settings_file(GlobalConfig::default(), Some((&keys, "pw"))).unwrap();
+        let json = serde_json::to_string(&file).unwrap();
+        assert!(!json.contains("sk-123"));
+
+        let (parsed, _) = parse_settings_file(&json).unwrap();
+        assert!(decrypt_keys(parsed.api_keys.as_ref(), None).is_err());
+        assert!(decrypt_keys(parsed.api_keys.as_ref(), Some("nope")).is_err());
+        assert_eq!(
+            decrypt_keys(parsed.api_keys.as_ref(), Some("pw")).unwrap(),
+            keys
+        );
+    }
+
+    #[test]
+    fn imported_configs_are_migrated_and_newer_schemas_rejected() {
+        let mut file = build_settings_file(GlobalConfig::default(), None).unwrap();
+        // A schema 1 export, from before parameter profiles existed.
+        file.config["schema_version"] = serde_json::json!(1);
+        file.config
+            .as_object_mut()
+            .unwrap()
+            .remove("parameter_profiles");
+        let (_, imported) = parse_settings_file(&serde_json::to_string(&file).unwrap()).unwrap();
+        assert_eq!(imported.schema_version, config::CURRENT_SCHEMA_VERSION);
+        assert!(!imported.parameter_profiles.is_empty());
+
+        let mut current = GlobalConfig {
+            schema_version: 0,
+            ..GlobalConfig::default()
+        };
+        apply_import(
+            &mut current,
+            imported,
+            false,
+            &mut SettingsImportReport::default(),
+        );
+        assert_eq!(current.schema_version, config::CURRENT_SCHEMA_VERSION);
+
+        file.config["schema_version"] = serde_json::json!(config::CURRENT_SCHEMA_VERSION + 1);
+        let err = parse_settings_file(&serde_json::to_string(&file).unwrap())
+            .expect_err("newer config schema should be rejected");
+        assert!(err.contains("newer version"), "{err}");
+    }
+}
@@ -0,0 +1,316 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::security::validate_path;
+use crate::write_protection;
+
+const SNAPSHOTS_DIR: &str = ".backup/snapshots";
+const META_FILE: &str = "snapshot.json";
+/// What a snapshot covers, relative to the project root. `.creatorai/rag/` is left out:
+/// the index is rebuilt on demand and would dominate the snapshot's size.
+const SNAPSHOT_SOURCES: &[&str] = &[
+    "chapters",
+    "summaries",
+    "summaries.json",
+    "knowledge",
+    ".creatorai/config.json",
+    ".creatorai/presets.json",
+];
+const MAX_SLUG_CHARS: usize = 32;
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectSnapshot {
+    /// Folder name under `.backup/snapshots/`: `<timestamp>-<label slug>`.
+    pub id: String,
+    pub label: String,
+    /// Unix milliseconds.
+    pub timestamp: u64,
+    pub file_count: usize,
+    pub total_size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SnapshotMeta {
+    label: String,
+    timestamp: u64,
+}
+
+fn now_millis() -> Result<u64, String> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .map_err(|e| format!("Failed to read system time: {e}"))
+}
+
+fn canonical_root(project_root: &Path) -> Result<PathBuf, String> {
+    project_root
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path: {e}"))
+}
+
+/// Letters and digits of `label` (any script), other runs collapsed to `-`.
+fn slug(label: &str) -> String {
+    let mut slug = String::new();
+    for c in label.chars() {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug: String = slug.trim_end_matches('-').chars().take(MAX_SLUG_CHARS).collect();
+    if slug.is_empty() {
+        "snapshot".to_string()
+    } else {
+        slug
+    }
+}
+
+fn is_snapshot_source(relative: &str) -> bool {
+    SNAPSHOT_SOURCES
+        .iter()
+        .any(|source| relative == *source || relative.starts_with(&format!("{source}/")))
+}
+
+/// Regular files under `dir`, as `/`-separated paths relative to `base`. Symlinks are
+/// not followed.
+fn collect_files(base: &Path, dir: &Path, out: &mut Vec<(String, u64)>) -> Result<(), String> {
+    let meta = match fs::symlink_metadata(dir) {
+        Ok(meta) => meta,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(format!("Failed to stat '{}': {e}", dir.display())),
+    };
+    if meta.is_file() {
+        if let Ok(relative) = dir.strip_prefix(base) {
+            out.push((relative.to_string_lossy().replace('\\', "/"), meta.len()));
+        }
+        return Ok(());
+    }
+    if !meta.is_dir() {
+        return Ok(());
+    }
+    let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read '{}': {e}", dir.display()))?;
+    for entry in entries.flatten() {
+        collect_files(base, &entry.path(), out)?;
+    }
+    Ok(())
+}
+
+/// Project files a snapshot would cover right now.
+fn current_files(project_root: &Path) -> Result<Vec<(String, u64)>, String> {
+    let mut files = Vec::new();
+    for source in SNAPSHOT_SOURCES {
+        collect_files(project_root, &project_root.join(source), &mut files)?;
+    }
+    Ok(files)
+}
+
+/// Files stored in a snapshot folder, without its metadata file.
+fn snapshot_files(snapshot_dir: &Path) -> Result<Vec<(String, u64)>, String> {
+    let mut files = Vec::new();
+    collect_files(snapshot_dir, snapshot_dir, &mut files)?;
+    files.retain(|(relative, _)| relative != META_FILE && is_snapshot_source(relative));
+    Ok(files)
+}
+
+fn summarize(id: String, meta: SnapshotMeta, files: &[(String, u64)]) -> ProjectSnapshot {
+    ProjectSnapshot {
+        id,
+        label: meta.label,
+        timestamp: meta.timestamp,
+        file_count: files.len(),
+        total_size: files.iter().map(|(_, size)| size).sum(),
+    }
+}
+
+fn read_meta(snapshot_dir: &Path) -> Option<SnapshotMeta> {
+    let bytes = fs::read(snapshot_dir.join(META_FILE)).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+/// The folder of `snapshot_id`, checked to be a direct child of `.backup/snapshots/`.
+fn snapshot_dir(project_root: &Path, snapshot_id: &str) -> Result<PathBuf, String> {
+    let snapshots = project_root.join(SNAPSHOTS_DIR);
+    let dir = validate_path(project_root, &format!("{SNAPSHOTS_DIR}/{snapshot_id}"))?;
+    if snapshot_id.starts_with('.') || dir.parent() != Some(snapshots.as_path()) || !dir.is_dir() {
+        return Err(format!("Snapshot '{snapshot_id}' not found"));
+    }
+    Ok(dir)
+}
+
+/// Copies the manuscript (chapters, summaries, knowledge and the project config) into
+/// `.backup/snapshots/<id>/`. The copy is staged under a hidden name and renamed into
+/// place, so a listed snapshot is always complete.
+pub fn create_snapshot(project_root: &Path, label: &str) -> Result<ProjectSnapshot, String> {
+    let project_root = canonical_root(project_root)?;
+    let snapshots = project_root.join(SNAPSHOTS_DIR);
+    let label = label.trim().to_string();
+
+    let mut timestamp = now_millis()?;
+    let mut id = format!("{timestamp}-{}", slug(&label));
+    while snapshots.join(&id).exists() {
+        timestamp += 1;
+        id = format!("{timestamp}-{}", slug(&label));
+    }
+    let staging = snapshots.join(format!(".tmp-{id}"));
+
+    let files = current_files(&project_root)?;
+    let result = (|| {
+        for (relative, _) in &files {
+            let target = staging.join(relative);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create directory '{}': {e}", parent.display()))?;
+            }
+            fs::copy(project_root.join(relative), &target)
+                .map_err(|e| format!("Failed to copy '{relative}' into snapshot: {e}"))?;
+        }
+        fs::create_dir_all(&staging).map_err(|e| format!("Failed to create snapshot: {e}"))?;
+        let meta = SnapshotMeta {
+            label: label.clone(),
+            timestamp,
+        };
+        let json = serde_json::to_string_pretty(&meta).map_err(|e| format!("Serialize JSON failed: {e}"))?;
+        fs::write(staging.join(META_FILE), format!("{json}\n"))
+            .map_err(|e| format!("Failed to write snapshot metadata: {e}"))?;
+        fs::rename(&staging, snapshots.join(&id)).map_err(|e| format!("Failed to finish snapshot: {e}"))
+    })();
+    if let Err(e) = result {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    Ok(summarize(id, SnapshotMeta { label, timestamp }, &files))
+}
+
+/// Snapshots of the project, newest first.
+pub fn list_snapshots(project_root: &Path) -> Result<Vec<ProjectSnapshot>, String> {
+    let project_root = canonical_root(project_root)?;
+    let Ok(entries) = fs::read_dir(project_root.join(SNAPSHOTS_DIR)) else {
+        return Ok(Vec::new());
+    };
+
+    let mut snapshots = Vec::new();
+    for entry in entries.flatten() {
+        let Some(id) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        if id.starts_with('.') || !entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+            continue;
+        }
+        let dir = entry.path();
+        let Some(meta) = read_meta(&dir) else {
+            continue;
+        };
+        snapshots.push(summarize(id, meta, &snapshot_files(&dir)?));
+    }
+    snapshots.sort_by(|a, b| b.timestamp.cmp(&a.timestamp).then_with(|| a.id.cmp(&b.id)));
+    Ok(snapshots)
+}
+
+/// Puts the project back to `snapshot_id` in one transaction: snapshot files are
+/// rewritten and files the snapshot doesn't have are deleted, every one of them backed
+/// up to `.backup/` first.
+pub fn restore_snapshot(project_root: &Path, snapshot_id: &str) -> Result<ProjectSnapshot, String> {
+    let project_root = canonical_root(project_root)?;
+    write_protection::ensure_writable(&project_root)?;
+    let dir = snapshot_dir(&project_root, snapshot_id)?;
+    let meta = read_meta(&dir).ok_or_else(|| format!("Snapshot '{snapshot_id}' has no metadata"))?;
+    let files = snapshot_files(&dir)?;
+
+    let mut transaction = write_protection::transaction(&project_root);
+    for (relative, _) in &files {
+        let content = fs::read(dir.join(relative))
+            .map_err(|e| format!("Failed to read '{relative}' from snapshot: {e}"))?;
+        transaction = transaction.write(relative, content);
+    }
+    for (relative, _) in current_files(&project_root)? {
+        if !files.iter().any(|(kept, _)| *kept == relative) {
+            transaction = transaction.delete(&relative);
+        }
+    }
+    transaction.commit()?;
+
+    Ok(summarize(snapshot_id.to_string(), meta, &files))
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn create_project_snapshot(project_path: String, label: String) -> Result<ProjectSnapshot, String> {
+    tauri::async_runtime::spawn_blocking(move || create_snapshot(Path::new(&project_path), &label))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn list_project_snapshots(project_path: String) -> Result<Vec<ProjectSnapshot>, String> {
+    tauri::async_runtime::spawn_blocking(move || list_snapshots(Path::new(&project_path)))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn restore_project_snapshot(
+    project_path: String,
+    snapshot_id: String,
+) -> Result<ProjectSnapshot, String> {
+    tauri::async_runtime::spawn_blocking(move || restore_snapshot(Path::new(&project_path), &snapshot_id))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn restoring_a_snapshot_brings_back_a_deleted_chapter() {
+        let temp = TempDir::new("creatorai-v2-snapshot");
+        let root = temp.path.join("novel");
+        let project_path = root.to_string_lossy().to_string();
+        crate::project::create_project_sync(project_path.clone(), "小说".to_string(), "main")
+            .expect("create_project");
+        let chapters: Vec<_> = ["第一章", "第二章"]
+            .into_iter()
+            .map(|title| {
+                tauri::async_runtime::block_on(crate::chapter::create_chapter(project_path.clone(), title.to_string()))
+                    .expect("create_chapter")
+            })
+            .collect();
+        let second = format!("chapters/{}.txt", chapters[1].id);
+        fs::write(root.join(&second), "第二章正文").unwrap();
+        fs::create_dir_all(root.join(".creatorai/rag")).unwrap();
+        fs::write(root.join(".creatorai/rag/index.bin"), "index").unwrap();
+        fs::write(root.join(".creatorai/presets.json"), "{\"presets\": []}\n").unwrap();
+
+        let snapshot = create_snapshot(&root, " AI 续写前 ").expect("create_snapshot");
+        assert_eq!(snapshot.label, "AI 续写前");
+        assert!(snapshot.id.ends_with("-AI-续写前"), "{}", snapshot.id);
+        let dir = root.join(SNAPSHOTS_DIR).join(&snapshot.id);
+        assert!(dir.join(&second).is_file());
+        assert!(dir.join(".creatorai/config.json").is_file());
+        assert!(dir.join(".creatorai/presets.json").is_file());
+        assert!(!dir.join(".creatorai/rag").exists());
+        assert_eq!(list_snapshots(&root).expect("list"), std::slice::from_ref(&snapshot));
+
+        tauri::async_runtime::block_on(crate::chapter::delete_chapter(project_path.clone(), chapters[1].id.clone()))
+            .expect("delete_chapter");
+        fs::write(root.join("knowledge/later.md"), "之后的设定").unwrap();
+        fs::write(root.join(".creatorai/presets.json"), "{\"presets\": [1]}\n").unwrap();
+
+        restore_snapshot(&root, &snapshot.id).expect("restore_snapshot");
+        assert_eq!(fs::read_to_string(root.join(&second)).unwrap(), "第二章正文");
+        let listed = tauri::async_runtime::block_on(crate::chapter::list_chapters(project_path)).expect("list_chapters");
+        assert_eq!(listed.iter().map(|c| c.id.as_str()).collect::<Vec<_>>(), [&chapters[0].id, &chapters[1].id]);
+        assert!(!root.join("knowledge/later.md").exists());
+        assert_eq!(fs::read_to_string(root.join(".creatorai/presets.json")).unwrap(), "{\"presets\": []}\n");
+        assert_eq!(write_protection::list_backups(&root, Some("knowledge/later.md")).unwrap().len(), 1);
+
+        assert!(restore_snapshot(&root, "../chapters").is_err());
+        assert!(restore_snapshot(&root, "missing").is_err());
+    }
+}
@@ -0,0 +1,192 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::activity_log::read_jsonl;
+
+const STATS_RELATIVE_PATH: &str = ".creatorai/stats.jsonl";
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+/// Longest range `get_writing_stats` aggregates over.
+const MAX_STATS_DAYS: u32 = 3660;
+
+/// One change to a chapter's word count.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsEntry {
+    /// UTC day, `YYYY-MM-DD`.
+    pub date: String,
+    pub chapter_id: String,
+    /// New word count minus the one previously in the index; negative for deletions.
+    pub delta: i64,
+    pub new_total: u32,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyWritingStats {
+    pub date: String,
+    /// Net words written that day across all chapters.
+    pub delta: i64,
+}
+
+static STATS_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+fn stats_lock() -> &'static Mutex<()> {
+    STATS_LOCK.get_or_init(|| Mutex::new(()))
+}
+
+fn now_unix_seconds() -> Result<u64, String> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .map_err(|e| format!("Failed to read system time: {e}"))
+}
+
+/// `YYYY-MM-DD` (UTC) for a unix timestamp.
+fn date_of(unix_seconds: u64) -> String {
+    // Howard Hinnant's civil_from_days.
+    let z = (unix_seconds / SECS_PER_DAY) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+fn append_entry(project_root: &Path, entry: &StatsEntry) -> Result<(), String> {
+    let _guard = stats_lock()
+        .lock()
+        .map_err(|_| "Failed to lock stats log".to_string())?;
+
+    let stats_path = project_root.join(STATS_RELATIVE_PATH);
+    if let Some(parent) = stats_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create .creatorai: {e}"))?;
+    }
+    let line = serde_json::to_string(entry).map_err(|e| format!("Serialize JSON failed: {e}"))?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&stats_path)
+        .map_err(|e| format!("Failed to open stats log: {e}"))?;
+    file.write_all(format!("{line}\n").as_bytes())
+        .map_err(|e| format!("Failed to write stats log: {e}"))
+}
+
+fn record_at(project_root: &Path, now: u64, chapter_id: &str, delta: i64, new_total: u32) -> Result<(), String> {
+    if delta == 0 {
+        return Ok(());
+    }
+    let entry = StatsEntry {
+        date: date_of(now),
+        chapter_id: chapter_id.to_string(),
+        delta,
+        new_total,
+    };
+    append_entry(project_root, &entry)
+}
+
+/// Records a change to a chapter's word count; unchanged counts are not logged.
+///
+/// Like the activity log this is best-effort: a failure comes back as a warning message
+/// so the save being recorded still succeeds.
+pub fn record(project_root: &Path, chapter_id: &str, delta: i64, new_total: u32) -> Option<String> {
+    now_unix_seconds()
+        .and_then(|now| record_at(project_root, now, chapter_id, delta, new_total))
+        .err()
+        .map(|e| format!("Writing stats not updated: {e}"))
+}
+
+fn read_entries(project_root: &Path) -> Result<Vec<StatsEntry>, String> {
+    read_jsonl(&project_root.join(STATS_RELATIVE_PATH), "stats log")
+}
+
+/// Net words written per day for the `days` days ending with the day of `now`, oldest
+/// first. Days without changes are reported with a delta of 0.
+fn stats_at(project_root: &Path, days: u32, now: u64) -> Result<Vec<DailyWritingStats>, String> {
+    let days = days.clamp(1, MAX_STATS_DAYS) as u64;
+    let mut totals: BTreeMap<String, i64> = (0..days)
+        .map(|i| (date_of(now.saturating_sub(i * SECS_PER_DAY)), 0))
+        .collect();
+    for entry in read_entries(project_root)? {
+        if let Some(total) = totals.get_mut(&entry.date) {
+            *total += entry.delta;
+        }
+    }
+    Ok(totals
+        .into_iter()
+        .map(|(date, delta)| DailyWritingStats { date, delta })
+        .collect())
+}
+
+pub fn writing_stats(project_root: &Path, days: u32) -> Result<Vec<DailyWritingStats>, String> {
+    let project_root = project_root
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path: {e}"))?;
+    stats_at(&project_root, days, now_unix_seconds()?)
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_writing_stats(project_path: String, days: u32) -> Result<Vec<DailyWritingStats>, String> {
+    tauri::async_runtime::spawn_blocking(move || writing_stats(Path::new(&project_path), days))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn temp_project(prefix: &str) -> TempDir {
+        let temp = TempDir::new(prefix);
+        fs::create_dir_all(temp.path.join(".creatorai")).expect("create temp dir");
+        temp
+    }
+
+    #[test]
+    fn dates_are_utc_days() {
+        assert_eq!(date_of(0), "1970-01-01");
+        assert_eq!(date_of(951_782_400), "2000-02-29");
+        assert_eq!(date_of(1_790_121_599), "2026-09-22");
+    }
+
+    #[test]
+    fn daily_stats_sum_net_deltas() {
+        let temp = temp_project("creatorai-v2-writing-stats");
+        // 2026-10-14 09:00 UTC
+        let day1 = 1_791_968_400;
+        let day2 = day1 + SECS_PER_DAY;
+
+        record_at(&temp.path, day1, "chapter_001", 1200, 1200).unwrap();
+        record_at(&temp.path, day2, "chapter_001", -300, 900).unwrap();
+        record_at(&temp.path, day2 + 3600, "chapter_002", 500, 500).unwrap();
+        record_at(&temp.path, day2 + 7200, "chapter_002", 0, 500).unwrap();
+
+        let entries = read_entries(&temp.path).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[1].date, "2026-10-15");
+        assert_eq!(entries[1].new_total, 900);
+
+        let stats = stats_at(&temp.path, 3, day2 + SECS_PER_DAY).unwrap();
+        let got: Vec<(&str, i64)> = stats.iter().map(|s| (s.date.as_str(), s.delta)).collect();
+        assert_eq!(got, [("2026-10-14", 1200), ("2026-10-15", 200), ("2026-10-16", 0)]);
+
+        let today = stats_at(&temp.path, 1, day2).unwrap();
+        assert_eq!(today, vec![DailyWritingStats { date: "2026-10-15".to_string(), delta: 200 }]);
+    }
+
+    #[test]
+    fn record_failure_is_reported_as_warning() {
+        let temp = temp_project("creatorai-v2-writing-stats-fail");
+        fs::create_dir_all(temp.path.join(STATS_RELATIVE_PATH)).unwrap();
+        assert!(record(&temp.path, "chapter_001", 10, 10).is_some());
+    }
+}
@@ -1,8 +1,12 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::diff::{self, Op};
+use crate::project::ChapterMeta;
 use crate::security::validate_path;
 use crate::write_protection;
 
@@ -12,6 +16,54 @@ pub struct SummaryEntry {
     pub chapter_id: String,
     pub summary: String,
     pub created_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<u64>,
+    /// Who wrote the summary; `None` for entries saved before this was tracked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<SummarySource>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+/// A saved, updated or deleted summary plus the bookkeeping that could not be updated after
+/// the change.
+#[derive(Debug, Clone, Serialize)]
+pub struct SavedSummary {
+    #[serde(flatten)]
+    pub entry: SummaryEntry,
+    /// The activity log was left stale; the summary itself was saved.
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SummarySource {
+    /// The AI's `save_summary` tool during a chat.
+    Tool,
+    /// Typed in by the user.
+    Manual,
+    /// The one-click chapter summary command.
+    Generated,
+}
+
+/// Where a new summary came from, recorded on the saved entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SummaryProvenance {
+    pub source: SummarySource,
+    pub session_id: Option<String>,
+    pub model: Option<String>,
+}
+
+impl SummaryProvenance {
+    pub fn manual() -> Self {
+        Self {
+            source: SummarySource::Manual,
+            session_id: None,
+            model: None,
+        }
+    }
 }
 
 fn now_unix_seconds() -> Result<u64, String> {
@@ -46,75 +98,673 @@ fn ensure_project_exists(project_root: &Path) -> Result<(), String> {
     Ok(())
 }
 
+/// Legacy single-file store. Left in place after migration so older builds can still read
+/// it, but never written again once `summaries/` exists.
 pub fn summaries_path(project_root: &Path) -> Result<PathBuf, String> {
     validate_path(project_root, "summaries.json")
 }
 
-pub fn load_summaries(project_root: &Path) -> Result<Vec<SummaryEntry>, String> {
-    ensure_project_exists(project_root)?;
-    let path = summaries_path(project_root)?;
+const SHARD_DIR: &str = "summaries";
+const MIGRATING_DIR: &str = "summaries.migrating";
+/// Shard for legacy summaries whose chapter id can't name a shard. The dot keeps its stem
+/// from ever being taken for a chapter id, so only [`load_summaries`] reads it.
+const INVALID_ID_SHARD: &str = "invalid-chapter-ids.legacy.json";
+
+fn fs_lock() -> &'static Mutex<()> {
+    static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+    LOCK.get_or_init(|| Mutex::new(()))
+}
+
+/// Chapter ids become file names, so only plain ids are accepted.
+fn validate_shard_id(chapter_id: &str) -> Result<(), String> {
+    if chapter_id.is_empty()
+        || !chapter_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(format!("Invalid chapterId for summaries: '{chapter_id}'"));
+    }
+    Ok(())
+}
+
+fn shard_path(project_root: &Path, chapter_id: &str) -> Result<PathBuf, String> {
+    validate_shard_id(chapter_id)?;
+    validate_path(project_root, &format!("{SHARD_DIR}/{chapter_id}.json"))
+}
+
+fn parse_entries(path: &Path) -> Result<Vec<SummaryEntry>, String> {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read {name}: {e}"))?;
+    serde_json::from_slice::<Vec<SummaryEntry>>(&bytes).map_err(|e| format!("Failed to parse {name}: {e}"))
+}
+
+/// Newest copy of `path` under `.backup/` that still parses.
+fn latest_backup(project_root: &Path, path: &Path) -> Option<(u64, Vec<SummaryEntry>)> {
+    let relative = path.strip_prefix(project_root).ok()?;
+    let backup_root = project_root.join(".backup");
+    let mut stamps: Vec<u64> = fs::read_dir(&backup_root)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<u64>().ok())
+        .collect();
+    stamps.sort_unstable_by(|a, b| b.cmp(a));
+
+    stamps.into_iter().find_map(|ts| {
+        let entries = parse_entries(&backup_root.join(ts.to_string()).join(relative)).ok()?;
+        Some((ts, entries))
+    })
+}
+
+/// Entries stored in `path`. A file that fails to parse (e.g. after a crash mid-write) is
+/// read from its newest backup instead, and the returned warning says so. The damaged file
+/// itself is left alone until the next write replaces it.
+fn read_entries(project_root: &Path, path: &Path) -> Result<(Vec<SummaryEntry>, Option<String>), String> {
     if !path.exists() {
-        return Ok(Vec::new());
+        return Ok((Vec::new(), None));
     }
-    let bytes = fs::read(&path).map_err(|e| format!("Failed to read summaries.json: {e}"))?;
-    serde_json::from_slice::<Vec<SummaryEntry>>(&bytes)
-        .map_err(|e| format!("Failed to parse summaries.json: {e}"))
+    let err = match parse_entries(path) {
+        Ok(entries) => return Ok((entries, None)),
+        Err(err) => err,
+    };
+    let (ts, entries) = latest_backup(project_root, path).ok_or(err.clone())?;
+    let name = path.strip_prefix(project_root).unwrap_or(path).display();
+    let warning = format!("{err}; using the backup from {ts} for {name}");
+    eprintln!("Warning: {warning}");
+    Ok((entries, Some(warning)))
+}
+
+fn entries_json(entries: &[SummaryEntry]) -> Result<String, String> {
+    let json = serde_json::to_string_pretty(entries)
+        .map_err(|e| format!("Serialize summaries failed: {e}"))?;
+    Ok(format!("{json}\n"))
 }
 
-pub fn save_summary(project_root: &Path, chapter_id: String, summary: String) -> Result<SummaryEntry, String> {
+/// Splits `summaries.json` into `summaries/{chapter_id}.json` the first time the project's
+/// summaries are touched; entries whose chapter id can't be a file name go to
+/// [`INVALID_ID_SHARD`]. Shards are staged in a sibling directory and renamed into place,
+/// so an interrupted migration simply runs again.
+fn ensure_sharded(project_root: &Path) -> Result<PathBuf, String> {
+    let shard_dir = validate_path(project_root, SHARD_DIR)?;
+    if shard_dir.is_dir() {
+        return Ok(shard_dir);
+    }
+
+    let mut by_chapter: BTreeMap<String, Vec<SummaryEntry>> = BTreeMap::new();
+    let mut invalid_ids = Vec::new();
+    let (legacy, _) = read_entries(project_root, &summaries_path(project_root)?)?;
+    for entry in legacy {
+        if validate_shard_id(&entry.chapter_id).is_err() {
+            invalid_ids.push(entry);
+            continue;
+        }
+        by_chapter.entry(entry.chapter_id.clone()).or_default().push(entry);
+    }
+
+    let staging = validate_path(project_root, MIGRATING_DIR)?;
+    if staging.exists() {
+        fs::remove_dir_all(&staging).map_err(|e| format!("Failed to clear {MIGRATING_DIR}: {e}"))?;
+    }
+    fs::create_dir_all(&staging).map_err(|e| format!("Failed to create {MIGRATING_DIR}: {e}"))?;
+    for (chapter_id, entries) in &by_chapter {
+        let path = staging.join(format!("{chapter_id}.json"));
+        write_protection::atomic_write_bytes(&path, entries_json(entries)?.as_bytes(), None)?;
+    }
+    if !invalid_ids.is_empty() {
+        let path = staging.join(INVALID_ID_SHARD);
+        write_protection::atomic_write_bytes(&path, entries_json(&invalid_ids)?.as_bytes(), None)?;
+    }
+    fs::rename(&staging, &shard_dir).map_err(|e| format!("Failed to move summaries into place: {e}"))?;
+    Ok(shard_dir)
+}
+
+fn open_project_root(project_root: &Path) -> Result<PathBuf, String> {
     ensure_project_exists(project_root)?;
-    let project_root = project_root
+    project_root
         .canonicalize()
-        .map_err(|e| format!("Invalid project path: {e}"))?;
+        .map_err(|e| format!("Invalid project path: {e}"))
+}
+
+fn write_shard(project_root: &Path, chapter_id: &str, entries: &[SummaryEntry]) -> Result<(), String> {
+    let path = shard_path(project_root, chapter_id)?;
+    write_protection::write_string_with_backup(project_root, &path, &entries_json(entries)?)?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadedSummaries {
+    /// Oldest first.
+    pub summaries: Vec<SummaryEntry>,
+    /// Some files failed to parse and were read from `.backup/` instead.
+    pub recovered_from_backup: bool,
+    pub warnings: Vec<String>,
+}
+
+/// Every summary in the project.
+pub fn load_summaries(project_root: &Path) -> Result<LoadedSummaries, String> {
+    let project_root = open_project_root(project_root)?;
+    let _guard = fs_lock().lock().map_err(|_| "Failed to lock summaries".to_string())?;
+    let shard_dir = ensure_sharded(&project_root)?;
+
+    let mut shards: Vec<PathBuf> = fs::read_dir(&shard_dir)
+        .map_err(|e| format!("Failed to read {SHARD_DIR}: {e}"))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    shards.sort();
+
+    let mut summaries = Vec::new();
+    let mut warnings = Vec::new();
+    for shard in shards {
+        let (entries, warning) = read_entries(&project_root, &shard)?;
+        summaries.extend(entries);
+        warnings.extend(warning);
+    }
+    // Stable, so entries saved within the same second keep their save order.
+    summaries.sort_by_key(|e| e.created_at);
+    Ok(LoadedSummaries {
+        summaries,
+        recovered_from_backup: !warnings.is_empty(),
+        warnings,
+    })
+}
+
+/// Newest summary of one chapter; reads only that chapter's shard.
+pub fn get_latest_summary(project_root: &Path, chapter_id: &str) -> Result<Option<SummaryEntry>, String> {
+    let project_root = open_project_root(project_root)?;
+    let _guard = fs_lock().lock().map_err(|_| "Failed to lock summaries".to_string())?;
+    ensure_sharded(&project_root)?;
+    if validate_shard_id(chapter_id).is_err() {
+        return Ok(None);
+    }
+
+    let mut best: Option<SummaryEntry> = None;
+    for entry in read_entries(&project_root, &shard_path(&project_root, chapter_id)?)?.0 {
+        if !matches!(&best, Some(b) if entry.created_at < b.created_at) {
+            best = Some(entry);
+        }
+    }
+    Ok(best)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffKind {
+    Equal,
+    Added,
+    Removed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffSegment {
+    pub kind: DiffKind,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SummaryDiff {
+    /// `false` when the chapter has fewer than two versions; `segments` is then empty.
+    pub comparable: bool,
+    pub older: Option<SummaryEntry>,
+    pub newer: Option<SummaryEntry>,
+    pub segments: Vec<DiffSegment>,
+}
+
+/// Sentences (and line breaks) with their closing punctuation attached.
+fn split_sentences(text: &str) -> Vec<&str> {
+    let mut units = Vec::new();
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        if matches!(c, '。' | '！' | '？' | '；' | '…' | '!' | '?' | ';' | '\n') {
+            let end = i + c.len_utf8();
+            units.push(&text[start..end]);
+            start = end;
+        }
+    }
+    if start < text.len() {
+        units.push(&text[start..]);
+    }
+    units
+}
+
+/// Sentence-level diff with the same edit script as chapter diffs; adjacent segments of the
+/// same kind are merged.
+fn diff_sentences(older: &str, newer: &str) -> Vec<DiffSegment> {
+    let (a, b) = (split_sentences(older), split_sentences(newer));
+
+    let mut segments: Vec<DiffSegment> = Vec::new();
+    let mut push = |kind: DiffKind, text: &str| match segments.last_mut() {
+        Some(last) if last.kind == kind => last.text.push_str(text),
+        _ => segments.push(DiffSegment {
+            kind,
+            text: text.to_string(),
+        }),
+    };
+    let (mut i, mut j) = (0, 0);
+    for op in diff::edit_script(&a, &b) {
+        match op {
+            Op::Equal => {
+                push(DiffKind::Equal, a[i]);
+                i += 1;
+                j += 1;
+            }
+            Op::Delete => {
+                push(DiffKind::Removed, a[i]);
+                i += 1;
+            }
+            Op::Insert => {
+                push(DiffKind::Added, b[j]);
+                j += 1;
+            }
+        }
+    }
+    segments
+}
+
+/// Compares two summary versions of a chapter: the two newest, or the entries created at
+/// `versions` (older, newer) when given.
+pub fn diff_summaries(
+    project_root: &Path,
+    chapter_id: &str,
+    versions: Option<(u64, u64)>,
+) -> Result<SummaryDiff, String> {
+    let project_root = open_project_root(project_root)?;
+    let _guard = fs_lock().lock().map_err(|_| "Failed to lock summaries".to_string())?;
+    ensure_sharded(&project_root)?;
+    let (mut entries, _) = read_entries(&project_root, &shard_path(&project_root, chapter_id)?)?;
+    entries.sort_by_key(|e| e.created_at);
+
+    let pair = match versions {
+        Some((older, newer)) => {
+            let find = |created_at: u64| {
+                entries
+                    .iter()
+                    .find(|e| e.created_at == created_at)
+                    .cloned()
+                    .ok_or(format!("Summary not found: {chapter_id} @ {created_at}"))
+            };
+            Some((find(older)?, find(newer)?))
+        }
+        None if entries.len() >= 2 => {
+            let newer = entries.pop();
+            entries.pop().zip(newer)
+        }
+        None => None,
+    };
+
+    Ok(match pair {
+        Some((older, newer)) => SummaryDiff {
+            comparable: true,
+            segments: diff_sentences(&older.summary, &newer.summary),
+            older: Some(older),
+            newer: Some(newer),
+        },
+        None => SummaryDiff {
+            comparable: false,
+            older: None,
+            newer: entries.pop(),
+            segments: Vec::new(),
+        },
+    })
+}
+
+const MAX_SEARCH_HITS: usize = 50;
+/// Characters of context kept on each side of the first match.
+const SNIPPET_CONTEXT_CHARS: usize = 30;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SummarySearchHit {
+    pub chapter_id: String,
+    /// `None` for summaries of chapters no longer in the index.
+    pub chapter_title: Option<String>,
+    pub created_at: u64,
+    /// Whether this is the chapter's newest summary.
+    pub is_latest: bool,
+    pub snippet: String,
+    /// `[start, end)` character ranges of the matches within `snippet`.
+    pub highlights: Vec<[usize; 2]>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SummarySearchResult {
+    pub hits: Vec<SummarySearchHit>,
+    /// More entries matched than were returned.
+    pub truncated: bool,
+}
+
+fn fold_case(text: &str) -> Vec<char> {
+    // One char out per char in, so match positions index the original text.
+    text.chars().map(|c| c.to_lowercase().next().unwrap_or(c)).collect()
+}
+
+/// Character ranges where `query` occurs in `text`, ignoring case.
+fn find_matches(text: &[char], query: &[char]) -> Vec<[usize; 2]> {
+    let mut found = Vec::new();
+    let mut i = 0;
+    while !query.is_empty() && i + query.len() <= text.len() {
+        if text[i..i + query.len()] == *query {
+            found.push([i, i + query.len()]);
+            i += query.len();
+        } else {
+            i += 1;
+        }
+    }
+    found
+}
+
+fn snippet_around(text: &str, matches: &[[usize; 2]]) -> (String, Vec<[usize; 2]>) {
+    let chars: Vec<char> = text.chars().collect();
+    let start = matches[0][0].saturating_sub(SNIPPET_CONTEXT_CHARS);
+    let end = (matches[0][1] + SNIPPET_CONTEXT_CHARS).min(chars.len());
+
+    let prefix = if start > 0 { "…" } else { "" };
+    let suffix = if end < chars.len() { "…" } else { "" };
+    let offset = prefix.chars().count();
+    let snippet = format!("{prefix}{}{suffix}", chars[start..end].iter().collect::<String>());
+    let highlights = matches
+        .iter()
+        .filter(|m| m[0] >= start && m[1] <= end)
+        .map(|m| [m[0] - start + offset, m[1] - start + offset])
+        .collect();
+    (snippet, highlights)
+}
+
+/// Case-insensitive search over summary text, in story order and newest version first
+/// within a chapter. Older versions are only searched with `include_older`.
+pub fn search_summaries(project_root: &Path, query: &str, include_older: bool) -> Result<SummarySearchResult, String> {
+    let needle = fold_case(query.trim());
+    if needle.is_empty() {
+        return Err("query is empty".to_string());
+    }
+    let project_root = open_project_root(project_root)?;
+    let chapters = crate::chapter::list_chapters_sync(project_root.to_string_lossy().into_owned())?;
+    let mut summaries = load_summaries(&project_root)?.summaries;
+    // Story order, unknown chapters last; newest first within a chapter.
+    let rank = |chapter_id: &str| chapters.iter().position(|c| c.id == chapter_id).unwrap_or(usize::MAX);
+    summaries.sort_by(|a, b| {
+        rank(&a.chapter_id)
+            .cmp(&rank(&b.chapter_id))
+            .then_with(|| a.chapter_id.cmp(&b.chapter_id))
+            .then_with(|| b.created_at.cmp(&a.created_at))
+    });
+
+    let mut hits = Vec::new();
+    let mut truncated = false;
+    let mut previous_chapter: Option<&str> = None;
+    for entry in &summaries {
+        let is_latest = previous_chapter != Some(entry.chapter_id.as_str());
+        previous_chapter = Some(&entry.chapter_id);
+        if !is_latest && !include_older {
+            continue;
+        }
+        let matches = find_matches(&fold_case(&entry.summary), &needle);
+        if matches.is_empty() {
+            continue;
+        }
+        if hits.len() == MAX_SEARCH_HITS {
+            truncated = true;
+            break;
+        }
+        let (snippet, highlights) = snippet_around(&entry.summary, &matches);
+        hits.push(SummarySearchHit {
+            chapter_id: entry.chapter_id.clone(),
+            chapter_title: chapters
+                .iter()
+                .find(|c| c.id == entry.chapter_id)
+                .map(|c| c.title.clone()),
+            created_at: entry.created_at,
+            is_latest,
+            snippet,
+            highlights,
+        });
+    }
+    Ok(SummarySearchResult { hits, truncated })
+}
+
+pub fn save_summary(
+    project_root: &Path,
+    chapter_id: String,
+    summary: String,
+    provenance: SummaryProvenance,
+) -> Result<SummaryEntry, String> {
+    let project_root = open_project_root(project_root)?;
+    write_protection::ensure_writable(&project_root)?;
     if chapter_id.trim().is_empty() {
         return Err("chapterId is empty".to_string());
     }
     if summary.trim().is_empty() {
         return Err("summary is empty".to_string());
     }
+    validate_shard_id(&chapter_id)?;
 
-    let mut summaries = load_summaries(&project_root)?;
+    let _guard = fs_lock().lock().map_err(|_| "Failed to lock summaries".to_string())?;
+    ensure_sharded(&project_root)?;
+    let (mut entries, _) = read_entries(&project_root, &shard_path(&project_root, &chapter_id)?)?;
     let entry = SummaryEntry {
         chapter_id,
         summary,
         created_at: now_unix_seconds()?,
+        updated_at: None,
+        source: Some(provenance.source),
+        session_id: provenance.session_id,
+        model: provenance.model,
     };
-    summaries.push(entry.clone());
-
-    let path = summaries_path(&project_root)?;
-    let json = serde_json::to_string_pretty(&summaries)
-        .map_err(|e| format!("Serialize summaries.json failed: {e}"))?;
-    write_protection::write_string_with_backup(&project_root, &path, &format!("{json}\n"))?;
+    entries.push(entry.clone());
+    if let Some(limit) = retention_limit(&project_root) {
+        keep_newest(&mut entries, limit);
+    }
+    write_shard(&project_root, &entry.chapter_id, &entries)?;
 
     Ok(entry)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::time::{SystemTime, UNIX_EPOCH};
+/// A chapter's latest summary, or a gap when it has none.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChapterSummaryRef {
+    pub chapter_id: String,
+    pub title: String,
+    pub order: u32,
+    pub summary: Option<String>,
+    pub created_at: Option<u64>,
+}
 
-    struct TempDir {
-        path: PathBuf,
-    }
+/// Latest summaries of the `count` chapters before `before_chapter_id`, in story order.
+pub fn get_recent_summaries(
+    project_root: &Path,
+    before_chapter_id: &str,
+    count: u32,
+) -> Result<Vec<ChapterSummaryRef>, String> {
+    let chapters = crate::chapter::list_chapters_sync(project_root.to_string_lossy().into_owned())?;
+    let position = chapters
+        .iter()
+        .position(|c| c.id == before_chapter_id)
+        .ok_or(format!("Chapter not found: {before_chapter_id}"))?;
+    let start = position.saturating_sub(count as usize);
+    summary_refs(project_root, &chapters[start..position])
+}
 
-    impl TempDir {
-        fn new(prefix: &str) -> Self {
-            let ts = SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_millis();
-            let path = std::env::temp_dir().join(format!("{prefix}-{ts}"));
-            fs::create_dir_all(&path).expect("create temp dir");
-            Self { path }
+pub(crate) fn summary_refs(project_root: &Path, chapters: &[ChapterMeta]) -> Result<Vec<ChapterSummaryRef>, String> {
+    chapters
+        .iter()
+        .map(|chapter| {
+            let latest = get_latest_summary(project_root, &chapter.id)?;
+            Ok(ChapterSummaryRef {
+                chapter_id: chapter.id.clone(),
+                title: chapter.title.clone(),
+                order: chapter.order,
+                created_at: latest.as_ref().map(|e| e.created_at),
+                summary: latest.map(|e| e.summary),
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SummaryExportReport {
+    pub covered: usize,
+    pub missing: usize,
+}
+
+fn render_summaries_markdown(project_name: &str, refs: &[ChapterSummaryRef]) -> String {
+    let mut out = match project_name.trim() {
+        "" => "# 前情提要\n".to_string(),
+        name => format!("# {name} · 前情提要\n"),
+    };
+    let mut missing = Vec::new();
+    for chapter in refs {
+        match chapter.summary.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            Some(summary) => out.push_str(&format!("\n## {}\n\n{summary}\n", chapter.title)),
+            None => missing.push(chapter),
+        }
+    }
+    if !missing.is_empty() {
+        out.push_str("\n## 缺少摘要\n\n");
+        for chapter in missing {
+            out.push_str(&format!("- {}（{}）\n", chapter.title, chapter.chapter_id));
         }
     }
+    out
+}
+
+/// Writes the latest summary of every chapter, in story order, as a Markdown outline.
+pub fn export_summaries_markdown(
+    project_root: &Path,
+    dest_path: &Path,
+    overwrite: bool,
+) -> Result<SummaryExportReport, String> {
+    if dest_path.exists() && !overwrite {
+        return Err(format!(
+            "'{}' already exists; export with overwrite to replace it",
+            dest_path.display()
+        ));
+    }
+    let project_name = crate::project::read_project_config(project_root)
+        .map(|config| config.name)
+        .unwrap_or_default();
+    let chapters = crate::chapter::list_chapters_sync(project_root.to_string_lossy().into_owned())?;
+    let refs = summary_refs(project_root, &chapters)?;
 
-    impl Drop for TempDir {
-        fn drop(&mut self) {
-            let _ = fs::remove_dir_all(&self.path);
+    let markdown = render_summaries_markdown(&project_name, &refs);
+    write_protection::atomic_write_bytes(dest_path, markdown.as_bytes(), None)?;
+    let covered = refs
+        .iter()
+        .filter(|r| r.summary.as_deref().is_some_and(|s| !s.trim().is_empty()))
+        .count();
+    Ok(SummaryExportReport {
+        covered,
+        missing: refs.len() - covered,
+    })
+}
+
+/// The project's `maxSummariesPerChapter`; unreadable settings keep everything.
+fn retention_limit(project_root: &Path) -> Option<usize> {
+    crate::project::read_project_config(project_root)
+        .ok()
+        .and_then(|config| config.settings.max_summaries_per_chapter)
+        .filter(|&limit| limit > 0)
+        .map(|limit| limit as usize)
+}
+
+/// Drops all but the `keep` newest entries; returns how many were removed.
+fn keep_newest(entries: &mut Vec<SummaryEntry>, keep: usize) -> usize {
+    if entries.len() <= keep {
+        return 0;
+    }
+    // Stable, so same-second entries stay in save order and the later one counts as newer.
+    entries.sort_by_key(|e| e.created_at);
+    let removed = entries.len() - keep;
+    entries.drain(..removed);
+    removed
+}
+
+/// Keeps the `keep_latest` newest summaries of every chapter; returns how many were removed.
+pub fn prune_summaries(project_root: &Path, keep_latest: u32) -> Result<usize, String> {
+    if keep_latest == 0 {
+        return Err("keepLatest must be at least 1".to_string());
+    }
+    let project_root = open_project_root(project_root)?;
+    write_protection::ensure_writable(&project_root)?;
+    let _guard = fs_lock().lock().map_err(|_| "Failed to lock summaries".to_string())?;
+    let shard_dir = ensure_sharded(&project_root)?;
+
+    let mut chapter_ids: Vec<String> = fs::read_dir(&shard_dir)
+        .map_err(|e| format!("Failed to read {SHARD_DIR}: {e}"))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| path.file_stem().map(|s| s.to_string_lossy().into_owned()))
+        .filter(|id| validate_shard_id(id).is_ok())
+        .collect();
+    chapter_ids.sort();
+
+    let mut removed = 0;
+    for chapter_id in chapter_ids {
+        let (mut entries, _) = read_entries(&project_root, &shard_path(&project_root, &chapter_id)?)?;
+        let dropped = keep_newest(&mut entries, keep_latest as usize);
+        if dropped > 0 {
+            write_shard(&project_root, &chapter_id, &entries)?;
+            removed += dropped;
         }
     }
+    Ok(removed)
+}
+
+/// Runs `edit` on the chapter's entry keyed by `created_at` and saves the shard.
+fn edit_entry<T>(
+    project_root: &Path,
+    chapter_id: &str,
+    created_at: u64,
+    edit: impl FnOnce(&mut Vec<SummaryEntry>, usize) -> Result<T, String>,
+) -> Result<T, String> {
+    let project_root = open_project_root(project_root)?;
+    write_protection::ensure_writable(&project_root)?;
+    let _guard = fs_lock().lock().map_err(|_| "Failed to lock summaries".to_string())?;
+    ensure_sharded(&project_root)?;
+
+    let not_found = || format!("Summary not found: {chapter_id} @ {created_at}");
+    let path = shard_path(&project_root, chapter_id).map_err(|_| not_found())?;
+    let (mut entries, _) = read_entries(&project_root, &path)?;
+    let index = entries
+        .iter()
+        .position(|e| e.chapter_id == chapter_id && e.created_at == created_at)
+        .ok_or_else(not_found)?;
+    let result = edit(&mut entries, index)?;
+    write_shard(&project_root, chapter_id, &entries)?;
+    Ok(result)
+}
+
+pub fn update_summary(
+    project_root: &Path,
+    chapter_id: &str,
+    created_at: u64,
+    new_text: String,
+) -> Result<SummaryEntry, String> {
+    if new_text.trim().is_empty() {
+        return Err("summary is empty".to_string());
+    }
+    edit_entry(project_root, chapter_id, created_at, |entries, index| {
+        let entry = &mut entries[index];
+        entry.summary = new_text;
+        entry.updated_at = Some(now_unix_seconds()?);
+        Ok(entry.clone())
+    })
+}
+
+pub fn delete_summary(project_root: &Path, chapter_id: &str, created_at: u64) -> Result<SummaryEntry, String> {
+    edit_entry(project_root, chapter_id, created_at, |entries, index| {
+        Ok(entries.remove(index))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
 
     fn create_min_project(root: &Path) {
         fs::create_dir_all(root.join(".creatorai")).unwrap();
@@ -132,6 +782,7 @@ mod tests {
             &temp.path,
             "chapter_001".to_string(),
             "第一章：主角出场，埋下悬念。".to_string(),
+            SummaryProvenance::manual(),
         )
         .expect("save summary 1");
         assert_eq!(entry1.chapter_id, "chapter_001");
@@ -140,13 +791,374 @@ mod tests {
             &temp.path,
             "chapter_001".to_string(),
             "续写：主角遇到神秘老人。".to_string(),
+            SummaryProvenance {
+                source: SummarySource::Tool,
+                session_id: Some("session_1".to_string()),
+                model: Some("test-model".to_string()),
+            },
         )
         .expect("save summary 2");
         assert_eq!(entry2.chapter_id, "chapter_001");
 
-        let loaded = load_summaries(&temp.path).expect("load summaries");
+        let loaded = load_summaries(&temp.path).expect("load summaries").summaries;
         assert_eq!(loaded.len(), 2);
         assert_eq!(loaded[0].summary, "第一章：主角出场，埋下悬念。");
+        assert_eq!(loaded[0].source, Some(SummarySource::Manual));
         assert_eq!(loaded[1].summary, "续写：主角遇到神秘老人。");
+        assert_eq!(loaded[1].source, Some(SummarySource::Tool));
+        assert_eq!(loaded[1].session_id.as_deref(), Some("session_1"));
+        assert_eq!(loaded[1].model.as_deref(), Some("test-model"));
+    }
+
+    #[test]
+    fn update_and_delete_target_one_entry() {
+        let temp = TempDir::new("creatorai-v2-summary-edit");
+        create_min_project(&temp.path);
+        fs::write(
+            temp.path.join("summaries.json"),
+            r#"[
+  { "chapterId": "chapter_001", "summary": "旧摘要", "createdAt": 100 },
+  { "chapterId": "chapter_001", "summary": "错误摘要", "createdAt": 200 },
+  { "chapterId": "chapter_002", "summary": "第二章", "createdAt": 200 }
+]
+"#,
+        )
+        .unwrap();
+
+        let updated = update_summary(&temp.path, "chapter_001", 200, "修正后的摘要".to_string())
+            .expect("update summary");
+        assert_eq!(updated.created_at, 200);
+        assert!(updated.updated_at.is_some());
+
+        let removed = delete_summary(&temp.path, "chapter_001", 100).expect("delete summary");
+        assert_eq!(removed.summary, "旧摘要");
+        assert!(delete_summary(&temp.path, "chapter_001", 100).is_err());
+        assert!(update_summary(&temp.path, "chapter_002", 200, " ".to_string()).is_err());
+
+        let loaded = load_summaries(&temp.path).expect("load summaries").summaries;
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].summary, "修正后的摘要");
+        assert_eq!(loaded[1].summary, "第二章");
+        assert_eq!(loaded[1].updated_at, None);
+    }
+
+    fn write_legacy(root: &Path) -> String {
+        let legacy = r#"[
+  { "chapterId": "chapter_001", "summary": "第一版", "createdAt": 100 },
+  { "chapterId": "chapter_002", "summary": "第二章", "createdAt": 150 },
+  { "chapterId": "chapter_001", "summary": "第二版", "createdAt": 200 }
+]
+"#;
+        fs::write(root.join("summaries.json"), legacy).unwrap();
+        legacy.to_string()
+    }
+
+    #[test]
+    fn latest_summary_matches_across_layouts() {
+        let legacy = TempDir::new("creatorai-v2-summary-legacy");
+        create_min_project(&legacy.path);
+        let legacy_json = write_legacy(&legacy.path);
+
+        let sharded = TempDir::new("creatorai-v2-summary-sharded");
+        create_min_project(&sharded.path);
+        fs::create_dir_all(sharded.path.join("summaries")).unwrap();
+        fs::write(
+            sharded.path.join("summaries/chapter_001.json"),
+            r#"[{ "chapterId": "chapter_001", "summary": "第一版", "createdAt": 100 },
+                { "chapterId": "chapter_001", "summary": "第二版", "createdAt": 200 }]"#,
+        )
+        .unwrap();
+        fs::write(
+            sharded.path.join("summaries/chapter_002.json"),
+            r#"[{ "chapterId": "chapter_002", "summary": "第二章", "createdAt": 150 }]"#,
+        )
+        .unwrap();
+
+        for root in [&legacy.path, &sharded.path] {
+            let latest = get_latest_summary(root, "chapter_001").expect("latest summary");
+            assert_eq!(latest.map(|e| e.summary).as_deref(), Some("第二版"));
+            assert_eq!(get_latest_summary(root, "chapter_009").expect("missing chapter"), None);
+
+            let all = load_summaries(root).expect("load summaries").summaries;
+            let texts: Vec<&str> = all.iter().map(|e| e.summary.as_str()).collect();
+            assert_eq!(texts, ["第一版", "第二章", "第二版"]);
+
+            save_summary(
+                root,
+                "chapter_002".to_string(),
+                "第二章新版".to_string(),
+                SummaryProvenance::manual(),
+            )
+            .expect("save");
+            let latest = get_latest_summary(root, "chapter_002").expect("latest summary");
+            assert_eq!(latest.map(|e| e.summary).as_deref(), Some("第二章新版"));
+        }
+
+        // The legacy file is split into shards and otherwise left untouched.
+        assert!(legacy.path.join("summaries/chapter_001.json").exists());
+        assert!(!legacy.path.join("summaries.migrating").exists());
+        assert_eq!(fs::read_to_string(legacy.path.join("summaries.json")).unwrap(), legacy_json);
+    }
+
+    #[test]
+    fn legacy_summaries_with_invalid_chapter_ids_are_kept() {
+        let temp = TempDir::new("creatorai-v2-summary-invalid-ids");
+        create_min_project(&temp.path);
+        fs::write(
+            temp.path.join("summaries.json"),
+            r#"[
+  { "chapterId": "chapter_001", "summary": "正常", "createdAt": 100 },
+  { "chapterId": "第二章", "summary": "旧版留下的", "createdAt": 150 }
+]"#,
+        )
+        .unwrap();
+
+        let all = load_summaries(&temp.path).expect("load summaries").summaries;
+        let got: Vec<(&str, &str)> = all.iter().map(|e| (e.chapter_id.as_str(), e.summary.as_str())).collect();
+        assert_eq!(got, [("chapter_001", "正常"), ("第二章", "旧版留下的")]);
+        assert!(temp.path.join("summaries").join(INVALID_ID_SHARD).exists());
+
+        // The fallback shard is never treated as a chapter's own shard.
+        assert_eq!(prune_summaries(&temp.path, 1).expect("prune"), 0);
+        assert_eq!(load_summaries(&temp.path).expect("load again").summaries.len(), 2);
+    }
+
+    #[test]
+    fn unsafe_chapter_ids_never_become_paths() {
+        let temp = TempDir::new("creatorai-v2-summary-ids");
+        create_min_project(&temp.path);
+
+        assert!(save_summary(
+            &temp.path,
+            "../escape".to_string(),
+            "x".to_string(),
+            SummaryProvenance::manual(),
+        ).is_err());
+        assert_eq!(get_latest_summary(&temp.path, "../escape").expect("lookup"), None);
+        assert!(!temp.path.join("escape.json").exists());
+    }
+
+    #[test]
+    fn retention_limit_trims_on_save_and_prune_reports_removals() {
+        let temp = TempDir::new("creatorai-v2-summary-retention");
+        create_min_project(&temp.path);
+        write_legacy(&temp.path);
+
+        assert!(prune_summaries(&temp.path, 0).is_err());
+        assert_eq!(prune_summaries(&temp.path, 1).expect("prune"), 1);
+        assert_eq!(prune_summaries(&temp.path, 1).expect("prune again"), 0);
+        let texts: Vec<String> = load_summaries(&temp.path)
+            .expect("load")
+            .summaries
+            .into_iter()
+            .map(|e| e.summary)
+            .collect();
+        assert_eq!(texts, ["第二章", "第二版"]);
+
+        fs::write(
+            temp.path.join(".creatorai/config.json"),
+            r#"{ "name": "n", "created": 1, "updated": 1, "version": "1.0",
+                 "settings": { "autoSave": true, "autoSaveInterval": 2000, "maxSummariesPerChapter": 2 } }"#,
+        )
+        .unwrap();
+        for text in ["第三版", "第四版"] {
+            save_summary(
+                &temp.path,
+                "chapter_001".to_string(),
+                text.to_string(),
+                SummaryProvenance::manual(),
+            )
+            .expect("save");
+        }
+        let chapter_one: Vec<String> = load_summaries(&temp.path)
+            .expect("load")
+            .summaries
+            .into_iter()
+            .filter(|e| e.chapter_id == "chapter_001")
+            .map(|e| e.summary)
+            .collect();
+        assert_eq!(chapter_one, ["第三版", "第四版"]);
+    }
+
+    #[test]
+    fn recent_summaries_cover_preceding_chapters_in_story_order() {
+        let temp = TempDir::new("creatorai-v2-summary-recent");
+        create_min_project(&temp.path);
+        write_legacy(&temp.path);
+        // Index order differs from id order: chapter_003 was moved to the front.
+        fs::write(
+            temp.path.join("chapters/index.json"),
+            r#"{ "chapters": [
+  { "id": "chapter_001", "title": "一", "order": 2, "created": 1, "updated": 1, "wordCount": 0 },
+  { "id": "chapter_002", "title": "二", "order": 3, "created": 1, "updated": 1, "wordCount": 0 },
+  { "id": "chapter_003", "title": "序", "order": 1, "created": 1, "updated": 1, "wordCount": 0 },
+  { "id": "chapter_004", "title": "四", "order": 4, "created": 1, "updated": 1, "wordCount": 0 }
+], "nextId": 5 }
+"#,
+        )
+        .unwrap();
+
+        let recent = get_recent_summaries(&temp.path, "chapter_004", 5).expect("recent summaries");
+        let ids: Vec<&str> = recent.iter().map(|r| r.chapter_id.as_str()).collect();
+        assert_eq!(ids, ["chapter_003", "chapter_001", "chapter_002"]);
+        assert_eq!(recent[0].summary, None);
+        assert_eq!(recent[1].summary.as_deref(), Some("第二版"));
+        assert_eq!(recent[1].title, "一");
+
+        let last_one = get_recent_summaries(&temp.path, "chapter_004", 1).expect("one chapter");
+        assert_eq!(last_one.len(), 1);
+        assert_eq!(last_one[0].chapter_id, "chapter_002");
+        assert!(get_recent_summaries(&temp.path, "chapter_003", 3).expect("first").is_empty());
+        assert!(get_recent_summaries(&temp.path, "chapter_404", 3).is_err());
+    }
+
+    #[test]
+    fn markdown_export_lists_missing_chapters_and_refuses_to_overwrite() {
+        let temp = TempDir::new("creatorai-v2-summary-export");
+        create_min_project(&temp.path);
+        write_legacy(&temp.path);
+        fs::write(
+            temp.path.join("chapters/index.json"),
+            r#"{ "chapters": [
+  { "id": "chapter_001", "title": "第一章 入城", "order": 1, "created": 1, "updated": 1, "wordCount": 0 },
+  { "id": "chapter_002", "title": "第二章 夜雨", "order": 2, "created": 1, "updated": 1, "wordCount": 0 },
+  { "id": "chapter_003", "title": "第三章 旧友", "order": 3, "created": 1, "updated": 1, "wordCount": 0 }
+], "nextId": 4 }
+"#,
+        )
+        .unwrap();
+
+        let dest = temp.path.join("outline.md");
+        let report = export_summaries_markdown(&temp.path, &dest, false).expect("export");
+        assert_eq!(report, SummaryExportReport { covered: 2, missing: 1 });
+        assert_eq!(
+            fs::read_to_string(&dest).unwrap(),
+            "# 前情提要\n\n## 第一章 入城\n\n第二版\n\n## 第二章 夜雨\n\n第二章\n\n\
+             ## 缺少摘要\n\n- 第三章 旧友（chapter_003）\n"
+        );
+
+        assert!(export_summaries_markdown(&temp.path, &dest, false).is_err());
+        export_summaries_markdown(&temp.path, &dest, true).expect("overwrite");
+    }
+
+    #[test]
+    fn corrupt_shard_falls_back_to_newest_backup() {
+        let temp = TempDir::new("creatorai-v2-summary-corrupt");
+        create_min_project(&temp.path);
+        save_summary(
+            &temp.path,
+            "chapter_001".to_string(),
+            "第一版".to_string(),
+            SummaryProvenance::manual(),
+        )
+        .expect("save 1");
+        save_summary(
+            &temp.path,
+            "chapter_001".to_string(),
+            "第二版".to_string(),
+            SummaryProvenance::manual(),
+        )
+        .expect("save 2");
+
+        // Simulate a crash that left a truncated file behind.
+        fs::write(temp.path.join("summaries/chapter_001.json"), "[{\"chapterId\": \"chap").unwrap();
+
+        let loaded = load_summaries(&temp.path).expect("load with fallback");
+        assert!(loaded.recovered_from_backup);
+        assert_eq!(loaded.warnings.len(), 1);
+        let texts: Vec<&str> = loaded.summaries.iter().map(|e| e.summary.as_str()).collect();
+        assert_eq!(texts, ["第一版"]);
+        let latest = get_latest_summary(&temp.path, "chapter_001").expect("latest");
+        assert_eq!(latest.map(|e| e.summary).as_deref(), Some("第一版"));
+
+        // The save_summary tool keeps working and repairs the file.
+        save_summary(
+            &temp.path,
+            "chapter_001".to_string(),
+            "第三版".to_string(),
+            SummaryProvenance::manual(),
+        )
+        .expect("save 3");
+        let loaded = load_summaries(&temp.path).expect("load repaired");
+        assert!(!loaded.recovered_from_backup);
+        let texts: Vec<&str> = loaded.summaries.iter().map(|e| e.summary.as_str()).collect();
+        assert_eq!(texts, ["第一版", "第三版"]);
+    }
+
+    #[test]
+    fn diff_compares_the_two_newest_versions_by_sentence() {
+        let temp = TempDir::new("creatorai-v2-summary-diff");
+        create_min_project(&temp.path);
+        write_legacy(&temp.path);
+
+        let diff = diff_summaries(&temp.path, "chapter_002", None).expect("single version");
+        assert!(!diff.comparable);
+        assert_eq!(diff.newer.map(|e| e.summary).as_deref(), Some("第二章"));
+        assert!(diff.segments.is_empty());
+
+        let diff = diff_summaries(&temp.path, "chapter_001", None).expect("two versions");
+        assert!(diff.comparable);
+        assert_eq!(diff.older.map(|e| e.created_at), Some(100));
+        assert_eq!(diff.newer.map(|e| e.created_at), Some(200));
+        assert!(diff_summaries(&temp.path, "chapter_001", Some((100, 999))).is_err());
+
+        let segments = diff_sentences("主角入城。遇见老人。夜里下雨。", "主角入城。遇见少女。夜里下雨。");
+        let kinds: Vec<(DiffKind, &str)> = segments.iter().map(|s| (s.kind, s.text.as_str())).collect();
+        assert_eq!(
+            kinds,
+            [
+                (DiffKind::Equal, "主角入城。"),
+                (DiffKind::Removed, "遇见老人。"),
+                (DiffKind::Added, "遇见少女。"),
+                (DiffKind::Equal, "夜里下雨。"),
+            ]
+        );
+    }
+
+    #[test]
+    fn search_is_case_insensitive_and_prefers_latest_versions() {
+        let temp = TempDir::new("creatorai-v2-summary-search");
+        create_min_project(&temp.path);
+        fs::write(
+            temp.path.join("summaries.json"),
+            r#"[
+  { "chapterId": "chapter_001", "summary": "主角捡到 Amulet。", "createdAt": 100 },
+  { "chapterId": "chapter_001", "summary": "主角在集市偷走了护身符 AMULET，被人看见。", "createdAt": 200 },
+  { "chapterId": "chapter_002", "summary": "护身符发光。", "createdAt": 150 }
+]
+"#,
+        )
+        .unwrap();
+        fs::write(
+            temp.path.join("chapters/index.json"),
+            r#"{ "chapters": [
+  { "id": "chapter_001", "title": "第一章 集市", "order": 1, "created": 1, "updated": 1, "wordCount": 0 }
+], "nextId": 3 }
+"#,
+        )
+        .unwrap();
+
+        let result = search_summaries(&temp.path, "amulet", false).expect("search");
+        assert!(!result.truncated);
+        assert_eq!(result.hits.len(), 1);
+        let hit = &result.hits[0];
+        assert_eq!(hit.created_at, 200);
+        assert_eq!(hit.chapter_title.as_deref(), Some("第一章 集市"));
+        let [start, end] = hit.highlights[0];
+        let marked: String = hit.snippet.chars().skip(start).take(end - start).collect();
+        assert_eq!(marked, "AMULET");
+
+        let with_older = search_summaries(&temp.path, "AMULET", true).expect("search older");
+        let versions: Vec<(u64, bool)> = with_older.hits.iter().map(|h| (h.created_at, h.is_latest)).collect();
+        assert_eq!(versions, [(200, true), (100, false)]);
+
+        let orphan = search_summaries(&temp.path, "护身符", false).expect("search orphan");
+        let chapters: Vec<(&str, Option<&str>)> = orphan
+            .hits
+            .iter()
+            .map(|h| (h.chapter_id.as_str(), h.chapter_title.as_deref()))
+            .collect();
+        assert_eq!(chapters, [("chapter_001", Some("第一章 集市")), ("chapter_002", None)]);
+        assert!(search_summaries(&temp.path, "  ", false).is_err());
     }
 }
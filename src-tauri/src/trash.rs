@@ -0,0 +1,299 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+use crate::security::validate_path;
+use crate::write_protection;
+
+const TRASH_DIR: &str = ".trash";
+const MANIFEST_RELATIVE_PATH: &str = ".trash/manifest.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashEntry {
+    pub id: String,
+    /// Path relative to the project root that the file was deleted from.
+    pub original_path: String,
+    /// Path relative to the project root where the file now lives.
+    pub trash_path: String,
+    pub size: u64,
+    /// Unix milliseconds.
+    pub deleted_at: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct TrashManifest {
+    entries: Vec<TrashEntry>,
+}
+
+static TRASH_FS_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+fn fs_lock() -> &'static Mutex<()> {
+    TRASH_FS_LOCK.get_or_init(|| Mutex::new(()))
+}
+
+fn now_millis() -> Result<u64, String> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .map_err(|e| format!("Failed to read system time: {e}"))
+}
+
+fn canonical_root(project_root: &Path) -> Result<PathBuf, String> {
+    project_root
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path: {e}"))
+}
+
+fn normalize_relative(relative_path: &str) -> String {
+    relative_path
+        .trim()
+        .replace('\\', "/")
+        .trim_start_matches("./")
+        .to_string()
+}
+
+fn read_manifest(project_root: &Path) -> Result<TrashManifest, String> {
+    let path = validate_path(project_root, MANIFEST_RELATIVE_PATH)?;
+    if !path.exists() {
+        return Ok(TrashManifest::default());
+    }
+    let bytes = fs::read(&path).map_err(|e| format!("Failed to read trash manifest: {e}"))?;
+    serde_json::from_slice::<TrashManifest>(&bytes)
+        .map_err(|e| format!("Failed to parse trash manifest: {e}"))
+}
+
+fn write_manifest(project_root: &Path, manifest: &TrashManifest) -> Result<(), String> {
+    let path = validate_path(project_root, MANIFEST_RELATIVE_PATH)?;
+    let json = serde_json::to_string_pretty(manifest)
+        .map_err(|e| format!("Serialize JSON failed: {e}"))?;
+    write_protection::atomic_write_bytes(&path, format!("{json}\n").as_bytes(), None)
+}
+
+fn move_file(from: &Path, to: &Path) -> Result<(), String> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory '{}': {e}", parent.display()))?;
+    }
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    // rename can fail across volumes; fall back to copy + remove.
+    fs::copy(from, to).map_err(|e| format!("Failed to move '{}': {e}", from.display()))?;
+    fs::remove_file(from).map_err(|e| format!("Failed to remove '{}': {e}", from.display()))
+}
+
+fn remove_empty_dirs_up_to(start: Option<&Path>, stop: &Path) {
+    let mut current = start.map(Path::to_path_buf);
+    while let Some(dir) = current {
+        if dir == stop || !dir.starts_with(stop) || fs::remove_dir(&dir).is_err() {
+            break;
+        }
+        current = dir.parent().map(Path::to_path_buf);
+    }
+}
+
+/// Moves `relative_path` into `.trash/<timestamp>/<relative_path>` and records it in the
+/// trash manifest.
+pub fn move_to_trash(project_root: &Path, relative_path: &str) -> Result<TrashEntry, String> {
+    let _guard = fs_lock()
+        .lock()
+        .map_err(|_| "Failed to lock trash storage".to_string())?;
+
+    let project_root = canonical_root(project_root)?;
+    write_protection::ensure_writable(&project_root)?;
+    let original_path = normalize_relative(relative_path);
+    if original_path.is_empty() {
+        return Err("Cannot move the project root to trash".to_string());
+    }
+    if original_path == TRASH_DIR || original_path.starts_with(".trash/") {
+        return Err("File is already in trash".to_string());
+    }
+
+    let full_path = validate_path(&project_root, &original_path)?;
+    let meta = fs::symlink_metadata(&full_path)
+        .map_err(|e| format!("Failed to stat '{original_path}': {e}"))?;
+    if meta.file_type().is_dir() {
+        return Err(format!("'{original_path}' is a directory"));
+    }
+
+    let mut deleted_at = now_millis()?;
+    let mut trash_path = format!("{TRASH_DIR}/{deleted_at}/{original_path}");
+    while project_root.join(&trash_path).exists() {
+        deleted_at += 1;
+        trash_path = format!("{TRASH_DIR}/{deleted_at}/{original_path}");
+    }
+    let trash_full = validate_path(&project_root, &trash_path)?;
+
+    let mut manifest = read_manifest(&project_root)?;
+    move_file(&full_path, &trash_full)?;
+
+    let entry = TrashEntry {
+        id: Uuid::new_v4().to_string(),
+        original_path,
+        trash_path,
+        size: meta.len(),
+        deleted_at,
+    };
+    manifest.entries.push(entry.clone());
+    if let Err(e) = write_manifest(&project_root, &manifest) {
+        let _ = move_file(&trash_full, &full_path);
+        return Err(e);
+    }
+
+    Ok(entry)
+}
+
+pub fn list_trash(project_root: &Path) -> Result<Vec<TrashEntry>, String> {
+    let project_root = canonical_root(project_root)?;
+    let mut entries = read_manifest(&project_root)?.entries;
+    entries.sort_by_key(|e| std::cmp::Reverse(e.deleted_at));
+    Ok(entries)
+}
+
+/// Puts a trashed file back at its original location. Refuses to replace an existing file
+/// unless `overwrite` is set, in which case the current content is backed up first.
+pub fn restore_from_trash(
+    project_root: &Path,
+    entry_id: &str,
+    overwrite: bool,
+) -> Result<TrashEntry, String> {
+    let _guard = fs_lock()
+        .lock()
+        .map_err(|_| "Failed to lock trash storage".to_string())?;
+
+    let project_root = canonical_root(project_root)?;
+    write_protection::ensure_writable(&project_root)?;
+    let mut manifest = read_manifest(&project_root)?;
+    let Some(pos) = manifest.entries.iter().position(|e| e.id == entry_id) else {
+        return Err("Trash entry not found".to_string());
+    };
+    let entry = manifest.entries[pos].clone();
+
+    let trash_full = validate_path(&project_root, &entry.trash_path)?;
+    if !trash_full.exists() {
+        return Err("Trashed file is missing on disk".to_string());
+    }
+    let target = validate_path(&project_root, &entry.original_path)?;
+    if target.exists() {
+        if !overwrite {
+            return Err(format!(
+                "'{}' already exists; restore with overwrite to replace it",
+                entry.original_path
+            ));
+        }
+        write_protection::backup_existing_file(&project_root, &target)?;
+    }
+
+    let content =
+        fs::read(&trash_full).map_err(|e| format!("Failed to read trashed file: {e}"))?;
+    write_protection::atomic_write_bytes(&target, &content, None)?;
+
+    manifest.entries.remove(pos);
+    write_manifest(&project_root, &manifest)?;
+    let _ = fs::remove_file(&trash_full);
+    remove_empty_dirs_up_to(trash_full.parent(), &project_root.join(TRASH_DIR));
+
+    Ok(entry)
+}
+
+/// Permanently deletes trash entries older than `older_than_days` (all entries when `None`).
+/// Returns the number of entries removed.
+pub fn empty_trash(project_root: &Path, older_than_days: Option<u32>) -> Result<usize, String> {
+    let _guard = fs_lock()
+        .lock()
+        .map_err(|_| "Failed to lock trash storage".to_string())?;
+
+    let project_root = canonical_root(project_root)?;
+    write_protection::ensure_writable(&project_root)?;
+    let mut manifest = read_manifest(&project_root)?;
+    let cutoff = match older_than_days {
+        Some(days) => now_millis()?.saturating_sub(u64::from(days) * 24 * 60 * 60 * 1000),
+        None => u64::MAX,
+    };
+
+    // The manifest is written first so a failed write leaves every entry restorable.
+    let (expired, kept): (Vec<TrashEntry>, Vec<TrashEntry>) =
+        manifest.entries.drain(..).partition(|entry| entry.deleted_at <= cutoff);
+    manifest.entries = kept;
+    if expired.is_empty() {
+        return Ok(0);
+    }
+    write_manifest(&project_root, &manifest)?;
+
+    let trash_root = project_root.join(TRASH_DIR);
+    for entry in &expired {
+        if let Ok(full) = validate_path(&project_root, &entry.trash_path) {
+            let _ = fs::remove_file(&full);
+            remove_empty_dirs_up_to(full.parent(), &trash_root);
+        }
+    }
+    Ok(expired.len())
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn list_trash_entries(project_path: String) -> Result<Vec<TrashEntry>, String> {
+    tauri::async_runtime::spawn_blocking(move || list_trash(Path::new(&project_path)))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn restore_trash_entry(
+    project_path: String,
+    entry_id: String,
+    overwrite: Option<bool>,
+) -> Result<TrashEntry, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        restore_from_trash(Path::new(&project_path), &entry_id, overwrite.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn empty_project_trash(
+    project_path: String,
+    older_than_days: Option<u32>,
+) -> Result<usize, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        empty_trash(Path::new(&project_path), older_than_days)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn trash_restore_and_empty_roundtrip() {
+        let temp = TempDir::new("creatorai-v2-trash");
+        fs::create_dir_all(temp.path.join("notes")).unwrap();
+        fs::write(temp.path.join("notes/idea.txt"), "first").unwrap();
+
+        let entry = move_to_trash(&temp.path, "notes/idea.txt").expect("move_to_trash");
+        assert!(!temp.path.join("notes/idea.txt").exists());
+        assert_eq!(entry.original_path, "notes/idea.txt");
+        assert_eq!(entry.size, 5);
+        assert_eq!(list_trash(&temp.path).unwrap().len(), 1);
+
+        fs::write(temp.path.join("notes/idea.txt"), "second").unwrap();
+        let err = restore_from_trash(&temp.path, &entry.id, false).expect_err("no overwrite");
+        assert!(err.contains("already exists"));
+
+        restore_from_trash(&temp.path, &entry.id, true).expect("restore with overwrite");
+        assert_eq!(fs::read_to_string(temp.path.join("notes/idea.txt")).unwrap(), "first");
+        assert!(list_trash(&temp.path).unwrap().is_empty());
+
+        move_to_trash(&temp.path, "notes/idea.txt").expect("trash again");
+        assert_eq!(empty_trash(&temp.path, Some(1)).unwrap(), 0);
+        assert_eq!(empty_trash(&temp.path, None).unwrap(), 1);
+        assert!(list_trash(&temp.path).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,416 @@
+//! Per-day AI usage counters in `~/.creatorai/usage.json`.
+//!
+//! Calls are accumulated in memory and merged into the file in batches. A flush re-reads
+//! the file under a lock file and adds only this process's deltas, so several app windows
+//! can share the file without losing each other's counts.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::config;
+use crate::write_protection;
+
+const USAGE_FILE_NAME: &str = "usage.json";
+const LOCK_FILE_NAME: &str = "usage.json.lock";
+const FLUSH_EVERY_CALLS: u32 = 20;
+const FLUSH_INTERVAL: Duration = Duration::from_secs(30);
+/// A lock file older than this is assumed to belong to a crashed window.
+const LOCK_STALE_AFTER: Duration = Duration::from_secs(10);
+const LOCK_WAIT: Duration = Duration::from_secs(2);
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+const CANCELLED_MESSAGE: &str = "已停止生成";
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageCounters {
+    pub requests: u64,
+    pub errors: u64,
+    /// The engine does not report token usage, so sizes are tracked in characters.
+    pub input_chars: u64,
+    pub output_chars: u64,
+}
+
+impl UsageCounters {
+    fn add(&mut self, other: &UsageCounters) {
+        self.requests += other.requests;
+        self.errors += other.errors;
+        self.input_chars += other.input_chars;
+        self.output_chars += other.output_chars;
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+struct UsageBucket {
+    /// Unix seconds at UTC midnight.
+    day: u64,
+    provider_id: String,
+    model: String,
+    #[serde(flatten)]
+    counters: UsageCounters,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct UsageFile {
+    #[serde(default)]
+    buckets: Vec<UsageBucket>,
+}
+
+type BucketKey = (u64, String, String);
+
+struct Pending {
+    deltas: HashMap<BucketKey, UsageCounters>,
+    calls: u32,
+    last_flush: Instant,
+}
+
+static PENDING: OnceLock<Mutex<Pending>> = OnceLock::new();
+
+fn pending() -> &'static Mutex<Pending> {
+    PENDING.get_or_init(|| {
+        Mutex::new(Pending {
+            deltas: HashMap::new(),
+            calls: 0,
+            last_flush: Instant::now(),
+        })
+    })
+}
+
+fn now_unix_seconds() -> Result<u64, String> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .map_err(|e| format!("Failed to read system time: {e}"))
+}
+
+fn day_start(unix_seconds: u64) -> u64 {
+    unix_seconds - unix_seconds % SECS_PER_DAY
+}
+
+struct UsageLock(PathBuf);
+
+impl Drop for UsageLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+fn lock_usage_file(dir: &Path) -> Result<UsageLock, String> {
+    let path = dir.join(LOCK_FILE_NAME);
+    let started = Instant::now();
+    loop {
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(_) => return Ok(UsageLock(path)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                let stale = fs::metadata(&path)
+                    .and_then(|m| m.modified())
+                    .ok()
+                    .and_then(|modified| modified.elapsed().ok())
+                    .is_some_and(|age| age > LOCK_STALE_AFTER);
+                if stale {
+                    let _ = fs::remove_file(&path);
+                    continue;
+                }
+                if started.elapsed() > LOCK_WAIT {
+                    return Err("Usage stats are locked by another window".to_string());
+                }
+                std::thread::sleep(Duration::from_millis(20));
+            }
+            Err(e) => return Err(format!("Failed to lock usage stats: {e}")),
+        }
+    }
+}
+
+fn read_usage_file(path: &Path) -> Result<UsageFile, String> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(UsageFile::default()),
+        Err(e) => return Err(format!("Failed to read usage stats: {e}")),
+    };
+    match serde_json::from_slice(&bytes) {
+        Ok(file) => Ok(file),
+        Err(e) => {
+            // Keep the unreadable file for inspection and start counting afresh.
+            eprintln!("[usage] usage.json is corrupt ({e}); starting a new file");
+            let _ = fs::rename(path, path.with_extension("json.corrupt"));
+            Ok(UsageFile::default())
+        }
+    }
+}
+
+fn write_usage_file(path: &Path, file: &UsageFile) -> Result<(), String> {
+    let json =
+        serde_json::to_string_pretty(file).map_err(|e| format!("Serialize JSON failed: {e}"))?;
+    write_protection::atomic_write_bytes(path, format!("{json}\n").as_bytes(), None)
+}
+
+fn merge_into(file: &mut UsageFile, deltas: &HashMap<BucketKey, UsageCounters>) {
+    for ((day, provider_id, model), counters) in deltas {
+        match file
+            .buckets
+            .iter_mut()
+            .find(|b| b.day == *day && &b.provider_id == provider_id && &b.model == model)
+        {
+            Some(bucket) => bucket.counters.add(counters),
+            None => file.buckets.push(UsageBucket {
+                day: *day,
+                provider_id: provider_id.clone(),
+                model: model.clone(),
+                counters: *counters,
+            }),
+        }
+    }
+    file.buckets.sort_by(|a, b| {
+        (a.day, &a.provider_id, &a.model).cmp(&(b.day, &b.provider_id, &b.model))
+    });
+}
+
+fn flush_deltas_in(dir: &Path, deltas: &HashMap<BucketKey, UsageCounters>) -> Result<(), String> {
+    let _lock = lock_usage_file(dir)?;
+    let path = dir.join(USAGE_FILE_NAME);
+    let mut file = read_usage_file(&path)?;
+    merge_into(&mut file, deltas);
+    write_usage_file(&path, &file)
+}
+
+/// Writes all pending counters to disk. On failure they stay queued for the next flush.
+pub fn flush() -> Result<(), String> {
+    let deltas = {
+        let mut pending = pending().lock().unwrap_or_else(|e| e.into_inner());
+        pending.calls = 0;
+        pending.last_flush = Instant::now();
+        std::mem::take(&mut pending.deltas)
+    };
+    if deltas.is_empty() {
+        return Ok(());
+    }
+    let result = config::get_global_config_dir().and_then(|dir| flush_deltas_in(&dir, &deltas));
+    if result.is_err() {
+        let mut pending = pending().lock().unwrap_or_else(|e| e.into_inner());
+        for (key, counters) in deltas {
+            pending.deltas.entry(key).or_default().add(&counters);
+        }
+    }
+    result
+}
+
+/// Counts one AI call. Never fails the caller; flush problems are only logged.
+pub fn record(provider_id: &str, model: &str, input_chars: u64, output: Result<u64, &str>) {
+    if provider_id.is_empty() {
+        return;
+    }
+    let Ok(now) = now_unix_seconds() else {
+        return;
+    };
+    let delta = UsageCounters {
+        requests: 1,
+        errors: u64::from(output.is_err_and(|e| e != CANCELLED_MESSAGE)),
+        input_chars,
+        output_chars: output.unwrap_or(0),
+    };
+
+    let due = {
+        let mut pending = pending().lock().unwrap_or_else(|e| e.into_inner());
+        pending
+            .deltas
+            .entry((day_start(now), provider_id.to_string(), model.to_string()))
+            .or_default()
+            .add(&delta);
+        pending.calls += 1;
+        pending.calls >= FLUSH_EVERY_CALLS || pending.last_flush.elapsed() >= FLUSH_INTERVAL
+    };
+    if due {
+        if let Err(e) = flush() {
+            eprintln!("[usage] Failed to save usage stats: {e}");
+        }
+    }
+}
+
+fn content_chars(value: &Value) -> u64 {
+    match value.get("content").unwrap_or(value) {
+        Value::String(text) => text.chars().count() as u64,
+        Value::Null => 0,
+        other => other.to_string().chars().count() as u64,
+    }
+}
+
+/// Character size of a system prompt plus chat messages.
+pub fn prompt_chars(system_prompt: &str, messages: &[Value]) -> u64 {
+    system_prompt.chars().count() as u64 + messages.iter().map(content_chars).sum::<u64>()
+}
+
+/// [`record`] for calls described by the provider/parameters JSON sent to the engine.
+pub fn record_call(provider: &Value, parameters: &Value, input_chars: u64, output: Result<u64, &str>) {
+    let provider_id = provider.get("id").and_then(Value::as_str).unwrap_or_default();
+    let model = parameters.get("model").and_then(Value::as_str).unwrap_or_default();
+    record(provider_id, model, input_chars, output);
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageRange {
+    /// Unix seconds; the whole day containing it is included.
+    pub since: Option<u64>,
+    /// Unix seconds; exclusive.
+    pub until: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageBreakdown {
+    pub provider_id: String,
+    pub model: String,
+    #[serde(flatten)]
+    pub counters: UsageCounters,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyUsage {
+    /// Unix seconds at UTC midnight.
+    pub day: u64,
+    #[serde(flatten)]
+    pub counters: UsageCounters,
+}
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageSummary {
+    pub totals: UsageCounters,
+    pub by_model: Vec<UsageBreakdown>,
+    pub daily: Vec<DailyUsage>,
+}
+
+fn summarize(buckets: &[UsageBucket], range: &UsageRange) -> UsageSummary {
+    let since = range.since.map(day_start).unwrap_or(0);
+    let until = range.until.unwrap_or(u64::MAX);
+    let mut totals = UsageCounters::default();
+    let mut by_model: BTreeMap<(&str, &str), UsageCounters> = BTreeMap::new();
+    let mut daily: BTreeMap<u64, UsageCounters> = BTreeMap::new();
+
+    for bucket in buckets.iter().filter(|b| b.day >= since && b.day < until) {
+        totals.add(&bucket.counters);
+        by_model
+            .entry((&bucket.provider_id, &bucket.model))
+            .or_default()
+            .add(&bucket.counters);
+        daily.entry(bucket.day).or_default().add(&bucket.counters);
+    }
+
+    UsageSummary {
+        totals,
+        by_model: by_model
+            .into_iter()
+            .map(|((provider_id, model), counters)| UsageBreakdown {
+                provider_id: provider_id.to_string(),
+                model: model.to_string(),
+                counters,
+            })
+            .collect(),
+        daily: daily
+            .into_iter()
+            .map(|(day, counters)| DailyUsage { day, counters })
+            .collect(),
+    }
+}
+
+pub fn usage_summary(range: &UsageRange) -> Result<UsageSummary, String> {
+    if let Err(e) = flush() {
+        eprintln!("[usage] Failed to save usage stats: {e}");
+    }
+    let dir = config::get_global_config_dir()?;
+    let mut file = read_usage_file(&dir.join(USAGE_FILE_NAME))?;
+    // Anything a failed flush left queued is still part of the picture.
+    {
+        let pending = pending().lock().unwrap_or_else(|e| e.into_inner());
+        merge_into(&mut file, &pending.deltas);
+    }
+    Ok(summarize(&file.buckets, range))
+}
+
+pub fn reset() -> Result<(), String> {
+    let dir = config::get_global_config_dir()?;
+    let _lock = lock_usage_file(&dir)?;
+    {
+        let mut pending = pending().lock().unwrap_or_else(|e| e.into_inner());
+        pending.deltas.clear();
+        pending.calls = 0;
+    }
+    write_usage_file(&dir.join(USAGE_FILE_NAME), &UsageFile::default())
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_usage_summary(range: Option<UsageRange>) -> Result<UsageSummary, String> {
+    tauri::async_runtime::spawn_blocking(move || usage_summary(&range.unwrap_or_default()))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command]
+pub async fn reset_usage_stats() -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(reset)
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn delta(day: u64, provider_id: &str, model: &str, requests: u64) -> HashMap<BucketKey, UsageCounters> {
+        HashMap::from([(
+            (day, provider_id.to_string(), model.to_string()),
+            UsageCounters {
+                requests,
+                errors: 0,
+                input_chars: 10 * requests,
+                output_chars: 5 * requests,
+            },
+        )])
+    }
+
+    #[test]
+    fn flushes_merge_with_counts_written_by_other_windows() {
+        let temp = TempDir::new("creatorai-v2-usage");
+        flush_deltas_in(&temp.path, &delta(0, "p1", "m1", 2)).unwrap();
+        // A second window flushing the same bucket adds to it instead of overwriting.
+        flush_deltas_in(&temp.path, &delta(0, "p1", "m1", 3)).unwrap();
+        flush_deltas_in(&temp.path, &delta(SECS_PER_DAY, "p2", "m2", 1)).unwrap();
+        assert!(!temp.path.join(LOCK_FILE_NAME).exists());
+
+        let file = read_usage_file(&temp.path.join(USAGE_FILE_NAME)).unwrap();
+        assert_eq!(file.buckets.len(), 2);
+        assert_eq!(file.buckets[0].counters.requests, 5);
+        assert_eq!(file.buckets[0].counters.input_chars, 50);
+    }
+
+    #[test]
+    fn summary_filters_by_day_and_groups_by_model() {
+        let mut file = UsageFile::default();
+        merge_into(&mut file, &delta(0, "p1", "m1", 1));
+        merge_into(&mut file, &delta(SECS_PER_DAY, "p1", "m1", 2));
+        merge_into(&mut file, &delta(SECS_PER_DAY, "p2", "m2", 4));
+
+        let all = summarize(&file.buckets, &UsageRange::default());
+        assert_eq!(all.totals.requests, 7);
+        assert_eq!(all.daily.len(), 2);
+        assert_eq!(all.by_model.len(), 2);
+
+        // `since` in the middle of day 1 still includes all of day 1.
+        let recent = summarize(
+            &file.buckets,
+            &UsageRange {
+                since: Some(SECS_PER_DAY + 3600),
+                until: None,
+            },
+        );
+        assert_eq!(recent.totals.requests, 6);
+        assert_eq!(recent.daily, vec![DailyUsage { day: SECS_PER_DAY, counters: recent.totals }]);
+    }
+}
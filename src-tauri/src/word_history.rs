@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::recent_projects::read_project_stats;
+use crate::security::validate_path;
+use crate::write_protection;
+
+const SNAPSHOTS_RELATIVE_PATH: &str = ".creatorai/stats/snapshots.json";
+const SNAPSHOT_INTERVAL_SECS: u64 = 24 * 60 * 60;
+const MAX_SNAPSHOTS: usize = 400;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WordCountSnapshot {
+    /// Unix seconds when the snapshot was taken.
+    pub date: u64,
+    pub total_words: u64,
+    pub chapter_count: u32,
+}
+
+fn now_unix_seconds() -> Result<u64, String> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .map_err(|e| format!("Failed to read system time: {e}"))
+}
+
+fn read_snapshots(project_root: &Path) -> Result<Vec<WordCountSnapshot>, String> {
+    let path = validate_path(project_root, SNAPSHOTS_RELATIVE_PATH)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let bytes = fs::read(&path).map_err(|e| format!("Failed to read stats snapshots: {e}"))?;
+    serde_json::from_slice::<Vec<WordCountSnapshot>>(&bytes)
+        .map_err(|e| format!("Failed to parse stats snapshots: {e}"))
+}
+
+fn write_snapshots(project_root: &Path, snapshots: &[WordCountSnapshot]) -> Result<(), String> {
+    let path = validate_path(project_root, SNAPSHOTS_RELATIVE_PATH)?;
+    let json = serde_json::to_string_pretty(snapshots)
+        .map_err(|e| format!("Serialize JSON failed: {e}"))?;
+    write_protection::atomic_write_bytes(&path, format!("{json}\n").as_bytes(), None)
+}
+
+fn snapshot_at(project_root: &Path, now: u64) -> Result<Option<WordCountSnapshot>, String> {
+    let mut snapshots = read_snapshots(project_root)?;
+    if let Some(last) = snapshots.last() {
+        if now.saturating_sub(last.date) < SNAPSHOT_INTERVAL_SECS {
+            return Ok(None);
+        }
+    }
+
+    let Some((total_words, chapter_count)) =
+        read_project_stats(&project_root.to_string_lossy())
+    else {
+        return Err("Failed to read chapters/index.json".to_string());
+    };
+    let snapshot = WordCountSnapshot {
+        date: now,
+        total_words,
+        chapter_count,
+    };
+    snapshots.push(snapshot.clone());
+    if snapshots.len() > MAX_SNAPSHOTS {
+        let excess = snapshots.len() - MAX_SNAPSHOTS;
+        snapshots.drain(..excess);
+    }
+    write_snapshots(project_root, &snapshots)?;
+    Ok(Some(snapshot))
+}
+
+/// Appends a word-count snapshot when the previous one is more than a day old.
+/// Returns the new snapshot, or `None` when it was not due yet.
+pub fn snapshot_if_due(project_root: &Path) -> Result<Option<WordCountSnapshot>, String> {
+    let project_root = project_root
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path: {e}"))?;
+    snapshot_at(&project_root, now_unix_seconds()?)
+}
+
+pub fn history_since(project_root: &Path, since: Option<u64>) -> Result<Vec<WordCountSnapshot>, String> {
+    let project_root = project_root
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path: {e}"))?;
+    let since = since.unwrap_or(0);
+    Ok(read_snapshots(&project_root)?
+        .into_iter()
+        .filter(|s| s.date >= since)
+        .collect())
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_word_count_history(
+    project_path: String,
+    since: Option<u64>,
+) -> Result<Vec<WordCountSnapshot>, String> {
+    tauri::async_runtime::spawn_blocking(move || history_since(Path::new(&project_path), since))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn temp_project(prefix: &str) -> TempDir {
+        let temp = TempDir::new(prefix);
+        fs::create_dir_all(temp.path.join("chapters")).expect("create temp dir");
+        fs::write(
+            temp.path.join("chapters/index.json"),
+            "{ \"chapters\": [{ \"id\": \"chapter_001\", \"title\": \"一\", \"order\": 1, \"created\": 1, \"updated\": 1, \"wordCount\": 42 }], \"nextId\": 2 }\n",
+        )
+        .expect("write index");
+        temp
+    }
+
+    #[test]
+    fn snapshots_are_daily_and_pruned() {
+        let temp = temp_project("creatorai-v2-word-history");
+        let root = temp.path.canonicalize().unwrap();
+
+        let first = snapshot_at(&root, 1_000).unwrap().expect("first snapshot");
+        assert_eq!(first.total_words, 42);
+        assert_eq!(first.chapter_count, 1);
+        assert!(snapshot_at(&root, 1_000 + 60).unwrap().is_none());
+
+        for day in 1..=(MAX_SNAPSHOTS as u64 + 5) {
+            snapshot_at(&root, 1_000 + day * SNAPSHOT_INTERVAL_SECS).unwrap();
+        }
+        let all = history_since(&root, None).unwrap();
+        assert_eq!(all.len(), MAX_SNAPSHOTS);
+        assert!(all[0].date > 1_000);
+
+        let cutoff = all[all.len() - 3].date;
+        assert_eq!(history_since(&root, Some(cutoff)).unwrap().len(), 3);
+    }
+}
@@ -1,6 +1,29 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::activity_log::{self, Actor};
+use crate::diff::{self, TextDiff};
+use crate::file_ops::{ReadParams, ReadResult};
+use crate::project::{BackupRetention, ChapterMeta};
+use crate::security::validate_path;
+
+const BACKUP_DIR: &str = ".backup";
+/// Newest backup per file with its hash, so unchanged content isn't copied again.
+const BACKUP_INDEX: &str = "index.json";
+/// Minimum time between automatic retention passes for one project.
+const AUTO_PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+const MILLIS_PER_DAY: u128 = 24 * 60 * 60 * 1000;
+/// Snapshots this recent may still be being written, so retention leaves them alone.
+const IN_FLIGHT_MILLIS: u128 = 10_000;
+/// Used when a project doesn't set `maxFileBytes`.
+pub const DEFAULT_MAX_FILE_BYTES: u64 = 20 * 1024 * 1024;
+/// Whole-file reads refuse anything larger (or larger than the write limit, if higher).
+pub const MAX_READ_BYTES: u64 = 100 * 1024 * 1024;
 
 fn now_millis() -> Result<u128, String> {
     SystemTime::now()
@@ -11,6 +34,128 @@ fn now_millis() -> Result<u128, String> {
         .map_err(|_| "System time overflowed u128".to_string())
 }
 
+/// Nearest ancestor of `path` that holds a project config.
+fn enclosing_project(path: &Path) -> Option<&Path> {
+    path.ancestors()
+        .skip(1)
+        .find(|dir| dir.join(".creatorai").join("config.json").is_file())
+}
+
+/// Returned for every write refused because the project is read-only.
+pub const READ_ONLY_ERROR: &str = "项目处于只读模式";
+/// Bookkeeping and derived caches that keep working in read-only mode, so the project can
+/// still be opened, searched and switched back; a trailing `/` covers a directory.
+const READ_ONLY_EXEMPT: &[&str] = &[
+    ".creatorai/config.json",
+    ".creatorai/lock",
+    ".creatorai/stats/",
+    ".creatorai/rag/index.bin",
+    ".creatorai/rag/embedding-status.json",
+    ".backup/",
+];
+
+/// Fails with [`READ_ONLY_ERROR`] when the project's `readOnly` setting is on.
+pub fn ensure_writable(project_root: &Path) -> Result<(), String> {
+    let read_only = crate::project::project_settings(project_root).is_ok_and(|s| s.read_only);
+    if read_only {
+        return Err(READ_ONLY_ERROR.to_string());
+    }
+    Ok(())
+}
+
+/// [`ensure_writable`] for a write to `full_path`; exempt bookkeeping files always pass.
+fn check_writable_in(project_root: &Path, full_path: &Path) -> Result<(), String> {
+    if let Ok(relative) = full_path.strip_prefix(project_root) {
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        let exempt = READ_ONLY_EXEMPT
+            .iter()
+            .any(|e| relative == *e || (e.ends_with('/') && relative.starts_with(e)));
+        if exempt {
+            return Ok(());
+        }
+    }
+    ensure_writable(project_root)
+}
+
+fn max_file_bytes(project_root: Option<&Path>) -> u64 {
+    project_root
+        .and_then(|root| crate::project::project_settings(root).ok())
+        .and_then(|settings| settings.max_file_bytes)
+        .filter(|&limit| limit > 0)
+        .unwrap_or(DEFAULT_MAX_FILE_BYTES)
+}
+
+/// Rejects a write of project content that would leave `full_path` at `new_len` bytes
+/// when that exceeds `project_root`'s `maxFileBytes`. Only content writes (files, chapters,
+/// knowledge docs) are capped; exports, indexes and app config are not.
+pub fn check_write_size(project_root: &Path, full_path: &Path, new_len: u64) -> Result<(), String> {
+    let limit = max_file_bytes(Some(project_root));
+    if new_len > limit {
+        return Err(format!(
+            "'{}' would be {new_len} bytes, over the {limit}-byte file size limit (maxFileBytes)",
+            full_path.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Rejects reading all of `full_path` at once when it is over the read cap.
+pub fn check_read_size(full_path: &Path) -> Result<(), String> {
+    let Ok(meta) = fs::metadata(full_path) else {
+        return Ok(());
+    };
+    let limit = MAX_READ_BYTES.max(max_file_bytes(enclosing_project(full_path)));
+    if meta.len() > limit {
+        return Err(format!(
+            "'{}' is {} bytes, over the {limit}-byte read limit; read it in ranges with offset/limit instead",
+            full_path.display(),
+            meta.len()
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn sha256_hex(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+struct IndexedBackup {
+    timestamp: u64,
+    sha256: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupIndex {
+    /// Keyed by path relative to the project root, `/`-separated.
+    files: BTreeMap<String, IndexedBackup>,
+}
+
+static BACKUP_INDEX_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+/// A missing or unreadable index only disables deduplication until it is rewritten.
+fn read_backup_index(project_root: &Path) -> BackupIndex {
+    fs::read(project_root.join(BACKUP_DIR).join(BACKUP_INDEX))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+fn write_backup_index(project_root: &Path, index: &BackupIndex) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(index).map_err(|e| format!("Serialize JSON failed: {e}"))?;
+    atomic_write_bytes(
+        &project_root.join(BACKUP_DIR).join(BACKUP_INDEX),
+        format!("{json}\n").as_bytes(),
+        None,
+    )
+}
+
+/// Copies `full_path` to `.backup/<millis>/<relative>` before it is overwritten. When the
+/// newest backup of the file already has the same content, that copy is returned instead.
 pub fn backup_existing_file(project_root: &Path, full_path: &Path) -> Result<Option<PathBuf>, String> {
     if !full_path.exists() {
         return Ok(None);
@@ -25,24 +170,506 @@ pub fn backup_existing_file(project_root: &Path, full_path: &Path) -> Result<Opt
     let relative = full_path
         .strip_prefix(project_root)
         .map_err(|_| "Failed to compute relative path".to_string())?;
+    let key = relative.to_string_lossy().replace('\\', "/");
 
-    let ts = now_millis()?;
-    let backup_path = project_root
-        .join(".backup")
-        .join(ts.to_string())
-        .join(relative);
+    let content = fs::read(full_path).map_err(|e| format!("Failed to backup '{}': {e}", full_path.display()))?;
+    let sha256 = sha256_hex(&content);
 
-    if let Some(parent) = backup_path.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create backup directory '{}': {e}", parent.display()))?;
-    }
+    let backup_path = {
+        let _guard = BACKUP_INDEX_LOCK
+            .get_or_init(|| Mutex::new(()))
+            .lock()
+            .map_err(|_| "Failed to lock backup index".to_string())?;
+        let mut index = read_backup_index(project_root);
+        if let Some(newest) = index.files.get(&key).filter(|b| b.sha256 == sha256) {
+            let existing = project_root
+                .join(BACKUP_DIR)
+                .join(newest.timestamp.to_string())
+                .join(relative);
+            // The copy may have been pruned or deleted by hand since it was indexed.
+            if existing.is_file() {
+                return Ok(Some(existing));
+            }
+        }
+
+        // Two backups of one file in the same millisecond must not overwrite each other.
+        let mut ts = now_millis()?;
+        let mut backup_path = project_root.join(BACKUP_DIR).join(ts.to_string()).join(relative);
+        while backup_path.exists() {
+            ts += 1;
+            backup_path = project_root.join(BACKUP_DIR).join(ts.to_string()).join(relative);
+        }
+
+        if let Some(parent) = backup_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create backup directory '{}': {e}", parent.display()))?;
+        }
 
-    fs::copy(full_path, &backup_path)
-        .map_err(|e| format!("Failed to backup '{}': {e}", full_path.display()))?;
+        fs::write(&backup_path, &content)
+            .map_err(|e| format!("Failed to backup '{}': {e}", full_path.display()))?;
 
+        index.files.insert(
+            key,
+            IndexedBackup {
+                timestamp: ts as u64,
+                sha256,
+            },
+        );
+        // A stale index would dedupe later backups against a copy that is no longer the
+        // newest, so the write this backup protects does not go ahead either.
+        write_backup_index(project_root, &index)
+            .map_err(|e| format!("Failed to update backup index: {e}"))?;
+        backup_path
+    };
+
+    auto_prune_backups(project_root);
     Ok(Some(backup_path))
 }
 
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum BackupRule {
+    Age,
+    CopiesPerFile,
+    TotalSize,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupRuleReport {
+    pub rule: BackupRule,
+    pub files: usize,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupPruneReport {
+    pub dry_run: bool,
+    /// One entry per configured rule, in the order they are applied.
+    pub rules: Vec<BackupRuleReport>,
+    pub reclaimed_files: usize,
+    pub reclaimed_bytes: u64,
+    pub remaining_bytes: u64,
+}
+
+struct BackupCopy {
+    path: PathBuf,
+    /// Path of the original file relative to the project root.
+    relative: PathBuf,
+    taken_at: u128,
+    size: u64,
+}
+
+fn collect_backup_files(dir: &Path, out: &mut Vec<(PathBuf, u64)>) -> Result<(), String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        // Removed by a concurrent prune.
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(format!("Failed to read '{}': {e}", dir.display())),
+    };
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            collect_backup_files(&entry.path(), out)?;
+        } else if file_type.is_file() {
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            out.push((entry.path(), size));
+        }
+    }
+    Ok(())
+}
+
+/// Every file under `.backup/<millis>/`; other directories there are left alone.
+fn list_backup_copies(project_root: &Path) -> Result<Vec<BackupCopy>, String> {
+    let backup_root = project_root.join(BACKUP_DIR);
+    let Ok(entries) = fs::read_dir(&backup_root) else {
+        return Ok(Vec::new());
+    };
+
+    let mut copies = Vec::new();
+    for entry in entries.flatten() {
+        let Some(taken_at) = entry.file_name().to_str().and_then(|n| n.parse::<u128>().ok()) else {
+            continue;
+        };
+        if !entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+            continue;
+        }
+        let snapshot = entry.path();
+        let mut files = Vec::new();
+        collect_backup_files(&snapshot, &mut files)?;
+        for (path, size) in files {
+            let Ok(relative) = path.strip_prefix(&snapshot).map(Path::to_path_buf) else {
+                continue;
+            };
+            copies.push(BackupCopy {
+                path,
+                relative,
+                taken_at,
+                size,
+            });
+        }
+    }
+    Ok(copies)
+}
+
+/// Removes empty directories below `dir`, and `dir` itself when it ends up empty.
+fn remove_empty_dirs(dir: &Path) {
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            if entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+                remove_empty_dirs(&entry.path());
+            }
+        }
+    }
+    let _ = fs::remove_dir(dir);
+}
+
+/// Applies `policy` to `.backup/` as of `now` (Unix millis). Rules run in the order
+/// age, copies per file, total size; the newest backup of each file, and anything taken
+/// in the last [`IN_FLIGHT_MILLIS`], is never removed.
+fn apply_retention(
+    project_root: &Path,
+    policy: &BackupRetention,
+    now: u128,
+    dry_run: bool,
+) -> Result<BackupPruneReport, String> {
+    let mut copies = list_backup_copies(project_root)?;
+    // Newest first, so the first copy seen for a file is the protected one.
+    copies.sort_by(|a, b| b.taken_at.cmp(&a.taken_at).then_with(|| a.path.cmp(&b.path)));
+
+    let mut seen_per_file: HashMap<&Path, usize> = HashMap::new();
+    let ranks: Vec<usize> = copies
+        .iter()
+        .map(|copy| {
+            let seen = seen_per_file.entry(copy.relative.as_path()).or_insert(0);
+            *seen += 1;
+            *seen - 1
+        })
+        .collect();
+    let in_flight_since = now.saturating_sub(IN_FLIGHT_MILLIS);
+    let prunable = |i: usize| ranks[i] > 0 && copies[i].taken_at < in_flight_since;
+
+    let mut removed: HashSet<usize> = HashSet::new();
+    let mut rules = Vec::new();
+    let mut record = |rule: BackupRule, picked: Vec<usize>, removed: &mut HashSet<usize>| {
+        let bytes = picked.iter().map(|&i| copies[i].size).sum();
+        rules.push(BackupRuleReport {
+            rule,
+            files: picked.len(),
+            bytes,
+        });
+        removed.extend(picked);
+    };
+
+    if let Some(days) = policy.max_age_days {
+        let cutoff = now.saturating_sub(u128::from(days) * MILLIS_PER_DAY);
+        let picked = (0..copies.len())
+            .filter(|&i| prunable(i) && copies[i].taken_at < cutoff)
+            .collect();
+        record(BackupRule::Age, picked, &mut removed);
+    }
+    if let Some(max_copies) = policy.max_copies_per_file {
+        let keep = (max_copies as usize).max(1);
+        let picked = (0..copies.len())
+            .filter(|&i| ranks[i] >= keep && prunable(i) && !removed.contains(&i))
+            .collect();
+        record(BackupRule::CopiesPerFile, picked, &mut removed);
+    }
+    if let Some(max_total) = policy.max_total_bytes {
+        let mut total: u64 = (0..copies.len())
+            .filter(|i| !removed.contains(i))
+            .map(|i| copies[i].size)
+            .sum();
+        let mut picked = Vec::new();
+        // Oldest first.
+        for i in (0..copies.len()).rev() {
+            if total <= max_total {
+                break;
+            }
+            if prunable(i) && !removed.contains(&i) {
+                total -= copies[i].size;
+                picked.push(i);
+            }
+        }
+        record(BackupRule::TotalSize, picked, &mut removed);
+    }
+
+    let reclaimed_bytes = removed.iter().map(|&i| copies[i].size).sum();
+    let total_bytes: u64 = copies.iter().map(|c| c.size).sum();
+    if !dry_run && !removed.is_empty() {
+        for &i in &removed {
+            match fs::remove_file(&copies[i].path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(format!("Failed to remove backup '{}': {e}", copies[i].path.display())),
+            }
+        }
+        let snapshots: HashSet<u128> = removed.iter().map(|&i| copies[i].taken_at).collect();
+        for taken_at in snapshots {
+            remove_empty_dirs(&project_root.join(BACKUP_DIR).join(taken_at.to_string()));
+        }
+    }
+
+    Ok(BackupPruneReport {
+        dry_run,
+        rules,
+        reclaimed_files: removed.len(),
+        reclaimed_bytes,
+        remaining_bytes: total_bytes - reclaimed_bytes,
+    })
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupEntry {
+    /// Unix millis; also the name of the `.backup/` folder holding the copy.
+    pub timestamp: u64,
+    /// Path of the copy relative to the project root.
+    pub backup_path: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FileBackups {
+    pub relative_path: String,
+    /// Newest first.
+    pub backups: Vec<BackupEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoredBackup {
+    pub relative_path: String,
+    pub timestamp: u64,
+    /// Refreshed index entry when the file is a chapter.
+    pub chapter: Option<ChapterMeta>,
+    /// Bookkeeping that could not be updated after the restore.
+    pub warnings: Vec<String>,
+}
+
+fn normalize_relative(relative_path: &str) -> String {
+    relative_path
+        .trim()
+        .replace('\\', "/")
+        .trim_start_matches("./")
+        .to_string()
+}
+
+fn project_relative(project_root: &Path, path: &Path) -> String {
+    path.strip_prefix(project_root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// Backups grouped by original file, sorted by path; limited to one file when
+/// `relative_path` is given.
+pub fn list_backups(project_root: &Path, relative_path: Option<&str>) -> Result<Vec<FileBackups>, String> {
+    let project_root = validate_path(project_root, "")?;
+    let filter = relative_path.map(normalize_relative);
+
+    let mut grouped: HashMap<String, Vec<BackupEntry>> = HashMap::new();
+    for copy in list_backup_copies(&project_root)? {
+        let relative = copy.relative.to_string_lossy().replace('\\', "/");
+        if filter.as_deref().is_some_and(|f| f != relative) {
+            continue;
+        }
+        grouped.entry(relative).or_default().push(BackupEntry {
+            timestamp: copy.taken_at as u64,
+            backup_path: project_relative(&project_root, &copy.path),
+            size: copy.size,
+        });
+    }
+
+    let mut files: Vec<FileBackups> = grouped
+        .into_iter()
+        .map(|(relative_path, mut backups)| {
+            backups.sort_by_key(|b| std::cmp::Reverse(b.timestamp));
+            FileBackups { relative_path, backups }
+        })
+        .collect();
+    files.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+    Ok(files)
+}
+
+/// Project-relative path of the newest backup of `relative_path` when its content hashes
+/// to `sha256`, i.e. the copy a write of that content made or reused.
+pub fn backup_with_hash(project_root: &Path, relative_path: &str, sha256: &str) -> Option<String> {
+    let project_root = validate_path(project_root, "").ok()?;
+    let relative = normalize_relative(relative_path);
+    let newest = read_backup_index(&project_root).files.remove(&relative)?;
+    let backup = format!("{BACKUP_DIR}/{}/{relative}", newest.timestamp);
+    (newest.sha256 == sha256 && project_root.join(&backup).is_file()).then_some(backup)
+}
+
+/// `chapters/<id>.txt` for a chapter listed in the index.
+pub(crate) fn indexed_chapter_id(project_root: &Path, relative: &str) -> Option<String> {
+    let id = relative.strip_prefix("chapters/")?.strip_suffix(".txt")?;
+    if id.contains('/') {
+        return None;
+    }
+    crate::chapter::list_chapters_sync(project_root.to_string_lossy().into_owned())
+        .ok()?
+        .into_iter()
+        .find(|c| c.id == id)
+        .map(|c| c.id)
+}
+
+/// The copy of `relative` taken at `timestamp`, checked to resolve inside `.backup/`.
+fn backup_copy(project_root: &Path, relative: &str, timestamp: u64) -> Result<PathBuf, String> {
+    if relative.is_empty() {
+        return Err("relativePath is empty".to_string());
+    }
+    let source = validate_path(project_root, &format!("{BACKUP_DIR}/{timestamp}/{relative}"))?;
+    if !source.starts_with(project_root.join(BACKUP_DIR)) {
+        return Err("Backup path escapes .backup".to_string());
+    }
+    if !source.is_file() {
+        return Err(format!("No backup of '{relative}' at {timestamp}"));
+    }
+    Ok(source)
+}
+
+/// Reads a backup copy with the same ranged, line-numbered output as `file_ops::read`.
+pub fn read_backup_file(
+    project_root: &Path,
+    relative_path: &str,
+    timestamp: u64,
+    offset: Option<i64>,
+    limit: Option<u32>,
+) -> Result<ReadResult, String> {
+    let project_root = validate_path(project_root, "")?;
+    let relative = normalize_relative(relative_path);
+    let source = backup_copy(&project_root, &relative, timestamp)?;
+    let params = ReadParams {
+        path: format!("{BACKUP_DIR}/{timestamp}/{relative}"),
+        offset,
+        limit,
+        byte_offset: None,
+        byte_limit: None,
+        encoding: None,
+    };
+    crate::file_ops::read::read_resolved(&source, params)
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupDiff {
+    pub relative_path: String,
+    pub from_timestamp: u64,
+    pub to_timestamp: u64,
+    #[serde(flatten)]
+    pub diff: TextDiff,
+}
+
+fn read_backup_text(path: &Path) -> Result<String, String> {
+    check_read_size(path)?;
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read backup '{}': {e}", path.display()))?;
+    if bytes.contains(&0u8) {
+        return Err("Binary files are not supported".to_string());
+    }
+    String::from_utf8(bytes).map_err(|_| "Binary files are not supported".to_string())
+}
+
+/// Line differences from the copy of `relative_path` at `from_timestamp` to the one at
+/// `to_timestamp`.
+pub fn diff_backups(
+    project_root: &Path,
+    relative_path: &str,
+    from_timestamp: u64,
+    to_timestamp: u64,
+) -> Result<BackupDiff, String> {
+    let project_root = validate_path(project_root, "")?;
+    let relative = normalize_relative(relative_path);
+    let old = read_backup_text(&backup_copy(&project_root, &relative, from_timestamp)?)?;
+    let new = read_backup_text(&backup_copy(&project_root, &relative, to_timestamp)?)?;
+    Ok(BackupDiff {
+        relative_path: relative,
+        from_timestamp,
+        to_timestamp,
+        diff: diff::diff_lines(&old, &new),
+    })
+}
+
+/// Puts the copy taken at `timestamp` back in place after backing up the current
+/// version. Chapter files also get their index word count refreshed.
+pub fn restore_file_backup(
+    project_root: &Path,
+    relative_path: &str,
+    timestamp: u64,
+) -> Result<RestoredBackup, String> {
+    let project_root = validate_path(project_root, "")?;
+    let relative = normalize_relative(relative_path);
+    let source = backup_copy(&project_root, &relative, timestamp)?;
+    let target = validate_path(&project_root, &relative)?;
+    let content = fs::read(&source).map_err(|e| format!("Failed to read backup '{}': {e}", source.display()))?;
+    let delta = activity_log::byte_delta(&target, content.len());
+
+    // Both paths back up the replaced version under a fresh timestamp first.
+    let chapter = match indexed_chapter_id(&project_root, &relative) {
+        Some(chapter_id) => {
+            let text = String::from_utf8(content).map_err(|_| "Backup is not valid UTF-8 text".to_string())?;
+            let project_path = project_root.to_string_lossy().into_owned();
+            Some(crate::chapter::write_chapter_content(project_path, chapter_id, text)?.0)
+        }
+        None => {
+            write_bytes_with_backup(&project_root, &target, &content)?;
+            None
+        }
+    };
+
+    let warnings = activity_log::record(&project_root, Actor::User, "restore_backup", &relative, delta)
+        .into_iter()
+        .collect();
+    Ok(RestoredBackup {
+        relative_path: relative,
+        timestamp,
+        chapter,
+        warnings,
+    })
+}
+
+fn retention_policy(project_root: &Path) -> Result<Option<BackupRetention>, String> {
+    Ok(crate::project::read_project_config(project_root)?.settings.backup_retention)
+}
+
+/// Enforces the project's backup retention settings; `dry_run` only reports.
+pub fn prune_backups(project_root: &Path, dry_run: bool) -> Result<BackupPruneReport, String> {
+    let policy = retention_policy(project_root)?.unwrap_or_default();
+    apply_retention(project_root, &policy, now_millis()?, dry_run)
+}
+
+static LAST_AUTO_PRUNE: OnceLock<Mutex<HashMap<PathBuf, Instant>>> = OnceLock::new();
+
+/// Best-effort retention pass after a backup, at most once per [`AUTO_PRUNE_INTERVAL`]
+/// per project and only when a policy is configured. The throttle is checked first, so
+/// most backups don't read the config at all.
+fn auto_prune_backups(project_root: &Path) {
+    {
+        let Ok(mut last) = LAST_AUTO_PRUNE.get_or_init(|| Mutex::new(HashMap::new())).lock() else {
+            return;
+        };
+        let now = Instant::now();
+        if last
+            .get(project_root)
+            .is_some_and(|at| now.duration_since(*at) < AUTO_PRUNE_INTERVAL)
+        {
+            return;
+        }
+        last.insert(project_root.to_path_buf(), now);
+    }
+    let Ok(Some(policy)) = retention_policy(project_root) else {
+        return;
+    };
+    if let Err(e) = now_millis().and_then(|now| apply_retention(project_root, &policy, now, false)) {
+        eprintln!("Warning: failed to prune backups: {e}");
+    }
+}
+
 pub fn restore_backup(full_path: &Path, backup_path: &Path) -> Result<(), String> {
     if let Some(parent) = full_path.parent() {
         fs::create_dir_all(parent)
@@ -54,6 +681,25 @@ pub fn restore_backup(full_path: &Path, backup_path: &Path) -> Result<(), String
     Ok(())
 }
 
+#[cfg(test)]
+thread_local! {
+    static FAIL_RENAMES: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+/// Makes every rename in [`atomic_write_bytes`] on this thread fail, to exercise rollback.
+#[cfg(test)]
+pub(crate) fn fail_renames(fail: bool) {
+    FAIL_RENAMES.with(|f| f.set(fail));
+}
+
+fn rename_into_place(from: &Path, to: &Path) -> std::io::Result<()> {
+    #[cfg(test)]
+    if FAIL_RENAMES.with(|f| f.get()) {
+        return Err(std::io::Error::other("injected rename failure"));
+    }
+    fs::rename(from, to)
+}
+
 fn temp_path_for(full_path: &Path) -> Result<PathBuf, String> {
     let ts = now_millis()?;
     let file_name = full_path
@@ -76,7 +722,7 @@ pub fn atomic_write_bytes(full_path: &Path, content: &[u8], rollback_backup: Opt
     fs::write(&tmp_path, content)
         .map_err(|e| format!("Failed to write temp file '{}': {e}", tmp_path.display()))?;
 
-    match fs::rename(&tmp_path, full_path) {
+    match rename_into_place(&tmp_path, full_path) {
         Ok(_) => Ok(()),
         Err(rename_err) => {
             // On Windows, rename fails if the destination exists. Fall back to remove+rename.
@@ -89,7 +735,7 @@ pub fn atomic_write_bytes(full_path: &Path, content: &[u8], rollback_backup: Opt
                     ));
                 }
 
-                match fs::rename(&tmp_path, full_path) {
+                match rename_into_place(&tmp_path, full_path) {
                     Ok(_) => Ok(()),
                     Err(e2) => {
                         let _ = fs::remove_file(&tmp_path);
@@ -112,6 +758,8 @@ pub fn write_string_with_backup(
     full_path: &Path,
     content: &str,
 ) -> Result<Option<PathBuf>, String> {
+    // Checked before backing up so a rejected write leaves no stray copy.
+    check_writable_in(project_root, full_path)?;
     let backup = backup_existing_file(project_root, full_path)?;
     atomic_write_bytes(full_path, content.as_bytes(), backup.as_deref())?;
     Ok(backup)
@@ -122,7 +770,423 @@ pub fn write_bytes_with_backup(
     full_path: &Path,
     content: &[u8],
 ) -> Result<Option<PathBuf>, String> {
+    check_writable_in(project_root, full_path)?;
     let backup = backup_existing_file(project_root, full_path)?;
     atomic_write_bytes(full_path, content, backup.as_deref())?;
     Ok(backup)
 }
+
+enum StagedOp {
+    Write(Vec<u8>),
+    /// Like `file_ops::append`: a newline is inserted first when the file doesn't end in one.
+    Append(Vec<u8>),
+    Delete,
+}
+
+/// Writes, appends and deletes applied together by [`Transaction::commit`]; see
+/// [`transaction`].
+pub struct Transaction {
+    project_root: PathBuf,
+    ops: Vec<(String, StagedOp)>,
+    /// Fails a change after earlier ones were applied.
+    #[cfg(test)]
+    fail_at: Option<usize>,
+}
+
+/// Starts a multi-file change in `project_root`. Nothing touches the disk until
+/// [`Transaction::commit`], which either applies every staged change or none.
+pub fn transaction(project_root: &Path) -> Transaction {
+    Transaction {
+        project_root: project_root.to_path_buf(),
+        ops: Vec::new(),
+        #[cfg(test)]
+        fail_at: None,
+    }
+}
+
+impl Transaction {
+    pub fn write(mut self, relative_path: &str, content: impl Into<Vec<u8>>) -> Self {
+        self.ops.push((relative_path.to_string(), StagedOp::Write(content.into())));
+        self
+    }
+
+    pub fn append(mut self, relative_path: &str, content: impl Into<Vec<u8>>) -> Self {
+        self.ops.push((relative_path.to_string(), StagedOp::Append(content.into())));
+        self
+    }
+
+    pub fn delete(mut self, relative_path: &str) -> Self {
+        self.ops.push((relative_path.to_string(), StagedOp::Delete));
+        self
+    }
+
+    /// Makes the `n`th staged change (0-based) fail.
+    #[cfg(test)]
+    fn fail_at(mut self, n: usize) -> Self {
+        self.fail_at = Some(n);
+        self
+    }
+
+    fn apply(full_path: &Path, op: &StagedOp) -> Result<(), String> {
+        match op {
+            StagedOp::Write(content) => atomic_write_bytes(full_path, content, None),
+            StagedOp::Append(content) => {
+                let mut combined = match fs::read(full_path) {
+                    Ok(existing) => existing,
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+                    Err(e) => return Err(format!("Failed to read '{}': {e}", full_path.display())),
+                };
+                if combined.last().is_some_and(|b| *b != b'\n') {
+                    combined.push(b'\n');
+                }
+                combined.extend_from_slice(content);
+                atomic_write_bytes(full_path, &combined, None)
+            }
+            StagedOp::Delete => fs::remove_file(full_path)
+                .map_err(|e| format!("Failed to delete '{}': {e}", full_path.display())),
+        }
+    }
+
+    /// Backs up every target, then applies the staged changes in order. If one fails,
+    /// the targets already changed are put back from their backups (or removed, if they
+    /// didn't exist) before the error is returned.
+    pub fn commit(self) -> Result<(), String> {
+        let project_root = validate_path(&self.project_root, "")?;
+        let mut targets = Vec::with_capacity(self.ops.len());
+        for (relative, op) in &self.ops {
+            let full_path = validate_path(&project_root, relative)?;
+            check_writable_in(&project_root, &full_path)?;
+            if matches!(op, StagedOp::Delete) && !full_path.is_file() {
+                return Err(format!("Cannot delete '{relative}': not a file"));
+            }
+            targets.push(full_path);
+        }
+
+        // Backed up once per target, so rollback restores the state before the transaction.
+        let mut originals: Vec<(PathBuf, Option<PathBuf>)> = Vec::new();
+        for full_path in &targets {
+            if !originals.iter().any(|(path, _)| path == full_path) {
+                let backup = backup_existing_file(&project_root, full_path)?;
+                originals.push((full_path.clone(), backup));
+            }
+        }
+
+        for (n, ((relative, op), full_path)) in self.ops.iter().zip(&targets).enumerate() {
+            #[cfg(test)]
+            let result = if self.fail_at == Some(n) {
+                Err(format!("Injected failure at change {n}"))
+            } else {
+                Self::apply(full_path, op)
+            };
+            #[cfg(not(test))]
+            let result = Self::apply(full_path, op);
+
+            if let Err(err) = result {
+                let touched: Vec<&PathBuf> = targets[..=n].iter().collect();
+                let mut not_restored = Vec::new();
+                for (path, backup) in originals.iter().rev().filter(|(path, _)| touched.contains(&path)) {
+                    let restored = match backup {
+                        Some(backup) => restore_backup(path, backup),
+                        None if path.exists() => fs::remove_file(path).map_err(|e| e.to_string()),
+                        None => Ok(()),
+                    };
+                    if restored.is_err() {
+                        not_restored.push(project_relative(&project_root, path));
+                    }
+                }
+                if !not_restored.is_empty() {
+                    return Err(format!(
+                        "{err} (while changing '{relative}'); rollback failed for: {}",
+                        not_restored.join(", ")
+                    ));
+                }
+                return Err(format!("{err} (while changing '{relative}'); all changes were rolled back"));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn write_backup(root: &Path, taken_at: u128, relative: &str, size: usize) {
+        let path = root.join(BACKUP_DIR).join(taken_at.to_string()).join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, vec![b'x'; size]).unwrap();
+    }
+
+    #[test]
+    fn retention_reports_per_rule_and_keeps_newest_copy() {
+        let temp = TempDir::new("creatorai-v2-backup-retention");
+        let day = MILLIS_PER_DAY;
+        let now = 100 * day;
+        write_backup(&temp.path, day, "chapters/chapter_001.txt", 10);
+        write_backup(&temp.path, 2 * day, "chapters/chapter_001.txt", 10);
+        write_backup(&temp.path, 2 * day, "chapters/index.json", 5);
+        write_backup(&temp.path, 98 * day, "chapters/chapter_001.txt", 10);
+        write_backup(&temp.path, 99 * day, "chapters/chapter_001.txt", 10);
+        write_backup(&temp.path, 99 * day + 1, "chapters/chapter_001.txt", 10);
+
+        let policy = BackupRetention {
+            max_total_bytes: Some(15),
+            max_age_days: Some(30),
+            max_copies_per_file: Some(2),
+        };
+        let report = apply_retention(&temp.path, &policy, now, true).expect("dry run");
+        let rules: Vec<(BackupRule, usize, u64)> =
+            report.rules.iter().map(|r| (r.rule, r.files, r.bytes)).collect();
+        assert_eq!(
+            rules,
+            [
+                (BackupRule::Age, 2, 20),
+                (BackupRule::CopiesPerFile, 1, 10),
+                (BackupRule::TotalSize, 1, 10),
+            ]
+        );
+        assert_eq!(report.reclaimed_bytes, 40);
+        assert_eq!(report.remaining_bytes, 15);
+        assert!(temp.path.join(".backup/86400000").exists());
+
+        apply_retention(&temp.path, &policy, now, false).expect("prune");
+        let mut left: Vec<(u128, String)> = list_backup_copies(&temp.path)
+            .expect("list")
+            .into_iter()
+            .map(|c| (c.taken_at, c.relative.to_string_lossy().replace('\\', "/")))
+            .collect();
+        left.sort();
+        // The old index.json backup survives every rule as that file's only copy.
+        assert_eq!(
+            left,
+            [
+                (2 * day, "chapters/index.json".to_string()),
+                (99 * day + 1, "chapters/chapter_001.txt".to_string()),
+            ]
+        );
+        assert!(!temp.path.join(".backup/86400000").exists());
+    }
+
+    #[test]
+    fn retention_leaves_snapshots_that_may_still_be_written() {
+        let temp = TempDir::new("creatorai-v2-backup-in-flight");
+        let now = 100 * MILLIS_PER_DAY;
+        for taken_at in [MILLIS_PER_DAY, 2 * MILLIS_PER_DAY, now - 3_000, now - 2_000, now - 1_000] {
+            write_backup(&temp.path, taken_at, "notes.md", 4);
+        }
+        fs::write(temp.path.join("notes.md"), "live").unwrap();
+
+        let policy = BackupRetention {
+            max_total_bytes: None,
+            max_age_days: None,
+            max_copies_per_file: Some(1),
+        };
+        let report = apply_retention(&temp.path, &policy, now, false).expect("prune");
+        assert_eq!((report.reclaimed_files, report.reclaimed_bytes), (2, 8));
+        let mut left: Vec<u128> = list_backup_copies(&temp.path).unwrap().iter().map(|c| c.taken_at).collect();
+        left.sort();
+        assert_eq!(left, [now - 3_000, now - 2_000, now - 1_000]);
+        assert_eq!(fs::read_to_string(temp.path.join("notes.md")).unwrap(), "live");
+    }
+
+    #[test]
+    fn restoring_a_chapter_backup_keeps_current_version_and_updates_index() {
+        let temp = TempDir::new("creatorai-v2-backup-restore");
+        fs::create_dir_all(temp.path.join(".creatorai")).unwrap();
+        fs::create_dir_all(temp.path.join("chapters")).unwrap();
+        fs::write(temp.path.join(".creatorai/config.json"), "{}\n").unwrap();
+        fs::write(
+            temp.path.join("chapters/index.json"),
+            r#"{ "chapters": [
+  { "id": "chapter_001", "title": "第一章", "order": 1, "created": 1, "updated": 1, "wordCount": 4 }
+], "nextId": 2 }
+"#,
+        )
+        .unwrap();
+        fs::write(temp.path.join("chapters/chapter_001.txt"), "现在版本").unwrap();
+        write_backup(&temp.path, 1_000, "chapters/chapter_001.txt", 3);
+        write_backup(&temp.path, 2_000, "chapters/chapter_001.txt", 6);
+        write_backup(&temp.path, 2_000, "notes.md", 1);
+
+        let files = list_backups(&temp.path, None).expect("list");
+        let listed: Vec<(&str, Vec<u64>)> = files
+            .iter()
+            .map(|f| (f.relative_path.as_str(), f.backups.iter().map(|b| b.timestamp).collect()))
+            .collect();
+        assert_eq!(listed, [("chapters/chapter_001.txt", vec![2_000, 1_000]), ("notes.md", vec![2_000])]);
+        assert_eq!(files[0].backups[1].backup_path, ".backup/1000/chapters/chapter_001.txt");
+        assert_eq!(list_backups(&temp.path, Some("./notes.md")).expect("filtered").len(), 1);
+
+        let restored = restore_file_backup(&temp.path, "chapters/chapter_001.txt", 1_000).expect("restore");
+        assert_eq!(restored.chapter.map(|c| c.word_count), Some(3));
+        assert_eq!(fs::read_to_string(temp.path.join("chapters/chapter_001.txt")).unwrap(), "xxx");
+        let versions = &list_backups(&temp.path, Some("chapters/chapter_001.txt")).expect("after")[0].backups;
+        assert_eq!(versions.len(), 3);
+        let saved = fs::read_to_string(temp.path.join(&versions[0].backup_path)).unwrap();
+        assert_eq!(saved, "现在版本");
+
+        assert!(restore_file_backup(&temp.path, "chapters/chapter_001.txt", 3_000).is_err());
+        assert!(restore_file_backup(&temp.path, "../outside.txt", 1_000).is_err());
+    }
+
+    #[test]
+    fn writes_over_the_project_limit_are_rejected_before_backup() {
+        let temp = TempDir::new("creatorai-v2-size-limit");
+        fs::create_dir_all(temp.path.join(".creatorai")).unwrap();
+        fs::write(
+            temp.path.join(".creatorai/config.json"),
+            r#"{ "name": "t", "created": 1, "updated": 1, "version": "1",
+  "settings": { "autoSave": true, "autoSaveInterval": 2000, "maxFileBytes": 10 } }"#,
+        )
+        .unwrap();
+        let notes = temp.path.join("notes.md");
+        let write = |content: &str| {
+            crate::file_ops::write_file(
+                &temp.path,
+                crate::file_ops::WriteParams {
+                    path: "notes.md".to_string(),
+                    content: content.to_string(),
+                },
+            )
+        };
+        write("0123456789").expect("at the limit");
+
+        let err = write("0123456789A").unwrap_err();
+        assert!(err.contains("11 bytes") && err.contains("10-byte"), "{err}");
+        assert!(!temp.path.join(BACKUP_DIR).exists());
+
+        // Exports, indexes and other non-content files go through the generic writer uncapped.
+        let export = temp.path.join("export.txt");
+        write_string_with_backup(&temp.path, &export, "0123456789ABCDEF").expect("uncapped write");
+        let err = crate::file_ops::append_file(
+            &temp.path,
+            crate::file_ops::AppendParams {
+                path: "notes.md".to_string(),
+                content: "!".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert!(err.contains("maxFileBytes"), "{err}");
+        assert_eq!(fs::read_to_string(&notes).unwrap(), "0123456789");
+
+        let huge = temp.path.join("huge.txt");
+        fs::File::create(&huge).unwrap().set_len(MAX_READ_BYTES + 1).unwrap();
+        assert!(check_read_size(&huge).unwrap_err().contains("offset/limit"));
+        assert!(check_read_size(&notes).is_ok());
+    }
+
+    #[test]
+    fn unchanged_content_reuses_the_newest_backup() {
+        let temp = TempDir::new("creatorai-v2-backup-dedup");
+        let root = temp.path.canonicalize().unwrap();
+        let chapter = root.join("chapters/chapter_001.txt");
+        let snapshots = || fs::read_dir(root.join(BACKUP_DIR)).unwrap().flatten().filter(|e| e.path().is_dir()).count();
+        let write = |content: &str| write_string_with_backup(&root, &chapter, content).expect("write");
+
+        assert_eq!(write("v1"), None, "first write has nothing to back up");
+        let first = write("v2").expect("backup of v1");
+        assert_eq!(fs::read_to_string(&first).unwrap(), "v1");
+
+        // Autosave of unchanged content: v2 isn't backed up yet, then it is.
+        let second = write("v2").expect("backup of v2");
+        assert_ne!(second, first);
+        assert_eq!(write("v2"), Some(second.clone()));
+        assert_eq!(write("v3"), Some(second.clone()));
+        assert_eq!(snapshots(), 2);
+
+        let third = write("v4").expect("backup of v3");
+        assert_eq!(fs::read_to_string(&third).unwrap(), "v3");
+        assert_eq!(snapshots(), 3);
+        assert_eq!(
+            backup_with_hash(&root, "chapters/chapter_001.txt", &sha256_hex(b"v3")),
+            Some(project_relative(&root, &third))
+        );
+
+        // A pruned copy is taken again rather than pointing at a missing file.
+        let fourth = write("v4").expect("backup of v4");
+        fs::remove_file(&fourth).unwrap();
+        let retaken = write("v4").expect("backup of v4 again");
+        assert_eq!(fs::read_to_string(&retaken).unwrap(), "v4");
+
+        // An index that can't be updated stops the write instead of going stale.
+        let index = root.join(BACKUP_DIR).join(BACKUP_INDEX);
+        fs::remove_file(&index).unwrap();
+        fs::create_dir(&index).unwrap();
+        let err = write_string_with_backup(&root, &chapter, "v5").unwrap_err();
+        assert!(err.contains("backup index"), "{err}");
+        assert_eq!(fs::read_to_string(&chapter).unwrap(), "v4");
+    }
+
+    #[test]
+    fn transaction_rolls_back_every_change_when_one_fails() {
+        let temp = TempDir::new("creatorai-v2-transaction");
+        let root = temp.path.canonicalize().unwrap();
+        let originals = [("a.txt", "A"), ("b.txt", "B"), ("c.txt", "C")];
+        for (name, content) in originals {
+            fs::write(root.join(name), content).unwrap();
+        }
+        let staged = || {
+            transaction(&root)
+                .write("a.txt", "A2")
+                .append("b.txt", "more")
+                .delete("c.txt")
+                .write("notes/d.txt", "new")
+                .append("a.txt", "again")
+        };
+
+        for n in 0..5 {
+            let err = staged().fail_at(n).commit().unwrap_err();
+            assert!(err.contains("rolled back"), "{err}");
+            for (name, content) in originals {
+                assert_eq!(fs::read_to_string(root.join(name)).unwrap(), content, "failure at {n}");
+            }
+            assert!(!root.join("notes/d.txt").exists(), "failure at {n}");
+        }
+
+        staged().commit().expect("commit");
+        assert_eq!(fs::read_to_string(root.join("a.txt")).unwrap(), "A2\nagain");
+        assert_eq!(fs::read_to_string(root.join("b.txt")).unwrap(), "B\nmore");
+        assert!(!root.join("c.txt").exists());
+        assert_eq!(fs::read_to_string(root.join("notes/d.txt")).unwrap(), "new");
+
+        // Invalid staging is caught before anything is changed.
+        let err = transaction(&root).write("a.txt", "A3").delete("missing.txt").commit().unwrap_err();
+        assert!(err.contains("missing.txt"), "{err}");
+        assert_eq!(fs::read_to_string(root.join("a.txt")).unwrap(), "A2\nagain");
+    }
+
+    #[test]
+    fn backups_can_be_read_in_ranges_and_diffed() {
+        let temp = TempDir::new("creatorai-v2-backup-diff");
+        let root = temp.path.canonicalize().unwrap();
+        let relative = "chapters/chapter_001.txt";
+        let copy = |taken_at: u64, content: &[u8]| {
+            let path = root.join(BACKUP_DIR).join(taken_at.to_string()).join(relative);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        copy(100, "第一行\n第二行\n第三行\n".as_bytes());
+        copy(200, "第一行\n第二行（修改）\n第三行\n第四行\n".as_bytes());
+        copy(300, b"\x00\x01binary");
+
+        let read = read_backup_file(&root, relative, 200, Some(1), Some(2)).expect("read");
+        assert_eq!(read.content, "00002| 第二行（修改）\n00003| 第三行");
+        assert_eq!(read.total_lines, 4);
+
+        let diff = diff_backups(&root, relative, 100, 200).expect("diff");
+        assert_eq!(diff.diff.hunks.len(), 1);
+        let changed: Vec<_> = diff.diff.hunks[0]
+            .lines
+            .iter()
+            .filter(|l| l.kind != diff::DiffLineKind::Context)
+            .map(|l| l.text.as_str())
+            .collect();
+        assert_eq!(changed, ["第二行", "第二行（修改）", "第四行"]);
+
+        assert_eq!(diff_backups(&root, relative, 100, 300).unwrap_err(), "Binary files are not supported");
+        assert_eq!(read_backup_file(&root, relative, 300, None, None).unwrap_err(), "Binary files are not supported");
+        assert!(read_backup_file(&root, relative, 999, None, None).unwrap_err().contains("No backup"));
+        assert!(read_backup_file(&root, "../chapter_001.txt", 100, None, None).is_err());
+    }
+}
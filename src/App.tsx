@@ -1,7 +1,7 @@
 import { useEffect } from "react";
 import { AppProviders } from "./app/AppProviders";
 import { useAppStore } from "./app/store";
-import { bootstrapApp } from "./app/bootstrap";
+import { bootstrapApp, setupConfigChangedListener } from "./app/bootstrap";
 import { useProjectCommands } from "./hooks/useProjectCommands";
 import { useTheme } from "./hooks/useTheme";
 import MainLayout from "./layouts/MainLayout";
@@ -21,6 +21,20 @@ export default function App() {
     void loadRecent();
   }, []);
 
+  // 其他窗口或外部编辑修改配置后，通知本窗口重新加载
+  useEffect(() => {
+    let disposed = false;
+    let unlisten: (() => void) | null = null;
+    void setupConfigChangedListener().then((fn) => {
+      if (disposed) fn();
+      else unlisten = fn;
+    });
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, []);
+
   return (
     <AppProviders>
       {currentProject ? (
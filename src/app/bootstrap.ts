@@ -8,6 +8,7 @@ import { isTauri } from "@tauri-apps/api/core";
 import { invoke } from "@tauri-apps/api/core";
 import { message } from "antd";
 import { formatError } from "../utils/error";
+import { CONFIG_CHANGED_EVENT, type ConfigChangedPayload } from "../platform/tauri/client";
 
 /**
  * 加载最近项目列表
@@ -124,6 +125,25 @@ export async function setupWindowCloseGuard(
   };
 }
 
+/**
+ * 初始化配置变更监听
+ *
+ * 后端每次保存配置（包括其他窗口的修改、外部编辑 config.json）都会发出 config:changed，
+ * 这里转成窗口内的 creatorai:globalConfigChanged 事件，已有的监听方会自动重新加载。
+ */
+export async function setupConfigChangedListener(): Promise<() => void> {
+  if (!isTauri()) return () => {};
+
+  const { listen } = await import("@tauri-apps/api/event");
+  try {
+    return await listen<ConfigChangedPayload>(CONFIG_CHANGED_EVENT, (event) => {
+      window.dispatchEvent(new CustomEvent("creatorai:globalConfigChanged", { detail: event.payload }));
+    });
+  } catch {
+    return () => {};
+  }
+}
+
 /**
  * 应用引导主函数
  */
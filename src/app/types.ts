@@ -18,12 +18,34 @@ export interface ProjectConfig {
 export interface ProjectSettings {
   autoSave: boolean;
   autoSaveInterval: number;
+  /** 每章保留的摘要版本数，未设置时全部保留 */
+  maxSummariesPerChapter?: number;
+  /** .backup 保留策略，未设置时保留全部备份；每个文件最新的备份始终保留 */
+  backupRetention?: {
+    maxTotalBytes?: number;
+    maxAgeDays?: number;
+    maxCopiesPerFile?: number;
+  };
+  /** 符号链接策略：仅限项目内（默认）/ 禁止 / 项目内及白名单目标 */
+  followSymlinks?: "within_project" | "deny" | "allow_listed";
+  /** allow_listed 时允许的符号链接目标（绝对路径） */
+  symlinkAllowlist?: string[];
+  /** 单个文件写入后的最大字节数，未设置时为 20MB */
+  maxFileBytes?: number;
+  /** 额外禁止通用文件工具修改的项目相对路径，以 / 结尾表示整个目录 */
+  protectedPaths?: string[];
+  /** 只读模式：拒绝一切写入，读取、搜索与导出照常 */
+  readOnly?: boolean;
 }
 
 export interface RecentProject {
   name: string;
   path: string;
   lastOpened: number;
+  pinned?: boolean;
+  wordCount?: number;
+  chapterCount?: number;
+  statsUpdatedAt?: number;
 }
 
 export type CreateProjectFn = (name: string, parentPath: string) => Promise<void>;
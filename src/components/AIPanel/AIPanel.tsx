@@ -8,7 +8,7 @@ import ChatHistory from "./ChatHistory";
 import SessionList from "./SessionList";
 import ContextDiagnosticsPanel from "./ContextDiagnosticsPanel";
 import { aiChat } from "../../lib/ai";
-import { getWritingPresets, saveWritingPresets } from "../../lib/writingPresets";
+import { getWritingPresets, saveWritingPresets, touchWritingPreset } from "../../lib/writingPresets";
 import { createBuiltinWritingPresets, createDefaultWritingPreset, type WritingPreset } from "../../types/writingPreset";
 import PresetSelector from "./PresetSelector";
 import PresetSettingsDrawer from "./PresetSettingsDrawer";
@@ -304,7 +304,7 @@ export default function AIPanel({ projectPath }: AIPanelProps) {
               if (call.id !== id) return call;
               return {
                 ...call,
-                status: payload.error ? "error" : "success",
+                status: payload.error && payload.result == null ? "error" : "success",
                 result: payload.result ?? call.result,
                 error: payload.error ?? call.error,
                 duration,
@@ -582,6 +582,9 @@ export default function AIPanel({ projectPath }: AIPanelProps) {
         retrievedContext,
       });
       setLastContextDiagnostics(diagnostics);
+      void touchWritingPreset({ projectPath, presetId: activePreset.id }).catch(() => {
+        // usage stats are best-effort
+      });
 
       const { content: reply, toolCalls } = await aiChat({
         projectDir: projectPath,
@@ -590,6 +593,7 @@ export default function AIPanel({ projectPath }: AIPanelProps) {
         systemPrompt: finalSystemPrompt,
         chapterId: resolved?.chapterId ?? null,
         allowWrite,
+        sessionId: currentSession.id,
       });
 
       const parsed = stripContinueDraftMarker(reply);
@@ -776,10 +780,18 @@ export default function AIPanel({ projectPath }: AIPanelProps) {
   const handleSavePresets = async (nextPresets: WritingPreset[], nextActiveId: string) => {
     setSavingPresets(true);
     try {
-      await saveWritingPresets({ projectPath, presets: nextPresets, activePresetId: nextActiveId });
-      setPresets(nextPresets);
-      setActivePresetId(nextActiveId);
-      message.success("写作预设已保存");
+      const report = await saveWritingPresets({
+        projectPath,
+        presets: nextPresets,
+        activePresetId: nextActiveId,
+      });
+      setPresets(report.presets);
+      setActivePresetId(report.activePresetId);
+      if (report.issues.length) {
+        message.warning(`写作预设已保存：${report.issues.map((issue) => issue.message).join("；")}`);
+      } else {
+        message.success("写作预设已保存");
+      }
     } finally {
       setSavingPresets(false);
     }
@@ -11,6 +11,7 @@ const TOOL_ICON_MAP: Record<string, string> = {
   search: "🔍",
   get_chapter_info: "ℹ️",
   save_summary: "💾",
+  get_story_rollup: "🗂️",
 };
 
 function truncate(value: string, maxLen: number): string {
@@ -58,9 +59,27 @@ function summarizeToolCall(call: ToolCall): string {
     }
     case "search": {
       const data = call.result ? safeJsonParse(call.result) : null;
-      const matches = (data as { matches?: unknown } | null)?.matches;
+      const result = data as { matches?: unknown; totalMatches?: unknown; truncated?: unknown } | null;
+      const matches = result?.matches;
       const count = Array.isArray(matches) ? matches.length : null;
-      const info = count === null ? "搜索完成" : `找到 ${count.toLocaleString()} 条结果`;
+      const total = typeof result?.totalMatches === "number" ? result.totalMatches : null;
+      const info =
+        count === null
+          ? "搜索完成"
+          : result?.truncated === true && total !== null
+            ? `显示 ${count.toLocaleString()} / ${total.toLocaleString()} 条结果`
+            : `找到 ${count.toLocaleString()} 条结果`;
+      return path ? `path: ${path} · ${info}` : info;
+    }
+    case "stat": {
+      const data = call.result ? safeJsonParse(call.result) : null;
+      const stat = data as { exists?: unknown; size?: unknown } | null;
+      const info =
+        stat?.exists === false
+          ? "不存在"
+          : typeof stat?.size === "number"
+            ? `${stat.size.toLocaleString()} 字节`
+            : "已查看";
       return path ? `path: ${path} · ${info}` : info;
     }
     case "list": {
@@ -74,8 +93,19 @@ function summarizeToolCall(call: ToolCall): string {
       return path ? `path: ${path} · 已追加` : "已追加";
     case "write":
       return path ? `path: ${path} · 已写入` : "已写入";
+    case "edit":
+      return path ? `path: ${path} · 已修改` : "已修改";
+    case "delete":
+      return path ? `path: ${path} · 已删除` : "已删除";
     case "save_summary":
       return "已保存";
+    case "get_story_rollup": {
+      const data = call.result ? safeJsonParse(call.result) : null;
+      const status = data as { stale?: unknown; rollup?: { chapterIds?: unknown } } | null;
+      if (!status?.rollup) return "尚未生成前情提要";
+      const count = Array.isArray(status.rollup.chapterIds) ? status.rollup.chapterIds.length : 0;
+      return `覆盖 ${count.toLocaleString()} 章${status.stale ? " · 已过期" : ""}`;
+    }
     case "get_chapter_info": {
       const data = call.result ? safeJsonParse(call.result) : null;
       const title = (data as { title?: unknown } | null)?.title;
@@ -126,6 +126,7 @@ function buildContinueSystemPrompt(params: {
 - list: 列出目录内容（需要时）
 - search: 搜索摘要获取前情
 - get_chapter_info: 获取当前章节信息（路径、字数等）
+- get_story_rollup: 获取全书前情提要（若已生成）
 - rag_search: 在知识库（knowledge/）中语义检索相关资料
 - append: 追加续写内容到章节末尾（仅在用户确认后）
 - save_summary: 保存本次续写的摘要（仅在用户确认后）
@@ -136,7 +137,7 @@ ${phaseHint}
 ## 工作流程（草稿阶段）
 1. 首先用 read 读取当前章节的最后部分（建议 offset: -2000）作为上下文
 2. 可用 rag_search 检索 knowledge/ 里的设定/人物/时间线资料
-3. 用 search 搜索 summaries.json 相关摘要，了解前情和人物关系
+3. 用 search 搜索 summaries/ 中的相关摘要，了解前情和人物关系
 4. 根据用户指令和上下文，生成续写内容（约 500-1000 字）
 5. 输出“续写预览”（只输出正文，不要把工具返回的 JSON 原样贴出来），等待用户确认
 
@@ -152,7 +153,7 @@ ${params.writingPreset}
 - 项目路径：${params.projectPath}
 - 当前章节：${chapterLabel}
 - 章节文件：${chapterPath}
-- 摘要文件：summaries.json
+- 摘要目录：summaries/（每章一个 JSON 文件）
 
 ## 注意
 - 续写内容要与前文风格一致，保持人物性格与情节连贯
@@ -178,7 +179,7 @@ function buildUnifiedSystemPrompt(params: {
 你是 Creator Studio 的小说写作 AI Agent。你要在同一个对话中同时支持“讨论”和“续写”，并能自动判断用户意图。
 
 ## 工具（重要）
-- 可读工具：list / read / search / get_chapter_info
+- 可读工具：list / read / search / get_chapter_info / get_story_rollup
 - 写入工具：append / write / save_summary
 - RAG 工具：rag_search（从 knowledge/ 语义检索资料）
 
@@ -195,7 +196,7 @@ function buildUnifiedSystemPrompt(params: {
 ## 续写草稿阶段（默认）
 1. 先用 read 读取当前章节最后部分作为上下文（建议 offset: -2000）
 2. 可用 rag_search 检索 knowledge/ 里的设定/人物/时间线资料
-3. 用 search 搜索 summaries.json 相关摘要，确保前后连贯
+3. 用 search 搜索 summaries/ 中的相关摘要，确保前后连贯
 4. 输出 500-1000 字的“正文续写预览”
 
 输出格式必须严格遵守（为了让前端识别草稿）：
@@ -213,7 +214,7 @@ function buildUnifiedSystemPrompt(params: {
 - 项目路径：${params.projectPath}
 - 当前选中章节：${chapterLabel}
 - 章节文件：${chapterPath}
-- 摘要文件：summaries.json
+- 摘要目录：summaries/（每章一个 JSON 文件）
 
 ## 写作要求
 ${params.writingPreset}
@@ -2,12 +2,22 @@ import { useEffect, useRef, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import { open } from "@tauri-apps/plugin-dialog";
-import { Button, Input, List, Modal, Progress, Select, message } from "antd";
+import { Button, Checkbox, Input, List, Modal, Progress, Select, message } from "antd";
 import { formatError } from "../../utils/error";
 
 interface ChapterPreview {
   title: string;
   wordCount: number;
+  /** 第一个章节标题之前的文字，单独作为一章导入 */
+  isPreamble?: boolean;
+  /** 项目中已有同名章节时为其 id */
+  existingChapterId?: string;
+}
+
+interface ImportTxtPreview {
+  /** 自动识别的文件编码，如 UTF-8、GBK、Big5 */
+  encoding: string;
+  chapters: ChapterPreview[];
 }
 
 interface ImportTxtProgressPayload {
@@ -15,6 +25,30 @@ interface ImportTxtProgressPayload {
   total: number;
   completed: number;
   currentTitle?: string | null;
+  cancelled?: boolean;
+}
+
+interface ImportTxtResult {
+  chapters: unknown[];
+  cancelled: boolean;
+  rolledBack: boolean;
+  skipped: number;
+  overwritten: number;
+  warnings: string[];
+}
+
+type ConflictStrategy = "skip" | "overwrite" | "duplicate";
+
+const CONFLICT_STRATEGIES: { label: string; value: ConflictStrategy }[] = [
+  { label: "跳过同名章节", value: "skip" },
+  { label: "覆盖同名章节的内容", value: "overwrite" },
+  { label: "作为新章节导入", value: "duplicate" },
+];
+
+interface ImportPattern {
+  id: string;
+  name: string;
+  pattern: string;
 }
 
 interface ImportModalProps {
@@ -42,6 +76,12 @@ export default function ImportModal({ visible, projectPath, onCancel, onSuccess
   const [filePath, setFilePath] = useState("");
   const [pattern, setPattern] = useState(CHAPTER_PATTERNS[0]?.value ?? "^第.+章.*");
   const [previews, setPreviews] = useState<ChapterPreview[]>([]);
+  const [encoding, setEncoding] = useState<string | null>(null);
+  // 预览列表中取消勾选的章节下标（如上架感言、请假条）
+  const [excluded, setExcluded] = useState<Set<number>>(new Set());
+  const [conflictStrategy, setConflictStrategy] = useState<ConflictStrategy>("skip");
+  const [savedPatterns, setSavedPatterns] = useState<ImportPattern[]>([]);
+  const [patternName, setPatternName] = useState("");
   const [previewLoading, setPreviewLoading] = useState(false);
   const [importing, setImporting] = useState(false);
   const [progress, setProgress] = useState<{ total: number; completed: number; currentTitle?: string } | null>(
@@ -54,6 +94,10 @@ export default function ImportModal({ visible, projectPath, onCancel, onSuccess
       setFilePath("");
       setPattern(CHAPTER_PATTERNS[0]?.value ?? "^第.+章.*");
       setPreviews([]);
+      setEncoding(null);
+      setExcluded(new Set());
+      setConflictStrategy("skip");
+      setPatternName("");
       setPreviewLoading(false);
       setImporting(false);
       setProgress(null);
@@ -61,9 +105,39 @@ export default function ImportModal({ visible, projectPath, onCancel, onSuccess
     }
   }, [visible]);
 
+  const loadSavedPatterns = async () => {
+    try {
+      setSavedPatterns((await invoke("list_import_patterns")) as ImportPattern[]);
+    } catch (error) {
+      message.error(`读取已保存规则失败: ${formatError(error)}`);
+    }
+  };
+
+  useEffect(() => {
+    if (visible) void loadSavedPatterns();
+  }, [visible]);
+
+  const handleSavePattern = async () => {
+    const name = patternName.trim();
+    if (!name) {
+      message.error("请输入规则名称");
+      return;
+    }
+    const existing = savedPatterns.find((p) => p.name === name);
+    try {
+      await invoke("save_import_pattern", { pattern: { id: existing?.id ?? "", name, pattern } });
+      message.success(existing ? `已更新规则「${name}」` : `已保存规则「${name}」`);
+      setPatternName("");
+      await loadSavedPatterns();
+    } catch (error) {
+      message.error(`保存规则失败: ${formatError(error)}`);
+    }
+  };
+
   const runPreview = async (path: string, pat: string) => {
     if (!path.trim()) {
       setPreviews([]);
+      setEncoding(null);
       return;
     }
     setPreviewLoading(true);
@@ -71,10 +145,14 @@ export default function ImportModal({ visible, projectPath, onCancel, onSuccess
       const result = (await invoke("preview_import_txt", {
         filePath: path,
         pattern: pat,
-      })) as ChapterPreview[];
-      setPreviews(result || []);
+        projectPath,
+      })) as ImportTxtPreview;
+      setPreviews(result?.chapters || []);
+      setEncoding(result?.encoding ?? null);
+      setExcluded(new Set());
     } catch (error) {
       setPreviews([]);
+      setEncoding(null);
       message.error(`预览失败: ${formatError(error)}`);
     } finally {
       setPreviewLoading(false);
@@ -114,13 +192,18 @@ export default function ImportModal({ visible, projectPath, onCancel, onSuccess
       message.error("未识别到章节，请调整章节识别规则");
       return;
     }
+    const selectedIndices = previews.map((_, index) => index).filter((index) => !excluded.has(index));
+    if (!selectedIndices.length) {
+      message.error("请至少选择一个章节");
+      return;
+    }
 
     const requestId = makeRequestId();
     requestIdRef.current = requestId;
 
     let unlisten: UnlistenFn | null = null;
     setImporting(true);
-    setProgress({ total: previews.length, completed: 0 });
+    setProgress({ total: selectedIndices.length, completed: 0 });
 
     try {
       unlisten = await listen<ImportTxtProgressPayload>(IMPORT_PROGRESS_EVENT, (event) => {
@@ -133,14 +216,31 @@ export default function ImportModal({ visible, projectPath, onCancel, onSuccess
         });
       });
 
-      await invoke("import_txt", {
+      const result = (await invoke("import_txt", {
         projectPath,
         filePath,
         pattern,
         requestId,
-      });
+        options: { selectedIndices, rollbackOnCancel: true, conflictStrategy },
+      })) as ImportTxtResult;
 
-      message.success(`成功导入 ${previews.length} 个章节`);
+      if (result.cancelled) {
+        message.info(
+          result.rolledBack
+            ? "已取消导入，已撤销本次导入的章节"
+            : `已取消导入，保留 ${result.chapters.length} 个章节`,
+        );
+        if (result.warnings?.length) message.warning(result.warnings.join("\n"));
+        if (!result.rolledBack) onSuccess();
+        return;
+      }
+      const created = result.chapters.length - result.overwritten;
+      const details = [
+        result.overwritten ? `覆盖 ${result.overwritten} 个` : "",
+        result.skipped ? `跳过 ${result.skipped} 个同名章节` : "",
+      ].filter(Boolean);
+      message.success(`成功导入 ${created} 个章节${details.length ? `（${details.join("，")}）` : ""}`);
+      if (result.warnings?.length) message.warning(result.warnings.join("\n"));
       onSuccess();
     } catch (error) {
       message.error(`导入失败: ${formatError(error)}`);
@@ -155,22 +255,34 @@ export default function ImportModal({ visible, projectPath, onCancel, onSuccess
     ? Math.min(100, Math.round((progress.completed / progress.total) * 100))
     : 0;
 
-  const presetValue = CHAPTER_PATTERNS.some((p) => p.value === pattern) ? pattern : undefined;
+  const conflictCount = previews.filter((p, index) => p.existingChapterId && !excluded.has(index)).length;
+
+  const patternOptions = [
+    ...CHAPTER_PATTERNS,
+    ...savedPatterns
+      .filter((saved) => !CHAPTER_PATTERNS.some((p) => p.value === saved.pattern))
+      .map((saved) => ({ label: `${saved.name}（已保存）`, value: saved.pattern })),
+  ];
+  const presetValue = patternOptions.some((p) => p.value === pattern) ? pattern : undefined;
 
   return (
     <Modal
       title="导入小说"
       open={visible}
       onCancel={() => {
-        if (importing) return;
+        if (importing) {
+          const requestId = requestIdRef.current;
+          if (requestId) void invoke("import_txt_cancel", { requestId }).catch(() => undefined);
+          return;
+        }
         onCancel();
       }}
       onOk={() => void handleImport()}
       okText="导入"
-      cancelText="取消"
+      cancelText={importing ? "停止导入" : "取消"}
       confirmLoading={importing}
       okButtonProps={{
-        disabled: previewLoading || importing || !filePath.trim() || previews.length === 0,
+        disabled: previewLoading || importing || !filePath.trim() || previews.length === excluded.size,
       }}
       width={640}
       destroyOnClose
@@ -202,7 +314,7 @@ export default function ImportModal({ visible, projectPath, onCancel, onSuccess
           <Select
             value={presetValue}
             onChange={(value) => setPattern(value)}
-            options={CHAPTER_PATTERNS}
+            options={patternOptions}
             style={{ width: "100%", marginBottom: 8 }}
             disabled={importing}
             placeholder="选择预设（可选）"
@@ -213,12 +325,34 @@ export default function ImportModal({ visible, projectPath, onCancel, onSuccess
             placeholder="例如：^第.+章.*"
             disabled={importing}
           />
+          <Input.Search
+            value={patternName}
+            onChange={(e) => setPatternName(e.target.value)}
+            placeholder="规则名称（保存后可在预设中选择）"
+            enterButton="保存规则"
+            onSearch={() => void handleSavePattern()}
+            disabled={importing || !pattern.trim()}
+            style={{ marginTop: 8 }}
+          />
         </div>
 
+        {conflictCount > 0 && (
+          <div>
+            <div style={{ marginBottom: 6 }}>有 {conflictCount} 个章节与项目中已有章节同名</div>
+            <Select
+              value={conflictStrategy}
+              onChange={(value) => setConflictStrategy(value)}
+              options={CONFLICT_STRATEGIES}
+              style={{ width: "100%" }}
+              disabled={importing}
+            />
+          </div>
+        )}
+
         {(importing || progress) && (
           <div>
             <div style={{ marginBottom: 6 }}>
-              导入进度：{progress?.completed ?? 0}/{progress?.total ?? previews.length}
+              导入进度：{progress?.completed ?? 0}/{progress?.total ?? previews.length - excluded.size}
               {progress?.currentTitle ? `（${progress.currentTitle}）` : ""}
             </div>
             <Progress percent={progressPercent} status={importing ? "active" : "normal"} />
@@ -227,7 +361,8 @@ export default function ImportModal({ visible, projectPath, onCancel, onSuccess
 
         <div>
           <div style={{ marginBottom: 6 }}>
-            预览（识别到 {previews.length} 个章节）
+            预览（识别到 {previews.length} 个章节{excluded.size ? `，已排除 ${excluded.size} 个` : ""}
+            {encoding ? `，编码 ${encoding}` : ""}）
             {previewLoading ? "，加载中…" : ""}
           </div>
           <List
@@ -237,9 +372,22 @@ export default function ImportModal({ visible, projectPath, onCancel, onSuccess
             style={{ maxHeight: 300, overflow: "auto" }}
             renderItem={(item, index) => (
               <List.Item>
-                <span>
+                <Checkbox
+                  checked={!excluded.has(index)}
+                  disabled={importing}
+                  onChange={(e) =>
+                    setExcluded((prev) => {
+                      const next = new Set(prev);
+                      if (e.target.checked) next.delete(index);
+                      else next.add(index);
+                      return next;
+                    })
+                  }
+                >
                   {index + 1}. {item.title}
-                </span>
+                  {item.isPreamble ? "（章节标题前的内容）" : ""}
+                  {item.existingChapterId ? "（已存在）" : ""}
+                </Checkbox>
                 <span style={{ color: "var(--text-secondary)" }}>
                   {item.wordCount.toLocaleString()} 字
                 </span>
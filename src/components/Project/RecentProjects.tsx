@@ -4,6 +4,8 @@ export interface RecentProject {
   name: string;
   path: string;
   lastOpened: number;
+  wordCount?: number;
+  chapterCount?: number;
 }
 
 interface RecentProjectsProps {
@@ -46,6 +48,13 @@ export default function RecentProjects({ projects, onOpen }: RecentProjectsProps
                       </Typography.Text>
                     </div>
                   ) : null}
+                  {item.wordCount != null && item.chapterCount != null ? (
+                    <div style={{ marginTop: 4 }}>
+                      <Typography.Text type="secondary">
+                        {item.chapterCount} 章 · {item.wordCount.toLocaleString()} 字
+                      </Typography.Text>
+                    </div>
+                  ) : null}
                 </div>
               }
             />
@@ -61,6 +61,7 @@ export default function ProviderSettings() {
     api_key?: string;
     provider_type: string;
   }>();
+  const providerType = Form.useWatch("provider_type", form);
 
   const loadProviders = async () => {
     setLoading(true);
@@ -116,8 +117,9 @@ export default function ProviderSettings() {
         }
       } else {
         const id = `provider_${Date.now()}`;
-        const apiKey = values.api_key?.trim();
-        if (!apiKey) {
+        const keyless = values.provider_type === "ollama";
+        const apiKey = values.api_key?.trim() ?? "";
+        if (!apiKey && !keyless) {
           message.error("请输入 API Key");
           return;
         }
@@ -241,7 +243,11 @@ export default function ProviderSettings() {
                 ? "Google"
                 : record.provider_type === "anthropic"
                   ? "Anthropic"
-                  : record.provider_type;
+                  : record.provider_type === "azure-openai"
+                    ? "Azure OpenAI"
+                    : record.provider_type === "ollama"
+                      ? "Ollama"
+                      : record.provider_type;
 
           const menuItems = [
             !isActive
@@ -363,7 +369,7 @@ export default function ProviderSettings() {
           <Form.Item
             name="api_key"
             label="API Key"
-            rules={[{ required: !editingProvider, message: "请输入 API Key" }]}
+            rules={[{ required: !editingProvider && providerType !== "ollama", message: "请输入 API Key" }]}
             extra="API Key 会保存在系统钥匙串（Keychain）。首次使用（发送消息/刷新模型）时，macOS 可能会弹窗请求授权访问。编辑时留空表示不修改。"
           >
             <Input.Password
@@ -376,6 +382,8 @@ export default function ProviderSettings() {
                 { value: "openai-compatible", label: "OpenAI Compatible（Authorization: Bearer）" },
                 { value: "google", label: "Google（x-goog-api-key）" },
                 { value: "anthropic", label: "Anthropic（x-api-key）" },
+                { value: "azure-openai", label: "Azure OpenAI（api-key + api-version）" },
+                { value: "ollama", label: "Ollama（本地，无需 API Key）" },
               ]}
             />
           </Form.Item>
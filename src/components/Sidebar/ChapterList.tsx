@@ -229,11 +229,7 @@ export default function ChapterList({ projectPath }: ChapterListProps) {
         )}
       </div>
 
-      <ChapterSummary
-        projectPath={projectPath}
-        chapterId={currentChapterId}
-        chapterTitle={chapters.find((c) => c.id === currentChapterId)?.title ?? null}
-      />
+      <ChapterSummary projectPath={projectPath} chapterId={currentChapterId} />
 
       <Modal
         title="新建章节"
@@ -1,20 +1,13 @@
 import { useCallback, useEffect, useMemo, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { Button, Space, Typography, message } from "antd";
-import { CopyOutlined, ReloadOutlined } from "@ant-design/icons";
-import { aiChat } from "../../lib/ai";
+import { Button, Input, Popconfirm, Space, Typography, message } from "antd";
+import { CopyOutlined, DeleteOutlined, EditOutlined, ReloadOutlined } from "@ant-design/icons";
+import { generateChapterSummary, type SavedSummary, type SummaryEntry } from "../../lib/ai";
 import { formatError } from "../../utils/error";
 
-type SummaryEntry = {
-  chapterId: string;
-  summary: string;
-  createdAt: number;
-};
-
 interface ChapterSummaryProps {
   projectPath: string;
   chapterId: string | null;
-  chapterTitle?: string | null;
 }
 
 function formatCreatedAt(ts: number): string {
@@ -52,34 +45,19 @@ async function copyText(text: string) {
   document.body.removeChild(el);
 }
 
-function summarySystemPrompt(): string {
-  return `
-你是一个小说章节摘要助手。你的任务是把“章节正文”压缩成一段简短摘要，帮助作者快速回忆本章发生了什么。
-
-要求：
-- 输出一段中文摘要（约 50-120 字，尽量精炼）
-- 只陈述本章已发生的事件，不要脑补未写内容
-- 不要使用 Markdown、标题、列表、引号
-- 只输出摘要正文本身
-  `.trim();
-}
-
-function summaryUserPrompt(params: { chapterId: string; chapterTitle?: string | null; content: string }): string {
-  const title = params.chapterTitle?.trim() ? `《${params.chapterTitle.trim()}》` : "";
-  return `
-请为章节 ${title}${params.chapterId ? `（${params.chapterId}）` : ""} 生成摘要。
-
-【章节正文】
-${params.content}
-  `.trim();
-}
-
-export default function ChapterSummary({ projectPath, chapterId, chapterTitle }: ChapterSummaryProps) {
+export default function ChapterSummary({ projectPath, chapterId }: ChapterSummaryProps) {
   const [entry, setEntry] = useState<SummaryEntry | null>(null);
   const [loading, setLoading] = useState(false);
   const [generating, setGenerating] = useState(false);
+  const [editing, setEditing] = useState(false);
+  const [draft, setDraft] = useState("");
+  const [saving, setSaving] = useState(false);
 
-  const createdAtText = useMemo(() => (entry?.createdAt ? formatCreatedAt(entry.createdAt) : ""), [entry]);
+  const createdAtText = useMemo(() => {
+    if (!entry?.createdAt) return "";
+    const created = formatCreatedAt(entry.createdAt);
+    return entry.updatedAt ? `${created}（已编辑）` : created;
+  }, [entry]);
 
   const loadLatest = useCallback(async () => {
     if (!chapterId) {
@@ -102,6 +80,7 @@ export default function ChapterSummary({ projectPath, chapterId, chapterTitle }:
   }, [projectPath, chapterId]);
 
   useEffect(() => {
+    setEditing(false);
     void loadLatest();
   }, [loadLatest]);
 
@@ -123,47 +102,12 @@ export default function ChapterSummary({ projectPath, chapterId, chapterTitle }:
     setGenerating(true);
     message.loading({ content: "正在生成摘要...", key: "summary", duration: 0 });
     try {
-      const content = (await invoke("get_chapter_content", {
-        projectPath,
-        chapterId,
-      })) as string;
-
-      if (!content || !content.trim()) {
-        message.error({ content: "章节内容为空，无法生成摘要。", key: "summary" });
-        return;
-      }
-
-      const { content: summaryText } = await aiChat({
-        projectDir: projectPath,
-        mode: "Discussion",
-        systemPrompt: summarySystemPrompt(),
-        chapterId,
-        allowWrite: false,
-        messages: [
-          {
-            role: "user",
-            content: summaryUserPrompt({ chapterId, chapterTitle, content }),
-          },
-        ],
-      });
-
-      const summary = summaryText.trim();
-      if (!summary) {
-        message.error({ content: "AI 未返回摘要，请重试。", key: "summary" });
-        return;
-      }
-
-      const saved = (await invoke("save_summary_entry", {
-        projectPath,
-        chapterId,
-        summary,
-      })) as SummaryEntry;
-
+      const saved = await generateChapterSummary({ projectDir: projectPath, chapterId });
       setEntry(saved);
-      window.dispatchEvent(
-        new CustomEvent("creatorai:summariesChanged", { detail: { projectPath, chapterId } }),
-      );
+      setEditing(false);
+      notifySummariesChanged();
       message.success({ content: "摘要已保存", key: "summary" });
+      if (saved.warnings?.length) message.warning(saved.warnings.join("\n"));
     } catch (error) {
       message.error({ content: `生成摘要失败: ${formatError(error)}`, key: "summary" });
     } finally {
@@ -171,6 +115,64 @@ export default function ChapterSummary({ projectPath, chapterId, chapterTitle }:
     }
   };
 
+  const notifySummariesChanged = () => {
+    window.dispatchEvent(
+      new CustomEvent("creatorai:summariesChanged", { detail: { projectPath, chapterId } }),
+    );
+  };
+
+  const handleStartEdit = () => {
+    if (!entry) return;
+    setDraft(entry.summary);
+    setEditing(true);
+  };
+
+  const handleSaveEdit = async () => {
+    if (!entry || saving) return;
+    const newText = draft.trim();
+    if (!newText) {
+      message.error("摘要不能为空");
+      return;
+    }
+
+    setSaving(true);
+    try {
+      const updated = (await invoke("update_summary", {
+        projectPath,
+        chapterId: entry.chapterId,
+        createdAt: entry.createdAt,
+        newText,
+      })) as SavedSummary;
+      setEntry(updated);
+      setEditing(false);
+      notifySummariesChanged();
+      message.success("摘要已更新");
+      if (updated.warnings?.length) message.warning(updated.warnings.join("\n"));
+    } catch (error) {
+      message.error(`保存摘要失败: ${formatError(error)}`);
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleDelete = async () => {
+    if (!entry) return;
+    try {
+      const deleted = (await invoke("delete_summary", {
+        projectPath,
+        chapterId: entry.chapterId,
+        createdAt: entry.createdAt,
+      })) as SavedSummary;
+      setEditing(false);
+      notifySummariesChanged();
+      await loadLatest();
+      message.success("摘要已删除");
+      if (deleted.warnings?.length) message.warning(deleted.warnings.join("\n"));
+    } catch (error) {
+      message.error(`删除摘要失败: ${formatError(error)}`);
+    }
+  };
+
   const handleCopy = async () => {
     if (!entry?.summary?.trim()) return;
     try {
@@ -209,6 +211,17 @@ export default function ChapterSummary({ projectPath, chapterId, chapterTitle }:
             disabled={!entry?.summary?.trim()}
             title="复制摘要"
           />
+          <Button
+            size="small"
+            type="text"
+            icon={<EditOutlined />}
+            onClick={handleStartEdit}
+            disabled={!entry || editing || generating}
+            title="编辑摘要"
+          />
+          <Popconfirm title="删除这条摘要？" onConfirm={() => void handleDelete()} disabled={!entry || generating}>
+            <Button size="small" type="text" icon={<DeleteOutlined />} disabled={!entry || generating} title="删除摘要" />
+          </Popconfirm>
         </Space>
       </div>
 
@@ -216,7 +229,19 @@ export default function ChapterSummary({ projectPath, chapterId, chapterTitle }:
         {loading ? (
           <div className="chapter-summary-empty">加载中...</div>
         ) : chapterId ? (
-          entry?.summary?.trim() ? (
+          editing ? (
+            <Space direction="vertical" size={4} style={{ width: "100%" }}>
+              <Input.TextArea value={draft} onChange={(e) => setDraft(e.target.value)} autoSize={{ minRows: 3, maxRows: 8 }} />
+              <Space size={4}>
+                <Button size="small" type="primary" loading={saving} onClick={() => void handleSaveEdit()}>
+                  保存
+                </Button>
+                <Button size="small" onClick={() => setEditing(false)} disabled={saving}>
+                  取消
+                </Button>
+              </Space>
+            </Space>
+          ) : entry?.summary?.trim() ? (
             <div className="chapter-summary-content">{entry.summary}</div>
           ) : (
             <div className="chapter-summary-empty">暂无摘要，可点击下方“生成摘要”。</div>
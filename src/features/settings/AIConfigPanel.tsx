@@ -73,6 +73,7 @@ export function AIConfigPanel(): ReactElement {
     api_key?: string;
     provider_type: string;
   }>();
+  const providerType = Form.useWatch("provider_type", form);
   const [modelForm] = Form.useForm<ModelParameters>();
   const autoRefreshAttemptedRef = useRef<Set<string>>(new Set());
 
@@ -164,8 +165,9 @@ export function AIConfigPanel(): ReactElement {
         }
       } else {
         // 添加模式
-        const apiKey = values.api_key?.trim();
-        if (!apiKey) {
+        const keyless = values.provider_type === "ollama";
+        const apiKey = values.api_key?.trim() ?? "";
+        if (!apiKey && !keyless) {
           message.error("请输入 API Key");
           return;
         }
@@ -283,7 +285,11 @@ export function AIConfigPanel(): ReactElement {
                   ? "Google"
                   : record.provider_type === "anthropic"
                     ? "Anthropic"
-                    : record.provider_type;
+                    : record.provider_type === "azure-openai"
+                      ? "Azure OpenAI"
+                      : record.provider_type === "ollama"
+                        ? "Ollama"
+                        : record.provider_type;
 
             const menuItems: Array<{
               key: string;
@@ -493,7 +499,7 @@ export function AIConfigPanel(): ReactElement {
           <Form.Item
             name="api_key"
             label="API Key"
-            rules={[{ required: !editingProvider, message: "请输入 API Key" }]}
+            rules={[{ required: !editingProvider && providerType !== "ollama", message: "请输入 API Key" }]}
             extra="API Key 会保存在系统钥匙串（Keychain）。编辑时留空表示不修改。"
           >
             <Input.Password
@@ -506,6 +512,8 @@ export function AIConfigPanel(): ReactElement {
                 { value: "openai-compatible", label: "OpenAI Compatible（Authorization: Bearer）" },
                 { value: "google", label: "Google（x-goog-api-key）" },
                 { value: "anthropic", label: "Anthropic（x-api-key）" },
+                { value: "azure-openai", label: "Azure OpenAI（api-key + api-version）" },
+                { value: "ollama", label: "Ollama（本地，无需 API Key）" },
               ]}
             />
           </Form.Item>
@@ -13,7 +13,7 @@ export interface ProviderPreset {
   /** API Base URL */
   baseUrl: string;
   /** Provider 类型 */
-  providerType: "openai-compatible" | "google" | "anthropic";
+  providerType: "openai-compatible" | "google" | "anthropic" | "azure-openai" | "ollama";
   /** 认证类型 */
   authType: "Bearer" | "x-api-key" | "x-goog-api-key";
   /** API Key 格式识别正则 */
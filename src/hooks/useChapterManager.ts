@@ -204,11 +204,12 @@ export function useChapterManager(
           setWebChapters(webChapters);
         }
       } else if (isTauri()) {
-        await invoke("save_chapter_content", {
+        const result = (await invoke("save_chapter_content", {
           projectPath,
           chapterId: currentChapterId,
           content,
-        });
+        })) as { warnings?: string[] };
+        if (result?.warnings?.length) message.warning(result.warnings.join("\n"));
       }
       setChapterContent(content);
       setDraftContentState(content);
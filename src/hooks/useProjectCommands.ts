@@ -8,10 +8,11 @@ import { useCallback, useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { open } from "@tauri-apps/plugin-dialog";
 import { isTauri } from "@tauri-apps/api/core";
-import { message } from "antd";
+import { message, Modal } from "antd";
 import { formatError } from "../utils/error";
 import type { ProjectConfig, RecentProject } from "../app/types";
 import { useAppStore } from "../app/store";
+import { openProject as openProjectCommand, releaseProjectLock } from "../platform/tauri/client";
 
 function joinPath(parent: string, child: string): string {
   const trimmedParent = parent.replace(/[\\/]+$/, "");
@@ -103,6 +104,38 @@ function setWebProjectData(data: WebProjectData): void {
   } catch {}
 }
 
+// ==================== 项目锁 ====================
+
+/** 后端在项目被其他窗口或实例占用时返回的错误前缀 */
+const PROJECT_LOCKED_PREFIX = "PROJECT_LOCKED";
+
+function isProjectLockedError(error: unknown): boolean {
+  return formatError(error).includes(PROJECT_LOCKED_PREFIX);
+}
+
+function confirmTakeover(): Promise<boolean> {
+  return new Promise((resolve) => {
+    Modal.confirm({
+      title: "项目已在其他窗口中打开",
+      content: "强制接管后，两个窗口中的修改可能会相互覆盖。确定要继续吗？",
+      okText: "强制接管",
+      okType: "danger",
+      cancelText: "取消",
+      onOk: () => resolve(true),
+      onCancel: () => resolve(false),
+    });
+  });
+}
+
+async function releaseLock(path: string): Promise<void> {
+  if (!isTauri() || path.startsWith("web-demo://")) return;
+  try {
+    await releaseProjectLock(path);
+  } catch (error) {
+    console.warn("释放项目锁失败:", error);
+  }
+}
+
 // ==================== 项目操作相关 Tauri 命令封装 ====================
 
 export function useProjectCommands() {
@@ -148,7 +181,16 @@ export function useProjectCommands() {
         addWebRecentProject(data.name, path);
         message.success({ content: `已打开项目：${data.name}`, key: "project" });
       } else if (isTauri()) {
-        const config = (await invoke("open_project", { path })) as ProjectConfig;
+        let config: ProjectConfig;
+        try {
+          config = await openProjectCommand(path);
+        } catch (error) {
+          if (!isProjectLockedError(error)) throw error;
+          message.destroy("project");
+          if (!(await confirmTakeover())) return;
+          message.loading({ content: "正在打开项目...", key: "project" });
+          config = await openProjectCommand(path, { forceTakeover: true });
+        }
         setCurrentProject({ path, name: config.name });
         await invoke("add_recent_project", { name: config.name, path });
         await loadRecentProjects();
@@ -254,7 +296,9 @@ export function useProjectCommands() {
   }, [openProject]);
 
   const closeProject = useCallback(() => {
+    const current = useAppStore.getState().currentProject;
     setCurrentProject(null);
+    if (current) void releaseLock(current.path);
   }, [setCurrentProject]);
 
   return {
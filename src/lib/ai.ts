@@ -21,6 +21,8 @@ export interface AIChatToolCall {
 export interface AIChatResult {
   content: string;
   tool_calls: AIChatToolCall[];
+  /** 本轮写入的文件分组，可用于 undoAiApply；未写入时为 null */
+  apply_id?: string | null;
 }
 
 interface ProviderConfig {
@@ -30,6 +32,8 @@ interface ProviderConfig {
   models: string[];
   provider_type: string;
   headers?: Record<string, string> | null;
+  api_version?: string | null;
+  deployments?: Record<string, string>;
 }
 
 interface ModelParametersConfig {
@@ -60,17 +64,17 @@ const DISCUSSION_SYSTEM_PROMPT_TEMPLATE = `你是一位专业的小说写作顾
 ## 可用工具
 - list: 列出目录内容（例如 chapters/）
 - read: 读取文件内容（例如 chapters/chapter_003.txt、chapters/index.json、.creatorai/config.json）
-- search: 在文件或目录中搜索关键词（例如 summaries.json 或 chapters/）
+- search: 在文件或目录中搜索关键词（例如 summaries/ 或 chapters/）
 
 ## 项目结构（项目根目录：{projectPath}）
 - chapters/ — 章节文件（chapter_001.txt, chapter_002.txt...）
 - chapters/index.json — 章节索引（标题、顺序、字数）
 - .creatorai/config.json — 项目配置
-- summaries.json — 摘要记录（若存在）
+- summaries/ — 按章节存放的摘要记录（如 summaries/chapter_003.json，若存在）
 
 ## 工作方式
 1. 当用户询问章节内容/风格/角色设定时，主动使用 read 读取相关章节；必要时先 read chapters/index.json 确认章节编号/标题
-2. 当用户询问“之前有没有写过类似情节/关键词”时，使用 search 在 summaries.json 或 chapters/ 中搜索
+2. 当用户询问“之前有没有写过类似情节/关键词”时，使用 search 在 summaries/ 或 chapters/ 中搜索
 3. 当用户没有指定章节时，先用 list 查看 chapters/ 或读取 chapters/index.json，再追问澄清
 4. 给出建议时要具体，引用你读取到的内容（可以引用行号前缀）
 
@@ -97,6 +101,8 @@ export async function getActiveChatConfig(): Promise<{
     models: string[];
     providerType: string;
     headers?: Record<string, string>;
+    apiVersion?: string;
+    deployments?: Record<string, string>;
   };
   parameters: {
     model: string;
@@ -113,10 +119,12 @@ export async function getActiveChatConfig(): Promise<{
     const activeProvider = config.providers.find((p) => p.id === config.active_provider_id);
     if (!activeProvider) return null;
 
-    const apiKey = (await invoke("get_api_key", {
+    const resolvedKey = (await invoke("get_api_key", {
       providerId: activeProvider.id,
-    })) as string | null;
-    if (!apiKey) return null;
+    })) as { key: string } | null;
+    // Local Ollama servers run without a key.
+    const apiKey = resolvedKey?.key ?? (activeProvider.provider_type === "ollama" ? "" : null);
+    if (apiKey === null) return null;
 
     const providerModels = Array.isArray(activeProvider.models) ? activeProvider.models : [];
     const desiredModel = (config.default_parameters.model ?? "").trim();
@@ -137,6 +145,8 @@ export async function getActiveChatConfig(): Promise<{
         models: providerModels,
         providerType: activeProvider.provider_type,
         headers: activeProvider.headers ?? undefined,
+        apiVersion: activeProvider.api_version ?? undefined,
+        deployments: activeProvider.deployments ?? undefined,
       },
       parameters: {
         model: resolvedModel,
@@ -158,7 +168,8 @@ export async function aiChat(params: {
   systemPrompt?: string;
   chapterId?: string | null;
   allowWrite?: boolean;
-}): Promise<{ content: string; toolCalls: AIChatToolCall[] }> {
+  sessionId?: string | null;
+}): Promise<{ content: string; toolCalls: AIChatToolCall[]; applyId: string | null }> {
   const active = await getActiveChatConfig();
   if (!active) {
     throw new Error("请先在设置中添加 Provider，并设为当前，然后配置模型参数。");
@@ -173,11 +184,13 @@ export async function aiChat(params: {
     mode: params.mode,
     chapterId: params.chapterId ?? null,
     allowWrite: params.allowWrite ?? false,
+    sessionId: params.sessionId ?? null,
   })) as AIChatResult;
 
   return {
     content: result.content ?? "",
     toolCalls: Array.isArray(result.tool_calls) ? result.tool_calls : [],
+    applyId: result.apply_id ?? null,
   };
 }
 
@@ -226,6 +239,86 @@ ${after}
   return typeof result === "string" ? result : String(result ?? "");
 }
 
+export interface SummaryEntry {
+  chapterId: string;
+  summary: string;
+  createdAt: number;
+  updatedAt?: number;
+  /** 来源：AI 工具保存 / 手动填写 / 一键生成；旧数据缺省 */
+  source?: "tool" | "manual" | "generated";
+  sessionId?: string;
+  model?: string;
+}
+
+/** 保存/更新/删除后的摘要；warnings 为未能更新的记录（如活动日志） */
+export interface SavedSummary extends SummaryEntry {
+  warnings: string[];
+}
+
+/** 一键生成并保存章节摘要；可用 ai_complete_cancel 取消 */
+export async function generateChapterSummary(params: {
+  projectDir: string;
+  chapterId: string;
+}): Promise<SavedSummary> {
+  const active = await getActiveChatConfig();
+  if (!active) {
+    throw new Error("请先在设置中添加 Provider，并设为当前，然后配置模型参数。");
+  }
+
+  return (await invoke("generate_chapter_summary", {
+    projectPath: params.projectDir,
+    chapterId: params.chapterId,
+    provider: active.provider,
+    parameters: active.parameters,
+  })) as SavedSummary;
+}
+
+export interface StoryRollup {
+  fromChapterId: string;
+  upToChapterId: string;
+  chapterIds: string[];
+  content: string;
+  generatedAt: number;
+  /** 生成时各章最新摘要的 SHA-256，用于判断摘要是否有变化 */
+  summaryHashes?: Record<string, string>;
+}
+
+/** 将截至 upToChapterId 的各章摘要整合为前情提要并保存；可用 ai_complete_cancel 取消 */
+export async function generateStoryRollup(params: {
+  projectDir: string;
+  upToChapterId: string;
+}): Promise<StoryRollup> {
+  const active = await getActiveChatConfig();
+  if (!active) {
+    throw new Error("请先在设置中添加 Provider，并设为当前，然后配置模型参数。");
+  }
+
+  return (await invoke("generate_story_rollup", {
+    projectPath: params.projectDir,
+    upToChapterId: params.upToChapterId,
+    provider: active.provider,
+    parameters: active.parameters,
+  })) as StoryRollup;
+}
+
+export interface StoryRollupStatus {
+  rollup: StoryRollup;
+  /** 范围内章节或摘要在生成后有变化 */
+  stale: boolean;
+  changedChapterIds: string[];
+}
+
+/** 读取前情提要；不传 upToChapterId 时返回覆盖最远的一份 */
+export async function getStoryRollup(params: {
+  projectDir: string;
+  upToChapterId?: string | null;
+}): Promise<StoryRollupStatus | null> {
+  return (await invoke("get_story_rollup", {
+    projectPath: params.projectDir,
+    upToChapterId: params.upToChapterId ?? null,
+  })) as StoryRollupStatus | null;
+}
+
 export interface ExtractedWorldbuilding {
   characters: Array<{
     name: string;
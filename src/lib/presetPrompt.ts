@@ -0,0 +1,39 @@
+import type { WritingPreset } from "../types/writingPreset";
+
+/**
+ * 预设在系统提示词中的文本。各段按行相连，后端的提示词预览（presets.rs）必须得到相同结果，
+ * 两端都以 test-suite/fixtures/preset-prompt.json 校验。
+ */
+export function formatWritingPreset(preset: WritingPreset): string {
+  const sections = [
+    [
+      "写作风格要求：",
+      `- 文风：${preset.style.tone}`,
+      `- 叙事视角：${preset.style.perspective}`,
+      `- 时态：${preset.style.tense}`,
+      `- 描写风格：${preset.style.description}`,
+    ].join("\n"),
+  ];
+
+  if (preset.rules.length > 0) {
+    sections.push(`写作规则：\n${preset.rules.map((r) => `- ${r}`).join("\n")}`);
+  }
+
+  const bannedWords = (preset.bannedWords ?? []).map((w) => w.trim()).filter(Boolean);
+  if (bannedWords.length > 0) {
+    sections.push(`禁用词（正文中不得出现）：${bannedWords.join("、")}`);
+  }
+
+  const glossary = (preset.glossary ?? []).filter(([wrong, correct]) => wrong.trim() && correct.trim());
+  if (glossary.length > 0) {
+    sections.push(
+      `术语规范：\n${glossary.map(([wrong, correct]) => `- 写作「${correct}」，不要写成「${wrong}」`).join("\n")}`,
+    );
+  }
+
+  if (preset.customPrompt) {
+    sections.push(`额外要求：\n${preset.customPrompt}`);
+  }
+
+  return sections.join("\n");
+}
@@ -4,6 +4,9 @@ import {
   DEFAULT_PRESET_ID,
   type WritingPreset,
 } from "../types/writingPreset";
+import { formatWritingPreset } from "./presetPrompt";
+
+export { formatWritingPreset };
 
 export interface WritingPresetsState {
   presets: WritingPreset[];
@@ -38,35 +41,185 @@ export async function getWritingPresets(projectPath: string): Promise<WritingPre
   return { presets, activePresetId };
 }
 
+export interface PresetIssue {
+  severity: "error" | "warning";
+  /** 出错预设在提交列表中的下标 */
+  index: number | null;
+  field: string;
+  message: string;
+}
+
+export interface SavePresetsReport {
+  saved: boolean;
+  issues: PresetIssue[];
+  /** 实际持久化的结果（未保存时为原有内容） */
+  presets: WritingPreset[];
+  activePresetId: string;
+}
+
+/**
+ * 保存预设；未能保存（存在错误，或 strict 模式下存在警告）时抛出包含全部问题的错误。
+ */
 export async function saveWritingPresets(params: {
   projectPath: string;
   presets: WritingPreset[];
   activePresetId: string;
-}): Promise<void> {
-  await invoke("save_presets", {
+  strict?: boolean;
+}): Promise<SavePresetsReport> {
+  const report = (await invoke("save_presets", {
     projectPath: params.projectPath,
     presets: params.presets,
     activePresetId: params.activePresetId,
+    strict: params.strict ?? false,
+  })) as SavePresetsReport;
+  if (!report.saved) {
+    throw new Error(report.issues.map((issue) => issue.message).join("；"));
+  }
+  return report;
+}
+
+/** 导出单个预设为带格式版本号的 JSON 文件 */
+export async function exportWritingPreset(params: {
+  projectPath: string;
+  presetId: string;
+  destPath: string;
+}): Promise<void> {
+  await invoke("export_preset", {
+    projectPath: params.projectPath,
+    presetId: params.presetId,
+    destPath: params.destPath,
   });
 }
 
-export function buildSystemPrompt(preset: WritingPreset, basePrompt: string): string {
-  return `${basePrompt}\n${formatWritingPreset(preset)}`.trim();
+/** 从 JSON 文件导入预设；id 冲突时会重新生成，且不会成为默认预设 */
+export async function importWritingPreset(params: {
+  projectPath: string;
+  filePath: string;
+}): Promise<WritingPreset> {
+  return (await invoke("import_preset", {
+    projectPath: params.projectPath,
+    filePath: params.filePath,
+  })) as WritingPreset;
+}
+
+export interface StyleViolation {
+  kind: "banned_word" | "glossary";
+  line: number;
+  column: number;
+  text: string;
+  /** 术语表中的正确写法 */
+  suggestion: string | null;
+}
+
+/** 按预设的禁用词与术语表检查章节正文 */
+export async function checkChapterAgainstPreset(params: {
+  projectPath: string;
+  chapterId: string;
+  presetId: string;
+}): Promise<StyleViolation[]> {
+  return (await invoke("check_chapter_against_preset", {
+    projectPath: params.projectPath,
+    chapterId: params.chapterId,
+    presetId: params.presetId,
+  })) as StyleViolation[];
+}
+
+export interface PromptPreview {
+  prompt: string;
+  /** 按渲染顺序排列的非空段落（style / rules / bannedWords / glossary / customPrompt / chapter / summary） */
+  sections: Array<{ key: string; chars: number }>;
+  totalChars: number;
+}
+
+/** 渲染预设最终注入的系统提示词，不调用任何模型 */
+export async function previewPresetPrompt(params: {
+  projectPath: string;
+  presetId: string;
+  chapterId?: string | null;
+}): Promise<PromptPreview> {
+  return (await invoke("preview_preset_prompt", {
+    projectPath: params.projectPath,
+    presetId: params.presetId,
+    chapterId: params.chapterId ?? null,
+  })) as PromptPreview;
+}
+
+/** 记录预设被一次生成使用（更新最近使用时间与次数） */
+export async function touchWritingPreset(params: {
+  projectPath: string;
+  presetId: string;
+}): Promise<WritingPreset> {
+  return (await invoke("touch_preset", {
+    projectPath: params.projectPath,
+    presetId: params.presetId,
+  })) as WritingPreset;
+}
+
+/** 复制预设（新 id、非默认），返回副本 */
+export async function duplicateWritingPreset(params: {
+  projectPath: string;
+  presetId: string;
+  newName: string;
+}): Promise<WritingPreset> {
+  return (await invoke("duplicate_preset", {
+    projectPath: params.projectPath,
+    presetId: params.presetId,
+    newName: params.newName,
+  })) as WritingPreset;
+}
+
+/** 内置题材预设库（都市、悬疑、古风、科幻、轻小说） */
+export async function listBuiltinWritingPresets(): Promise<WritingPreset[]> {
+  return (await invoke("list_builtin_presets")) as WritingPreset[];
+}
+
+/** 将内置题材预设复制到项目中，返回带新 id 的副本 */
+export async function addBuiltinWritingPreset(params: {
+  projectPath: string;
+  builtinId: string;
+}): Promise<WritingPreset> {
+  return (await invoke("add_builtin_preset", {
+    projectPath: params.projectPath,
+    builtinId: params.builtinId,
+  })) as WritingPreset;
+}
+
+/** 全局预设库（~/.creatorai/presets.json），与各项目的预设互不同步 */
+export async function listGlobalWritingPresets(): Promise<WritingPreset[]> {
+  return (await invoke("list_global_presets")) as WritingPreset[];
 }
 
-export function formatWritingPreset(preset: WritingPreset): string {
-  const styleDesc = `
-写作风格要求：
-- 文风：${preset.style.tone}
-- 叙事视角：${preset.style.perspective}
-- 时态：${preset.style.tense}
-- 描写风格：${preset.style.description}
-`;
+/** 新增或按 id 覆盖全局预设；id 为空时分配新 id */
+export async function saveGlobalWritingPreset(preset: WritingPreset): Promise<WritingPreset> {
+  return (await invoke("save_global_preset", { preset })) as WritingPreset;
+}
 
-  const rulesDesc =
-    preset.rules.length > 0 ? `\n写作规则：\n${preset.rules.map((r) => `- ${r}`).join("\n")}` : "";
+export async function deleteGlobalWritingPreset(presetId: string): Promise<void> {
+  await invoke("delete_global_preset", { presetId });
+}
+
+/** 将全局预设复制到项目中，返回带新 id 的副本 */
+export async function copyGlobalPresetToProject(params: {
+  projectPath: string;
+  globalPresetId: string;
+}): Promise<WritingPreset> {
+  return (await invoke("copy_preset_to_project", {
+    projectPath: params.projectPath,
+    globalPresetId: params.globalPresetId,
+  })) as WritingPreset;
+}
 
-  const customDesc = preset.customPrompt ? `\n额外要求：\n${preset.customPrompt}` : "";
+/** 将项目预设复制到全局预设库，返回带新 id 的副本 */
+export async function promotePresetToGlobal(params: {
+  projectPath: string;
+  presetId: string;
+}): Promise<WritingPreset> {
+  return (await invoke("promote_preset_to_global", {
+    projectPath: params.projectPath,
+    presetId: params.presetId,
+  })) as WritingPreset;
+}
 
-  return `${styleDesc}${rulesDesc}${customDesc}`.trim();
+export function buildSystemPrompt(preset: WritingPreset, basePrompt: string): string {
+  return `${basePrompt}\n${formatWritingPreset(preset)}`.trim();
 }
@@ -5,6 +5,7 @@
  */
 
 import { invoke, isTauri } from "@tauri-apps/api/core";
+import type { WritingPreset } from "../../types/writingPreset";
 
 /**
  * 安全地检测是否在 Tauri 环境中
@@ -37,6 +38,34 @@ export interface Provider {
   models_updated_at: number | null;
   provider_type: string;
   headers?: Record<string, string> | null;
+  default_parameters?: ModelParameters | null;
+  proxy?: ProxySettings | null;
+  favorite?: boolean;
+  hidden_models?: string[];
+  model_aliases?: Record<string, string>;
+  default_model?: string | null;
+  api_key_env?: string | null;
+  max_concurrent_requests?: number | null;
+  min_request_interval_ms?: number | null;
+  /** Azure OpenAI only. */
+  api_version?: string | null;
+  /** Azure OpenAI only: model id -> deployment name. */
+  deployments?: Record<string, string>;
+}
+
+export interface ModelOption {
+  id: string;
+  label: string;
+}
+
+export interface ProviderModels {
+  models: ModelOption[];
+  defaultModel: string | null;
+}
+
+export interface ProxySettings {
+  url: string;
+  bypass: string[];
 }
 
 export interface ModelParameters {
@@ -51,6 +80,31 @@ export interface GlobalConfig {
   providers: Provider[];
   active_provider_id: string | null;
   default_parameters: ModelParameters;
+  parameter_profiles?: NamedParameters[];
+  proxy?: ProxySettings | null;
+  /** 只读参考目录（绝对路径），AI 可通过 ext://<id>/相对路径 读取与搜索 */
+  reference_roots?: ReferenceRoot[];
+  /** 保存的 txt 导入章节识别规则 */
+  import_patterns?: ImportPattern[];
+}
+
+export interface ReferenceRoot {
+  id: string;
+  path: string;
+}
+
+export interface ImportPattern {
+  id: string;
+  name: string;
+  /** 章节识别正则，按行匹配（^ 和 $ 匹配行首行尾） */
+  pattern: string;
+}
+
+export interface NamedParameters {
+  id: string;
+  name: string;
+  parameters: ModelParameters;
+  is_default: boolean;
 }
 
 export interface ProjectConfig {
@@ -61,6 +115,13 @@ export interface ProjectConfig {
   settings: {
     autoSave: boolean;
     autoSaveInterval: number;
+    maxSummariesPerChapter?: number;
+    backupRetention?: { maxTotalBytes?: number; maxAgeDays?: number; maxCopiesPerFile?: number };
+    followSymlinks?: "within_project" | "deny" | "allow_listed";
+    symlinkAllowlist?: string[];
+    maxFileBytes?: number;
+    protectedPaths?: string[];
+    readOnly?: boolean;
   };
 }
 
@@ -68,6 +129,10 @@ export interface RecentProject {
   name: string;
   path: string;
   lastOpened: number;
+  pinned?: boolean;
+  wordCount?: number;
+  chapterCount?: number;
+  statsUpdatedAt?: number;
 }
 
 export interface ChapterMeta {
@@ -77,6 +142,56 @@ export interface ChapterMeta {
   created: number;
   updated: number;
   wordCount: number;
+  /** 旧项目没有该字段时视为 draft */
+  status: ChapterStatus;
+  /** 所属卷，未分卷时省略 */
+  volumeId?: string;
+}
+
+export interface Volume {
+  id: string;
+  title: string;
+  order: number;
+}
+
+/** unknown：较新版本写入的状态，只读 */
+export type ChapterStatus = "draft" | "revising" | "done" | "unknown";
+
+export interface ActivityEntry {
+  timestamp: number;
+  actor: "user" | "ai";
+  action: string;
+  path: string;
+  byteDelta: number;
+}
+
+/** AI 工具写入审计记录（.creatorai/ai_writes.jsonl） */
+export interface AiWriteRecord {
+  timestamp: number;
+  mode: string;
+  chapterId: string | null;
+  sessionId: string | null;
+  applyId: string | null;
+  tool: string;
+  argsSha256: string;
+  path: string;
+  /** 写入前内容的备份（项目内相对路径），新建文件时为 null */
+  backupPath: string | null;
+  beforeSha256: string | null;
+  afterSha256: string | null;
+}
+
+export interface WordCountSnapshot {
+  date: number;
+  totalWords: number;
+  chapterCount: number;
+}
+
+export interface DailyWritingStats {
+  /** UTC 日期，YYYY-MM-DD */
+  date: string;
+  /** 当天净增字数（删除计为负数） */
+  delta: number;
 }
 
 export interface Session {
@@ -85,6 +200,9 @@ export interface Session {
   mode: string;
   created: number;
   updated: number;
+  profile_id?: string | null;
+  /** 覆盖项目当前写作预设的会话预设 */
+  preset_id?: string | null;
 }
 
 export interface Message {
@@ -126,18 +244,32 @@ export interface AIChatToolCall {
 export interface AIChatResult {
   content: string;
   tool_calls: AIChatToolCall[];
+  /** 本轮写入的文件分组，可用于 undoAiApply；未写入时为 null */
+  apply_id?: string | null;
 }
 
 // ==================== 项目相关命令 ====================
 
-export async function openProject(path: string): Promise<ProjectConfig> {
-  return tauriInvoke<ProjectConfig>("open_project", { path });
+export async function openProject(
+  path: string,
+  options?: { autoRecover?: boolean; forceTakeover?: boolean },
+): Promise<ProjectConfig> {
+  return tauriInvoke<ProjectConfig>("open_project", { path, ...options });
+}
+
+export async function releaseProjectLock(projectPath: string): Promise<void> {
+  return tauriInvoke<void>("release_project_lock", { projectPath });
 }
 
 export async function createProject(path: string, name: string): Promise<ProjectConfig> {
   return tauriInvoke<ProjectConfig>("create_project", { path, name });
 }
 
+/** 开关项目只读模式；只读时所有写入都会被拒绝，读取、搜索与导出不受影响 */
+export async function setProjectReadOnly(path: string, readOnly: boolean): Promise<ProjectConfig> {
+  return tauriInvoke<ProjectConfig>("set_project_read_only", { path, readOnly });
+}
+
 export async function getRecentProjects(): Promise<RecentProject[]> {
   return tauriInvoke<RecentProject[]>("get_recent_projects");
 }
@@ -146,6 +278,14 @@ export async function addRecentProject(name: string, path: string): Promise<void
   return tauriInvoke<void>("add_recent_project", { name, path });
 }
 
+export async function refreshRecentProjectStats(): Promise<RecentProject[]> {
+  return tauriInvoke<RecentProject[]>("refresh_recent_project_stats");
+}
+
+export async function setRecentProjectPinned(path: string, pinned: boolean): Promise<void> {
+  return tauriInvoke<void>("set_recent_project_pinned", { path, pinned });
+}
+
 export async function consumeUiCleanupFlag(): Promise<boolean> {
   return tauriInvoke<boolean>("consume_ui_cleanup_flag");
 }
@@ -160,14 +300,107 @@ export async function getChapterContent(projectPath: string, chapterId: string):
   return tauriInvoke<string>("get_chapter_content", { projectPath, chapterId });
 }
 
-export async function saveChapterContent(projectPath: string, chapterId: string, content: string): Promise<void> {
-  return tauriInvoke<void>("save_chapter_content", { projectPath, chapterId, content });
+export interface ChapterContent {
+  id: string;
+  content: string;
+  wordCount: number;
+  /** 章节文件不存在，content 为空 */
+  missing: boolean;
+  /** 该章读取失败的原因（过大、非 UTF-8 等），content 为空；其余章节照常返回 */
+  error: string | null;
+}
+
+/** 一次读取多个章节的正文；超过总字节上限时 truncated 为 true，其后的章节不再返回 */
+export async function getChaptersContent(
+  projectPath: string,
+  chapterIds: string[],
+): Promise<{ chapters: ChapterContent[]; truncated: boolean }> {
+  return tauriInvoke<{ chapters: ChapterContent[]; truncated: boolean }>("get_chapters_content", {
+    projectPath,
+    chapterIds,
+  });
+}
+
+export interface ChapterSaveResult extends ChapterMeta {
+  /** 内容已保存，但活动日志或写作统计未能更新 */
+  warnings: string[];
+}
+
+export async function saveChapterContent(
+  projectPath: string,
+  chapterId: string,
+  content: string,
+): Promise<ChapterSaveResult> {
+  return tauriInvoke<ChapterSaveResult>("save_chapter_content", { projectPath, chapterId, content });
 }
 
 export async function createChapter(projectPath: string, title: string): Promise<ChapterMeta> {
   return tauriInvoke<ChapterMeta>("create_chapter", { projectPath, title });
 }
 
+export async function setChapterStatus(
+  projectPath: string,
+  chapterId: string,
+  status: ChapterStatus,
+): Promise<ChapterMeta> {
+  return tauriInvoke<ChapterMeta>("set_chapter_status", { projectPath, chapterId, status });
+}
+
+export async function listVolumes(projectPath: string): Promise<Volume[]> {
+  return tauriInvoke<Volume[]>("list_volumes", { projectPath });
+}
+
+export async function createVolume(projectPath: string, title: string): Promise<Volume> {
+  return tauriInvoke<Volume>("create_volume", { projectPath, title });
+}
+
+export async function renameVolume(projectPath: string, volumeId: string, newTitle: string): Promise<Volume> {
+  return tauriInvoke<Volume>("rename_volume", { projectPath, volumeId, newTitle });
+}
+
+/** 删除卷，卷内章节保留并变为未分卷 */
+export async function deleteVolume(projectPath: string, volumeId: string): Promise<void> {
+  return tauriInvoke<void>("delete_volume", { projectPath, volumeId });
+}
+
+/** 把章节归入某卷；volumeId 为 null 时移出所有卷 */
+export async function assignChapterVolume(
+  projectPath: string,
+  chapterId: string,
+  volumeId: string | null,
+): Promise<ChapterMeta> {
+  return tauriInvoke<ChapterMeta>("assign_chapter_volume", { projectPath, chapterId, volumeId });
+}
+
+/** 根据 chapters/ 下的章节文件重建 index.json（原索引损坏时先备份） */
+export async function rebuildChapterIndex(projectPath: string): Promise<ChapterMeta[]> {
+  return tauriInvoke<ChapterMeta[]>("rebuild_chapter_index", { projectPath });
+}
+
+/** 读取章节备注，没有备注时返回空字符串 */
+export async function getChapterNotes(projectPath: string, chapterId: string): Promise<string> {
+  return tauriInvoke<string>("get_chapter_notes", { projectPath, chapterId });
+}
+
+/** 保存章节备注（chapters/{chapterId}.notes.md），覆盖前自动备份 */
+export async function saveChapterNotes(projectPath: string, chapterId: string, notes: string): Promise<void> {
+  return tauriInvoke<void>("save_chapter_notes", { projectPath, chapterId, notes });
+}
+
+/** 在 insertAfter 章节之后或第 position 位（从 1 开始）插入新章节，后续章节顺延 */
+export async function createChapterAt(
+  projectPath: string,
+  title: string,
+  at: { insertAfter?: string; position?: number },
+): Promise<ChapterMeta> {
+  return tauriInvoke<ChapterMeta>("create_chapter_at", {
+    projectPath,
+    title,
+    insertAfter: at.insertAfter ?? null,
+    position: at.position ?? null,
+  });
+}
+
 export async function deleteChapter(projectPath: string, chapterId: string): Promise<void> {
   return tauriInvoke<void>("delete_chapter", { projectPath, chapterId });
 }
@@ -180,6 +413,274 @@ export async function reorderChapters(projectPath: string, orderedIds: string[])
   return tauriInvoke<void>("reorder_chapters", { projectPath, orderedIds });
 }
 
+/** 从第 splitLine 行（从 1 开始）起拆出新章节，插在原章节之后 */
+export async function splitChapter(
+  projectPath: string,
+  chapterId: string,
+  splitLine: number,
+  newTitle: string,
+): Promise<{ original: ChapterMeta; created: ChapterMeta }> {
+  return tauriInvoke<{ original: ChapterMeta; created: ChapterMeta }>("split_chapter", {
+    projectPath,
+    chapterId,
+    splitLine,
+    newTitle,
+  });
+}
+
+/** 把 secondId 的内容接到 firstId 之后并删除 secondId；默认用空行分隔，不相邻时需 force */
+export async function mergeChapters(
+  projectPath: string,
+  firstId: string,
+  secondId: string,
+  separator?: string,
+  force?: boolean,
+): Promise<ChapterMeta> {
+  return tauriInvoke<ChapterMeta>("merge_chapters", {
+    projectPath,
+    firstId,
+    secondId,
+    separator: separator ?? null,
+    force: force ?? null,
+  });
+}
+
+export interface ChapterSummaryRef {
+  chapterId: string;
+  title: string;
+  order: number;
+  /** 该章尚无摘要时为 null */
+  summary: string | null;
+  createdAt: number | null;
+}
+
+/** 按故事顺序返回 beforeChapterId 之前 count 章各自的最新摘要 */
+export async function getRecentSummaries(
+  projectPath: string,
+  beforeChapterId: string,
+  count: number,
+): Promise<ChapterSummaryRef[]> {
+  return tauriInvoke<ChapterSummaryRef[]>("get_recent_summaries", { projectPath, beforeChapterId, count });
+}
+
+export interface SummaryExportReport {
+  covered: number;
+  missing: number;
+}
+
+/** 按章节顺序导出各章最新摘要为 Markdown；目标已存在时需 overwrite */
+export async function exportSummariesMarkdown(
+  projectPath: string,
+  destPath: string,
+  overwrite = false,
+): Promise<SummaryExportReport> {
+  return tauriInvoke<SummaryExportReport>("export_summaries_markdown", { projectPath, destPath, overwrite });
+}
+
+export interface SummaryDiff {
+  /** 该章不足两个版本时为 false，segments 为空 */
+  comparable: boolean;
+  older: { chapterId: string; summary: string; createdAt: number; updatedAt?: number } | null;
+  newer: { chapterId: string; summary: string; createdAt: number; updatedAt?: number } | null;
+  segments: Array<{ kind: "equal" | "added" | "removed"; text: string }>;
+}
+
+/** 按句对比章节的两个摘要版本；默认比较最新的两个 */
+export async function diffSummaries(
+  projectPath: string,
+  chapterId: string,
+  versions?: { olderCreatedAt: number; newerCreatedAt: number },
+): Promise<SummaryDiff> {
+  return tauriInvoke<SummaryDiff>("diff_summaries", {
+    projectPath,
+    chapterId,
+    olderCreatedAt: versions?.olderCreatedAt ?? null,
+    newerCreatedAt: versions?.newerCreatedAt ?? null,
+  });
+}
+
+export interface SummarySearchHit {
+  chapterId: string;
+  /** 章节已不在目录中时为 null */
+  chapterTitle: string | null;
+  createdAt: number;
+  isLatest: boolean;
+  snippet: string;
+  /** 命中位置在 snippet 中的字符区间 [start, end) */
+  highlights: Array<[number, number]>;
+}
+
+/** 全文搜索摘要（不区分大小写），默认只搜索每章最新版本，最多返回 50 条 */
+export async function searchSummaries(
+  projectPath: string,
+  query: string,
+  includeOlder = false,
+): Promise<{ hits: SummarySearchHit[]; truncated: boolean }> {
+  return tauriInvoke<{ hits: SummarySearchHit[]; truncated: boolean }>("search_summaries", {
+    projectPath,
+    query,
+    includeOlder,
+  });
+}
+
+/** 每章只保留最新的 keepLatest 条摘要，返回删除的条数 */
+export async function pruneSummaries(projectPath: string, keepLatest: number): Promise<number> {
+  return tauriInvoke<number>("prune_summaries", { projectPath, keepLatest });
+}
+
+export interface BackupPruneReport {
+  dryRun: boolean;
+  /** 按执行顺序列出已配置的规则及其回收的文件数与字节数 */
+  rules: Array<{ rule: "age" | "copiesPerFile" | "totalSize"; files: number; bytes: number }>;
+  reclaimedFiles: number;
+  reclaimedBytes: number;
+  remainingBytes: number;
+}
+
+/** 按项目设置清理 .backup；dryRun 时只报告将回收的空间 */
+export async function pruneBackups(projectPath: string, dryRun = false): Promise<BackupPruneReport> {
+  return tauriInvoke<BackupPruneReport>("prune_backups", { projectPath, dryRun });
+}
+
+export interface FileBackups {
+  relativePath: string;
+  /** 新的在前；timestamp 为毫秒时间戳 */
+  backups: Array<{ timestamp: number; backupPath: string; size: number }>;
+}
+
+/** 按原文件分组列出 .backup 中的备份；传入 relativePath 时只列该文件 */
+export async function listBackups(projectPath: string, relativePath?: string): Promise<FileBackups[]> {
+  return tauriInvoke<FileBackups[]>("list_backups", { projectPath, relativePath: relativePath ?? null });
+}
+
+export interface RestoredBackup {
+  relativePath: string;
+  timestamp: number;
+  chapter: ChapterMeta | null;
+  /** 未能更新的记录（如活动日志） */
+  warnings: string[];
+}
+
+/** 用指定备份覆盖文件（覆盖前会先备份当前版本）；章节文件会同步更新字数 */
+export async function restoreFileBackup(
+  projectPath: string,
+  relativePath: string,
+  timestamp: number,
+): Promise<RestoredBackup> {
+  return tauriInvoke<RestoredBackup>("restore_file_backup", {
+    projectPath,
+    relativePath,
+    timestamp,
+  });
+}
+
+export interface BackupReadResult {
+  /** 带行号（00001| ）的内容 */
+  content: string;
+  total_lines: number;
+  truncated: boolean;
+}
+
+/** 按行范围读取某个备份版本，格式与 file_read 相同 */
+export async function readBackupFile(
+  projectPath: string,
+  relativePath: string,
+  timestamp: number,
+  range?: { offset?: number; limit?: number },
+): Promise<BackupReadResult> {
+  return tauriInvoke<BackupReadResult>("read_backup_file", {
+    projectPath,
+    relativePath,
+    timestamp,
+    offset: range?.offset ?? null,
+    limit: range?.limit ?? null,
+  });
+}
+
+export interface DiffHunk {
+  oldStart: number;
+  oldLines: number;
+  newStart: number;
+  newLines: number;
+  lines: Array<{ kind: "context" | "removed" | "added"; text: string }>;
+}
+
+export interface BackupDiff {
+  relativePath: string;
+  fromTimestamp: number;
+  toTimestamp: number;
+  hunks: DiffHunk[];
+  /** 差异过长，仅返回了前面的部分 */
+  truncated: boolean;
+}
+
+/** 比较同一文件的两个备份版本（从 timestampA 到 timestampB） */
+export async function diffBackups(
+  projectPath: string,
+  relativePath: string,
+  timestampA: number,
+  timestampB: number,
+): Promise<BackupDiff> {
+  return tauriInvoke<BackupDiff>("diff_backups", { projectPath, relativePath, timestampA, timestampB });
+}
+
+export interface ProjectSnapshot {
+  /** .backup/snapshots/ 下的目录名 */
+  id: string;
+  label: string;
+  /** 毫秒时间戳 */
+  timestamp: number;
+  fileCount: number;
+  totalSize: number;
+}
+
+/** 为章节、摘要、知识库、项目配置和预设创建整体快照（不含 RAG 索引） */
+export async function createProjectSnapshot(projectPath: string, label: string): Promise<ProjectSnapshot> {
+  return tauriInvoke<ProjectSnapshot>("create_project_snapshot", { projectPath, label });
+}
+
+/** 新的在前 */
+export async function listProjectSnapshots(projectPath: string): Promise<ProjectSnapshot[]> {
+  return tauriInvoke<ProjectSnapshot[]>("list_project_snapshots", { projectPath });
+}
+
+/** 恢复到快照；当前文件会先备份，快照中没有的文件会被删除（同样先备份） */
+export async function restoreProjectSnapshot(projectPath: string, snapshotId: string): Promise<ProjectSnapshot> {
+  return tauriInvoke<ProjectSnapshot>("restore_project_snapshot", { projectPath, snapshotId });
+}
+
+export async function getActivityLog(projectPath: string, limit?: number, offset?: number): Promise<ActivityEntry[]> {
+  return tauriInvoke<ActivityEntry[]>("get_activity_log", { projectPath, limit, offset });
+}
+
+/** 最近的 AI 写入记录，最新的在前 */
+export async function getAiWriteLog(projectPath: string, limit?: number): Promise<AiWriteRecord[]> {
+  return tauriInvoke<AiWriteRecord[]>("get_ai_write_log", { projectPath, limit });
+}
+
+export interface UndoApplyReport {
+  applyId: string;
+  /** 已恢复为写入前内容的文件 */
+  restored: string[];
+  /** 本次写入新建、已删除的文件 */
+  removed: string[];
+  failed: Array<{ path: string; error: string }>;
+}
+
+/** 撤销一轮 AI 写入：恢复其涉及的全部文件（恢复前会先备份当前内容） */
+export async function undoAiApply(projectPath: string, applyId: string): Promise<UndoApplyReport> {
+  return tauriInvoke<UndoApplyReport>("undo_ai_apply", { projectPath, applyId });
+}
+
+export async function getWordCountHistory(projectPath: string, since?: number): Promise<WordCountSnapshot[]> {
+  return tauriInvoke<WordCountSnapshot[]>("get_word_count_history", { projectPath, since });
+}
+
+/** 最近 days 天（含今天）每天的净写作字数，按日期升序 */
+export async function getWritingStats(projectPath: string, days: number): Promise<DailyWritingStats[]> {
+  return tauriInvoke<DailyWritingStats[]>("get_writing_stats", { projectPath, days });
+}
+
 // ==================== 会话相关命令 ====================
 
 export async function listSessions(projectPath: string): Promise<Session[]> {
@@ -206,48 +707,457 @@ export async function renameSession(projectPath: string, sessionId: string, newN
   return tauriInvoke<void>("rename_session", { projectPath, sessionId, newName });
 }
 
+export async function setSessionProfile(
+  projectPath: string,
+  sessionId: string,
+  profileId: string | null,
+): Promise<void> {
+  return tauriInvoke<void>("set_session_profile", { projectPath, sessionId, profileId });
+}
+
+export async function setSessionPreset(
+  projectPath: string,
+  sessionId: string,
+  presetId: string | null,
+): Promise<void> {
+  return tauriInvoke<void>("set_session_preset", { projectPath, sessionId, presetId });
+}
+
+/** 会话实际使用的预设：会话预设 → 项目当前预设 → 默认预设 */
+export async function getEffectivePreset(projectPath: string, sessionId: string): Promise<WritingPreset> {
+  return tauriInvoke<WritingPreset>("get_effective_preset", { projectPath, sessionId });
+}
+
 // ==================== 配置相关命令 ====================
 
 export async function getConfig(): Promise<GlobalConfig> {
   return tauriInvoke<GlobalConfig>("get_config");
 }
 
-export async function getApiKey(providerId: string): Promise<string | null> {
-  return tauriInvoke<string | null>("get_api_key", { providerId });
+/** 配置保存后后端广播给所有窗口的事件 */
+export const CONFIG_CHANGED_EVENT = "config:changed";
+
+export interface ConfigChangedPayload {
+  section: "all" | "providers" | "active_provider" | "parameters" | "import_patterns";
+  activeProviderId: string | null;
+  /** 是否来自应用外对 config.json 的编辑 */
+  external: boolean;
+}
+
+export interface ResolvedApiKey {
+  key: string;
+  source: "keyring" | "env";
+  envVar: string | null;
+}
+
+export interface KeyStorageInfo {
+  backend: "keyring" | "encrypted-file";
+  keyringError: string | null;
+  usesPassphrase: boolean;
+  secretsPath: string;
+}
+
+export async function getKeyStorageInfo(): Promise<KeyStorageInfo> {
+  return tauriInvoke<KeyStorageInfo>("get_key_storage_info");
+}
+
+export async function listOrphanedKeys(): Promise<string[]> {
+  return tauriInvoke<string[]>("list_orphaned_keys");
 }
 
-export async function addProvider(provider: Provider, apiKey: string): Promise<void> {
-  return tauriInvoke<void>("add_provider", { provider, apiKey });
+export async function deleteOrphanedKey(keyId: string): Promise<void> {
+  return tauriInvoke<void>("delete_orphaned_key", { keyId });
 }
 
-export async function updateProvider(provider: Provider, apiKey: string | null): Promise<void> {
-  return tauriInvoke<void>("update_provider", { provider, apiKey });
+export async function getApiKey(providerId: string): Promise<ResolvedApiKey | null> {
+  return tauriInvoke<ResolvedApiKey | null>("get_api_key", { providerId });
+}
+
+export async function addProvider(provider: Provider, apiKey: string, verify = false): Promise<void> {
+  return tauriInvoke<void>("add_provider", { provider, apiKey, verify });
+}
+
+export async function updateProvider(
+  provider: Provider,
+  apiKey: string | null,
+  previousId?: string,
+  verify = false,
+): Promise<void> {
+  return tauriInvoke<void>("update_provider", {
+    provider,
+    apiKey,
+    previousId: previousId ?? null,
+    verify,
+  });
+}
+
+export type ApiKeyStatus = "valid" | "invalid_key" | "no_model_access" | "unreachable" | "error";
+
+export interface ApiKeyVerification {
+  status: ApiKeyStatus;
+  latencyMs: number;
+  message: string | null;
+}
+
+/** `provider` is either a saved provider id or an unsaved provider config. */
+export async function verifyApiKey(
+  provider: string | Provider,
+  apiKey: string,
+): Promise<ApiKeyVerification> {
+  return tauriInvoke<ApiKeyVerification>("verify_api_key", { provider, apiKey });
+}
+
+export interface UsageCounters {
+  requests: number;
+  errors: number;
+  inputChars: number;
+  outputChars: number;
+}
+
+export interface UsageSummary {
+  totals: UsageCounters;
+  byModel: Array<UsageCounters & { providerId: string; model: string }>;
+  /** `day` is Unix seconds at UTC midnight. */
+  daily: Array<UsageCounters & { day: number }>;
+}
+
+export async function getUsageSummary(range?: { since?: number; until?: number }): Promise<UsageSummary> {
+  return tauriInvoke<UsageSummary>("get_usage_summary", { range: range ?? null });
+}
+
+export async function resetUsageStats(): Promise<void> {
+  return tauriInvoke<void>("reset_usage_stats");
 }
 
 export async function deleteProvider(providerId: string): Promise<void> {
   return tauriInvoke<void>("delete_provider", { providerId });
 }
 
+export async function listConfigBackups(): Promise<number[]> {
+  return tauriInvoke<number[]>("list_config_backups");
+}
+
+export async function restoreConfigBackup(timestamp: number): Promise<GlobalConfig> {
+  return tauriInvoke<GlobalConfig>("restore_config_backup", { timestamp });
+}
+
+export interface SettingsImportReport {
+  added: string[];
+  replaced: string[];
+  conflicts: string[];
+  keysRestored: number;
+}
+
+export async function exportSettings(destPath: string, includeApiKeys: boolean, passphrase?: string): Promise<void> {
+  return tauriInvoke<void>("export_settings", { destPath, includeApiKeys, passphrase });
+}
+
+export async function importSettings(filePath: string, passphrase: string | null, merge: boolean): Promise<SettingsImportReport> {
+  return tauriInvoke<SettingsImportReport>("import_settings", { filePath, passphrase, merge });
+}
+
 export async function setActiveProvider(providerId: string): Promise<void> {
   return tauriInvoke<void>("set_active_provider", { providerId });
 }
 
+export async function getProviderModels(providerId: string): Promise<ProviderModels> {
+  return tauriInvoke<ProviderModels>("get_provider_models", { providerId });
+}
+
+export async function setHiddenModels(providerId: string, hiddenModels: string[]): Promise<void> {
+  return tauriInvoke<void>("set_hidden_models", { providerId, hiddenModels });
+}
+
+export async function setModelAlias(providerId: string, alias: string, modelId: string | null): Promise<void> {
+  return tauriInvoke<void>("set_model_alias", { providerId, alias, modelId });
+}
+
+export async function setDefaultModel(providerId: string, modelId: string | null): Promise<void> {
+  return tauriInvoke<void>("set_default_model", { providerId, modelId });
+}
+
+export async function reorderProviders(providerIds: string[]): Promise<Provider[]> {
+  return tauriInvoke<Provider[]>("reorder_providers", { providerIds });
+}
+
+export async function setProviderFavorite(providerId: string, favorite: boolean): Promise<void> {
+  return tauriInvoke<void>("set_provider_favorite", { providerId, favorite });
+}
+
+export async function setProviderHeaders(
+  providerId: string,
+  headers: Record<string, string>,
+  allowAuthOverride = false,
+): Promise<void> {
+  return tauriInvoke<void>("set_provider_headers", { providerId, headers, allowAuthOverride });
+}
+
 export async function refreshProviderModels(providerId: string): Promise<string[]> {
   return tauriInvoke<string[]>("refresh_provider_models", { providerId });
 }
 
+export interface ProviderConnectionTest {
+  ok: boolean;
+  latencyMs: number;
+  modelsFound: number;
+  error: string | null;
+}
+
+export async function testProviderConnection(provider: Provider, apiKey?: string | null): Promise<ProviderConnectionTest> {
+  return tauriInvoke<ProviderConnectionTest>("test_provider_connection", { provider, apiKey });
+}
+
 export async function setDefaultParameters(parameters: ModelParameters): Promise<void> {
   return tauriInvoke<void>("set_default_parameters", { parameters });
 }
 
+export async function listParameterProfiles(): Promise<NamedParameters[]> {
+  return tauriInvoke<NamedParameters[]>("list_parameter_profiles");
+}
+
+/** An empty `id` creates a new profile; the stored profile (with its id) is returned. */
+export async function saveParameterProfile(profile: NamedParameters): Promise<NamedParameters> {
+  return tauriInvoke<NamedParameters>("save_parameter_profile", { profile });
+}
+
+export async function deleteParameterProfile(profileId: string): Promise<void> {
+  return tauriInvoke<void>("delete_parameter_profile", { profileId });
+}
+
+export async function getProviderParameters(providerId: string): Promise<ModelParameters> {
+  return tauriInvoke<ModelParameters>("get_provider_parameters", { providerId });
+}
+
+export async function setProviderParameters(providerId: string, parameters: ModelParameters | null): Promise<void> {
+  return tauriInvoke<void>("set_provider_parameters", { providerId, parameters });
+}
+
 // ==================== 导入相关命令 ====================
 
-export async function previewImportTxt(projectPath: string, filePath: string): Promise<{ title: string; content: string }[]> {
-  return tauriInvoke<{ title: string; content: string }[]>("preview_import_txt", { projectPath, filePath });
+export interface ImportTxtPreview {
+  /** 读取文件时使用的编码（UTF-8 / GBK / Big5 等） */
+  encoding: string;
+  chapters: {
+    title: string;
+    wordCount: number;
+    isPreamble: boolean;
+    /** 预览时传入 projectPath 且项目中已有同名章节时，为该章节 id */
+    existingChapterId?: string;
+  }[];
+  /** 第一个章节标题之前的文字字数；即使不导入也会报告，没有时为 null */
+  preambleWordCount: number | null;
+}
+
+export interface ImportTxtOptions {
+  /** 是否把第一个章节标题之前的文字导入为单独一章，默认 true */
+  includePreamble?: boolean;
+  /** 该章标题，默认 "前言" */
+  preambleTitle?: string;
+  /** 仅 importTxt 使用：要导入的章节在预览列表中的下标，省略时全部导入 */
+  selectedIndices?: number[];
+  /** 仅 importTxt 使用：取消时删除本次已创建的章节、恢复导入前的章节索引，并还原被覆盖章节的内容 */
+  rollbackOnCancel?: boolean;
+  /**
+   * 仅 importTxt 使用：标题与已有章节相同时的处理方式，默认 "duplicate"。
+   * "skip" 不导入；"overwrite" 覆盖已有章节内容，保留其 id 与顺序；"duplicate" 仍作为新章节导入
+   */
+  conflictStrategy?: ImportConflictStrategy;
+}
+
+export type ImportConflictStrategy = "skip" | "overwrite" | "duplicate";
+
+export interface ImportTxtResult {
+  chapters: ChapterMeta[];
+  cancelled: boolean;
+  rolledBack: boolean;
+  /** 因标题已存在而跳过的章节数 */
+  skipped: number;
+  /** 被覆盖内容的已有章节数 */
+  overwritten: number;
+  /** 章节已导入，但活动日志未能更新 */
+  warnings: string[];
+}
+
+/**
+ * encoding 省略或为 "auto" 时自动识别（BOM → UTF-8 → GBK/Big5 猜测）；
+ * 传入 projectPath 时标出与项目中已有章节同名的章节；
+ * 传入 patternId 时使用已保存的规则，此时 pattern 须为空
+ */
+export async function previewImportTxt(
+  filePath: string,
+  pattern: string,
+  encoding?: string,
+  options?: ImportTxtOptions,
+  projectPath?: string,
+  patternId?: string,
+): Promise<ImportTxtPreview> {
+  return tauriInvoke<ImportTxtPreview>("preview_import_txt", {
+    filePath,
+    pattern,
+    patternId,
+    encoding,
+    options,
+    projectPath,
+  });
+}
+
+export async function listImportPatterns(): Promise<ImportPattern[]> {
+  return tauriInvoke<ImportPattern[]>("list_import_patterns");
+}
+
+/** id 为空时新建；正则无法编译时返回编译错误 */
+export async function saveImportPattern(pattern: ImportPattern): Promise<ImportPattern> {
+  return tauriInvoke<ImportPattern>("save_import_pattern", { pattern });
+}
+
+export async function deleteImportPattern(patternId: string): Promise<void> {
+  return tauriInvoke<void>("delete_import_pattern", { patternId });
+}
+
+export async function importTxt(
+  projectPath: string,
+  filePath: string,
+  pattern: string,
+  requestId: string,
+  encoding?: string,
+  options?: ImportTxtOptions,
+): Promise<ImportTxtResult> {
+  return tauriInvoke<ImportTxtResult>("import_txt", {
+    projectPath,
+    filePath,
+    pattern,
+    requestId,
+    encoding,
+    options,
+  });
+}
+
+/** 请求停止 requestId 对应的导入，当前章节写完后生效 */
+export async function importTxtCancel(requestId: string): Promise<void> {
+  return tauriInvoke<void>("import_txt_cancel", { requestId });
+}
+
+/**
+ * 按 Markdown 标题拆分章节：headingLevel 为 1–3（默认 1，即 "# 标题"），代码块中的 # 不算标题；
+ * 没有该级标题时整个文件作为一章，以文件名为标题
+ */
+export async function previewImportMarkdown(
+  filePath: string,
+  headingLevel?: number,
+  encoding?: string,
+  options?: ImportTxtOptions,
+  projectPath?: string,
+): Promise<ImportTxtPreview> {
+  return tauriInvoke<ImportTxtPreview>("preview_import_markdown", {
+    filePath,
+    headingLevel,
+    encoding,
+    options,
+    projectPath,
+  });
+}
+
+/** 进度事件与取消（importTxtCancel）与 importTxt 共用 */
+export async function importMarkdown(
+  projectPath: string,
+  filePath: string,
+  requestId: string,
+  headingLevel?: number,
+  encoding?: string,
+  options?: ImportTxtOptions,
+): Promise<ImportTxtResult> {
+  return tauriInvoke<ImportTxtResult>("import_markdown", {
+    projectPath,
+    filePath,
+    headingLevel,
+    requestId,
+    encoding,
+    options,
+  });
+}
+
+export interface ExportOptions {
+  /** {n} 为导出中的章节序号（从 1 开始），{title} 为章节标题；默认 "第{n}章 {title}" */
+  headingTemplate?: string;
+  /** 章节之间的空行数，默认 1 */
+  blankLines?: number;
+  /** 跳过字数少于该值的章节 */
+  minWords?: number;
+  /** 只导出状态为 done 的章节 */
+  onlyDone?: boolean;
+  /** 目标文件已存在时覆盖 */
+  overwrite?: boolean;
+}
+
+export interface ExportResult {
+  outputPath: string;
+  chapterCount: number;
+  skipped: number;
+  wordCount: number;
+  /** 导出文件大小（字节） */
+  bytes: number;
+  /** 未能更新的记录（如活动日志） */
+  warnings: string[];
+}
+
+/** 按章节顺序把整个项目导出为一个 TXT/Markdown 文件；每导出一章发送 creatorai:exportProgress 事件 */
+export async function exportProject(
+  projectPath: string,
+  format: "txt" | "markdown",
+  outputPath: string,
+  requestId: string,
+  options?: ExportOptions,
+): Promise<ExportResult> {
+  return tauriInvoke<ExportResult>("export_project", { projectPath, format, outputPath, options, requestId });
+}
+
+/** 导出选中的章节，按目录顺序排列（与传入顺序无关）；标题默认为 "{title}" */
+export async function exportChapters(
+  projectPath: string,
+  chapterIds: string[],
+  format: "txt" | "markdown",
+  outputPath: string,
+  requestId: string,
+  options?: ExportOptions,
+): Promise<ExportResult> {
+  return tauriInvoke<ExportResult>("export_chapters", {
+    projectPath,
+    chapterIds,
+    format,
+    outputPath,
+    options,
+    requestId,
+  });
+}
+
+export interface SummaryImportPlan {
+  mappings: Array<{
+    sectionTitle: string;
+    chapterId: string;
+    chapterTitle: string;
+    /** title：标题一致；order：第 n 段对应顺序为 n 的章节 */
+    matchedBy: "title" | "order";
+    summary: string;
+  }>;
+  unmatchedSections: string[];
+  /** 导入后仍没有摘要的章节 id */
+  chaptersWithoutSummary: string[];
+  /** 仅导入时返回：摘要已保存，但活动日志未能更新 */
+  warnings?: string[];
+}
+
+/** 预览大纲文件与章节的对应关系，不写入任何内容；pattern 为空时使用默认章节标题正则 */
+export async function previewImportSummaries(
+  projectPath: string,
+  filePath: string,
+  pattern = "",
+): Promise<SummaryImportPlan> {
+  return tauriInvoke<SummaryImportPlan>("preview_import_summaries", { projectPath, filePath, pattern });
 }
 
-export async function importTxt(projectPath: string, filePath: string, mode: string): Promise<number> {
-  return tauriInvoke<number>("import_txt", { projectPath, filePath, mode });
+/** 将大纲文件的每一段保存为对应章节的新摘要 */
+export async function importSummaries(projectPath: string, filePath: string, pattern = ""): Promise<SummaryImportPlan> {
+  return tauriInvoke<SummaryImportPlan>("import_summaries", { projectPath, filePath, pattern });
 }
 
 // ==================== RAG 相关命令 ====================
@@ -297,6 +1207,8 @@ export interface AIChatParams {
   mode: string;
   chapterId: string | null;
   allowWrite: boolean;
+  /** 所属会话，记录在 AI 保存的摘要上 */
+  sessionId?: string | null;
 }
 
 export async function aiChat(params: AIChatParams): Promise<AIChatResult> {
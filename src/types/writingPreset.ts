@@ -5,6 +5,14 @@ export interface WritingPreset {
   style: WritingStyle;
   rules: string[];
   customPrompt: string;
+  /** 禁用词 */
+  bannedWords?: string[];
+  /** 术语表：[错误写法, 正确写法] */
+  glossary?: Array<[string, string]>;
+  /** 最近一次用于生成的时间（Unix 秒） */
+  lastUsedAt?: number | null;
+  /** 用于生成的次数 */
+  useCount?: number;
 }
 
 export interface WritingStyle {
@@ -0,0 +1,41 @@
+import { mkdtempSync, readFileSync, rmSync, writeFileSync } from "node:fs";
+import { tmpdir } from "node:os";
+import { join } from "node:path";
+import { fileURLToPath, pathToFileURL } from "node:url";
+
+function fail(message) {
+  console.error(`[preset-prompt] ${message}`);
+  process.exit(1);
+}
+
+// presetPrompt.ts only has type imports, so transpiling that one file is enough to run it.
+async function loadFormatter(root) {
+  const { default: ts } = await import("typescript");
+  const source = readFileSync(join(root, "src", "lib", "presetPrompt.ts"), "utf8");
+  const { outputText } = ts.transpileModule(source, {
+    compilerOptions: { module: ts.ModuleKind.ESNext, target: ts.ScriptTarget.ES2020 },
+  });
+  const dir = mkdtempSync(join(tmpdir(), "creatorai-preset-prompt-"));
+  try {
+    const file = join(dir, "presetPrompt.mjs");
+    writeFileSync(file, outputText);
+    return (await import(pathToFileURL(file).href)).formatWritingPreset;
+  } finally {
+    rmSync(dir, { recursive: true, force: true });
+  }
+}
+
+export async function runPresetPromptSuite({ rootDir }) {
+  const root = fileURLToPath(rootDir);
+  const fixture = JSON.parse(readFileSync(join(root, "test-suite", "fixtures", "preset-prompt.json"), "utf8"));
+  const formatWritingPreset = await loadFormatter(root);
+
+  console.log("\n[preset-prompt] Compare formatWritingPreset with the shared fixture");
+  for (const testCase of fixture.cases) {
+    const prompt = formatWritingPreset(testCase.preset);
+    if (prompt !== testCase.prompt) {
+      fail(`${testCase.name}: expected\n${testCase.prompt}\n--- got\n${prompt}`);
+    }
+  }
+  console.log("[preset-prompt] Check passed.");
+}
@@ -35,6 +35,11 @@ export async function runRegressionSuite({ rootDir }) {
       command: "node",
       args: ["test-suite/run.mjs", "no-hardcoded-secrets"],
     },
+    {
+      name: "Preset prompt matches the shared fixture",
+      command: "node",
+      args: ["test-suite/run.mjs", "preset-prompt"],
+    },
     {
       name: "Rust backend smoke and regression tests",
       command: "cargo",
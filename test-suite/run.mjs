@@ -9,6 +9,7 @@ import { runAiEngineSpawnSuite } from "./cases/ai-engine-spawn.mjs";
 import { runAiEngineToolSafetySuite } from "./cases/ai-engine-tool-safety.mjs";
 import { runAiEngineErrorRecoverySuite } from "./cases/ai-engine-error-recovery.mjs";
 import { runReleaseCopySuite } from "./cases/release-copy.mjs";
+import { runPresetPromptSuite } from "./cases/preset-prompt.mjs";
 
 const suite = process.argv[2] ?? "regression";
 
@@ -24,6 +25,7 @@ const suites = {
   "ai-engine-tool-safety": runAiEngineToolSafetySuite,
   "ai-engine-error-recovery": runAiEngineErrorRecoverySuite,
   "release-copy": runReleaseCopySuite,
+  "preset-prompt": runPresetPromptSuite,
 };
 
 const runner = suites[suite];